@@ -0,0 +1,71 @@
+//! Apply arbitrary rule output to a tile. Nothing may panic, and every field
+//! `set()` can write must stay finite and inside its range.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use rhai::Dynamic;
+use worldground::simulation::engine::{Phase, TileMutations, apply_mutations};
+use worldground::world::Tile;
+use worldground::world::tile::{Position, ResourceDeposit};
+
+#[derive(Debug, Arbitrary)]
+enum Value {
+    Float(f64),
+    Int(i64),
+    Str(String),
+    Bool(bool),
+    Unit,
+}
+
+impl From<Value> for Dynamic {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Float(v) => Dynamic::from(v),
+            Value::Int(v) => Dynamic::from(v),
+            Value::Str(v) => Dynamic::from(v),
+            Value::Bool(v) => Dynamic::from(v),
+            Value::Unit => Dynamic::UNIT,
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    phase: u8,
+    mutations: Vec<(String, Value)>,
+}
+
+fuzz_target!(|input: Input| {
+    let phase = Phase::all()[input.phase as usize % Phase::all().len()];
+    let mut tile = Tile::new_default(0, vec![1, 2, 3], Position::flat(0.0, 0.0));
+    tile.resources.resources.push(ResourceDeposit {
+        resource_type: "grain".to_string(),
+        quantity: 50.0,
+        max_quantity: 100.0,
+        renewal_rate: 1.0,
+        requires_biome: None,
+        quality: 0.5,
+        discovered: true,
+    });
+    let mutations = TileMutations {
+        mutations: input.mutations.into_iter().map(|(f, v)| (f, v.into())).collect(),
+        ..Default::default()
+    };
+    apply_mutations(&mut tile, &mutations, phase);
+
+    let unit = |v: f32| (0.0..=1.0).contains(&v);
+    let (w, c, b) = (&tile.weather, &tile.conditions, &tile.biome);
+    assert!(w.temperature.is_finite() && w.temperature >= 0.0);
+    assert!(unit(w.precipitation) && unit(w.cloud_cover) && unit(w.storm_intensity) && unit(w.humidity));
+    assert!(w.wind_speed.is_finite() && w.wind_speed >= 0.0);
+    assert!((0.0..360.0).contains(&w.wind_direction));
+    assert!(unit(c.soil_moisture) && unit(c.mud_level) && unit(c.flood_level) && unit(c.fire_risk));
+    assert!(c.snow_depth.is_finite() && c.snow_depth >= 0.0);
+    assert!(unit(b.vegetation_density) && unit(b.vegetation_health));
+    assert!((-1.0..=1.0).contains(&b.transition_pressure));
+    let grain = &tile.resources.resources[0];
+    assert!((0.0..=grain.max_quantity).contains(&grain.quantity) && grain.renewal_rate >= 0.0);
+    assert!(tile.scratch.values().all(|v| v.is_finite()));
+});
@@ -0,0 +1,13 @@
+//! Decode arbitrary bytes as a snapshot. Corrupt files must fail with an
+//! error, never panic or exhaust memory.
+
+#![no_main]
+
+use std::path::Path;
+
+use libfuzzer_sys::fuzz_target;
+use worldground::persistence::snapshot::decode_snapshot;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_snapshot(data, Path::new("fuzz.bin"));
+});
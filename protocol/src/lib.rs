@@ -0,0 +1,819 @@
+//! Wire types for the worldground server.
+//!
+//! Everything a client needs to decode the WebSocket stream and the `/health`
+//! endpoint, without depending on the simulation engine. Every WebSocket text
+//! message (or deflated binary message, see the server README) is one JSON
+//! [`ServerMessage`], tagged by its `message_type` field. Clients that choose
+//! [`WireFormat::Msgpack`] get the same messages as MessagePack maps with the
+//! same field names, in binary frames:
+//!
+//! ```
+//! use worldground_protocol::ServerMessage;
+//!
+//! # let text = r#"{"message_type":"TickDiff","tick":7,"season":"Spring","changed_tiles":[],
+//! #   "statistics":{"tick":7,"biome_distribution":{},"avg_temperature":288.0,"avg_moisture":0.4,
+//! #   "avg_vegetation_health":0.6,"diversity_index":0.8,"rule_errors":0,"tick_duration_ms":12.0},
+//! #   "pressure_systems":[],"sun":{"subsolar_lat":0.0,"subsolar_lon":0.0}}"#;
+//! match serde_json::from_str::<ServerMessage>(text).unwrap() {
+//!     ServerMessage::WorldSnapshot(snapshot) => println!("{} tiles", snapshot.tiles.len()),
+//!     ServerMessage::TickDiff(diff) => println!("tick {}", diff.tick),
+//!     ServerMessage::TourStep(step) => println!("now showing {}", step.bookmark),
+//!     ServerMessage::TourEnd(end) => println!("tour {} over", end.tour),
+//!     ServerMessage::Events(events) => println!("{} events", events.events.len()),
+//! }
+//! ```
+//!
+//! # Versioning
+//!
+//! The crate version is the protocol version. A minor release may add message
+//! types, optional fields and enum variants. Fields added after 0.1 carry
+//! `#[serde(default)]`, so newer clients still read older servers. Older
+//! clients ignore fields they don't know, but they fail on an unknown enum
+//! variant or message type. Removing or renaming a field, changing its type,
+//! or changing what it means is a major release.
+//!
+//! Clients may send a [`ClientMessage`] back, tagged by `type`, to narrow
+//! the tick diffs they receive to a [`Subscription`] or to switch the
+//! [`WireFormat`] of everything sent after it.
+//!
+//! Temperatures are Kelvin and humidities fractions unless the snapshot's
+//! [`units`](WorldSnapshot::units) say otherwise; see [`units`].
+
+pub mod tile;
+pub mod units;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use tile::*;
+
+/// Any message the server sends over the WebSocket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "message_type")]
+pub enum ServerMessage {
+    /// Sent once on connect, and again whenever the whole world is replaced
+    WorldSnapshot(WorldSnapshot),
+    /// Sent after every tick
+    TickDiff(TickDiff),
+    /// Sent at each stop of a guided tour; viewers move their camera to `view`
+    TourStep(TourStep),
+    /// Sent when a tour finishes or is stopped
+    TourEnd(TourEnd),
+    /// Sent after each tick on which something happened
+    Events(Events),
+}
+
+/// Complete world state sent to a client on connect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub world_id: String,
+    pub name: String,
+    pub tick: u64,
+    pub season: Season,
+    pub season_length: u32,
+    pub tile_count: u32,
+    pub topology_type: TopologyType,
+    pub tiles: Vec<TileSnapshot>,
+    pub pressure_systems: Vec<PressureSystemSnapshot>,
+    pub sun: SunSnapshot,
+    /// Units of temperatures and humidities in this and every later message
+    #[serde(default)]
+    pub units: units::Units,
+}
+
+/// A tile's complete state in a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileSnapshot {
+    pub id: u32,
+    pub position: Position,
+    pub geology: GeologyLayer,
+    pub climate: ClimateLayer,
+    pub biome: BiomeLayer,
+    pub resources: ResourceLayer,
+    pub weather: WeatherLayer,
+    pub conditions: ConditionsLayer,
+}
+
+impl TileSnapshot {
+    pub fn from_tile(tile: &Tile) -> Self {
+        TileSnapshot {
+            id: tile.id,
+            position: tile.position,
+            geology: tile.geology.clone(),
+            climate: tile.climate.clone(),
+            biome: tile.biome.clone(),
+            resources: tile.resources.discovered(),
+            weather: tile.weather.clone(),
+            conditions: tile.conditions.clone(),
+        }
+    }
+}
+
+/// Per-tick diff sent after each simulation tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickDiff {
+    pub tick: u64,
+    pub season: Season,
+    pub changed_tiles: Vec<TileChange>,
+    pub statistics: TickStatSummary,
+    pub pressure_systems: Vec<PressureSystemSnapshot>,
+    pub sun: SunSnapshot,
+    /// Tiles that started flooding this tick
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub floods: Vec<FloodEvent>,
+    /// Geomagnetic storm that started this tick (cosmetic aurora)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aurora: Option<AuroraEvent>,
+    /// Resource deposits that ran out this tick
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depletions: Vec<DepletionEvent>,
+    /// Tiles under each transient effect this tick, in ascending ID order.
+    /// Sent whole every tick, so a tile drops out of a list when its effect ends.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub effects: BTreeMap<TileEffect, Vec<u32>>,
+}
+
+impl TickDiff {
+    /// Fold a later diff into this one, so that together they read as one
+    /// diff from before this one to after `later`. Each tile keeps the newest
+    /// copy of every layer either diff changed. Tick, season, statistics,
+    /// pressure systems and the sun come from `later`; floods and depletions
+    /// of both are kept, as is the aurora onset of either. Lasting effects are
+    /// `later`'s, and one-tick effects of both are kept.
+    pub fn merge(&mut self, later: TickDiff) {
+        let mut index: HashMap<u32, usize> =
+            self.changed_tiles.iter().enumerate().map(|(i, c)| (c.id, i)).collect();
+        for change in later.changed_tiles {
+            match index.get(&change.id) {
+                Some(&i) => {
+                    let earlier = &mut self.changed_tiles[i];
+                    earlier.weather = change.weather.or(earlier.weather.take());
+                    earlier.conditions = change.conditions.or(earlier.conditions.take());
+                    earlier.biome = change.biome.or(earlier.biome.take());
+                    earlier.resources = change.resources.or(earlier.resources.take());
+                    earlier.scratch = change.scratch.or(earlier.scratch.take());
+                }
+                None => {
+                    index.insert(change.id, self.changed_tiles.len());
+                    self.changed_tiles.push(change);
+                }
+            }
+        }
+
+        self.tick = later.tick;
+        self.season = later.season;
+        self.statistics = later.statistics;
+        self.pressure_systems = later.pressure_systems;
+        self.sun = later.sun;
+        self.floods.extend(later.floods);
+        self.aurora = later.aurora.or(self.aurora);
+        self.depletions.extend(later.depletions);
+
+        self.effects.retain(|effect, _| !effect.lasts());
+        for (effect, ids) in later.effects {
+            let merged = self.effects.entry(effect).or_default();
+            merged.extend(ids);
+            merged.sort_unstable();
+            merged.dedup();
+        }
+    }
+}
+
+/// Changed fields for a single tile in a diff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileChange {
+    pub id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherLayer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<ConditionsLayer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub biome: Option<BiomeLayer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourceLayer>,
+    /// Rule scratch variables, only sent when `scratch_in_diffs` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scratch: Option<BTreeMap<String, f64>>,
+}
+
+/// Summary statistics included in tick diffs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickStatSummary {
+    pub tick: u64,
+    pub biome_distribution: HashMap<String, u32>,
+    pub avg_temperature: f32,
+    pub avg_moisture: f32,
+    pub avg_vegetation_health: f32,
+    pub diversity_index: f32,
+    pub rule_errors: u32,
+    pub tick_duration_ms: f32,
+}
+
+/// Snapshot of a pressure system for the wire protocol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PressureSystemSnapshot {
+    pub id: u32,
+    pub lat: f64,
+    pub lon: f64,
+    pub pressure_anomaly: f32,
+    pub radius: f32,
+    pub system_type: String,
+    pub moisture: f32,
+    /// Eastward drift in rad/tick, for motion vectors in the viewer
+    pub velocity_east: f32,
+    /// Northward drift in rad/tick
+    pub velocity_north: f32,
+}
+
+/// Sun position for viewer lighting and terminator rendering.
+///
+/// There is no diurnal cycle yet, so only the seasonal declination moves; the
+/// subsolar point stays on the prime meridian.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SunSnapshot {
+    /// Latitude of the subsolar point in degrees (the solar declination)
+    pub subsolar_lat: f64,
+    /// Longitude of the subsolar point in degrees
+    pub subsolar_lon: f64,
+}
+
+/// A tile whose flood level rose past the flood event threshold this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FloodEvent {
+    pub tile_id: u32,
+    pub flood_level: f32,
+}
+
+/// A transient effect on a tile, for viewers to draw as an overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileEffect {
+    /// Standing floodwater past the flood event threshold
+    Flood,
+    /// A volcano erupting this tick
+    Eruption,
+    /// A slope that gave way this tick
+    Landslide,
+    /// Glacier ice breaking off into the sea this tick
+    Calving,
+    /// Aurora bright enough to see
+    Aurora,
+}
+
+impl TileEffect {
+    /// Whether the effect lasts over several ticks (floods, aurora) rather
+    /// than happening on one (eruptions, landslides, calvings).
+    pub fn lasts(self) -> bool {
+        matches!(self, TileEffect::Flood | TileEffect::Aurora)
+    }
+}
+
+/// A resource deposit whose quantity reached zero this tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepletionEvent {
+    pub tile_id: u32,
+    pub resource_type: String,
+}
+
+/// A geomagnetic storm that began this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AuroraEvent {
+    /// Peak storm strength (0.0-1.0)
+    pub strength: f32,
+    /// Latitude (degrees, both hemispheres) of the brightest aurora at peak
+    pub oval_latitude: f32,
+}
+
+/// A named view of the world: a tile to center on and how much to show.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub tile_id: u32,
+    /// Latitude of the tile in degrees (filled in by the server)
+    #[serde(default)]
+    pub lat: f64,
+    /// Longitude of the tile in degrees (filled in by the server)
+    #[serde(default)]
+    pub lon: f64,
+    /// Angular radius of the region to frame, in degrees (0 keeps the viewer's zoom)
+    #[serde(default)]
+    pub radius_deg: f32,
+    /// Text shown while the view is on screen
+    #[serde(default)]
+    pub caption: String,
+}
+
+/// One stop of a guided tour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TourStep {
+    pub tour: String,
+    /// Position of this stop in the tour, from 0
+    pub step: u32,
+    /// Number of stops in the tour
+    pub steps: u32,
+    /// Name of the bookmark being shown
+    pub bookmark: String,
+    pub view: Bookmark,
+    /// Seconds until the next step or the end of the tour
+    pub dwell_secs: f32,
+}
+
+/// End of a guided tour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TourEnd {
+    pub tour: String,
+    /// False when the tour was stopped or replaced before its last stop ended
+    pub completed: bool,
+}
+
+/// Something that happened in the world: a discrete event, for clients that
+/// want to know what happened rather than read it out of tile diffs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldEvent {
+    /// What happened: one of the kinds below, or any name a rule chose
+    pub kind: String,
+    /// Tick the event happened on
+    pub tick: u64,
+    /// Tile the event happened on; world-wide events have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tile: Option<u32>,
+    /// [`WorldEvent::NATIVE`] for events the simulation raises itself,
+    /// otherwise the name of the rule that emitted it
+    pub source: String,
+    /// Details of the event, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data: BTreeMap<String, EventValue>,
+}
+
+impl WorldEvent {
+    /// A volcano erupted on the tile
+    pub const VOLCANIC_ERUPTION: &'static str = "volcanic_eruption";
+    /// A tropical cyclone came ashore on the tile
+    pub const HURRICANE_LANDFALL: &'static str = "hurricane_landfall";
+    /// The tile's biome changed; `data` has the biomes `from` and `to`
+    pub const BIOME_FLIP: &'static str = "biome_flip";
+    /// The tile has been in drought long enough to call it one
+    pub const DROUGHT_DECLARED: &'static str = "drought_declared";
+    /// An invariant failed on part of its sample; the tile is the first it
+    /// failed on, and `data` has the `invariant`, how many sampled tiles
+    /// `failed` out of `sampled`, and its `message`
+    pub const INVARIANT_VIOLATION: &'static str = "invariant_violation";
+    /// Source of events raised by the simulation rather than a rule
+    pub const NATIVE: &'static str = "native";
+}
+
+/// A value in a [`WorldEvent`]'s data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EventValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+/// The world events of one tick, in the order they happened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Events {
+    pub tick: u64,
+    pub events: Vec<WorldEvent>,
+}
+
+/// Any message a client may send over the WebSocket, tagged by its `type`
+/// field. Messages the server can't parse are ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Receive only some tiles and layers in tick diffs, replacing any
+    /// earlier subscription
+    Subscribe(Subscription),
+    /// Encode every later message in `format`, starting with a fresh
+    /// snapshot
+    SetFormat { format: WireFormat },
+}
+
+/// How server messages are encoded. The messages are the same either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireFormat {
+    /// JSON text frames
+    #[default]
+    Json,
+    /// MessagePack binary frames, with fields as named map keys
+    Msgpack,
+}
+
+/// A layer of a tile's state as sent in tick diffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLayer {
+    Weather,
+    Conditions,
+    Biome,
+    Resources,
+    Scratch,
+}
+
+/// Which parts of each tick diff a client wants. Omitting a field keeps
+/// everything along it, so an empty subscription restores the full stream.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    /// Tile IDs to receive changes and events for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<Vec<u32>>,
+    /// Layers to receive for those tiles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layers: Option<Vec<DiffLayer>>,
+    /// Receive one diff every this many ticks, each covering the ticks since
+    /// the last (see [`TickDiff::merge`]). Never more often than the server
+    /// sends them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub every: Option<u32>,
+}
+
+impl Subscription {
+    /// Whether this subscription lets everything through.
+    pub fn is_everything(&self) -> bool {
+        self.region.is_none() && self.layers.is_none() && self.every.is_none_or(|n| n <= 1)
+    }
+
+    /// The part of `diff` this subscription asks for. Tiles left with no
+    /// changed layer are dropped, and floods, depletions and effects outside
+    /// the region too; statistics, pressure systems, the sun and auroras are
+    /// world-wide and always kept.
+    pub fn filter(&self, diff: &TickDiff) -> TickDiff {
+        let region: Option<std::collections::HashSet<u32>> =
+            self.region.as_ref().map(|ids| ids.iter().copied().collect());
+        let in_region = |id: u32| region.as_ref().is_none_or(|r| r.contains(&id));
+        let wants = |layer| self.layers.as_ref().is_none_or(|l| l.contains(&layer));
+
+        let changed_tiles = diff
+            .changed_tiles
+            .iter()
+            .filter(|c| in_region(c.id))
+            .map(|c| TileChange {
+                id: c.id,
+                weather: c.weather.clone().filter(|_| wants(DiffLayer::Weather)),
+                conditions: c.conditions.clone().filter(|_| wants(DiffLayer::Conditions)),
+                biome: c.biome.clone().filter(|_| wants(DiffLayer::Biome)),
+                resources: c.resources.clone().filter(|_| wants(DiffLayer::Resources)),
+                scratch: c.scratch.clone().filter(|_| wants(DiffLayer::Scratch)),
+            })
+            .filter(|c| {
+                c.weather.is_some()
+                    || c.conditions.is_some()
+                    || c.biome.is_some()
+                    || c.resources.is_some()
+                    || c.scratch.is_some()
+            })
+            .collect();
+
+        TickDiff {
+            tick: diff.tick,
+            season: diff.season,
+            changed_tiles,
+            statistics: diff.statistics.clone(),
+            pressure_systems: diff.pressure_systems.clone(),
+            sun: diff.sun,
+            floods: diff.floods.iter().filter(|f| in_region(f.tile_id)).copied().collect(),
+            aurora: diff.aurora,
+            depletions: diff.depletions.iter().filter(|d| in_region(d.tile_id)).cloned().collect(),
+            effects: diff
+                .effects
+                .iter()
+                .map(|(&effect, ids)| (effect, ids.iter().copied().filter(|&id| in_region(id)).collect::<Vec<_>>()))
+                .filter(|(_, ids)| !ids.is_empty())
+                .collect(),
+        }
+    }
+
+    /// The part of `events` this subscription asks for: events on tiles
+    /// outside the region are dropped, world-wide ones kept. Layers and
+    /// cadence don't apply to events.
+    pub fn filter_events(&self, events: &Events) -> Events {
+        let region: Option<std::collections::HashSet<u32>> =
+            self.region.as_ref().map(|ids| ids.iter().copied().collect());
+        Events {
+            tick: events.tick,
+            events: events
+                .events
+                .iter()
+                .filter(|e| e.tile.is_none_or(|id| region.as_ref().is_none_or(|r| r.contains(&id))))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Health endpoint response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub tick: u64,
+    pub tick_rate: f32,
+    pub diversity_index: f32,
+    pub rule_errors: u32,
+    pub snapshot_age_ticks: u64,
+    pub tile_count: u32,
+    pub season: Season,
+    /// 95th-percentile lateness of recent tick starts against the schedule (ms)
+    pub jitter_p95_ms: f32,
+    /// 99th-percentile lateness of recent tick starts against the schedule (ms)
+    pub jitter_p99_ms: f32,
+    /// Ticks that took longer than their slot since startup
+    pub overrun_ticks: u64,
+    /// Whether ticks are currently overrunning their slot several times in a row
+    pub overrunning: bool,
+    /// Invariant violations since startup
+    pub invariant_violations: u64,
+    /// Invariants that failed at the most recent check
+    pub failing_invariants: Vec<String>,
+    /// Whether the simulation is paused through the control endpoint
+    #[serde(default)]
+    pub paused: bool,
+    /// Overall verdict; `/health` answers 503 when unhealthy
+    #[serde(default)]
+    pub status: HealthState,
+    /// Why the status isn't ok, one line per limit crossed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status_reasons: Vec<String>,
+}
+
+/// How well the simulation is running, worst last.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    #[default]
+    Ok,
+    /// Running, but past a warning limit
+    Degraded,
+    /// Past a limit orchestrators should act on
+    Unhealthy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(message: &ServerMessage) -> ServerMessage {
+        let json = serde_json::to_string(message).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn tick_diff() -> TickDiff {
+        TickDiff {
+            tick: 5,
+            season: Season::Winter,
+            changed_tiles: vec![TileChange {
+                id: 42,
+                weather: Some(Tile::new_default(42, vec![], Position::flat(0.0, 0.0)).weather),
+                conditions: None,
+                biome: None,
+                resources: None,
+                scratch: Some(BTreeMap::from([("ticks_since_fire".to_string(), 3.0)])),
+            }],
+            statistics: TickStatSummary {
+                tick: 5,
+                biome_distribution: HashMap::from([("Grassland".to_string(), 10)]),
+                avg_temperature: 270.0,
+                avg_moisture: 0.4,
+                avg_vegetation_health: 0.6,
+                diversity_index: 0.8,
+                rule_errors: 0,
+                tick_duration_ms: 50.0,
+            },
+            pressure_systems: vec![PressureSystemSnapshot {
+                id: 3,
+                lat: 40.0,
+                lon: 10.0,
+                pressure_anomaly: -12.0,
+                radius: 0.3,
+                system_type: "MidLatCyclone".to_string(),
+                moisture: 0.6,
+                velocity_east: 0.02,
+                velocity_north: -0.01,
+            }],
+            sun: SunSnapshot { subsolar_lat: -20.0, subsolar_lon: 0.0 },
+            floods: vec![FloodEvent { tile_id: 7, flood_level: 0.4 }],
+            aurora: Some(AuroraEvent { strength: 0.8, oval_latitude: 55.0 }),
+            depletions: vec![DepletionEvent { tile_id: 42, resource_type: "timber".to_string() }],
+            effects: BTreeMap::from([(TileEffect::Flood, vec![7, 42]), (TileEffect::Aurora, vec![3])]),
+        }
+    }
+
+    #[test]
+    fn tick_diff_round_trips_with_message_type() {
+        let message = ServerMessage::TickDiff(tick_diff());
+        let json: serde_json::Value = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["message_type"], "TickDiff");
+        // Effects are keyed by name, each with its list of tiles
+        assert_eq!(json["effects"]["flood"], serde_json::json!([7, 42]));
+        assert_eq!(round_trip(&message), message);
+    }
+
+    #[test]
+    fn world_snapshot_round_trips() {
+        let tile = Tile::new_default(0, vec![1], Position::flat(1.0, 2.0));
+        let message = ServerMessage::WorldSnapshot(WorldSnapshot {
+            world_id: "d4c2".to_string(),
+            name: "test".to_string(),
+            tick: 42,
+            season: Season::Summer,
+            season_length: 90,
+            tile_count: 1,
+            topology_type: TopologyType::Geodesic,
+            tiles: vec![TileSnapshot::from_tile(&tile)],
+            pressure_systems: Vec::new(),
+            sun: SunSnapshot { subsolar_lat: 23.44, subsolar_lon: 0.0 },
+            units: Default::default(),
+        });
+        assert!(serde_json::to_string(&message).unwrap().starts_with(r#"{"message_type":"WorldSnapshot""#));
+        assert_eq!(round_trip(&message), message);
+    }
+
+    #[test]
+    fn optional_diff_fields_may_be_absent() {
+        let mut diff = tick_diff();
+        diff.floods.clear();
+        diff.aurora = None;
+        diff.depletions.clear();
+        diff.effects.clear();
+        diff.changed_tiles[0].scratch = None;
+        let json = serde_json::to_string(&ServerMessage::TickDiff(diff.clone())).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value.get("floods").is_none() && value.get("aurora").is_none());
+        assert!(value.get("depletions").is_none() && value.get("effects").is_none());
+        assert!(!json.contains("null"));
+        assert_eq!(serde_json::from_str::<ServerMessage>(&json).unwrap(), ServerMessage::TickDiff(diff));
+    }
+
+    #[test]
+    fn tour_messages_round_trip() {
+        let step = ServerMessage::TourStep(TourStep {
+            tour: "coasts".to_string(),
+            step: 1,
+            steps: 3,
+            bookmark: "delta".to_string(),
+            view: Bookmark { tile_id: 12, lat: 10.5, lon: -40.0, radius_deg: 8.0, caption: "River delta".to_string() },
+            dwell_secs: 6.0,
+        });
+        let json: serde_json::Value = serde_json::to_value(&step).unwrap();
+        assert_eq!(json["message_type"], "TourStep");
+        assert_eq!(round_trip(&step), step);
+
+        let end = ServerMessage::TourEnd(TourEnd { tour: "coasts".to_string(), completed: false });
+        assert_eq!(round_trip(&end), end);
+
+        // Only the tile is required when a bookmark is written by hand
+        let bookmark: Bookmark = serde_json::from_str(r#"{"tile_id":4}"#).unwrap();
+        assert_eq!((bookmark.radius_deg, bookmark.caption.as_str()), (0.0, ""));
+    }
+
+    #[test]
+    fn events_round_trip_and_follow_the_region() {
+        let flip = WorldEvent {
+            kind: WorldEvent::BIOME_FLIP.to_string(),
+            tick: 12,
+            tile: Some(42),
+            source: WorldEvent::NATIVE.to_string(),
+            data: BTreeMap::from([
+                ("from".to_string(), EventValue::Text("Grassland".to_string())),
+                ("to".to_string(), EventValue::Text("Desert".to_string())),
+            ]),
+        };
+        let comet = WorldEvent {
+            kind: "comet_sighted".to_string(),
+            tick: 12,
+            tile: None,
+            source: "50-comets.rhai".to_string(),
+            data: BTreeMap::from([
+                ("brightness".to_string(), EventValue::Number(2.5)),
+                ("named".to_string(), EventValue::Bool(false)),
+            ]),
+        };
+        let message = ServerMessage::Events(Events { tick: 12, events: vec![flip.clone(), comet.clone()] });
+        let json: serde_json::Value = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["message_type"], "Events");
+        assert_eq!(json["events"][0]["data"]["to"], "Desert");
+        assert!(json["events"][1].get("tile").is_none());
+        assert_eq!(round_trip(&message), message);
+        let bytes = rmp_serde::to_vec_named(&message).unwrap();
+        assert_eq!(rmp_serde::from_slice::<ServerMessage>(&bytes).unwrap(), message);
+
+        let ServerMessage::Events(events) = &message else { unreachable!() };
+        let elsewhere = Subscription { region: Some(vec![7]), ..Default::default() };
+        assert_eq!(elsewhere.filter_events(events).events, vec![comet], "world-wide events are kept");
+        let here = Subscription { region: Some(vec![42]), layers: Some(vec![DiffLayer::Weather]), ..Default::default() };
+        assert_eq!(here.filter_events(events), *events);
+    }
+
+    #[test]
+    fn subscriptions_narrow_diffs_to_tiles_and_layers() {
+        let message: ClientMessage =
+            serde_json::from_str(r#"{"type":"subscribe","region":[42,8],"layers":["weather"]}"#).unwrap();
+        let ClientMessage::Subscribe(subscription) = message else { panic!("expected a subscription") };
+        assert_eq!(subscription.region, Some(vec![42, 8]));
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"type":"subscribe","layers":["clouds"]}"#).is_err());
+
+        let mut diff = tick_diff();
+        diff.changed_tiles.push(TileChange { id: 9, ..diff.changed_tiles[0].clone() });
+        let filtered = subscription.filter(&diff);
+        assert_eq!(filtered.changed_tiles.len(), 1);
+        let change = &filtered.changed_tiles[0];
+        assert_eq!(change.id, 42);
+        assert!(change.weather.is_some() && change.scratch.is_none());
+        assert!(filtered.floods.is_empty(), "tile 7 is outside the region");
+        assert_eq!(filtered.effects, BTreeMap::from([(TileEffect::Flood, vec![42])]));
+        assert_eq!(filtered.depletions, diff.depletions);
+        assert_eq!((filtered.statistics, filtered.aurora), (diff.statistics.clone(), diff.aurora));
+
+        // A tile with none of the wanted layers changed is left out
+        let biome_only = Subscription { layers: Some(vec![DiffLayer::Biome]), ..Default::default() };
+        assert!(biome_only.filter(&diff).changed_tiles.is_empty());
+
+        let everything = Subscription::default();
+        assert!(everything.is_everything());
+        assert_eq!(everything.filter(&diff), diff);
+    }
+
+    #[test]
+    fn merged_diffs_keep_the_newest_layers_and_every_event() {
+        let mut first = tick_diff();
+        first.changed_tiles[0].conditions = Some(Tile::new_default(42, vec![], Position::flat(0.0, 0.0)).conditions);
+        let mut second = tick_diff();
+        second.tick = 6;
+        second.changed_tiles[0].weather.as_mut().unwrap().temperature = 250.0;
+        second.changed_tiles.push(TileChange { id: 9, ..second.changed_tiles[0].clone() });
+        second.floods = vec![FloodEvent { tile_id: 8, flood_level: 0.5 }];
+        second.aurora = None;
+        second.effects = BTreeMap::from([(TileEffect::Flood, vec![8]), (TileEffect::Eruption, vec![11])]);
+        first.effects.insert(TileEffect::Eruption, vec![2]);
+
+        first.merge(second);
+        assert_eq!(first.tick, 6);
+        assert_eq!(first.changed_tiles.len(), 2);
+        let tile = &first.changed_tiles[0];
+        assert_eq!(tile.weather.as_ref().unwrap().temperature, 250.0, "the later weather wins");
+        assert!(tile.conditions.is_some(), "layers only the earlier diff changed are kept");
+        assert_eq!(first.floods.len(), 2);
+        assert!(first.aurora.is_some(), "the storm started within the merged ticks");
+        assert_eq!(first.effects[&TileEffect::Flood], vec![8], "lasting effects are the latest");
+        assert_eq!(first.effects[&TileEffect::Eruption], vec![2, 11], "one-tick effects accumulate");
+        assert!(!first.effects.contains_key(&TileEffect::Aurora), "the aurora faded");
+
+        let slow: ClientMessage = serde_json::from_str(r#"{"type":"subscribe","every":10}"#).unwrap();
+        let ClientMessage::Subscribe(slow) = slow else { panic!("expected a subscription") };
+        assert_eq!(slow.every, Some(10));
+        assert!(!slow.is_everything());
+        assert!(Subscription { every: Some(1), ..Default::default() }.is_everything());
+    }
+
+    #[test]
+    fn messages_round_trip_through_msgpack() {
+        let tile = Tile::new_default(0, vec![1], Position::flat(1.0, 2.0));
+        let snapshot = ServerMessage::WorldSnapshot(WorldSnapshot {
+            world_id: "d4c2".to_string(),
+            name: "test".to_string(),
+            tick: 42,
+            season: Season::Summer,
+            season_length: 90,
+            tile_count: 1,
+            topology_type: TopologyType::Geodesic,
+            tiles: vec![TileSnapshot::from_tile(&tile)],
+            pressure_systems: Vec::new(),
+            sun: SunSnapshot { subsolar_lat: 23.44, subsolar_lon: 0.0 },
+            units: Default::default(),
+        });
+        for message in [ServerMessage::TickDiff(tick_diff()), snapshot] {
+            let bytes = rmp_serde::to_vec_named(&message).unwrap();
+            assert_eq!(rmp_serde::from_slice::<ServerMessage>(&bytes).unwrap(), message);
+            assert!(bytes.len() < serde_json::to_string(&message).unwrap().len());
+        }
+
+        let message: ClientMessage = serde_json::from_str(r#"{"type":"set_format","format":"msgpack"}"#).unwrap();
+        assert_eq!(message, ClientMessage::SetFormat { format: WireFormat::Msgpack });
+    }
+
+    #[test]
+    fn health_status_round_trips() {
+        let health = HealthStatus {
+            tick: 100,
+            tick_rate: 1.0,
+            diversity_index: 0.7,
+            rule_errors: 0,
+            snapshot_age_ticks: 5,
+            tile_count: 1000,
+            season: Season::Autumn,
+            jitter_p95_ms: 3.5,
+            jitter_p99_ms: 12.0,
+            overrun_ticks: 0,
+            overrunning: false,
+            invariant_violations: 2,
+            failing_invariants: vec!["moisture_bounded".to_string()],
+            paused: true,
+            status: HealthState::Degraded,
+            status_reasons: vec!["rule errors on 2.0% of tiles".to_string()],
+        };
+        let json = serde_json::to_string(&health).unwrap();
+        assert!(json.contains(r#""status":"degraded""#));
+        assert_eq!(serde_json::from_str::<HealthStatus>(&json).unwrap(), health);
+        assert!(HealthState::Unhealthy > HealthState::Degraded);
+    }
+}
@@ -0,0 +1,535 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// === Enums ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TerrainType {
+    Ocean,
+    Coast,
+    Plains,
+    Hills,
+    Mountains,
+    Cliffs,
+    Wetlands,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SoilType {
+    Sand,
+    Clay,
+    Loam,
+    Rock,
+    Silt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClimateZone {
+    Polar,
+    Subpolar,
+    Temperate,
+    Subtropical,
+    Tropical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BiomeType {
+    Ocean,
+    Ice,
+    Tundra,
+    BorealForest,
+    TemperateForest,
+    Grassland,
+    Savanna,
+    Desert,
+    TropicalForest,
+    Wetland,
+    Barren,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PrecipitationType {
+    None,
+    Rain,
+    Snow,
+    Hail,
+    Sleet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn next(self) -> Season {
+        match self {
+            Season::Spring => Season::Summer,
+            Season::Summer => Season::Autumn,
+            Season::Autumn => Season::Winter,
+            Season::Winter => Season::Spring,
+        }
+    }
+
+    /// Position in the year, 0 (Spring) through 3 (Winter).
+    pub fn index(self) -> u32 {
+        match self {
+            Season::Spring => 0,
+            Season::Summer => 1,
+            Season::Autumn => 2,
+            Season::Winter => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TopologyType {
+    FlatHex,
+    Geodesic,
+}
+
+// === Position ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Position {
+    /// Create a flat-mode position (z/lat/lon default to 0.0).
+    /// Used by flat hex grid and in tests for convenience.
+    pub fn flat(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            z: 0.0,
+            lat: 0.0,
+            lon: 0.0,
+        }
+    }
+}
+
+// === Layer Structs ===
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeologyLayer {
+    pub terrain_type: TerrainType,
+    pub elevation: f32,
+    pub soil_type: SoilType,
+    pub drainage: f32,
+    pub tectonic_stress: f32,
+    /// Depth of loose sediment laid down by rivers and wind, in elevation units.
+    #[serde(default)]
+    pub sediment: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClimateLayer {
+    pub zone: ClimateZone,
+    pub base_temperature: f32,
+    pub base_precipitation: f32,
+    pub latitude: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BiomeLayer {
+    pub biome_type: BiomeType,
+    pub vegetation_density: f32,
+    pub vegetation_health: f32,
+    pub transition_pressure: f32,
+    pub ticks_in_current_biome: u32,
+    /// How well roots bind the soil (0.0-1.0). Follows vegetation density,
+    /// regrowing slowly and rotting away after the cover is lost.
+    #[serde(default = "default_root_strength")]
+    pub root_strength: f32,
+}
+
+fn default_root_strength() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDeposit {
+    pub resource_type: String,
+    pub quantity: f32,
+    pub max_quantity: f32,
+    pub renewal_rate: f32,
+    pub requires_biome: Option<Vec<BiomeType>>,
+    /// Grade of the deposit (0.0-1.0), drawn from the tile's geology at generation
+    #[serde(default = "default_quality")]
+    pub quality: f32,
+    /// Whether the deposit has been surveyed. Undiscovered deposits still
+    /// simulate but are left out of what viewers and clients receive.
+    #[serde(default = "default_discovered")]
+    pub discovered: bool,
+}
+
+fn default_quality() -> f32 {
+    0.5
+}
+
+fn default_discovered() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceLayer {
+    pub resources: Vec<ResourceDeposit>,
+}
+
+impl ResourceLayer {
+    /// The layer as clients see it: only deposits that have been discovered.
+    pub fn discovered(&self) -> ResourceLayer {
+        ResourceLayer {
+            resources: self.resources.iter().filter(|r| r.discovered).cloned().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherLayer {
+    pub temperature: f32,
+    pub precipitation: f32,
+    pub precipitation_type: PrecipitationType,
+    pub wind_speed: f32,
+    pub wind_direction: f32,
+    pub cloud_cover: f32,
+    pub humidity: f32,
+    pub storm_intensity: f32,
+    /// Sea-level pressure in hPa (default 1013.25), set by macro weather.
+    /// Comparable across tiles whatever their elevation.
+    #[serde(default = "default_pressure")]
+    pub pressure: f32,
+    /// Station pressure in hPa: `pressure` reduced to the tile's surface
+    /// elevation. Equal to `pressure` at and below sea level.
+    #[serde(default = "default_pressure")]
+    pub station_pressure: f32,
+    /// Wind speed from macro pressure systems (m/s equivalent scale)
+    #[serde(default)]
+    pub macro_wind_speed: f32,
+    /// Wind direction from macro pressure systems (degrees, 0=N)
+    #[serde(default)]
+    pub macro_wind_direction: f32,
+    /// Humidity contribution from macro pressure systems (0.0-1.0)
+    #[serde(default)]
+    pub macro_humidity: f32,
+    /// Cosmetic aurora brightness (0.0-1.0) from the optional space weather driver
+    #[serde(default)]
+    pub aurora: f32,
+    /// Airborne dust and volcanic ash (0.0-1.0) from the optional dust transport step
+    #[serde(default)]
+    pub dust: f32,
+}
+
+fn default_pressure() -> f32 {
+    1013.25
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionsLayer {
+    pub soil_moisture: f32,
+    pub snow_depth: f32,
+    pub mud_level: f32,
+    pub flood_level: f32,
+    pub frost_days: u32,
+    pub drought_days: u32,
+    pub fire_risk: f32,
+    /// Freeze-thaw cycles since the tile's soil last changed type.
+    #[serde(default)]
+    pub freeze_thaw_cycles: u32,
+    /// How easily the ground can be crossed on foot or by vehicle
+    /// (0.0 impassable - 1.0 firm and open), derived from the other conditions.
+    #[serde(default = "default_trafficability")]
+    pub trafficability: f32,
+    /// Fertile silt left by receding floods (0.0-1.0), fading slowly.
+    #[serde(default)]
+    pub silt_fertility: f32,
+    /// Potential evapotranspiration this tick, in precipitation units.
+    #[serde(default)]
+    pub pet: f32,
+    /// Running precipitation / PET ratio; below 0.5 counts as drought.
+    #[serde(default = "default_aridity_index")]
+    pub aridity_index: f32,
+    /// Fog density (0.0-1.0).
+    #[serde(default)]
+    pub fog: f32,
+    /// Horizontal visibility through fog and precipitation, km.
+    #[serde(default = "default_visibility_km")]
+    pub visibility_km: f32,
+    /// How many people or animals the tile could support (0.0-1.0), derived
+    /// from climate, water, soil, vegetation and resources.
+    #[serde(default)]
+    pub carrying_capacity: f32,
+    /// Water flowing through the tile, in precipitation units per tick: its
+    /// own runoff plus everything draining into it from upstream.
+    #[serde(default)]
+    pub river_flow: f32,
+    /// How full the groundwater under the tile is (0.0-1.0).
+    #[serde(default)]
+    pub water_table: f32,
+    /// Sea-surface salinity in PSU on ocean tiles, from the optional ocean
+    /// circulation step; 0.0 on land and until the step first runs.
+    #[serde(default)]
+    pub salinity: f32,
+    /// Glacier ice on the tile, in the same water-equivalent units as
+    /// `snow_depth`, from the optional glacier step.
+    #[serde(default)]
+    pub glacier_mass: f32,
+    /// Glacier ice that melted this tick, routed downhill with the runoff.
+    #[serde(default)]
+    pub glacier_melt: f32,
+    /// Significant wave height in meters on ocean tiles, from the optional
+    /// wave step; 0.0 on land and until the step first runs.
+    #[serde(default)]
+    pub wave_height: f32,
+}
+
+fn default_trafficability() -> f32 {
+    1.0
+}
+
+fn default_aridity_index() -> f32 {
+    1.0
+}
+
+fn default_visibility_km() -> f32 {
+    20.0
+}
+
+// === Tile ===
+
+/// Maximum number of scratch variables a single tile may hold.
+pub const MAX_SCRATCH_KEYS: usize = 16;
+
+/// Maximum length of a scratch variable name.
+pub const MAX_SCRATCH_KEY_LEN: usize = 32;
+
+/// Whether `key` is a valid scratch variable name: 1-32 ASCII letters,
+/// digits or underscores.
+pub fn is_valid_scratch_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_SCRATCH_KEY_LEN
+        && key.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tile {
+    pub id: u32,
+    pub neighbors: Vec<u32>,
+    pub position: Position,
+    pub geology: GeologyLayer,
+    pub climate: ClimateLayer,
+    pub biome: BiomeLayer,
+    pub resources: ResourceLayer,
+    pub weather: WeatherLayer,
+    pub conditions: ConditionsLayer,
+    /// Rule-defined scalars that persist across ticks (e.g. "ticks_since_fire").
+    /// Bounded by MAX_SCRATCH_KEYS.
+    #[serde(default)]
+    pub scratch: BTreeMap<String, f64>,
+}
+
+impl Tile {
+    /// Create a tile with neutral default values for all layers.
+    /// Used during topology generation; world generation overwrites all layer data.
+    pub fn new_default(id: u32, neighbors: Vec<u32>, position: Position) -> Self {
+        Self {
+            id,
+            neighbors,
+            position,
+            geology: GeologyLayer {
+                terrain_type: TerrainType::Plains,
+                elevation: 0.0,
+                soil_type: SoilType::Loam,
+                drainage: 0.5,
+                tectonic_stress: 0.0,
+                sediment: 0.0,
+            },
+            climate: ClimateLayer {
+                zone: ClimateZone::Temperate,
+                base_temperature: 288.15,
+                base_precipitation: 0.5,
+                latitude: 0.0,
+            },
+            biome: BiomeLayer {
+                biome_type: BiomeType::Grassland,
+                vegetation_density: 0.5,
+                vegetation_health: 1.0,
+                transition_pressure: 0.0,
+                ticks_in_current_biome: 0,
+                root_strength: 0.5,
+            },
+            resources: ResourceLayer {
+                resources: Vec::new(),
+            },
+            weather: WeatherLayer {
+                temperature: 288.15,
+                precipitation: 0.0,
+                precipitation_type: PrecipitationType::None,
+                wind_speed: 0.0,
+                wind_direction: 0.0,
+                cloud_cover: 0.3,
+                humidity: 0.3,
+                storm_intensity: 0.0,
+                pressure: 1013.25,
+                station_pressure: 1013.25,
+                macro_wind_speed: 0.0,
+                macro_wind_direction: 0.0,
+                macro_humidity: 0.0,
+                aurora: 0.0,
+                dust: 0.0,
+            },
+            conditions: ConditionsLayer {
+                soil_moisture: 0.3,
+                snow_depth: 0.0,
+                mud_level: 0.0,
+                flood_level: 0.0,
+                frost_days: 0,
+                drought_days: 0,
+                fire_risk: 0.0,
+                freeze_thaw_cycles: 0,
+                trafficability: 1.0,
+                silt_fertility: 0.0,
+                pet: 0.0,
+                aridity_index: 1.0,
+                fog: 0.0,
+                visibility_km: 20.0,
+                carrying_capacity: 0.0,
+                river_flow: 0.0,
+                water_table: 0.0,
+                salinity: 0.0,
+                glacier_mass: 0.0,
+                glacier_melt: 0.0,
+                wave_height: 0.0,
+            },
+            scratch: BTreeMap::new(),
+        }
+    }
+
+    /// Trafficability implied by the current terrain and conditions. Mud is
+    /// ignored while the ground is frozen (`frost_days > 0`); snow, flooding
+    /// and rough terrain always slow travel. Open water is impassable.
+    pub fn compute_trafficability(&self) -> f32 {
+        let terrain = match self.geology.terrain_type {
+            TerrainType::Ocean => return 0.0,
+            TerrainType::Cliffs => 0.1,
+            TerrainType::Mountains => 0.3,
+            TerrainType::Wetlands => 0.4,
+            TerrainType::Hills => 0.8,
+            _ => 1.0,
+        };
+        let c = &self.conditions;
+        let mud = if c.frost_days > 0 { 0.0 } else { c.mud_level };
+        let snow = 0.5 * c.snow_depth.min(1.0);
+        (terrain * (1.0 - mud) * (1.0 - snow) * (1.0 - c.flood_level)).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_creation_has_all_layers() {
+        let tile = Tile::new_default(0, vec![1, 2, 3, 4, 5, 6], Position::flat(0.0, 0.0));
+        assert_eq!(tile.id, 0);
+        assert_eq!(tile.neighbors.len(), 6);
+        assert_eq!(tile.geology.terrain_type, TerrainType::Plains);
+        assert_eq!(tile.geology.elevation, 0.0);
+        assert_eq!(tile.geology.soil_type, SoilType::Loam);
+        assert_eq!(tile.climate.zone, ClimateZone::Temperate);
+        assert_eq!(tile.climate.base_temperature, 288.15);
+        assert_eq!(tile.biome.biome_type, BiomeType::Grassland);
+        assert_eq!(tile.biome.vegetation_health, 1.0);
+        assert!(tile.resources.resources.is_empty());
+        assert_eq!(tile.weather.precipitation_type, PrecipitationType::None);
+        assert_eq!(tile.weather.storm_intensity, 0.0);
+        assert_eq!(tile.conditions.frost_days, 0);
+        assert_eq!(tile.conditions.drought_days, 0);
+    }
+
+    #[test]
+    fn tile_serde_round_trip() {
+        let mut tile =
+            Tile::new_default(42, vec![1, 2, 3, 4, 5, 6], Position::flat(10.5, 20.3));
+        tile.resources.resources.push(ResourceDeposit {
+            resource_type: "iron".to_string(),
+            quantity: 50.0,
+            max_quantity: 100.0,
+            renewal_rate: 0.0,
+            requires_biome: Some(vec![BiomeType::Grassland, BiomeType::BorealForest]),
+            quality: 0.8,
+            discovered: false,
+        });
+        tile.scratch.insert("ticks_since_fire".to_string(), 12.0);
+        let encoded = bincode::serialize(&tile).expect("serialize");
+        let decoded: Tile = bincode::deserialize(&encoded).expect("deserialize");
+        assert_eq!(tile, decoded);
+    }
+
+    #[test]
+    fn scratch_key_validation() {
+        assert!(is_valid_scratch_key("ticks_since_fire"));
+        assert!(is_valid_scratch_key("a1"));
+        assert!(!is_valid_scratch_key(""));
+        assert!(!is_valid_scratch_key("has space"));
+        assert!(!is_valid_scratch_key("dotted.key"));
+        assert!(!is_valid_scratch_key(&"k".repeat(MAX_SCRATCH_KEY_LEN + 1)));
+    }
+
+    #[test]
+    fn season_cycles_correctly() {
+        assert_eq!(Season::Spring.next(), Season::Summer);
+        assert_eq!(Season::Summer.next(), Season::Autumn);
+        assert_eq!(Season::Autumn.next(), Season::Winter);
+        assert_eq!(Season::Winter.next(), Season::Spring);
+    }
+
+    #[test]
+    fn all_terrain_types_serialize() {
+        let types = [
+            TerrainType::Ocean,
+            TerrainType::Coast,
+            TerrainType::Plains,
+            TerrainType::Hills,
+            TerrainType::Mountains,
+            TerrainType::Cliffs,
+            TerrainType::Wetlands,
+        ];
+        for t in &types {
+            let encoded = bincode::serialize(t).expect("serialize");
+            let decoded: TerrainType = bincode::deserialize(&encoded).expect("deserialize");
+            assert_eq!(*t, decoded);
+        }
+    }
+
+    #[test]
+    fn all_biome_types_serialize() {
+        let types = [
+            BiomeType::Ocean,
+            BiomeType::Ice,
+            BiomeType::Tundra,
+            BiomeType::BorealForest,
+            BiomeType::TemperateForest,
+            BiomeType::Grassland,
+            BiomeType::Savanna,
+            BiomeType::Desert,
+            BiomeType::TropicalForest,
+            BiomeType::Wetland,
+            BiomeType::Barren,
+        ];
+        for b in &types {
+            let encoded = bincode::serialize(b).expect("serialize");
+            let decoded: BiomeType = bincode::deserialize(&encoded).expect("deserialize");
+            assert_eq!(*b, decoded);
+        }
+    }
+}
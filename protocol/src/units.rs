@@ -0,0 +1,264 @@
+//! Units of temperatures and humidities on the wire.
+//!
+//! The simulation works in Kelvin and in humidity fractions (0.0-1.0). A
+//! server can be configured to send either in a unit of its choice instead;
+//! [`WorldSnapshot::units`](crate::WorldSnapshot::units) says which, and
+//! every later message of the connection uses the same. Only these fields
+//! change: `weather.temperature`, `climate.base_temperature` and the
+//! statistics' `avg_temperature`, and `weather.humidity` and
+//! `weather.macro_humidity`.
+//!
+//! ```
+//! use worldground_protocol::units::TemperatureUnit;
+//!
+//! assert_eq!(TemperatureUnit::Celsius.from_kelvin(273.15), 0.0);
+//! assert_eq!(TemperatureUnit::Fahrenheit.to_kelvin(212.0), 373.15);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::tile::{ClimateLayer, WeatherLayer};
+use crate::{ServerMessage, TickDiff, TickStatSummary, TileChange, TileSnapshot, WorldSnapshot};
+
+/// 0 °C in Kelvin.
+pub const ZERO_CELSIUS: f64 = 273.15;
+
+/// How temperatures are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureUnit {
+    /// As simulated
+    #[default]
+    Kelvin,
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// `kelvin` in this unit.
+    pub fn from_kelvin(self, kelvin: f32) -> f32 {
+        let celsius = kelvin - ZERO_CELSIUS as f32;
+        match self {
+            TemperatureUnit::Kelvin => kelvin,
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 1.8 + 32.0,
+        }
+    }
+
+    /// `value`, in this unit, in Kelvin.
+    pub fn to_kelvin(self, value: f32) -> f32 {
+        match self {
+            TemperatureUnit::Kelvin => value,
+            TemperatureUnit::Celsius => value + ZERO_CELSIUS as f32,
+            TemperatureUnit::Fahrenheit => (value - 32.0) / 1.8 + ZERO_CELSIUS as f32,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Kelvin => "K",
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+}
+
+/// How humidities are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HumidityUnit {
+    /// 0.0-1.0, as simulated
+    #[default]
+    Fraction,
+    /// 0-100
+    Percent,
+}
+
+impl HumidityUnit {
+    /// `fraction` (0.0-1.0) in this unit.
+    pub fn from_fraction(self, fraction: f32) -> f32 {
+        match self {
+            HumidityUnit::Fraction => fraction,
+            HumidityUnit::Percent => fraction * 100.0,
+        }
+    }
+
+    /// `value`, in this unit, as a fraction.
+    pub fn to_fraction(self, value: f32) -> f32 {
+        match self {
+            HumidityUnit::Fraction => value,
+            HumidityUnit::Percent => value / 100.0,
+        }
+    }
+
+    /// `None` for a bare fraction.
+    pub fn symbol(self) -> Option<&'static str> {
+        match self {
+            HumidityUnit::Fraction => None,
+            HumidityUnit::Percent => Some("%"),
+        }
+    }
+}
+
+/// What a tile field with a choice of unit measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Temperature,
+    Humidity,
+}
+
+impl Quantity {
+    /// The quantity at a tile field path ("weather.temperature"), if its
+    /// unit can be chosen.
+    pub fn of_field(path: &str) -> Option<Quantity> {
+        match path {
+            "weather.temperature" | "climate.base_temperature" => Some(Quantity::Temperature),
+            "weather.humidity" | "weather.macro_humidity" => Some(Quantity::Humidity),
+            _ => None,
+        }
+    }
+}
+
+/// The units a server sends temperatures and humidities in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Units {
+    #[serde(default)]
+    pub temperature: TemperatureUnit,
+    #[serde(default)]
+    pub humidity: HumidityUnit,
+}
+
+impl Units {
+    /// Whether these are the simulation's own units, so nothing changes.
+    pub fn is_default(&self) -> bool {
+        *self == Units::default()
+    }
+
+    /// A value of `quantity`, as simulated, in these units.
+    pub fn convert(&self, quantity: Quantity, value: f32) -> f32 {
+        match quantity {
+            Quantity::Temperature => self.temperature.from_kelvin(value),
+            Quantity::Humidity => self.humidity.from_fraction(value),
+        }
+    }
+
+    /// Symbol of `quantity` in these units, if it has one.
+    pub fn symbol(&self, quantity: Quantity) -> Option<&'static str> {
+        match quantity {
+            Quantity::Temperature => Some(self.temperature.symbol()),
+            Quantity::Humidity => self.humidity.symbol(),
+        }
+    }
+}
+
+/// A wire value that holds temperatures or humidities, converted from the
+/// simulation's units.
+pub trait InUnits {
+    fn in_units(self, units: Units) -> Self;
+}
+
+impl InUnits for WeatherLayer {
+    fn in_units(mut self, units: Units) -> Self {
+        self.temperature = units.temperature.from_kelvin(self.temperature);
+        self.humidity = units.humidity.from_fraction(self.humidity);
+        self.macro_humidity = units.humidity.from_fraction(self.macro_humidity);
+        self
+    }
+}
+
+impl InUnits for ClimateLayer {
+    fn in_units(mut self, units: Units) -> Self {
+        self.base_temperature = units.temperature.from_kelvin(self.base_temperature);
+        self
+    }
+}
+
+impl InUnits for TileSnapshot {
+    fn in_units(mut self, units: Units) -> Self {
+        self.weather = self.weather.in_units(units);
+        self.climate = self.climate.in_units(units);
+        self
+    }
+}
+
+impl InUnits for TileChange {
+    fn in_units(mut self, units: Units) -> Self {
+        self.weather = self.weather.map(|w| w.in_units(units));
+        self
+    }
+}
+
+impl InUnits for TickStatSummary {
+    fn in_units(mut self, units: Units) -> Self {
+        self.avg_temperature = units.temperature.from_kelvin(self.avg_temperature);
+        self
+    }
+}
+
+impl InUnits for WorldSnapshot {
+    fn in_units(mut self, units: Units) -> Self {
+        if units.is_default() {
+            return self;
+        }
+        self.tiles = self.tiles.into_iter().map(|t| t.in_units(units)).collect();
+        self.units = units;
+        self
+    }
+}
+
+impl InUnits for TickDiff {
+    fn in_units(mut self, units: Units) -> Self {
+        if units.is_default() {
+            return self;
+        }
+        self.changed_tiles = self.changed_tiles.into_iter().map(|c| c.in_units(units)).collect();
+        self.statistics = self.statistics.in_units(units);
+        self
+    }
+}
+
+impl InUnits for ServerMessage {
+    fn in_units(self, units: Units) -> Self {
+        match self {
+            ServerMessage::WorldSnapshot(snapshot) => ServerMessage::WorldSnapshot(snapshot.in_units(units)),
+            ServerMessage::TickDiff(diff) => ServerMessage::TickDiff(diff.in_units(units)),
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::{Position, Tile};
+
+    #[test]
+    fn temperatures_convert_both_ways() {
+        for unit in [TemperatureUnit::Kelvin, TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit] {
+            assert!((unit.to_kelvin(unit.from_kelvin(288.15)) - 288.15).abs() < 1e-4);
+        }
+        assert_eq!(TemperatureUnit::Celsius.from_kelvin(288.15), 15.0);
+        assert!((TemperatureUnit::Fahrenheit.from_kelvin(233.15) + 40.0).abs() < 1e-4);
+        assert_eq!(HumidityUnit::Percent.from_fraction(0.25), 25.0);
+        assert_eq!(HumidityUnit::Percent.to_fraction(50.0), 0.5);
+        assert_eq!(Quantity::of_field("climate.base_temperature"), Some(Quantity::Temperature));
+        assert_eq!(Quantity::of_field("weather.pressure"), None);
+    }
+
+    #[test]
+    fn snapshots_carry_their_units() {
+        let tile = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        let units = Units { temperature: TemperatureUnit::Celsius, humidity: HumidityUnit::Percent };
+        let snapshot = TileSnapshot::from_tile(&tile).in_units(units);
+        assert_eq!(snapshot.weather.temperature, 15.0);
+        assert_eq!(snapshot.climate.base_temperature, 15.0);
+        assert_eq!(snapshot.weather.humidity, tile.weather.humidity * 100.0);
+        assert_eq!(snapshot.weather.pressure, tile.weather.pressure);
+
+        let json: serde_json::Value = serde_json::to_value(units).unwrap();
+        assert_eq!(json, serde_json::json!({"temperature": "celsius", "humidity": "percent"}));
+        // Servers that predate units send Kelvin and fractions
+        let units: Units = serde_json::from_str("{}").unwrap();
+        assert!(units.is_default());
+    }
+}
@@ -1,17 +1,57 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
 use std::net::SocketAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{error, info, warn};
+use uuid::Uuid;
 
 use crate::config::generation::GenerationParams;
+use crate::config::palette::Palette;
+use crate::config::registry::WorldRegistry;
 use crate::config::simulation::SimulationConfig;
-use crate::persistence;
-use crate::server::{self, ServerState};
+use crate::error::WorldgroundError;
+use crate::persistence::{self, SnapshotSort, TickRange};
+use crate::server::{self, Broadcast, ServerState};
+use worldground_protocol::units::InUnits;
+use worldground_protocol::TileChange;
 use crate::simulation;
-use crate::simulation::engine::RuleEngine;
-use crate::world::generation::generate_world;
-use crate::world::tile::{WeatherLayer, ConditionsLayer, BiomeLayer, ResourceLayer};
-use crate::world::World;
+use crate::simulation::engine::{
+    is_settable_field, rule_syntax_errors, scan_alias_usages, scan_set_calls, Phase, RejectionReason, RuleEngine,
+    RuleLimits, RuleStats,
+};
+use crate::simulation::builtin_rules::{self, is_builtin};
+use crate::simulation::crash;
+use crate::simulation::events::EventLog;
+use crate::simulation::invariants::{InvariantSet, InvariantViolation, INVARIANT_DIR};
+use crate::simulation::narration::{narrate, Baseline, Narrator, PeriodEvents};
+use crate::simulation::regression::{self, RegressionBaseline};
+use crate::simulation::replay::{self, ReplayLog, TickStart, REPLAY_LOG};
+use crate::simulation::rng_check::check_rng;
+use crate::simulation::rule_params::RULE_PARAMS_FILE;
+use crate::simulation::rule_tests::{self, Fixture};
+use crate::simulation::rule_watch::RuleWatcher;
+use crate::simulation::macro_weather::Blocking;
+use crate::simulation::activity::IdleSkip;
+use crate::simulation::aurora::{Aurora, AuroraEvent};
+use crate::simulation::effects::TileEffects;
+use crate::simulation::flood::FloodEvent;
+use crate::simulation::native_resources::DepletionEvent;
+use crate::simulation::statistics::TickStatistics;
+use crate::simulation::dust::Dust;
+use crate::simulation::glacier::Glaciers;
+use crate::simulation::sea_level::SeaLevel;
+use crate::simulation::ocean::Ocean;
+use crate::simulation::erosion::Erosion;
+use crate::simulation::freeze_thaw::FreezeThaw;
+use crate::simulation::profiler::Profiler;
+use crate::simulation::thermostat::Thermostat;
+use crate::simulation::waves::Waves;
+use crate::world::generation::{shift_on_grid, splice_tiles, tiles_in_box, try_generate_world};
+use crate::world::layers::{DiffLayer, LayerArrays, DIFF_LAYERS};
+use crate::world::paging::PageFile;
+use crate::world::topology::{repair_neighbor_graph, validate_neighbor_graph};
+use crate::world::{Checkpoints, Season, Tile, TopologyType, World};
 
 /// How the simulation should obtain its initial world.
 pub enum WorldSource {
@@ -19,200 +59,2042 @@ pub enum WorldSource {
     Snapshot(String),
     /// Generate a fresh world from a worldgen config file.
     Generate(String),
+    /// Take over the world from a running server at the given address (live handoff).
+    Handoff(String),
 }
 
 /// Run the simulation: load world, start WebSocket server, run tick loop.
 pub async fn run_simulation(
     config: &SimulationConfig,
     source: WorldSource,
-) -> Result<(), String> {
+) -> Result<(), WorldgroundError> {
+    crash::install_panic_hook();
+
     // 1. Load or generate world
     let snapshot_dir = Path::new(&config.snapshot_directory);
-    let mut world = match source {
+    let successor = matches!(source, WorldSource::Handoff(_));
+    let mut world = load_world(source).await?;
+
+    info!(
+        tiles = world.tiles.len(),
+        tick = world.tick_count,
+        season = ?world.season,
+        "World loaded"
+    );
+
+    // 2. Load rules, from the pack the world was created with
+    adopt_rule_pack(config, &mut world);
+    let mut engine = build_engine(config, &world)?;
+    let rule_dir = engine.rule_dir().to_path_buf();
+    info!(dir = %rule_dir.display(), pack = ?engine.pack(), strict = config.strict_rules, "Rules loaded");
+    if config.watch_rules && is_builtin(&rule_dir) {
+        warn!("watch_rules has no effect on the built-in rules");
+    }
+
+    if config.profile_sample_interval > 0 {
+        engine.set_profiler(Some(Profiler::new(config.profile_sample_interval)));
+        info!(
+            sample_interval = config.profile_sample_interval,
+            output = %config.profile_output,
+            "Rule profiler enabled"
+        );
+    }
+
+    if config.page_cold_layers {
+        let pages = PageFile::create(snapshot_dir)?;
+        info!(path = %pages.path().display(), "Paging deposits out between resources phases");
+        engine.set_paging(Some(pages));
+    }
+
+    let mut hooks = TickHooks::new(config, &world, &rule_dir)?;
+    apply_config_tiles(config, &mut world)?;
+
+    // 3-4. Build initial snapshot JSON and start the WebSocket server in background
+    let state = start_server(config, &world, successor)?;
+    state.set_frozen_tiles(&world.protected_tiles()).await;
+    state.set_bookmarks(&world.bookmarks).await;
+    state.set_pressure_systems(&world.macro_weather.systems).await;
+    // Viewers have the whole world, so from here on deposits can live in the page file
+    if let Some(pages) = engine.paging() {
+        pages.page_out(&mut world.tiles)?;
+    }
+
+    // 5. Set up shutdown signal (Ctrl-C or SIGTERM); a running tick always finishes
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    // 6. Run tick loop
+    let tick_interval_ms = (1000.0 / config.tick_rate_hz) as u64;
+    let first_tick = world.tick_count;
+    // Start of the previous paced tick, for start-time jitter
+    let mut last_paced_start: Option<std::time::Instant> = None;
+    if config.warmup_ticks > 0 {
+        info!(ticks = config.warmup_ticks, "Warming up");
+    }
+
+    info!(
+        tick_rate_hz = config.tick_rate_hz,
+        snapshot_interval = config.snapshot_interval,
+        "Simulation running"
+    );
+
+    loop {
+        // Rollbacks from the control API undo ticks before anything else
+        hooks.roll_back(&mut world, &state).await;
+
+        // Tile queries from the API see the last completed tick
+        if state.has_tile_queries() {
+            with_deposits(&mut world, &engine, |world| state.answer_tile_queries(world));
+        }
+
+        // Paused through the control API: wait for a resume or step, but
+        // still answer a live handoff and Ctrl-C
+        if !state.take_tick_permit() {
+            if state.handoff_requested() && with_deposits(&mut world, &engine, |world| state.answer_handoff(world)) {
+                break;
+            }
+            tokio::select! {
+                _ = state.control_changed() => {}
+                // Handoff requests and tile queries don't wake the loop, so look again shortly
+                _ = tokio::time::sleep(std::time::Duration::from_millis(200)) => {}
+                _ = &mut shutdown => {
+                    info!("Shutdown signal received");
+                    break;
+                }
+            }
+            // Time spent paused is not tick jitter
+            last_paced_start = None;
+            continue;
+        }
+
+        let tick_start = std::time::Instant::now();
+        let warming_up = hooks.warming_up(&world);
+
+        hooks.reload_rules(&mut engine);
+        let edited = apply_api_edits(&mut world, &engine, &state).await;
+        let result = hooks.tick(&mut world, &engine, config, &state, edited).await?;
+        hooks.report(&mut world, &engine, &result, &state, config, warming_up).await;
+
+        // Live handoff: a successor asked for the world, so send it and stop ticking
+        if state.handoff_requested() && with_deposits(&mut world, &engine, |world| state.answer_handoff(world)) {
+            break;
+        }
+
+        hooks.log_rule_problems(&world, &result);
+        hooks.autosave(&mut world, &engine, config);
+
+        // Tick milestone logging
+        if world.tick_count % 1000 == 0 {
+            info!(
+                tick = world.tick_count,
+                season = ?world.season,
+                diversity = result.statistics.diversity_index,
+                rule_errors = result.statistics.rule_errors,
+                "Tick milestone"
+            );
+        }
+
+        // Rate limiting: sleep remaining time to hit target tick rate (none during warmup)
+        let elapsed = tick_start.elapsed();
+        let target = std::time::Duration::from_millis(tick_interval_ms);
+
+        // Jitter: how far this tick started behind its slot after the previous one
+        if !warming_up {
+            let jitter = last_paced_start
+                .map_or(std::time::Duration::ZERO, |prev| {
+                    tick_start.duration_since(prev).saturating_sub(target)
+                });
+            last_paced_start = Some(tick_start);
+            if state.record_tick_timing(jitter.as_secs_f32() * 1000.0, elapsed > target).await {
+                warn!(
+                    tick = world.tick_count,
+                    ticks = server::SUSTAINED_OVERRUN_TICKS,
+                    tick_ms = elapsed.as_millis() as u64,
+                    target_ms = tick_interval_ms,
+                    "Ticks keep overrunning tick_rate_hz; lower it or the tile count"
+                );
+            }
+        }
+        if elapsed < target && !warming_up {
+            let sleep_duration = target - elapsed;
+            tokio::select! {
+                _ = tokio::time::sleep(sleep_duration) => {}
+                _ = &mut shutdown => {
+                    info!("Shutdown signal received");
+                    break;
+                }
+            }
+        } else {
+            // Tick took longer than target — yield to tokio runtime and check for shutdown
+            tokio::select! {
+                biased;
+                _ = &mut shutdown => {
+                    info!("Shutdown signal received");
+                    break;
+                }
+                _ = tokio::task::yield_now() => {}
+            }
+        }
+    }
+
+    // Graceful shutdown: save final snapshot, then say goodbye to viewers
+    info!("Saving final snapshot...");
+    match with_deposits(&mut world, &engine, |world| persistence::save_snapshot(world, snapshot_dir)) {
+        Ok(path) => info!(path = %path.display(), "Final snapshot saved"),
+        Err(e) => warn!("Final snapshot save failed: {}", e),
+    }
+    state.close_clients(server::CLOSE_TIMEOUT).await;
+    print_slowest_rules(&engine.rule_stats(), world.tick_count - first_tick, SLOWEST_RULES_SHOWN);
+    if write_rule_profile(&engine, config)
+        && let Some(profiler) = engine.profiler()
+    {
+        let ticks = (world.tick_count - first_tick).max(1) as f64;
+        for (stack, us) in profiler.top().into_iter().take(5) {
+            info!(stack = %stack, ms_per_tick = us as f64 / 1000.0 / ticks, "Slowest in rule profile");
+        }
+    }
+
+    info!(tick = world.tick_count, "Simulation stopped");
+    Ok(())
+}
+
+/// Load the world `run` starts from.
+async fn load_world(source: WorldSource) -> Result<World, WorldgroundError> {
+    match source {
         WorldSource::Snapshot(path) => {
             info!(path = %path, "Loading world from snapshot");
-            persistence::load_snapshot(Path::new(&path))
-                .map_err(|e| format!("Failed to load snapshot: {}", e))?
+            Ok(persistence::load_snapshot(Path::new(&path))?)
+        }
+        WorldSource::Generate(worldgen_path) => {
+            let params = GenerationParams::from_file(Path::new(&worldgen_path))?;
+            info!(config = %worldgen_path, "Generating fresh world");
+            try_generate_world(&params)
+        }
+        WorldSource::Handoff(addr) => {
+            info!(from = %addr, "Requesting live handoff");
+            server::request_handoff(&addr).await
+        }
+    }
+}
+
+/// Apply the config's frozen tiles and active region to `world`.
+fn apply_config_tiles(config: &SimulationConfig, world: &mut World) -> Result<(), WorldgroundError> {
+    // Frozen tiles from config are added to any stored in the world
+    let unknown = world.update_frozen_tiles(&config.frozen_tiles, &[]);
+    if !unknown.is_empty() {
+        warn!(?unknown, "Ignoring frozen_tiles entries with no matching tile");
+    }
+
+    // The active region in config replaces any mask stored in the world
+    let inert = match config.active_region {
+        Some(region) if world.topology_type == TopologyType::Geodesic => {
+            world.tiles_outside(region.lat, region.lon, region.radius_deg)
+        }
+        Some(_) => {
+            return Err(WorldgroundError::Config(
+                "active_region needs a geodesic world; flat tiles have no latitude and longitude".to_string(),
+            ))
+        }
+        None => Default::default(),
+    };
+    world.set_inert_tiles(inert);
+    if !world.inert_tiles.is_empty() {
+        info!(
+            active = world.tiles.len() - world.inert_tiles.len(),
+            inert = world.inert_tiles.len(),
+            "Simulating only the active region"
+        );
+    }
+    Ok(())
+}
+
+/// Apply the edits queued through the API before this tick's rules run.
+/// Returns whether any of them change what the tick computes, which a
+/// replay can't repeat.
+async fn apply_api_edits(world: &mut World, engine: &RuleEngine, state: &ServerState) -> bool {
+    let frozen = apply_frozen_updates(world, state).await;
+    let surveyed = apply_surveys(world, engine, state);
+    let observed = assimilate_observations(world, state);
+    apply_bookmark_updates(world, state).await;
+    frozen || surveyed || observed
+}
+
+/// Freeze and thaw the tiles the API asked for. Returns whether any
+/// requests came in.
+async fn apply_frozen_updates(world: &mut World, state: &ServerState) -> bool {
+    let frozen_updates = state.take_frozen_updates();
+    if frozen_updates.is_empty() {
+        return false;
+    }
+    for update in &frozen_updates {
+        let unknown = world.update_frozen_tiles(&update.add, &update.remove);
+        if !unknown.is_empty() {
+            warn!(?unknown, "Ignoring frozen tile IDs with no matching tile");
+        }
+    }
+    state.set_frozen_tiles(&world.protected_tiles()).await;
+    info!(frozen = world.frozen_tiles.len(), "Frozen tiles updated");
+    true
+}
+
+/// Survey the tiles the API asked for, revealing their deposits in this
+/// tick's diff. Returns whether any were asked for.
+fn apply_surveys(world: &mut World, engine: &RuleEngine, state: &ServerState) -> bool {
+    let survey = state.take_survey_requests();
+    if survey.is_empty() {
+        return false;
+    }
+    let (found, unknown) = with_deposits(world, engine, |world| world.survey_tiles(&survey));
+    if !unknown.is_empty() {
+        warn!(?unknown, "Ignoring survey of tile IDs with no matching tile");
+    }
+    info!(tiles = survey.len(), deposits = found, "Tiles surveyed");
+    true
+}
+
+/// Start nudging the world toward the weather observed through the API.
+/// Returns whether any observations came in.
+fn assimilate_observations(world: &mut World, state: &ServerState) -> bool {
+    let observations = state.take_observations();
+    if observations.is_empty() {
+        return false;
+    }
+    let count = observations.len();
+    let unknown = world.observe(observations);
+    if !unknown.is_empty() {
+        warn!(?unknown, "Ignoring observations of tile IDs with no matching tile");
+    }
+    info!(observations = count - unknown.len(), "Observations assimilating");
+    true
+}
+
+/// Apply the bookmark and tour edits from the API.
+async fn apply_bookmark_updates(world: &mut World, state: &ServerState) {
+    let bookmark_updates = state.take_bookmarks_updates();
+    if bookmark_updates.is_empty() {
+        return;
+    }
+    for update in &bookmark_updates {
+        let skipped = world.bookmarks.apply(
+            &world.tiles,
+            &update.set,
+            &update.remove,
+            &update.tours,
+            &update.remove_tours,
+        );
+        for reason in skipped {
+            warn!("Ignoring bookmark edit: {}", reason);
+        }
+    }
+    state.set_bookmarks(&world.bookmarks).await;
+    info!(
+        bookmarks = world.bookmarks.bookmarks.len(),
+        tours = world.bookmarks.tours.len(),
+        "Bookmarks updated"
+    );
+}
+
+/// What `run` does around each tick besides executing it, one method per
+/// feature, with the state those features keep from tick to tick.
+struct TickHooks {
+    snapshot_dir: PathBuf,
+    rule_watcher: Option<RuleWatcher>,
+    invariants: InvariantSet,
+    narrator: Narrator,
+    checkpoints: Checkpoints,
+    replay_log: Option<ReplayLog>,
+    event_log: Option<EventLog>,
+    pending_diff: Option<PendingDiff>,
+    /// The first ticks run unpaced and without diffs; the world is re-sent
+    /// to viewers as a full snapshot once they end
+    warmup_end: u64,
+    warmup_start: std::time::Instant,
+    last_snapshot_tick: u64,
+    ticks_since_snapshot: u32,
+    /// Rule/reason pairs already warned about, so each rejection kind is logged once
+    seen_rejections: BTreeSet<(String, RejectionReason)>,
+    /// For the crash report, should the next tick panic
+    last_rule_errors: Vec<String>,
+}
+
+impl TickHooks {
+    fn new(config: &SimulationConfig, world: &World, rule_dir: &Path) -> Result<Self, WorldgroundError> {
+        let snapshot_dir = PathBuf::from(&config.snapshot_directory);
+        let rule_watcher = (config.watch_rules && !is_builtin(rule_dir)).then(|| RuleWatcher::new(rule_dir));
+        if rule_watcher.is_some() {
+            info!(dir = %rule_dir.display(), "Watching rules for changes");
+        }
+
+        let invariants = InvariantSet::load(rule_dir)
+            .map_err(|e| WorldgroundError::Rules(format!("Failed to load invariants: {}", e)))?;
+        if !invariants.is_empty() {
+            info!(
+                count = invariants.invariants().len(),
+                interval = config.invariant_interval,
+                "Invariants loaded"
+            );
+        }
+
+        let replay_log = if config.replay_log {
+            let log = ReplayLog::open(&snapshot_dir)
+                .map_err(|e| WorldgroundError::Persistence(format!("Cannot open replay log in {}: {}", snapshot_dir.display(), e)))?;
+            info!(path = %log.path().display(), "Recording replay log");
+            Some(log)
+        } else {
+            None
+        };
+        let event_log = if config.event_log {
+            let log = EventLog::open(&snapshot_dir)
+                .map_err(|e| WorldgroundError::Persistence(format!("Cannot open event log in {}: {}", snapshot_dir.display(), e)))?;
+            info!(path = %log.path().display(), "Recording event log");
+            Some(log)
+        } else {
+            None
+        };
+
+        Ok(TickHooks {
+            snapshot_dir,
+            rule_watcher,
+            invariants,
+            narrator: Narrator::new(world, config.narration_ticks(), config.season_length),
+            checkpoints: Checkpoints::new(config.checkpoint_ticks as usize),
+            replay_log,
+            event_log,
+            pending_diff: None,
+            warmup_end: world.tick_count + config.warmup_ticks as u64,
+            warmup_start: std::time::Instant::now(),
+            last_snapshot_tick: world.tick_count,
+            ticks_since_snapshot: 0,
+            seen_rejections: BTreeSet::new(),
+            last_rule_errors: Vec::new(),
+        })
+    }
+
+    /// Whether the next tick is a warmup tick.
+    fn warming_up(&self, world: &World) -> bool {
+        world.tick_count < self.warmup_end
+    }
+
+    /// Undo the ticks a rollback request from the control API asked for.
+    async fn roll_back(&mut self, world: &mut World, state: &ServerState) {
+        let Some(ticks) = state.take_rollback() else {
+            return;
+        };
+        match self.checkpoints.rollback(world, ticks as u64) {
+            Ok(tick) => {
+                // The undone ticks' events go with them
+                self.pending_diff = None;
+                state.on_rollback(world).await;
+                warn!(ticks, tick, "Rolled back; paused until resumed");
+            }
+            Err(e) => warn!("Cannot roll back: {}", e),
+        }
+        state.set_rollback_depth(self.checkpoints.depth(world));
+    }
+
+    /// Recompile the rules if their scripts were edited, so the edits take
+    /// effect from this tick.
+    fn reload_rules(&mut self, engine: &mut RuleEngine) {
+        if let Some(watcher) = &mut self.rule_watcher
+            && watcher.changed()
+        {
+            match engine.reload_rules() {
+                Ok(count) => info!(rules = count, "Rules reloaded"),
+                Err(e) => warn!("Keeping previous rules: {}", e),
+            }
+        }
+    }
+
+    /// Run one tick, quarantining the world if it panics, and send viewers
+    /// what it changed. `edited` says whether API edits changed what it
+    /// computes, for the replay log.
+    async fn tick(
+        &mut self,
+        world: &mut World,
+        engine: &RuleEngine,
+        config: &SimulationConfig,
+        state: &ServerState,
+        edited: bool,
+    ) -> Result<simulation::TickResult, WorldgroundError> {
+        let warming_up = self.warming_up(world);
+        // Warmup ticks aren't worth undoing
+        if !warming_up {
+            self.checkpoints.take(world);
+        }
+        let replay_start = self.replay_log.is_some().then(|| TickStart::of(world));
+
+        let result = if warming_up {
+            let result = run_tick(world, engine, config, &self.last_rule_errors)?;
+            self.end_warmup_tick(world, engine, &result, state).await;
+            result
+        } else {
+            let pending = self.pending_diff.get_or_insert_with(|| PendingDiff::start(world, config));
+            let result = run_tick(world, engine, config, &self.last_rule_errors)?;
+            pending.add(&result);
+            self.publish(world, engine, &result, state, config).await;
+            result
+        };
+
+        if let (Some(log), Some(start)) = (&mut self.replay_log, replay_start)
+            && let Err(e) = log.append(&start.finish(world, engine.rules_hash(), edited))
+        {
+            warn!("Replay log write failed: {}", e);
+        }
+        Ok(result)
+    }
+
+    /// Report a warmup tick's health, and once warmup ends, send viewers the
+    /// warmed-up world.
+    async fn end_warmup_tick(
+        &self,
+        world: &mut World,
+        engine: &RuleEngine,
+        result: &simulation::TickResult,
+        state: &ServerState,
+    ) {
+        state
+            .update_health(
+                &result.statistics,
+                world.tick_count,
+                world.season,
+                world.tile_count,
+                self.last_snapshot_tick,
+            )
+            .await;
+        if world.tick_count == self.warmup_end {
+            // Viewers connected during warmup switch to the warmed-up world
+            let snapshot_json = with_deposits(world, engine, |world| state.snapshot_json_of(world));
+            state
+                .on_tick(
+                    Some(snapshot_json.clone()),
+                    snapshot_json,
+                    &result.statistics,
+                    world.tick_count,
+                    world.season,
+                    world.tile_count,
+                    self.last_snapshot_tick,
+                )
+                .await;
+            info!(
+                tick = world.tick_count,
+                elapsed_s = self.warmup_start.elapsed().as_secs_f32(),
+                "Warmup complete"
+            );
+        }
+    }
+
+    /// Broadcast the pending diff once it covers `diff_interval` ticks, or
+    /// just report the tick's health until then.
+    async fn publish(
+        &mut self,
+        world: &mut World,
+        engine: &RuleEngine,
+        result: &simulation::TickResult,
+        state: &ServerState,
+        config: &SimulationConfig,
+    ) {
+        if world.tick_count % config.diff_interval as u64 != 0 {
+            state
+                .update_health(
+                    &result.statistics,
+                    world.tick_count,
+                    world.season,
+                    world.tile_count,
+                    self.last_snapshot_tick,
+                )
+                .await;
+            return;
+        }
+        let Some(pending) = self.pending_diff.take() else {
+            return;
+        };
+        let diff = pending.finish(world, &result.statistics, engine.paging()).in_units(state.units());
+
+        // Rebuild full snapshot JSON periodically (every 10 ticks) instead of every tick.
+        // This avoids serializing all tiles to JSON on every tick at large tile counts.
+        let new_snapshot_json = if world.tick_count / 10 > pending.start_tick / 10 {
+            Some(with_deposits(world, engine, |world| state.snapshot_json_of(world)))
+        } else {
+            None
+        };
+
+        // Update server state (broadcasts diff to clients)
+        state
+            .on_tick(
+                new_snapshot_json,
+                diff,
+                &result.statistics,
+                world.tick_count,
+                world.season,
+                world.tile_count,
+                self.last_snapshot_tick,
+            )
+            .await;
+    }
+
+    /// Broadcast and log the tick's events, update the API's stats and
+    /// narration, and check the invariants when they're due.
+    async fn report(
+        &mut self,
+        world: &mut World,
+        engine: &RuleEngine,
+        result: &simulation::TickResult,
+        state: &ServerState,
+        config: &SimulationConfig,
+        warming_up: bool,
+    ) {
+        // Sanity checks: sample tiles against rules/invariants/ every few
+        // ticks. Violations are events of the tick, as well as warnings.
+        let mut events = std::borrow::Cow::Borrowed(&result.events[..]);
+        if config.invariant_interval > 0
+            && !self.invariants.is_empty()
+            && world.tick_count % config.invariant_interval as u64 == 0
+        {
+            let violations = with_deposits(world, engine, |world| {
+                self.invariants.check(world, &result.statistics, config.invariant_sample_size as usize)
+            });
+            for v in &violations {
+                warn!(tick = v.tick, "{}", v);
+            }
+            events.to_mut().extend(violations.iter().map(InvariantViolation::event));
+            state.record_invariant_check(&violations).await;
+        }
+
+        if !events.is_empty() {
+            if !warming_up {
+                state.broadcast_events(world.tick_count, &events);
+            }
+            if let Some(log) = &mut self.event_log
+                && let Err(e) = log.append(&events)
+            {
+                warn!("Event log write failed: {}", e);
+            }
+        }
+        state
+            .record_mutation_stats(world.tick_count, &result.mutation_stats)
+            .await;
+        state.record_rule_stats(&result.rule_stats).await;
+        state.set_rollback_depth(self.checkpoints.depth(world));
+        state.set_records(&world.records).await;
+        state.set_energy(&world.energy).await;
+        state.set_pressure_systems(&world.macro_weather.systems).await;
+        if let Some(narration) = self.narrator.observe(world, result) {
+            info!(tick = world.tick_count, "{}", narration.summary());
+            state.add_narration(narration).await;
+        }
+    }
+
+    /// Log the tick's rule errors, and each kind of rejected mutation the
+    /// first time it's seen. Keeps the errors for a crash report.
+    fn log_rule_problems(&mut self, world: &World, result: &simulation::TickResult) {
+        self.last_rule_errors = result.rule_errors.iter().take(CRASH_REPORT_ERRORS).map(ToString::to_string).collect();
+        if !result.rule_errors.is_empty() {
+            warn!(
+                tick = world.tick_count,
+                error_count = result.rule_errors.len(),
+                "Rule errors in tick"
+            );
+        }
+        for ((rule, reason), count) in &result.mutation_stats.rejected {
+            if self.seen_rejections.insert((rule.clone(), *reason)) {
+                warn!(
+                    tick = world.tick_count,
+                    rule = %rule,
+                    reason = reason.as_str(),
+                    count,
+                    "Rule mutations rejected (see /api/metrics)"
+                );
+            }
+        }
+    }
+
+    /// Save a snapshot every `snapshot_interval` ticks, pruning old ones and
+    /// the replay log they no longer need.
+    fn autosave(&mut self, world: &mut World, engine: &RuleEngine, config: &SimulationConfig) {
+        self.ticks_since_snapshot += 1;
+        if self.ticks_since_snapshot < config.snapshot_interval {
+            return;
+        }
+        let snapshot_dir = self.snapshot_dir.as_path();
+        match with_deposits(world, engine, |world| persistence::save_snapshot(world, snapshot_dir)) {
+            Ok(path) => {
+                self.last_snapshot_tick = world.tick_count;
+                self.ticks_since_snapshot = 0;
+                info!(path = %path.display(), "Snapshot saved");
+                write_rule_profile(engine, config);
+
+                // Prune old snapshots
+                if let Err(e) = persistence::prune_snapshots(snapshot_dir, config.max_snapshots as usize) {
+                    warn!("Snapshot pruning failed: {}", e);
+                }
+                // The log only needs to reach back to the oldest snapshot
+                if let Some(log) = &mut self.replay_log
+                    && let Some(oldest) = persistence::list_snapshots(snapshot_dir)
+                        .ok()
+                        .and_then(|s| s.iter().map(|s| s.tick_count).min())
+                    && let Err(e) = log.trim(oldest)
+                {
+                    warn!("Replay log trim failed: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!("Snapshot save failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM (what service managers and
+/// `docker stop` send), so either stops the server cleanly.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!("Cannot listen for SIGTERM, only Ctrl-C stops the server cleanly: {}", e),
+        }
+    }
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!("Cannot listen for Ctrl-C: {}", e);
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Settle the world's rule pack against the config's, warning when the
+/// world keeps its own (see `World::adopt_rule_pack`).
+fn adopt_rule_pack(config: &SimulationConfig, world: &mut World) {
+    if let Some(ignored) = world.adopt_rule_pack(config.rule_pack.as_deref()) {
+        warn!(
+            pack = ?world.rule_pack,
+            ignored = %ignored,
+            "The world keeps the rule pack it was created with; ignoring rule_pack from config"
+        );
+    }
+}
+
+/// Load the world's rule pack and set up the native evaluators and steps
+/// the config enables. Shared by `run` and `rules regress` so both simulate alike.
+fn build_engine(config: &SimulationConfig, world: &World) -> Result<RuleEngine, WorldgroundError> {
+    let rule_dir = Path::new(&config.rule_directory);
+    let mut engine = RuleEngine::with_pack(rule_dir, world.rule_pack.as_deref(), config.rule_timeout_ms as u64)
+        .map_err(|e| WorldgroundError::Rules(format!("Failed to load rules: {}", e)))?
+        .with_max_operations(config.rule_max_operations);
+    for phase in Phase::all() {
+        if let Some(limits) = config.rule_limits.get(phase.dir_name()) {
+            engine.set_phase_limits(
+                *phase,
+                RuleLimits {
+                    max_operations: limits.max_operations.unwrap_or(config.rule_max_operations),
+                    timeout_ms: limits.timeout_ms.unwrap_or(config.rule_timeout_ms) as u64,
+                },
+            );
+        }
+    }
+    for phase in Phase::all() {
+        if let Some(&ticks) = config.phase_periods.get(phase.dir_name()) {
+            engine.set_phase_period(*phase, ticks);
+        }
+    }
+    engine.set_strict(config.strict_rules);
+    engine.set_area_weighted_statistics(config.area_weighted_statistics);
+    engine.set_batch_size(config.rule_batch_size as usize);
+    if config.strict_rules {
+        let unwritable = engine.unwritable_set_calls();
+        if !unwritable.is_empty() {
+            let calls: Vec<String> = unwritable.iter().map(|c| c.unwritable_message()).collect();
+            return Err(WorldgroundError::Rules(format!("Failed to load rules (strict_rules):\n{}", calls.join("\n"))));
+        }
+    }
+
+    // Register native evaluators when enabled (default: true)
+    if config.native_evaluation {
+        use crate::simulation::native_resources::NativeResourcesEvaluator;
+        use crate::simulation::native_weather::NativeWeatherEvaluator;
+        engine.register_native_evaluator(Box::new(NativeWeatherEvaluator::new(&world.tiles)));
+        engine.register_native_evaluator(Box::new(NativeResourcesEvaluator));
+        info!("Native weather and resources evaluation enabled");
+    }
+
+    if config.idle_skip_interval > 1 {
+        engine.set_idle_skip(Some(IdleSkip {
+            threshold: config.idle_threshold,
+            interval: config.idle_skip_interval,
+        }));
+        info!(
+            interval = config.idle_skip_interval,
+            threshold = config.idle_threshold,
+            "Idle tiles skip rule evaluation"
+        );
+    }
+
+    if config.thermostat_rate > 0.0 {
+        engine.set_thermostat(Some(Thermostat {
+            rate: config.thermostat_rate,
+            band_degrees: config.thermostat_band_degrees,
+        }));
+        info!(
+            rate = config.thermostat_rate,
+            band_degrees = config.thermostat_band_degrees,
+            "Latitude-band thermostat enabled"
+        );
+    }
+
+    if config.freeze_thaw {
+        engine.set_freeze_thaw(Some(FreezeThaw {
+            rock_to_sand: config.freeze_thaw_rock_to_sand,
+        }));
+        info!(rock_to_sand = config.freeze_thaw_rock_to_sand, "Freeze-thaw weathering enabled");
+    }
+
+    if config.erosion {
+        engine.set_erosion(Some(Erosion {
+            landslides: config.erosion_landslides,
+            sediment: config.erosion_sediment,
+        }));
+        info!(
+            landslides = config.erosion_landslides,
+            sediment = config.erosion_sediment,
+            "Erosion enabled"
+        );
+    }
+
+    if config.blocking_chance > 0.0 {
+        engine.set_blocking(Some(Blocking { chance: config.blocking_chance }));
+        info!(chance = config.blocking_chance, "Blocking regimes enabled");
+    }
+
+    if config.aurora {
+        engine.set_aurora(Some(Aurora { chance: config.aurora_chance }));
+        info!(chance = config.aurora_chance, "Aurora driver enabled");
+    }
+
+    if config.dust {
+        engine.set_dust(Some(Dust::new(&world.tiles, config.dust_eruption_chance)));
+        info!(eruption_chance = config.dust_eruption_chance, "Dust and ash transport enabled");
+    }
+
+    if config.ocean_circulation {
+        engine.set_ocean(Some(Ocean { heat_transport: config.ocean_heat_transport }));
+        info!(heat_transport = config.ocean_heat_transport, "Ocean circulation enabled");
+    }
+
+    if config.waves {
+        engine.set_waves(Some(Waves::new(&world.tiles)));
+        info!("Ocean waves enabled");
+    }
+
+    if config.glaciers {
+        engine.set_glaciers(Some(Glaciers { carving: config.glacier_carving }));
+        info!(carving = config.glacier_carving, "Glaciers enabled");
+    }
+    if config.sea_level {
+        engine.set_sea_level(Some(SeaLevel { ice_factor: config.sea_level_ice_factor }));
+        info!(ice_factor = config.sea_level_ice_factor, "Sea level enabled");
+    }
+    Ok(engine)
+}
+
+/// Rules listed in the table printed when `run` stops.
+const SLOWEST_RULES_SHOWN: usize = 10;
+
+/// Print the rules that took the most time, with their call and error
+/// counts, over `ticks` ticks.
+fn print_slowest_rules(stats: &RuleStats, ticks: u64, count: usize) {
+    let slowest = stats.slowest();
+    if slowest.is_empty() {
+        return;
+    }
+    let ticks = ticks.max(1) as f64;
+    println!("\nSlowest rules over {} tick(s):", ticks);
+    println!("{:<40} {:>12} {:>8} {:>10} {:>10} {:>9}", "Rule", "Calls", "Errors", "Total ms", "ms/tick", "us/call");
+    for (rule, m) in slowest.into_iter().take(count) {
+        let ms = m.time.as_secs_f64() * 1000.0;
+        println!(
+            "{:<40} {:>12} {:>8} {:>10.1} {:>10.2} {:>9.1}",
+            rule,
+            m.invocations,
+            m.errors,
+            ms,
+            ms / ticks,
+            ms * 1000.0 / m.invocations.max(1) as f64
+        );
+    }
+}
+
+/// Write the rule profile to `profile_output`, if profiling. Returns whether it was written.
+fn write_rule_profile(engine: &RuleEngine, config: &SimulationConfig) -> bool {
+    let Some(profiler) = engine.profiler() else {
+        return false;
+    };
+    match profiler.write_collapsed(Path::new(&config.profile_output)) {
+        Ok(()) => {
+            info!(path = %config.profile_output, "Rule profile written");
+            true
+        }
+        Err(e) => {
+            warn!("Rule profile not written: {}", e);
+            false
+        }
+    }
+}
+
+/// Rule errors of the last tick kept for a crash report.
+const CRASH_REPORT_ERRORS: usize = 20;
+
+/// How long a handoff successor waits for its predecessor to release the port.
+const HANDOFF_BIND_WAIT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A tick diff built up over `diff_interval` ticks: the layers it compares
+/// as they were when it started, and the events of every tick since.
+struct PendingDiff {
+    start_tick: u64,
+    before: LayerArrays,
+    floods: Vec<FloodEvent>,
+    aurora: Option<AuroraEvent>,
+    depletions: Vec<DepletionEvent>,
+    effects: TileEffects,
+}
+
+impl PendingDiff {
+    fn start(world: &World, config: &SimulationConfig) -> Self {
+        // Capture only the layers the diff compares, not whole tiles
+        let mut diff_layers = DIFF_LAYERS.to_vec();
+        if config.scratch_in_diffs {
+            diff_layers.push(DiffLayer::Scratch);
+        }
+        PendingDiff {
+            start_tick: world.tick_count,
+            before: world.capture_layers(&diff_layers),
+            floods: Vec::new(),
+            aurora: None,
+            depletions: Vec::new(),
+            effects: TileEffects::default(),
+        }
+    }
+
+    fn add(&mut self, result: &simulation::TickResult) {
+        self.floods.extend_from_slice(&result.floods);
+        self.aurora = result.aurora.or(self.aurora);
+        self.depletions.extend_from_slice(&result.depletions);
+        self.effects.merge(result.effects.clone());
+    }
+
+    /// The diff from the start to now, built from lightweight layer snapshots
+    /// (avoids full tile clones). It is encoded as JSON or MessagePack only
+    /// for clients that need it. Deposits paged out in bounded-memory mode
+    /// aren't on the tiles to compare, so the page file says which changed.
+    fn finish(&self, world: &World, stats: &TickStatistics, paging: Option<&PageFile>) -> server::protocol::ServerMessage {
+        let mut diff = server::build_diff_from_layers(
+            &self.before,
+            world,
+            stats,
+            &self.floods,
+            self.aurora,
+            &self.depletions,
+            &self.effects,
+        );
+        if let (Some(pages), server::protocol::ServerMessage::TickDiff(diff)) = (paging, &mut diff) {
+            match pages.take_changes(&world.tiles) {
+                Ok(changes) => merge_changes(&mut diff.changed_tiles, changes, world),
+                Err(e) => warn!("Cannot read changed deposits: {}", e),
+            }
+        }
+        diff
+    }
+}
+
+/// Add `resources` changes, in tile order, to a diff's `changes`, merging
+/// them into the changes of tiles already there. Inert tiles are never sent.
+fn merge_changes(changes: &mut Vec<TileChange>, resources: Vec<TileChange>, world: &World) {
+    for change in resources.into_iter().filter(|c| !world.is_inert(c.id)) {
+        match changes.binary_search_by_key(&change.id, |c| c.id) {
+            Ok(i) => changes[i].resources = change.resources,
+            Err(i) => changes.insert(i, change),
+        }
+    }
+}
+
+/// Run `f` with every tile's deposits on it, paging them in and back out
+/// around it in bounded-memory mode. If they can't be read back, `f` sees
+/// the tiles without them.
+fn with_deposits<T>(world: &mut World, engine: &RuleEngine, f: impl FnOnce(&mut World) -> T) -> T {
+    let Some(pages) = engine.paging() else {
+        return f(world);
+    };
+    if let Err(e) = pages.page_in(&mut world.tiles) {
+        error!("{}", e);
+    }
+    let result = f(world);
+    if let Err(e) = pages.page_out(&mut world.tiles) {
+        warn!("Keeping deposits in memory: {}", e);
+    }
+    result
+}
+
+/// Run one tick. If it panics, save the world and a crash report to the
+/// quarantine directory and fail with where they went.
+fn run_tick(
+    world: &mut World,
+    engine: &RuleEngine,
+    config: &SimulationConfig,
+    last_rule_errors: &[String],
+) -> Result<simulation::TickResult, WorldgroundError> {
+    let tick = world.tick_count + 1;
+    let panic = match crash::catch_tick(|| simulation::execute_tick(world, engine, config.season_length)) {
+        Ok(result) => return Ok(result),
+        Err(panic) => panic,
+    };
+    error!(tick, step = panic.step, "Tick panicked: {}", panic.message);
+    let quarantined = with_deposits(world, engine, |world| {
+        crash::quarantine(world, Path::new(&config.snapshot_directory), tick, &panic, last_rule_errors)
+    });
+    match quarantined {
+        Ok(report) => Err(WorldgroundError::Simulation(format!(
+            "Tick {} panicked in {}: {}. World and crash report saved to {}",
+            tick,
+            panic.step,
+            panic.message,
+            report.parent().unwrap_or(&report).display()
+        ))),
+        Err(e) => Err(WorldgroundError::Simulation(format!(
+            "Tick {} panicked in {}: {}. Crash report failed: {}",
+            tick, panic.step, panic.message, e
+        ))),
+    }
+}
+
+/// The `[health]` thresholds in the units `/health` checks: ticks per second
+/// and ticks since the last snapshot.
+fn health_limits(config: &SimulationConfig) -> server::HealthLimits {
+    let health = &config.health;
+    let ticks = |intervals: f32| (intervals * config.snapshot_interval as f32).round() as u64;
+    server::HealthLimits {
+        tick_rate: (health.degraded_tick_rate * config.tick_rate_hz, health.unhealthy_tick_rate * config.tick_rate_hz),
+        rule_error_ratio: (health.degraded_rule_errors, health.unhealthy_rule_errors),
+        snapshot_age_ticks: (ticks(health.degraded_snapshot_age), ticks(health.unhealthy_snapshot_age)),
+    }
+}
+
+/// Create the server state for a world and start the WebSocket server in the background.
+///
+/// A handoff `successor` waits for its predecessor to release the port when the
+/// two processes don't share it with `websocket_reuse_port`.
+fn start_server(
+    config: &SimulationConfig,
+    world: &World,
+    successor: bool,
+) -> Result<Arc<ServerState>, WorldgroundError> {
+    let palette_path = Path::new(&config.palette_file);
+    let palette = if palette_path.exists() {
+        info!(path = %config.palette_file, "Loading palette");
+        Palette::from_file(palette_path)?
+    } else {
+        info!(path = %config.palette_file, "No palette file; serving the built-in palette");
+        Palette::default()
+    };
+
+    let snapshot_json = server::build_snapshot_json_in(world, config.units());
+    let state = Arc::new(
+        ServerState::new(snapshot_json)
+            .with_palette(palette)
+            .with_units(config.units())
+            .with_health_limits(Some(health_limits(config)))
+            .with_compression(config.websocket_compression.then_some(config.websocket_compression_level))
+            .with_heartbeat((config.websocket_ping_interval_secs > 0).then(|| server::Heartbeat {
+                interval: std::time::Duration::from_secs(config.websocket_ping_interval_secs),
+                timeout: std::time::Duration::from_secs(config.websocket_ping_timeout_secs),
+            }))
+            .with_reuse_port(config.websocket_reuse_port)
+            .with_bind_wait(successor.then_some(HANDOFF_BIND_WAIT)),
+    );
+
+    let addr: SocketAddr = format!("{}:{}", config.websocket_bind, config.websocket_port)
+        .parse()
+        .map_err(|e| WorldgroundError::Config(format!("Invalid bind address: {}", e)))?;
+
+    let server_state = Arc::clone(&state);
+    tokio::spawn(async move {
+        if let Err(e) = server::start_server(server_state, addr).await {
+            error!("Server error: {}", e);
+        }
+    });
+    Ok(state)
+}
+
+/// Replay a directory of snapshots over the WebSocket protocol instead of simulating.
+///
+/// Snapshots are played in tick order, one per frame at `tick_rate_hz`, each
+/// sent as a diff against the previous one. Snapshots of other worlds than
+/// the first are skipped. Nothing is saved. After the last frame the server
+/// keeps serving it until Ctrl-C.
+pub async fn replay_snapshots(config: &SimulationConfig, dir: &Path) -> Result<(), WorldgroundError> {
+    let mut frames = persistence::list_snapshots(dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Cannot list snapshots in {}: {}", dir.display(), e)))?;
+    frames.sort_by(|a, b| a.tick_count.cmp(&b.tick_count).then(a.timestamp.cmp(&b.timestamp)));
+    let Some((first, rest)) = frames.split_first() else {
+        return Err(WorldgroundError::Persistence(format!("No snapshots to replay in {}", dir.display())));
+    };
+
+    let mut world = persistence::load_snapshot(&first.path)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load {}: {}", first.path.display(), e)))?;
+    let state = start_server(config, &world, false)?;
+    state.set_bookmarks(&world.bookmarks).await;
+    state.set_pressure_systems(&world.macro_weather.systems).await;
+    info!(
+        frames = frames.len(),
+        from_tick = first.tick_count,
+        to_tick = frames[frames.len() - 1].tick_count,
+        rate_hz = config.tick_rate_hz,
+        "Replaying snapshots"
+    );
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    let frame_interval = std::time::Duration::from_secs_f32(1.0 / config.tick_rate_hz);
+
+    for frame in rest {
+        state.answer_tile_queries(&world);
+        tokio::select! {
+            _ = tokio::time::sleep(frame_interval) => {}
+            _ = &mut shutdown => {
+                info!("Shutdown signal received");
+                state.close_clients(server::CLOSE_TIMEOUT).await;
+                return Ok(());
+            }
+        }
+
+        let next = match persistence::load_snapshot(&frame.path) {
+            Ok(next) if next.id == world.id => next,
+            Ok(next) => {
+                warn!(path = %frame.path.display(), world_id = %next.id, "Skipping snapshot of another world");
+                continue;
+            }
+            Err(e) => {
+                warn!(path = %frame.path.display(), "Skipping unreadable snapshot: {}", e);
+                continue;
+            }
+        };
+
+        let stats = simulation::statistics::compute_statistics(&next, 0, 0.0, config.area_weighted_statistics);
+        let snapshot_json = state.snapshot_json_of(&next);
+        // A changed tile count can't be diffed, so the full world goes out instead
+        let diff: Broadcast = if next.tiles.len() == world.tiles.len() {
+            Broadcast::new(server::build_diff_json(
+                &world.tiles,
+                &next.tiles,
+                next.tick_count,
+                next.season,
+                next.season_length,
+                &stats,
+                &next.macro_weather.systems,
+            ))
+            .in_units(state.units())
+        } else {
+            snapshot_json.clone().into()
+        };
+        state
+            .on_tick(
+                Some(snapshot_json),
+                diff,
+                &stats,
+                next.tick_count,
+                next.season,
+                next.tile_count,
+                next.tick_count,
+            )
+            .await;
+        state.set_frozen_tiles(&next.protected_tiles()).await;
+        state.set_records(&next.records).await;
+        state.set_energy(&next.energy).await;
+        state.set_pressure_systems(&next.macro_weather.systems).await;
+        state.set_bookmarks(&next.bookmarks).await;
+        world = next;
+    }
+
+    info!(tick = world.tick_count, "Replay finished; serving the last frame until Ctrl-C");
+    loop {
+        state.answer_tile_queries(&world);
+        tokio::select! {
+            // Tile queries don't wake the loop, so look again shortly
+            _ = tokio::time::sleep(std::time::Duration::from_millis(200)) => {}
+            _ = &mut shutdown => {
+                info!("Shutdown signal received");
+                state.close_clients(server::CLOSE_TIMEOUT).await;
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Re-run ticks from a snapshot with the current rules and config, and
+/// check each against the replay log, up to tick `to` (by default the last
+/// logged). Without `from`, starts from the newest snapshot in the snapshot
+/// directory before `to`. Returns whether every logged tick came out the same.
+pub fn replay_ticks(
+    config: &SimulationConfig,
+    from: Option<&Path>,
+    to: Option<u64>,
+    log_path: Option<&Path>,
+) -> Result<bool, WorldgroundError> {
+    let snapshot_dir = Path::new(&config.snapshot_directory);
+    let log_path = log_path.map_or_else(|| snapshot_dir.join(REPLAY_LOG), Path::to_path_buf);
+    if !log_path.exists() {
+        return Err(WorldgroundError::Persistence(format!(
+            "No replay log at {}; set replay_log = true in config.toml to record one",
+            log_path.display()
+        )));
+    }
+    let log = replay::read_log(&log_path).map_err(WorldgroundError::Persistence)?;
+    let to = match (to, log.keys().next_back()) {
+        (Some(to), _) => to,
+        (None, Some(&last)) => last,
+        (None, None) => {
+            return Err(WorldgroundError::Persistence(format!("{} has no ticks; pass --to", log_path.display())))
+        }
+    };
+
+    let from = match from {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let mut snapshots = persistence::list_snapshots(snapshot_dir)
+                .map_err(|e| WorldgroundError::Persistence(format!("Cannot list snapshots in {}: {}", snapshot_dir.display(), e)))?;
+            snapshots.retain(|s| s.tick_count < to);
+            persistence::sort_snapshots(&mut snapshots, SnapshotSort::Tick);
+            snapshots
+                .first()
+                .map(|s| s.path.clone())
+                .ok_or_else(|| {
+                    WorldgroundError::Persistence(format!("No snapshot in {} before tick {}", snapshot_dir.display(), to))
+                })?
+        }
+    };
+    let mut world = persistence::load_snapshot(&from).map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+    if world.tick_count >= to {
+        return Err(WorldgroundError::Persistence(format!(
+            "{} is at tick {}, not before tick {}",
+            from.display(),
+            world.tick_count,
+            to
+        )));
+    }
+    adopt_rule_pack(config, &mut world);
+    let engine = build_engine(config, &world)?;
+    println!(
+        "Replaying tick {} to {} from {} with rules from {}",
+        world.tick_count,
+        to,
+        from.display(),
+        engine.rule_dir().display()
+    );
+
+    let report = replay::replay(&mut world, &engine, config.season_length, to, &log);
+    if let Some(tick) = report.rules_changed_at {
+        println!("  Rules differ from those logged from tick {}", tick);
+    }
+    match &report.divergence {
+        Some((tick, what)) => {
+            println!("\nDiverged at tick {}: {}", tick, what);
+            Ok(false)
+        }
+        None if report.checked == 0 => {
+            println!("\nRan {} tick(s); the log has none of them to check", report.ticks);
+            Ok(true)
+        }
+        None => {
+            println!(
+                "\nRan {} tick(s); all {} logged matched (state {:016x})",
+                report.ticks,
+                report.checked,
+                world.state_hash()
+            );
+            Ok(true)
+        }
+    }
+}
+
+/// Compile all rules in a directory and report problems, including deprecated field names.
+///
+/// Returns the number of deprecated field usages found.
+pub fn check_rules(rule_dir: &Path) -> Result<usize, WorldgroundError> {
+    let engine = RuleEngine::new(rule_dir, 10)?;
+    for phase in Phase::all() {
+        println!(
+            "{:<12} {} rule(s)",
+            phase.dir_name(),
+            engine.rules_for_phase(*phase).len()
+        );
+    }
+    let invariants = InvariantSet::load(rule_dir)?;
+    println!("{:<12} {} invariant(s)", INVARIANT_DIR, invariants.invariants().len());
+    println!("{:<12} {} parameter(s)", RULE_PARAMS_FILE, engine.param_count());
+
+    let usages = scan_alias_usages(rule_dir).map_err(WorldgroundError::Rules)?;
+    if usages.is_empty() {
+        println!("\n{} rule(s) OK, no deprecated fields", engine.rule_count());
+    } else {
+        println!("\nDeprecated fields:");
+        for u in &usages {
+            println!(
+                "  {}:{}: '{}' -> '{}' (removed in {})",
+                u.rule_path.display(),
+                u.line,
+                u.alias.old_name,
+                u.alias.new_name,
+                u.alias.removed_in
+            );
         }
-        WorldSource::Generate(worldgen_path) => {
-            let params = GenerationParams::from_file(Path::new(&worldgen_path))
-                .map_err(|e| format!("Failed to load worldgen config: {}", e))?;
-            info!(config = %worldgen_path, "Generating fresh world");
-            generate_world(&params)
+        println!(
+            "\n{} rule(s) compiled, {} deprecated field use(s)",
+            engine.rule_count(),
+            usages.len()
+        );
+    }
+
+    Ok(usages.len())
+}
+
+/// Load the simulation config, the worldgen config, the palette and the rule
+/// directory, and print every problem found rather than stopping at the
+/// first. Rules are compiled one by one, and each `set()` with a literal
+/// field name is checked against the fields writable in its phase. Returns
+/// whether no problems were found.
+pub fn validate(
+    config_path: &Path,
+    config: Result<SimulationConfig, WorldgroundError>,
+    worldgen_path: &Path,
+) -> Result<bool, WorldgroundError> {
+    let (mut checked, mut failed) = (0, 0);
+    let mut report = |label: &str, result: Result<String, String>| {
+        checked += 1;
+        match result {
+            Ok(summary) => println!("  ok    {:<24} {}", label, summary),
+            Err(e) => {
+                failed += 1;
+                println!("  FAIL  {}", label);
+                for line in e.lines() {
+                    println!("          {}", line);
+                }
+            }
         }
     };
 
-    info!(
-        tiles = world.tiles.len(),
-        tick = world.tick_count,
-        season = ?world.season,
-        "World loaded"
+    report(
+        &config_path.display().to_string(),
+        config.as_ref().map(|c| format!("{} Hz, rules in {}", c.tick_rate_hz, c.rule_directory)).map_err(|e| e.to_string()),
+    );
+    report(
+        &worldgen_path.display().to_string(),
+        GenerationParams::from_file(worldgen_path)
+            .map(|p| format!("seed {}, {} topology", p.seed, p.topology.mode))
+            .map_err(|e| e.to_string()),
     );
 
-    // 2. Load rules
-    let rule_dir = Path::new(&config.rule_directory);
-    let mut engine = RuleEngine::new(rule_dir, config.rule_timeout_ms as u64)
-        .map_err(|e| format!("Failed to load rules: {}", e))?;
+    // Without a usable config, check the rules and palette in their default places
+    let config = match config {
+        Ok(config) => config,
+        Err(_) => SimulationConfig::from_toml_str("", config_path)?,
+    };
+    let palette_path = Path::new(&config.palette_file);
+    if palette_path.exists() {
+        report(
+            &config.palette_file,
+            Palette::from_file(palette_path).map(|p| format!("{} layer(s)", p.layers.len())).map_err(|e| e.to_string()),
+        );
+    }
 
-    // Register native evaluators when enabled (default: true)
-    if config.native_evaluation {
-        use crate::simulation::native_weather::NativeWeatherEvaluator;
-        engine.register_native_evaluator(Box::new(NativeWeatherEvaluator::new(&world.tiles)));
-        info!("Native weather evaluation enabled");
+    // A configured rule pack is checked in place of the whole rule directory
+    let rule_path = config.rule_pack_directory();
+    let rule_dir = rule_path.as_path();
+    let rule_label = rule_path.display().to_string();
+    if !is_builtin(rule_dir) && !rule_dir.exists() {
+        report(&rule_label, Err(format!("Rule directory not found: {}", rule_dir.display())));
+    } else {
+        let mut rule_problems = rule_syntax_errors(rule_dir).map_err(WorldgroundError::Rules)?;
+        let calls = scan_set_calls(rule_dir).map_err(WorldgroundError::Rules)?;
+        rule_problems.extend(
+            calls.iter().filter(|c| !is_settable_field(&c.field, c.phase)).map(|c| c.unwritable_message()),
+        );
+        if let Err(e) = InvariantSet::load(rule_dir) {
+            rule_problems.push(e.to_string());
+        }
+        let fixture_dir = rule_dir.join("tests");
+        if fixture_dir.is_dir() {
+            let mut fixtures: Vec<PathBuf> = std::fs::read_dir(&fixture_dir)
+                .map_err(|e| WorldgroundError::Rules(format!("Failed to read {}: {}", fixture_dir.display(), e)))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect();
+            fixtures.sort();
+            for path in fixtures {
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(WorldgroundError::from)
+                    .and_then(|text| Fixture::parse(&text));
+                if let Err(e) = parsed {
+                    rule_problems.push(format!("Invalid fixture {}: {}", path.display(), e));
+                }
+            }
+        }
+        report(
+            &rule_label,
+            if rule_problems.is_empty() {
+                Ok(format!("{} set() call(s) checked", calls.len()))
+            } else {
+                Err(rule_problems.join("\n"))
+            },
+        );
+
+        for usage in scan_alias_usages(rule_dir).map_err(WorldgroundError::Rules)? {
+            println!(
+                "  warn  {}:{}: '{}' is deprecated, use '{}' (removed in {})",
+                usage.rule_path.display(),
+                usage.line,
+                usage.alias.old_name,
+                usage.alias.new_name,
+                usage.alias.removed_in
+            );
+        }
     }
 
-    info!(dir = %config.rule_directory, "Rules loaded");
+    if failed == 0 {
+        println!("\nNo problems found");
+    } else {
+        println!("\nProblems in {} of {} checked", failed, checked);
+    }
+    Ok(failed == 0)
+}
 
-    // 3. Build initial snapshot JSON and create server state
-    let snapshot_json = server::build_snapshot_json(&world);
-    let state = Arc::new(ServerState::new(snapshot_json));
+/// Validate a snapshot's neighbor graph and optionally repair it.
+///
+/// With `repair`, fixable issues are corrected and the world is saved as a new
+/// snapshot next to the original. Returns the number of issues left unresolved.
+/// Options for `snapshots list`.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotListOptions {
+    pub sort: SnapshotSort,
+    pub ticks: TickRange,
+    /// Only snapshots of this world (reads each snapshot's header)
+    pub world_id: Option<Uuid>,
+    /// Show world name, tile count and season (reads each snapshot's header)
+    pub verbose: bool,
+    pub json: bool,
+}
 
-    // 4. Start WebSocket server in background
-    let addr: SocketAddr = format!("{}:{}", config.websocket_bind, config.websocket_port)
-        .parse()
-        .map_err(|e| format!("Invalid bind address: {}", e))?;
+/// One snapshot in `snapshots list --json`.
+#[derive(Debug, Serialize)]
+struct SnapshotListing {
+    /// Registered world whose directory holds the file (`--all`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    registered_world: Option<String>,
+    file: String,
+    path: String,
+    tick_count: u64,
+    timestamp: u64,
+    file_size: u64,
+    /// Present when headers were read (`--verbose` or `--world-id`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    world: Option<SnapshotWorld>,
+}
 
-    let server_state = Arc::clone(&state);
-    tokio::spawn(async move {
-        if let Err(e) = server::start_server(server_state, addr).await {
-            error!("Server error: {}", e);
+#[derive(Debug, Serialize)]
+struct SnapshotWorld {
+    id: Uuid,
+    name: String,
+    tile_count: u32,
+    season: Season,
+    topology: TopologyType,
+}
+
+/// List the snapshots in one or more directories as a table or JSON array.
+/// Each directory may be labelled with the registered world it belongs to.
+pub fn list_snapshots(
+    dirs: &[(Option<String>, PathBuf)],
+    options: &SnapshotListOptions,
+) -> Result<(), WorldgroundError> {
+    let mut snapshots = Vec::new();
+    for (_, dir) in dirs {
+        snapshots.extend(
+            persistence::list_snapshots(dir)
+                .map_err(|e| WorldgroundError::Persistence(format!("Error listing snapshots in {}: {}", dir.display(), e)))?,
+        );
+    }
+    snapshots.retain(|s| options.ticks.contains(s.tick_count));
+    persistence::sort_snapshots(&mut snapshots, options.sort);
+    let label_of = |path: &Path| {
+        dirs.iter()
+            .find(|(_, dir)| path.parent() == Some(dir.as_path()))
+            .and_then(|(label, _)| label.clone())
+    };
+
+    let read_headers = options.verbose || options.world_id.is_some();
+    let mut listings = Vec::with_capacity(snapshots.len());
+    for s in &snapshots {
+        let world = if read_headers {
+            match persistence::SnapshotReader::open(&s.path) {
+                Ok(reader) => {
+                    let w = reader.world();
+                    Some(SnapshotWorld {
+                        id: w.id,
+                        name: w.name.clone(),
+                        tile_count: w.tile_count,
+                        season: w.season,
+                        topology: w.topology_type,
+                    })
+                }
+                Err(e) => {
+                    warn!(path = %s.path.display(), error = %e, "Unreadable snapshot header");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(id) = options.world_id
+            && world.as_ref().is_none_or(|w| w.id != id)
+        {
+            continue;
         }
-    });
+        listings.push(SnapshotListing {
+            registered_world: label_of(&s.path),
+            file: s.path.file_name().and_then(|n| n.to_str()).unwrap_or("?").to_string(),
+            path: s.path.display().to_string(),
+            tick_count: s.tick_count,
+            timestamp: s.timestamp,
+            file_size: s.file_size,
+            world,
+        });
+    }
 
-    // 5. Set up shutdown signal
-    let shutdown = tokio::signal::ctrl_c();
-    tokio::pin!(shutdown);
+    if options.json {
+        let json = serde_json::to_string_pretty(&listings).map_err(|e| WorldgroundError::Persistence(e.to_string()))?;
+        println!("{}", json);
+        return Ok(());
+    }
 
-    // 6. Run tick loop
-    let tick_interval_ms = (1000.0 / config.tick_rate_hz) as u64;
-    let mut last_snapshot_tick = world.tick_count;
-    let mut ticks_since_snapshot: u32 = 0;
+    let location = match dirs {
+        [(_, dir)] => dir.display().to_string(),
+        _ => format!("{} directories", dirs.len()),
+    };
+    if listings.is_empty() {
+        println!("No snapshots found in {}", location);
+        return Ok(());
+    }
+    let labelled = listings.iter().any(|l| l.registered_world.is_some());
+    if labelled {
+        print!("{:<16} ", "World");
+    }
+    println!("{:<40} {:>8} {:>12}", "File", "Tick", "Size");
+    println!("{}", "-".repeat(if labelled { 79 } else { 62 }));
+    for l in &listings {
+        if labelled {
+            print!("{:<16} ", l.registered_world.as_deref().unwrap_or("-"));
+        }
+        println!("{:<40} {:>8} {:>9} KB", l.file, l.tick_count, l.file_size / 1024);
+        if options.verbose {
+            match &l.world {
+                Some(w) => println!(
+                    "    {} ({}): {} tiles, {:?}, {:?}",
+                    w.name, w.id, w.tile_count, w.season, w.topology
+                ),
+                None => println!("    unreadable header"),
+            }
+        }
+    }
+    println!("\n{} snapshot(s) in {}", listings.len(), location);
+    Ok(())
+}
 
-    info!(
-        tick_rate_hz = config.tick_rate_hz,
-        snapshot_interval = config.snapshot_interval,
-        "Simulation running"
-    );
+/// Print the registered worlds, marking the one in use.
+pub fn list_worlds(registry: &WorldRegistry, registry_path: &Path) {
+    if registry.worlds.is_empty() {
+        println!("No worlds registered in {}", registry_path.display());
+        println!("Register one with: worldground worlds add NAME --snapshots DIR");
+        return;
+    }
+    println!("{:<2}{:<20} {:>9} {:>10}  Directory", "", "Name", "Snapshots", "Latest");
+    println!("{}", "-".repeat(72));
+    for (name, world) in &registry.worlds {
+        let marker = if registry.current.as_deref() == Some(name) { "*" } else { "" };
+        let snapshots =
+            persistence::list_snapshots(Path::new(&world.snapshot_directory)).unwrap_or_default();
+        let latest = snapshots
+            .iter()
+            .map(|s| s.tick_count)
+            .max()
+            .map_or("-".to_string(), |t| format!("tick {}", t));
+        println!(
+            "{:<2}{:<20} {:>9} {:>10}  {}",
+            marker,
+            name,
+            snapshots.len(),
+            latest,
+            world.snapshot_directory
+        );
+        if let Some(rules) = &world.rule_directory {
+            println!("{:<23}rules: {}", "", rules);
+        }
+        if let Some(config) = &world.config {
+            println!("{:<23}config: {}", "", config);
+        }
+        if let Some(worldgen) = &world.worldgen {
+            println!("{:<23}worldgen: {}", "", worldgen);
+        }
+    }
+    println!("\n{} world(s) in {}", registry.worlds.len(), registry_path.display());
+}
 
-    loop {
-        let tick_start = std::time::Instant::now();
+/// Which tiles `worlds splice` copies.
+pub enum SpliceRegion {
+    /// These tile IDs
+    Tiles(Vec<u32>),
+    /// Tiles in a latitude/longitude box, in degrees
+    Box { lat: (f64, f64), lon: (f64, f64) },
+}
 
-        // Lightweight snapshot: only capture mutable layers for diff computation
-        let before_layers: Vec<(WeatherLayer, ConditionsLayer, BiomeLayer, ResourceLayer)> =
-            world.tiles.iter().map(|t| {
-                (t.weather.clone(), t.conditions.clone(), t.biome.clone(), t.resources.clone())
-            }).collect();
+/// Copy a region of the world in snapshot `from` into the world in snapshot
+/// `into`, shifted by `cols` columns and `rows` rows on a flat grid, and save
+/// the result as a new snapshot of `into`'s world in `output` (by default
+/// beside `into`). Returns the snapshot's path.
+pub fn splice_worlds(
+    from: &Path,
+    into: &Path,
+    region: &SpliceRegion,
+    (cols, rows): (i32, i32),
+    output: Option<&Path>,
+) -> Result<PathBuf, WorldgroundError> {
+    let source = persistence::load_snapshot(from)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot {}: {}", from.display(), e)))?;
+    let mut world = persistence::load_snapshot(into)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot {}: {}", into.display(), e)))?;
 
-        // Execute tick
-        let result = simulation::execute_tick(&mut world, &engine, config.season_length);
+    let ids = match region {
+        SpliceRegion::Tiles(ids) => ids.clone(),
+        SpliceRegion::Box { lat, lon } => tiles_in_box(&source, *lat, *lon),
+    };
+    if ids.is_empty() {
+        return Err(WorldgroundError::Config("The region holds no tiles".to_string()));
+    }
+    let pairs = if (cols, rows) == (0, 0) {
+        ids.iter().map(|&id| (id, id)).collect()
+    } else {
+        shift_on_grid(&source, &ids, cols, rows).map_err(WorldgroundError::Simulation)?
+    };
+    let spliced = splice_tiles(&mut world, &source, &pairs)?;
 
-        // Build diff from lightweight layer snapshots (avoids full tile clone)
-        let diff_json = server::build_diff_json_from_layers(
-            &before_layers,
-            &world.tiles,
+    let dir = output.unwrap_or_else(|| into.parent().unwrap_or(Path::new(".")));
+    let saved = persistence::save_snapshot(&world, dir).map_err(|e| WorldgroundError::Persistence(format!("Cannot save snapshot: {}", e)))?;
+    println!(
+        "Spliced {} tile(s) of {} ({}) into {} ({}) at tick {}",
+        spliced.len(),
+        source.name,
+        from.display(),
+        world.name,
+        into.display(),
+        world.tick_count
+    );
+    println!("Saved to {}", saved.display());
+    Ok(saved)
+}
+
+/// Write the latest snapshot's rolling wind and solar potential as CSV (or
+/// JSON) to `output`, or to stdout. Tiles are read a chunk at a time.
+pub fn export_energy(config: &SimulationConfig, output: Option<&Path>, json: bool) -> Result<(), WorldgroundError> {
+    let snapshot_dir = Path::new(&config.snapshot_directory);
+    let reader = persistence::open_latest_valid_snapshot(snapshot_dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+    let world = reader.world();
+    if world.energy.samples == 0 {
+        return Err(WorldgroundError::Persistence(format!(
+            "Snapshot at tick {} has no energy data yet; run the simulation for a few ticks first",
+            world.tick_count
+        )));
+    }
+
+    let write_error = |source: std::io::Error| match output {
+        Some(path) => WorldgroundError::Write { path: path.to_path_buf(), source },
+        None => WorldgroundError::Persistence(format!("Cannot write to stdout: {}", source)),
+    };
+    let mut out: Box<dyn std::io::Write> = match output {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path).map_err(write_error)?)),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    if json {
+        serde_json::to_writer_pretty(&mut out, &world.energy)?;
+    } else {
+        out.write_all(crate::world::energy::CSV_HEADER.as_bytes()).map_err(write_error)?;
+        // Rows go out as each chunk is decoded, so the CSV is never held whole
+        let mut written = Ok(());
+        reader
+            .for_each_chunk(|tiles| {
+                if written.is_ok() {
+                    written = world.energy.write_csv_rows(tiles, &mut out);
+                }
+            })
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to read tiles: {}", e)))?;
+        written.map_err(write_error)?;
+    }
+    out.flush().map_err(write_error)?;
+
+    if let Some(path) = output {
+        println!(
+            "Wrote energy potential for {} tiles ({} tick mean, tick {}) to {}",
+            world.energy.wind_power.len(),
+            world.energy.samples,
             world.tick_count,
-            world.season,
-            &result.statistics,
-            &world.macro_weather.systems,
+            path.display()
         );
+    }
+    Ok(())
+}
 
-        // Rebuild full snapshot JSON periodically (every 10 ticks) instead of every tick.
-        // This avoids serializing all tiles to JSON on every tick at large tile counts.
-        let new_snapshot_json = if world.tick_count % 10 == 0 {
-            Some(server::build_snapshot_json(&world))
-        } else {
-            None
-        };
-
-        // Update server state (broadcasts diff to clients)
-        state
-            .on_tick(
-                new_snapshot_json,
-                diff_json,
-                &result.statistics,
-                world.tick_count,
-                world.season,
-                world.tile_count,
-                last_snapshot_tick,
-            )
-            .await;
+/// Export some layers of every tile of the latest snapshot: as a layer
+/// snapshot that `persistence::load_layer_snapshot` reads back, or as JSON.
+pub fn export_layers(
+    config: &SimulationConfig,
+    layers: &[DiffLayer],
+    output: Option<&Path>,
+    json: bool,
+) -> Result<(), WorldgroundError> {
+    let snapshot_dir = Path::new(&config.snapshot_directory);
+    let world = persistence::load_latest_valid_snapshot(snapshot_dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+    let snapshot = persistence::LayerSnapshot::capture(&world, layers);
+    let names: Vec<String> = snapshot.layers.layers().iter().map(|l| format!("{:?}", l).to_lowercase()).collect();
 
-        // Log errors
-        if !result.rule_errors.is_empty() {
-            warn!(
-                tick = world.tick_count,
-                error_count = result.rule_errors.len(),
-                "Rule errors in tick"
-            );
+    let Some(path) = output else {
+        if !json {
+            return Err(WorldgroundError::Config("A binary layer snapshot needs --output; use --json for stdout".to_string()));
         }
+        let body = serde_json::to_string_pretty(&snapshot.layers).map_err(|e| WorldgroundError::Persistence(e.to_string()))?;
+        return std::io::Write::write_all(&mut std::io::stdout(), body.as_bytes())
+            .map_err(|e| WorldgroundError::Persistence(format!("Cannot write to stdout: {}", e)));
+    };
+    if json {
+        let body = serde_json::to_string_pretty(&snapshot.layers).map_err(|e| WorldgroundError::Persistence(e.to_string()))?;
+        std::fs::write(path, body).map_err(|e| WorldgroundError::Persistence(format!("Cannot write {}: {}", path.display(), e)))?;
+    } else {
+        persistence::save_layer_snapshot(&snapshot, path)
+            .map_err(|e| WorldgroundError::Persistence(format!("Cannot write {}: {}", path.display(), e)))?;
+    }
+    println!(
+        "Wrote {} for {} tiles (tick {}) to {}",
+        names.join(", "),
+        world.tile_count,
+        world.tick_count,
+        path.display()
+    );
+    Ok(())
+}
 
-        // Periodic auto-save
-        ticks_since_snapshot += 1;
-        if ticks_since_snapshot >= config.snapshot_interval {
-            match persistence::save_snapshot(&world, snapshot_dir) {
-                Ok(path) => {
-                    last_snapshot_tick = world.tick_count;
-                    ticks_since_snapshot = 0;
-                    info!(path = %path.display(), "Snapshot saved");
-
-                    // Prune old snapshots
-                    if let Err(e) =
-                        persistence::prune_snapshots(snapshot_dir, config.max_snapshots as usize)
-                    {
-                        warn!("Snapshot pruning failed: {}", e);
-                    }
-                }
-                Err(e) => {
-                    warn!("Snapshot save failed: {}", e);
-                }
-            }
+/// Narrate how the world changed between two of its snapshots: the latest
+/// one, and the newest at or before tick `since` (by default the oldest).
+///
+/// Snapshots don't record floods, landslides or auroras, so only droughts,
+/// biome shifts, temperature and records are described.
+pub fn narrate_snapshots(config: &SimulationConfig, since: Option<u64>) -> Result<(), WorldgroundError> {
+    let snapshot_dir = Path::new(&config.snapshot_directory);
+    let latest = persistence::load_latest_valid_snapshot(snapshot_dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+    let mut candidates = persistence::list_snapshots(snapshot_dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Cannot list snapshots in {}: {}", snapshot_dir.display(), e)))?;
+    candidates.retain(|s| s.tick_count < latest.tick_count && since.is_none_or(|t| s.tick_count <= t));
+    // Closest to `since` first, or oldest first without it
+    match since {
+        Some(_) => candidates.sort_by_key(|s| std::cmp::Reverse(s.tick_count)),
+        None => candidates.sort_by_key(|s| s.tick_count),
+    }
+    let start = candidates
+        .iter()
+        .find_map(|s| persistence::load_snapshot(&s.path).ok().filter(|w| w.id == latest.id))
+        .ok_or_else(|| {
+            WorldgroundError::Persistence(match since {
+                Some(tick) => format!("No snapshot of this world at or before tick {}", tick),
+                None => format!("Only one snapshot of this world at tick {}; nothing to compare", latest.tick_count),
+            })
+        })?;
+
+    let period = format!("since tick {}", start.tick_count);
+    let lines = narrate(&Baseline::of(&start), &latest, &PeriodEvents::default(), &period);
+    println!("Tick {} to {}:", start.tick_count, latest.tick_count);
+    for line in lines {
+        println!("  {}", line);
+    }
+    Ok(())
+}
+
+/// Check the per-tile RNG streams of the latest snapshot for correlations.
+/// Returns whether they look independent.
+pub fn check_rng_streams(config: &SimulationConfig, ticks: u32, draws: u32) -> Result<bool, WorldgroundError> {
+    let snapshot_dir = Path::new(&config.snapshot_directory);
+    let world = persistence::load_latest_valid_snapshot(snapshot_dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+
+    let report = check_rng(&world, ticks, draws);
+    println!(
+        "Replayed {} tick(s) x {} draw(s) for {} tiles from tick {}",
+        report.ticks,
+        report.draws,
+        world.tiles.len(),
+        world.tick_count
+    );
+    println!("  Expected |r| (independent): {:.4}", report.expected_correlation);
+    println!(
+        "  Adjacent tiles:   mean |r| {:.4}, max {:.4}",
+        report.neighbor_correlation, report.max_neighbor_correlation
+    );
+    println!("  Next phase:       mean |r| {:.4}", report.phase_correlation);
+    println!("  Next tick:        mean |r| {:.4}", report.tick_correlation);
+    println!(
+        "  Bit bias:         {:.5} (limit {:.5})",
+        report.bit_bias, report.bit_bias_limit
+    );
+    if report.is_ok() {
+        println!("\nRNG streams look independent");
+    } else {
+        println!("\nCorrelated RNG streams: rules may show striping between tiles");
+    }
+    Ok(report.is_ok())
+}
+
+/// Run the regression world with the current rules and compare its end
+/// statistics with the baseline at `baseline_path`. With `update`, record
+/// them as the new baseline instead, keeping any tolerances already set.
+/// Returns whether every statistic stayed within tolerance.
+pub fn regress_rules(
+    config: &SimulationConfig,
+    baseline_path: &Path,
+    ticks: Option<u32>,
+    update: bool,
+) -> Result<bool, WorldgroundError> {
+    let stored = if baseline_path.exists() {
+        let text = std::fs::read_to_string(baseline_path)
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to read baseline {}: {}", baseline_path.display(), e)))?;
+        let baseline: RegressionBaseline = serde_json::from_str(&text)
+            .map_err(|e| WorldgroundError::Persistence(format!("Invalid baseline {}: {}", baseline_path.display(), e)))?;
+        Some(baseline)
+    } else if update {
+        None
+    } else {
+        return Err(WorldgroundError::Persistence(format!(
+            "No baseline at {}; record one with --update",
+            baseline_path.display()
+        )));
+    };
+
+    let ticks = match (&stored, ticks) {
+        (Some(baseline), Some(n)) if !update && n != baseline.ticks => {
+            return Err(WorldgroundError::Config(format!(
+                "Baseline was recorded over {} ticks, not {}; pass --update to re-record it",
+                baseline.ticks, n
+            )));
         }
+        (_, Some(n)) => n,
+        (Some(baseline), None) => baseline.ticks,
+        (None, None) => regression::DEFAULT_TICKS,
+    };
+    let params = stored.as_ref().map_or_else(regression::standard_world, |b| b.world.clone());
 
-        // Tick milestone logging
-        if world.tick_count % 1000 == 0 {
-            info!(
-                tick = world.tick_count,
-                season = ?world.season,
-                diversity = result.statistics.diversity_index,
-                rule_errors = result.statistics.rule_errors,
-                "Tick milestone"
-            );
+    let mut world = try_generate_world(&params)?;
+    adopt_rule_pack(config, &mut world);
+    let engine = build_engine(config, &world)?;
+    let mut rule_errors = 0;
+    let mut last = None;
+    for _ in 0..ticks {
+        let result = simulation::execute_tick(&mut world, &engine, config.season_length);
+        rule_errors += result.rule_errors.len() as u32;
+        last = Some(result.statistics);
+    }
+    let mut current = server::protocol::TickStatSummary::from(
+        &last.unwrap_or_else(|| simulation::statistics::compute_statistics(&world, 0, 0.0, engine.area_weighted_statistics())),
+    );
+    // Rule errors count over the whole run, not just the last tick
+    current.rule_errors = rule_errors;
+    println!(
+        "Ran {} ticks on {} tiles (seed {}) with rules from {}",
+        ticks,
+        world.tiles.len(),
+        params.seed,
+        engine.rule_dir().display()
+    );
+
+    if update {
+        let baseline = RegressionBaseline {
+            ticks,
+            world: params,
+            statistics: current,
+            tolerances: stored.map(|b| b.tolerances).unwrap_or_default(),
+        };
+        let json = serde_json::to_string_pretty(&baseline)
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to serialize baseline: {}", e)))?;
+        std::fs::write(baseline_path, json + "\n")
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to write baseline {}: {}", baseline_path.display(), e)))?;
+        println!("Baseline written to {}", baseline_path.display());
+        return Ok(true);
+    }
+
+    let baseline = stored.expect("a baseline is loaded unless updating");
+    let comparisons = regression::compare(&baseline, &current);
+    println!(
+        "\n  {:<28} {:>12} {:>12} {:>10} {:>10}",
+        "Statistic", "Baseline", "Current", "Change", "Tolerance"
+    );
+    for c in &comparisons {
+        println!(
+            "{} {:<28} {:>12.4} {:>12.4} {:>+10.4} {:>10.4}",
+            if c.passed() { " " } else { "!" },
+            c.metric,
+            c.baseline,
+            c.current,
+            c.current - c.baseline,
+            c.tolerance
+        );
+    }
+    let failed = comparisons.iter().filter(|c| !c.passed()).count();
+    if failed == 0 {
+        println!("\nAll {} statistics within tolerance", comparisons.len());
+    } else {
+        println!(
+            "\n{} of {} statistics outside tolerance (marked !)",
+            failed,
+            comparisons.len()
+        );
+    }
+    Ok(failed == 0)
+}
+
+/// Run the cases in each fixture file against the rules in `rule_dir`.
+/// Directories stand for the `.toml` files in them, and no fixtures at all
+/// for `<rule_dir>/tests` (the built-in rules bring their own). Returns
+/// whether every case passed.
+pub fn test_rules(rule_dir: &Path, fixtures: &[PathBuf]) -> Result<bool, WorldgroundError> {
+    let engine = RuleEngine::new(rule_dir, 10)?;
+    let default_dir = [rule_dir.join("tests")];
+    if fixtures.is_empty() && is_builtin(rule_dir) {
+        let texts = builtin_rules::FIXTURES.iter().map(|(name, text)| (default_dir[0].join(name), text.to_string()));
+        return run_fixtures(&engine, texts.collect());
+    }
+    let roots = if fixtures.is_empty() { &default_dir[..] } else { fixtures };
+
+    let mut files = Vec::new();
+    for root in roots {
+        if root.is_dir() {
+            let mut found: Vec<PathBuf> = std::fs::read_dir(root)
+                .map_err(|e| WorldgroundError::Rules(format!("Failed to read {}: {}", root.display(), e)))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect();
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(root.clone());
         }
+    }
+    if files.is_empty() {
+        return Err(WorldgroundError::Rules(format!("No fixtures found in {}", roots[0].display())));
+    }
+    let texts = files
+        .into_iter()
+        .map(|file| {
+            let text = std::fs::read_to_string(&file)
+                .map_err(|e| WorldgroundError::Rules(format!("Failed to read {}: {}", file.display(), e)))?;
+            Ok((file, text))
+        })
+        .collect::<Result<_, WorldgroundError>>()?;
+    run_fixtures(&engine, texts)
+}
 
-        // Rate limiting: sleep remaining time to hit target tick rate
-        let elapsed = tick_start.elapsed();
-        let target = std::time::Duration::from_millis(tick_interval_ms);
-        if elapsed < target {
-            let sleep_duration = target - elapsed;
-            tokio::select! {
-                _ = tokio::time::sleep(sleep_duration) => {}
-                _ = &mut shutdown => {
-                    info!("Shutdown signal received");
-                    break;
-                }
+/// Run every case of each fixture, given as (file, text), and print how
+/// each went.
+fn run_fixtures(engine: &RuleEngine, fixtures: Vec<(PathBuf, String)>) -> Result<bool, WorldgroundError> {
+    let (mut passed, mut failed) = (0, 0);
+    for (file, text) in &fixtures {
+        let fixture = Fixture::parse(text).map_err(|e| WorldgroundError::Rules(format!("Invalid fixture {}: {}", file.display(), e)))?;
+        println!("{}", file.display());
+        for case in &fixture.cases {
+            let outcome = rule_tests::run_case(engine, case)
+                .map_err(|e| WorldgroundError::Rules(format!("{}: case '{}': {}", file.display(), case.name, e)))?;
+            println!("  {} {}", if outcome.passed() { "ok  " } else { "FAIL" }, case.name);
+            for failure in &outcome.failures {
+                println!("         {}", failure);
             }
-        } else {
-            // Tick took longer than target — yield to tokio runtime and check for shutdown
-            tokio::select! {
-                biased;
-                _ = &mut shutdown => {
-                    info!("Shutdown signal received");
-                    break;
-                }
-                _ = tokio::task::yield_now() => {}
+            for rejected in &outcome.rejected {
+                println!("         rejected: {}", rejected);
+            }
+            if outcome.passed() {
+                passed += 1;
+            } else {
+                failed += 1;
             }
         }
     }
+    println!("\n{} passed, {} failed", passed, failed);
+    Ok(failed == 0)
+}
 
-    // Graceful shutdown: save final snapshot
-    info!("Saving final snapshot...");
-    match persistence::save_snapshot(&world, snapshot_dir) {
-        Ok(path) => info!(path = %path.display(), "Final snapshot saved"),
-        Err(e) => warn!("Final snapshot save failed: {}", e),
+pub fn doctor_snapshot(path: &Path, repair: bool) -> Result<usize, WorldgroundError> {
+    let mut world = persistence::load_snapshot(path)
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+    let wraps = world.generation_params.topology.wraps();
+
+    println!(
+        "Checking {} ({} tiles, {:?}, tick {})",
+        path.display(),
+        world.tiles.len(),
+        world.topology_type,
+        world.tick_count
+    );
+
+    let issues = validate_neighbor_graph(&world.tiles, world.topology_type, wraps);
+    if issues.is_empty() {
+        println!("Neighbor graph OK");
+        return Ok(0);
     }
 
-    info!(tick = world.tick_count, "Simulation stopped");
-    Ok(())
+    const MAX_LISTED: usize = 20;
+    for issue in issues.iter().take(MAX_LISTED) {
+        println!("  {}", issue);
+    }
+    if issues.len() > MAX_LISTED {
+        println!("  ... and {} more", issues.len() - MAX_LISTED);
+    }
+    let repairable = issues.iter().filter(|i| i.is_repairable()).count();
+    println!(
+        "\n{} issue(s) found, {} repairable",
+        issues.len(),
+        repairable
+    );
+
+    if !repair {
+        if repairable > 0 {
+            println!("Run with --repair to fix repairable issues");
+        }
+        return Ok(issues.len());
+    }
+
+    let changes = repair_neighbor_graph(&mut world.tiles);
+    let remaining = validate_neighbor_graph(&world.tiles, world.topology_type, wraps);
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let saved = persistence::save_snapshot(&world, dir)
+        .map_err(|e| WorldgroundError::Persistence(format!("Cannot save repaired snapshot: {}", e)))?;
+    println!(
+        "Repaired {} neighbor link(s), saved to {}",
+        changes,
+        saved.display()
+    );
+    if !remaining.is_empty() {
+        println!("{} issue(s) could not be repaired:", remaining.len());
+        for issue in remaining.iter().take(MAX_LISTED) {
+            println!("  {}", issue);
+        }
+    }
+
+    Ok(remaining.len())
 }
 
 /// Inspect a tile or world summary from the latest snapshot.
@@ -220,32 +2102,47 @@ pub fn inspect(
     config: &SimulationConfig,
     tile_id: Option<u32>,
     show_world: bool,
-) -> Result<(), String> {
+) -> Result<(), WorldgroundError> {
     let snapshot_dir = Path::new(&config.snapshot_directory);
-    let world = persistence::load_latest_valid_snapshot(snapshot_dir)
-        .map_err(|e| format!("Failed to load snapshot: {}", e))?;
 
     if let Some(id) = tile_id {
-        inspect_tile(&world, id)
-    } else if show_world {
-        inspect_world(&world);
+        // Only the chunk holding the tile is decoded
+        let reader = persistence::open_latest_valid_snapshot(snapshot_dir)
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+        let tile = reader
+            .tile(id)
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to read tile {}: {}", id, e)))?
+            .ok_or_else(|| {
+                WorldgroundError::Config(format!("Tile {} not found (world has {} tiles)", id, reader.world().tile_count))
+            })?;
+        inspect_tile(&tile, id)?;
+        let energy = &reader.world().energy;
+        if let (Some(wind), Some(sun)) =
+            (energy.wind_power.get(id as usize), energy.insolation.get(id as usize))
+        {
+            println!();
+            println!("--- Energy ({} tick mean) ---", energy.samples);
+            println!("  Wind power: {:.1} W/m²", wind);
+            println!("  Insolation: {:.1} W/m²", sun);
+        }
         Ok(())
+    } else if show_world {
+        // Tiles are summarized a chunk at a time, so huge worlds fit in memory
+        let reader = persistence::open_latest_valid_snapshot(snapshot_dir)
+            .map_err(|e| WorldgroundError::Persistence(format!("Failed to load snapshot: {}", e)))?;
+        inspect_world(&reader)
     } else {
-        Err("Specify --tile <ID> or --world".to_string())
+        Err(WorldgroundError::Config("Specify --tile <ID> or --world".to_string()))
     }
 }
 
-fn inspect_tile(world: &World, tile_id: u32) -> Result<(), String> {
-    let tile = world
-        .tiles
-        .get(tile_id as usize)
-        .ok_or_else(|| format!("Tile {} not found (world has {} tiles)", tile_id, world.tiles.len()))?;
+fn inspect_tile(tile: &Tile, tile_id: u32) -> Result<(), WorldgroundError> {
 
     if tile.id != tile_id {
-        return Err(format!(
+        return Err(WorldgroundError::Persistence(format!(
             "Tile at index {} has id {} (expected {})",
             tile_id, tile.id, tile_id
-        ));
+        )));
     }
 
     println!("=== Tile {} ===", tile.id);
@@ -256,6 +2153,7 @@ fn inspect_tile(world: &World, tile_id: u32) -> Result<(), String> {
     println!("  Terrain: {:?}", tile.geology.terrain_type);
     println!("  Elevation: {:.3}", tile.geology.elevation);
     println!("  Soil: {:?}", tile.geology.soil_type);
+    println!("  Sediment: {:.4}", tile.geology.sediment);
     println!("  Drainage: {:.3}", tile.geology.drainage);
     println!();
     println!("--- Climate ---");
@@ -269,6 +2167,7 @@ fn inspect_tile(world: &World, tile_id: u32) -> Result<(), String> {
     println!("  Vegetation health: {:.3}", tile.biome.vegetation_health);
     println!("  Transition pressure: {:.3}", tile.biome.transition_pressure);
     println!("  Ticks in current biome: {}", tile.biome.ticks_in_current_biome);
+    println!("  Root strength: {:.2}", tile.biome.root_strength);
     println!();
     println!("--- Weather ---");
     println!("  Temperature: {:.1}K ({:.1}°C)", tile.weather.temperature, tile.weather.temperature - 273.15);
@@ -285,6 +2184,19 @@ fn inspect_tile(world: &World, tile_id: u32) -> Result<(), String> {
     println!("  Frost days: {}", tile.conditions.frost_days);
     println!("  Drought days: {}", tile.conditions.drought_days);
     println!("  Fire risk: {:.3}", tile.conditions.fire_risk);
+    println!("  Freeze-thaw cycles: {}", tile.conditions.freeze_thaw_cycles);
+    println!("  Trafficability: {:.3}", tile.conditions.trafficability);
+    println!("  Silt fertility: {:.3}", tile.conditions.silt_fertility);
+    println!("  PET: {:.3}", tile.conditions.pet);
+    println!("  Aridity index: {:.3}", tile.conditions.aridity_index);
+    println!("  Fog: {:.3}", tile.conditions.fog);
+    println!("  Visibility: {:.1} km", tile.conditions.visibility_km);
+    println!("  Carrying capacity: {:.2}", tile.conditions.carrying_capacity);
+    println!("  River flow: {:.3}", tile.conditions.river_flow);
+    println!("  Water table: {:.3}", tile.conditions.water_table);
+    println!("  Salinity: {:.2} PSU", tile.conditions.salinity);
+    println!("  Glacier mass: {:.3}", tile.conditions.glacier_mass);
+    println!("  Wave height: {:.1} m", tile.conditions.wave_height);
     println!();
     println!("--- Resources ---");
     if tile.resources.resources.is_empty() {
@@ -298,18 +2210,29 @@ fn inspect_tile(world: &World, tile_id: u32) -> Result<(), String> {
         }
     }
 
+    if !tile.scratch.is_empty() {
+        println!();
+        println!("--- Scratch ---");
+        for (key, value) in &tile.scratch {
+            println!("  {}: {}", key, value);
+        }
+    }
+
     Ok(())
 }
 
-fn inspect_world(world: &World) {
+fn inspect_world(reader: &persistence::SnapshotReader) -> Result<(), WorldgroundError> {
     use std::collections::HashMap;
 
+    let world = reader.world();
     println!("=== World: {} ===", world.name);
     println!("ID: {}", world.id);
     println!("Tick: {}", world.tick_count);
     println!("Season: {:?}", world.season);
-    println!("Tiles: {}", world.tiles.len());
+    println!("Tiles: {}", world.tile_count);
     println!("Topology: {:?}", world.topology_type);
+    println!("Ocean overturning: {:.2}", world.ocean.overturning);
+    println!("Sea level: {:+.3}", world.sea_level.offset);
     println!();
 
     // Biome distribution
@@ -318,14 +2241,18 @@ fn inspect_world(world: &World) {
     let mut total_moisture = 0.0_f64;
     let mut total_veg_health = 0.0_f64;
 
-    for tile in &world.tiles {
-        *biome_counts.entry(tile.biome.biome_type).or_default() += 1;
-        total_temp += tile.weather.temperature as f64;
-        total_moisture += tile.conditions.soil_moisture as f64;
-        total_veg_health += tile.biome.vegetation_health as f64;
-    }
+    reader
+        .for_each_chunk(|tiles| {
+            for tile in tiles {
+                *biome_counts.entry(tile.biome.biome_type).or_default() += 1;
+                total_temp += tile.weather.temperature as f64;
+                total_moisture += tile.conditions.soil_moisture as f64;
+                total_veg_health += tile.biome.vegetation_health as f64;
+            }
+        })
+        .map_err(|e| WorldgroundError::Persistence(format!("Failed to read tiles: {}", e)))?;
 
-    let n = world.tiles.len() as f64;
+    let n = world.tile_count as f64;
     println!("--- Averages ---");
     println!("  Temperature: {:.1}K ({:.1}°C)", total_temp / n, total_temp / n - 273.15);
     println!("  Soil moisture: {:.3}", total_moisture / n);
@@ -339,4 +2266,27 @@ fn inspect_world(world: &World) {
         let pct = (*count as f64 / n) * 100.0;
         println!("  {:?}: {} ({:.1}%)", biome, count, pct);
     }
+
+    let records: Vec<_> = world
+        .records
+        .entries()
+        .into_iter()
+        .filter_map(|(name, record)| record.map(|r| (name, r)))
+        .collect();
+    if !records.is_empty() {
+        println!();
+        println!("--- Records ---");
+        for (name, r) in records {
+            println!("  {}: {:.2} (tile {}, tick {})", name, r.value, r.tile_id, r.tick);
+        }
+    }
+
+    if !world.globals.is_empty() {
+        println!();
+        println!("--- Rule Globals ---");
+        for (name, value) in &world.globals {
+            println!("  {}: {}", name, value);
+        }
+    }
+    Ok(())
 }
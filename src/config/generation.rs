@@ -1,17 +1,28 @@
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
-/// Topology configuration: flat hex grid or geodesic sphere.
+use crate::error::WorldgroundError;
+use crate::world::tile::ClimateZone;
+use crate::world::topology::MAX_GEODESIC_LEVEL;
+
+/// Topology configuration: flat hex grid, cylinder, or geodesic sphere.
 ///
 /// Uses a flat struct (not a tagged enum) for bincode + TOML compatibility.
-/// `mode` is "flat" (default) or "geodesic".
-/// `subdivision_level` is only used in geodesic mode (1-7, default 4).
+/// `mode` is "flat" (default), "wrapping_hex" (flat hex that wraps east-west
+/// only) or "geodesic".
+/// `subdivision_level` is only used in geodesic mode (1-8, default 4).
+/// `boundary` is only used in flat mode: "wrap" (default, toroidal), or one of the
+/// non-wrapping edge modes "reflective", "fixed_climate", "ocean_buffer".
+/// In wrapping_hex mode it sets what the north and south edges see; "wrap"
+/// leaves them bare.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TopologyConfig {
     #[serde(default = "default_mode")]
     pub mode: String,
     #[serde(default = "default_subdivision_level")]
     pub subdivision_level: u32,
+    #[serde(default = "default_boundary")]
+    pub boundary: String,
 }
 
 fn default_mode() -> String {
@@ -22,10 +33,34 @@ fn default_subdivision_level() -> u32 {
     4
 }
 
+fn default_boundary() -> String {
+    "wrap".to_string()
+}
+
+/// Valid values for `TopologyConfig::mode`.
+pub const TOPOLOGY_MODES: &[&str] = &["flat", "wrapping_hex", "geodesic"];
+
+/// Valid values for `GenerationParams::elevation_source`.
+pub const ELEVATION_SOURCES: &[&str] = &["noise", "heightmap"];
+
+/// Valid values for `TopologyConfig::boundary`.
+pub const BOUNDARY_MODES: &[&str] = &["wrap", "reflective", "fixed_climate", "ocean_buffer"];
+
 impl TopologyConfig {
     pub fn is_geodesic(&self) -> bool {
         self.mode == "geodesic"
     }
+
+    /// A flat hex cylinder: columns wrap east-west, rows end at the poles.
+    pub fn is_wrapping_hex(&self) -> bool {
+        self.mode == "wrapping_hex"
+    }
+
+    /// Whether the grid wraps at every edge (toroidal) or has hard borders.
+    /// False for a cylinder, whose north and south edges are hard.
+    pub fn wraps(&self) -> bool {
+        self.is_geodesic() || (!self.is_wrapping_hex() && self.boundary == "wrap")
+    }
 }
 
 impl Default for TopologyConfig {
@@ -33,7 +68,152 @@ impl Default for TopologyConfig {
         TopologyConfig {
             mode: "flat".to_string(),
             subdivision_level: 4,
+            boundary: "wrap".to_string(),
+        }
+    }
+}
+
+/// Layered elevation noise for `elevation_source = "noise"`, the `[noise]`
+/// table in worldgen.toml.
+///
+/// `octaves` layers of Perlin noise are summed, each `lacunarity` times finer
+/// and `persistence` times fainter than the one before. `style` shapes every
+/// layer: "perlin" (default, plain), "ridged" (sharp crests, for mountain
+/// chains) or "billow" (rounded hills). `warp` bends the sampling point by a
+/// second noise field, twisting coastlines and ranges; 0.0 leaves it straight.
+/// The defaults are the single plain octave noise worlds were always made of.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseConfig {
+    #[serde(default = "default_octaves")]
+    pub octaves: u32,
+    #[serde(default = "default_persistence")]
+    pub persistence: f32,
+    #[serde(default = "default_lacunarity")]
+    pub lacunarity: f32,
+    #[serde(default = "default_noise_style")]
+    pub style: String,
+    #[serde(default)]
+    pub warp: f32,
+}
+
+fn default_octaves() -> u32 {
+    1
+}
+
+fn default_persistence() -> f32 {
+    0.5
+}
+
+fn default_lacunarity() -> f32 {
+    2.0
+}
+
+fn default_noise_style() -> String {
+    "perlin".to_string()
+}
+
+/// Valid values for `NoiseConfig::style`.
+pub const NOISE_STYLES: &[&str] = &["perlin", "ridged", "billow"];
+
+/// Most noise layers a world may stack.
+pub const MAX_OCTAVES: u32 = 12;
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        NoiseConfig {
+            octaves: 1,
+            persistence: 0.5,
+            lacunarity: 2.0,
+            style: "perlin".to_string(),
+            warp: 0.0,
+        }
+    }
+}
+
+impl NoiseConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_OCTAVES).contains(&self.octaves) {
+            return Err(format!("noise.octaves must be 1-{}, got {}", MAX_OCTAVES, self.octaves));
+        }
+        if !(self.persistence > 0.0 && self.persistence <= 1.0) {
+            return Err(format!("noise.persistence must be above 0.0 and at most 1.0, got {}", self.persistence));
+        }
+        if !(1.0..=4.0).contains(&self.lacunarity) {
+            return Err(format!("noise.lacunarity must be 1.0-4.0, got {}", self.lacunarity));
+        }
+        if !NOISE_STYLES.contains(&self.style.as_str()) {
+            return Err(format!("noise.style must be one of {:?}, got '{}'", NOISE_STYLES, self.style));
+        }
+        if !(0.0..=2.0).contains(&self.warp) {
+            return Err(format!("noise.warp must be 0.0-2.0, got {}", self.warp));
+        }
+        Ok(())
+    }
+}
+
+/// A region whose climate is forced regardless of latitude bands, one
+/// `[[climate_override]]` table in worldgen.toml.
+///
+/// A tile is in the region when it is within every range given, so at least
+/// one of `lat`, `lon` and `tiles` is required. Overrides apply in order, a
+/// later one winning where regions overlap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClimateOverride {
+    /// Latitude range in degrees, south to north.
+    #[serde(default)]
+    pub lat: Option<[f32; 2]>,
+    /// Longitude range in degrees, west to east. West above east crosses
+    /// the antimeridian.
+    #[serde(default)]
+    pub lon: Option<[f32; 2]>,
+    /// Tile ID range, first and last included.
+    #[serde(default)]
+    pub tiles: Option<[u32; 2]>,
+    /// Zone forced on the region (`ClimateZone` name, e.g. "Polar").
+    #[serde(default)]
+    pub zone: Option<ClimateZone>,
+    /// Base precipitation (0.0-1.0) in place of the zone's.
+    #[serde(default)]
+    pub precipitation: Option<f32>,
+}
+
+impl ClimateOverride {
+    /// Whether a tile at `lat`/`lon` with ID `tile_id` is in the region.
+    pub fn covers(&self, tile_id: u32, lat: f32, lon: f32) -> bool {
+        let in_lat = self.lat.is_none_or(|[south, north]| (south..=north).contains(&lat));
+        let in_lon = self.lon.is_none_or(|[west, east]| {
+            if west <= east {
+                (west..=east).contains(&lon)
+            } else {
+                lon >= west || lon <= east
+            }
+        });
+        let in_tiles = self.tiles.is_none_or(|[first, last]| (first..=last).contains(&tile_id));
+        in_lat && in_lon && in_tiles
+    }
+
+    fn validate(&self, index: usize) -> Result<(), String> {
+        let name = format!("climate_override[{}]", index);
+        if self.lat.is_none() && self.lon.is_none() && self.tiles.is_none() {
+            return Err(format!("{} needs a region: lat, lon or tiles", name));
+        }
+        if self.zone.is_none() && self.precipitation.is_none() {
+            return Err(format!("{} overrides nothing: give zone or precipitation", name));
+        }
+        let outside = |range: std::ops::RangeInclusive<f32>, [a, b]: [f32; 2]| !range.contains(&a) || !range.contains(&b);
+        if let Some([south, north]) = self.lat.filter(|&lat| outside(-90.0..=90.0, lat) || lat[0] > lat[1]) {
+            return Err(format!("{}.lat must be [south, north] within -90.0-90.0, got [{}, {}]", name, south, north));
+        }
+        if let Some([west, east]) = self.lon.filter(|&lon| outside(-180.0..=180.0, lon)) {
+            return Err(format!("{}.lon must be within -180.0-180.0, got [{}, {}]", name, west, east));
         }
+        if let Some([first, last]) = self.tiles.filter(|&[first, last]| first > last) {
+            return Err(format!("{}.tiles must be [first, last] with first <= last, got [{}, {}]", name, first, last));
+        }
+        if let Some(precipitation) = self.precipitation.filter(|p| !(0.0..=1.0).contains(p)) {
+            return Err(format!("{}.precipitation must be 0.0-1.0, got {}", name, precipitation));
+        }
+        Ok(())
     }
 }
 
@@ -51,68 +231,159 @@ pub struct GenerationParams {
     pub initial_biome_maturity: f32,
     #[serde(default)]
     pub topology: TopologyConfig,
+    /// Regions whose climate is forced after the latitude bands are assigned.
+    #[serde(default, rename = "climate_override")]
+    pub climate_overrides: Vec<ClimateOverride>,
+    /// "noise" (default, Perlin noise) or "heightmap" (a grayscale image).
+    #[serde(default = "default_elevation_source")]
+    pub elevation_source: String,
+    /// Path to the grayscale PNG or TIFF read with `elevation_source = "heightmap"`.
+    #[serde(default)]
+    pub heightmap: Option<String>,
+    /// How noise elevation is layered and shaped (ignored for heightmaps).
+    #[serde(default)]
+    pub noise: NoiseConfig,
+    /// Name of the `WorldGenerator` that builds the world (default "procedural").
+    #[serde(default = "default_generator")]
+    pub generator: String,
+}
+
+fn default_elevation_source() -> String {
+    "noise".to_string()
+}
+
+fn default_generator() -> String {
+    crate::world::generator::PROCEDURAL.to_string()
+}
+
+/// The shipped worldgen.toml's parameters, on a 4000-tile flat grid with a
+/// random seed.
+impl Default for GenerationParams {
+    fn default() -> Self {
+        GenerationParams {
+            seed: 0,
+            tile_count: 4000,
+            ocean_ratio: 0.6,
+            mountain_ratio: 0.1,
+            elevation_roughness: 0.5,
+            climate_bands: true,
+            resource_density: 0.3,
+            initial_biome_maturity: 0.5,
+            topology: TopologyConfig::default(),
+            climate_overrides: Vec::new(),
+            elevation_source: default_elevation_source(),
+            heightmap: None,
+            noise: NoiseConfig::default(),
+            generator: default_generator(),
+        }
+    }
 }
 
 impl GenerationParams {
+    /// The image elevation is read from, if it isn't noise.
+    pub fn heightmap_path(&self) -> Option<&Path> {
+        match self.elevation_source.as_str() {
+            "heightmap" => self.heightmap.as_deref().map(Path::new),
+            _ => None,
+        }
+    }
+
     /// Load generation parameters from a TOML file.
-    pub fn from_file(path: &Path) -> Result<Self, String> {
+    pub fn from_file(path: &Path) -> Result<Self, WorldgroundError> {
         let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
-        let params: Self =
-            toml::from_str(&content).map_err(|e| format!("Invalid TOML in {}: {}", path.display(), e))?;
+            .map_err(|source| WorldgroundError::ConfigRead { path: path.to_path_buf(), source })?;
+        let params: Self = toml::from_str(&content)
+            .map_err(|source| WorldgroundError::ConfigToml { path: path.to_path_buf(), source })?;
         params.validate()?;
         Ok(params)
     }
 
     /// Validate parameter ranges.
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), WorldgroundError> {
         if self.tile_count < 100 {
-            return Err(format!(
+            return Err(WorldgroundError::Config(format!(
                 "tile_count must be >= 100, got {}",
                 self.tile_count
-            ));
+            )));
         }
         if !(0.0..=1.0).contains(&self.ocean_ratio) {
-            return Err(format!(
+            return Err(WorldgroundError::Config(format!(
                 "ocean_ratio must be 0.0-1.0, got {}",
                 self.ocean_ratio
-            ));
+            )));
         }
         if !(0.0..=0.5).contains(&self.mountain_ratio) {
-            return Err(format!(
+            return Err(WorldgroundError::Config(format!(
                 "mountain_ratio must be 0.0-0.5, got {}",
                 self.mountain_ratio
-            ));
+            )));
         }
         if !(0.0..=1.0).contains(&self.elevation_roughness) {
-            return Err(format!(
+            return Err(WorldgroundError::Config(format!(
                 "elevation_roughness must be 0.0-1.0, got {}",
                 self.elevation_roughness
-            ));
+            )));
         }
         if !(0.0..=1.0).contains(&self.resource_density) {
-            return Err(format!(
+            return Err(WorldgroundError::Config(format!(
                 "resource_density must be 0.0-1.0, got {}",
                 self.resource_density
-            ));
+            )));
         }
         if !(0.0..=1.0).contains(&self.initial_biome_maturity) {
-            return Err(format!(
+            return Err(WorldgroundError::Config(format!(
                 "initial_biome_maturity must be 0.0-1.0, got {}",
                 self.initial_biome_maturity
-            ));
+            )));
+        }
+        if !TOPOLOGY_MODES.contains(&self.topology.mode.as_str()) {
+            return Err(WorldgroundError::Config(format!(
+                "topology.mode must be one of {:?}, got '{}'",
+                TOPOLOGY_MODES, self.topology.mode
+            )));
+        }
+        if self.topology.is_geodesic() && !(1..=MAX_GEODESIC_LEVEL).contains(&self.topology.subdivision_level) {
+            return Err(WorldgroundError::Config(format!(
+                "subdivision_level must be 1-{}, got {}",
+                MAX_GEODESIC_LEVEL, self.topology.subdivision_level
+            )));
+        }
+        if !BOUNDARY_MODES.contains(&self.topology.boundary.as_str()) {
+            return Err(WorldgroundError::Config(format!(
+                "topology.boundary must be one of {:?}, got '{}'",
+                BOUNDARY_MODES, self.topology.boundary
+            )));
+        }
+        if self.topology.is_geodesic() && self.topology.boundary != "wrap" {
+            return Err(WorldgroundError::Config(format!(
+                "topology.boundary '{}' only applies to flat worlds; geodesic worlds have no edges",
+                self.topology.boundary
+            )));
         }
-        if self.topology.mode != "flat" && self.topology.mode != "geodesic" {
-            return Err(format!(
-                "topology.mode must be 'flat' or 'geodesic', got '{}'",
-                self.topology.mode
-            ));
+        if !ELEVATION_SOURCES.contains(&self.elevation_source.as_str()) {
+            return Err(WorldgroundError::Config(format!(
+                "elevation_source must be one of {:?}, got '{}'",
+                ELEVATION_SOURCES, self.elevation_source
+            )));
         }
-        if self.topology.is_geodesic() && !(1..=7).contains(&self.topology.subdivision_level) {
-            return Err(format!(
-                "subdivision_level must be 1-7, got {}",
-                self.topology.subdivision_level
-            ));
+        match (self.elevation_source.as_str(), &self.heightmap) {
+            ("heightmap", None) => {
+                return Err(WorldgroundError::Config("elevation_source \"heightmap\" needs heightmap = \"<path to a PNG or TIFF>\"".to_string()));
+            }
+            ("heightmap", Some(path)) if !Path::new(path).is_file() => {
+                return Err(WorldgroundError::Config(format!("heightmap '{}' does not exist", path)));
+            }
+            ("noise", Some(_)) => {
+                return Err(WorldgroundError::Config("heightmap is only read with elevation_source = \"heightmap\"".to_string()));
+            }
+            _ => {}
+        }
+        self.noise.validate().map_err(WorldgroundError::Config)?;
+        if self.generator.trim().is_empty() {
+            return Err(WorldgroundError::Config("generator must name a world generator, e.g. \"procedural\"".to_string()));
+        }
+        for (index, climate_override) in self.climate_overrides.iter().enumerate() {
+            climate_override.validate(index).map_err(WorldgroundError::Config)?;
         }
         Ok(())
     }
@@ -128,15 +399,47 @@ mod tests {
         let params = GenerationParams {
             seed: 42,
             tile_count: 1000,
-            ocean_ratio: 0.6,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: TopologyConfig::default(),
+            ..Default::default()
+        };
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn subdivision_level_range() {
+        let mut params = GenerationParams {
+            seed: 42,
+            tile_count: 1000,
+            topology: TopologyConfig {
+                mode: "geodesic".to_string(),
+                subdivision_level: 8,
+                boundary: "wrap".to_string(),
+            },
+            ..Default::default()
         };
         assert!(params.validate().is_ok());
+        params.topology.subdivision_level = 9;
+        assert!(params.validate().unwrap_err().to_string().contains("subdivision_level must be 1-8"));
+    }
+
+    #[test]
+    fn wrapping_hex_mode() {
+        let mut params = GenerationParams {
+            seed: 42,
+            tile_count: 1000,
+            topology: TopologyConfig {
+                mode: "wrapping_hex".to_string(),
+                subdivision_level: 4,
+                boundary: "wrap".to_string(),
+            },
+            ..Default::default()
+        };
+        assert!(params.validate().is_ok());
+        assert!(params.topology.is_wrapping_hex());
+        assert!(!params.topology.wraps(), "the poles are edges");
+        params.topology.boundary = "ocean_buffer".to_string();
+        assert!(params.validate().is_ok());
+        params.topology.mode = "cylinder".to_string();
+        assert!(params.validate().unwrap_err().to_string().contains("topology.mode must be one of"));
     }
 
     #[test]
@@ -144,15 +447,9 @@ mod tests {
         let params = GenerationParams {
             seed: 42,
             tile_count: 50,
-            ocean_ratio: 0.6,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: TopologyConfig::default(),
+            ..Default::default()
         };
-        let err = params.validate().unwrap_err();
+        let err = params.validate().unwrap_err().to_string();
         assert!(
             err.contains("tile_count"),
             "Error should mention tile_count: {}",
@@ -166,14 +463,9 @@ mod tests {
             seed: 42,
             tile_count: 1000,
             ocean_ratio: 1.5,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: TopologyConfig::default(),
+            ..Default::default()
         };
-        let err = params.validate().unwrap_err();
+        let err = params.validate().unwrap_err().to_string();
         assert!(
             err.contains("ocean_ratio"),
             "Error should mention ocean_ratio: {}",
@@ -186,15 +478,10 @@ mod tests {
         let params = GenerationParams {
             seed: 42,
             tile_count: 1000,
-            ocean_ratio: 0.6,
             mountain_ratio: 0.7,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: TopologyConfig::default(),
+            ..Default::default()
         };
-        let err = params.validate().unwrap_err();
+        let err = params.validate().unwrap_err().to_string();
         assert!(
             err.contains("mountain_ratio"),
             "Error should mention mountain_ratio: {}",
@@ -246,7 +533,7 @@ initial_biome_maturity = 0.3
 
     #[test]
     fn from_file_missing() {
-        let err = GenerationParams::from_file(Path::new("/nonexistent/file.toml")).unwrap_err();
+        let err = GenerationParams::from_file(Path::new("/nonexistent/file.toml")).unwrap_err().to_string();
         assert!(err.contains("Cannot read"), "Error: {}", err);
     }
 
@@ -255,7 +542,7 @@ initial_biome_maturity = 0.3
         let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
         write!(tmpfile, "this is not valid toml {{{{").unwrap();
 
-        let err = GenerationParams::from_file(tmpfile.path()).unwrap_err();
+        let err = GenerationParams::from_file(tmpfile.path()).unwrap_err().to_string();
         assert!(err.contains("Invalid TOML"), "Error: {}", err);
     }
 
@@ -277,7 +564,121 @@ initial_biome_maturity = 0.5
         )
         .unwrap();
 
-        let err = GenerationParams::from_file(tmpfile.path()).unwrap_err();
+        let err = GenerationParams::from_file(tmpfile.path()).unwrap_err().to_string();
         assert!(err.contains("tile_count"), "Error: {}", err);
     }
+
+    #[test]
+    fn heightmap_elevation_source() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            tmpfile,
+            r#"
+seed = 1
+tile_count = 1000
+ocean_ratio = 0.5
+mountain_ratio = 0.1
+elevation_roughness = 0.5
+climate_bands = true
+resource_density = 0.3
+initial_biome_maturity = 0.5
+"#
+        )
+        .unwrap();
+        let mut params = GenerationParams::from_file(tmpfile.path()).unwrap();
+        assert_eq!(params.elevation_source, "noise");
+        assert_eq!(params.heightmap_path(), None);
+
+        params.elevation_source = "heightmap".to_string();
+        assert!(params.validate().unwrap_err().to_string().contains("needs heightmap"));
+        params.heightmap = Some("/nonexistent/map.png".to_string());
+        assert!(params.validate().unwrap_err().to_string().contains("does not exist"));
+        let existing = tmpfile.path().to_str().unwrap().to_string();
+        params.heightmap = Some(existing.clone());
+        params.validate().unwrap();
+        assert_eq!(params.heightmap_path(), Some(Path::new(&existing)));
+
+        params.elevation_source = "noise".to_string();
+        assert!(params.validate().unwrap_err().to_string().contains("only read with"));
+        params.elevation_source = "satellite".to_string();
+        assert!(params.validate().unwrap_err().to_string().contains("elevation_source must be one of"));
+    }
+
+    #[test]
+    fn noise_table_parsed_and_checked() {
+        let base = "seed = 1\ntile_count = 1000\nocean_ratio = 0.5\nmountain_ratio = 0.1\nelevation_roughness = 0.5\nclimate_bands = true\nresource_density = 0.3\ninitial_biome_maturity = 0.5\n";
+        let params: GenerationParams = toml::from_str(base).unwrap();
+        assert_eq!(params.noise, NoiseConfig::default());
+
+        let params: GenerationParams =
+            toml::from_str(&format!("{}[noise]\noctaves = 6\nstyle = \"ridged\"\nwarp = 0.5\n", base)).unwrap();
+        assert_eq!(params.noise, NoiseConfig { octaves: 6, style: "ridged".to_string(), warp: 0.5, ..NoiseConfig::default() });
+        params.validate().unwrap();
+
+        for (noise, field) in [
+            (NoiseConfig { octaves: 0, ..NoiseConfig::default() }, "noise.octaves"),
+            (NoiseConfig { persistence: 0.0, ..NoiseConfig::default() }, "noise.persistence"),
+            (NoiseConfig { lacunarity: 0.5, ..NoiseConfig::default() }, "noise.lacunarity"),
+            (NoiseConfig { style: "voronoi".to_string(), ..NoiseConfig::default() }, "noise.style"),
+            (NoiseConfig { warp: 3.0, ..NoiseConfig::default() }, "noise.warp"),
+        ] {
+            let params = GenerationParams { noise, ..params.clone() };
+            assert!(params.validate().unwrap_err().to_string().contains(field), "{}", field);
+        }
+    }
+
+    #[test]
+    fn generator_defaults_to_procedural() {
+        let base = "seed = 1\ntile_count = 1000\nocean_ratio = 0.5\nmountain_ratio = 0.1\nelevation_roughness = 0.5\nclimate_bands = true\nresource_density = 0.3\ninitial_biome_maturity = 0.5\n";
+        let params: GenerationParams = toml::from_str(base).unwrap();
+        assert_eq!(params.generator, "procedural");
+
+        let params: GenerationParams = toml::from_str(&format!("generator = \"tectonic\"\n{}", base)).unwrap();
+        assert_eq!(params.generator, "tectonic");
+        params.validate().unwrap();
+
+        let params = GenerationParams { generator: " ".to_string(), ..params };
+        assert!(params.validate().unwrap_err().to_string().contains("generator"));
+    }
+
+    #[test]
+    fn climate_overrides_from_toml() {
+        let toml_str = r#"
+seed = 42
+tile_count = 1000
+ocean_ratio = 0.6
+mountain_ratio = 0.1
+elevation_roughness = 0.5
+climate_bands = true
+resource_density = 0.3
+initial_biome_maturity = 0.5
+
+[[climate_override]]
+lat = [15.0, 30.0]
+zone = "Subtropical"
+precipitation = 0.05
+
+[[climate_override]]
+lon = [170.0, -170.0]
+tiles = [0, 99]
+zone = "Polar"
+"#;
+        let mut params: GenerationParams = toml::from_str(toml_str).unwrap();
+        params.validate().unwrap();
+        let [desert, ice] = params.climate_overrides.as_slice() else { panic!("expected two overrides") };
+        assert_eq!(desert.zone, Some(ClimateZone::Subtropical));
+        assert!(desert.covers(500, 20.0, 0.0) && !desert.covers(500, 40.0, 0.0));
+        // West above east crosses the antimeridian
+        assert!(ice.covers(5, 0.0, 175.0) && ice.covers(5, 0.0, -175.0));
+        assert!(!ice.covers(5, 0.0, 0.0) && !ice.covers(100, 0.0, 175.0));
+
+        params.climate_overrides[0].zone = None;
+        params.climate_overrides[0].precipitation = None;
+        assert!(params.validate().unwrap_err().to_string().contains("overrides nothing"));
+        params.climate_overrides[0].precipitation = Some(0.05);
+        params.climate_overrides[0].lat = None;
+        assert!(params.validate().unwrap_err().to_string().contains("climate_override[0] needs a region"));
+        params.climate_overrides[0].lat = Some([30.0, 15.0]);
+        assert!(params.validate().unwrap_err().to_string().contains("climate_override[0].lat"));
+    }
 }
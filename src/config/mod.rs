@@ -1,2 +1,4 @@
 pub mod generation;
+pub mod palette;
+pub mod registry;
 pub mod simulation;
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::WorldgroundError;
+use crate::world::tile::{BiomeType, TerrainType};
+use worldground_protocol::units::{Quantity, Units};
+
+/// The palette shipped with worldground; every palette file is laid over it.
+const DEFAULT_PALETTE: &str = include_str!("../../palettes.toml");
+
+/// How one biome or terrain type is drawn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Swatch {
+    /// "#rrggbb"
+    pub color: String,
+    pub label: String,
+}
+
+/// How a continuous tile field is drawn, and its legend. Temperature and
+/// humidity ranges are given in Kelvin and fractions, whatever units the
+/// server sends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerLegend {
+    pub label: String,
+    /// Tile field path, e.g. "weather.temperature"
+    pub field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub min: f32,
+    pub max: f32,
+    /// "#rrggbb" stops spaced evenly from `min` to `max`
+    pub colors: Vec<String>,
+}
+
+/// Colors, labels and legends served at `/api/palette`, so every client
+/// draws the world alike.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    /// Keyed by `BiomeType` name
+    #[serde(default)]
+    pub biomes: BTreeMap<String, Swatch>,
+    /// Keyed by `TerrainType` name
+    #[serde(default)]
+    pub terrain: BTreeMap<String, Swatch>,
+    /// Keyed by viewer color mode
+    #[serde(default)]
+    pub layers: BTreeMap<String, LayerLegend>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        toml::from_str(DEFAULT_PALETTE).expect("the built-in palette is valid")
+    }
+}
+
+/// Parse "#rrggbb" into its channels.
+pub fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#').filter(|h| h.len() == 6)?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+impl Palette {
+    /// Load a palette from a TOML file, keeping the built-in entry for
+    /// anything the file leaves out.
+    pub fn from_file(path: &Path) -> Result<Self, WorldgroundError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|source| WorldgroundError::ConfigRead { path: path.to_path_buf(), source })?;
+        let overrides: Self = toml::from_str(&content)
+            .map_err(|source| WorldgroundError::ConfigToml { path: path.to_path_buf(), source })?;
+        let mut palette = Palette::default();
+        palette.biomes.extend(overrides.biomes);
+        palette.terrain.extend(overrides.terrain);
+        palette.layers.extend(overrides.layers);
+        palette.validate().map_err(WorldgroundError::Config)?;
+        Ok(palette)
+    }
+
+    /// This palette as served to clients that get `units`: the range and
+    /// unit of each temperature or humidity layer converted to match.
+    pub fn in_units(&self, units: Units) -> Palette {
+        let mut palette = self.clone();
+        for layer in palette.layers.values_mut() {
+            if let Some(quantity) = Quantity::of_field(&layer.field) {
+                layer.min = units.convert(quantity, layer.min);
+                layer.max = units.convert(quantity, layer.max);
+                layer.unit = units.symbol(quantity).map(str::to_string);
+            }
+        }
+        palette
+    }
+
+    /// Check names and colors.
+    pub fn validate(&self) -> Result<(), String> {
+        let check_color = |what: &str, color: &str| match parse_color(color) {
+            Some(_) => Ok(()),
+            None => Err(format!("{} color must be \"#rrggbb\", got '{}'", what, color)),
+        };
+        for (name, swatch) in &self.biomes {
+            if serde_json::from_value::<BiomeType>(serde_json::Value::String(name.clone())).is_err() {
+                return Err(format!("Unknown biome '{}' in palette", name));
+            }
+            check_color(&format!("biomes.{}", name), &swatch.color)?;
+        }
+        for (name, swatch) in &self.terrain {
+            if serde_json::from_value::<TerrainType>(serde_json::Value::String(name.clone())).is_err() {
+                return Err(format!("Unknown terrain type '{}' in palette", name));
+            }
+            check_color(&format!("terrain.{}", name), &swatch.color)?;
+        }
+        for (name, layer) in &self.layers {
+            if layer.min.is_nan() || layer.max.is_nan() || layer.min >= layer.max {
+                return Err(format!(
+                    "layers.{} needs min < max, got {} and {}",
+                    name, layer.min, layer.max
+                ));
+            }
+            if layer.colors.len() < 2 {
+                return Err(format!("layers.{} needs at least 2 colors, got {}", name, layer.colors.len()));
+            }
+            for color in &layer.colors {
+                check_color(&format!("layers.{}", name), color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use worldground_protocol::units::{HumidityUnit, TemperatureUnit};
+
+    fn load(toml: &str) -> Result<Palette, String> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        Palette::from_file(file.path()).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn default_palette_covers_every_biome_and_terrain() {
+        let palette = Palette::default();
+        palette.validate().unwrap();
+        assert_eq!(palette.biomes.len(), 11);
+        assert_eq!(palette.terrain.len(), 7);
+        assert_eq!(palette.biomes["BorealForest"].label, "Boreal Forest");
+        assert_eq!(parse_color(&palette.biomes["Ocean"].color), Some([0x1a, 0x52, 0x76]));
+        assert_eq!(palette.layers["temperature"].field, "weather.temperature");
+    }
+
+    #[test]
+    fn temperature_and_humidity_layers_follow_the_units() {
+        let units = Units { temperature: TemperatureUnit::Celsius, humidity: HumidityUnit::Percent };
+        let palette = Palette::default().in_units(units);
+        let temperature = &palette.layers["temperature"];
+        assert!((temperature.min - (230.0 - 273.15)).abs() < 1e-4);
+        assert_eq!(temperature.unit.as_deref(), Some("°C"));
+        assert_eq!(palette.layers["humidity"].max, 100.0);
+        assert_eq!(palette.layers["humidity"].unit.as_deref(), Some("%"));
+        assert_eq!(Palette::default().in_units(Units::default()), Palette::default());
+    }
+
+    #[test]
+    fn file_overrides_only_what_it_names() {
+        let palette = load(
+            r##"
+[biomes]
+Desert = { color = "#ffcc00", label = "Sand Sea" }
+
+[layers.dust]
+label = "Dust"
+field = "weather.dust"
+min = 0.0
+max = 1.0
+colors = ["#1a1a2e", "#c8a050"]
+"##,
+        )
+        .unwrap();
+        assert_eq!(palette.biomes["Desert"].label, "Sand Sea");
+        assert_eq!(palette.biomes["Ocean"], Palette::default().biomes["Ocean"]);
+        assert!(palette.layers.contains_key("dust") && palette.layers.contains_key("temperature"));
+    }
+
+    #[test]
+    fn invalid_palettes_rejected() {
+        assert!(load("[biomes]\nJungle = { color = \"#00ff00\", label = \"Jungle\" }").unwrap_err().contains("Jungle"));
+        assert!(load("[terrain]\nHills = { color = \"green\", label = \"Hills\" }").unwrap_err().contains("#rrggbb"));
+        let flat = "[layers.t]\nlabel = \"T\"\nfield = \"weather.temperature\"\nmin = 1.0\nmax = 1.0\ncolors = [\"#000000\", \"#ffffff\"]";
+        assert!(load(flat).unwrap_err().contains("min < max"));
+        let one_color = "[layers.t]\nlabel = \"T\"\nfield = \"weather.temperature\"\nmin = 0.0\nmax = 1.0\ncolors = [\"#000000\"]";
+        assert!(load(one_color).unwrap_err().contains("at least 2"));
+        assert_eq!(parse_color("#12345"), None);
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::simulation::SimulationConfig;
+use crate::error::WorldgroundError;
+
+/// Directory holding the registry, in the project or the home directory.
+pub const REGISTRY_DIR: &str = ".worldground";
+/// Registry file name inside `REGISTRY_DIR`.
+pub const REGISTRY_FILE: &str = "registry.toml";
+
+/// Known worlds and the one commands use when not given explicit paths.
+///
+/// A project-level `./.worldground/registry.toml` takes precedence over
+/// `~/.worldground/registry.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldRegistry {
+    /// Name of the world selected with `worlds use`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    #[serde(default)]
+    pub worlds: BTreeMap<String, RegisteredWorld>,
+}
+
+/// Where a registered world's files live. Paths are stored absolute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredWorld {
+    pub snapshot_directory: String,
+    /// Rule pack, overriding the config's `rule_directory`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_directory: Option<String>,
+    /// Simulation config used instead of `config.toml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+    /// Generation config used instead of `worldgen.toml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worldgen: Option<String>,
+}
+
+impl RegisteredWorld {
+    /// Point a loaded config at this world's snapshots and rules.
+    pub fn apply_to(&self, config: &mut SimulationConfig) {
+        config.snapshot_directory = self.snapshot_directory.clone();
+        if let Some(rules) = &self.rule_directory {
+            config.rule_directory = rules.clone();
+        }
+    }
+}
+
+impl WorldRegistry {
+    /// The project-level registry if one exists, else the one in the home
+    /// directory. `None` when there is no project registry and no home directory.
+    pub fn default_path() -> Option<PathBuf> {
+        let project = Path::new(REGISTRY_DIR).join(REGISTRY_FILE);
+        if project.exists() {
+            return Some(project);
+        }
+        Self::home_path()
+    }
+
+    /// `~/.worldground/registry.toml`, if the home directory is known.
+    pub fn home_path() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(REGISTRY_DIR).join(REGISTRY_FILE))
+    }
+
+    /// Load a registry; a missing file is an empty registry.
+    pub fn load(path: &Path) -> Result<Self, WorldgroundError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|source| WorldgroundError::ConfigRead { path: path.to_path_buf(), source })?;
+        let registry: Self = toml::from_str(&content)
+            .map_err(|source| WorldgroundError::ConfigToml { path: path.to_path_buf(), source })?;
+        if let Some(current) = &registry.current
+            && !registry.worlds.contains_key(current)
+        {
+            return Err(WorldgroundError::Config(format!(
+                "{}: current world '{}' is not registered",
+                path.display(),
+                current
+            )));
+        }
+        Ok(registry)
+    }
+
+    /// Write the registry, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), WorldgroundError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| WorldgroundError::Persistence(format!("Cannot create {}: {}", dir.display(), e)))?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| WorldgroundError::Persistence(e.to_string()))?;
+        std::fs::write(path, content)
+            .map_err(|e| WorldgroundError::Persistence(format!("Cannot write {}: {}", path.display(), e)))
+    }
+
+    /// The world selected with `worlds use`, if any.
+    pub fn current_world(&self) -> Option<(&str, &RegisteredWorld)> {
+        let name = self.current.as_deref()?;
+        self.worlds.get(name).map(|w| (name, w))
+    }
+
+    /// Register a world, replacing any existing entry with the same name.
+    pub fn add(&mut self, name: &str, world: RegisteredWorld) -> Result<(), WorldgroundError> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(WorldgroundError::Config(format!(
+                "World name '{}' must be letters, digits, '-' or '_'. Example: worlds add ice-age --snapshots ./ice-age",
+                name
+            )));
+        }
+        self.worlds.insert(name.to_string(), world);
+        Ok(())
+    }
+
+    /// Select the world commands use by default.
+    pub fn use_world(&mut self, name: &str) -> Result<(), WorldgroundError> {
+        if !self.worlds.contains_key(name) {
+            return Err(self.unknown(name));
+        }
+        self.current = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Forget a world. Clears the selection if it was the current one.
+    pub fn remove(&mut self, name: &str) -> Result<RegisteredWorld, WorldgroundError> {
+        let world = self.worlds.remove(name).ok_or_else(|| self.unknown(name))?;
+        if self.current.as_deref() == Some(name) {
+            self.current = None;
+        }
+        Ok(world)
+    }
+
+    fn unknown(&self, name: &str) -> WorldgroundError {
+        let known: Vec<&str> = self.worlds.keys().map(String::as_str).collect();
+        WorldgroundError::Config(format!("No registered world '{}' (known: {:?})", name, known))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world(dir: &str) -> RegisteredWorld {
+        RegisteredWorld {
+            snapshot_directory: dir.to_string(),
+            rule_directory: Some("/rules/ice".to_string()),
+            config: None,
+            worldgen: None,
+        }
+    }
+
+    #[test]
+    fn add_use_and_remove_worlds() {
+        let mut registry = WorldRegistry::default();
+        registry.add("ice-age", world("/snaps/ice")).unwrap();
+        registry.add("desert", world("/snaps/desert")).unwrap();
+        assert!(registry.current_world().is_none());
+
+        registry.use_world("desert").unwrap();
+        assert_eq!(registry.current_world().unwrap().0, "desert");
+        assert!(registry.use_world("ocean").unwrap_err().to_string().contains("ice-age"));
+
+        registry.remove("desert").unwrap();
+        assert!(registry.current.is_none());
+        assert!(registry.remove("desert").is_err());
+        assert!(registry.add("has space", world("/x")).is_err());
+    }
+
+    #[test]
+    fn round_trip_through_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(REGISTRY_DIR).join(REGISTRY_FILE);
+        assert_eq!(WorldRegistry::load(&path).unwrap(), WorldRegistry::default());
+
+        let mut registry = WorldRegistry::default();
+        registry.add("ice-age", world("/snaps/ice")).unwrap();
+        registry.use_world("ice-age").unwrap();
+        registry.save(&path).unwrap();
+        assert_eq!(WorldRegistry::load(&path).unwrap(), registry);
+    }
+
+    #[test]
+    fn unknown_current_world_rejected() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(REGISTRY_FILE);
+        std::fs::write(&path, "current = \"gone\"\n").unwrap();
+        assert!(WorldRegistry::load(&path).unwrap_err().to_string().contains("gone"));
+    }
+
+    #[test]
+    fn registered_world_overrides_config_paths() {
+        let mut config: SimulationConfig = toml::from_str("").unwrap();
+        world("/snaps/ice").apply_to(&mut config);
+        assert_eq!(config.snapshot_directory, "/snaps/ice");
+        assert_eq!(config.rule_directory, "/rules/ice");
+    }
+}
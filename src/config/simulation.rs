@@ -1,5 +1,13 @@
 use serde::Deserialize;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use worldground_protocol::units::{HumidityUnit, TemperatureUnit, Units};
+
+use crate::error::WorldgroundError;
+
+/// Largest `rule_batch_size`, well under the sandbox's 1000-element array
+/// limit so a batch always fits in the `tiles` array batch rules are given.
+pub const MAX_RULE_BATCH_SIZE: u32 = 256;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SimulationConfig {
@@ -15,16 +23,232 @@ pub struct SimulationConfig {
     pub websocket_port: u16,
     #[serde(default = "default_websocket_bind")]
     pub websocket_bind: String,
+    /// Send deflated binary messages to clients that ask for them.
+    #[serde(default = "default_websocket_compression")]
+    pub websocket_compression: bool,
+    /// Deflate level (0-9) for compressed clients; low levels keep tick latency down.
+    #[serde(default = "default_websocket_compression_level")]
+    pub websocket_compression_level: u32,
+    /// Seconds between pings to each WebSocket client; 0 disables pings.
+    #[serde(default = "default_websocket_ping_interval")]
+    pub websocket_ping_interval_secs: u64,
+    /// Seconds a client may stay silent before it is disconnected.
+    #[serde(default = "default_websocket_ping_timeout")]
+    pub websocket_ping_timeout_secs: u64,
+    /// Share the port (SO_REUSEPORT) so a live-handoff successor binds it before
+    /// this process exits. Both processes need it.
+    #[serde(default)]
+    pub websocket_reuse_port: bool,
+    /// Limits past which `/health` reports degraded or unhealthy.
+    #[serde(default)]
+    pub health: HealthThresholds,
+    /// Colors, labels and legends served at /api/palette; the built-in palette is used if the file is missing.
+    #[serde(default = "default_palette_file")]
+    pub palette_file: String,
+    /// Unit of temperatures sent to clients and served by the HTTP API: kelvin, celsius or fahrenheit.
+    #[serde(default)]
+    pub temperature_unit: TemperatureUnit,
+    /// Unit of humidities sent to clients and served by the HTTP API: fraction (0-1) or percent.
+    #[serde(default)]
+    pub humidity_unit: HumidityUnit,
     #[serde(default = "default_rule_directory")]
     pub rule_directory: String,
+    /// Rule pack (a subdirectory of `rule_directory`) new worlds run; a world
+    /// keeps the pack it was created with.
+    #[serde(default)]
+    pub rule_pack: Option<String>,
+    /// Reload the rule scripts whenever a `.rhai` file in `rule_directory` changes.
+    #[serde(default = "default_watch_rules")]
+    pub watch_rules: bool,
     #[serde(default = "default_log_level")]
     pub log_level: String,
     #[serde(default = "default_season_length")]
     pub season_length: u32,
     #[serde(default = "default_rule_timeout_ms")]
     pub rule_timeout_ms: u32,
+    /// Rhai operations each rule run may take.
+    #[serde(default = "default_rule_max_operations")]
+    pub rule_max_operations: u64,
+    /// Per-phase overrides of `rule_max_operations` and `rule_timeout_ms`, keyed by phase name.
+    #[serde(default)]
+    pub rule_limits: BTreeMap<String, PhaseRuleLimits>,
+    /// Ticks between runs of the terrain and resources phases, keyed by phase name.
+    #[serde(default)]
+    pub phase_periods: BTreeMap<String, u32>,
     #[serde(default = "default_native_evaluation")]
     pub native_evaluation: bool,
+    /// Tile IDs frozen at startup (added to any frozen set stored in the world).
+    #[serde(default = "default_frozen_tiles")]
+    pub frozen_tiles: Vec<u32>,
+    /// Simulate only this part of a geodesic world; every tile outside it is inert.
+    #[serde(default)]
+    pub active_region: Option<ActiveRegion>,
+    /// Weight statistics by each tile's area on the sphere instead of counting tiles.
+    #[serde(default)]
+    pub area_weighted_statistics: bool,
+    /// Treat `set()` on unknown or phase-forbidden fields as a rule error instead of ignoring it.
+    #[serde(default = "default_strict_rules")]
+    pub strict_rules: bool,
+    /// Tiles each worker evaluates together, sharing one Rhai scope; batch rules (`*.batch.rhai`) run once per batch.
+    #[serde(default = "default_rule_batch_size")]
+    pub rule_batch_size: u32,
+    /// Evaluate idle tiles' rules only every this many ticks; 0 or 1 evaluates every tile every tick.
+    #[serde(default)]
+    pub idle_skip_interval: u32,
+    /// Activity score below which a tile counts as idle.
+    #[serde(default = "default_idle_threshold")]
+    pub idle_threshold: f32,
+    /// Include changed rule scratch variables in per-tick diffs.
+    #[serde(default = "default_scratch_in_diffs")]
+    pub scratch_in_diffs: bool,
+    /// Ticks between tick diffs; each diff covers every tick since the last.
+    #[serde(default = "default_diff_interval")]
+    pub diff_interval: u32,
+    /// Ticks between invariant checks (scripts in `<rule_directory>/invariants/`); 0 disables them.
+    #[serde(default = "default_invariant_interval")]
+    pub invariant_interval: u32,
+    /// Tiles sampled per invariant check.
+    #[serde(default = "default_invariant_sample_size")]
+    pub invariant_sample_size: u32,
+    /// Fraction of each latitude band's long-run temperature anomaly removed per tick; 0 disables the thermostat.
+    #[serde(default = "default_thermostat_rate")]
+    pub thermostat_rate: f32,
+    /// Width in degrees of the thermostat's latitude bands.
+    #[serde(default = "default_thermostat_band_degrees")]
+    pub thermostat_band_degrees: f32,
+    /// Chance per tick that a stationary blocking high forms in the mid-latitudes; 0 disables blocking.
+    #[serde(default = "default_blocking_chance")]
+    pub blocking_chance: f32,
+    /// Thaws add mud and loosen soil after the conditions phase.
+    #[serde(default = "default_freeze_thaw")]
+    pub freeze_thaw: bool,
+    /// With `freeze_thaw`, let rock soil break down to sand over many cycles.
+    #[serde(default = "default_freeze_thaw_rock_to_sand")]
+    pub freeze_thaw_rock_to_sand: bool,
+    /// Soil creeps down slopes, held back by vegetation, after the terrain phase.
+    #[serde(default = "default_erosion")]
+    pub erosion: bool,
+    /// With `erosion`, let steep wet slopes with decayed roots fail as landslides.
+    #[serde(default = "default_erosion_landslides")]
+    pub erosion_landslides: bool,
+    /// With `erosion`, let rivers and wind carry sediment downhill and downwind.
+    #[serde(default = "default_erosion_sediment")]
+    pub erosion_sediment: bool,
+    /// Recent ticks kept as in-memory checkpoints that `/api/control` can roll back; 0 disables rollback.
+    #[serde(default = "default_checkpoint_ticks")]
+    pub checkpoint_ticks: u32,
+    /// Append each tick's seed, rule hash, pressure-system changes and state hash to
+    /// `replay.jsonl` in the snapshot directory, for `worldground replay`.
+    #[serde(default = "default_replay_log")]
+    pub replay_log: bool,
+    /// Append each tick's world events to `events.jsonl` in the snapshot directory.
+    #[serde(default = "default_event_log")]
+    pub event_log: bool,
+    /// Keep deposits in a memory-mapped page file in the snapshot directory
+    /// outside the resources phase, trading tick time for memory on very large worlds.
+    #[serde(default = "default_page_cold_layers")]
+    pub page_cold_layers: bool,
+    /// Ticks to run unpaced and without diffs at startup before settling to `tick_rate_hz`.
+    #[serde(default = "default_warmup_ticks")]
+    pub warmup_ticks: u32,
+    /// Generate cosmetic aurora on polar tiles for the viewer; rules never see it.
+    #[serde(default = "default_aurora")]
+    pub aurora: bool,
+    /// With `aurora`, chance per tick that a geomagnetic storm starts.
+    #[serde(default = "default_aurora_chance")]
+    pub aurora_chance: f32,
+    /// Carry desert dust and volcanic ash on the wind, cooling tiles under it and fertilizing where it settles.
+    #[serde(default = "default_dust")]
+    pub dust: bool,
+    /// With `dust`, chance per tick that a mountain tile erupts.
+    #[serde(default = "default_dust_eruption_chance")]
+    pub dust_eruption_chance: f32,
+    /// Track sea-surface salinity and an overturning circulation that carries heat poleward.
+    #[serde(default = "default_ocean_circulation")]
+    pub ocean_circulation: bool,
+    /// With `ocean_circulation`, warming (K) of the polar ocean at full overturning strength.
+    #[serde(default = "default_ocean_heat_transport")]
+    pub ocean_heat_transport: f32,
+    /// Grow glaciers where snow lasts year-round, flowing downslope and melting into rivers.
+    #[serde(default = "default_glaciers")]
+    pub glaciers: bool,
+    /// With `glaciers`, let moving ice carve down its bed.
+    #[serde(default = "default_glacier_carving")]
+    pub glacier_carving: bool,
+    /// Let the sea rise and fall with melting land ice and scenario rules, drowning or exposing coasts.
+    #[serde(default = "default_sea_level")]
+    pub sea_level: bool,
+    /// With `sea_level`, rise (elevation units) for each unit of land ice melted per ocean tile.
+    #[serde(default = "default_sea_level_ice_factor")]
+    pub sea_level_ice_factor: f32,
+    /// Raise wind waves on the open ocean from wind speed, fetch and storms.
+    #[serde(default = "default_waves")]
+    pub waves: bool,
+    /// Ticks between plain-English narrations of the world; 0 narrates once a year (4 × `season_length`).
+    #[serde(default = "default_narration_interval")]
+    pub narration_interval: u32,
+    /// Time one tile evaluation in this many, rule by rule, for a flamegraph; 0 disables profiling.
+    #[serde(default = "default_profile_sample_interval")]
+    pub profile_sample_interval: u32,
+    /// Collapsed-stack file the rule profile is written to at each snapshot and at shutdown.
+    #[serde(default = "default_profile_output")]
+    pub profile_output: String,
+}
+
+/// A circle on a geodesic world, `radius_deg` degrees of arc around (`lat`, `lon`).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ActiveRegion {
+    pub lat: f64,
+    pub lon: f64,
+    pub radius_deg: f64,
+}
+
+/// When `/health` reports degraded or unhealthy; past an unhealthy limit it
+/// answers 503.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthThresholds {
+    /// Ticks per second the simulation can run, as a fraction of `tick_rate_hz`
+    pub degraded_tick_rate: f32,
+    pub unhealthy_tick_rate: f32,
+    /// Share of tiles whose rules failed on the last tick
+    pub degraded_rule_errors: f32,
+    pub unhealthy_rule_errors: f32,
+    /// Time since the last snapshot, in snapshot intervals
+    pub degraded_snapshot_age: f32,
+    pub unhealthy_snapshot_age: f32,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        HealthThresholds {
+            degraded_tick_rate: 1.0,
+            unhealthy_tick_rate: 0.5,
+            degraded_rule_errors: 0.01,
+            unhealthy_rule_errors: 0.1,
+            degraded_snapshot_age: 2.0,
+            unhealthy_snapshot_age: 5.0,
+        }
+    }
+}
+
+/// The phases `rule_limits` may name, as their rule directories are named.
+pub const RULE_PHASES: [&str; 4] = ["weather", "conditions", "terrain", "resources"];
+
+/// Whether `name` can name a rule pack: one plain directory name.
+fn is_pack_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+/// The phases `phase_periods` may name; weather and conditions run every tick.
+pub const SLOW_PHASES: [&str; 2] = ["terrain", "resources"];
+
+/// One phase's rule budget; a limit left out falls back to the global one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhaseRuleLimits {
+    pub max_operations: Option<u64>,
+    pub timeout_ms: Option<u32>,
 }
 
 fn default_tick_rate() -> f32 {
@@ -42,12 +266,30 @@ fn default_snapshot_directory() -> String {
 fn default_websocket_port() -> u16 {
     8118
 }
+fn default_websocket_compression() -> bool {
+    true
+}
+fn default_websocket_compression_level() -> u32 {
+    1
+}
+fn default_websocket_ping_interval() -> u64 {
+    20
+}
+fn default_websocket_ping_timeout() -> u64 {
+    60
+}
 fn default_websocket_bind() -> String {
     "127.0.0.1".to_string()
 }
+fn default_palette_file() -> String {
+    "./palettes.toml".to_string()
+}
 fn default_rule_directory() -> String {
     "./rules".to_string()
 }
+fn default_watch_rules() -> bool {
+    false
+}
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -57,25 +299,155 @@ fn default_season_length() -> u32 {
 fn default_rule_timeout_ms() -> u32 {
     10
 }
+fn default_rule_max_operations() -> u64 {
+    100_000
+}
 fn default_native_evaluation() -> bool {
     true
 }
+fn default_frozen_tiles() -> Vec<u32> {
+    Vec::new()
+}
+fn default_strict_rules() -> bool {
+    false
+}
+fn default_rule_batch_size() -> u32 {
+    1
+}
+fn default_scratch_in_diffs() -> bool {
+    false
+}
+fn default_diff_interval() -> u32 {
+    1
+}
+fn default_invariant_interval() -> u32 {
+    10
+}
+fn default_invariant_sample_size() -> u32 {
+    256
+}
+fn default_idle_threshold() -> f32 {
+    0.05
+}
+fn default_thermostat_rate() -> f32 {
+    0.0
+}
+fn default_thermostat_band_degrees() -> f32 {
+    10.0
+}
+fn default_freeze_thaw() -> bool {
+    false
+}
+fn default_freeze_thaw_rock_to_sand() -> bool {
+    true
+}
+fn default_erosion() -> bool {
+    false
+}
+fn default_erosion_landslides() -> bool {
+    true
+}
+fn default_erosion_sediment() -> bool {
+    true
+}
+fn default_checkpoint_ticks() -> u32 {
+    0
+}
+fn default_replay_log() -> bool {
+    false
+}
+fn default_event_log() -> bool {
+    false
+}
+fn default_page_cold_layers() -> bool {
+    false
+}
+fn default_warmup_ticks() -> u32 {
+    0
+}
+fn default_blocking_chance() -> f32 {
+    0.0
+}
+fn default_aurora() -> bool {
+    false
+}
+fn default_aurora_chance() -> f32 {
+    0.005
+}
+fn default_dust() -> bool {
+    false
+}
+fn default_dust_eruption_chance() -> f32 {
+    0.002
+}
+fn default_ocean_circulation() -> bool {
+    false
+}
+fn default_ocean_heat_transport() -> f32 {
+    2.0
+}
+fn default_glaciers() -> bool {
+    false
+}
+fn default_glacier_carving() -> bool {
+    true
+}
+fn default_sea_level() -> bool {
+    false
+}
+fn default_sea_level_ice_factor() -> f32 {
+    0.02
+}
+fn default_waves() -> bool {
+    false
+}
+fn default_narration_interval() -> u32 {
+    0
+}
+fn default_profile_sample_interval() -> u32 {
+    0
+}
+fn default_profile_output() -> String {
+    "./rule-profile.folded".to_string()
+}
 
 impl SimulationConfig {
-    pub fn from_file(path: &Path) -> Result<Self, String> {
+    pub fn from_file(path: &Path) -> Result<Self, WorldgroundError> {
         let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            .map_err(|source| WorldgroundError::ConfigRead { path: path.to_path_buf(), source })?;
         Self::from_toml_str(&content, path)
     }
 
-    pub fn from_toml_str(content: &str, source_path: &Path) -> Result<Self, String> {
-        let config: SimulationConfig =
-            toml::from_str(content).map_err(|e| format!("{}: {}", source_path.display(), e))?;
+    pub fn from_toml_str(content: &str, source_path: &Path) -> Result<Self, WorldgroundError> {
+        let config: SimulationConfig = toml::from_str(content)
+            .map_err(|source| WorldgroundError::ConfigToml { path: source_path.to_path_buf(), source })?;
         config.validate()?;
         Ok(config)
     }
 
-    pub fn validate(&self) -> Result<(), String> {
+    /// Ticks per narration period, resolving 0 to one year.
+    pub fn narration_ticks(&self) -> u64 {
+        match self.narration_interval {
+            0 => 4 * self.season_length as u64,
+            n => n as u64,
+        }
+    }
+
+    /// The units served to clients.
+    pub fn units(&self) -> Units {
+        Units { temperature: self.temperature_unit, humidity: self.humidity_unit }
+    }
+
+    /// Where new worlds' rules are: `rule_directory`, or its `rule_pack`
+    /// subdirectory.
+    pub fn rule_pack_directory(&self) -> PathBuf {
+        match &self.rule_pack {
+            Some(pack) => Path::new(&self.rule_directory).join(pack),
+            None => PathBuf::from(&self.rule_directory),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), WorldgroundError> {
         let mut errors = Vec::new();
 
         if self.tick_rate_hz <= 0.0 {
@@ -120,6 +492,183 @@ impl SimulationConfig {
             ));
         }
 
+        if self.rule_max_operations == 0 {
+            errors.push(format!(
+                "rule_max_operations must be > 0, got {}. Example: rule_max_operations = 100000",
+                self.rule_max_operations
+            ));
+        }
+
+        for (phase, limits) in &self.rule_limits {
+            if !RULE_PHASES.contains(&phase.as_str()) {
+                errors.push(format!(
+                    "rule_limits names unknown phase '{}' (expected {}). Example: rule_limits.terrain = {{ max_operations = 500000 }}",
+                    phase,
+                    RULE_PHASES.join(", ")
+                ));
+            }
+            if limits.max_operations == Some(0) || limits.timeout_ms == Some(0) {
+                errors.push(format!(
+                    "rule_limits.{} limits must be > 0. Example: rule_limits.{} = {{ max_operations = 500000, timeout_ms = 50 }}",
+                    phase, phase
+                ));
+            }
+        }
+
+        if let Some(pack) = self.rule_pack.as_deref().filter(|p| !is_pack_name(p)) {
+            errors.push(format!(
+                "rule_pack must be the name of a subdirectory of rule_directory, got '{}'. Example: rule_pack = \"arid-world\"",
+                pack
+            ));
+        }
+
+        for (phase, &ticks) in &self.phase_periods {
+            if !SLOW_PHASES.contains(&phase.as_str()) {
+                errors.push(format!(
+                    "phase_periods names '{}', which can't be slowed (expected {}). Example: phase_periods.terrain = 10",
+                    phase,
+                    SLOW_PHASES.join(", ")
+                ));
+            } else if ticks == 0 {
+                errors.push(format!(
+                    "phase_periods.{} must be >= 1 tick. Example: phase_periods.{} = 10",
+                    phase, phase
+                ));
+            }
+        }
+
+        let health = &self.health;
+        if !(health.unhealthy_tick_rate >= 0.0 && health.unhealthy_tick_rate <= health.degraded_tick_rate) {
+            errors.push(format!(
+                "health.unhealthy_tick_rate must be 0.0-degraded_tick_rate ({}), got {}. Example: health = {{ degraded_tick_rate = 1.0, unhealthy_tick_rate = 0.5 }}",
+                health.degraded_tick_rate, health.unhealthy_tick_rate
+            ));
+        }
+        if !(0.0 <= health.degraded_rule_errors
+            && health.degraded_rule_errors <= health.unhealthy_rule_errors
+            && health.unhealthy_rule_errors <= 1.0)
+        {
+            errors.push(format!(
+                "health rule error limits must satisfy 0.0 <= degraded_rule_errors <= unhealthy_rule_errors <= 1.0, got {} and {}. Example: health = {{ degraded_rule_errors = 0.01, unhealthy_rule_errors = 0.1 }}",
+                health.degraded_rule_errors, health.unhealthy_rule_errors
+            ));
+        }
+        if !(1.0 <= health.degraded_snapshot_age && health.degraded_snapshot_age <= health.unhealthy_snapshot_age) {
+            errors.push(format!(
+                "health snapshot age limits must satisfy 1.0 <= degraded_snapshot_age <= unhealthy_snapshot_age, got {} and {}. Example: health = {{ degraded_snapshot_age = 2.0, unhealthy_snapshot_age = 5.0 }}",
+                health.degraded_snapshot_age, health.unhealthy_snapshot_age
+            ));
+        }
+
+        if !(1..=MAX_RULE_BATCH_SIZE).contains(&self.rule_batch_size) {
+            errors.push(format!(
+                "rule_batch_size must be 1-{}, got {}. Example: rule_batch_size = 32",
+                MAX_RULE_BATCH_SIZE, self.rule_batch_size
+            ));
+        }
+
+        if self.page_cold_layers && (self.checkpoint_ticks > 0 || self.replay_log) {
+            errors.push(
+                "page_cold_layers can't be combined with checkpoint_ticks or replay_log, which need every tile's deposits each tick. Example: page_cold_layers = true, checkpoint_ticks = 0, replay_log = false".to_string(),
+            );
+        }
+
+        if self.diff_interval == 0 {
+            errors.push(format!(
+                "diff_interval must be > 0, got {}. Example: diff_interval = 10",
+                self.diff_interval
+            ));
+        }
+
+        if !(self.idle_threshold > 0.0 && self.idle_threshold.is_finite()) {
+            errors.push(format!(
+                "idle_threshold must be > 0.0, got {}. Example: idle_threshold = 0.05",
+                self.idle_threshold
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.thermostat_rate) {
+            errors.push(format!(
+                "thermostat_rate must be 0.0-1.0, got {}. Example: thermostat_rate = 0.01",
+                self.thermostat_rate
+            ));
+        }
+
+        if !(self.thermostat_band_degrees > 0.0 && self.thermostat_band_degrees <= 90.0) {
+            errors.push(format!(
+                "thermostat_band_degrees must be > 0.0 and <= 90.0, got {}. Example: thermostat_band_degrees = 10.0",
+                self.thermostat_band_degrees
+            ));
+        }
+
+        if self.websocket_compression_level > 9 {
+            errors.push(format!(
+                "websocket_compression_level must be 0-9, got {}. Example: websocket_compression_level = 1",
+                self.websocket_compression_level
+            ));
+        }
+
+        if self.websocket_ping_interval_secs > 0
+            && self.websocket_ping_timeout_secs <= self.websocket_ping_interval_secs
+        {
+            errors.push(format!(
+                "websocket_ping_timeout_secs ({}) must be greater than websocket_ping_interval_secs ({}). Example: websocket_ping_timeout_secs = 60",
+                self.websocket_ping_timeout_secs, self.websocket_ping_interval_secs
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.blocking_chance) {
+            errors.push(format!(
+                "blocking_chance must be 0.0-1.0, got {}. Example: blocking_chance = 0.01",
+                self.blocking_chance
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.aurora_chance) {
+            errors.push(format!(
+                "aurora_chance must be 0.0-1.0, got {}. Example: aurora_chance = 0.005",
+                self.aurora_chance
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.dust_eruption_chance) {
+            errors.push(format!(
+                "dust_eruption_chance must be 0.0-1.0, got {}. Example: dust_eruption_chance = 0.002",
+                self.dust_eruption_chance
+            ));
+        }
+
+        if !(0.0..=20.0).contains(&self.ocean_heat_transport) {
+            errors.push(format!(
+                "ocean_heat_transport must be 0.0-20.0, got {}. Example: ocean_heat_transport = 2.0",
+                self.ocean_heat_transport
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.sea_level_ice_factor) {
+            errors.push(format!(
+                "sea_level_ice_factor must be 0.0-1.0, got {}. Example: sea_level_ice_factor = 0.02",
+                self.sea_level_ice_factor
+            ));
+        }
+
+        if let Some(region) = &self.active_region
+            && (!(-90.0..=90.0).contains(&region.lat)
+                || !(-180.0..=180.0).contains(&region.lon)
+                || !(0.0..=180.0).contains(&region.radius_deg))
+        {
+            errors.push(format!(
+                "active_region needs lat -90-90, lon -180-180 and radius_deg 0-180, got lat {}, lon {}, radius_deg {}. Example: active_region = {{ lat = 45.0, lon = 10.0, radius_deg = 30.0 }}",
+                region.lat, region.lon, region.radius_deg
+            ));
+        }
+
+        if self.profile_sample_interval > 0 && self.profile_output.is_empty() {
+            errors.push(
+                "profile_output must be set when profile_sample_interval > 0. Example: profile_output = \"./rule-profile.folded\"".to_string(),
+            );
+        }
+
         let valid_levels = ["error", "warn", "info", "debug", "trace"];
         if !valid_levels.contains(&self.log_level.as_str()) {
             errors.push(format!(
@@ -131,7 +680,7 @@ impl SimulationConfig {
         if errors.is_empty() {
             Ok(())
         } else {
-            Err(errors.join("\n"))
+            Err(WorldgroundError::Config(errors.join("\n")))
         }
     }
 }
@@ -155,10 +704,55 @@ mod tests {
             snapshot_directory = "./data/snapshots"
             websocket_port = 9090
             websocket_bind = "0.0.0.0"
+            websocket_compression = false
+            websocket_compression_level = 6
+            websocket_ping_interval_secs = 10
+            websocket_ping_timeout_secs = 25
+            websocket_reuse_port = true
+            palette_file = "./look/palettes.toml"
+            temperature_unit = "celsius"
+            humidity_unit = "percent"
+            health = { degraded_tick_rate = 0.8, unhealthy_snapshot_age = 10.0 }
             rule_directory = "./my-rules"
+            rule_pack = "arid-world"
             log_level = "debug"
             season_length = 120
             rule_timeout_ms = 20
+            strict_rules = true
+            area_weighted_statistics = true
+            rule_batch_size = 32
+            idle_skip_interval = 4
+            idle_threshold = 0.1
+            scratch_in_diffs = true
+            diff_interval = 10
+            invariant_interval = 5
+            invariant_sample_size = 64
+            thermostat_rate = 0.01
+            thermostat_band_degrees = 15.0
+            blocking_chance = 0.02
+            freeze_thaw = true
+            freeze_thaw_rock_to_sand = false
+            aurora = true
+            aurora_chance = 0.02
+            dust = true
+            dust_eruption_chance = 0.01
+            ocean_circulation = true
+            ocean_heat_transport = 3.5
+            glaciers = true
+            glacier_carving = false
+            sea_level = true
+            sea_level_ice_factor = 0.05
+            waves = true
+            warmup_ticks = 500
+            checkpoint_ticks = 20
+            replay_log = true
+            event_log = true
+            erosion = true
+            erosion_landslides = false
+            erosion_sediment = false
+            narration_interval = 90
+            profile_sample_interval = 8
+            profile_output = "./perf/rules.folded"
         "#;
         let config = SimulationConfig::from_toml_str(toml, &test_path()).unwrap();
         assert_eq!(config.tick_rate_hz, 2.0);
@@ -167,10 +761,59 @@ mod tests {
         assert_eq!(config.snapshot_directory, "./data/snapshots");
         assert_eq!(config.websocket_port, 9090);
         assert_eq!(config.websocket_bind, "0.0.0.0");
+        assert!(!config.websocket_compression);
+        assert_eq!(config.websocket_compression_level, 6);
+        assert_eq!(config.websocket_ping_interval_secs, 10);
+        assert_eq!(config.websocket_ping_timeout_secs, 25);
+        assert!(config.websocket_reuse_port);
+        assert_eq!(config.palette_file, "./look/palettes.toml");
+        assert_eq!(config.temperature_unit, TemperatureUnit::Celsius);
+        assert_eq!(config.humidity_unit, HumidityUnit::Percent);
+        assert_eq!(config.health.degraded_tick_rate, 0.8);
+        assert_eq!(config.health.unhealthy_snapshot_age, 10.0);
+        assert_eq!(config.health.unhealthy_tick_rate, 0.5);
         assert_eq!(config.rule_directory, "./my-rules");
+        assert_eq!(config.rule_pack.as_deref(), Some("arid-world"));
+        assert_eq!(config.rule_pack_directory(), Path::new("./my-rules/arid-world"));
         assert_eq!(config.log_level, "debug");
         assert_eq!(config.season_length, 120);
         assert_eq!(config.rule_timeout_ms, 20);
+        assert!(config.strict_rules);
+        assert!(config.area_weighted_statistics);
+        assert_eq!(config.rule_batch_size, 32);
+        assert_eq!(config.idle_skip_interval, 4);
+        assert_eq!(config.idle_threshold, 0.1);
+        assert!(config.scratch_in_diffs);
+        assert_eq!(config.diff_interval, 10);
+        assert_eq!(config.invariant_interval, 5);
+        assert_eq!(config.invariant_sample_size, 64);
+        assert_eq!(config.thermostat_rate, 0.01);
+        assert_eq!(config.thermostat_band_degrees, 15.0);
+        assert_eq!(config.blocking_chance, 0.02);
+        assert!(config.freeze_thaw);
+        assert!(!config.freeze_thaw_rock_to_sand);
+        assert!(config.aurora);
+        assert_eq!(config.aurora_chance, 0.02);
+        assert!(config.dust);
+        assert_eq!(config.dust_eruption_chance, 0.01);
+        assert!(config.ocean_circulation);
+        assert_eq!(config.ocean_heat_transport, 3.5);
+        assert!(config.glaciers);
+        assert!(!config.glacier_carving);
+        assert!(config.sea_level);
+        assert_eq!(config.sea_level_ice_factor, 0.05);
+        assert!(config.waves);
+        assert_eq!(config.warmup_ticks, 500);
+        assert_eq!(config.checkpoint_ticks, 20);
+        assert!(config.replay_log);
+        assert!(config.event_log);
+        assert!(config.erosion);
+        assert!(!config.erosion_landslides);
+        assert!(!config.erosion_sediment);
+        assert_eq!(config.narration_interval, 90);
+        assert_eq!(config.narration_ticks(), 90);
+        assert_eq!(config.profile_sample_interval, 8);
+        assert_eq!(config.profile_output, "./perf/rules.folded");
     }
 
     #[test]
@@ -182,15 +825,84 @@ mod tests {
         assert_eq!(config.snapshot_directory, "./snapshots");
         assert_eq!(config.websocket_port, 8118);
         assert_eq!(config.websocket_bind, "127.0.0.1");
+        assert!(config.websocket_compression);
+        assert_eq!(config.websocket_compression_level, 1);
+        assert_eq!(config.websocket_ping_interval_secs, 20);
+        assert_eq!(config.websocket_ping_timeout_secs, 60);
+        assert!(!config.websocket_reuse_port);
+        assert_eq!(config.palette_file, "./palettes.toml");
+        assert!(config.units().is_default());
+        assert_eq!(config.health, HealthThresholds::default());
         assert_eq!(config.rule_directory, "./rules");
+        assert_eq!(config.rule_pack, None);
         assert_eq!(config.log_level, "info");
         assert_eq!(config.season_length, 90);
         assert_eq!(config.rule_timeout_ms, 10);
+        assert_eq!(config.rule_max_operations, 100_000);
+        assert!(config.rule_limits.is_empty());
+        assert!(config.phase_periods.is_empty());
+        assert!(config.frozen_tiles.is_empty());
+        assert_eq!(config.active_region, None);
+        assert!(!config.area_weighted_statistics);
+        assert!(!config.strict_rules);
+        assert_eq!(config.rule_batch_size, 1);
+        assert!(!config.scratch_in_diffs);
+        assert_eq!(config.idle_skip_interval, 0);
+        assert_eq!(config.idle_threshold, 0.05);
+        assert_eq!(config.diff_interval, 1);
+        assert_eq!(config.invariant_interval, 10);
+        assert_eq!(config.invariant_sample_size, 256);
+        assert_eq!(config.thermostat_rate, 0.0);
+        assert_eq!(config.thermostat_band_degrees, 10.0);
+        assert_eq!(config.blocking_chance, 0.0);
+        assert!(!config.freeze_thaw);
+        assert!(config.freeze_thaw_rock_to_sand);
+        assert!(!config.aurora);
+        assert_eq!(config.aurora_chance, 0.005);
+        assert!(!config.dust);
+        assert_eq!(config.dust_eruption_chance, 0.002);
+        assert!(!config.ocean_circulation);
+        assert_eq!(config.ocean_heat_transport, 2.0);
+        assert!(!config.glaciers);
+        assert!(config.glacier_carving);
+        assert!(!config.sea_level);
+        assert_eq!(config.sea_level_ice_factor, 0.02);
+        assert!(!config.waves);
+        assert_eq!(config.warmup_ticks, 0);
+        assert_eq!(config.checkpoint_ticks, 0);
+        assert!(!config.replay_log);
+        assert!(!config.event_log);
+        assert!(!config.page_cold_layers);
+        assert!(!config.erosion);
+        assert!(config.erosion_landslides);
+        assert!(config.erosion_sediment);
+        assert_eq!(config.narration_interval, 0);
+        assert_eq!(config.narration_ticks(), 360);
+        assert_eq!(config.profile_sample_interval, 0);
+        assert_eq!(config.profile_output, "./rule-profile.folded");
+    }
+
+    #[test]
+    fn frozen_tiles_parsed() {
+        let config =
+            SimulationConfig::from_toml_str("frozen_tiles = [3, 1, 4]", &test_path()).unwrap();
+        assert_eq!(config.frozen_tiles, vec![3, 1, 4]);
+    }
+
+    #[test]
+    fn load_failures_are_config_errors() {
+        let missing = SimulationConfig::from_file(Path::new("/nonexistent/config.toml")).unwrap_err();
+        assert!(matches!(missing, WorldgroundError::ConfigRead { .. }), "{:?}", missing);
+        assert!(std::error::Error::source(&missing).is_some());
+        let malformed = SimulationConfig::from_toml_str("tick_rate_hz = ", &test_path()).unwrap_err();
+        assert!(matches!(malformed, WorldgroundError::ConfigToml { .. }), "{:?}", malformed);
+        let invalid = SimulationConfig::from_toml_str("tick_rate_hz = -1.0", &test_path()).unwrap_err();
+        assert!(matches!(invalid, WorldgroundError::Config(_)), "{:?}", invalid);
     }
 
     #[test]
     fn invalid_tick_rate_rejected() {
-        let err = SimulationConfig::from_toml_str("tick_rate_hz = -1.0", &test_path()).unwrap_err();
+        let err = SimulationConfig::from_toml_str("tick_rate_hz = -1.0", &test_path()).unwrap_err().to_string();
         assert!(err.contains("tick_rate_hz"));
         assert!(err.contains("> 0.0"));
     }
@@ -198,14 +910,14 @@ mod tests {
     #[test]
     fn invalid_snapshot_interval_rejected() {
         let err =
-            SimulationConfig::from_toml_str("snapshot_interval = 0", &test_path()).unwrap_err();
+            SimulationConfig::from_toml_str("snapshot_interval = 0", &test_path()).unwrap_err().to_string();
         assert!(err.contains("snapshot_interval"));
     }
 
     #[test]
     fn invalid_websocket_port_rejected() {
         let err =
-            SimulationConfig::from_toml_str("websocket_port = 80", &test_path()).unwrap_err();
+            SimulationConfig::from_toml_str("websocket_port = 80", &test_path()).unwrap_err().to_string();
         assert!(err.contains("websocket_port"));
         assert!(err.contains("1024-65535"));
     }
@@ -213,21 +925,191 @@ mod tests {
     #[test]
     fn invalid_log_level_rejected() {
         let err =
-            SimulationConfig::from_toml_str(r#"log_level = "verbose""#, &test_path()).unwrap_err();
+            SimulationConfig::from_toml_str(r#"log_level = "verbose""#, &test_path()).unwrap_err().to_string();
         assert!(err.contains("log_level"));
     }
 
     #[test]
     fn invalid_season_length_rejected() {
         let err =
-            SimulationConfig::from_toml_str("season_length = 0", &test_path()).unwrap_err();
+            SimulationConfig::from_toml_str("season_length = 0", &test_path()).unwrap_err().to_string();
         assert!(err.contains("season_length"));
     }
 
+    #[test]
+    fn invalid_rule_batch_size_rejected() {
+        for toml in ["rule_batch_size = 0", "rule_batch_size = 1000"] {
+            let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err().to_string();
+            assert!(err.contains("rule_batch_size"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn invalid_diff_interval_rejected() {
+        let err = SimulationConfig::from_toml_str("diff_interval = 0", &test_path()).unwrap_err().to_string();
+        assert!(err.contains("diff_interval must be > 0"), "{}", err);
+    }
+
+    #[test]
+    fn non_positive_idle_threshold_rejected() {
+        let err = SimulationConfig::from_toml_str("idle_threshold = 0.0", &test_path()).unwrap_err().to_string();
+        assert!(err.contains("idle_threshold must be > 0.0"), "{}", err);
+    }
+
+    #[test]
+    fn invalid_thermostat_settings_rejected() {
+        let err = SimulationConfig::from_toml_str(
+            "thermostat_rate = 1.5\nthermostat_band_degrees = 0.0",
+            &test_path(),
+        )
+        .unwrap_err().to_string();
+        assert!(err.contains("thermostat_rate"));
+        assert!(err.contains("thermostat_band_degrees"));
+    }
+
+    #[test]
+    fn inverted_health_limits_rejected() {
+        let err = SimulationConfig::from_toml_str("health = { degraded_rule_errors = 0.5, unhealthy_rule_errors = 0.2 }", &test_path())
+            .unwrap_err().to_string();
+        assert!(err.contains("degraded_rule_errors <= unhealthy_rule_errors"));
+        let err = SimulationConfig::from_toml_str("health = { degraded_snapshot_age = 0.5 }", &test_path()).unwrap_err().to_string();
+        assert!(err.contains("1.0 <= degraded_snapshot_age"));
+        assert!(SimulationConfig::from_toml_str("health = { stale_ticks = 3 }", &test_path()).is_err());
+    }
+
+    #[test]
+    fn unknown_unit_rejected() {
+        let err = SimulationConfig::from_toml_str(r#"temperature_unit = "rankine""#, &test_path()).unwrap_err().to_string();
+        assert!(err.contains("rankine"));
+    }
+
+    #[test]
+    fn invalid_compression_level_rejected() {
+        let err = SimulationConfig::from_toml_str("websocket_compression_level = 10", &test_path())
+            .unwrap_err().to_string();
+        assert!(err.contains("websocket_compression_level"));
+    }
+
+    #[test]
+    fn ping_timeout_must_exceed_interval() {
+        let err = SimulationConfig::from_toml_str(
+            "websocket_ping_interval_secs = 30\nwebsocket_ping_timeout_secs = 30",
+            &test_path(),
+        )
+        .unwrap_err().to_string();
+        assert!(err.contains("websocket_ping_timeout_secs"));
+        // Disabling pings makes the timeout irrelevant
+        assert!(SimulationConfig::from_toml_str(
+            "websocket_ping_interval_secs = 0\nwebsocket_ping_timeout_secs = 0",
+            &test_path(),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn profiling_needs_an_output_file() {
+        let err = SimulationConfig::from_toml_str(
+            "profile_sample_interval = 16\nprofile_output = \"\"",
+            &test_path(),
+        )
+        .unwrap_err().to_string();
+        assert!(err.contains("profile_output"));
+        // Without profiling the output is unused
+        assert!(SimulationConfig::from_toml_str("profile_output = \"\"", &test_path()).is_ok());
+    }
+
+    #[test]
+    fn invalid_aurora_chance_rejected() {
+        let err =
+            SimulationConfig::from_toml_str("aurora_chance = 2.0", &test_path()).unwrap_err().to_string();
+        assert!(err.contains("aurora_chance"));
+        let err =
+            SimulationConfig::from_toml_str("dust_eruption_chance = -0.1", &test_path()).unwrap_err().to_string();
+        assert!(err.contains("dust_eruption_chance"));
+        let err =
+            SimulationConfig::from_toml_str("blocking_chance = 1.5", &test_path()).unwrap_err().to_string();
+        assert!(err.contains("blocking_chance"));
+    }
+
+    #[test]
+    fn rule_limits_parsed_and_checked() {
+        let toml = "rule_max_operations = 50000\n[rule_limits.terrain]\nmax_operations = 500000\ntimeout_ms = 50";
+        let config = SimulationConfig::from_toml_str(toml, &test_path()).unwrap();
+        assert_eq!(config.rule_max_operations, 50_000);
+        assert_eq!(
+            config.rule_limits["terrain"],
+            PhaseRuleLimits { max_operations: Some(500_000), timeout_ms: Some(50) }
+        );
+        for toml in [
+            "rule_max_operations = 0",
+            "rule_limits = { terain = { timeout_ms = 50 } }",
+            "rule_limits = { weather = { max_operations = 0 } }",
+        ] {
+            let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err().to_string();
+            assert!(err.contains("rule_"), "{}", err);
+        }
+        let err = SimulationConfig::from_toml_str("rule_limits = { weather = { timeout = 5 } }", &test_path())
+            .unwrap_err().to_string();
+        assert!(err.contains("timeout"), "{}", err);
+    }
+
+    #[test]
+    fn rule_pack_must_be_a_directory_name() {
+        for pack in ["", "..", "packs/arid", "C:\\\\rules"] {
+            let err = SimulationConfig::from_toml_str(&format!("rule_pack = \"{}\"", pack), &test_path()).unwrap_err().to_string();
+            assert!(err.contains("rule_pack"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn phase_periods_parsed_and_checked() {
+        let config =
+            SimulationConfig::from_toml_str("phase_periods = { terrain = 10, resources = 5 }", &test_path()).unwrap();
+        assert_eq!(config.phase_periods["terrain"], 10);
+        assert_eq!(config.phase_periods["resources"], 5);
+        for toml in ["phase_periods = { weather = 2 }", "phase_periods = { terrain = 0 }"] {
+            let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err().to_string();
+            assert!(err.contains("phase_periods"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn page_cold_layers_excludes_checkpoints_and_replay() {
+        assert!(SimulationConfig::from_toml_str("page_cold_layers = true", &test_path()).unwrap().page_cold_layers);
+        for toml in ["page_cold_layers = true\ncheckpoint_ticks = 5", "page_cold_layers = true\nreplay_log = true"] {
+            let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err().to_string();
+            assert!(err.contains("page_cold_layers"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn active_region_parsed_and_checked() {
+        let toml = "active_region = { lat = 45.0, lon = -20.0, radius_deg = 30.0 }";
+        let config = SimulationConfig::from_toml_str(toml, &test_path()).unwrap();
+        assert_eq!(config.active_region, Some(ActiveRegion { lat: 45.0, lon: -20.0, radius_deg: 30.0 }));
+        let toml = "active_region = { lat = 95.0, lon = 0.0, radius_deg = 30.0 }";
+        let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err().to_string();
+        assert!(err.contains("active_region"));
+    }
+
+    #[test]
+    fn invalid_ocean_heat_transport_rejected() {
+        let err = SimulationConfig::from_toml_str("ocean_heat_transport = -1.0", &test_path())
+            .unwrap_err().to_string();
+        assert!(err.contains("ocean_heat_transport"));
+    }
+
+    #[test]
+    fn invalid_sea_level_ice_factor_rejected() {
+        let err = SimulationConfig::from_toml_str("sea_level_ice_factor = 2.0", &test_path())
+            .unwrap_err().to_string();
+        assert!(err.contains("sea_level_ice_factor"));
+    }
+
     #[test]
     fn multiple_errors_reported_together() {
         let toml = "tick_rate_hz = 0.0\nsnapshot_interval = 0\nseason_length = 0";
-        let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err();
+        let err = SimulationConfig::from_toml_str(toml, &test_path()).unwrap_err().to_string();
         assert!(err.contains("tick_rate_hz"));
         assert!(err.contains("snapshot_interval"));
         assert!(err.contains("season_length"));
@@ -236,7 +1118,7 @@ mod tests {
     #[test]
     fn malformed_toml_includes_source_path() {
         let err =
-            SimulationConfig::from_toml_str("tick_rate_hz = [invalid", &test_path()).unwrap_err();
+            SimulationConfig::from_toml_str("tick_rate_hz = [invalid", &test_path()).unwrap_err().to_string();
         assert!(err.contains("test-config.toml"));
     }
 
@@ -251,7 +1133,7 @@ mod tests {
 
     #[test]
     fn from_file_missing_file_error() {
-        let err = SimulationConfig::from_file(Path::new("/nonexistent/config.toml")).unwrap_err();
+        let err = SimulationConfig::from_file(Path::new("/nonexistent/config.toml")).unwrap_err().to_string();
         assert!(err.contains("Cannot read"));
     }
 }
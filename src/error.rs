@@ -0,0 +1,84 @@
+//! The error type of the library's entry points.
+//!
+//! Loading configs and rules, running commands and starting the server all
+//! fail with a `WorldgroundError`, so callers can tell a bad config from a
+//! broken rule script or an unreadable snapshot without parsing messages.
+//! Each variant displays the message that would be shown to a user; those
+//! wrapping a lower-level error keep it as their `source()`.
+
+use std::path::PathBuf;
+
+use crate::persistence::SnapshotError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorldgroundError {
+    /// A config file or command option that holds invalid values
+    #[error("{0}")]
+    Config(String),
+    /// A config file that can't be read
+    #[error("Cannot read {}: {source}", path.display())]
+    ConfigRead {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// A config file that isn't valid TOML, or doesn't fit its schema
+    #[error("Invalid TOML in {}: {source}", path.display())]
+    ConfigToml {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    /// A heightmap image that can't be read or decoded
+    #[error("Cannot read heightmap {}: {source}", path.display())]
+    Heightmap {
+        path: PathBuf,
+        #[source]
+        source: image::ImageError,
+    },
+    /// Rule scripts that can't be found, read or checked
+    #[error("{0}")]
+    Rules(String),
+    /// A rule script that doesn't compile
+    #[error("Syntax error in {}: {source}", path.display())]
+    RuleSyntax {
+        path: PathBuf,
+        #[source]
+        source: rhai::ParseError,
+    },
+    /// Logs, reports or the world registry that can't be saved or loaded
+    #[error("{0}")]
+    Persistence(String),
+    /// A file or directory that can't be written
+    #[error("Cannot write {}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// A snapshot that can't be saved or loaded
+    #[error(transparent)]
+    Snapshot(#[from] SnapshotError),
+    /// A file or socket that can't be read or written
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A value that can't be encoded as JSON
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// A WebSocket connection that fails
+    #[error(transparent)]
+    WebSocket(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+    /// The server that can't start, another server that can't be reached, or
+    /// a request it can't carry out
+    #[error("{0}")]
+    Server(String),
+    /// A world that can't be generated, run or checked
+    #[error("{0}")]
+    Simulation(String),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for WorldgroundError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        WorldgroundError::WebSocket(Box::new(e))
+    }
+}
@@ -1,6 +1,9 @@
 pub mod cli;
 pub mod config;
+pub mod error;
 pub mod persistence;
 pub mod server;
 pub mod simulation;
 pub mod world;
+
+pub use error::WorldgroundError;
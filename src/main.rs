@@ -1,22 +1,26 @@
-use clap::{Parser, Subcommand};
-use std::path::Path;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
 use tracing::error;
 use tracing_subscriber::EnvFilter;
 
 use worldground::cli::commands;
 use worldground::config::generation::GenerationParams;
+use worldground::config::registry::{RegisteredWorld, WorldRegistry, REGISTRY_DIR, REGISTRY_FILE};
 use worldground::config::simulation::SimulationConfig;
-use worldground::persistence;
-use worldground::world::generation::{generate_world, print_world_summary};
+use worldground::persistence::{self, SnapshotSort, TickRange};
+use worldground::WorldgroundError;
+use worldground::simulation::builtin_rules::is_builtin;
+use worldground::world::generation::{parse_range, print_world_preview, print_world_summary, try_generate_world, GridSize};
+use worldground::world::layers::{parse_layer, DiffLayer};
 
 #[derive(Parser)]
 #[command(name = "worldground")]
 #[command(about = "A perpetual world simulation engine with configurable terrain evolution rules")]
 #[command(version)]
 struct Cli {
-    /// Path to the configuration file
-    #[arg(short, long, default_value = "config.toml")]
-    config: String,
+    /// Path to the configuration file [default: the registered world's config, else config.toml]
+    #[arg(short, long)]
+    config: Option<String>,
 
     #[command(subcommand)]
     command: Commands,
@@ -26,24 +30,34 @@ struct Cli {
 enum Commands {
     /// Generate a new world from procedural parameters
     Generate {
-        /// Path to world generation config file
-        #[arg(short, long, default_value = "worldgen.toml")]
-        worldgen: String,
+        /// Path to world generation config file [default: the registered world's, else worldgen.toml]
+        #[arg(short, long)]
+        worldgen: Option<String>,
+
+        /// Output snapshot directory [default: the registered world's, else snapshots]
+        #[arg(short, long)]
+        output: Option<String>,
 
-        /// Output snapshot directory
-        #[arg(short, long, default_value = "snapshots")]
-        output: String,
+        /// Print the world's size, memory and tick cost without generating it
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Start the simulation server
     Run {
-        /// Path to a snapshot file to resume from (omit to generate a fresh world)
+        /// Path to a snapshot file to resume from (omit to resume the registered
+        /// world's latest snapshot, or generate a fresh world)
         #[arg(short, long)]
         world: Option<String>,
 
-        /// Path to world generation config file (used when generating fresh)
-        #[arg(long, default_value = "worldgen.toml")]
-        worldgen: String,
+        /// Take over the world from a running server (HOST:PORT) for a zero-downtime upgrade
+        #[arg(long, conflicts_with = "world")]
+        handoff_from: Option<String>,
+
+        /// Path to world generation config file, used when generating fresh
+        /// [default: the registered world's, else worldgen.toml]
+        #[arg(long)]
+        worldgen: Option<String>,
 
         /// Override tick rate (Hz) from config
         #[arg(long)]
@@ -56,6 +70,20 @@ enum Commands {
         /// Override log level from config
         #[arg(long)]
         log_level: Option<String>,
+
+        /// Run the first N ticks unpaced and without diffs before settling to the tick rate
+        #[arg(long)]
+        warmup_ticks: Option<u32>,
+
+        /// Instead of simulating, play back this directory's snapshots in tick
+        /// order, one per tick at the tick rate
+        #[arg(long, conflicts_with_all = ["world", "handoff_from", "warmup_ticks"])]
+        replay_dir: Option<PathBuf>,
+
+        /// Rule directory, overriding the config's rule_directory; `builtin`
+        /// runs the reference rules compiled into the binary
+        #[arg(long)]
+        rules: Option<String>,
     },
 
     /// Inspect world or tile state
@@ -74,15 +102,256 @@ enum Commands {
         #[command(subcommand)]
         action: SnapshotAction,
     },
+
+    /// Check config.toml, worldgen.toml, the palette and the rules, reporting every problem
+    Validate {
+        /// Path to world generation config file [default: the registered world's, else worldgen.toml]
+        #[arg(short, long)]
+        worldgen: Option<String>,
+    },
+
+    /// Work with rule scripts
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+
+    /// Manage the registry of known worlds
+    Worlds {
+        #[command(subcommand)]
+        action: WorldsAction,
+    },
+
+    /// Re-run ticks from a snapshot and check them against the replay log
+    Replay {
+        /// Tick to replay to [default: the last one in the log]
+        #[arg(long)]
+        to: Option<u64>,
+
+        /// Snapshot to start from [default: the newest before --to]
+        #[arg(long)]
+        from: Option<PathBuf>,
+
+        /// Replay log [default: replay.jsonl in the snapshot directory]
+        #[arg(long)]
+        log: Option<PathBuf>,
+
+        /// Rule directory, overriding the config's rule_directory, e.g. to
+        /// bisect a rule change; `builtin` runs the built-in rules
+        #[arg(long)]
+        rules: Option<String>,
+    },
+
+    /// Describe in plain English how the world changed between snapshots
+    Narrate {
+        /// Compare against the newest snapshot at or before this tick [default: the oldest]
+        #[arg(long)]
+        since: Option<u64>,
+    },
+
+    /// Export derived data from the latest snapshot
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportAction {
+    /// Per-tile mean wind power density and insolation (CSV)
+    Energy {
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Write JSON instead of CSV
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Some layers of every tile, as a layer snapshot
+    Layers {
+        /// Layer to export: weather, conditions, biome, resources or scratch (repeatable)
+        #[arg(short, long = "layer", required = true, value_parser = parse_layer)]
+        layers: Vec<DiffLayer>,
+
+        /// Write to this file (stdout only with --json)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Write JSON instead of a binary layer snapshot
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorldsAction {
+    /// List registered worlds (* marks the one in use)
+    List,
+
+    /// Use a registered world's config, snapshots and rules by default
+    Use {
+        /// Registered world name
+        name: String,
+    },
+
+    /// Register a world (replaces an existing entry with the same name)
+    Add {
+        /// Name to register the world under
+        name: String,
+
+        /// Directory holding the world's snapshots
+        #[arg(long)]
+        snapshots: String,
+
+        /// Rule directory (or `builtin`), overriding the config's rule_directory
+        #[arg(long)]
+        rules: Option<String>,
+
+        /// Simulation config file
+        #[arg(long)]
+        config: Option<String>,
+
+        /// World generation config file
+        #[arg(long)]
+        worldgen: Option<String>,
+
+        /// Create a project-level registry in ./.worldground if none exists
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Forget a registered world (its files are left alone)
+    Remove {
+        /// Registered world name
+        name: String,
+    },
+
+    /// Copy a region of one snapshot into another world of the same topology
+    Splice {
+        /// Snapshot to copy the region from
+        #[arg(long)]
+        from: PathBuf,
+
+        /// Snapshot of the world to copy it into
+        #[arg(long)]
+        into: PathBuf,
+
+        /// Tile IDs to copy, comma-separated
+        #[arg(long, value_delimiter = ',', required_unless_present = "lat", conflicts_with = "lat")]
+        tiles: Vec<u32>,
+
+        /// Latitude range of the region to copy: MIN,MAX degrees
+        #[arg(long, value_parser = parse_range, allow_hyphen_values = true, requires = "lon")]
+        lat: Option<(f64, f64)>,
+
+        /// Longitude range of the region to copy: MIN,MAX degrees (MIN > MAX wraps across 180)
+        #[arg(long, value_parser = parse_range, allow_hyphen_values = true, requires = "lat")]
+        lon: Option<(f64, f64)>,
+
+        /// Shift the region this many columns east (negative: west); flat worlds only
+        #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+        cols: i32,
+
+        /// Shift the region this many rows south (negative: north); flat worlds only
+        #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+        rows: i32,
+
+        /// Directory to save the spliced world to [default: the --into snapshot's]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesAction {
+    /// Compile rules and report deprecated field names
+    Check {
+        /// Rule directory (defaults to rule_directory from config, or its rule_pack)
+        #[arg(short, long)]
+        dir: Option<String>,
+    },
+
+    /// Check that per-tile rand() streams in the latest snapshot are uncorrelated
+    Rng {
+        /// Ticks to replay
+        #[arg(long, default_value_t = 100)]
+        ticks: u32,
+
+        /// rand() draws per tile, phase and tick
+        #[arg(long, default_value_t = 4)]
+        draws: u32,
+    },
+
+    /// Run a standard world with the current rules and compare its end statistics to a baseline
+    Regress {
+        /// Baseline statistics file (JSON)
+        #[arg(long)]
+        baseline: PathBuf,
+
+        /// Ticks to run [default: the baseline's, or 100 for a new baseline]
+        #[arg(long)]
+        ticks: Option<u32>,
+
+        /// Record the results as the new baseline instead of comparing
+        #[arg(long)]
+        update: bool,
+    },
+
+    /// Run rules on synthetic tiles from TOML fixtures and check the results
+    Test {
+        /// Fixture files or directories of them [default: <rule dir>/tests]
+        fixtures: Vec<PathBuf>,
+
+        /// Rule directory (defaults to rule_directory from config, or its rule_pack)
+        #[arg(short, long)]
+        dir: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SnapshotSortArg {
+    Age,
+    Tick,
+    Size,
 }
 
 #[derive(Subcommand)]
 enum SnapshotAction {
     /// List available snapshots
     List {
-        /// Snapshot directory
-        #[arg(short, long, default_value = "snapshots")]
-        dir: String,
+        /// Snapshot directory [default: the registered world's, else snapshots]
+        #[arg(short, long, conflicts_with = "all")]
+        dir: Option<String>,
+
+        /// Search the snapshot directories of every registered world
+        #[arg(long)]
+        all: bool,
+
+        /// Order by age (newest first), tick or size (largest first)
+        #[arg(long, value_enum, default_value_t = SnapshotSortArg::Age)]
+        sort: SnapshotSortArg,
+
+        /// Only snapshots at or after this tick
+        #[arg(long)]
+        since: Option<u64>,
+
+        /// Only snapshots at or before this tick
+        #[arg(long)]
+        until: Option<u64>,
+
+        /// Only snapshots of this world (reads snapshot headers)
+        #[arg(long)]
+        world_id: Option<uuid::Uuid>,
+
+        /// Also show each world's name, ID, tile count and season (reads only snapshot headers)
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Print a JSON array instead of a table
+        #[arg(long)]
+        json: bool,
     },
 
     /// Restore and display a world from a snapshot file
@@ -90,6 +359,16 @@ enum SnapshotAction {
         /// Path to the snapshot file
         file: String,
     },
+
+    /// Validate a snapshot's neighbor graph
+    Doctor {
+        /// Path to the snapshot file
+        file: String,
+
+        /// Fix asymmetric, duplicate, and out-of-range links and save a repaired snapshot
+        #[arg(long)]
+        repair: bool,
+    },
 }
 
 #[tokio::main]
@@ -103,8 +382,41 @@ async fn main() {
 
     let cli = Cli::parse();
 
+    let registry_path = WorldRegistry::default_path();
+    let registry = match registry_path.as_deref().map(WorldRegistry::load).transpose() {
+        Ok(r) => r.unwrap_or_default(),
+        Err(e) => {
+            error!("Error loading world registry: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let current = registry.current_world().map(|(_, w)| w.clone());
+    let config_path = cli
+        .config
+        .clone()
+        .or_else(|| current.as_ref().and_then(|w| w.config.clone()))
+        .unwrap_or_else(|| "config.toml".to_string());
+    // Config with the registered world's snapshot and rule directories applied
+    let load_config = || -> Result<SimulationConfig, WorldgroundError> {
+        let mut config = SimulationConfig::from_file(Path::new(&config_path))?;
+        if let Some(world) = &current {
+            world.apply_to(&mut config);
+        }
+        Ok(config)
+    };
+    let default_worldgen = || {
+        current
+            .as_ref()
+            .and_then(|w| w.worldgen.clone())
+            .unwrap_or_else(|| "worldgen.toml".to_string())
+    };
+
     match cli.command {
-        Commands::Generate { worldgen, output } => {
+        Commands::Generate { worldgen, output, dry_run } => {
+            let worldgen = worldgen.unwrap_or_else(default_worldgen);
+            let output = output
+                .or_else(|| current.as_ref().map(|w| w.snapshot_directory.clone()))
+                .unwrap_or_else(|| "snapshots".to_string());
             let params = match GenerationParams::from_file(Path::new(&worldgen)) {
                 Ok(p) => p,
                 Err(e) => {
@@ -112,8 +424,18 @@ async fn main() {
                     std::process::exit(1);
                 }
             };
-            println!("Generating world from {}...", worldgen);
-            let world = generate_world(&params);
+            if dry_run {
+                print_world_preview(&params);
+                return;
+            }
+            println!("Generating {} tiles from {}...", GridSize::of(&params).tile_count(), worldgen);
+            let world = match try_generate_world(&params) {
+                Ok(world) => world,
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            };
             print_world_summary(&world);
 
             let snapshot_dir = Path::new(&output);
@@ -126,8 +448,8 @@ async fn main() {
             }
         }
 
-        Commands::Run { world, worldgen, tick_rate, port, log_level } => {
-            let mut config = match SimulationConfig::from_file(Path::new(&cli.config)) {
+        Commands::Run { world, handoff_from, worldgen, tick_rate, port, log_level, warmup_ticks, replay_dir, rules } => {
+            let mut config = match load_config() {
                 Ok(c) => c,
                 Err(e) => {
                     error!("Error loading config: {}", e);
@@ -145,12 +467,36 @@ async fn main() {
             if let Some(level) = log_level {
                 config.log_level = level;
             }
+            if let Some(ticks) = warmup_ticks {
+                config.warmup_ticks = ticks;
+            }
+            if let Some(rules) = rules {
+                config.rule_directory = rules;
+            }
+
+            if let Some(dir) = replay_dir {
+                if let Err(e) = commands::replay_snapshots(&config, &dir).await {
+                    error!("Replay error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            }
 
-            // Determine world source: explicit snapshot path or generate fresh
-            let initial_world = if let Some(ref path) = world {
+            // Determine world source: live handoff, explicit snapshot path, the registered
+            // world's latest snapshot, or generate fresh
+            let latest_registered = current.as_ref().and_then(|w| {
+                persistence::list_snapshots(Path::new(&w.snapshot_directory))
+                    .ok()
+                    .and_then(|s| s.into_iter().next())
+            });
+            let initial_world = if let Some(addr) = handoff_from {
+                commands::WorldSource::Handoff(addr)
+            } else if let Some(ref path) = world {
                 commands::WorldSource::Snapshot(path.clone())
+            } else if let Some(latest) = latest_registered {
+                commands::WorldSource::Snapshot(latest.path.display().to_string())
             } else {
-                commands::WorldSource::Generate(worldgen)
+                commands::WorldSource::Generate(worldgen.unwrap_or_else(default_worldgen))
             };
 
             if let Err(e) = commands::run_simulation(&config, initial_world).await {
@@ -160,7 +506,7 @@ async fn main() {
         }
 
         Commands::Inspect { tile, world } => {
-            let config = match SimulationConfig::from_file(Path::new(&cli.config)) {
+            let config = match load_config() {
                 Ok(c) => c,
                 Err(e) => {
                     error!("Error loading config: {}", e);
@@ -175,41 +521,33 @@ async fn main() {
         }
 
         Commands::Snapshots { action } => match action {
-            SnapshotAction::List { dir } => {
-                let snapshot_dir = Path::new(&dir);
-                match persistence::list_snapshots(snapshot_dir) {
-                    Ok(snapshots) => {
-                        if snapshots.is_empty() {
-                            println!("No snapshots found in {}", snapshot_dir.display());
-                        } else {
-                            println!(
-                                "{:<40} {:>8} {:>12}",
-                                "File", "Tick", "Size"
-                            );
-                            println!("{}", "-".repeat(62));
-                            for s in &snapshots {
-                                let name = s
-                                    .path
-                                    .file_name()
-                                    .and_then(|n| n.to_str())
-                                    .unwrap_or("?");
-                                let size_kb = s.file_size / 1024;
-                                println!(
-                                    "{:<40} {:>8} {:>9} KB",
-                                    name, s.tick_count, size_kb
-                                );
-                            }
-                            println!(
-                                "\n{} snapshot(s) in {}",
-                                snapshots.len(),
-                                snapshot_dir.display()
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error listing snapshots: {}", e);
-                        std::process::exit(1);
-                    }
+            SnapshotAction::List { dir, all, sort, since, until, world_id, verbose, json } => {
+                let dirs: Vec<(Option<String>, PathBuf)> = if all {
+                    registry
+                        .worlds
+                        .iter()
+                        .map(|(name, w)| (Some(name.clone()), PathBuf::from(&w.snapshot_directory)))
+                        .collect()
+                } else {
+                    let dir = dir
+                        .or_else(|| current.as_ref().map(|w| w.snapshot_directory.clone()))
+                        .unwrap_or_else(|| "snapshots".to_string());
+                    vec![(None, PathBuf::from(dir))]
+                };
+                let options = commands::SnapshotListOptions {
+                    sort: match sort {
+                        SnapshotSortArg::Age => SnapshotSort::Age,
+                        SnapshotSortArg::Tick => SnapshotSort::Tick,
+                        SnapshotSortArg::Size => SnapshotSort::Size,
+                    },
+                    ticks: TickRange { since, until },
+                    world_id,
+                    verbose,
+                    json,
+                };
+                if let Err(e) = commands::list_snapshots(&dirs, &options) {
+                    error!("{}", e);
+                    std::process::exit(1);
                 }
             }
             SnapshotAction::Restore { file } => {
@@ -225,6 +563,242 @@ async fn main() {
                     }
                 }
             }
+            SnapshotAction::Doctor { file, repair } => {
+                match commands::doctor_snapshot(Path::new(&file), repair) {
+                    Ok(0) => {}
+                    Ok(_) => std::process::exit(1),
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+
+        Commands::Validate { worldgen } => {
+            let worldgen = worldgen.unwrap_or_else(default_worldgen);
+            match commands::validate(Path::new(&config_path), load_config(), Path::new(&worldgen)) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Rules { action } => match action {
+            RulesAction::Check { dir } => {
+                let rule_dir = match dir {
+                    Some(d) => d,
+                    None => match load_config() {
+                        Ok(c) => c.rule_pack_directory().to_string_lossy().into_owned(),
+                        Err(e) => {
+                            error!("Error loading config: {}", e);
+                            std::process::exit(1);
+                        }
+                    },
+                };
+
+                if let Err(e) = commands::check_rules(Path::new(&rule_dir)) {
+                    error!("Rule check failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            RulesAction::Rng { ticks, draws } => {
+                let config = match load_config() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        error!("Error loading config: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                match commands::check_rng_streams(&config, ticks, draws) {
+                    Ok(true) => {}
+                    Ok(false) => std::process::exit(1),
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            RulesAction::Regress { baseline, ticks, update } => {
+                let config = match load_config() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        error!("Error loading config: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                match commands::regress_rules(&config, &baseline, ticks, update) {
+                    Ok(true) => {}
+                    Ok(false) => std::process::exit(1),
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            RulesAction::Test { fixtures, dir } => {
+                let rule_dir = match dir {
+                    Some(d) => d,
+                    None => match load_config() {
+                        Ok(c) => c.rule_pack_directory().to_string_lossy().into_owned(),
+                        Err(e) => {
+                            error!("Error loading config: {}", e);
+                            std::process::exit(1);
+                        }
+                    },
+                };
+
+                match commands::test_rules(Path::new(&rule_dir), &fixtures) {
+                    Ok(true) => {}
+                    Ok(false) => std::process::exit(1),
+                    Err(e) => {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
         },
+
+        Commands::Replay { to, from, log, rules } => {
+            let mut config = match load_config() {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Error loading config: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if let Some(rules) = rules {
+                config.rule_directory = rules;
+            }
+
+            match commands::replay_ticks(&config, from.as_deref(), to, log.as_deref()) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Narrate { since } => {
+            let config = match load_config() {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Error loading config: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            if let Err(e) = commands::narrate_snapshots(&config, since) {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Export { action } => match action {
+            ExportAction::Energy { output, json } => {
+                let config = match load_config() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        error!("Error loading config: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                if let Err(e) = commands::export_energy(&config, output.as_deref(), json) {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            ExportAction::Layers { layers, output, json } => {
+                let config = match load_config() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        error!("Error loading config: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                if let Err(e) = commands::export_layers(&config, &layers, output.as_deref(), json) {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+
+        Commands::Worlds { action: WorldsAction::Splice { from, into, tiles, lat, lon, cols, rows, output } } => {
+            let region = match (lat, lon) {
+                (Some(lat), Some(lon)) => commands::SpliceRegion::Box { lat, lon },
+                _ => commands::SpliceRegion::Tiles(tiles),
+            };
+            if let Err(e) = commands::splice_worlds(&from, &into, &region, (cols, rows), output.as_deref()) {
+                error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Worlds { action } => {
+            let mut registry = registry;
+            let path = match (&action, registry_path) {
+                (WorldsAction::Add { project: true, .. }, _) => {
+                    Path::new(REGISTRY_DIR).join(REGISTRY_FILE)
+                }
+                (_, Some(path)) => path,
+                (_, None) => {
+                    error!("Cannot locate a world registry: no ./{} and no home directory", REGISTRY_DIR);
+                    std::process::exit(1);
+                }
+            };
+            if let WorldsAction::Add { project: true, .. } = action {
+                registry = WorldRegistry::load(&path).unwrap_or_else(|e| {
+                    error!("Error loading world registry: {}", e);
+                    std::process::exit(1);
+                });
+            }
+
+            let result = match action {
+                WorldsAction::List => {
+                    commands::list_worlds(&registry, &path);
+                    Ok(false)
+                }
+                WorldsAction::Use { name } => registry.use_world(&name).map(|()| {
+                    println!("Using world '{}'", name);
+                    true
+                }),
+                WorldsAction::Add { name, snapshots, rules, config, worldgen, project: _ } => {
+                    let absolute = |p: String| {
+                        std::path::absolute(&p).map_or(p, |a| a.display().to_string())
+                    };
+                    let world = RegisteredWorld {
+                        snapshot_directory: absolute(snapshots),
+                        rule_directory: rules.map(|r| if is_builtin(Path::new(&r)) { r } else { absolute(r) }),
+                        config: config.map(absolute),
+                        worldgen: worldgen.map(absolute),
+                    };
+                    registry.add(&name, world).map(|()| {
+                        println!("Registered world '{}' in {}", name, path.display());
+                        true
+                    })
+                }
+                WorldsAction::Remove { name } => registry.remove(&name).map(|_| {
+                    println!("Removed world '{}' (files left in place)", name);
+                    true
+                }),
+                WorldsAction::Splice { .. } => unreachable!("splice leaves the registry alone"),
+            };
+            match result.and_then(|changed| if changed { registry.save(&path) } else { Ok(()) }) {
+                Ok(()) => {}
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
     }
 }
@@ -1,6 +1,9 @@
 pub mod snapshot;
+mod v1;
 
 pub use snapshot::{
-    list_snapshots, load_latest_valid_snapshot, load_snapshot, prune_snapshots, save_snapshot,
-    SnapshotError, SnapshotMetadata,
+    list_snapshots, load_latest_valid_snapshot, load_layer_snapshot, load_snapshot,
+    open_latest_valid_snapshot, prune_snapshots, save_layer_snapshot, save_snapshot,
+    sort_snapshots, LayerSnapshot, SnapshotError, SnapshotMetadata, SnapshotReader, SnapshotSort,
+    TickRange,
 };
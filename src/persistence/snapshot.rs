@@ -1,10 +1,300 @@
+use bincode::Options;
+use memmap2::Mmap;
+use rayon::prelude::*;
+use serde::{de, Deserialize, Serialize};
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::warn;
 
-use crate::world::World;
+use super::v1::WorldV1;
+use crate::world::layers::DiffLayer;
+use crate::world::{LayerArrays, Tile, World};
+
+/// Magic bytes opening a versioned snapshot, followed by its format version.
+const VERSIONED_MAGIC: &[u8; 8] = b"WGSNAPV\0";
+/// Magic bytes opening an uncompressed chunked snapshot (format 2), written
+/// by older versions. Files with neither magic are single-blob snapshots
+/// (format 1), decoded with the frozen layout in `v1`. Both still load.
+const CHUNKED_MAGIC: &[u8; 8] = b"WGSNAP2\0";
+/// Format written by this version: chunked, with each chunk zstd-compressed.
+pub const FORMAT_VERSION: u16 = 3;
+/// Magic bytes opening a layer snapshot, followed by its format version.
+const LAYER_MAGIC: &[u8; 8] = b"WGLAYER\0";
+/// Layer snapshot format written by this version.
+pub const LAYER_FORMAT_VERSION: u16 = 1;
+/// Tiles per independently decodable chunk.
+const TILES_PER_CHUNK: usize = 4096;
+/// zstd level for tile chunks: most of the size win at a fraction of the time.
+const ZSTD_LEVEL: i32 = 3;
+/// Upper bound on a decompressed tile, so a corrupt chunk can't exhaust memory.
+const MAX_TILE_BYTES: u64 = 1 << 16;
+
+/// bincode as `bincode::serialize` writes it, refusing to read more than
+/// `limit` bytes. Every decode goes through this, so a corrupt length
+/// prefix fails once it has read `limit` bytes rather than spinning on.
+fn bincode_limited(limit: u64) -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(limit)
+}
+
+/// Location of one chunk of tiles, relative to the end of the header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkIndex {
+    first_tile: u32,
+    tile_count: u32,
+    offset: u64,
+    len: u64,
+}
+
+/// World-level state (with an empty tile list) and the chunk table.
+///
+/// A snapshot is `VERSIONED_MAGIC`, the format version as a little-endian
+/// u16, the schema hash and the header length as little-endian u64s, the
+/// bincode header, then each chunk as a zstd-compressed bincode `Vec<Tile>`.
+/// Format 2 is `CHUNKED_MAGIC`, the header length and the bincode header,
+/// with the chunks uncompressed.
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotHeader {
+    world: World,
+    chunks: Vec<ChunkIndex>,
+}
+
+/// How the chunks of an opened snapshot are stored.
+#[derive(Debug, Clone, Copy)]
+struct ChunkEncoding {
+    compressed: bool,
+}
+
+/// A snapshot's decoded header, its chunk area and how the chunks are stored.
+struct Layout<'a> {
+    header: SnapshotHeader,
+    body: &'a [u8],
+    encoding: ChunkEncoding,
+}
+
+/// Hash of the world schema: the shape of `World` as its `Deserialize`
+/// impl reads it, tiles and generation parameters included. bincode isn't
+/// self-describing, so a snapshot written by a build with any other schema
+/// can't be read safely; the hash stored in each one says so up front.
+pub fn schema_hash() -> u64 {
+    static HASH: OnceLock<u64> = OnceLock::new();
+    *HASH.get_or_init(|| {
+        let mut shape = Vec::new();
+        // Only fails if a type rejects the sample values, which a test rules out
+        let _ = World::deserialize(SchemaTracer { shape: &mut shape, depth: 0 });
+        // FNV-1a, which unlike std's hasher is stable across Rust releases
+        shape.iter().flat_map(|s| s.bytes().chain([0])).fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    })
+}
+
+/// Sequences, maps and options nested deeper than this are traced empty.
+const MAX_SCHEMA_DEPTH: usize = 32;
+/// The sample string, which parses as a UUID, the one string type checked.
+const SAMPLE_STR: &str = "00000000-0000-0000-0000-000000000000";
+
+/// A deserializer that records the shape of what it deserializes: each
+/// struct's name and fields, each enum's variants, each scalar's kind. It
+/// follows sequences, maps and options into one element and enums into
+/// their first variant, so types reached only through them count too,
+/// whatever a sample value would hold.
+struct SchemaTracer<'a> {
+    shape: &'a mut Vec<String>,
+    depth: usize,
+}
+
+impl SchemaTracer<'_> {
+    fn record(&mut self, entry: String) {
+        self.shape.push(entry);
+    }
+
+    fn nested(&mut self, count: usize) -> Nested<'_> {
+        let remaining = if self.depth < MAX_SCHEMA_DEPTH { count } else { 0 };
+        Nested { shape: &mut *self.shape, depth: self.depth + 1, remaining }
+    }
+}
+
+macro_rules! trace_scalars {
+    ($($method:ident => $visit:ident($($value:expr)?)),* $(,)?) => {$(
+        fn $method<V: de::Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
+            self.record(stringify!($method).to_string());
+            visitor.$visit($($value)?)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for SchemaTracer<'_> {
+    type Error = de::value::Error;
+
+    trace_scalars! {
+        deserialize_any => visit_unit(),
+        deserialize_bool => visit_bool(false),
+        deserialize_i8 => visit_i8(0),
+        deserialize_i16 => visit_i16(0),
+        deserialize_i32 => visit_i32(0),
+        deserialize_i64 => visit_i64(0),
+        deserialize_u8 => visit_u8(0),
+        deserialize_u16 => visit_u16(0),
+        deserialize_u32 => visit_u32(0),
+        deserialize_u64 => visit_u64(0),
+        deserialize_f32 => visit_f32(0.0),
+        deserialize_f64 => visit_f64(0.0),
+        deserialize_char => visit_char('0'),
+        deserialize_str => visit_str(SAMPLE_STR),
+        deserialize_string => visit_str(SAMPLE_STR),
+        deserialize_bytes => visit_bytes(&[]),
+        deserialize_byte_buf => visit_bytes(&[]),
+        deserialize_unit => visit_unit(),
+        deserialize_identifier => visit_str(""),
+        deserialize_ignored_any => visit_unit(),
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.record("option".to_string());
+        if self.depth < MAX_SCHEMA_DEPTH {
+            visitor.visit_some(SchemaTracer { shape: self.shape, depth: self.depth + 1 })
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(mut self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        self.record(format!("unit {}", name));
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(mut self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        self.record(format!("newtype {}", name));
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.record("seq".to_string());
+        visitor.visit_seq(self.nested(1))
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.record(format!("tuple {}", len));
+        visitor.visit_seq(self.nested(len))
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.record(format!("tuple {} {}", name, len));
+        visitor.visit_seq(self.nested(len))
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.record("map".to_string());
+        visitor.visit_map(self.nested(1))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.record(format!("struct {} {}", name, fields.join(",")));
+        // Every field, however deep: structs don't nest without bound
+        visitor.visit_seq(Nested { shape: self.shape, depth: self.depth, remaining: fields.len() })
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.record(format!("enum {} {}", name, variants.join(",")));
+        visitor.visit_enum(self)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for SchemaTracer<'_> {
+    type Error = de::value::Error;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Self::Error> {
+        let variant = seed.deserialize(de::IntoDeserializer::<Self::Error>::into_deserializer(0_u32))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for SchemaTracer<'_> {
+    type Error = de::value::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+/// The elements, or map entries, of something being traced.
+struct Nested<'a> {
+    shape: &'a mut Vec<String>,
+    depth: usize,
+    remaining: usize,
+}
+
+impl Nested<'_> {
+    fn tracer(&mut self) -> SchemaTracer<'_> {
+        SchemaTracer { shape: &mut *self.shape, depth: self.depth }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Nested<'_> {
+    type Error = de::value::Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(self.tracer()).map(Some)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Nested<'_> {
+    type Error = de::value::Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(self.tracer()).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        seed.deserialize(self.tracer())
+    }
+}
 
 /// Metadata about a snapshot file on disk.
 #[derive(Debug, Clone)]
@@ -22,6 +312,9 @@ pub enum SnapshotError {
     Serialize(String),
     Deserialize(String),
     Corrupt(PathBuf),
+    /// Written by a build with a different world schema, so its bincode
+    /// can't be read by this one
+    SchemaMismatch { path: PathBuf, found: u64, expected: u64 },
     NoValidSnapshots,
 }
 
@@ -34,6 +327,13 @@ impl std::fmt::Display for SnapshotError {
             SnapshotError::Corrupt(path) => {
                 write!(f, "Corrupt snapshot: {}", path.display())
             }
+            SnapshotError::SchemaMismatch { path, found, expected } => write!(
+                f,
+                "{} was written by a build with a different world schema ({:016x}, this build has {:016x}); load it with the build that wrote it",
+                path.display(),
+                found,
+                expected
+            ),
             SnapshotError::NoValidSnapshots => {
                 write!(
                     f,
@@ -44,7 +344,14 @@ impl std::fmt::Display for SnapshotError {
     }
 }
 
-impl std::error::Error for SnapshotError {}
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for SnapshotError {
     fn from(e: io::Error) -> Self {
@@ -77,8 +384,10 @@ fn unix_timestamp_now() -> u64 {
 
 /// Save a world snapshot to the snapshot directory using atomic write.
 ///
-/// Writes to a temporary file first, then atomically renames to the final path.
-/// This ensures a partial write never corrupts an existing snapshot.
+/// Tiles are serialized in parallel chunks so they can also be decoded in
+/// parallel, or one at a time by `SnapshotReader`. Writes to a temporary file
+/// first, then atomically renames to the final path. This ensures a partial
+/// write never corrupts an existing snapshot.
 pub fn save_snapshot(world: &World, snapshot_dir: &Path) -> Result<PathBuf, SnapshotError> {
     fs::create_dir_all(snapshot_dir)?;
 
@@ -87,7 +396,7 @@ pub fn save_snapshot(world: &World, snapshot_dir: &Path) -> Result<PathBuf, Snap
     let target = snapshot_dir.join(&filename);
     let tmp = snapshot_dir.join(format!(".{}.tmp", filename));
 
-    let encoded = bincode::serialize(world).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    let encoded = encode_chunked(world, TILES_PER_CHUNK)?;
 
     // Write to temp file, then atomic rename
     if let Err(e) = fs::write(&tmp, &encoded) {
@@ -104,13 +413,149 @@ pub fn save_snapshot(world: &World, snapshot_dir: &Path) -> Result<PathBuf, Snap
     Ok(target)
 }
 
-/// Load a world from a snapshot file.
-///
-/// Validates that the deserialized world has consistent tile count.
-pub fn load_snapshot(path: &Path) -> Result<World, SnapshotError> {
-    let data = fs::read(path)?;
-    let world: World =
-        bincode::deserialize(&data).map_err(|e| SnapshotError::Deserialize(e.to_string()))?;
+/// Serialize a world as a compressed chunked snapshot, `chunk_tiles` tiles per chunk.
+fn encode_chunked(world: &World, chunk_tiles: usize) -> Result<Vec<u8>, SnapshotError> {
+    let chunks: Vec<Vec<u8>> = world
+        .tiles
+        .par_chunks(chunk_tiles.max(1))
+        .map(|tiles| {
+            let raw = bincode::serialize(tiles).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+            zstd::bulk::compress(&raw, ZSTD_LEVEL).map_err(|e| SnapshotError::Serialize(e.to_string()))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut index = Vec::with_capacity(chunks.len());
+    let mut offset = 0_u64;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let first = i * chunk_tiles.max(1);
+        index.push(ChunkIndex {
+            first_tile: first as u32,
+            tile_count: (world.tiles.len() - first).min(chunk_tiles.max(1)) as u32,
+            offset,
+            len: chunk.len() as u64,
+        });
+        offset += chunk.len() as u64;
+    }
+    let header = bincode::serialize(&SnapshotHeader { world: world.without_tiles(), chunks: index })
+        .map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+
+    let mut out = Vec::with_capacity(26 + header.len() + offset as usize);
+    out.write_all(VERSIONED_MAGIC)?;
+    out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    out.write_all(&schema_hash().to_le_bytes())?;
+    out.write_all(&(header.len() as u64).to_le_bytes())?;
+    out.write_all(&header)?;
+    for chunk in &chunks {
+        out.write_all(chunk)?;
+    }
+    Ok(out)
+}
+
+/// Split a chunked snapshot into its header and chunk area.
+/// Returns `None` for an older single-blob snapshot.
+fn decode_header<'a>(data: &'a [u8], path: &Path) -> Result<Option<Layout<'a>>, SnapshotError> {
+    let corrupt = || SnapshotError::Corrupt(path.to_path_buf());
+    let (rest, encoding) = if let Some(rest) = data.strip_prefix(VERSIONED_MAGIC.as_slice()) {
+        let version = u16::from_le_bytes(rest.get(..2).ok_or_else(corrupt)?.try_into().map_err(|_| corrupt())?);
+        if version != FORMAT_VERSION {
+            return Err(SnapshotError::Deserialize(format!(
+                "snapshot format {} is not supported by this build (format {}); upgrade worldground",
+                version, FORMAT_VERSION
+            )));
+        }
+        let hash = u64::from_le_bytes(rest.get(2..10).ok_or_else(corrupt)?.try_into().map_err(|_| corrupt())?);
+        check_schema(hash, path)?;
+        (&rest[10..], ChunkEncoding { compressed: true })
+    } else if let Some(rest) = data.strip_prefix(CHUNKED_MAGIC.as_slice()) {
+        (rest, ChunkEncoding { compressed: false })
+    } else {
+        return Ok(None);
+    };
+    let len_bytes: [u8; 8] = rest.get(..8).ok_or_else(corrupt)?.try_into().map_err(|_| corrupt())?;
+    let header_end = usize::try_from(u64::from_le_bytes(len_bytes))
+        .ok()
+        .and_then(|len| len.checked_add(8))
+        .ok_or_else(corrupt)?;
+    let header_bytes = rest.get(8..header_end).ok_or_else(corrupt)?;
+    let header: SnapshotHeader = bincode_limited(header_bytes.len() as u64)
+        .deserialize(header_bytes)
+        .map_err(|e| decode_error(e))?;
+
+    // The chunk table must cover every tile, in order, within the file, in
+    // chunks no larger than this build writes
+    let body = &rest[header_end..];
+    let mut next_tile = 0_u32;
+    for chunk in &header.chunks {
+        if chunk.first_tile != next_tile
+            || chunk.tile_count as usize > TILES_PER_CHUNK
+            || chunk.offset.saturating_add(chunk.len) > body.len() as u64
+        {
+            return Err(corrupt());
+        }
+        next_tile = next_tile.checked_add(chunk.tile_count).ok_or_else(corrupt)?;
+    }
+    if next_tile != header.world.tile_count {
+        return Err(corrupt());
+    }
+    Ok(Some(Layout { header, body, encoding }))
+}
+
+/// Refuse a snapshot whose stored schema hash isn't this build's.
+fn check_schema(found: u64, path: &Path) -> Result<(), SnapshotError> {
+    let expected = schema_hash();
+    if found != expected {
+        return Err(SnapshotError::SchemaMismatch { path: path.to_path_buf(), found, expected });
+    }
+    Ok(())
+}
+
+fn decode_error(e: impl std::fmt::Display) -> SnapshotError {
+    SnapshotError::Deserialize(e.to_string())
+}
+
+/// Decode the tiles of one chunk.
+fn decode_chunk(
+    body: &[u8],
+    chunk: &ChunkIndex,
+    encoding: ChunkEncoding,
+    path: &Path,
+) -> Result<Vec<Tile>, SnapshotError> {
+    let bytes = &body[chunk.offset as usize..(chunk.offset + chunk.len) as usize];
+    let tiles: Vec<Tile> = if encoding.compressed {
+        let limit = (chunk.tile_count as u64 + 1) * MAX_TILE_BYTES;
+        let decoder = zstd::stream::read::Decoder::new(bytes).map_err(|e| decode_error(e))?;
+        bincode_limited(limit).deserialize_from(decoder).map_err(|e| decode_error(e))?
+    } else {
+        bincode_limited(bytes.len() as u64).deserialize(bytes).map_err(|e| decode_error(e))?
+    };
+    if tiles.len() as u32 != chunk.tile_count {
+        return Err(SnapshotError::Corrupt(path.to_path_buf()));
+    }
+    Ok(tiles)
+}
+
+/// Decode a whole snapshot, chunked or single-blob, from bytes already in
+/// memory. `path` only labels errors.
+pub fn decode_snapshot(data: &[u8], path: &Path) -> Result<World, SnapshotError> {
+    let world = match decode_header(data, path)? {
+        Some(Layout { header, body, encoding }) => {
+            let chunks: Vec<Vec<Tile>> = header
+                .chunks
+                .par_iter()
+                .map(|chunk| decode_chunk(body, chunk, encoding, path))
+                .collect::<Result<_, _>>()?;
+            let mut world = header.world;
+            world.tiles = Vec::with_capacity(world.tile_count as usize);
+            for chunk in chunks {
+                world.tiles.extend(chunk);
+            }
+            world
+        }
+        None => bincode_limited(data.len() as u64)
+            .deserialize::<WorldV1>(data)
+            .map_err(|e| SnapshotError::Deserialize(e.to_string()))?
+            .into(),
+    };
 
     // Validate tile count consistency
     if world.tiles.len() as u32 != world.tile_count {
@@ -120,6 +565,196 @@ pub fn load_snapshot(path: &Path) -> Result<World, SnapshotError> {
     Ok(world)
 }
 
+/// Load a world from a snapshot file.
+///
+/// Tile chunks are decoded in parallel. Validates that the deserialized world
+/// has consistent tile count.
+pub fn load_snapshot(path: &Path) -> Result<World, SnapshotError> {
+    let data = fs::read(path)?;
+    decode_snapshot(&data, path)
+}
+
+/// Read-only view of a snapshot that decodes tiles on demand.
+///
+/// Chunked snapshots are memory-mapped: opening one decodes only the header,
+/// and `tile` decodes just the chunk holding the requested tile, so a single
+/// tile of a very large world can be inspected without loading the rest.
+/// `for_each_chunk` walks every tile with one chunk resident at a time.
+/// Older single-blob snapshots are decoded in full when opened.
+pub struct SnapshotReader {
+    path: PathBuf,
+    world: World,
+    tiles: ReaderTiles,
+}
+
+enum ReaderTiles {
+    Mapped { map: Mmap, body_start: usize, chunks: Vec<ChunkIndex>, encoding: ChunkEncoding },
+    Loaded(Vec<Tile>),
+}
+
+impl SnapshotReader {
+    /// Open a snapshot file, decoding only its world-level state.
+    pub fn open(path: &Path) -> Result<Self, SnapshotError> {
+        let file = fs::File::open(path)?;
+        // SAFETY: snapshots are written to a temporary file and renamed into
+        // place, never modified in place, so the mapped bytes cannot change.
+        let map = unsafe { Mmap::map(&file)? };
+        match decode_header(&map, path)? {
+            Some(Layout { header, body, encoding }) => {
+                let body_start = map.len() - body.len();
+                Ok(Self {
+                    path: path.to_path_buf(),
+                    world: header.world,
+                    tiles: ReaderTiles::Mapped { map, body_start, chunks: header.chunks, encoding },
+                })
+            }
+            None => {
+                let mut world = decode_snapshot(&map, path)?;
+                let tiles = std::mem::take(&mut world.tiles);
+                Ok(Self { path: path.to_path_buf(), world, tiles: ReaderTiles::Loaded(tiles) })
+            }
+        }
+    }
+
+    /// World-level state. Its `tiles` list is always empty; use `tile`.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Decode a single tile, or `None` if the world has no tile at that index.
+    pub fn tile(&self, index: u32) -> Result<Option<Tile>, SnapshotError> {
+        match &self.tiles {
+            ReaderTiles::Loaded(tiles) => Ok(tiles.get(index as usize).cloned()),
+            ReaderTiles::Mapped { map, body_start, chunks, encoding } => {
+                let Some(chunk) = chunks
+                    .iter()
+                    .find(|c| index >= c.first_tile && index - c.first_tile < c.tile_count)
+                else {
+                    return Ok(None);
+                };
+                let mut tiles = decode_chunk(&map[*body_start..], chunk, *encoding, &self.path)?;
+                Ok(Some(tiles.swap_remove((index - chunk.first_tile) as usize)))
+            }
+        }
+    }
+
+    /// Visit every tile in order, one decoded chunk at a time. Memory use is
+    /// bounded by the chunk size however large the world is.
+    pub fn for_each_chunk(&self, mut f: impl FnMut(&[Tile])) -> Result<(), SnapshotError> {
+        match &self.tiles {
+            ReaderTiles::Loaded(tiles) => f(tiles),
+            ReaderTiles::Mapped { map, body_start, chunks, encoding } => {
+                for chunk in chunks {
+                    f(&decode_chunk(&map[*body_start..], chunk, *encoding, &self.path)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode every tile, in parallel for chunked snapshots.
+    pub fn into_world(self) -> Result<World, SnapshotError> {
+        let mut world = self.world;
+        world.tiles = match self.tiles {
+            ReaderTiles::Loaded(tiles) => tiles,
+            ReaderTiles::Mapped { map, body_start, chunks, encoding } => {
+                let body = &map[body_start..];
+                let decoded: Vec<Vec<Tile>> = chunks
+                    .par_iter()
+                    .map(|chunk| decode_chunk(body, chunk, encoding, &self.path))
+                    .collect::<Result<_, _>>()?;
+                decoded.into_iter().flatten().collect()
+            }
+        };
+        Ok(world)
+    }
+}
+
+/// World-level state and some layers of every tile, e.g. only the weather
+/// for a replay, or only the biomes for an export.
+///
+/// On disk this is `LAYER_MAGIC`, the format version as a little-endian
+/// u16, the schema hash and the tile count as little-endian u64s, then the
+/// snapshot as zstd-compressed bincode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerSnapshot {
+    /// World-level state, with an empty tile list
+    pub world: World,
+    pub layers: LayerArrays,
+}
+
+impl LayerSnapshot {
+    /// Capture `layers` of every tile of `world`.
+    pub fn capture(world: &World, layers: &[DiffLayer]) -> Self {
+        LayerSnapshot { world: world.without_tiles(), layers: world.capture_layers(layers) }
+    }
+}
+
+/// Serialize a layer snapshot.
+pub fn encode_layer_snapshot(snapshot: &LayerSnapshot) -> Result<Vec<u8>, SnapshotError> {
+    let raw = bincode::serialize(snapshot).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    let body = zstd::bulk::compress(&raw, ZSTD_LEVEL).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+
+    let mut out = Vec::with_capacity(26 + body.len());
+    out.write_all(LAYER_MAGIC)?;
+    out.write_all(&LAYER_FORMAT_VERSION.to_le_bytes())?;
+    out.write_all(&schema_hash().to_le_bytes())?;
+    out.write_all(&(snapshot.world.tile_count as u64).to_le_bytes())?;
+    out.write_all(&body)?;
+    Ok(out)
+}
+
+/// Decode a layer snapshot from bytes already in memory. `path` only labels
+/// errors.
+pub fn decode_layer_snapshot(data: &[u8], path: &Path) -> Result<LayerSnapshot, SnapshotError> {
+    let corrupt = || SnapshotError::Corrupt(path.to_path_buf());
+    let rest = data.strip_prefix(LAYER_MAGIC.as_slice()).ok_or_else(corrupt)?;
+    let version = u16::from_le_bytes(rest.get(..2).ok_or_else(corrupt)?.try_into().map_err(|_| corrupt())?);
+    if version != LAYER_FORMAT_VERSION {
+        return Err(SnapshotError::Deserialize(format!(
+            "layer snapshot format {} is not supported by this build (format {}); upgrade worldground",
+            version, LAYER_FORMAT_VERSION
+        )));
+    }
+    let hash = u64::from_le_bytes(rest.get(2..10).ok_or_else(corrupt)?.try_into().map_err(|_| corrupt())?);
+    check_schema(hash, path)?;
+    let tile_count = u64::from_le_bytes(rest.get(10..18).ok_or_else(corrupt)?.try_into().map_err(|_| corrupt())?);
+    if tile_count > u32::MAX as u64 {
+        return Err(corrupt());
+    }
+
+    let limit = (tile_count + 1) * MAX_TILE_BYTES;
+    let decoder = zstd::stream::read::Decoder::new(&rest[18..]).map_err(|e| decode_error(e))?;
+    let snapshot: LayerSnapshot =
+        bincode_limited(limit).deserialize_from(decoder).map_err(|e| decode_error(e))?;
+
+    let covered = snapshot.layers.layers().is_empty()
+        || snapshot.layers.tile_count() == Some(snapshot.world.tile_count as usize);
+    if snapshot.world.tile_count as u64 != tile_count || !covered {
+        return Err(corrupt());
+    }
+    Ok(snapshot)
+}
+
+/// Write a layer snapshot to `path`, through a temporary file like
+/// `save_snapshot`.
+pub fn save_layer_snapshot(snapshot: &LayerSnapshot, path: &Path) -> Result<(), SnapshotError> {
+    let encoded = encode_layer_snapshot(snapshot)?;
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("layers");
+    let tmp = path.with_file_name(format!(".{}.tmp", filename));
+    if let Err(e) = fs::write(&tmp, &encoded).and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(SnapshotError::Io(e));
+    }
+    Ok(())
+}
+
+/// Load a layer snapshot file.
+pub fn load_layer_snapshot(path: &Path) -> Result<LayerSnapshot, SnapshotError> {
+    let data = fs::read(path)?;
+    decode_layer_snapshot(&data, path)
+}
+
 /// List all valid snapshots in a directory, sorted by timestamp descending (newest first).
 pub fn list_snapshots(snapshot_dir: &Path) -> Result<Vec<SnapshotMetadata>, SnapshotError> {
     if !snapshot_dir.exists() {
@@ -167,6 +802,45 @@ pub fn list_snapshots(snapshot_dir: &Path) -> Result<Vec<SnapshotMetadata>, Snap
     Ok(snapshots)
 }
 
+/// Order for snapshot listings. Every order puts the largest value first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotSort {
+    /// Newest timestamp first (the `list_snapshots` order)
+    #[default]
+    Age,
+    /// Highest tick first
+    Tick,
+    /// Largest file first
+    Size,
+}
+
+/// Sort snapshot metadata in place. Ties fall back to newest first.
+pub fn sort_snapshots(snapshots: &mut [SnapshotMetadata], sort: SnapshotSort) {
+    snapshots.sort_by(|a, b| {
+        let primary = match sort {
+            SnapshotSort::Age => b.timestamp.cmp(&a.timestamp),
+            SnapshotSort::Tick => b.tick_count.cmp(&a.tick_count),
+            SnapshotSort::Size => b.file_size.cmp(&a.file_size),
+        };
+        primary
+            .then(b.timestamp.cmp(&a.timestamp))
+            .then(b.tick_count.cmp(&a.tick_count))
+    });
+}
+
+/// Inclusive tick range for snapshot listings; `None` leaves that end open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TickRange {
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl TickRange {
+    pub fn contains(&self, tick: u64) -> bool {
+        self.since.is_none_or(|s| tick >= s) && self.until.is_none_or(|u| tick <= u)
+    }
+}
+
 /// Prune old snapshots, keeping only the `max_snapshots` most recent.
 ///
 /// Returns the list of deleted file paths.
@@ -191,6 +865,19 @@ pub fn prune_snapshots(
 ///
 /// Returns an error only if no valid snapshots exist.
 pub fn load_latest_valid_snapshot(snapshot_dir: &Path) -> Result<World, SnapshotError> {
+    first_valid(snapshot_dir, load_snapshot)
+}
+
+/// Open the most recent snapshot whose header is valid for lazy reading,
+/// falling back to older ones like `load_latest_valid_snapshot`.
+pub fn open_latest_valid_snapshot(snapshot_dir: &Path) -> Result<SnapshotReader, SnapshotError> {
+    first_valid(snapshot_dir, SnapshotReader::open)
+}
+
+fn first_valid<T>(
+    snapshot_dir: &Path,
+    open: impl Fn(&Path) -> Result<T, SnapshotError>,
+) -> Result<T, SnapshotError> {
     let snapshots = list_snapshots(snapshot_dir)?;
 
     if snapshots.is_empty() {
@@ -198,8 +885,8 @@ pub fn load_latest_valid_snapshot(snapshot_dir: &Path) -> Result<World, Snapshot
     }
 
     for snapshot in &snapshots {
-        match load_snapshot(&snapshot.path) {
-            Ok(world) => return Ok(world),
+        match open(&snapshot.path) {
+            Ok(value) => return Ok(value),
             Err(e) => {
                 warn!(
                     path = %snapshot.path.display(),
@@ -218,6 +905,7 @@ mod tests {
     use super::*;
     use crate::config::generation::GenerationParams;
     use crate::world::generation::generate_world;
+    use proptest::prelude::*;
     use std::time::Instant;
     use tempfile::TempDir;
 
@@ -225,13 +913,7 @@ mod tests {
         let params = GenerationParams {
             seed: 42,
             tile_count,
-            ocean_ratio: 0.6,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: crate::config::generation::TopologyConfig::default(),
+            ..Default::default()
         };
         generate_world(&params)
     }
@@ -339,6 +1021,40 @@ mod tests {
         assert!(snapshots.is_empty());
     }
 
+    #[test]
+    fn sort_snapshots_by_tick_size_and_age() {
+        let meta = |tick_count, timestamp, file_size| SnapshotMetadata {
+            path: PathBuf::from(snapshot_filename(tick_count, timestamp)),
+            tick_count,
+            timestamp,
+            file_size,
+        };
+        let mut snapshots = vec![meta(10, 3000, 50), meta(30, 1000, 20), meta(20, 2000, 90)];
+
+        sort_snapshots(&mut snapshots, SnapshotSort::Tick);
+        let ticks: Vec<u64> = snapshots.iter().map(|s| s.tick_count).collect();
+        assert_eq!(ticks, vec![30, 20, 10]);
+
+        sort_snapshots(&mut snapshots, SnapshotSort::Size);
+        let sizes: Vec<u64> = snapshots.iter().map(|s| s.file_size).collect();
+        assert_eq!(sizes, vec![90, 50, 20]);
+
+        sort_snapshots(&mut snapshots, SnapshotSort::Age);
+        let stamps: Vec<u64> = snapshots.iter().map(|s| s.timestamp).collect();
+        assert_eq!(stamps, vec![3000, 2000, 1000]);
+    }
+
+    #[test]
+    fn tick_range_is_inclusive_and_open_ended() {
+        let range = TickRange { since: Some(10), until: Some(20) };
+        assert!(!range.contains(9));
+        assert!(range.contains(10));
+        assert!(range.contains(20));
+        assert!(!range.contains(21));
+        assert!(TickRange::default().contains(0));
+        assert!(TickRange { since: Some(5), until: None }.contains(u64::MAX));
+    }
+
     #[test]
     fn prune_keeps_max_snapshots() {
         let dir = TempDir::new().unwrap();
@@ -382,6 +1098,259 @@ mod tests {
         assert_eq!(remaining.len(), 2);
     }
 
+    #[test]
+    fn chunked_round_trip_across_many_chunks() {
+        let dir = TempDir::new().unwrap();
+        let world = make_test_world(200);
+        let path = dir.path().join("world-tick0-1000.bin");
+        fs::write(&path, encode_chunked(&world, 7).unwrap()).unwrap();
+
+        assert_eq!(load_snapshot(&path).unwrap(), world);
+        assert_eq!(SnapshotReader::open(&path).unwrap().into_world().unwrap(), world);
+    }
+
+    #[test]
+    fn reader_hydrates_single_tiles() {
+        let dir = TempDir::new().unwrap();
+        let world = make_test_world(200);
+        let path = dir.path().join("world-tick0-1000.bin");
+        fs::write(&path, encode_chunked(&world, 16).unwrap()).unwrap();
+
+        let reader = SnapshotReader::open(&path).unwrap();
+        assert!(reader.world().tiles.is_empty());
+        assert_eq!(reader.world().tile_count, world.tile_count);
+        assert_eq!(reader.world().name, world.name);
+        for id in [0, 15, 16, world.tile_count - 1] {
+            assert_eq!(reader.tile(id).unwrap().as_ref(), Some(&world.tiles[id as usize]));
+        }
+        assert!(reader.tile(world.tile_count).unwrap().is_none());
+    }
+
+    #[test]
+    fn reader_walks_tiles_chunk_by_chunk() {
+        let dir = TempDir::new().unwrap();
+        let world = make_test_world(200);
+        let path = dir.path().join("world-tick0-1000.bin");
+        fs::write(&path, encode_chunked(&world, 16).unwrap()).unwrap();
+
+        let mut sizes = Vec::new();
+        let mut ids = Vec::new();
+        SnapshotReader::open(&path)
+            .unwrap()
+            .for_each_chunk(|tiles| {
+                sizes.push(tiles.len());
+                ids.extend(tiles.iter().map(|t| t.id));
+            })
+            .unwrap();
+        assert_eq!(sizes.len(), world.tiles.len().div_ceil(16));
+        assert!(sizes.iter().all(|&n| n <= 16));
+        assert_eq!(ids, world.tiles.iter().map(|t| t.id).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn single_blob_snapshots_still_load() {
+        // Written by a release that only knew format 1: flat, 100 tiles, seed 7
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("world-tick324-1000.bin");
+        fs::write(&path, include_bytes!("fixtures/format1-flat100-tick324.bin")).unwrap();
+
+        let world = load_snapshot(&path).unwrap();
+        assert_eq!(world.tick_count, 324);
+        assert_eq!(world.generation_params.seed, 7);
+        assert_eq!(world.tiles.len(), 100);
+        assert!(world.tiles.iter().enumerate().all(|(i, t)| t.id == i as u32 && !t.neighbors.is_empty()));
+        assert!(!world.macro_weather.systems.is_empty());
+        let reader = SnapshotReader::open(&path).unwrap();
+        assert_eq!(reader.tile(3).unwrap().as_ref(), Some(&world.tiles[3]));
+
+        // and is saved again in the current format
+        let resaved = dir.path().join("resaved.bin");
+        fs::write(&resaved, encode_chunked(&world, 16).unwrap()).unwrap();
+        assert_eq!(load_snapshot(&resaved).unwrap(), world);
+    }
+
+    /// A world in the uncompressed chunked format older versions wrote.
+    fn encode_format_2(world: &World, chunk_tiles: usize) -> Vec<u8> {
+        let chunks: Vec<Vec<u8>> =
+            world.tiles.chunks(chunk_tiles).map(|t| bincode::serialize(t).unwrap()).collect();
+        let mut offset = 0;
+        let index = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let entry = ChunkIndex {
+                    first_tile: (i * chunk_tiles) as u32,
+                    tile_count: (world.tiles.len() - i * chunk_tiles).min(chunk_tiles) as u32,
+                    offset,
+                    len: chunk.len() as u64,
+                };
+                offset += chunk.len() as u64;
+                entry
+            })
+            .collect();
+        let header = bincode::serialize(&SnapshotHeader { world: world.without_tiles(), chunks: index }).unwrap();
+        let mut out = CHUNKED_MAGIC.to_vec();
+        out.extend((header.len() as u64).to_le_bytes());
+        out.extend(header);
+        out.extend(chunks.concat());
+        out
+    }
+
+    #[test]
+    fn snapshots_are_compressed_and_uncompressed_ones_still_load() {
+        let dir = TempDir::new().unwrap();
+        let world = make_test_world(200);
+        let legacy = encode_format_2(&world, 16);
+        let compressed = encode_chunked(&world, 16).unwrap();
+        assert!(compressed.len() * 2 < legacy.len(), "{} vs {}", compressed.len(), legacy.len());
+        assert_eq!(&compressed[8..10], FORMAT_VERSION.to_le_bytes().as_slice());
+        assert_eq!(&compressed[10..18], schema_hash().to_le_bytes().as_slice());
+
+        let path = dir.path().join("world-tick0-1000.bin");
+        fs::write(&path, &legacy).unwrap();
+        assert_eq!(load_snapshot(&path).unwrap(), world);
+        let reader = SnapshotReader::open(&path).unwrap();
+        assert_eq!(reader.tile(20).unwrap().as_ref(), Some(&world.tiles[20]));
+    }
+
+    #[test]
+    fn newer_formats_and_foreign_schemas_are_reported() {
+        let world = make_test_world(40);
+        let path = Path::new("fuzz.bin");
+
+        let mut newer = encode_chunked(&world, 8).unwrap();
+        newer[8..10].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        let err = decode_snapshot(&newer, path).unwrap_err().to_string();
+        assert!(err.contains("format 4"), "{}", err);
+
+        // bincode can't tell a changed schema from a valid one, so a
+        // different hash is refused even when the bytes would decode
+        let mut foreign = encode_chunked(&world, 8).unwrap();
+        foreign[10..18].copy_from_slice(&(schema_hash() ^ 1).to_le_bytes());
+        let err = decode_snapshot(&foreign, path).unwrap_err();
+        assert!(matches!(err, SnapshotError::SchemaMismatch { found, .. } if found == schema_hash() ^ 1));
+        assert!(err.to_string().contains("different world schema"), "{}", err);
+
+        let mut layers = encode_layer_snapshot(&LayerSnapshot::capture(&world, &[DiffLayer::Weather])).unwrap();
+        layers[10..18].copy_from_slice(&(schema_hash() ^ 1).to_le_bytes());
+        assert!(matches!(decode_layer_snapshot(&layers, path), Err(SnapshotError::SchemaMismatch { .. })));
+    }
+
+    #[test]
+    fn schema_hash_covers_the_whole_world() {
+        let mut shape = Vec::new();
+        World::deserialize(SchemaTracer { shape: &mut shape, depth: 0 }).unwrap();
+        let has = |entry: &str| shape.iter().any(|s| s.starts_with(entry));
+        // World-level state, the generation parameters, tiles, and types
+        // only reached through a Vec or Option, such as deposits
+        assert!(has("struct World id,name,"), "{:?}", &shape[..4]);
+        assert!(has("struct GenerationParams "));
+        assert!(has("struct Tile "));
+        assert!(has("struct ResourceDeposit "));
+        assert!(has("struct PressureSystem "));
+        assert!(has("enum Season "));
+        assert_eq!(schema_hash(), schema_hash());
+    }
+
+    #[test]
+    fn layer_snapshots_hold_only_their_layers() {
+        let dir = TempDir::new().unwrap();
+        let mut world = make_test_world(200);
+        let path = dir.path().join("weather.bin");
+        let snapshot = LayerSnapshot::capture(&world, &[DiffLayer::Weather]);
+        save_layer_snapshot(&snapshot, &path).unwrap();
+        assert!(fs::metadata(&path).unwrap().len() < encode_chunked(&world, TILES_PER_CHUNK).unwrap().len() as u64);
+
+        let loaded = load_layer_snapshot(&path).unwrap();
+        assert_eq!(loaded.layers, snapshot.layers);
+        assert_eq!(loaded.layers.layers(), vec![DiffLayer::Weather]);
+        assert_eq!(loaded.world.tick_count, world.tick_count);
+
+        let biomes = world.tiles[7].biome.clone();
+        world.tiles[7].weather.temperature += 10.0;
+        world.tiles[7].biome.vegetation_density += 0.25;
+        world.restore_layers(loaded.layers).unwrap();
+        assert_eq!(world.tiles[7].weather, snapshot.layers.weather.as_ref().unwrap()[7]);
+        assert_ne!(world.tiles[7].biome, biomes);
+
+        let bytes = fs::read(&path).unwrap();
+        assert!(decode_layer_snapshot(&bytes[..bytes.len() / 2], &path).is_err());
+        assert!(matches!(decode_layer_snapshot(b"WGSNAPV\0", &path), Err(SnapshotError::Corrupt(_))));
+    }
+
+    #[test]
+    fn truncated_chunked_snapshot_returns_error() {
+        let dir = TempDir::new().unwrap();
+        let world = make_test_world(100);
+        let data = encode_chunked(&world, 16).unwrap();
+        let path = dir.path().join("world-tick0-1000.bin");
+        fs::write(&path, &data[..data.len() - 10]).unwrap();
+
+        assert!(matches!(load_snapshot(&path), Err(SnapshotError::Corrupt(_))));
+        assert!(SnapshotReader::open(&path).is_err());
+    }
+
+    proptest! {
+        // Shrinking is capped too, so a failure is reported in bounded time
+        #![proptest_config(ProptestConfig { cases: 64, max_shrink_iters: 256, ..ProptestConfig::default() })]
+
+        #[test]
+        fn save_load_is_lossless(
+            seed in any::<u64>(),
+            chunk_tiles in 1_usize..64,
+            temperatures in prop::collection::vec(150.0_f32..350.0, 1..20),
+            scratch in prop::collection::btree_map("[a-z]{1,8}", -1e9_f64..1e9, 0..4),
+        ) {
+            let mut world = make_test_world(60);
+            world.tick_count = seed;
+            for (tile, t) in world.tiles.iter_mut().zip(&temperatures) {
+                tile.weather.temperature = *t;
+                tile.scratch = scratch.clone();
+            }
+            let path = Path::new("fuzz.bin");
+            let restored = decode_snapshot(&encode_chunked(&world, chunk_tiles).unwrap(), path).unwrap();
+            prop_assert_eq!(restored, world);
+        }
+
+        #[test]
+        fn corrupt_bytes_never_panic(
+            edits in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+            cut in any::<prop::sample::Index>(),
+            chunked in any::<bool>(),
+        ) {
+            let world = make_test_world(40);
+            let mut data = if chunked {
+                encode_chunked(&world, 8).unwrap()
+            } else {
+                include_bytes!("fixtures/format1-flat100-tick324.bin").to_vec()
+            };
+            for (at, byte) in edits {
+                let i = at.index(data.len());
+                data[i] = byte;
+            }
+            data.truncate(cut.index(data.len() + 1).max(1));
+            // Decoding may fail or produce a different world, but must not panic
+            let _ = decode_snapshot(&data, Path::new("fuzz.bin"));
+        }
+    }
+
+    #[test]
+    fn corrupt_lengths_stop_at_the_decode_limit() {
+        let path = Path::new("fuzz.bin");
+        // A tile count far past the chunk, then more zeros than 8 tiles may take
+        let mut raw = u64::MAX.to_le_bytes().to_vec();
+        raw.resize(4 << 20, 0);
+        let body = zstd::bulk::compress(&raw, ZSTD_LEVEL).unwrap();
+        let chunk = ChunkIndex { first_tile: 0, tile_count: 8, offset: 0, len: body.len() as u64 };
+        let encoding = ChunkEncoding { compressed: true };
+        let err = decode_chunk(&body, &chunk, encoding, path).unwrap_err().to_string();
+        assert!(err.contains("limit"), "{}", err);
+
+        let mut layers = encode_layer_snapshot(&LayerSnapshot::capture(&make_test_world(4), &[])).unwrap();
+        layers[18..26].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(decode_layer_snapshot(&layers, path), Err(SnapshotError::Corrupt(_))));
+    }
+
     #[test]
     fn load_corrupt_snapshot_returns_error() {
         let dir = TempDir::new().unwrap();
@@ -395,7 +1364,7 @@ mod tests {
     fn load_truncated_snapshot_returns_error() {
         let dir = TempDir::new().unwrap();
         let world = make_test_world(100);
-        let data = bincode::serialize(&world).unwrap();
+        let data = encode_chunked(&world, 16).unwrap();
 
         let path = dir.path().join("world-tick0-1000.bin");
         fs::write(&path, &data[..data.len() / 2]).unwrap();
@@ -407,7 +1376,7 @@ mod tests {
     fn load_latest_valid_falls_back_on_corrupt() {
         let dir = TempDir::new().unwrap();
         let world = make_test_world(100);
-        let valid_data = bincode::serialize(&world).unwrap();
+        let valid_data = encode_chunked(&world, 16).unwrap();
 
         // Oldest: valid
         fs::write(dir.path().join("world-tick10-1000.bin"), &valid_data).unwrap();
@@ -505,3 +1474,4 @@ mod tests {
         assert!(path2.exists());
     }
 }
+
@@ -0,0 +1,337 @@
+//! Frozen layout of format 1 snapshots: the single bincode `World` blob
+//! written before snapshots were chunked.
+//!
+//! bincode is not self-describing, so `#[serde(default)]` on a field added
+//! later doesn't let an old snapshot decode into the current `World`. These
+//! structs keep the field order format 1 was written with and must never
+//! change; `From` fills everything added since with its default. The enums
+//! are shared with the current types, whose variants are only ever appended.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::generation::{GenerationParams, TopologyConfig};
+use crate::world::tile::{
+    BiomeLayer, BiomeType, ClimateLayer, ClimateZone, ConditionsLayer, GeologyLayer, Position,
+    PrecipitationType, ResourceDeposit, ResourceLayer, SoilType, TerrainType, WeatherLayer,
+};
+use crate::world::weather_systems::{PressureSystem, PressureSystemType, SpaceWeatherState};
+use crate::world::{MacroWeatherState, Season, Tile, TopologyType, World};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldV1 {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: String,
+    pub tick_count: u64,
+    pub season: Season,
+    pub season_length: u32,
+    pub tile_count: u32,
+    pub topology_type: TopologyType,
+    pub generation_params: GenerationParamsV1,
+    pub snapshot_path: Option<String>,
+    pub tiles: Vec<TileV1>,
+    pub macro_weather: MacroWeatherStateV1,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationParamsV1 {
+    pub seed: u64,
+    pub tile_count: u32,
+    pub ocean_ratio: f32,
+    pub mountain_ratio: f32,
+    pub elevation_roughness: f32,
+    pub climate_bands: bool,
+    pub resource_density: f32,
+    pub initial_biome_maturity: f32,
+    pub topology: TopologyConfigV1,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyConfigV1 {
+    pub mode: String,
+    pub subdivision_level: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroWeatherStateV1 {
+    pub systems: Vec<PressureSystemV1>,
+    pub next_id: u32,
+    pub rng_state: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureSystemV1 {
+    pub id: u32,
+    pub lat: f64,
+    pub lon: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub pressure_anomaly: f32,
+    pub radius: f32,
+    pub velocity_east: f32,
+    pub velocity_north: f32,
+    pub age: u32,
+    pub max_age: u32,
+    pub system_type: PressureSystemType,
+    pub moisture: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileV1 {
+    pub id: u32,
+    pub neighbors: Vec<u32>,
+    pub position: PositionV1,
+    pub geology: GeologyLayerV1,
+    pub climate: ClimateLayerV1,
+    pub biome: BiomeLayerV1,
+    pub resources: ResourceLayerV1,
+    pub weather: WeatherLayerV1,
+    pub conditions: ConditionsLayerV1,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PositionV1 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeologyLayerV1 {
+    pub terrain_type: TerrainType,
+    pub elevation: f32,
+    pub soil_type: SoilType,
+    pub drainage: f32,
+    pub tectonic_stress: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClimateLayerV1 {
+    pub zone: ClimateZone,
+    pub base_temperature: f32,
+    pub base_precipitation: f32,
+    pub latitude: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BiomeLayerV1 {
+    pub biome_type: BiomeType,
+    pub vegetation_density: f32,
+    pub vegetation_health: f32,
+    pub transition_pressure: f32,
+    pub ticks_in_current_biome: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLayerV1 {
+    pub resources: Vec<ResourceDepositV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceDepositV1 {
+    pub resource_type: String,
+    pub quantity: f32,
+    pub max_quantity: f32,
+    pub renewal_rate: f32,
+    pub requires_biome: Option<Vec<BiomeType>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherLayerV1 {
+    pub temperature: f32,
+    pub precipitation: f32,
+    pub precipitation_type: PrecipitationType,
+    pub wind_speed: f32,
+    pub wind_direction: f32,
+    pub cloud_cover: f32,
+    pub humidity: f32,
+    pub storm_intensity: f32,
+    pub pressure: f32,
+    pub macro_wind_speed: f32,
+    pub macro_wind_direction: f32,
+    pub macro_humidity: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionsLayerV1 {
+    pub soil_moisture: f32,
+    pub snow_depth: f32,
+    pub mud_level: f32,
+    pub flood_level: f32,
+    pub frost_days: u32,
+    pub drought_days: u32,
+    pub fire_risk: f32,
+}
+
+impl From<WorldV1> for World {
+    fn from(v1: WorldV1) -> Self {
+        let seed = v1.generation_params.seed;
+        World {
+            id: v1.id,
+            name: v1.name,
+            created_at: v1.created_at,
+            tick_count: v1.tick_count,
+            season: v1.season,
+            season_length: v1.season_length,
+            tile_count: v1.tile_count,
+            topology_type: v1.topology_type,
+            generation_params: v1.generation_params.into(),
+            snapshot_path: v1.snapshot_path,
+            tiles: v1.tiles.into_iter().map(Tile::from).collect(),
+            macro_weather: v1.macro_weather.into(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: SpaceWeatherState::with_seed(seed),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
+        }
+    }
+}
+
+impl From<GenerationParamsV1> for GenerationParams {
+    fn from(v1: GenerationParamsV1) -> Self {
+        GenerationParams {
+            seed: v1.seed,
+            tile_count: v1.tile_count,
+            ocean_ratio: v1.ocean_ratio,
+            mountain_ratio: v1.mountain_ratio,
+            elevation_roughness: v1.elevation_roughness,
+            climate_bands: v1.climate_bands,
+            resource_density: v1.resource_density,
+            initial_biome_maturity: v1.initial_biome_maturity,
+            topology: TopologyConfig {
+                mode: v1.topology.mode,
+                subdivision_level: v1.topology.subdivision_level,
+                ..TopologyConfig::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl From<MacroWeatherStateV1> for MacroWeatherState {
+    fn from(v1: MacroWeatherStateV1) -> Self {
+        MacroWeatherState {
+            systems: v1.systems.into_iter().map(PressureSystem::from).collect(),
+            next_id: v1.next_id,
+            rng_state: v1.rng_state,
+            ..MacroWeatherState::default()
+        }
+    }
+}
+
+impl From<PressureSystemV1> for PressureSystem {
+    fn from(v1: PressureSystemV1) -> Self {
+        PressureSystem {
+            id: v1.id,
+            lat: v1.lat,
+            lon: v1.lon,
+            x: v1.x,
+            y: v1.y,
+            z: v1.z,
+            pressure_anomaly: v1.pressure_anomaly,
+            radius: v1.radius,
+            velocity_east: v1.velocity_east,
+            velocity_north: v1.velocity_north,
+            age: v1.age,
+            max_age: v1.max_age,
+            system_type: v1.system_type,
+            moisture: v1.moisture,
+        }
+    }
+}
+
+impl From<TileV1> for Tile {
+    fn from(v1: TileV1) -> Self {
+        let p = v1.position;
+        let mut tile = Tile::new_default(v1.id, v1.neighbors, Position { x: p.x, y: p.y, z: p.z, lat: p.lat, lon: p.lon });
+        let g = v1.geology;
+        tile.geology = GeologyLayer {
+            terrain_type: g.terrain_type,
+            elevation: g.elevation,
+            soil_type: g.soil_type,
+            drainage: g.drainage,
+            tectonic_stress: g.tectonic_stress,
+            ..tile.geology
+        };
+        let c = v1.climate;
+        tile.climate = ClimateLayer {
+            zone: c.zone,
+            base_temperature: c.base_temperature,
+            base_precipitation: c.base_precipitation,
+            latitude: c.latitude,
+        };
+        let b = v1.biome;
+        tile.biome = BiomeLayer {
+            biome_type: b.biome_type,
+            vegetation_density: b.vegetation_density,
+            vegetation_health: b.vegetation_health,
+            transition_pressure: b.transition_pressure,
+            ticks_in_current_biome: b.ticks_in_current_biome,
+            ..tile.biome
+        };
+        // Deposits from before quality grades and surveys are average and known
+        tile.resources = ResourceLayer {
+            resources: v1
+                .resources
+                .resources
+                .into_iter()
+                .map(|r| ResourceDeposit {
+                    resource_type: r.resource_type,
+                    quantity: r.quantity,
+                    max_quantity: r.max_quantity,
+                    renewal_rate: r.renewal_rate,
+                    requires_biome: r.requires_biome,
+                    quality: 0.5,
+                    discovered: true,
+                })
+                .collect(),
+        };
+        let w = v1.weather;
+        tile.weather = WeatherLayer {
+            temperature: w.temperature,
+            precipitation: w.precipitation,
+            precipitation_type: w.precipitation_type,
+            wind_speed: w.wind_speed,
+            wind_direction: w.wind_direction,
+            cloud_cover: w.cloud_cover,
+            humidity: w.humidity,
+            storm_intensity: w.storm_intensity,
+            pressure: w.pressure,
+            station_pressure: crate::simulation::macro_weather::station_pressure(
+                w.pressure,
+                tile.geology.elevation,
+                w.temperature,
+            ),
+            macro_wind_speed: w.macro_wind_speed,
+            macro_wind_direction: w.macro_wind_direction,
+            macro_humidity: w.macro_humidity,
+            ..tile.weather
+        };
+        let k = v1.conditions;
+        tile.conditions = ConditionsLayer {
+            soil_moisture: k.soil_moisture,
+            snow_depth: k.snow_depth,
+            mud_level: k.mud_level,
+            flood_level: k.flood_level,
+            frost_days: k.frost_days,
+            drought_days: k.drought_days,
+            fire_risk: k.fire_risk,
+            ..tile.conditions
+        };
+        tile
+    }
+}
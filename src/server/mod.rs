@@ -1,30 +1,405 @@
 pub mod protocol;
 
+use std::collections::{HashMap, VecDeque};
 use std::net::SocketAddr;
-use std::sync::Arc;
-
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, RwLock};
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
+use tokio::sync::{broadcast, oneshot, RwLock};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{error, info, warn};
 
+use crate::config::palette::Palette;
+use crate::error::WorldgroundError;
+use crate::simulation::assimilation::Observation;
+use crate::simulation::engine::{MutationStats, RuleStats};
+use crate::simulation::invariants::InvariantViolation;
+use crate::simulation::narration::Narration;
 use crate::simulation::statistics::TickStatistics;
 use crate::world::tile::Season;
-use crate::world::Tile;
+use crate::world::tile::BiomeType;
+use crate::world::{Bookmarks, EnergyPotential, LayerArrays, Tile, World, WorldRecords};
 use crate::world::weather_systems::PressureSystem;
 use protocol::{
-    compute_tile_diffs, HealthStatus, PressureSystemSnapshot, TickDiff, TickStatSummary,
-    WorldSnapshot,
+    compute_tile_diffs, BookmarksStatus, BookmarksUpdate, ClientInfo, ClientList, ClientMessage, ControlAction,
+    ControlRequest, ControlStatus, Events, FrozenTilesStatus, FrozenTilesUpdate, HealthState, HealthStatus, MutationMetrics,
+    ObservationsRequest, ObservationsStatus, PressureSystemSnapshot, RuleExecution, RuleRejections, ServerMessage, Subscription, SurveyRequest, SurveyStatus, TickDiff,
+    TickStatSummary, TileSnapshot, TourEnd, TourRequest, TourStep, WireFormat, WorldEvent, WorldSnapshot,
 };
+use worldground_protocol::units::{InUnits, Units};
 
 /// Shared server state accessible from all connection handlers and the simulation loop.
 pub struct ServerState {
     /// Current world snapshot message (JSON string, ready to send).
     pub snapshot_json: RwLock<String>,
     /// Broadcast channel for tick diffs.
-    pub tick_sender: broadcast::Sender<String>,
+    pub tick_sender: broadcast::Sender<Arc<Broadcast>>,
+    /// Deflate level for clients that ask for compression; `None` sends
+    /// plain text to everyone.
+    compression: Option<u32>,
+    /// Ping schedule for reaping dead connections; `None` never pings.
+    heartbeat: Option<Heartbeat>,
+    /// Set SO_REUSEPORT on the listener so a handoff successor can bind the
+    /// port while this process is still running.
+    reuse_port: bool,
+    /// How long to keep retrying a bind while the port is still held, as a
+    /// handoff successor does while its predecessor exits.
+    bind_wait: Option<Duration>,
+    /// Limits `/health` judges the simulation by; `None` always reports ok.
+    health_limits: Option<HealthLimits>,
+    /// Colors, labels and legends for `/api/palette`.
+    palette: Palette,
+    /// Units of temperatures and humidities in everything sent out.
+    units: Units,
+    /// Connections dropped because they stopped answering pings.
+    reaped_clients: AtomicU64,
     /// Health data updated each tick.
     pub health: RwLock<HealthData>,
+    /// Statistics for each connected WebSocket client, keyed by connection ID.
+    pub clients: RwLock<HashMap<u64, ClientStats>>,
+    next_client_id: AtomicU64,
+    /// Pending handoff: the simulation loop sends the serialized world here and stops.
+    handoff_request: std::sync::Mutex<Option<oneshot::Sender<Vec<u8>>>>,
+    /// Frozen tile IDs as of the last tick, less inert ones (mirrors `World::protected_tiles`).
+    pub frozen_tiles: RwLock<Vec<u32>>,
+    /// Freeze/thaw requests waiting for the simulation loop to apply them.
+    frozen_updates: std::sync::Mutex<Vec<FrozenTilesUpdate>>,
+    /// Tiles waiting for the simulation loop to survey their deposits.
+    survey_requests: std::sync::Mutex<Vec<u32>>,
+    /// Observed weather waiting for the simulation loop to start nudging toward it.
+    observations: std::sync::Mutex<Vec<Observation>>,
+    /// Rule mutation acceptance/rejection counts for the metrics endpoint.
+    pub metrics: RwLock<MetricsData>,
+    /// World records as of the last tick (mirrors `World::records`).
+    pub records: RwLock<WorldRecords>,
+    /// Wind and solar potential as of the last tick (mirrors `World::energy`).
+    pub energy: RwLock<EnergyPotential>,
+    /// Statistics of the last tick, for `/statistics`.
+    pub statistics: RwLock<Option<TickStatSummary>>,
+    /// Pressure systems as of the last tick (mirrors `World::macro_weather`).
+    pub pressure_systems: RwLock<Vec<PressureSystemSnapshot>>,
+    /// Tile queries waiting for the simulation loop to answer them.
+    tile_queries: std::sync::Mutex<Vec<(TileQuery, oneshot::Sender<Option<String>>)>>,
+    /// Most recent narrations, oldest first.
+    pub narrations: RwLock<VecDeque<Narration>>,
+    /// Bookmarks and tours as of the last tick (mirrors `World::bookmarks`).
+    pub bookmarks: RwLock<Bookmarks>,
+    /// Bookmark edits waiting for the simulation loop to apply them.
+    bookmark_updates: std::sync::Mutex<Vec<BookmarksUpdate>>,
+    /// Name and playback task of the tour being shown to viewers.
+    tour: std::sync::Mutex<Option<(String, tokio::task::JoinHandle<()>)>>,
+    /// Pause and single-step requests for the simulation loop.
+    control: std::sync::Mutex<SimControl>,
+    /// Wakes a paused simulation loop when `control` changes.
+    control_changed: tokio::sync::Notify,
+    /// Set when the server is shutting down, so every connection closes.
+    closing: tokio::sync::watch::Sender<bool>,
+}
+
+/// Whether the simulation loop may run its next tick.
+#[derive(Debug, Default)]
+struct SimControl {
+    paused: bool,
+    /// Ticks the loop may still run while paused.
+    pending_steps: u32,
+    /// Ticks the loop should undo before anything else.
+    pending_rollback: u32,
+    /// Ticks the loop holds checkpoints for.
+    rollback_depth: u64,
+}
+
+/// A tile lookup from `/tiles`, answered by the simulation loop between ticks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TileQuery {
+    /// One tile by ID.
+    Id(u32),
+    /// Every active tile, or only those of one biome.
+    Filter { biome: Option<BiomeType> },
+}
+
+impl TileQuery {
+    /// The JSON response body, with tiles in `units`, or `None` when no
+    /// active tile has the requested ID. Inert tiles are not part of the
+    /// simulation and never match.
+    pub fn answer(&self, world: &World, units: Units) -> Option<String> {
+        match self {
+            TileQuery::Id(id) => {
+                let tile = world.tiles.get(*id as usize).filter(|t| !world.is_inert(t.id))?;
+                Some(
+                    serde_json::json!({
+                        "tick": world.tick_count,
+                        "tile": TileSnapshot::from_tile(tile).in_units(units),
+                    })
+                    .to_string(),
+                )
+            }
+            TileQuery::Filter { biome } => {
+                let tiles: Vec<TileSnapshot> = world
+                    .tiles
+                    .iter()
+                    .filter(|t| !world.is_inert(t.id))
+                    .filter(|t| biome.is_none_or(|b| t.biome.biome_type == b))
+                    .map(|t| TileSnapshot::from_tile(t).in_units(units))
+                    .collect();
+                Some(
+                    serde_json::json!({
+                        "tick": world.tick_count,
+                        "count": tiles.len(),
+                        "tiles": tiles,
+                    })
+                    .to_string(),
+                )
+            }
+        }
+    }
+}
+
+/// Mutation and rule execution statistics for the latest tick and since startup.
+#[derive(Default)]
+pub struct MetricsData {
+    pub tick: u64,
+    pub last_tick: MutationStats,
+    pub totals: MutationStats,
+    pub rules_last_tick: RuleStats,
+    pub rules_total: RuleStats,
+}
+
+/// Live statistics for a single WebSocket connection.
+pub struct ClientStats {
+    pub peer: SocketAddr,
+    pub connected_at: Instant,
+    pub bytes_sent: u64,
+    pub messages_sent: u64,
+    /// Number of times the client fell behind the broadcast channel.
+    pub lag_events: u64,
+    /// Total diffs skipped because of lag.
+    pub diffs_dropped: u64,
+    /// Subscription filters requested by the client (empty = everything).
+    pub filters: Vec<String>,
+    /// Whether messages go out deflated.
+    pub compressed: bool,
+    /// How messages are encoded.
+    pub format: WireFormat,
+}
+
+/// How often clients are pinged and how long they may stay silent.
+///
+/// Any frame from the client counts as a sign of life, not just pongs. A
+/// client silent for longer than `timeout`, or one that stops reading for
+/// that long so sends stall, is disconnected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heartbeat {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+/// When `/health` calls the simulation degraded or unhealthy. Each limit is
+/// a pair: the degraded one, then the unhealthy one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthLimits {
+    /// Ticks per second the simulation must be able to run, below which it
+    /// falls behind its schedule
+    pub tick_rate: (f32, f32),
+    /// Share of tiles whose rules failed on the last tick
+    pub rule_error_ratio: (f32, f32),
+    /// Ticks since the last snapshot
+    pub snapshot_age_ticks: (u64, u64),
+}
+
+/// WebSocket URL query that asks for deflated messages.
+pub const COMPRESSION_QUERY: &str = "compression=deflate";
+
+/// WebSocket URL query that asks for MessagePack messages.
+pub const MSGPACK_QUERY: &str = "format=msgpack";
+
+/// How messages to one client are encoded: the wire format, deflated at a
+/// compression level or sent as is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Encoding {
+    pub format: WireFormat,
+    pub compression: Option<u32>,
+}
+
+/// A message broadcast to every client. It may start out as JSON or as a
+/// typed message; each other form is built by the first client that needs
+/// it and shared with the rest, so a server with only MessagePack clients
+/// never encodes JSON.
+pub struct Broadcast {
+    json: OnceLock<String>,
+    /// The message as a typed value, `None` if the JSON isn't a server message.
+    message: OnceLock<Option<ServerMessage>>,
+    msgpack: OnceLock<Vec<u8>>,
+    /// Deflated JSON and deflated MessagePack
+    deflated: [OnceLock<Vec<u8>>; 2],
+}
+
+impl Broadcast {
+    pub fn new(json: String) -> Self {
+        Broadcast { json: OnceLock::from(json), message: OnceLock::new(), msgpack: OnceLock::new(), deflated: Default::default() }
+    }
+
+    pub fn from_message(message: ServerMessage) -> Self {
+        Broadcast { json: OnceLock::new(), message: OnceLock::from(Some(message)), msgpack: OnceLock::new(), deflated: Default::default() }
+    }
+
+    /// The message as JSON text.
+    pub fn json(&self) -> &str {
+        self.json.get_or_init(|| match self.server_message() {
+            Some(message) => serde_json::to_string(message).unwrap_or_else(|_| "{}".to_string()),
+            None => "{}".to_string(),
+        })
+    }
+
+    /// The message as a typed value, or `None` if it isn't a server
+    /// message. Parsed at most once, however many clients need it.
+    pub fn server_message(&self) -> Option<&ServerMessage> {
+        self.message.get_or_init(|| serde_json::from_str(self.json()).ok()).as_ref()
+    }
+
+    /// The message as a tick diff, or `None` for any other message.
+    pub fn tick_diff(&self) -> Option<&TickDiff> {
+        match self.server_message() {
+            Some(ServerMessage::TickDiff(diff)) => Some(diff),
+            _ => None,
+        }
+    }
+
+    /// The message as MessagePack. Anything that isn't a server message is
+    /// carried over from its JSON as is.
+    pub fn msgpack(&self) -> &[u8] {
+        self.msgpack.get_or_init(|| match self.server_message() {
+            Some(message) => protocol::to_msgpack(message),
+            None => serde_json::from_str::<serde_json::Value>(self.json())
+                .map(|value| protocol::to_msgpack(&value))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// The message in `format`, as raw DEFLATE (RFC 1951) at `level`.
+    pub fn deflated(&self, format: WireFormat, level: u32) -> &[u8] {
+        match format {
+            WireFormat::Json => self.deflated[0].get_or_init(|| deflate(self.json().as_bytes(), level)),
+            WireFormat::Msgpack => self.deflated[1].get_or_init(|| deflate(self.msgpack(), level)),
+        }
+    }
+
+    /// The WebSocket message for a client: JSON goes out as text and
+    /// MessagePack as binary, and anything deflated as binary.
+    pub fn message(&self, encoding: Encoding) -> Message {
+        match (encoding.format, encoding.compression) {
+            (format, Some(level)) => Message::Binary(self.deflated(format, level).to_vec().into()),
+            (WireFormat::Json, None) => Message::Text(self.json().to_string().into()),
+            (WireFormat::Msgpack, None) => Message::Binary(self.msgpack().to_vec().into()),
+        }
+    }
+
+    /// The WebSocket message for a client with a subscription: a tick diff
+    /// cut down to the subscribed tiles and layers, events to the subscribed
+    /// tiles, anything else unchanged.
+    pub fn message_for(&self, encoding: Encoding, subscription: &Subscription) -> Message {
+        if subscription.is_everything() {
+            return self.message(encoding);
+        }
+        match self.server_message() {
+            Some(ServerMessage::TickDiff(diff)) => {
+                Broadcast::from_message(ServerMessage::TickDiff(subscription.filter(diff))).message(encoding)
+            }
+            Some(ServerMessage::Events(events)) => {
+                Broadcast::from_message(ServerMessage::Events(subscription.filter_events(events))).message(encoding)
+            }
+            _ => self.message(encoding),
+        }
+    }
+}
+
+impl InUnits for Broadcast {
+    /// Convert a message built in the simulation's units. Anything that
+    /// isn't a server message is passed on as is.
+    fn in_units(self, units: Units) -> Self {
+        if units.is_default() {
+            return self;
+        }
+        match self.server_message() {
+            Some(message) => Broadcast::from_message(message.clone().in_units(units)),
+            None => self,
+        }
+    }
+}
+
+impl From<String> for Broadcast {
+    fn from(json: String) -> Self {
+        Broadcast::new(json)
+    }
+}
+
+impl From<ServerMessage> for Broadcast {
+    fn from(message: ServerMessage) -> Self {
+        Broadcast::from_message(message)
+    }
+}
+
+/// How a subscription shows up in `/api/clients`; empty for everything.
+fn subscription_filters(subscription: &Subscription) -> Vec<String> {
+    let region = subscription.region.as_ref().map(|ids| format!("region: {} tiles", ids.len()));
+    let layers = subscription.layers.as_ref().map(|layers| {
+        let names: Vec<String> = layers
+            .iter()
+            .filter_map(|l| serde_json::to_value(l).ok()?.as_str().map(str::to_string))
+            .collect();
+        format!("layers: {}", names.join(", "))
+    });
+    let every = subscription.every.filter(|&n| n > 1).map(|n| format!("every {n} ticks"));
+    region.into_iter().chain(layers).chain(every).collect()
+}
+
+/// Tick diffs held back for a client subscribed to one every N ticks.
+#[derive(Default)]
+struct Cadence {
+    pending: Option<TickDiff>,
+    last_sent: Option<u64>,
+}
+
+impl Cadence {
+    /// Whether diffs are held back, waiting to be merged into the next.
+    fn is_holding(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// The diff to send now, merged with those held back since the last, or
+    /// `None` until one is due: the first right away, then one each time the
+    /// tick crosses a multiple of `every`.
+    fn offer(&mut self, diff: &TickDiff, every: u32) -> Option<TickDiff> {
+        let merged = match self.pending.take() {
+            Some(mut pending) => {
+                pending.merge(diff.clone());
+                pending
+            }
+            None => diff.clone(),
+        };
+        let every = u64::from(every.max(1));
+        if self.last_sent.is_none_or(|last| merged.tick / every > last / every) {
+            self.last_sent = Some(merged.tick);
+            Some(merged)
+        } else {
+            self.pending = Some(merged);
+            None
+        }
+    }
+}
+
+/// Compress a message as raw DEFLATE, which browsers decode with
+/// `DecompressionStream("deflate-raw")`.
+pub fn deflate(data: &[u8], level: u32) -> Vec<u8> {
+    let mut encoder =
+        flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::new(level.min(9)));
+    // Writing to a Vec cannot fail
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
 }
 
 /// Data needed for the health endpoint.
@@ -36,8 +411,25 @@ pub struct HealthData {
     pub rule_errors: u32,
     pub last_snapshot_tick: u64,
     pub recent_tick_durations_ms: Vec<f32>,
+    /// How late each recent tick started against the tick-rate schedule.
+    pub recent_jitter_ms: Vec<f32>,
+    /// Ticks that took longer than their slot since startup.
+    pub overrun_ticks: u64,
+    /// Length of the current run of overrunning ticks.
+    pub consecutive_overruns: u32,
+    pub invariant_violations: u64,
+    pub failing_invariants: Vec<String>,
 }
 
+/// Overrunning ticks in a row after which the overruns count as sustained.
+pub const SUSTAINED_OVERRUN_TICKS: u32 = 10;
+
+/// Narrations kept for `/api/narration`.
+pub const NARRATION_HISTORY: usize = 20;
+
+/// How long shutdown waits for WebSocket clients to be sent a close frame.
+pub const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
 impl HealthData {
     pub fn tick_rate(&self) -> f32 {
         if self.recent_tick_durations_ms.is_empty() {
@@ -50,6 +442,59 @@ impl HealthData {
         }
         1000.0 / avg_ms
     }
+
+    /// Start-time jitter (ms) at percentile `p` (0.0-1.0) over recent ticks.
+    pub fn jitter_percentile(&self, p: f32) -> f32 {
+        if self.recent_jitter_ms.is_empty() {
+            return 0.0;
+        }
+        let mut sorted = self.recent_jitter_ms.clone();
+        sorted.sort_by(f32::total_cmp);
+        let rank = (p.clamp(0.0, 1.0) * sorted.len() as f32).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    /// The health verdict under `limits`, with a reason for each limit
+    /// crossed. Nothing is judged before the first tick.
+    pub fn assess(&self, limits: &HealthLimits) -> (HealthState, Vec<String>) {
+        let mut verdict = HealthState::Ok;
+        let mut reasons = Vec::new();
+        if self.recent_tick_durations_ms.is_empty() {
+            return (verdict, reasons);
+        }
+        // The worse of the two limits crossed, if any
+        let crossed = |degraded: bool, unhealthy: bool| {
+            if unhealthy {
+                Some(HealthState::Unhealthy)
+            } else if degraded {
+                Some(HealthState::Degraded)
+            } else {
+                None
+            }
+        };
+
+        // Ticks replayed from snapshots aren't timed, so their rate is 0
+        let rate = self.tick_rate();
+        let (degraded, unhealthy) = limits.tick_rate;
+        if let Some(level) = crossed(rate > 0.0 && rate < degraded, rate > 0.0 && rate < unhealthy) {
+            let limit = if level == HealthState::Unhealthy { unhealthy } else { degraded };
+            verdict = verdict.max(level);
+            reasons.push(format!("tick rate {:.2}/s is below {:.2}/s", rate, limit));
+        }
+        let errors = self.rule_errors as f32 / self.tile_count.max(1) as f32;
+        let (degraded, unhealthy) = limits.rule_error_ratio;
+        if let Some(level) = crossed(errors > degraded, errors > unhealthy) {
+            verdict = verdict.max(level);
+            reasons.push(format!("rules failed on {:.1}% of tiles last tick", errors * 100.0));
+        }
+        let age = self.tick.saturating_sub(self.last_snapshot_tick);
+        let (degraded, unhealthy) = limits.snapshot_age_ticks;
+        if let Some(level) = crossed(age > degraded, age > unhealthy) {
+            verdict = verdict.max(level);
+            reasons.push(format!("last snapshot was {} ticks ago", age));
+        }
+        (verdict, reasons)
+    }
 }
 
 impl ServerState {
@@ -58,6 +503,14 @@ impl ServerState {
         ServerState {
             snapshot_json: RwLock::new(initial_snapshot_json),
             tick_sender: tx,
+            compression: None,
+            heartbeat: None,
+            reuse_port: false,
+            bind_wait: None,
+            health_limits: None,
+            palette: Palette::default(),
+            units: Units::default(),
+            reaped_clients: AtomicU64::new(0),
             health: RwLock::new(HealthData {
                 tick: 0,
                 season: Season::Spring,
@@ -66,16 +519,558 @@ impl ServerState {
                 rule_errors: 0,
                 last_snapshot_tick: 0,
                 recent_tick_durations_ms: Vec::new(),
+                recent_jitter_ms: Vec::new(),
+                overrun_ticks: 0,
+                consecutive_overruns: 0,
+                invariant_violations: 0,
+                failing_invariants: Vec::new(),
             }),
+            clients: RwLock::new(HashMap::new()),
+            next_client_id: AtomicU64::new(1),
+            handoff_request: std::sync::Mutex::new(None),
+            frozen_tiles: RwLock::new(Vec::new()),
+            frozen_updates: std::sync::Mutex::new(Vec::new()),
+            survey_requests: std::sync::Mutex::new(Vec::new()),
+            observations: std::sync::Mutex::new(Vec::new()),
+            metrics: RwLock::new(MetricsData::default()),
+            records: RwLock::new(WorldRecords::default()),
+            energy: RwLock::new(EnergyPotential::default()),
+            statistics: RwLock::new(None),
+            pressure_systems: RwLock::new(Vec::new()),
+            tile_queries: std::sync::Mutex::new(Vec::new()),
+            narrations: RwLock::new(VecDeque::new()),
+            bookmarks: RwLock::new(Bookmarks::default()),
+            bookmark_updates: std::sync::Mutex::new(Vec::new()),
+            tour: std::sync::Mutex::new(None),
+            control: std::sync::Mutex::new(SimControl::default()),
+            control_changed: tokio::sync::Notify::new(),
+            closing: tokio::sync::watch::Sender::new(false),
+        }
+    }
+
+    /// Record a tick's mutation statistics for the metrics endpoint.
+    pub async fn record_mutation_stats(&self, tick: u64, stats: &MutationStats) {
+        let mut metrics = self.metrics.write().await;
+        metrics.tick = tick;
+        metrics.last_tick = stats.clone();
+        metrics.totals.merge(stats.clone());
+    }
+
+    /// Record a tick's rule execution statistics for the metrics endpoint.
+    pub async fn record_rule_stats(&self, stats: &RuleStats) {
+        let mut metrics = self.metrics.write().await;
+        metrics.rules_total.merge(stats);
+        metrics.rules_last_tick = stats.clone();
+    }
+
+    /// Snapshot of mutation metrics, one entry per rule and reason seen since
+    /// startup, plus each rule's execution counts and time, slowest first.
+    pub async fn mutation_metrics(&self) -> MutationMetrics {
+        let metrics = self.metrics.read().await;
+        let rejections = metrics
+            .totals
+            .rejected
+            .iter()
+            .map(|(key, &total)| RuleRejections {
+                rule: key.0.clone(),
+                reason: key.1.as_str(),
+                last_tick: metrics.last_tick.rejected.get(key).copied().unwrap_or(0),
+                total,
+            })
+            .collect();
+        let rules = metrics
+            .rules_total
+            .slowest()
+            .into_iter()
+            .map(|(rule, total)| RuleExecution {
+                rule: rule.to_string(),
+                invocations: total.invocations,
+                errors: total.errors,
+                total_ms: total.time.as_secs_f64() * 1000.0,
+                last_tick_ms: metrics
+                    .rules_last_tick
+                    .rules
+                    .get(rule)
+                    .map_or(0.0, |m| m.time.as_secs_f64() * 1000.0),
+                mean_us: total.time.as_secs_f64() * 1e6 / total.invocations.max(1) as f64,
+            })
+            .collect();
+        MutationMetrics {
+            tick: metrics.tick,
+            mutations_applied: metrics.last_tick.applied,
+            mutations_rejected: metrics.last_tick.total_rejected(),
+            total_applied: metrics.totals.applied,
+            total_rejected: metrics.totals.total_rejected(),
+            rejections,
+            rules,
+        }
+    }
+
+    /// Record the outcome of an invariant check for the health endpoint.
+    pub async fn record_invariant_check(&self, violations: &[InvariantViolation]) {
+        let mut health = self.health.write().await;
+        health.invariant_violations += violations.len() as u64;
+        health.failing_invariants = violations.iter().map(|v| v.invariant.clone()).collect();
+    }
+
+    /// Publish the world's records for the records endpoint.
+    pub async fn set_records(&self, records: &WorldRecords) {
+        *self.records.write().await = records.clone();
+    }
+
+    /// Publish the world's wind and solar potential for the energy endpoint.
+    pub async fn set_energy(&self, energy: &EnergyPotential) {
+        self.energy.write().await.clone_from(energy);
+    }
+
+    /// Publish the world's pressure systems for the pressure systems endpoint.
+    pub async fn set_pressure_systems(&self, systems: &[PressureSystem]) {
+        *self.pressure_systems.write().await = systems.iter().map(Into::into).collect();
+    }
+
+    /// Whether tile queries are waiting for `answer_tile_queries`.
+    pub fn has_tile_queries(&self) -> bool {
+        !self.tile_queries.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+    }
+
+    /// Answer all queued tile queries against the world as it is now.
+    /// Returns how many were answered.
+    pub fn answer_tile_queries(&self, world: &World) -> usize {
+        let queries = std::mem::take(&mut *self.tile_queries.lock().unwrap_or_else(|e| e.into_inner()));
+        let count = queries.len();
+        for (query, reply) in queries {
+            // The requester may have timed out and gone
+            let _ = reply.send(query.answer(world, self.units));
+        }
+        count
+    }
+
+    /// Keep a narration for the narration endpoint, dropping the oldest past
+    /// `NARRATION_HISTORY`.
+    pub async fn add_narration(&self, narration: Narration) {
+        let mut narrations = self.narrations.write().await;
+        narrations.push_back(narration);
+        while narrations.len() > NARRATION_HISTORY {
+            narrations.pop_front();
+        }
+    }
+
+    /// Queue a freeze/thaw request for the simulation loop.
+    pub fn queue_frozen_update(&self, update: FrozenTilesUpdate) {
+        self.frozen_updates
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(update);
+    }
+
+    /// Take all queued freeze/thaw requests, oldest first.
+    pub fn take_frozen_updates(&self) -> Vec<FrozenTilesUpdate> {
+        std::mem::take(&mut *self.frozen_updates.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Publish the world's current frozen set for the API.
+    pub async fn set_frozen_tiles(&self, tiles: &std::collections::BTreeSet<u32>) {
+        *self.frozen_tiles.write().await = tiles.iter().copied().collect();
+    }
+
+    /// Queue tiles for the simulation loop to survey.
+    pub fn queue_survey(&self, tiles: &[u32]) {
+        self.survey_requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(tiles);
+    }
+
+    /// Take all tiles queued for survey, oldest first.
+    pub fn take_survey_requests(&self) -> Vec<u32> {
+        std::mem::take(&mut *self.survey_requests.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Queue observations, in Kelvin and humidity fractions, for the
+    /// simulation loop to assimilate.
+    pub fn queue_observations(&self, observations: Vec<Observation>) {
+        self.observations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(observations);
+    }
+
+    /// Take all queued observations, oldest first.
+    pub fn take_observations(&self) -> Vec<Observation> {
+        std::mem::take(&mut *self.observations.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Queue a bookmark or tour edit for the simulation loop.
+    pub fn queue_bookmarks_update(&self, update: BookmarksUpdate) {
+        self.bookmark_updates
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(update);
+    }
+
+    /// Take all queued bookmark edits, oldest first.
+    pub fn take_bookmarks_updates(&self) -> Vec<BookmarksUpdate> {
+        std::mem::take(&mut *self.bookmark_updates.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Publish the world's bookmarks and tours for the API and tour playback.
+    pub async fn set_bookmarks(&self, bookmarks: &Bookmarks) {
+        self.bookmarks.write().await.clone_from(bookmarks);
+    }
+
+    /// Name of the tour currently playing, if any.
+    pub fn active_tour(&self) -> Option<String> {
+        let tour = self.tour.lock().unwrap_or_else(|e| e.into_inner());
+        tour.as_ref().filter(|(_, task)| !task.is_finished()).map(|(name, _)| name.clone())
+    }
+
+    /// Play a tour to every viewer: one `TourStep` per stop, each held for its
+    /// dwell time, then a `TourEnd`. Replaces any tour already playing.
+    /// Returns false if there is no such tour.
+    pub async fn start_tour(self: &Arc<Self>, name: &str) -> bool {
+        let Some(views) = self.bookmarks.read().await.tour_views(name) else {
+            return false;
+        };
+        self.stop_tour();
+
+        let state = Arc::clone(self);
+        let tour = name.to_string();
+        let task = tokio::spawn(async move {
+            let steps = views.len() as u32;
+            for (step, (bookmark, view, dwell_secs)) in views.into_iter().enumerate() {
+                state.broadcast_message(&ServerMessage::TourStep(TourStep {
+                    tour: tour.clone(),
+                    step: step as u32,
+                    steps,
+                    bookmark,
+                    view,
+                    dwell_secs,
+                }));
+                tokio::time::sleep(Duration::from_secs_f32(dwell_secs)).await;
+            }
+            state.broadcast_message(&ServerMessage::TourEnd(TourEnd { tour, completed: true }));
+        });
+        *self.tour.lock().unwrap_or_else(|e| e.into_inner()) = Some((name.to_string(), task));
+        info!(tour = %name, "Tour started");
+        true
+    }
+
+    /// Stop the tour that is playing, telling viewers with a `TourEnd`.
+    /// Returns the stopped tour's name.
+    pub fn stop_tour(&self) -> Option<String> {
+        let (name, task) = self.tour.lock().unwrap_or_else(|e| e.into_inner()).take()?;
+        if task.is_finished() {
+            return None;
+        }
+        task.abort();
+        self.broadcast_message(&ServerMessage::TourEnd(TourEnd { tour: name.clone(), completed: false }));
+        info!(tour = %name, "Tour stopped");
+        Some(name)
+    }
+
+    /// Send a tick's world events to every connected client.
+    pub fn broadcast_events(&self, tick: u64, events: &[WorldEvent]) {
+        self.broadcast_message(&ServerMessage::Events(Events { tick, events: events.to_vec() }));
+    }
+
+    /// Send a message to every connected client outside the tick cycle.
+    fn broadcast_message(&self, message: &ServerMessage) {
+        let _ = self.tick_sender.send(Arc::new(Broadcast::from_message(message.clone())));
+    }
+
+    /// Pause, resume, single-step or roll back the simulation loop.
+    /// Stepping pauses the loop once the requested ticks have run; rolling
+    /// back pauses it straight away, so the rules can be fixed before the
+    /// undone ticks run again. Fails if there aren't that many ticks to undo.
+    pub fn control(&self, request: ControlRequest) -> Result<(), WorldgroundError> {
+        {
+            let mut control = self.control.lock().unwrap_or_else(|e| e.into_inner());
+            match request.action {
+                ControlAction::Pause => {
+                    control.paused = true;
+                    control.pending_steps = 0;
+                }
+                ControlAction::Resume => {
+                    control.paused = false;
+                    control.pending_steps = 0;
+                }
+                ControlAction::Step => {
+                    control.paused = true;
+                    control.pending_steps = control.pending_steps.saturating_add(request.ticks);
+                }
+                ControlAction::Rollback => {
+                    let ticks = control.pending_rollback as u64 + request.ticks as u64;
+                    if request.ticks == 0 || ticks > control.rollback_depth {
+                        return Err(WorldgroundError::Server(format!(
+                            "can roll back 1-{} tick(s), not {}",
+                            control.rollback_depth.saturating_sub(control.pending_rollback as u64),
+                            request.ticks
+                        )));
+                    }
+                    control.paused = true;
+                    control.pending_steps = 0;
+                    control.pending_rollback = ticks as u32;
+                }
+            }
+        }
+        self.control_changed.notify_one();
+        Ok(())
+    }
+
+    /// Take the ticks a rollback request asked to undo, if any.
+    pub fn take_rollback(&self) -> Option<u32> {
+        let mut control = self.control.lock().unwrap_or_else(|e| e.into_inner());
+        Some(std::mem::take(&mut control.pending_rollback)).filter(|&ticks| ticks > 0)
+    }
+
+    /// Record how many ticks the loop holds checkpoints for.
+    pub fn set_rollback_depth(&self, ticks: u64) {
+        self.control.lock().unwrap_or_else(|e| e.into_inner()).rollback_depth = ticks;
+    }
+
+    /// Send every client the world as it stands after a rollback, as a new
+    /// snapshot, and report its tick.
+    pub async fn on_rollback(&self, world: &crate::world::World) {
+        let snapshot_json = self.snapshot_json_of(world);
+        *self.snapshot_json.write().await = snapshot_json.clone();
+        let _ = self.tick_sender.send(Arc::new(Broadcast::new(snapshot_json)));
+        let mut health = self.health.write().await;
+        health.tick = world.tick_count;
+        health.season = world.season;
+    }
+
+    /// Whether the simulation is paused (it may still have steps to run).
+    pub fn is_paused(&self) -> bool {
+        self.control.lock().unwrap_or_else(|e| e.into_inner()).paused
+    }
+
+    /// Current pause state, with the last completed tick.
+    pub async fn control_status(&self) -> ControlStatus {
+        let tick = self.health.read().await.tick;
+        let control = self.control.lock().unwrap_or_else(|e| e.into_inner());
+        ControlStatus {
+            tick,
+            paused: control.paused,
+            pending_steps: control.pending_steps,
+            rollback_ticks: control.rollback_depth.saturating_sub(control.pending_rollback as u64),
+        }
+    }
+
+    /// Whether the simulation loop may run a tick now. Uses up one pending
+    /// step while paused.
+    pub fn take_tick_permit(&self) -> bool {
+        let mut control = self.control.lock().unwrap_or_else(|e| e.into_inner());
+        if !control.paused {
+            return true;
+        }
+        if control.pending_steps == 0 {
+            return false;
+        }
+        control.pending_steps -= 1;
+        true
+    }
+
+    /// Wait until the pause state changes.
+    pub async fn control_changed(&self) {
+        self.control_changed.notified().await;
+    }
+
+    /// Whether a successor process has asked for the world.
+    pub fn handoff_requested(&self) -> bool {
+        self.handoff_request.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    /// Take a pending handoff request, if a successor process has asked for the world.
+    fn take_handoff_request(&self) -> Option<oneshot::Sender<Vec<u8>>> {
+        self.handoff_request
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// Send `world` to a successor process, if one has asked for it.
+    ///
+    /// The simulation loop calls this once per tick, after the tick's diff has been
+    /// broadcast. When it returns true the loop must stop ticking: the successor
+    /// continues from `world.tick_count`, so no diffs follow the handoff tick from
+    /// this process. Returns false when nobody asked, or when the world could not be
+    /// serialized and the simulation should keep running.
+    pub fn answer_handoff(&self, world: &World) -> bool {
+        let Some(handoff) = self.take_handoff_request() else {
+            return false;
+        };
+        match bincode::serialize(world) {
+            Ok(bytes) => {
+                let _ = handoff.send(bytes);
+                info!(tick = world.tick_count, "World handed off to successor");
+                true
+            }
+            Err(e) => {
+                warn!("Handoff serialization failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Offer deflated messages at `level` (0-9) to clients that ask for them.
+    pub fn with_compression(mut self, level: Option<u32>) -> Self {
+        self.compression = level;
+        self
+    }
+
+    /// Serve this palette at `/api/palette` instead of the built-in one.
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Send temperatures and humidities in `units` instead of Kelvin and
+    /// fractions.
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Units of temperatures and humidities in everything sent out; tick
+    /// diffs must be converted to them before they are broadcast.
+    pub fn units(&self) -> Units {
+        self.units
+    }
+
+    /// The snapshot message for `world`, in this server's units.
+    pub fn snapshot_json_of(&self, world: &crate::world::World) -> String {
+        build_snapshot_json_in(world, self.units)
+    }
+
+    /// Judge `/health` by these limits, answering 503 when unhealthy.
+    pub fn with_health_limits(mut self, limits: Option<HealthLimits>) -> Self {
+        self.health_limits = limits;
+        self
+    }
+
+    /// Ping clients on a schedule and drop the ones that stop responding.
+    pub fn with_heartbeat(mut self, heartbeat: Option<Heartbeat>) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    /// Share the port with a handoff successor (SO_REUSEPORT). Both processes
+    /// must enable it; without it a second server on the same port fails to bind.
+    pub fn with_reuse_port(mut self, reuse_port: bool) -> Self {
+        self.reuse_port = reuse_port;
+        self
+    }
+
+    /// Keep retrying the bind for up to `wait` while the port is in use.
+    pub fn with_bind_wait(mut self, wait: Option<Duration>) -> Self {
+        self.bind_wait = wait;
+        self
+    }
+
+    /// Register a new WebSocket client and return its connection ID.
+    pub async fn register_client(&self, peer: SocketAddr, encoding: Encoding) -> u64 {
+        let id = self.next_client_id.fetch_add(1, Ordering::Relaxed);
+        self.clients.write().await.insert(
+            id,
+            ClientStats {
+                peer,
+                connected_at: Instant::now(),
+                bytes_sent: 0,
+                messages_sent: 0,
+                lag_events: 0,
+                diffs_dropped: 0,
+                filters: Vec::new(),
+                compressed: encoding.compression.is_some(),
+                format: encoding.format,
+            },
+        );
+        id
+    }
+
+    /// Remove a client when its connection closes.
+    pub async fn unregister_client(&self, id: u64) {
+        self.clients.write().await.remove(&id);
+    }
+
+    /// Send every WebSocket client a close frame (1001, going away) and
+    /// wait up to `timeout` for the connections to end. Clients that
+    /// connect afterwards are closed as soon as they have their snapshot.
+    pub async fn close_clients(&self, timeout: Duration) {
+        self.closing.send_replace(true);
+        let deadline = tokio::time::Instant::now() + timeout;
+        while !self.clients.read().await.is_empty() && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let open = self.clients.read().await.len();
+        if open > 0 {
+            warn!(open, "Some WebSocket clients did not close in time");
+        }
+    }
+
+    /// Record a message sent to a client.
+    pub async fn record_client_send(&self, id: u64, bytes: usize) {
+        if let Some(c) = self.clients.write().await.get_mut(&id) {
+            c.bytes_sent += bytes as u64;
+            c.messages_sent += 1;
+        }
+    }
+
+    /// Record the subscription a client asked for, as filters for the clients endpoint.
+    pub async fn set_client_subscription(&self, id: u64, subscription: &Subscription) {
+        if let Some(c) = self.clients.write().await.get_mut(&id) {
+            c.filters = subscription_filters(subscription);
+        }
+    }
+
+    /// Record the wire format a client switched to.
+    pub async fn set_client_format(&self, id: u64, format: WireFormat) {
+        if let Some(c) = self.clients.write().await.get_mut(&id) {
+            c.format = format;
+        }
+    }
+
+    /// Record a client falling behind the broadcast channel by `dropped` diffs.
+    pub async fn record_client_lag(&self, id: u64, dropped: u64) {
+        if let Some(c) = self.clients.write().await.get_mut(&id) {
+            c.lag_events += 1;
+            c.diffs_dropped += dropped;
+        }
+    }
+
+    /// Snapshot of all connected clients, sorted by connection ID.
+    pub async fn client_list(&self) -> ClientList {
+        let clients = self.clients.read().await;
+        let mut infos: Vec<ClientInfo> = clients
+            .iter()
+            .map(|(&id, c)| ClientInfo {
+                id,
+                peer: c.peer.to_string(),
+                connected_secs: c.connected_at.elapsed().as_secs(),
+                bytes_sent: c.bytes_sent,
+                messages_sent: c.messages_sent,
+                lag_events: c.lag_events,
+                diffs_dropped: c.diffs_dropped,
+                filters: c.filters.clone(),
+                compressed: c.compressed,
+                format: c.format,
+            })
+            .collect();
+        infos.sort_by_key(|c| c.id);
+        ClientList {
+            client_count: infos.len(),
+            total_bytes_sent: infos.iter().map(|c| c.bytes_sent).sum(),
+            reaped_clients: self.reaped_clients.load(Ordering::Relaxed),
+            clients: infos,
         }
     }
 
     /// Update server state after a tick completes.
-    /// Called by the simulation loop with the new snapshot, diff, and statistics.
+    /// Called by the simulation loop with the new snapshot, diff (as JSON or
+    /// a typed message), and statistics.
     pub async fn on_tick(
         &self,
         new_snapshot_json: Option<String>,
-        diff_json: String,
+        diff: impl Into<Broadcast>,
         stats: &TickStatistics,
         tick: u64,
         season: Season,
@@ -89,9 +1084,21 @@ impl ServerState {
 
         // Broadcast diff to all connected clients
         // Ignore send error (no receivers is fine)
-        let _ = self.tick_sender.send(diff_json);
+        let _ = self.tick_sender.send(Arc::new(diff.into()));
 
-        // Update health data
+        self.update_health(stats, tick, season, tile_count, last_snapshot_tick).await;
+    }
+
+    /// Update health data after a tick without broadcasting anything
+    /// (used during warmup, when no diffs are sent).
+    pub async fn update_health(
+        &self,
+        stats: &TickStatistics,
+        tick: u64,
+        season: Season,
+        tile_count: u32,
+        last_snapshot_tick: u64,
+    ) {
         let mut health = self.health.write().await;
         health.tick = tick;
         health.season = season;
@@ -104,6 +1111,26 @@ impl ServerState {
         if health.recent_tick_durations_ms.len() > 100 {
             health.recent_tick_durations_ms.remove(0);
         }
+        drop(health);
+        *self.statistics.write().await = Some(TickStatSummary::from(stats).in_units(self.units));
+    }
+
+    /// Record how late a paced tick started against the schedule and whether
+    /// it ran past its slot. Returns true on the tick a run of overruns
+    /// reaches `SUSTAINED_OVERRUN_TICKS`.
+    pub async fn record_tick_timing(&self, jitter_ms: f32, overran: bool) -> bool {
+        let mut health = self.health.write().await;
+        health.recent_jitter_ms.push(jitter_ms);
+        if health.recent_jitter_ms.len() > 100 {
+            health.recent_jitter_ms.remove(0);
+        }
+        if overran {
+            health.overrun_ticks += 1;
+            health.consecutive_overruns += 1;
+        } else {
+            health.consecutive_overruns = 0;
+        }
+        health.consecutive_overruns == SUSTAINED_OVERRUN_TICKS
     }
 }
 
@@ -113,83 +1140,137 @@ pub fn build_diff_json(
     after_tiles: &[Tile],
     tick: u64,
     season: Season,
+    season_length: u32,
     stats: &TickStatistics,
     pressure_systems: &[PressureSystem],
 ) -> String {
     let changed_tiles = compute_tile_diffs(before_tiles, after_tiles);
     let diff = TickDiff {
-        message_type: "TickDiff",
         tick,
         season,
         changed_tiles,
-        statistics: TickStatSummary::from_statistics(stats),
-        pressure_systems: pressure_systems
-            .iter()
-            .map(PressureSystemSnapshot::from_system)
-            .collect(),
+        statistics: TickStatSummary::from(stats),
+        pressure_systems: pressure_systems.iter().map(Into::into).collect(),
+        sun: protocol::sun_snapshot(season, tick, season_length),
+        floods: Vec::new(),
+        aurora: None,
+        depletions: Vec::new(),
+        effects: Default::default(),
     };
-    serde_json::to_string(&diff).unwrap_or_else(|_| "{}".to_string())
+    serde_json::to_string(&ServerMessage::TickDiff(diff)).unwrap_or_else(|_| "{}".to_string())
 }
 
-/// Build the JSON diff from lightweight layer snapshots (avoids full tile clone).
+/// Build the JSON diff from layers captured before the tick (avoids full tile clone).
 pub fn build_diff_json_from_layers(
-    before_layers: &[(crate::world::tile::WeatherLayer, crate::world::tile::ConditionsLayer, crate::world::tile::BiomeLayer, crate::world::tile::ResourceLayer)],
-    after_tiles: &[Tile],
-    tick: u64,
-    season: Season,
+    before: &LayerArrays,
+    world: &crate::world::World,
     stats: &TickStatistics,
-    pressure_systems: &[PressureSystem],
+    floods: &[crate::simulation::flood::FloodEvent],
+    aurora: Option<crate::simulation::aurora::AuroraEvent>,
+    depletions: &[crate::simulation::native_resources::DepletionEvent],
+    effects: &crate::simulation::effects::TileEffects,
 ) -> String {
-    let mut changed_tiles = Vec::new();
-    for (i, tile) in after_tiles.iter().enumerate() {
-        if let Some((bw, bc, bb, br)) = before_layers.get(i) {
-            let weather_changed = *bw != tile.weather;
-            let conditions_changed = *bc != tile.conditions;
-            let biome_changed = *bb != tile.biome;
-            let resources_changed = *br != tile.resources;
-
-            if weather_changed || conditions_changed || biome_changed || resources_changed {
-                changed_tiles.push(protocol::TileChange {
-                    id: tile.id,
-                    weather: if weather_changed { Some(tile.weather.clone()) } else { None },
-                    conditions: if conditions_changed { Some(tile.conditions.clone()) } else { None },
-                    biome: if biome_changed { Some(tile.biome.clone()) } else { None },
-                    resources: if resources_changed { Some(tile.resources.clone()) } else { None },
-                });
-            }
-        }
-    }
-    let diff = protocol::TickDiff {
-        message_type: "TickDiff",
-        tick,
-        season,
+    let diff = build_diff_from_layers(before, world, stats, floods, aurora, depletions, effects);
+    serde_json::to_string(&diff).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Build the diff message from layers captured before the tick, leaving its
+/// encoding to the clients it is sent to. Only the captured layers are
+/// compared, so scratch is sent only when it was captured.
+pub fn build_diff_from_layers(
+    before: &LayerArrays,
+    world: &crate::world::World,
+    stats: &TickStatistics,
+    floods: &[crate::simulation::flood::FloodEvent],
+    aurora: Option<crate::simulation::aurora::AuroraEvent>,
+    depletions: &[crate::simulation::native_resources::DepletionEvent],
+    effects: &crate::simulation::effects::TileEffects,
+) -> ServerMessage {
+    // Inert tiles never change, so they are never sent
+    let changed_tiles = world
+        .tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !world.is_inert(t.id))
+        .filter_map(|(i, tile)| before.change(i, tile))
+        .collect();
+    let diff = TickDiff {
+        tick: world.tick_count,
+        season: world.season,
         changed_tiles,
-        statistics: protocol::TickStatSummary::from_statistics(stats),
-        pressure_systems: pressure_systems
-            .iter()
-            .map(protocol::PressureSystemSnapshot::from_system)
-            .collect(),
+        statistics: TickStatSummary::from(stats),
+        pressure_systems: world.macro_weather.systems.iter().map(Into::into).collect(),
+        sun: protocol::sun_snapshot(world.season, world.tick_count, world.season_length),
+        floods: floods.to_vec(),
+        aurora,
+        depletions: depletions.to_vec(),
+        effects: effects.to_wire(),
     };
-    serde_json::to_string(&diff).unwrap_or_else(|_ | "{}".to_string())
+    ServerMessage::TickDiff(diff)
 }
 
 /// Build the JSON snapshot message for a world.
 pub fn build_snapshot_json(world: &crate::world::World) -> String {
-    let snapshot = WorldSnapshot::from_world(world);
+    build_snapshot_json_in(world, Units::default())
+}
+
+/// Build the JSON snapshot message for a world, with temperatures and
+/// humidities in `units`.
+pub fn build_snapshot_json_in(world: &crate::world::World, units: Units) -> String {
+    let snapshot = ServerMessage::WorldSnapshot(WorldSnapshot::from(world).in_units(units));
     serde_json::to_string(&snapshot).unwrap_or_else(|_| "{}".to_string())
 }
 
+/// Bind the server socket. With `reuse_port` (unix only) the socket uses
+/// SO_REUSEPORT, so a successor process can bind the same port during a live
+/// handoff; without it a second server on the port gets `AddrInUse`.
+pub fn bind_listener(addr: SocketAddr, reuse_port: bool) -> std::io::Result<TcpListener> {
+    let socket = if addr.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    socket.set_reuseaddr(true)?;
+    #[cfg(unix)]
+    if reuse_port {
+        socket.set_reuseport(true)?;
+    }
+    #[cfg(not(unix))]
+    let _ = reuse_port;
+    socket.bind(addr)?;
+    socket.listen(1024)
+}
+
+/// Bind the server socket, retrying while the port is in use for up to
+/// `state.bind_wait`.
+async fn bind_with_wait(state: &ServerState, addr: SocketAddr) -> std::io::Result<TcpListener> {
+    let deadline = state.bind_wait.map(|wait| Instant::now() + wait);
+    loop {
+        match bind_listener(addr, state.reuse_port) {
+            Err(e)
+                if e.kind() == std::io::ErrorKind::AddrInUse
+                    && deadline.is_some_and(|d| Instant::now() < d) =>
+            {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Start the WebSocket + HTTP server on the given address.
 /// Returns a handle that can be used to stop the server.
-pub async fn start_server(
-    state: Arc<ServerState>,
-    addr: SocketAddr,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let listener = TcpListener::bind(addr).await?;
+pub async fn start_server(state: Arc<ServerState>, addr: SocketAddr) -> Result<(), WorldgroundError> {
+    let listener = bind_with_wait(&state, addr)
+        .await
+        .map_err(|e| WorldgroundError::Server(format!("Cannot listen on {}: {}", addr, e)))?;
     info!(%addr, "Server listening — viewer at http://{}", addr);
 
     loop {
-        let (stream, peer) = listener.accept().await?;
+        let (stream, peer) = listener
+            .accept()
+            .await
+            .map_err(|e| WorldgroundError::Server(format!("Cannot accept a connection: {}", e)))?;
         let state = Arc::clone(&state);
         tokio::spawn(async move {
             if let Err(e) = handle_connection(stream, peer, state).await {
@@ -199,24 +1280,77 @@ pub async fn start_server(
     }
 }
 
+/// Method, path and query string of an HTTP request line. The path is
+/// lowercased and loses any trailing slash.
+#[derive(Debug, PartialEq)]
+struct Route {
+    method: String,
+    path: String,
+    query: String,
+}
+
+impl Route {
+    fn parse(head: &str) -> Route {
+        let mut parts = head.lines().next().unwrap_or("").split_whitespace();
+        let method = parts.next().unwrap_or("").to_ascii_uppercase();
+        let target = parts.next().unwrap_or("/");
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let path = path.to_lowercase();
+        let path = match path.trim_end_matches('/') {
+            "" => "/".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+        Route { method, path, query: query.to_string() }
+    }
+
+    /// Value of a query string parameter.
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+}
+
 /// Handle an incoming TCP connection — route to WebSocket or HTTP.
 async fn handle_connection(
     stream: TcpStream,
     peer: SocketAddr,
     state: Arc<ServerState>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(), WorldgroundError> {
     // Peek at the first bytes to determine if this is a WebSocket upgrade or HTTP request
     let mut buf = [0u8; 512];
     let n = stream.peek(&mut buf).await?;
-    let request_line = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+    let head = String::from_utf8_lossy(&buf[..n]);
 
-    if request_line.contains("upgrade: websocket") {
-        handle_websocket(stream, peer, state).await
-    } else if request_line.contains("get /health") {
-        handle_health_request(stream, state).await
-    } else {
+    if head.to_lowercase().contains("upgrade: websocket") {
+        return handle_websocket(stream, peer, state).await;
+    }
+
+    let route = Route::parse(&head);
+    match (route.method.as_str(), route.path.as_str()) {
+        ("GET", "/health") => handle_health_request(stream, state).await,
+        ("GET", "/api/clients") => handle_clients_request(stream, state).await,
+        ("GET", "/api/metrics") => handle_metrics_request(stream, state).await,
+        ("GET", "/api/records") => handle_records_request(stream, state).await,
+        ("GET", "/api/energy") => handle_energy_request(stream, state).await,
+        ("GET", "/api/narration") => handle_narration_request(stream, state).await,
+        ("GET", "/api/palette") => handle_palette_request(stream, state).await,
+        ("GET", "/admin") => handle_admin_request(stream).await,
+        ("GET", "/api/handoff") => handle_handoff_request(stream, peer, state).await,
+        ("GET" | "POST", "/api/frozen") => handle_frozen_request(stream, peer, state).await,
+        ("POST", "/api/survey") => handle_survey_request(stream, peer, state).await,
+        ("POST", "/api/observations") => handle_observations_request(stream, peer, state).await,
+        ("GET" | "POST", "/api/bookmarks") => handle_bookmarks_request(stream, peer, state).await,
+        ("POST", path) if path.starts_with("/api/tours/") => handle_tour_request(stream, peer, state).await,
+        ("GET" | "POST", "/api/control") => handle_control_request(stream, peer, state).await,
+        ("GET", "/tiles") => handle_tiles_request(stream, &route, state).await,
+        ("GET", path) if path.starts_with("/tiles/") => handle_tiles_request(stream, &route, state).await,
+        ("GET", "/statistics") => handle_statistics_request(stream, state).await,
+        ("GET", "/pressure-systems") => handle_pressure_systems_request(stream, state).await,
         // Serve the viewer for any other HTTP request (GET /, GET /index.html, etc.)
-        handle_viewer_request(stream).await
+        _ => handle_viewer_request(stream).await,
     }
 }
 
@@ -225,31 +1359,115 @@ async fn handle_websocket(
     stream: TcpStream,
     peer: SocketAddr,
     state: Arc<ServerState>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let ws_stream = tokio_tungstenite::accept_async(stream).await?;
-    info!(%peer, "WebSocket connected");
+) -> Result<(), WorldgroundError> {
+    let (mut wants_compression, mut wants_msgpack) = (false, false);
+    // The error type is fixed by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let check_query = |request: &tokio_tungstenite::tungstenite::handshake::server::Request, response| {
+        let query = request.uri().query().unwrap_or("");
+        wants_compression = query.split('&').any(|p| p == COMPRESSION_QUERY);
+        wants_msgpack = query.split('&').any(|p| p == MSGPACK_QUERY);
+        Ok(response)
+    };
+    let ws_stream = tokio_tungstenite::accept_hdr_async(stream, check_query).await?;
+    let encoding = Encoding {
+        format: if wants_msgpack { WireFormat::Msgpack } else { WireFormat::Json },
+        compression: state.compression.filter(|_| wants_compression),
+    };
+    let client_id = state.register_client(peer, encoding).await;
+    info!(%peer, client_id, compressed = encoding.compression.is_some(), format = ?encoding.format, "WebSocket connected");
 
+    let result = stream_to_client(ws_stream, peer, client_id, encoding, &state).await;
+
+    state.unregister_client(client_id).await;
+    info!(%peer, client_id, "WebSocket disconnected");
+    result
+}
+
+/// Send the snapshot, then stream diffs until the client disconnects.
+/// A client may subscribe to some tiles and layers, after which its diffs
+/// carry only those. A client may also switch between JSON and MessagePack,
+/// and gets a fresh snapshot in its new format when it does. With a
+/// compression level, every message goes out as a deflated binary frame.
+/// With a heartbeat, the client is pinged every interval and dropped once it
+/// has been silent, or a send has stalled, for the timeout.
+async fn stream_to_client(
+    ws_stream: tokio_tungstenite::WebSocketStream<TcpStream>,
+    peer: SocketAddr,
+    client_id: u64,
+    mut encoding: Encoding,
+    state: &ServerState,
+) -> Result<(), WorldgroundError> {
     let (mut write, mut read) = futures_util::StreamExt::split(ws_stream);
+    let send_timeout = state.heartbeat.map(|h| h.timeout);
 
     // Send current snapshot
-    let snapshot = state.snapshot_json.read().await.clone();
-    futures_util::SinkExt::send(&mut write, Message::Text(snapshot.into())).await?;
+    let snapshot = Broadcast::new(state.snapshot_json.read().await.clone()).message(encoding);
+    let snapshot_len = snapshot.len();
+    if !send_within(&mut write, snapshot, send_timeout).await? {
+        reap_client(state, peer, client_id);
+        return Ok(());
+    }
+    state.record_client_send(client_id, snapshot_len).await;
 
     // Subscribe to tick diffs
     let mut rx = state.tick_sender.subscribe();
 
-    // Stream diffs until client disconnects
+    // The ping timer only runs with a heartbeat; the period is a placeholder without one
+    let ping_every = state.heartbeat.map_or(Duration::from_secs(3600), |h| h.interval);
+    let mut pings = tokio::time::interval_at(tokio::time::Instant::now() + ping_every, ping_every);
+    pings.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut last_seen = Instant::now();
+    let mut subscription = Subscription::default();
+    let mut cadence = Cadence::default();
+    let mut closing = state.closing.subscribe();
+
+    // Stream diffs until client disconnects or the server shuts down
     loop {
         tokio::select! {
+            // The borrow wait_for returns isn't Send, so drop it before the arm runs
+            _ = async { closing.wait_for(|closing| *closing).await.is_ok() } => {
+                let frame = CloseFrame { code: CloseCode::Away, reason: "server shutting down".into() };
+                let _ = send_within(&mut write, Message::Close(Some(frame)), send_timeout).await;
+                break;
+            }
             diff = rx.recv() => {
                 match diff {
-                    Ok(json) => {
-                        if futures_util::SinkExt::send(&mut write, Message::Text(json.into())).await.is_err() {
-                            break; // Client disconnected
+                    Ok(broadcast) => {
+                        // A client on a slower cadence gets diffs merged; held ones go out even if it speeds up
+                        let every = subscription.every.unwrap_or(1);
+                        let merged;
+                        let broadcast = match broadcast.tick_diff() {
+                            Some(diff) if every > 1 || cadence.is_holding() => match cadence.offer(diff, every) {
+                                Some(diff) => {
+                                    merged = Broadcast::from_message(ServerMessage::TickDiff(diff));
+                                    &merged
+                                }
+                                None => continue,
+                            },
+                            Some(_) => &*broadcast,
+                            None => {
+                                // A fresh snapshot replaces whatever diffs were held
+                                if matches!(broadcast.server_message(), Some(ServerMessage::WorldSnapshot(_))) {
+                                    cadence = Cadence::default();
+                                }
+                                &*broadcast
+                            }
+                        };
+                        let message = broadcast.message_for(encoding, &subscription);
+                        let len = message.len();
+                        match send_within(&mut write, message, send_timeout).await {
+                            Ok(true) => state.record_client_send(client_id, len).await,
+                            Ok(false) => {
+                                reap_client(state, peer, client_id);
+                                break;
+                            }
+                            Err(_) => break, // Client disconnected
                         }
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         warn!(%peer, lagged = n, "Client lagged behind on diffs");
+                        state.record_client_lag(client_id, n).await;
                         // Continue — client missed some diffs but will stay connected
                     }
                     Err(broadcast::error::RecvError::Closed) => {
@@ -261,20 +1479,90 @@ async fn handle_websocket(
                 match msg {
                     Some(Ok(Message::Close(_))) | None => break,
                     Some(Err(_)) => break,
-                    _ => {} // Ignore other messages from client
+                    Some(Ok(message @ (Message::Text(_) | Message::Binary(_)))) => {
+                        last_seen = Instant::now();
+                        // Clients write JSON text or, once on MessagePack, binary
+                        let parsed = match &message {
+                            Message::Text(text) => serde_json::from_str::<ClientMessage>(text).map_err(|e| e.to_string()),
+                            _ => rmp_serde::from_slice::<ClientMessage>(&message.into_data()).map_err(|e| e.to_string()),
+                        };
+                        match parsed {
+                            Ok(ClientMessage::Subscribe(requested)) => {
+                                subscription = requested;
+                                state.set_client_subscription(client_id, &subscription).await;
+                                info!(%peer, client_id, filters = ?subscription_filters(&subscription), "Client subscribed");
+                            }
+                            Ok(ClientMessage::SetFormat { format }) => {
+                                encoding.format = format;
+                                state.set_client_format(client_id, format).await;
+                                info!(%peer, client_id, ?format, "Client switched wire format");
+                                let snapshot = Broadcast::new(state.snapshot_json.read().await.clone()).message(encoding);
+                                let len = snapshot.len();
+                                match send_within(&mut write, snapshot, send_timeout).await {
+                                    Ok(true) => state.record_client_send(client_id, len).await,
+                                    Ok(false) => {
+                                        reap_client(state, peer, client_id);
+                                        break;
+                                    }
+                                    Err(_) => break,
+                                }
+                            }
+                            Err(e) => warn!(%peer, client_id, "Ignoring unreadable client message: {}", e),
+                        }
+                    }
+                    _ => last_seen = Instant::now(), // Pongs and anything else show the client is alive
+                }
+            }
+            _ = pings.tick(), if state.heartbeat.is_some() => {
+                if send_timeout.is_some_and(|timeout| last_seen.elapsed() > timeout) {
+                    reap_client(state, peer, client_id);
+                    break;
+                }
+                match send_within(&mut write, Message::Ping(Vec::new().into()), send_timeout).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        reap_client(state, peer, client_id);
+                        break;
+                    }
+                    Err(_) => break,
                 }
             }
         }
     }
 
-    info!(%peer, "WebSocket disconnected");
     Ok(())
 }
 
+/// Send a message, giving up after `timeout` if the client has stopped
+/// reading. Returns `Ok(false)` when the send timed out.
+async fn send_within<S>(
+    write: &mut S,
+    message: Message,
+    timeout: Option<Duration>,
+) -> Result<bool, S::Error>
+where
+    S: futures_util::Sink<Message> + Unpin,
+{
+    let send = futures_util::SinkExt::send(write, message);
+    match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, send).await {
+            Ok(result) => result.map(|_| true),
+            Err(_) => Ok(false),
+        },
+        None => send.await.map(|_| true),
+    }
+}
+
+/// Count and log a client dropped for not responding.
+fn reap_client(state: &ServerState, peer: SocketAddr, client_id: u64) {
+    state.reaped_clients.fetch_add(1, Ordering::Relaxed);
+    warn!(%peer, client_id, "Dropping unresponsive WebSocket client");
+}
+
 /// Handle an HTTP request by serving the embedded viewer.
 async fn handle_viewer_request(
     mut stream: TcpStream,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(), WorldgroundError> {
     use tokio::io::AsyncReadExt;
     use tokio::io::AsyncWriteExt;
 
@@ -299,7 +1587,7 @@ async fn handle_viewer_request(
 async fn handle_health_request(
     mut stream: TcpStream,
     state: Arc<ServerState>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(), WorldgroundError> {
     use tokio::io::AsyncReadExt;
     use tokio::io::AsyncWriteExt;
 
@@ -308,6 +1596,7 @@ async fn handle_health_request(
     let _ = stream.read(&mut buf).await?;
 
     let health = state.health.read().await;
+    let (verdict, reasons) = state.health_limits.map(|l| health.assess(&l)).unwrap_or_default();
     let status = HealthStatus {
         tick: health.tick,
         tick_rate: health.tick_rate(),
@@ -316,11 +1605,25 @@ async fn handle_health_request(
         snapshot_age_ticks: health.tick.saturating_sub(health.last_snapshot_tick),
         tile_count: health.tile_count,
         season: health.season,
+        jitter_p95_ms: health.jitter_percentile(0.95),
+        jitter_p99_ms: health.jitter_percentile(0.99),
+        overrun_ticks: health.overrun_ticks,
+        overrunning: health.consecutive_overruns >= SUSTAINED_OVERRUN_TICKS,
+        invariant_violations: health.invariant_violations,
+        failing_invariants: health.failing_invariants.clone(),
+        paused: state.is_paused(),
+        status: verdict,
+        status_reasons: reasons,
     };
 
     let body = serde_json::to_string(&status)?;
+    let code = match verdict {
+        HealthState::Unhealthy => "503 Service Unavailable",
+        _ => "200 OK",
+    };
     let response = format!(
-        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
         body.len(),
         body
     );
@@ -331,330 +1634,2247 @@ async fn handle_health_request(
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::generation::GenerationParams;
-    use crate::simulation::statistics::TickStatistics;
-    use crate::world::generation::generate_world;
-    use crate::world::tile::*;
-    use crate::world::World;
-    use std::collections::HashMap;
-    use std::time::Duration;
+/// Handle GET /api/clients: per-connection statistics as JSON.
+async fn handle_clients_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
 
-    fn default_gen_params(tile_count: u32) -> GenerationParams {
-        GenerationParams {
-            seed: 42,
-            tile_count,
-            ocean_ratio: 0.3,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: crate::config::generation::TopologyConfig::default(),
-        }
-    }
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
 
-    fn make_small_world() -> World {
-        generate_world(&default_gen_params(100))
-    }
+    let body = serde_json::to_string(&state.client_list().await)?;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
 
-    fn make_test_stats(tick: u64) -> TickStatistics {
-        TickStatistics {
-            tick,
-            biome_distribution: HashMap::new(),
-            avg_temperature: 288.0,
-            avg_moisture: 0.4,
-            avg_vegetation_health: 0.7,
-            weather_coverage: HashMap::new(),
-            diversity_index: 0.65,
-            rule_errors: 0,
-            tick_duration_ms: 100.0,
-        }
-    }
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
 
-    #[test]
-    fn build_snapshot_json_is_valid() {
-        let world = make_small_world();
-        let json = build_snapshot_json(&world);
-        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
-        assert_eq!(parsed["message_type"], "WorldSnapshot");
-        assert_eq!(parsed["tile_count"], 100);
-        assert!(parsed["tiles"].is_array());
-        assert_eq!(parsed["tiles"].as_array().unwrap().len(), 100);
-    }
+    Ok(())
+}
 
-    #[test]
-    fn build_diff_json_is_valid() {
-        let before = vec![
-            Tile::new_default(0, vec![], Position::flat(0.0, 0.0)),
-            Tile::new_default(1, vec![], Position::flat(1.0, 0.0)),
-        ];
-        let mut after = before.clone();
-        after[0].weather.temperature = 300.0;
+/// Handle GET /api/metrics: rule mutation acceptance/rejection counts and
+/// per-rule execution time as JSON.
+async fn handle_metrics_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
 
-        let stats = make_test_stats(1);
-        let json = build_diff_json(&before, &after, 1, Season::Spring, &stats, &[]);
-        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
-        assert_eq!(parsed["message_type"], "TickDiff");
-        assert_eq!(parsed["tick"], 1);
-        let changes = parsed["changed_tiles"].as_array().unwrap();
-        assert_eq!(changes.len(), 1);
-        assert_eq!(changes[0]["id"], 0);
-        assert!(changes[0]["weather"].is_object());
-        // conditions/biome/resources should not be in JSON (skip_serializing_if)
-        assert!(changes[0].get("conditions").is_none());
-    }
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
 
-    #[test]
-    fn build_diff_json_empty_when_no_changes() {
-        let tiles = vec![Tile::new_default(0, vec![], Position::flat(0.0, 0.0))];
-        let stats = make_test_stats(1);
-        let json = build_diff_json(&tiles, &tiles, 1, Season::Spring, &stats, &[]);
-        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
-        assert!(parsed["changed_tiles"].as_array().unwrap().is_empty());
-    }
+    let body = serde_json::to_string(&state.mutation_metrics().await)?;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
 
-    #[tokio::test]
-    async fn server_state_on_tick_updates_health() {
-        let state = ServerState::new("{}".to_string());
-        let stats = make_test_stats(5);
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
 
-        state
-            .on_tick(
-                Some("new_snapshot".to_string()),
-                "diff".to_string(),
-                &stats,
-                5,
-                Season::Summer,
-                100,
-                3,
-            )
-            .await;
+    Ok(())
+}
 
-        let health = state.health.read().await;
-        assert_eq!(health.tick, 5);
-        assert_eq!(health.season, Season::Summer);
-        assert_eq!(health.tile_count, 100);
-        assert!((health.diversity_index - 0.65).abs() < 0.01);
-        assert_eq!(health.last_snapshot_tick, 3);
-        assert_eq!(health.recent_tick_durations_ms.len(), 1);
-    }
+/// Handle GET /api/energy: per-tile mean wind power and insolation as JSON.
+async fn handle_energy_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
 
-    #[tokio::test]
-    async fn server_state_updates_snapshot_for_new_clients() {
-        let state = ServerState::new("initial".to_string());
-        assert_eq!(*state.snapshot_json.read().await, "initial");
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
 
-        let stats = make_test_stats(1);
-        state
-            .on_tick(
-                Some("updated".to_string()),
-                "diff".to_string(),
-                &stats,
-                1,
-                Season::Spring,
-                100,
-                0,
-            )
-            .await;
+    let body = serde_json::to_string(&*state.energy.read().await)?;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
 
-        assert_eq!(*state.snapshot_json.read().await, "updated");
-    }
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
 
-    #[tokio::test]
-    async fn tick_rate_calculation() {
-        let state = ServerState::new("{}".to_string());
+    Ok(())
+}
 
-        // Simulate 5 ticks at 200ms each → 5 ticks/sec
-        for i in 0..5 {
-            let mut stats = make_test_stats(i);
-            stats.tick_duration_ms = 200.0;
-            state
-                .on_tick(
-                    Some("{}".to_string()),
-                    "{}".to_string(),
-                    &stats,
-                    i,
-                    Season::Spring,
-                    100,
-                    0,
-                )
-                .await;
-        }
+/// Handle GET /api/narration: recent plain-English summaries as JSON, oldest first.
+async fn handle_narration_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
 
-        let health = state.health.read().await;
-        assert!((health.tick_rate() - 5.0).abs() < 0.1);
-    }
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
 
-    #[tokio::test]
-    async fn health_recent_durations_capped_at_100() {
-        let state = ServerState::new("{}".to_string());
+    let narrations = state.narrations.read().await;
+    let body = serde_json::to_string(&serde_json::json!({ "narrations": &*narrations }))?;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
 
-        for i in 0..150 {
-            let stats = make_test_stats(i);
-            state
-                .on_tick(
-                    Some("{}".to_string()),
-                    "{}".to_string(),
-                    &stats,
-                    i,
-                    Season::Spring,
-                    100,
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle GET /api/records: all-time weather extremes as JSON.
+async fn handle_records_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
+
+    let body = serde_json::to_string(&state.records.read().await.in_units(state.units))?;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// How long a tile query waits for the simulation loop to answer it.
+const TILE_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Write a JSON response with the given status and close the connection.
+async fn write_json_response(
+    mut stream: TcpStream,
+    status: &str,
+    body: &str,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Handle GET /tiles/{id} and GET /tiles?biome=Desert: full tile state as JSON.
+///
+/// Tiles live in the simulation loop, so the query waits for the loop to
+/// answer it between ticks, against the last completed tick.
+async fn handle_tiles_request(
+    mut stream: TcpStream,
+    route: &Route,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    read_http_request(&mut stream).await?;
+
+    let query = match route.path.strip_prefix("/tiles/") {
+        Some(id) => id.parse().map(TileQuery::Id).ok(),
+        None => match route.param("biome") {
+            Some(name) => serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .ok()
+                .map(|biome| TileQuery::Filter { biome: Some(biome) }),
+            None => Some(TileQuery::Filter { biome: None }),
+        },
+    };
+    let Some(query) = query else {
+        return write_json_response(stream, "400 Bad Request", "{}").await;
+    };
+
+    let (tx, rx) = oneshot::channel();
+    state.tile_queries.lock().unwrap_or_else(|e| e.into_inner()).push((query, tx));
+    match tokio::time::timeout(TILE_QUERY_TIMEOUT, rx).await {
+        Ok(Ok(Some(body))) => write_json_response(stream, "200 OK", &body).await,
+        Ok(Ok(None)) => write_json_response(stream, "404 Not Found", "{}").await,
+        // The simulation loop has stopped or is stuck in a long tick
+        _ => write_json_response(stream, "503 Service Unavailable", "{}").await,
+    }
+}
+
+/// Handle GET /statistics: the last tick's statistics as JSON.
+async fn handle_statistics_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    read_http_request(&mut stream).await?;
+
+    let statistics = state.statistics.read().await.clone();
+    match statistics {
+        Some(statistics) => write_json_response(stream, "200 OK", &serde_json::to_string(&statistics)?).await,
+        // No tick has run yet
+        None => write_json_response(stream, "503 Service Unavailable", "{}").await,
+    }
+}
+
+/// Handle GET /pressure-systems: the last tick's pressure systems as JSON.
+async fn handle_pressure_systems_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    read_http_request(&mut stream).await?;
+
+    let body = serde_json::to_string(&*state.pressure_systems.read().await)?;
+    write_json_response(stream, "200 OK", &body).await
+}
+
+/// Handle GET /api/palette: biome and terrain colors and layer legends as JSON.
+async fn handle_palette_request(
+    mut stream: TcpStream,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    read_http_request(&mut stream).await?;
+
+    let body = serde_json::to_string(&state.palette.in_units(state.units))?;
+    write_json_response(stream, "200 OK", &body).await
+}
+
+/// Handle GET /admin: a small page that polls /api/clients.
+async fn handle_admin_request(
+    mut stream: TcpStream,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
+
+    const ADMIN_HTML: &str = include_str!("../../viewer/admin.html");
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        ADMIN_HTML.len(),
+        ADMIN_HTML
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle GET /api/handoff: stop the simulation and return the world to a successor.
+///
+/// Only loopback peers may request a handoff. The response body is the
+/// bincode-serialized `World` at the last completed tick.
+async fn handle_handoff_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    // Read and discard the full HTTP request
+    let mut buf = vec![0u8; 4096];
+    let _ = stream.read(&mut buf).await?;
+
+    let rx = if !peer.ip().is_loopback() {
+        Err("403 Forbidden")
+    } else {
+        let mut pending = state.handoff_request.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.is_some() {
+            Err("409 Conflict")
+        } else {
+            let (tx, rx) = oneshot::channel();
+            *pending = Some(tx);
+            Ok(rx)
+        }
+    };
+
+    let world_bytes = match rx {
+        Ok(rx) => {
+            info!(%peer, "Handoff requested — waiting for current tick to finish");
+            match tokio::time::timeout(std::time::Duration::from_secs(30), rx).await {
+                Ok(Ok(bytes)) => Ok(bytes),
+                _ => {
+                    // Withdraw the request so the simulation keeps running
+                    state.take_handoff_request();
+                    Err("503 Service Unavailable")
+                }
+            }
+        }
+        Err(status) => Err(status),
+    };
+
+    match world_bytes {
+        Ok(bytes) => {
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                bytes.len()
+            );
+            stream.write_all(header.as_bytes()).await?;
+            stream.write_all(&bytes).await?;
+            info!(%peer, bytes = bytes.len(), "World handed off");
+        }
+        Err(status) => {
+            warn!(%peer, status, "Handoff refused");
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).await?;
+        }
+    }
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Read an HTTP request's headers and body (using Content-Length).
+async fn read_http_request(
+    stream: &mut TcpStream,
+) -> Result<(String, Vec<u8>), WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+
+    const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&data[..end]).to_string();
+            let content_length = head
+                .lines()
+                .filter_map(|l| l.split_once(':'))
+                .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if content_length > MAX_REQUEST_BYTES {
+                return Err(WorldgroundError::Server("Request body too large".to_string()));
+            }
+            let body_start = end + 4;
+            while data.len() < body_start + content_length {
+                let n = stream.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                data.extend_from_slice(&buf[..n]);
+            }
+            let body_end = data.len().min(body_start + content_length);
+            return Ok((head, data[body_start..body_end].to_vec()));
+        }
+        if data.len() > MAX_REQUEST_BYTES {
+            return Err(WorldgroundError::Server("Request headers too large".to_string()));
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok((String::from_utf8_lossy(&data).to_string(), Vec::new()));
+        }
+        data.extend_from_slice(&buf[..n]);
+    }
+}
+
+/// Handle GET/POST /api/frozen: list frozen tiles or queue freeze/thaw changes.
+///
+/// POST bodies look like `{"add": [1, 2], "remove": [3]}` and take effect at the
+/// next tick. Only loopback peers may change the frozen set.
+async fn handle_frozen_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (head, body) = read_http_request(&mut stream).await?;
+    let is_post = head.to_lowercase().starts_with("post");
+
+    let status = if !is_post {
+        "200 OK"
+    } else if !peer.ip().is_loopback() {
+        "403 Forbidden"
+    } else {
+        match serde_json::from_slice::<FrozenTilesUpdate>(&body) {
+            Ok(update) => {
+                info!(%peer, add = update.add.len(), remove = update.remove.len(), "Frozen tiles update queued");
+                state.queue_frozen_update(update);
+                "202 Accepted"
+            }
+            Err(_) => "400 Bad Request",
+        }
+    };
+
+    let response_body = serde_json::to_string(&FrozenTilesStatus {
+        frozen_tiles: state.frozen_tiles.read().await.clone(),
+        pending_updates: state.frozen_updates.lock().unwrap_or_else(|e| e.into_inner()).len(),
+    })?;
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response_body.len(),
+        response_body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle POST /api/survey: reveal the resource deposits on some tiles.
+///
+/// Bodies look like `{"tiles": [12, 13]}`. Surveyed deposits appear in the
+/// next tick's diff. Only loopback peers may survey.
+async fn handle_survey_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (_, body) = read_http_request(&mut stream).await?;
+
+    let status = if !peer.ip().is_loopback() {
+        "403 Forbidden"
+    } else {
+        match serde_json::from_slice::<SurveyRequest>(&body) {
+            Ok(request) => {
+                info!(%peer, tiles = request.tiles.len(), "Survey queued");
+                state.queue_survey(&request.tiles);
+                "202 Accepted"
+            }
+            Err(_) => "400 Bad Request",
+        }
+    };
+
+    let response_body = serde_json::to_string(&SurveyStatus {
+        pending_tiles: state.survey_requests.lock().unwrap_or_else(|e| e.into_inner()).len(),
+    })?;
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response_body.len(),
+        response_body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle POST /api/observations: nudge tiles toward observed weather.
+///
+/// Bodies look like `{"observations": [{"tile": 12, "temperature": 288.5,
+/// "humidity": 0.7}]}`, in the server's units; `ticks` and `strength` are
+/// optional. Observations start nudging at the next tick. Only loopback peers
+/// may post them.
+async fn handle_observations_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (_, body) = read_http_request(&mut stream).await?;
+
+    let status = if !peer.ip().is_loopback() {
+        "403 Forbidden"
+    } else {
+        match serde_json::from_slice::<ObservationsRequest>(&body) {
+            Ok(request) => {
+                let units = state.units;
+                let observations: Vec<Observation> = request
+                    .observations
+                    .into_iter()
+                    .map(|o| Observation {
+                        temperature: o.temperature.map(|t| units.temperature.to_kelvin(t)),
+                        humidity: o.humidity.map(|h| units.humidity.to_fraction(h)),
+                        ..o
+                    })
+                    .collect();
+                match observations.iter().try_for_each(Observation::validate) {
+                    Ok(()) => {
+                        info!(%peer, observations = observations.len(), "Observations queued");
+                        state.queue_observations(observations);
+                        "202 Accepted"
+                    }
+                    Err(e) => {
+                        warn!(%peer, "Rejecting observations: {}", e);
+                        "400 Bad Request"
+                    }
+                }
+            }
+            Err(_) => "400 Bad Request",
+        }
+    };
+
+    let response_body = serde_json::to_string(&ObservationsStatus {
+        pending_observations: state.observations.lock().unwrap_or_else(|e| e.into_inner()).len(),
+    })?;
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response_body.len(),
+        response_body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle GET/POST /api/bookmarks: list bookmarks and tours or queue edits.
+///
+/// POST bodies look like `{"set": {"delta": {"tile_id": 812, "radius_deg": 10,
+/// "caption": "..."}}, "remove": [...], "tours": {"coasts": {"stops": [{"bookmark":
+/// "delta", "dwell_secs": 8}]}}, "remove_tours": [...]}` and take effect at the
+/// next tick. Only loopback peers may edit bookmarks.
+async fn handle_bookmarks_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (head, body) = read_http_request(&mut stream).await?;
+    let is_post = head.to_lowercase().starts_with("post");
+
+    let status = if !is_post {
+        "200 OK"
+    } else if !peer.ip().is_loopback() {
+        "403 Forbidden"
+    } else {
+        match serde_json::from_slice::<BookmarksUpdate>(&body) {
+            Ok(update) => {
+                info!(%peer, set = update.set.len(), tours = update.tours.len(), "Bookmarks update queued");
+                state.queue_bookmarks_update(update);
+                "202 Accepted"
+            }
+            Err(_) => "400 Bad Request",
+        }
+    };
+
+    let response_body = {
+        let bookmarks = state.bookmarks.read().await;
+        serde_json::to_string(&BookmarksStatus {
+            bookmarks: bookmarks.bookmarks.clone(),
+            tours: bookmarks.tours.clone(),
+            pending_updates: state.bookmark_updates.lock().unwrap_or_else(|e| e.into_inner()).len(),
+            active_tour: state.active_tour(),
+        })?
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response_body.len(),
+        response_body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle POST /api/tours/start (`{"tour": "coasts"}`) and POST /api/tours/stop.
+///
+/// Starting a tour replaces any tour already playing. Only loopback peers may
+/// start or stop tours. Responds with the tour now playing, if any.
+async fn handle_tour_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (head, body) = read_http_request(&mut stream).await?;
+    let path = head.split_whitespace().nth(1).unwrap_or("").to_lowercase();
+
+    let status = if !peer.ip().is_loopback() {
+        "403 Forbidden"
+    } else if path == "/api/tours/stop" {
+        state.stop_tour();
+        "200 OK"
+    } else if path == "/api/tours/start" {
+        match serde_json::from_slice::<TourRequest>(&body) {
+            Ok(request) if state.start_tour(&request.tour).await => "200 OK",
+            Ok(_) => "404 Not Found",
+            Err(_) => "400 Bad Request",
+        }
+    } else {
+        "404 Not Found"
+    };
+
+    let response_body = serde_json::json!({ "active_tour": state.active_tour() }).to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response_body.len(),
+        response_body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Handle GET/POST /api/control: report or change whether the simulation is paused.
+///
+/// POST bodies are `{"action": "pause"}`, `{"action": "resume"}`,
+/// `{"action": "step", "ticks": 5}` or `{"action": "rollback", "ticks": 5}`.
+/// Only loopback peers may change it.
+async fn handle_control_request(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState>,
+) -> Result<(), WorldgroundError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (head, body) = read_http_request(&mut stream).await?;
+    let is_post = head.to_lowercase().starts_with("post");
+
+    let status = if !is_post {
+        "200 OK"
+    } else if !peer.ip().is_loopback() {
+        "403 Forbidden"
+    } else {
+        match serde_json::from_slice::<ControlRequest>(&body) {
+            Ok(request) => match state.control(request) {
+                Ok(()) => {
+                    info!(%peer, action = ?request.action, ticks = request.ticks, "Simulation control");
+                    "200 OK"
+                }
+                Err(e) => {
+                    warn!(%peer, "Refusing simulation control: {}", e);
+                    "409 Conflict"
+                }
+            },
+            Err(_) => "400 Bad Request",
+        }
+    };
+
+    let response_body = serde_json::to_string(&state.control_status().await)?;
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response_body.len(),
+        response_body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Ask a running server to hand over its world (successor side of a live handoff).
+///
+/// The old process stops ticking once it has sent the world, so it sends no diffs
+/// after the returned world's tick; the caller continues the simulation from that
+/// tick and its first diff is for the next one. Viewers reconnect to the caller and
+/// get its snapshot. Bind only after this returns: with SO_REUSEPORT the kernel may
+/// otherwise route the request to the caller's own socket.
+pub async fn request_handoff(addr: &str) -> Result<crate::world::World, WorldgroundError> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    let mut stream = TcpStream::connect(addr)
+        .await
+        .map_err(|e| WorldgroundError::Server(format!("Cannot connect to {}: {}", addr, e)))?;
+    stream
+        .write_all(format!("GET /api/handoff HTTP/1.1\r\nHost: {}\r\n\r\n", addr).as_bytes())
+        .await
+        .map_err(|e| WorldgroundError::Server(format!("Cannot send handoff request: {}", e)))?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| WorldgroundError::Server(format!("Cannot read handoff response: {}", e)))?;
+
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| WorldgroundError::Server("Malformed handoff response".to_string()))?;
+    let status_line = String::from_utf8_lossy(&response[..header_end])
+        .lines()
+        .next()
+        .unwrap_or("")
+        .to_string();
+    if !status_line.contains(" 200 ") {
+        return Err(WorldgroundError::Server(format!("Handoff refused by {}: {}", addr, status_line)));
+    }
+
+    bincode::deserialize(&response[header_end + 4..])
+        .map_err(|e| WorldgroundError::Server(format!("Cannot decode handed-off world: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::GenerationParams;
+    use crate::simulation::statistics::TickStatistics;
+    use crate::world::generation::generate_world;
+    use crate::world::layers::{DiffLayer, DIFF_LAYERS};
+    use crate::world::tile::*;
+    use crate::world::World;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn default_gen_params(tile_count: u32) -> GenerationParams {
+        GenerationParams {
+            seed: 42,
+            tile_count,
+            ocean_ratio: 0.3,
+            ..Default::default()
+        }
+    }
+
+    fn make_small_world() -> World {
+        generate_world(&default_gen_params(100))
+    }
+
+    fn make_test_stats(tick: u64) -> TickStatistics {
+        TickStatistics {
+            tick,
+            biome_distribution: HashMap::new(),
+            avg_temperature: 288.0,
+            avg_moisture: 0.4,
+            avg_vegetation_health: 0.7,
+            weather_coverage: HashMap::new(),
+            diversity_index: 0.65,
+            rule_errors: 0,
+            tick_duration_ms: 100.0,
+        }
+    }
+
+    #[test]
+    fn build_snapshot_json_is_valid() {
+        let world = make_small_world();
+        let json = build_snapshot_json(&world);
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        assert_eq!(parsed["message_type"], "WorldSnapshot");
+        assert_eq!(parsed["tile_count"], 100);
+        assert!(parsed["tiles"].is_array());
+        assert_eq!(parsed["tiles"].as_array().unwrap().len(), 100);
+    }
+
+    #[test]
+    fn build_diff_json_is_valid() {
+        let before = vec![
+            Tile::new_default(0, vec![], Position::flat(0.0, 0.0)),
+            Tile::new_default(1, vec![], Position::flat(1.0, 0.0)),
+        ];
+        let mut after = before.clone();
+        after[0].weather.temperature = 300.0;
+
+        let stats = make_test_stats(1);
+        let json = build_diff_json(&before, &after, 1, Season::Spring, 90, &stats, &[]);
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        assert_eq!(parsed["message_type"], "TickDiff");
+        assert_eq!(parsed["tick"], 1);
+        let changes = parsed["changed_tiles"].as_array().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["id"], 0);
+        assert!(changes[0]["weather"].is_object());
+        // conditions/biome/resources should not be in JSON (skip_serializing_if)
+        assert!(changes[0].get("conditions").is_none());
+    }
+
+    #[test]
+    fn build_diff_json_empty_when_no_changes() {
+        let tiles = vec![Tile::new_default(0, vec![], Position::flat(0.0, 0.0))];
+        let stats = make_test_stats(1);
+        let json = build_diff_json(&tiles, &tiles, 1, Season::Spring, 90, &stats, &[]);
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        assert!(parsed["changed_tiles"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn build_diff_json_from_layers_includes_scratch_only_when_captured() {
+        let mut world = make_small_world();
+        let layers = world.capture_layers(&DIFF_LAYERS);
+        let with_scratch = world.capture_layers(&[DIFF_LAYERS.as_slice(), &[DiffLayer::Scratch]].concat());
+        world.tiles[0].scratch.insert("ticks_since_fire".to_string(), 4.0);
+        let stats = make_test_stats(1);
+
+        let json = build_diff_json_from_layers(&layers, &world, &stats, &[], None, &[], &Default::default());
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        assert!(parsed["changed_tiles"].as_array().unwrap().is_empty());
+
+        let json = build_diff_json_from_layers(&with_scratch, &world, &stats, &[], None, &[], &Default::default());
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        let changes = parsed["changed_tiles"].as_array().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["scratch"]["ticks_since_fire"], 4.0);
+        assert!(changes[0].get("weather").is_none());
+    }
+
+    #[test]
+    fn inert_tiles_are_left_out_of_diffs() {
+        let mut world = make_small_world();
+        let layers = world.capture_layers(&DIFF_LAYERS);
+        world.tiles[0].weather.temperature += 5.0;
+        world.tiles[1].weather.temperature += 5.0;
+        world.set_inert_tiles([1].into());
+
+        let json = build_diff_json_from_layers(&layers, &world, &make_test_stats(1), &[], None, &[], &Default::default());
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        let changes = parsed["changed_tiles"].as_array().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["id"], 0);
+    }
+
+    #[test]
+    fn diff_lists_flood_events_only_when_present() {
+        let world = make_small_world();
+        let stats = make_test_stats(1);
+        let quiet: serde_json::Value =
+            serde_json::from_str(&build_diff_json_from_layers(&LayerArrays::default(), &world, &stats, &[], None, &[], &Default::default()))
+                .expect("valid JSON");
+        assert!(quiet.get("floods").is_none());
+
+        let floods = [crate::simulation::flood::FloodEvent { tile_id: 3, flood_level: 0.5 }];
+        let diff: serde_json::Value =
+            serde_json::from_str(&build_diff_json_from_layers(&LayerArrays::default(), &world, &stats, &floods, None, &[], &Default::default()))
+                .expect("valid JSON");
+        assert_eq!(diff["floods"][0]["tile_id"], 3);
+        assert_eq!(diff["floods"][0]["flood_level"], 0.5);
+    }
+
+    #[test]
+    fn diff_includes_aurora_onset_only_when_present() {
+        let world = make_small_world();
+        let stats = make_test_stats(1);
+        let quiet: serde_json::Value =
+            serde_json::from_str(&build_diff_json_from_layers(&LayerArrays::default(), &world, &stats, &[], None, &[], &Default::default()))
+                .expect("valid JSON");
+        assert!(quiet.get("aurora").is_none());
+
+        let storm = crate::simulation::aurora::AuroraEvent { strength: 0.5, oval_latitude: 57.0 };
+        let diff: serde_json::Value = serde_json::from_str(&build_diff_json_from_layers(
+            &LayerArrays::default(),
+            &world,
+            &stats,
+            &[],
+            Some(storm),
+            &[],
+            &Default::default(),
+        ))
+        .expect("valid JSON");
+        assert_eq!(diff["aurora"]["strength"], 0.5);
+        assert_eq!(diff["aurora"]["oval_latitude"], 57.0);
+    }
+
+    #[test]
+    fn diff_lists_tile_effects_only_when_present() {
+        use crate::simulation::effects::{TileEffect, TileEffects};
+        let world = make_small_world();
+        let stats = make_test_stats(1);
+        let quiet: serde_json::Value = serde_json::from_str(&build_diff_json_from_layers(
+            &LayerArrays::default(),
+            &world,
+            &stats,
+            &[],
+            None,
+            &[],
+            &TileEffects::default(),
+        ))
+        .expect("valid JSON");
+        assert!(quiet.get("effects").is_none());
+
+        let mut effects = TileEffects::default();
+        effects.mark(TileEffect::Flood, 9);
+        effects.mark(TileEffect::Flood, 3);
+        effects.mark(TileEffect::Eruption, 5);
+        let diff: serde_json::Value = serde_json::from_str(&build_diff_json_from_layers(
+            &LayerArrays::default(),
+            &world,
+            &stats,
+            &[],
+            None,
+            &[],
+            &effects,
+        ))
+        .expect("valid JSON");
+        assert_eq!(diff["effects"], serde_json::json!({"flood": [3, 9], "eruption": [5]}));
+    }
+
+    #[test]
+    fn cadence_sends_one_merged_diff_every_n_ticks() {
+        use crate::simulation::effects::TileEffect;
+        let world = make_small_world();
+        let stats = make_test_stats(1);
+        let ServerMessage::TickDiff(template) =
+            build_diff_from_layers(&LayerArrays::default(), &world, &stats, &[], None, &[], &Default::default())
+        else {
+            panic!("expected a tick diff");
+        };
+        let diff_at = |tick: u64| TickDiff {
+            tick,
+            effects: [(TileEffect::Landslide, vec![tick as u32])].into(),
+            ..template.clone()
+        };
+        let mut cadence = Cadence::default();
+
+        // The first goes out at once, then one per multiple of 5
+        assert_eq!(cadence.offer(&diff_at(3), 5).map(|d| d.tick), Some(3));
+        assert!(cadence.offer(&diff_at(4), 5).is_none());
+        assert!(cadence.is_holding());
+        let sent = cadence.offer(&diff_at(5), 5).expect("tick 5 is due");
+        assert_eq!(sent.tick, 5);
+        assert_eq!(sent.effects[&TileEffect::Landslide], vec![4, 5], "held diffs are merged in");
+        assert!(!cadence.is_holding());
+        assert!(cadence.offer(&diff_at(6), 5).is_none());
+
+        // Back to every tick, what was held goes out with the next
+        let sent = cadence.offer(&diff_at(7), 1).expect("due every tick");
+        assert_eq!(sent.effects[&TileEffect::Landslide], vec![6, 7]);
+    }
+
+    #[tokio::test]
+    async fn everything_sent_out_follows_the_server_units() {
+        use worldground_protocol::units::{HumidityUnit, TemperatureUnit};
+        let units = Units { temperature: TemperatureUnit::Celsius, humidity: HumidityUnit::Percent };
+        let mut world = make_small_world();
+        world.tiles[0].weather.temperature = 300.0;
+        let state = ServerState::new("{}".to_string()).with_units(units);
+
+        let snapshot: serde_json::Value = serde_json::from_str(&state.snapshot_json_of(&world)).unwrap();
+        assert_eq!(snapshot["units"]["temperature"], "celsius");
+        let celsius = snapshot["tiles"][0]["weather"]["temperature"].as_f64().unwrap();
+        assert!((celsius - 26.85).abs() < 1e-4, "got {}", celsius);
+
+        let before = world.capture_layers(&crate::world::layers::DIFF_LAYERS);
+        world.tiles[0].weather.humidity = 0.5;
+        let stats = make_test_stats(1);
+        let diff = Broadcast::new(build_diff_json_from_layers(&before, &world, &stats, &[], None, &[], &Default::default())).in_units(units);
+        let diff = diff.tick_diff().unwrap();
+        assert_eq!(diff.changed_tiles[0].weather.as_ref().unwrap().humidity, 50.0);
+        assert_eq!(diff.statistics.avg_temperature, TemperatureUnit::Celsius.from_kelvin(stats.avg_temperature));
+
+        state.update_health(&stats, 1, Season::Spring, 100, 0).await;
+        let summary = state.statistics.read().await.clone().unwrap();
+        assert_eq!(summary.avg_temperature, diff.statistics.avg_temperature);
+
+        let answer: serde_json::Value =
+            serde_json::from_str(&TileQuery::Id(0).answer(&world, state.units()).unwrap()).unwrap();
+        assert_eq!(answer["tile"]["weather"]["humidity"], 50.0);
+    }
+
+    #[test]
+    fn snapshot_and_diff_carry_sun_position() {
+        let mut world = make_small_world();
+        world.season = Season::Summer;
+        world.tick_count = world.season_length as u64;
+
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&build_snapshot_json(&world)).expect("valid JSON");
+        let lat = snapshot["sun"]["subsolar_lat"].as_f64().unwrap();
+        assert!((lat - 23.44).abs() < 1e-9, "summer solstice, got {}", lat);
+
+        let stats = make_test_stats(1);
+        let diff: serde_json::Value = serde_json::from_str(&build_diff_json_from_layers(
+            &LayerArrays::default(),
+            &world,
+            &stats,
+            &[],
+            None,
+            &[],
+            &Default::default(),
+        ))
+        .expect("valid JSON");
+        assert_eq!(diff["sun"], snapshot["sun"]);
+    }
+
+    #[tokio::test]
+    async fn server_state_on_tick_updates_health() {
+        let state = ServerState::new("{}".to_string());
+        let stats = make_test_stats(5);
+
+        state
+            .on_tick(
+                Some("new_snapshot".to_string()),
+                "diff".to_string(),
+                &stats,
+                5,
+                Season::Summer,
+                100,
+                3,
+            )
+            .await;
+
+        let health = state.health.read().await;
+        assert_eq!(health.tick, 5);
+        assert_eq!(health.season, Season::Summer);
+        assert_eq!(health.tile_count, 100);
+        assert!((health.diversity_index - 0.65).abs() < 0.01);
+        assert_eq!(health.last_snapshot_tick, 3);
+        assert_eq!(health.recent_tick_durations_ms.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn update_health_does_not_broadcast() {
+        let state = ServerState::new("initial".to_string());
+        let mut rx = state.tick_sender.subscribe();
+        let stats = make_test_stats(7);
+        state.update_health(&stats, 7, Season::Summer, 100, 0).await;
+
+        assert_eq!(state.health.read().await.tick, 7);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(*state.snapshot_json.read().await, "initial");
+    }
+
+    #[tokio::test]
+    async fn server_state_updates_snapshot_for_new_clients() {
+        let state = ServerState::new("initial".to_string());
+        assert_eq!(*state.snapshot_json.read().await, "initial");
+
+        let stats = make_test_stats(1);
+        state
+            .on_tick(
+                Some("updated".to_string()),
+                "diff".to_string(),
+                &stats,
+                1,
+                Season::Spring,
+                100,
+                0,
+            )
+            .await;
+
+        assert_eq!(*state.snapshot_json.read().await, "updated");
+    }
+
+    #[tokio::test]
+    async fn jitter_percentiles_and_sustained_overruns() {
+        let state = ServerState::new("{}".to_string());
+        // 98 punctual ticks and two late ones
+        for i in 0..100 {
+            let jitter = match i {
+                10 => 40.0,
+                50 => 15.0,
+                _ => 1.0,
+            };
+            assert!(!state.record_tick_timing(jitter, false).await);
+        }
+        {
+            let health = state.health.read().await;
+            assert_eq!(health.jitter_percentile(0.95), 1.0);
+            assert_eq!(health.jitter_percentile(0.99), 15.0);
+            assert_eq!(health.jitter_percentile(1.0), 40.0);
+        }
+
+        // Only the tick that completes the run reports it
+        let mut sustained = Vec::new();
+        for _ in 0..SUSTAINED_OVERRUN_TICKS + 2 {
+            sustained.push(state.record_tick_timing(5.0, true).await);
+        }
+        assert_eq!(sustained.iter().filter(|&&s| s).count(), 1);
+        assert!(sustained[SUSTAINED_OVERRUN_TICKS as usize - 1]);
+        state.record_tick_timing(0.0, false).await;
+        let health = state.health.read().await;
+        assert_eq!(health.overrun_ticks, SUSTAINED_OVERRUN_TICKS as u64 + 2);
+        assert_eq!(health.consecutive_overruns, 0);
+        assert_eq!(health.recent_jitter_ms.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn tick_rate_calculation() {
+        let state = ServerState::new("{}".to_string());
+
+        // Simulate 5 ticks at 200ms each → 5 ticks/sec
+        for i in 0..5 {
+            let mut stats = make_test_stats(i);
+            stats.tick_duration_ms = 200.0;
+            state
+                .on_tick(
+                    Some("{}".to_string()),
+                    "{}".to_string(),
+                    &stats,
+                    i,
+                    Season::Spring,
+                    100,
+                    0,
+                )
+                .await;
+        }
+
+        let health = state.health.read().await;
+        assert!((health.tick_rate() - 5.0).abs() < 0.1);
+    }
+
+    #[tokio::test]
+    async fn health_recent_durations_capped_at_100() {
+        let state = ServerState::new("{}".to_string());
+
+        for i in 0..150 {
+            let stats = make_test_stats(i);
+            state
+                .on_tick(
+                    Some("{}".to_string()),
+                    "{}".to_string(),
+                    &stats,
+                    i,
+                    Season::Spring,
+                    100,
                     0,
                 )
                 .await;
         }
 
-        let health = state.health.read().await;
-        assert_eq!(health.recent_tick_durations_ms.len(), 100);
+        let health = state.health.read().await;
+        assert_eq!(health.recent_tick_durations_ms.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn broadcast_diff_to_subscribers() {
+        let state = ServerState::new("{}".to_string());
+        let mut rx = state.tick_sender.subscribe();
+
+        let stats = make_test_stats(1);
+        state
+            .on_tick(
+                Some("{}".to_string()),
+                "test_diff".to_string(),
+                &stats,
+                1,
+                Season::Spring,
+                100,
+                0,
+            )
+            .await;
+
+        let received = rx.recv().await.expect("should receive diff");
+        assert_eq!(received.json(), "test_diff");
+    }
+
+    #[tokio::test]
+    async fn events_reach_clients_subscribed_to_their_tiles() {
+        let state = ServerState::new("{}".to_string());
+        let mut rx = state.tick_sender.subscribe();
+        let event = |tile| WorldEvent {
+            kind: WorldEvent::DROUGHT_DECLARED.to_string(),
+            tick: 9,
+            tile,
+            source: WorldEvent::NATIVE.to_string(),
+            data: Default::default(),
+        };
+        state.broadcast_events(9, &[event(Some(4)), event(Some(5)), event(None)]);
+
+        let received = rx.recv().await.unwrap();
+        let Some(ServerMessage::Events(events)) = received.server_message() else { panic!("{}", received.json()) };
+        assert_eq!((events.tick, events.events.len()), (9, 3));
+        let encoding = Encoding { format: WireFormat::Json, compression: None };
+        let subscription = Subscription { region: Some(vec![5]), ..Default::default() };
+        let Message::Text(text) = received.message_for(encoding, &subscription) else { panic!("expected text") };
+        let filtered: ServerMessage = serde_json::from_str(&text).unwrap();
+        assert_eq!(filtered, ServerMessage::Events(Events { tick: 9, events: vec![event(Some(5)), event(None)] }));
+    }
+
+    #[tokio::test]
+    async fn websocket_client_receives_snapshot_and_diff() {
+        let world = make_small_world();
+        let snapshot_json = build_snapshot_json(&world);
+        let state = Arc::new(ServerState::new(snapshot_json));
+
+        // Bind server to ephemeral port
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_websocket(stream, peer, server_state).await;
+            }
+        });
+
+        // Connect as WebSocket client
+        let url = format!("ws://127.0.0.1:{}", addr.port());
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+        // Receive snapshot
+        let msg = tokio::time::timeout(
+            Duration::from_secs(5),
+            futures_util::StreamExt::next(&mut ws),
+        )
+        .await
+        .expect("timeout waiting for snapshot")
+        .expect("stream ended")
+        .expect("message error");
+
+        let text = msg.into_text().unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed["message_type"], "WorldSnapshot");
+        assert_eq!(parsed["tiles"].as_array().unwrap().len(), 100);
+
+        // Send a tick diff via broadcast
+        let stats = make_test_stats(1);
+        state
+            .on_tick(
+                Some("{}".to_string()),
+                r#"{"message_type":"TickDiff","tick":1}"#.to_string(),
+                &stats,
+                1,
+                Season::Spring,
+                100,
+                0,
+            )
+            .await;
+
+        // Receive diff
+        let msg = tokio::time::timeout(
+            Duration::from_secs(5),
+            futures_util::StreamExt::next(&mut ws),
+        )
+        .await
+        .expect("timeout waiting for diff")
+        .expect("stream ended")
+        .expect("message error");
+
+        let text = msg.into_text().unwrap();
+        assert!(text.contains("TickDiff"));
+
+        // Clean disconnect
+        futures_util::SinkExt::close(&mut ws).await.unwrap();
+        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn subscribed_client_receives_only_its_tiles_and_layers() {
+        let mut world = make_small_world();
+        let state = Arc::new(ServerState::new(build_snapshot_json(&world)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_websocket(stream, peer, server_state).await;
+            }
+        });
+
+        let url = format!("ws://127.0.0.1:{}", addr.port());
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let snapshot = tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws)).await;
+        assert!(snapshot.unwrap().unwrap().unwrap().into_text().unwrap().contains("WorldSnapshot"));
+
+        let subscribe = r#"{"type":"subscribe","region":[3,4],"layers":["weather"]}"#;
+        futures_util::SinkExt::send(&mut ws, Message::Text(subscribe.into())).await.unwrap();
+        while state.clients.read().await.values().all(|c| c.filters.is_empty()) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let filters = state.clients.read().await.values().next().unwrap().filters.clone();
+        assert_eq!(filters, vec!["region: 2 tiles", "layers: weather"]);
+
+        let before = world.capture_layers(&DIFF_LAYERS);
+        for id in [3, 5] {
+            world.tiles[id].weather.temperature += 5.0;
+            world.tiles[id].biome.vegetation_density += 0.1;
+        }
+        world.tiles[4].biome.vegetation_density += 0.1;
+        let diff = build_diff_json_from_layers(&before, &world, &make_test_stats(1), &[], None, &[], &Default::default());
+        state.on_tick(None, diff, &make_test_stats(1), 1, Season::Spring, 100, 0).await;
+
+        let message = tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws)).await;
+        let text = message.unwrap().unwrap().unwrap().into_text().unwrap();
+        let Ok(ServerMessage::TickDiff(received)) = serde_json::from_str(&text) else {
+            panic!("expected a tick diff: {}", text);
+        };
+        assert_eq!(received.changed_tiles.len(), 1, "tile 5 is outside the region, tile 4 changed no weather");
+        assert_eq!(received.changed_tiles[0].id, 3);
+        assert!(received.changed_tiles[0].weather.is_some() && received.changed_tiles[0].biome.is_none());
+        assert_eq!(received.statistics.tick, 1);
+    }
+
+    #[tokio::test]
+    async fn compressed_client_receives_deflated_binary() {
+        use std::io::Read;
+
+        let world = make_small_world();
+        let state = Arc::new(ServerState::new(build_snapshot_json(&world)).with_compression(Some(1)));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            for _ in 0..2 {
+                if let Ok((stream, peer)) = listener.accept().await {
+                    let state = Arc::clone(&server_state);
+                    tokio::spawn(async move { handle_websocket(stream, peer, state).await });
+                }
+            }
+        });
+
+        let inflate = |bytes: &[u8]| {
+            let mut text = String::new();
+            flate2::read::DeflateDecoder::new(bytes).read_to_string(&mut text).unwrap();
+            text
+        };
+        type Client = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>>;
+        async fn next(ws: &mut Client) -> Message {
+            tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(ws))
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap()
+        }
+
+        // A client that asks gets deflated binary frames
+        let url = format!("ws://127.0.0.1:{}/?{}", addr.port(), COMPRESSION_QUERY);
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let msg = next(&mut ws).await;
+        assert!(msg.is_binary());
+        let snapshot_len = build_snapshot_json(&world).len();
+        assert!(msg.len() < snapshot_len / 3, "{} vs {}", msg.len(), snapshot_len);
+        let parsed: serde_json::Value = serde_json::from_str(&inflate(&msg.into_data())).unwrap();
+        assert_eq!(parsed["message_type"], "WorldSnapshot");
+
+        // Other clients keep getting text
+        let plain_url = format!("ws://127.0.0.1:{}", addr.port());
+        let (mut plain, _) = tokio_tungstenite::connect_async(&plain_url).await.unwrap();
+        assert!(next(&mut plain).await.is_text());
+
+        let diff = r#"{"message_type":"TickDiff","tick":1}"#;
+        state
+            .on_tick(None, diff.to_string(), &make_test_stats(1), 1, Season::Spring, 100, 0)
+            .await;
+        let msg = next(&mut ws).await;
+        assert_eq!(inflate(&msg.into_data()), diff);
+        assert_eq!(next(&mut plain).await.into_text().unwrap(), diff);
+
+        let clients = state.client_list().await.clients;
+        assert_eq!(clients.iter().filter(|c| c.compressed).count(), 1);
+
+        futures_util::SinkExt::close(&mut ws).await.unwrap();
+        futures_util::SinkExt::close(&mut plain).await.unwrap();
+        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn msgpack_client_receives_binary_and_may_switch_back() {
+        let mut world = make_small_world();
+        let state = Arc::new(ServerState::new(build_snapshot_json(&world)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_websocket(stream, peer, server_state).await;
+            }
+        });
+
+        let url = format!("ws://127.0.0.1:{}/?{}", addr.port(), MSGPACK_QUERY);
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let msg = tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws)).await;
+        let msg = msg.unwrap().unwrap().unwrap();
+        assert!(msg.is_binary());
+        assert!(msg.len() < build_snapshot_json(&world).len());
+        let Ok(ServerMessage::WorldSnapshot(snapshot)) = rmp_serde::from_slice(&msg.into_data()) else {
+            panic!("expected a MessagePack snapshot");
+        };
+        assert_eq!(snapshot.tiles.len(), world.tiles.len());
+        assert_eq!(state.client_list().await.clients[0].format, WireFormat::Msgpack);
+
+        // Typed diffs go out as MessagePack without being encoded as JSON
+        let before = world.capture_layers(&DIFF_LAYERS);
+        world.tiles[3].weather.temperature += 5.0;
+        let diff = build_diff_from_layers(&before, &world, &make_test_stats(1), &[], None, &[], &Default::default());
+        state.on_tick(None, diff.clone(), &make_test_stats(1), 1, Season::Spring, 100, 0).await;
+        let msg = tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws)).await;
+        let received: ServerMessage = rmp_serde::from_slice(&msg.unwrap().unwrap().unwrap().into_data()).unwrap();
+        assert_eq!(received, diff);
+
+        // Switching back to JSON resends the snapshot as text
+        let switch = ClientMessage::SetFormat { format: WireFormat::Json };
+        futures_util::SinkExt::send(&mut ws, Message::Binary(protocol::to_msgpack(&switch).into())).await.unwrap();
+        let msg = tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws)).await;
+        assert!(msg.unwrap().unwrap().unwrap().into_text().unwrap().contains("WorldSnapshot"));
+        assert_eq!(state.client_list().await.clients[0].format, WireFormat::Json);
+    }
+
+    #[test]
+    fn broadcast_encodes_each_form_once_from_either_source() {
+        let message = ServerMessage::TourEnd(TourEnd { tour: "coasts".to_string(), completed: true });
+        let typed = Broadcast::from_message(message.clone());
+        let from_json = Broadcast::new(serde_json::to_string(&message).unwrap());
+        assert_eq!(typed.json(), from_json.json());
+        assert_eq!(typed.msgpack(), from_json.msgpack());
+        assert_eq!(from_json.server_message(), Some(&message));
+
+        let msgpack_deflated = Encoding { format: WireFormat::Msgpack, compression: Some(1) };
+        let Message::Binary(bytes) = typed.message(msgpack_deflated) else { panic!("expected binary") };
+        let mut inflated = Vec::new();
+        std::io::Read::read_to_end(&mut flate2::read::DeflateDecoder::new(&bytes[..]), &mut inflated).unwrap();
+        assert_eq!(rmp_serde::from_slice::<ServerMessage>(&inflated).unwrap(), message);
+
+        // Anything that isn't a server message is carried over from its JSON
+        let other = Broadcast::new(r#"{"message_type":"TickDiff","tick":1}"#.to_string());
+        assert!(other.server_message().is_none());
+        let value: serde_json::Value = rmp_serde::from_slice(other.msgpack()).unwrap();
+        assert_eq!(value["tick"], 1);
+    }
+
+    #[tokio::test]
+    async fn silent_client_is_reaped_and_responsive_client_kept() {
+        let state = Arc::new(ServerState::new("{}".to_string()).with_heartbeat(Some(Heartbeat {
+            interval: Duration::from_millis(50),
+            timeout: Duration::from_millis(200),
+        })));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                if let Ok((stream, peer)) = listener.accept().await {
+                    let state = Arc::clone(&server_state);
+                    tokio::spawn(async move { handle_websocket(stream, peer, state).await });
+                }
+            }
+        });
+
+        let url = format!("ws://127.0.0.1:{}", addr.port());
+        // Never reads after connecting, so never answers pings
+        let (_silent, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let (mut alive, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let pings = tokio::spawn(async move {
+            let mut pings = 0;
+            let deadline = tokio::time::Instant::now() + Duration::from_millis(500);
+            // Reading answers pings automatically
+            while let Ok(Some(Ok(msg))) =
+                tokio::time::timeout_at(deadline, futures_util::StreamExt::next(&mut alive)).await
+            {
+                pings += msg.is_ping() as u32;
+            }
+            (alive, pings)
+        });
+
+        let (_alive, pings) = pings.await.unwrap();
+        assert!(pings >= 5, "{}", pings);
+        let clients = state.client_list().await;
+        assert_eq!(clients.reaped_clients, 1);
+        assert_eq!(clients.client_count, 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_sends_clients_a_close_frame() {
+        let state = Arc::new(ServerState::new(build_snapshot_json(&make_small_world())));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_websocket(stream, peer, server_state).await;
+            }
+        });
+
+        let url = format!("ws://127.0.0.1:{}", addr.port());
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let snapshot = futures_util::StreamExt::next(&mut ws).await.unwrap().unwrap();
+        assert!(snapshot.is_text());
+        assert_eq!(state.client_list().await.client_count, 1);
+
+        let closing = {
+            let state = Arc::clone(&state);
+            tokio::spawn(async move { state.close_clients(Duration::from_secs(5)).await })
+        };
+        let msg = tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws)).await;
+        let Ok(Some(Ok(Message::Close(Some(frame))))) = msg else {
+            panic!("expected a close frame: {:?}", msg);
+        };
+        assert_eq!(frame.code, CloseCode::Away);
+        closing.await.unwrap();
+        assert_eq!(state.client_list().await.client_count, 0);
+    }
+
+    #[tokio::test]
+    async fn health_endpoint_returns_json() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+
+        // Update health data
+        let stats = make_test_stats(42);
+        state
+            .on_tick(
+                Some("{}".to_string()),
+                "{}".to_string(),
+                &stats,
+                42,
+                Season::Autumn,
+                1000,
+                40,
+            )
+            .await;
+
+        // Bind server
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            if let Ok((stream, _peer)) = listener.accept().await {
+                let _ = handle_health_request(stream, server_state).await;
+            }
+        });
+
+        // Make HTTP GET /health request
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response_str = String::from_utf8_lossy(&response);
+
+        assert!(response_str.contains("200 OK"));
+        assert!(response_str.contains("application/json"));
+
+        // Parse JSON body (after headers)
+        let body_start = response_str.find('{').unwrap();
+        let body = &response_str[body_start..];
+        let parsed: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(parsed["tick"], 42);
+        assert_eq!(parsed["tile_count"], 1000);
+        assert_eq!(parsed["snapshot_age_ticks"], 2);
+        assert_eq!(parsed["season"], "Autumn");
+        assert_eq!(parsed["status"], "ok");
+        assert!(parsed.get("status_reasons").is_none());
+
+        let _ = server_handle.await;
+    }
+
+    #[test]
+    fn health_is_judged_by_the_worst_limit_crossed() {
+        let limits = HealthLimits { tick_rate: (5.0, 2.0), rule_error_ratio: (0.01, 0.1), snapshot_age_ticks: (200, 500) };
+        let mut health = ServerState::new("{}".to_string()).health.into_inner();
+        assert_eq!(health.assess(&limits), (HealthState::Ok, Vec::new()));
+
+        health.tile_count = 1000;
+        health.tick = 150;
+        health.recent_tick_durations_ms = vec![100.0];
+        let (verdict, reasons) = health.assess(&limits);
+        assert_eq!(verdict, HealthState::Ok);
+        assert!(reasons.is_empty());
+
+        health.rule_errors = 20;
+        health.tick = 350;
+        let (verdict, reasons) = health.assess(&limits);
+        assert_eq!(verdict, HealthState::Degraded);
+        assert_eq!(reasons.len(), 2, "{:?}", reasons);
+
+        health.recent_tick_durations_ms = vec![1000.0];
+        let (verdict, reasons) = health.assess(&limits);
+        assert_eq!(verdict, HealthState::Unhealthy);
+        assert!(reasons[0].contains("below 2.00/s"), "{:?}", reasons);
+    }
+
+    #[tokio::test]
+    async fn unhealthy_server_answers_503() {
+        let limits = HealthLimits { tick_rate: (0.0, 0.0), rule_error_ratio: (0.0, 0.5), snapshot_age_ticks: (100, 100) };
+        let state = Arc::new(ServerState::new("{}".to_string()).with_health_limits(Some(limits)));
+        let mut stats = make_test_stats(42);
+        stats.rule_errors = 600;
+        state.update_health(&stats, 42, Season::Autumn, 1000, 40).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            if let Ok((stream, _peer)) = listener.accept().await {
+                let _ = handle_health_request(stream, server_state).await;
+            }
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        stream.write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response = String::from_utf8_lossy(&response);
+        assert!(response.starts_with("HTTP/1.1 503"), "{}", response);
+        let parsed: serde_json::Value = serde_json::from_str(&response[response.find('{').unwrap()..]).unwrap();
+        assert_eq!(parsed["status"], "unhealthy");
+        assert_eq!(parsed["status_reasons"][0], "rules failed on 60.0% of tiles last tick");
+
+        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn records_endpoint_returns_current_records() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        let mut records = WorldRecords::default();
+        let mut hot = Tile::new_default(7, vec![], Position::flat(0.0, 0.0));
+        hot.weather.temperature = 320.0;
+        records.update(&[hot], 12);
+        state.set_records(&records).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_connection(stream, peer, server_state).await;
+            }
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        stream
+            .write_all(b"GET /api/records HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response_str = String::from_utf8_lossy(&response);
+        assert!(response_str.contains("200 OK"));
+
+        let body = &response_str[response_str.find('{').unwrap()..];
+        let parsed: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(parsed["highest_temperature"]["value"], 320.0);
+        assert_eq!(parsed["highest_temperature"]["tile_id"], 7);
+        assert_eq!(parsed["highest_temperature"]["tick"], 12);
+
+        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn narration_endpoint_keeps_recent_history() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        for i in 0..NARRATION_HISTORY as u64 + 3 {
+            state
+                .add_narration(Narration {
+                    tick_start: i * 360,
+                    tick_end: (i + 1) * 360,
+                    season: Season::Spring,
+                    lines: vec![format!("Year {}.", i)],
+                })
+                .await;
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_connection(stream, peer, server_state).await;
+            }
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        stream
+            .write_all(b"GET /api/narration HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response_str = String::from_utf8_lossy(&response);
+        assert!(response_str.contains("200 OK"));
+
+        let body = &response_str[response_str.find('{').unwrap()..];
+        let parsed: serde_json::Value = serde_json::from_str(body).unwrap();
+        let narrations = parsed["narrations"].as_array().unwrap();
+        assert_eq!(narrations.len(), NARRATION_HISTORY);
+        assert_eq!(narrations[0]["tick_start"], 3 * 360);
+        assert_eq!(narrations[NARRATION_HISTORY - 1]["lines"][0], "Year 22.");
+
+        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn energy_endpoint_returns_current_potential() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        let mut windy = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        windy.weather.wind_speed = 10.0;
+        let mut energy = EnergyPotential::default();
+        energy.update(&[windy], 0.0, 360);
+        state.set_energy(&energy).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(&state);
+        let server_handle = tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_connection(stream, peer, server_state).await;
+            }
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        stream
+            .write_all(b"GET /api/energy HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response_str = String::from_utf8_lossy(&response);
+        assert!(response_str.contains("200 OK"));
+
+        let body = &response_str[response_str.find('{').unwrap()..];
+        let parsed: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(parsed["samples"], 1);
+        assert!(parsed["wind_power"][0].as_f64().unwrap() > 500.0);
+        assert!(parsed["insolation"][0].as_f64().unwrap() > 0.0);
+
+        let _ = server_handle.await;
     }
 
     #[tokio::test]
-    async fn broadcast_diff_to_subscribers() {
+    async fn client_stats_track_sends_and_lag() {
         let state = ServerState::new("{}".to_string());
-        let mut rx = state.tick_sender.subscribe();
+        let peer: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+
+        let id = state.register_client(peer, Encoding::default()).await;
+        state.record_client_send(id, 100).await;
+        state.record_client_send(id, 50).await;
+        state.record_client_lag(id, 7).await;
+
+        let list = state.client_list().await;
+        assert_eq!(list.client_count, 1);
+        assert_eq!(list.total_bytes_sent, 150);
+        assert_eq!(list.clients[0].peer, "127.0.0.1:9000");
+        assert_eq!(list.clients[0].messages_sent, 2);
+        assert_eq!(list.clients[0].lag_events, 1);
+        assert_eq!(list.clients[0].diffs_dropped, 7);
+
+        state.unregister_client(id).await;
+        assert_eq!(state.client_list().await.client_count, 0);
+    }
 
-        let stats = make_test_stats(1);
-        state
-            .on_tick(
-                Some("{}".to_string()),
-                "test_diff".to_string(),
-                &stats,
-                1,
-                Season::Spring,
-                100,
-                0,
-            )
-            .await;
+    #[tokio::test]
+    async fn clients_endpoint_lists_websocket_clients() {
+        let state = Arc::new(ServerState::new(r#"{"message_type":"WorldSnapshot"}"#.to_string()));
 
-        let received = rx.recv().await.expect("should receive diff");
-        assert_eq!(received, "test_diff");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((stream, peer)) = listener.accept().await {
+                let s = Arc::clone(&server_state);
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, peer, s).await;
+                });
+            }
+        });
+
+        // Connect a viewer and wait for its snapshot
+        let url = format!("ws://127.0.0.1:{}", addr.port());
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), futures_util::StreamExt::next(&mut ws))
+            .await
+            .expect("timeout waiting for snapshot");
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        stream
+            .write_all(b"GET /api/clients HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response_str = String::from_utf8_lossy(&response);
+        assert!(response_str.contains("200 OK"));
+
+        let body_start = response_str.find('{').unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&response_str[body_start..]).unwrap();
+        assert_eq!(parsed["client_count"], 1);
+        let snapshot_len = r#"{"message_type":"WorldSnapshot"}"#.len() as u64;
+        assert_eq!(parsed["clients"][0]["bytes_sent"], snapshot_len);
+        assert_eq!(parsed["clients"][0]["messages_sent"], 1);
+
+        futures_util::SinkExt::close(&mut ws).await.unwrap();
     }
 
     #[tokio::test]
-    async fn websocket_client_receives_snapshot_and_diff() {
+    async fn mutation_metrics_track_last_tick_and_totals() {
+        use crate::simulation::engine::RejectionReason;
+
+        let state = ServerState::new("{}".to_string());
+        let mut stats = MutationStats { applied: 10, ..Default::default() };
+        stats.record_rejection("02-bad.rhai", RejectionReason::UnwritableField);
+        state.record_mutation_stats(1, &stats).await;
+
+        let stats = MutationStats { applied: 7, ..Default::default() };
+        state.record_mutation_stats(2, &stats).await;
+
+        let metrics = state.mutation_metrics().await;
+        assert_eq!(metrics.tick, 2);
+        assert_eq!(metrics.mutations_applied, 7);
+        assert_eq!(metrics.mutations_rejected, 0);
+        assert_eq!(metrics.total_applied, 17);
+        assert_eq!(metrics.total_rejected, 1);
+        assert_eq!(
+            metrics.rejections,
+            vec![RuleRejections {
+                rule: "02-bad.rhai".to_string(),
+                reason: "unwritable_field",
+                last_tick: 0,
+                total: 1,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn rule_metrics_sum_ticks_slowest_first() {
+        use crate::simulation::engine::RuleMetrics;
+        use std::time::Duration;
+
+        let state = ServerState::new("{}".to_string());
+        let tick = |fast_ms, slow_ms| RuleStats {
+            rules: [
+                ("weather/01-fast.rhai".to_string(), RuleMetrics { invocations: 10, errors: 0, time: Duration::from_millis(fast_ms) }),
+                ("conditions/01-slow.rhai".to_string(), RuleMetrics { invocations: 10, errors: 1, time: Duration::from_millis(slow_ms) }),
+            ]
+            .into(),
+        };
+        state.record_rule_stats(&tick(1, 4)).await;
+        state.record_rule_stats(&tick(1, 6)).await;
+
+        let rules = state.mutation_metrics().await.rules;
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].rule, "conditions/01-slow.rhai");
+        assert_eq!((rules[0].invocations, rules[0].errors), (20, 2));
+        assert!((rules[0].total_ms - 10.0).abs() < 1e-9);
+        assert!((rules[0].last_tick_ms - 6.0).abs() < 1e-9);
+        assert!((rules[0].mean_us - 500.0).abs() < 1e-6);
+        assert_eq!(rules[1].rule, "weather/01-fast.rhai");
+    }
+
+    #[tokio::test]
+    async fn handoff_transfers_world_to_successor() {
         let world = make_small_world();
-        let snapshot_json = build_snapshot_json(&world);
-        let state = Arc::new(ServerState::new(snapshot_json));
+        let state = Arc::new(ServerState::new("{}".to_string()));
 
-        // Bind server to ephemeral port
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener = bind_listener("127.0.0.1:0".parse().unwrap(), true).unwrap();
         let addr = listener.local_addr().unwrap();
 
+        // Old server answers one connection and keeps its listener open afterwards
         let server_state = Arc::clone(&state);
-        let server_handle = tokio::spawn(async move {
+        let server = tokio::spawn(async move {
             if let Ok((stream, peer)) = listener.accept().await {
-                let _ = handle_websocket(stream, peer, server_state).await;
+                let _ = handle_connection(stream, peer, server_state).await;
             }
+            listener
         });
 
-        // Connect as WebSocket client
-        let url = format!("ws://127.0.0.1:{}", addr.port());
-        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        // Stand-in for the simulation loop: answer the handoff at the next "tick"
+        let loop_state = Arc::clone(&state);
+        let loop_world = world.clone();
+        let sim = tokio::spawn(async move {
+            while !loop_state.answer_handoff(&loop_world) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
 
-        // Receive snapshot
-        let msg = tokio::time::timeout(
-            Duration::from_secs(5),
-            futures_util::StreamExt::next(&mut ws),
-        )
-        .await
-        .expect("timeout waiting for snapshot")
-        .expect("stream ended")
-        .expect("message error");
+        // Request before binding, as `run --handoff-from` does: with SO_REUSEPORT the
+        // kernel could otherwise route the request to the successor's own listener.
+        let received = request_handoff(&addr.to_string()).await.unwrap();
+        assert_eq!(received, world);
+        sim.await.unwrap();
 
-        let text = msg.into_text().unwrap();
-        let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
-        assert_eq!(parsed["message_type"], "WorldSnapshot");
-        assert_eq!(parsed["tiles"].as_array().unwrap().len(), 100);
+        // Successor can bind the same port while the old server is still listening
+        let _old_listener = server.await.unwrap();
+        let successor = bind_listener(addr, true).unwrap();
+        assert_eq!(successor.local_addr().unwrap(), addr);
+    }
 
-        // Send a tick diff via broadcast
-        let stats = make_test_stats(1);
-        state
-            .on_tick(
-                Some("{}".to_string()),
-                r#"{"message_type":"TickDiff","tick":1}"#.to_string(),
-                &stats,
-                1,
-                Season::Spring,
-                100,
-                0,
-            )
-            .await;
+    #[tokio::test]
+    async fn second_server_cannot_share_port_without_reuse_port() {
+        let first = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = first.local_addr().unwrap();
+        drop(first);
+
+        let _running = bind_listener(addr, false).unwrap();
+        let err = bind_listener(addr, false).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+    }
 
-        // Receive diff
-        let msg = tokio::time::timeout(
-            Duration::from_secs(5),
-            futures_util::StreamExt::next(&mut ws),
-        )
-        .await
-        .expect("timeout waiting for diff")
-        .expect("stream ended")
-        .expect("message error");
+    #[tokio::test]
+    async fn successor_waits_for_port_to_be_released() {
+        let held = bind_listener("127.0.0.1:0".parse().unwrap(), false).unwrap();
+        let addr = held.local_addr().unwrap();
+        let state = ServerState::new("{}".to_string()).with_bind_wait(Some(Duration::from_secs(5)));
 
-        let text = msg.into_text().unwrap();
-        assert!(text.contains("TickDiff"));
+        // The predecessor exits shortly after handing off
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drop(held);
+        });
 
-        // Clean disconnect
-        futures_util::SinkExt::close(&mut ws).await.unwrap();
-        let _ = server_handle.await;
+        let successor = bind_with_wait(&state, addr).await.unwrap();
+        assert_eq!(successor.local_addr().unwrap(), addr);
     }
 
     #[tokio::test]
-    async fn health_endpoint_returns_json() {
+    async fn no_diffs_follow_the_handoff_tick() {
         let state = Arc::new(ServerState::new("{}".to_string()));
+        let mut rx = state.tick_sender.subscribe();
+        let mut world = make_small_world();
+
+        // Stand-in for the simulation loop: broadcast each tick's diff, then
+        // check for a handoff as `run` does. A successor asks during tick 3.
+        let (tx, handed_off) = oneshot::channel();
+        let mut request = Some(tx);
+        let mut stopped_at = None;
+        for tick in 1..=5u64 {
+            world.tick_count = tick;
+            let stats = make_test_stats(tick);
+            state
+                .on_tick(None, format!("diff {}", tick), &stats, tick, Season::Spring, 100, 0)
+                .await;
+            if tick == 3 {
+                *state.handoff_request.lock().unwrap() = request.take();
+            }
+            if state.answer_handoff(&world) {
+                stopped_at = Some(tick);
+                break;
+            }
+        }
+        assert_eq!(stopped_at, Some(3));
 
-        // Update health data
-        let stats = make_test_stats(42);
-        state
-            .on_tick(
-                Some("{}".to_string()),
-                "{}".to_string(),
-                &stats,
-                42,
-                Season::Autumn,
-                1000,
-                40,
-            )
-            .await;
+        let received: World = bincode::deserialize(&handed_off.await.unwrap()).unwrap();
+        let mut last_diff = None;
+        while let Ok(diff) = rx.try_recv() {
+            last_diff = Some(diff.json().to_string());
+        }
+        // The successor resumes exactly where viewers' last diff left off
+        assert_eq!(received.tick_count, 3);
+        assert_eq!(last_diff.as_deref(), Some("diff 3"));
+    }
+
+    #[tokio::test]
+    async fn second_handoff_request_conflicts() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
 
-        // Bind server
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
         let addr = listener.local_addr().unwrap();
 
         let server_state = Arc::clone(&state);
-        let server_handle = tokio::spawn(async move {
-            if let Ok((stream, _peer)) = listener.accept().await {
-                let _ = handle_health_request(stream, server_state).await;
+        tokio::spawn(async move {
+            while let Ok((stream, peer)) = listener.accept().await {
+                let s = Arc::clone(&server_state);
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, peer, s).await;
+                });
             }
         });
 
-        // Make HTTP GET /health request
+        // First request waits for the simulation loop, which never answers here
+        let first_addr = addr.to_string();
+        let _first = tokio::spawn(async move { request_handoff(&first_addr).await });
+        while state.handoff_request.lock().unwrap().is_none() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let err = request_handoff(&addr.to_string()).await.unwrap_err();
+        assert!(matches!(err, WorldgroundError::Server(_)), "{:?}", err);
+        assert!(err.to_string().contains("409"), "unexpected error: {}", err);
+    }
+
+    #[tokio::test]
+    async fn frozen_endpoint_queues_updates() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        state.set_frozen_tiles(&[9].into_iter().collect()).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_connection(stream, peer, server_state).await;
+            }
+        });
+
+        let body = r#"{"add":[1,2],"remove":[9]}"#;
         let mut stream = TcpStream::connect(addr).await.unwrap();
         use tokio::io::{AsyncReadExt, AsyncWriteExt};
         stream
-            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .write_all(
+                format!(
+                    "POST /api/frozen HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
             .await
             .unwrap();
 
         let mut response = Vec::new();
         stream.read_to_end(&mut response).await.unwrap();
         let response_str = String::from_utf8_lossy(&response);
+        assert!(response_str.contains("202 Accepted"));
+        assert!(response_str.contains(r#""frozen_tiles":[9]"#));
+        assert!(response_str.contains(r#""pending_updates":1"#));
+
+        let updates = state.take_frozen_updates();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].add, vec![1, 2]);
+        assert_eq!(updates[0].remove, vec![9]);
+        assert!(state.take_frozen_updates().is_empty());
+    }
 
-        assert!(response_str.contains("200 OK"));
-        assert!(response_str.contains("application/json"));
+    /// Send one HTTP request to a fresh connection handler and return the response.
+    async fn http_request(state: &Arc<ServerState>, request: String) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-        // Parse JSON body (after headers)
-        let body_start = response_str.find('{').unwrap();
-        let body = &response_str[body_start..];
-        let parsed: serde_json::Value = serde_json::from_str(body).unwrap();
-        assert_eq!(parsed["tick"], 42);
-        assert_eq!(parsed["tile_count"], 1000);
-        assert_eq!(parsed["snapshot_age_ticks"], 2);
-        assert_eq!(parsed["season"], "Autumn");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = Arc::clone(state);
+        tokio::spawn(async move {
+            if let Ok((stream, peer)) = listener.accept().await {
+                let _ = handle_connection(stream, peer, server_state).await;
+            }
+        });
 
-        let _ = server_handle.await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        String::from_utf8_lossy(&response).to_string()
+    }
+
+    fn post(path: &str, body: &str) -> String {
+        format!("POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", path, body.len(), body)
+    }
+
+    #[tokio::test]
+    async fn bookmark_edits_queue_and_tours_play_to_viewers() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        let body = r#"{"set":{"a":{"tile_id":3,"caption":"Lake"},"b":{"tile_id":40,"radius_deg":12}},
+            "tours":{"lakes":{"stops":[{"bookmark":"a","dwell_secs":0.05},{"bookmark":"b","dwell_secs":0.05}]}}}"#;
+        let response = http_request(&state, post("/api/bookmarks", body)).await;
+        assert!(response.contains("202 Accepted"), "{}", response);
+        assert!(response.contains(r#""pending_updates":1"#));
+
+        // The simulation loop applies the edit and publishes the result
+        let mut world = make_small_world();
+        for update in state.take_bookmarks_updates() {
+            let skipped =
+                world.bookmarks.apply(&world.tiles, &update.set, &update.remove, &update.tours, &update.remove_tours);
+            assert!(skipped.is_empty(), "{:?}", skipped);
+        }
+        state.set_bookmarks(&world.bookmarks).await;
+        let listing = http_request(&state, "GET /api/bookmarks HTTP/1.1\r\n\r\n".to_string()).await;
+        assert!(listing.contains(r#""caption":"Lake""#) && listing.contains(r#""lakes""#));
+
+        let response = http_request(&state, post("/api/tours/start", r#"{"tour":"nowhere"}"#)).await;
+        assert!(response.contains("404 Not Found"));
+
+        let mut rx = state.tick_sender.subscribe();
+        let response = http_request(&state, post("/api/tours/start", r#"{"tour":"lakes"}"#)).await;
+        assert!(response.contains(r#""active_tour":"lakes""#), "{}", response);
+
+        let mut messages = Vec::new();
+        for _ in 0..3 {
+            let broadcast = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+            messages.push(serde_json::from_str::<ServerMessage>(broadcast.json()).unwrap());
+        }
+        let ServerMessage::TourStep(first) = &messages[0] else { panic!("{:?}", messages[0]) };
+        assert_eq!((first.step, first.steps, first.bookmark.as_str()), (0, 2, "a"));
+        assert_eq!(first.view.lat, world.tiles[3].position.lat);
+        assert!(matches!(&messages[1], ServerMessage::TourStep(step) if step.view.radius_deg == 12.0));
+        assert!(matches!(&messages[2], ServerMessage::TourEnd(end) if end.completed));
+    }
+
+    #[tokio::test]
+    async fn survey_endpoint_reveals_hidden_deposits_to_clients() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        let mut world = make_small_world();
+        world.tiles[5].resources.resources = vec![crate::world::tile::ResourceDeposit {
+            resource_type: "silver".to_string(),
+            quantity: 10.0,
+            max_quantity: 10.0,
+            renewal_rate: 0.0,
+            requires_biome: None,
+            quality: 0.9,
+            discovered: false,
+        }];
+        assert!(!build_snapshot_json(&world).contains("silver"));
+
+        let response = http_request(&state, post("/api/survey", r#"{"tiles":[5]}"#)).await;
+        assert!(response.contains("202 Accepted") && response.contains(r#""pending_tiles":1"#), "{}", response);
+        let response = http_request(&state, post("/api/survey", "[5]")).await;
+        assert!(response.contains("400 Bad Request"));
+
+        let before = world.capture_layers(&DIFF_LAYERS);
+        assert_eq!(world.survey_tiles(&state.take_survey_requests()), (1, Vec::new()));
+        let diff = build_diff_json_from_layers(&before, &world, &make_test_stats(1), &[], None, &[], &Default::default());
+        assert!(diff.contains(r#""resource_type":"silver""#) && diff.contains(r#""quality":0.9"#), "{}", diff);
+        assert!(build_snapshot_json(&world).contains("silver"));
+    }
+
+    #[tokio::test]
+    async fn observations_endpoint_queues_observations_in_kelvin() {
+        use worldground_protocol::units::{HumidityUnit, TemperatureUnit};
+        let units = Units { temperature: TemperatureUnit::Celsius, humidity: HumidityUnit::Percent };
+        let state = Arc::new(ServerState::new("{}".to_string()).with_units(units));
+
+        let body = r#"{"observations":[{"tile":3,"temperature":15.0,"humidity":70.0,"ticks":4}]}"#;
+        let response = http_request(&state, post("/api/observations", body)).await;
+        assert!(response.contains("202 Accepted") && response.contains(r#""pending_observations":1"#), "{}", response);
+        let body = r#"{"observations":[{"tile":3,"humidity":150.0}]}"#;
+        let response = http_request(&state, post("/api/observations", body)).await;
+        assert!(response.contains("400 Bad Request"), "{}", response);
+
+        let observations = state.take_observations();
+        assert_eq!(observations.len(), 1);
+        assert!((observations[0].temperature.unwrap() - 288.15).abs() < 1e-4);
+        assert!((observations[0].humidity.unwrap() - 0.7).abs() < 1e-6);
+        assert_eq!(observations[0].ticks, 4);
+        assert!(state.take_observations().is_empty());
+    }
+
+    #[test]
+    fn routes_parse_method_path_and_query() {
+        let route = Route::parse("get /Tiles/?biome=Desert&x=1 HTTP/1.1\r\nHost: localhost\r\n");
+        assert_eq!(
+            route,
+            Route { method: "GET".to_string(), path: "/tiles".to_string(), query: "biome=Desert&x=1".to_string() }
+        );
+        assert_eq!(route.param("biome"), Some("Desert"));
+        assert_eq!(route.param("y"), None);
+        assert_eq!(Route::parse("GET / HTTP/1.1").path, "/");
+        assert_eq!(Route::parse("").method, "");
+    }
+
+    #[tokio::test]
+    async fn query_endpoints_answer_from_the_simulation() {
+        let mut world = make_small_world();
+        world.set_inert_tiles([1].into());
+        let biome = world.tiles[0].biome.biome_type;
+        let state = Arc::new(ServerState::new("{}".to_string()));
+
+        // Stand-in for the simulation loop answering queries between ticks
+        let loop_state = Arc::clone(&state);
+        let loop_world = world.clone();
+        let sim = tokio::spawn(async move {
+            loop {
+                loop_state.answer_tile_queries(&loop_world);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+
+        let get = |path: &str| format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        let response = http_request(&state, get("/tiles/0")).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        let body: serde_json::Value = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["tile"]["id"], 0);
+        assert_eq!(body["tick"], world.tick_count);
+
+        // Inert and unknown tiles don't exist as far as the API is concerned
+        assert!(http_request(&state, get("/tiles/1")).await.contains("404 Not Found"));
+        assert!(http_request(&state, get("/tiles/100")).await.contains("404 Not Found"));
+        assert!(http_request(&state, get("/tiles/x")).await.contains("400 Bad Request"));
+        assert!(http_request(&state, get("/tiles?biome=Swamp")).await.contains("400 Bad Request"));
+
+        let name = serde_json::to_value(biome).unwrap();
+        let response = http_request(&state, get(&format!("/tiles?biome={}", name.as_str().unwrap()))).await;
+        let body: serde_json::Value = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        let expected = world.tiles.iter().filter(|t| t.id != 1 && t.biome.biome_type == biome).count();
+        assert_eq!(body["count"], expected);
+        assert!(body["tiles"].as_array().unwrap().iter().all(|t| t["biome"]["biome_type"] == name));
+        let response = http_request(&state, get("/tiles")).await;
+        assert!(response.contains(r#""count":99"#), "{}", response);
+        sim.abort();
+
+        // Statistics and pressure systems are published after each tick
+        assert!(http_request(&state, get("/statistics")).await.contains("503 Service Unavailable"));
+        state.update_health(&make_test_stats(7), 7, Season::Spring, 100, 0).await;
+        let response = http_request(&state, get("/statistics")).await;
+        assert!(response.contains(r#""tick":7"#) && response.contains(r#""diversity_index":0.65"#), "{}", response);
+
+        state.set_pressure_systems(&world.macro_weather.systems).await;
+        let response = http_request(&state, get("/pressure-systems")).await;
+        let body: Vec<PressureSystemSnapshot> =
+            serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body.len(), world.macro_weather.systems.len());
+    }
+
+    #[tokio::test]
+    async fn palette_endpoint_serves_the_configured_palette() {
+        let get = "GET /api/palette HTTP/1.1\r\nHost: localhost\r\n\r\n".to_string();
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        let response = http_request(&state, get.clone()).await;
+        let body: Palette = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body, Palette::default());
+
+        let mut palette = Palette::default();
+        palette.biomes.get_mut("Desert").unwrap().color = "#ffcc00".to_string();
+        let state = Arc::new(ServerState::new("{}".to_string()).with_palette(palette));
+        let response = http_request(&state, get).await;
+        assert!(response.contains(r##""Desert":{"color":"#ffcc00""##), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn control_endpoint_pauses_and_steps_the_loop() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        assert!(state.take_tick_permit());
+
+        let response = http_request(&state, post("/api/control", r#"{"action":"pause"}"#)).await;
+        assert!(response.contains(r#""paused":true"#), "{}", response);
+        assert!(!state.take_tick_permit());
+
+        let response = http_request(&state, post("/api/control", r#"{"action":"step","ticks":2}"#)).await;
+        assert!(response.contains(r#""pending_steps":2"#), "{}", response);
+        assert!(state.take_tick_permit() && state.take_tick_permit());
+        assert!(!state.take_tick_permit());
+        assert!(state.is_paused());
+
+        let response = http_request(&state, post("/api/control", r#"{"action":"rewind"}"#)).await;
+        assert!(response.contains("400 Bad Request"));
+
+        // A paused loop waiting for a change is woken by resume
+        let waiter = Arc::clone(&state);
+        let woken = tokio::spawn(async move { waiter.control_changed().await });
+        http_request(&state, post("/api/control", r#"{"action":"resume"}"#)).await;
+        tokio::time::timeout(Duration::from_secs(5), woken).await.unwrap().unwrap();
+        assert!(state.take_tick_permit() && !state.is_paused());
+
+        let status = http_request(&state, "GET /api/control HTTP/1.1\r\n\r\n".to_string()).await;
+        assert!(status.contains(r#""paused":false"#) && status.contains(r#""pending_steps":0"#));
+    }
+
+    #[tokio::test]
+    async fn rollback_is_limited_to_the_checkpoints_held() {
+        let state = Arc::new(ServerState::new("{}".to_string()));
+        let response = http_request(&state, post("/api/control", r#"{"action":"rollback"}"#)).await;
+        assert!(response.contains("409 Conflict"), "{}", response);
+        assert_eq!(state.take_rollback(), None);
+
+        state.set_rollback_depth(5);
+        let response = http_request(&state, post("/api/control", r#"{"action":"rollback","ticks":3}"#)).await;
+        assert!(response.contains(r#""paused":true"#) && response.contains(r#""rollback_ticks":2"#), "{}", response);
+        let response = http_request(&state, post("/api/control", r#"{"action":"rollback","ticks":3}"#)).await;
+        assert!(response.contains("409 Conflict"), "{}", response);
+        assert_eq!(state.take_rollback(), Some(3));
+        assert_eq!(state.take_rollback(), None);
+        assert!(!state.take_tick_permit());
     }
 
     #[tokio::test]
@@ -1,119 +1,236 @@
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
+use crate::simulation::assimilation::Observation;
+use crate::simulation::sphere_math::{solar_declination, year_fraction};
 use crate::simulation::statistics::TickStatistics;
+use crate::world::bookmarks::Tour;
 use crate::world::tile::*;
 use crate::world::weather_systems::PressureSystem;
-use crate::world::World;
+use crate::world::layers::DIFF_LAYERS;
+use crate::world::{LayerArrays, World};
+
+// WebSocket and health messages are shared with clients through the
+// worldground-protocol crate
+pub use worldground_protocol::{
+    Bookmark, ClientMessage, DiffLayer, EventValue, Events, HealthState, HealthStatus, PressureSystemSnapshot,
+    ServerMessage, Subscription, SunSnapshot, TickDiff, TickStatSummary, TileChange, TileSnapshot, TourEnd, TourStep,
+    WireFormat, WorldEvent, WorldSnapshot,
+};
+
+/// Encode a message as MessagePack with named fields, the form
+/// `WireFormat::Msgpack` clients decode.
+pub fn to_msgpack<T: Serialize + ?Sized>(message: &T) -> Vec<u8> {
+    rmp_serde::to_vec_named(message).unwrap_or_default()
+}
 
-/// Complete world state sent to a client on connect.
+impl From<&PressureSystem> for PressureSystemSnapshot {
+    fn from(sys: &PressureSystem) -> Self {
+        PressureSystemSnapshot {
+            id: sys.id,
+            lat: sys.lat,
+            lon: sys.lon,
+            pressure_anomaly: sys.pressure_anomaly,
+            radius: sys.radius,
+            system_type: format!("{:?}", sys.system_type),
+            moisture: sys.moisture,
+            velocity_east: sys.velocity_east,
+            velocity_north: sys.velocity_north,
+        }
+    }
+}
+
+/// Sun position for a tick (see `year_fraction`).
+pub fn sun_snapshot(season: Season, tick: u64, season_length: u32) -> SunSnapshot {
+    SunSnapshot {
+        subsolar_lat: solar_declination(year_fraction(season, tick, season_length)),
+        subsolar_lon: 0.0,
+    }
+}
+
+/// Per-connection statistics for the clients endpoint.
 #[derive(Debug, Clone, Serialize)]
-pub struct WorldSnapshot {
-    pub message_type: &'static str,
-    pub world_id: String,
-    pub name: String,
-    pub tick: u64,
-    pub season: Season,
-    pub season_length: u32,
-    pub tile_count: u32,
-    pub topology_type: TopologyType,
-    pub tiles: Vec<TileSnapshot>,
-    pub pressure_systems: Vec<PressureSystemSnapshot>,
+pub struct ClientInfo {
+    pub id: u64,
+    pub peer: String,
+    pub connected_secs: u64,
+    pub bytes_sent: u64,
+    pub messages_sent: u64,
+    pub lag_events: u64,
+    pub diffs_dropped: u64,
+    /// Subscription filters requested by the client (empty = everything).
+    pub filters: Vec<String>,
+    /// Whether the client receives deflated binary messages.
+    pub compressed: bool,
+    /// How the client's messages are encoded.
+    pub format: WireFormat,
 }
 
-/// A tile's complete state in a snapshot.
+/// Clients endpoint response.
 #[derive(Debug, Clone, Serialize)]
-pub struct TileSnapshot {
-    pub id: u32,
-    pub position: Position,
-    pub geology: GeologyLayer,
-    pub climate: ClimateLayer,
-    pub biome: BiomeLayer,
-    pub resources: ResourceLayer,
-    pub weather: WeatherLayer,
-    pub conditions: ConditionsLayer,
+pub struct ClientList {
+    pub client_count: usize,
+    pub total_bytes_sent: u64,
+    /// Connections dropped since startup for not answering pings.
+    pub reaped_clients: u64,
+    pub clients: Vec<ClientInfo>,
 }
 
-/// Per-tick diff sent after each simulation tick.
+/// Rejected-mutation counts for one rule and reason.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleRejections {
+    pub rule: String,
+    pub reason: &'static str,
+    /// Rejections in the most recent tick.
+    pub last_tick: u64,
+    /// Rejections since the server started.
+    pub total: u64,
+}
+
+/// Execution counts and time for one rule since the server started.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleExecution {
+    /// "phase/file", e.g. "weather/01-temperature.rhai"
+    pub rule: String,
+    /// Tile evaluations of the rule
+    pub invocations: u64,
+    /// Evaluations that ended in a script error
+    pub errors: u64,
+    /// Time summed over threads
+    pub total_ms: f64,
+    /// Time in the most recent tick
+    pub last_tick_ms: f64,
+    /// Mean time per evaluation
+    pub mean_us: f64,
+}
+
+/// Metrics endpoint response.
 #[derive(Debug, Clone, Serialize)]
-pub struct TickDiff {
-    pub message_type: &'static str,
+pub struct MutationMetrics {
     pub tick: u64,
-    pub season: Season,
-    pub changed_tiles: Vec<TileChange>,
-    pub statistics: TickStatSummary,
-    pub pressure_systems: Vec<PressureSystemSnapshot>,
+    pub mutations_applied: u64,
+    pub mutations_rejected: u64,
+    pub total_applied: u64,
+    pub total_rejected: u64,
+    pub rejections: Vec<RuleRejections>,
+    /// Slowest first
+    pub rules: Vec<RuleExecution>,
 }
 
-/// Changed fields for a single tile in a diff.
+/// Body of POST /api/frozen: tile IDs to freeze and thaw.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FrozenTilesUpdate {
+    #[serde(default)]
+    pub add: Vec<u32>,
+    #[serde(default)]
+    pub remove: Vec<u32>,
+}
+
+/// Frozen tiles endpoint response.
 #[derive(Debug, Clone, Serialize)]
-pub struct TileChange {
-    pub id: u32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub weather: Option<WeatherLayer>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub conditions: Option<ConditionsLayer>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub biome: Option<BiomeLayer>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub resources: Option<ResourceLayer>,
+pub struct FrozenTilesStatus {
+    pub frozen_tiles: Vec<u32>,
+    /// Updates queued but not yet applied (they take effect at the next tick).
+    pub pending_updates: usize,
+}
+
+/// Body of POST /api/survey: tiles whose deposits should be revealed.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SurveyRequest {
+    pub tiles: Vec<u32>,
 }
 
-/// Summary statistics included in tick diffs.
+/// Survey endpoint response.
 #[derive(Debug, Clone, Serialize)]
-pub struct TickStatSummary {
-    pub tick: u64,
-    pub biome_distribution: HashMap<String, u32>,
-    pub avg_temperature: f32,
-    pub avg_moisture: f32,
-    pub avg_vegetation_health: f32,
-    pub diversity_index: f32,
-    pub rule_errors: u32,
-    pub tick_duration_ms: f32,
+pub struct SurveyStatus {
+    /// Tiles queued for survey (they are revealed at the next tick).
+    pub pending_tiles: usize,
+}
+
+/// Body of POST /api/observations: observed weather to nudge tiles toward,
+/// in the server's units.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ObservationsRequest {
+    pub observations: Vec<Observation>,
 }
 
-/// Snapshot of a pressure system for the wire protocol.
+/// Observations endpoint response.
 #[derive(Debug, Clone, Serialize)]
-pub struct PressureSystemSnapshot {
-    pub id: u32,
-    pub lat: f64,
-    pub lon: f64,
-    pub pressure_anomaly: f32,
-    pub radius: f32,
-    pub system_type: String,
-    pub moisture: f32,
+pub struct ObservationsStatus {
+    /// Observations queued (they start nudging at the next tick).
+    pub pending_observations: usize,
 }
 
-impl PressureSystemSnapshot {
-    pub fn from_system(sys: &PressureSystem) -> Self {
-        PressureSystemSnapshot {
-            id: sys.id,
-            lat: sys.lat,
-            lon: sys.lon,
-            pressure_anomaly: sys.pressure_anomaly,
-            radius: sys.radius,
-            system_type: format!("{:?}", sys.system_type),
-            moisture: sys.moisture,
-        }
-    }
+/// Body of POST /api/bookmarks: bookmarks and tours to add, replace and remove.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BookmarksUpdate {
+    #[serde(default)]
+    pub set: BTreeMap<String, Bookmark>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+    #[serde(default)]
+    pub tours: BTreeMap<String, Tour>,
+    #[serde(default)]
+    pub remove_tours: Vec<String>,
 }
 
-/// Health endpoint response.
+/// Bookmarks endpoint response.
 #[derive(Debug, Clone, Serialize)]
-pub struct HealthStatus {
+pub struct BookmarksStatus {
+    pub bookmarks: BTreeMap<String, Bookmark>,
+    pub tours: BTreeMap<String, Tour>,
+    /// Updates queued but not yet applied (they take effect at the next tick).
+    pub pending_updates: usize,
+    /// Tour currently playing to viewers, if any.
+    pub active_tour: Option<String>,
+}
+
+/// Body of POST /api/tours/start.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TourRequest {
+    pub tour: String,
+}
+
+/// What POST /api/control asks the simulation loop to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlAction {
+    Pause,
+    Resume,
+    /// Run `ticks` more ticks, then stay paused.
+    Step,
+    /// Undo the last `ticks` ticks from in-memory checkpoints and pause.
+    Rollback,
+}
+
+/// Body of POST /api/control, e.g. `{"action": "step", "ticks": 5}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct ControlRequest {
+    pub action: ControlAction,
+    /// Ticks to run for `step` or undo for `rollback` (default 1); ignored otherwise.
+    #[serde(default = "default_step_ticks")]
+    pub ticks: u32,
+}
+
+fn default_step_ticks() -> u32 {
+    1
+}
+
+/// Control endpoint response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ControlStatus {
+    /// Last completed tick
     pub tick: u64,
-    pub tick_rate: f32,
-    pub diversity_index: f32,
-    pub rule_errors: u32,
-    pub snapshot_age_ticks: u64,
-    pub tile_count: u32,
-    pub season: Season,
+    pub paused: bool,
+    /// Steps requested but not yet run.
+    pub pending_steps: u32,
+    /// Ticks that can be rolled back (0 unless `checkpoint_ticks` is set).
+    pub rollback_ticks: u64,
 }
 
-impl WorldSnapshot {
-    pub fn from_world(world: &World) -> Self {
+impl From<&World> for WorldSnapshot {
+    fn from(world: &World) -> Self {
         WorldSnapshot {
-            message_type: "WorldSnapshot",
             world_id: world.id.to_string(),
             name: world.name.clone(),
             tick: world.tick_count,
@@ -122,33 +239,15 @@ impl WorldSnapshot {
             tile_count: world.tile_count,
             topology_type: world.topology_type,
             tiles: world.tiles.iter().map(TileSnapshot::from_tile).collect(),
-            pressure_systems: world
-                .macro_weather
-                .systems
-                .iter()
-                .map(PressureSystemSnapshot::from_system)
-                .collect(),
-        }
-    }
-}
-
-impl TileSnapshot {
-    pub fn from_tile(tile: &Tile) -> Self {
-        TileSnapshot {
-            id: tile.id,
-            position: tile.position,
-            geology: tile.geology.clone(),
-            climate: tile.climate.clone(),
-            biome: tile.biome.clone(),
-            resources: tile.resources.clone(),
-            weather: tile.weather.clone(),
-            conditions: tile.conditions.clone(),
+            pressure_systems: world.macro_weather.systems.iter().map(Into::into).collect(),
+            sun: sun_snapshot(world.season, world.tick_count, world.season_length),
+            units: Default::default(),
         }
     }
 }
 
-impl TickStatSummary {
-    pub fn from_statistics(stats: &TickStatistics) -> Self {
+impl From<&TickStatistics> for TickStatSummary {
+    fn from(stats: &TickStatistics) -> Self {
         TickStatSummary {
             tick: stats.tick,
             biome_distribution: stats
@@ -169,47 +268,13 @@ impl TickStatSummary {
 /// Compute tile-level diffs between two world states.
 /// Returns only tiles where weather, conditions, biome, or resources changed.
 pub fn compute_tile_diffs(before: &[Tile], after: &[Tile]) -> Vec<TileChange> {
-    let mut changes = Vec::new();
-
-    for (old, new) in before.iter().zip(after.iter()) {
-        let weather_changed = old.weather != new.weather;
-        let conditions_changed = old.conditions != new.conditions;
-        let biome_changed = old.biome != new.biome;
-        let resources_changed = old.resources != new.resources;
-
-        if weather_changed || conditions_changed || biome_changed || resources_changed {
-            changes.push(TileChange {
-                id: new.id,
-                weather: if weather_changed {
-                    Some(new.weather.clone())
-                } else {
-                    None
-                },
-                conditions: if conditions_changed {
-                    Some(new.conditions.clone())
-                } else {
-                    None
-                },
-                biome: if biome_changed {
-                    Some(new.biome.clone())
-                } else {
-                    None
-                },
-                resources: if resources_changed {
-                    Some(new.resources.clone())
-                } else {
-                    None
-                },
-            });
-        }
-    }
-
-    changes
+    LayerArrays::capture(before, &DIFF_LAYERS).changes(after)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
     use crate::world::tile::{Position, Tile};
 
     fn make_tile(id: u32) -> Tile {
@@ -230,21 +295,28 @@ mod tests {
             generation_params: crate::config::generation::GenerationParams {
                 seed: 1,
                 tile_count: 3,
-                ocean_ratio: 0.6,
-                mountain_ratio: 0.1,
-                elevation_roughness: 0.5,
-                climate_bands: true,
-                resource_density: 0.3,
-                initial_biome_maturity: 0.5,
-                topology: crate::config::generation::TopologyConfig::default(),
+                ..Default::default()
             },
             snapshot_path: None,
             macro_weather: Default::default(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: Default::default(),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
             tiles: vec![make_tile(0), make_tile(1), make_tile(2)],
         };
 
-        let snapshot = WorldSnapshot::from_world(&world);
-        assert_eq!(snapshot.message_type, "WorldSnapshot");
+        let snapshot = WorldSnapshot::from(&world);
         assert_eq!(snapshot.tick, 42);
         assert_eq!(snapshot.season, Season::Summer);
         assert_eq!(snapshot.tiles.len(), 3);
@@ -266,20 +338,28 @@ mod tests {
             generation_params: crate::config::generation::GenerationParams {
                 seed: 1,
                 tile_count: 1,
-                ocean_ratio: 0.6,
-                mountain_ratio: 0.1,
-                elevation_roughness: 0.5,
-                climate_bands: true,
-                resource_density: 0.3,
-                initial_biome_maturity: 0.5,
-                topology: crate::config::generation::TopologyConfig::default(),
+                ..Default::default()
             },
             snapshot_path: None,
             macro_weather: Default::default(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: Default::default(),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
             tiles: vec![make_tile(0)],
         };
 
-        let snapshot = WorldSnapshot::from_world(&world);
+        let snapshot = ServerMessage::WorldSnapshot(WorldSnapshot::from(&world));
         let json = serde_json::to_string(&snapshot).expect("serialization should succeed");
         assert!(json.contains("\"message_type\":\"WorldSnapshot\""));
         assert!(json.contains("\"name\":\"json_test\""));
@@ -337,7 +417,6 @@ mod tests {
     #[test]
     fn tick_diff_serializes_to_json() {
         let diff = TickDiff {
-            message_type: "TickDiff",
             tick: 5,
             season: Season::Winter,
             changed_tiles: vec![TileChange {
@@ -352,13 +431,17 @@ mod tests {
                     humidity: 0.7,
                     storm_intensity: 0.3,
                     pressure: 1013.25,
+                    station_pressure: 1013.25,
                     macro_wind_speed: 0.0,
                     macro_wind_direction: 0.0,
                     macro_humidity: 0.0,
+                    aurora: 0.0,
+                    dust: 0.0,
                 }),
                 conditions: None,
                 biome: None,
                 resources: None,
+                scratch: None,
             }],
             statistics: TickStatSummary {
                 tick: 5,
@@ -371,14 +454,20 @@ mod tests {
                 tick_duration_ms: 50.0,
             },
             pressure_systems: vec![],
+            sun: sun_snapshot(Season::Winter, 5, 90),
+            floods: vec![],
+            aurora: None,
+            depletions: vec![],
+            effects: BTreeMap::new(),
         };
 
-        let json = serde_json::to_string(&diff).expect("serialization should succeed");
+        let json = serde_json::to_string(&ServerMessage::TickDiff(diff)).expect("serialization should succeed");
         assert!(json.contains("\"message_type\":\"TickDiff\""));
         assert!(json.contains("\"tick\":5"));
         // Null layers should not appear in JSON (skip_serializing_if)
         assert!(!json.contains("\"conditions\":null"));
         assert!(!json.contains("\"biome\":null"));
+        assert!(!json.contains("\"scratch\""));
     }
 
     #[test]
@@ -391,10 +480,60 @@ mod tests {
             snapshot_age_ticks: 5,
             tile_count: 1000,
             season: Season::Autumn,
+            jitter_p95_ms: 3.5,
+            jitter_p99_ms: 12.0,
+            overrun_ticks: 0,
+            overrunning: false,
+            invariant_violations: 0,
+            failing_invariants: Vec::new(),
+            paused: false,
+            status: HealthState::Ok,
+            status_reasons: Vec::new(),
         };
 
         let json = serde_json::to_string(&health).expect("serialization should succeed");
         assert!(json.contains("\"tick\":100"));
         assert!(json.contains("\"tick_rate\":1.0"));
     }
+
+    #[test]
+    fn pressure_system_snapshot_includes_motion() {
+        use crate::world::weather_systems::PressureSystemType;
+
+        let system = PressureSystem {
+            id: 3,
+            lat: 40.0,
+            lon: 10.0,
+            x: 0.75,
+            y: 0.13,
+            z: 0.64,
+            pressure_anomaly: -12.0,
+            radius: 0.3,
+            velocity_east: 0.02,
+            velocity_north: -0.01,
+            age: 5,
+            max_age: 100,
+            system_type: PressureSystemType::MidLatCyclone,
+            moisture: 0.6,
+        };
+
+        let snap = PressureSystemSnapshot::from(&system);
+        assert_eq!(snap.system_type, "MidLatCyclone");
+        let json: serde_json::Value = serde_json::to_value(&snap).unwrap();
+        assert!((json["velocity_east"].as_f64().unwrap() - 0.02).abs() < 1e-6);
+        assert!((json["velocity_north"].as_f64().unwrap() + 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sun_follows_seasons() {
+        let lat = |season, tick| sun_snapshot(season, tick, 90).subsolar_lat;
+        assert!(lat(Season::Spring, 0).abs() < 1e-9);
+        assert!((lat(Season::Summer, 90) - 23.44).abs() < 1e-9);
+        assert!(lat(Season::Autumn, 180).abs() < 1e-9);
+        assert!((lat(Season::Winter, 270) + 23.44).abs() < 1e-9);
+        // Mid-spring, sun is heading north
+        let mid = lat(Season::Spring, 45);
+        assert!(mid > 0.0 && mid < 23.44);
+        assert_eq!(sun_snapshot(Season::Spring, 45, 90).subsolar_lon, 0.0);
+    }
 }
@@ -0,0 +1,184 @@
+//! Skipping rule evaluation on quiet tiles.
+//!
+//! Much of a large world barely changes from one tick to the next: open
+//! ocean, settled desert, ice sheets. With `idle_skip_interval` set, each tile
+//! keeps an activity score, a decaying sum of how far its weather, soil and
+//! vegetation moved each tick, raised to the full threshold under a storm or
+//! an event (flood, landslide, eruption, calving). A tile whose score has
+//! decayed below `idle_threshold` is idle, and the rule phases evaluate it
+//! only every `idle_skip_interval`th tick, staggered across tiles so the work
+//! is spread evenly. As a guardrail a tile is never idle while one of its
+//! neighbors changed by more than the threshold last tick, so a front moving
+//! in wakes the tiles ahead of it. Native steps still run on every tile.
+
+use serde::{Deserialize, Serialize};
+
+use super::effects::{TileEffect, TileEffects};
+use crate::world::{Tile, World};
+
+/// Share of its activity score a tile keeps from one tick to the next.
+const DECAY: f32 = 0.5;
+
+/// Storm intensity at which a tile counts as fully active.
+const STORM_INTENSITY: f32 = 0.1;
+
+/// Idle-tile skipping settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleSkip {
+    /// Activity score below which a tile is idle
+    pub threshold: f32,
+    /// Idle tiles are evaluated every this many ticks
+    pub interval: u32,
+}
+
+/// Per-tile activity, saved with the world. The vectors are indexed by tile
+/// and start over when the tile count changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Activity {
+    /// Decaying activity score of each tile
+    pub scores: Vec<f32>,
+    /// How far each tile moved over the last tick
+    pub changes: Vec<f32>,
+    /// Tiles the rule phases skip this tick, chosen at its start
+    #[serde(skip)]
+    pub skipped: Vec<bool>,
+}
+
+impl Activity {
+    /// Whether the rule phases skip the tile at `index` this tick.
+    pub fn is_skipped(&self, index: usize) -> bool {
+        self.skipped.get(index).copied().unwrap_or(false)
+    }
+}
+
+/// The values a tile's movement is measured on, each scaled so that about
+/// 1.0 is a large change in one tick.
+pub fn measure(tile: &Tile) -> [f32; 5] {
+    [
+        tile.weather.temperature / 10.0,
+        tile.weather.humidity,
+        tile.weather.precipitation,
+        tile.conditions.soil_moisture,
+        tile.biome.vegetation_health,
+    ]
+}
+
+impl IdleSkip {
+    /// Choose the tiles the rule phases skip this tick. Returns how many.
+    pub fn choose(&self, world: &mut World) -> usize {
+        let tiles = &world.tiles;
+        let activity = &mut world.activity;
+        if activity.scores.len() != tiles.len() || activity.changes.len() != tiles.len() {
+            // Nothing measured yet: every tile is evaluated
+            activity.skipped.clear();
+            return 0;
+        }
+        let tick = world.tick_count;
+        let interval = self.interval.max(1) as u64;
+        activity.skipped = tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| {
+                !(tick + tile.id as u64).is_multiple_of(interval)
+                    && activity.scores[i] < self.threshold
+                    && tile
+                        .neighbors
+                        .iter()
+                        .all(|&n| activity.changes.get(n as usize).is_none_or(|&c| c <= self.threshold))
+            })
+            .collect();
+        activity.skipped.iter().filter(|&&s| s).count()
+    }
+
+    /// Fold the tick's movement into the activity scores, given each tile's
+    /// measures at the start of the tick and the tick's effects.
+    pub fn update(&self, world: &mut World, before: &[[f32; 5]], effects: &TileEffects) {
+        let tiles = &world.tiles;
+        let activity = &mut world.activity;
+        if activity.scores.len() != tiles.len() {
+            activity.scores = vec![self.threshold; tiles.len()];
+        }
+        activity.changes = tiles
+            .iter()
+            .zip(before)
+            .map(|(tile, before)| measure(tile).iter().zip(before).map(|(a, b)| (a - b).abs()).sum())
+            .collect();
+        for (i, tile) in tiles.iter().enumerate() {
+            let score = &mut activity.scores[i];
+            *score = *score * DECAY + activity.changes[i];
+            if tile.weather.storm_intensity >= STORM_INTENSITY {
+                *score = score.max(self.threshold);
+            }
+        }
+        for (effect, ids) in effects.to_wire() {
+            // The aurora is only a glow; rules can't see it
+            if effect == TileEffect::Aurora {
+                continue;
+            }
+            for id in ids {
+                if let Some(score) = activity.scores.get_mut(id as usize) {
+                    *score = score.max(self.threshold);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+
+    #[test]
+    fn quiet_tiles_are_skipped_until_something_moves_nearby() {
+        let skip = IdleSkip { threshold: 0.05, interval: 4 };
+        let mut world = test_world(100);
+        for tile in &mut world.tiles {
+            tile.weather.storm_intensity = 0.0;
+        }
+        // Nothing measured yet
+        assert_eq!(skip.choose(&mut world), 0);
+        assert!(!world.activity.is_skipped(0));
+
+        // Still worlds settle into skipping three ticks in four
+        let before: Vec<[f32; 5]> = world.tiles.iter().map(measure).collect();
+        for _ in 0..8 {
+            skip.update(&mut world, &before, &TileEffects::default());
+        }
+        assert!(world.activity.scores.iter().all(|&s| s < 0.05));
+        assert_eq!(skip.choose(&mut world), 75);
+        let evaluated: Vec<u32> = world
+            .tiles
+            .iter()
+            .filter(|t| !world.activity.is_skipped(t.id as usize))
+            .map(|t| t.id)
+            .collect();
+        assert!(evaluated.iter().all(|id| (world.tick_count + *id as u64).is_multiple_of(4)));
+
+        // A tile that moves wakes itself and its neighbors
+        world.tiles[10].weather.temperature += 5.0;
+        let mut neighbors = world.tiles[10].neighbors.clone();
+        skip.update(&mut world, &before, &TileEffects::default());
+        world.tiles[10].weather.temperature -= 5.0;
+        skip.choose(&mut world);
+        assert!(!world.activity.is_skipped(10));
+        assert!(neighbors.iter().all(|&n| !world.activity.is_skipped(n as usize)));
+
+        // Next tick the neighbors may rest again, but the tile's score is still high
+        skip.update(&mut world, &before, &TileEffects::default());
+        skip.choose(&mut world);
+        assert!(!world.activity.is_skipped(10));
+        neighbors.retain(|&n| !(world.tick_count + n as u64).is_multiple_of(4));
+        assert!(neighbors.iter().all(|&n| world.activity.is_skipped(n as usize)));
+
+        // Storms and events keep tiles awake
+        world.tiles[20].weather.storm_intensity = 0.5;
+        let mut effects = TileEffects::default();
+        effects.mark(TileEffect::Landslide, 30);
+        effects.mark(TileEffect::Aurora, 40);
+        skip.update(&mut world, &before, &effects);
+        assert_eq!(world.activity.scores[20], 0.05);
+        assert_eq!(world.activity.scores[30], 0.05);
+        assert!(world.activity.scores[40] < 0.05);
+    }
+}
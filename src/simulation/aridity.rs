@@ -0,0 +1,104 @@
+//! Potential evapotranspiration (PET) and aridity, computed natively right
+//! after the Weather phase so Conditions and Terrain rules can read them.
+//!
+//! PET is the water the air could draw out of the ground this tick if it were
+//! freely available: it rises with warmth, dry air and wind, and vegetation
+//! transpires on top of bare-soil evaporation. The aridity index is the
+//! running ratio of precipitation to PET (the UNEP definition), smoothed over
+//! about a month: below 0.2 is arid, below 0.5 semi-arid, above 0.65 humid.
+//! `drought_days` counts consecutive ticks below `DROUGHT_ARIDITY`.
+
+use crate::world::{Tile, World};
+
+/// PET (precipitation units per tick) of warm, dry, still air over bare soil.
+const PET_SCALE: f32 = 0.3;
+/// Temperature (°C above freezing) at which the warmth term saturates.
+const PET_WARM_C: f32 = 30.0;
+/// Wind speed that doubles PET.
+const PET_WIND_DOUBLING: f32 = 20.0;
+/// PET floor so the ratio stays finite in cold, saturated air.
+const PET_FLOOR: f32 = 0.01;
+/// Largest per-tick precipitation/PET ratio fed into the running index.
+const MAX_TICK_RATIO: f32 = 3.0;
+/// Weight of the current tick in the running index (~30-tick memory).
+const ARIDITY_SMOOTHING: f32 = 1.0 / 30.0;
+/// Aridity index below which a tick counts as a drought day (semi-arid).
+pub const DROUGHT_ARIDITY: f32 = 0.5;
+
+/// Potential evapotranspiration for a tile's current weather and vegetation.
+pub fn potential_evapotranspiration(tile: &Tile) -> f32 {
+    let w = &tile.weather;
+    let warmth = ((w.temperature - 273.15) / PET_WARM_C).clamp(0.0, 1.0);
+    let dryness = 0.5 + 0.5 * (1.0 - w.humidity).clamp(0.0, 1.0);
+    let wind = 1.0 + w.wind_speed.max(0.0) / PET_WIND_DOUBLING;
+    let transpiration = 0.7 + 0.3 * tile.biome.vegetation_density;
+    PET_SCALE * warmth * dryness * wind * transpiration
+}
+
+/// Next running aridity index for a tile given this tick's PET.
+pub fn next_aridity_index(tile: &Tile, pet: f32) -> f32 {
+    let ratio = (tile.weather.precipitation / pet.max(PET_FLOOR)).min(MAX_TICK_RATIO);
+    let current = tile.conditions.aridity_index;
+    current + ARIDITY_SMOOTHING * (ratio - current)
+}
+
+/// Update `pet` and `aridity_index` on every unfrozen tile.
+pub fn update_aridity(world: &mut World) {
+    for tile in &mut world.tiles {
+        if world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        let pet = potential_evapotranspiration(tile);
+        tile.conditions.aridity_index = next_aridity_index(tile, pet);
+        tile.conditions.pet = pet;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    fn tile(temperature: f32, humidity: f32, wind: f32) -> Tile {
+        let mut t = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        t.weather.temperature = temperature;
+        t.weather.humidity = humidity;
+        t.weather.wind_speed = wind;
+        t.biome.vegetation_density = 0.0;
+        t
+    }
+
+    #[test]
+    fn pet_rises_with_heat_dryness_wind_and_vegetation() {
+        let base = potential_evapotranspiration(&tile(293.15, 0.5, 5.0));
+        assert!(potential_evapotranspiration(&tile(303.15, 0.5, 5.0)) > base);
+        assert!(potential_evapotranspiration(&tile(293.15, 0.1, 5.0)) > base);
+        assert!(potential_evapotranspiration(&tile(293.15, 0.5, 15.0)) > base);
+        let mut forest = tile(293.15, 0.5, 5.0);
+        forest.biome.vegetation_density = 1.0;
+        assert!(potential_evapotranspiration(&forest) > base);
+        // No evaporation demand at or below freezing
+        assert_eq!(potential_evapotranspiration(&tile(260.0, 0.1, 10.0)), 0.0);
+    }
+
+    #[test]
+    fn aridity_tracks_precipitation_over_demand() {
+        let mut dry = tile(303.15, 0.2, 5.0);
+        dry.conditions.aridity_index = 1.0;
+        dry.weather.precipitation = 0.0;
+        for _ in 0..100 {
+            let pet = potential_evapotranspiration(&dry);
+            dry.conditions.aridity_index = next_aridity_index(&dry, pet);
+        }
+        assert!(dry.conditions.aridity_index < 0.1);
+
+        let mut wet = dry.clone();
+        wet.weather.precipitation = 0.8;
+        for _ in 0..100 {
+            let pet = potential_evapotranspiration(&wet);
+            wet.conditions.aridity_index = next_aridity_index(&wet, pet);
+        }
+        assert!(wet.conditions.aridity_index > 2.0);
+        assert!(wet.conditions.aridity_index <= MAX_TICK_RATIO);
+    }
+}
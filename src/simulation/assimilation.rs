@@ -0,0 +1,161 @@
+//! Nudging the simulation toward observed weather.
+//!
+//! An external source (a weather feed, a station network) can post observed
+//! temperatures and humidities for some tiles to `/api/observations`.
+//! Writing them straight onto the tiles would jolt the weather around them,
+//! and the next tick's rules would wash them out. Instead each observation is
+//! kept for a few ticks, and right after the weather phase it pulls its tile
+//! part of the way toward the observed values. Pending observations are saved
+//! with the world.
+
+use serde::{Deserialize, Serialize};
+
+use crate::world::World;
+
+/// Ticks an observation nudges for unless it says otherwise.
+pub const DEFAULT_TICKS: u32 = 6;
+
+/// Fraction of the gap closed per tick unless an observation says otherwise.
+pub const DEFAULT_STRENGTH: f32 = 0.3;
+
+fn default_ticks() -> u32 {
+    DEFAULT_TICKS
+}
+
+fn default_strength() -> f32 {
+    DEFAULT_STRENGTH
+}
+
+/// Observed weather on one tile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub tile: u32,
+    /// Observed temperature (K)
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Observed humidity (0.0-1.0)
+    #[serde(default)]
+    pub humidity: Option<f32>,
+    /// Ticks left to nudge for
+    #[serde(default = "default_ticks")]
+    pub ticks: u32,
+    /// Fraction of the gap to the observed values closed each tick (0.0-1.0)
+    #[serde(default = "default_strength")]
+    pub strength: f32,
+}
+
+impl Observation {
+    /// Check the observation can be assimilated.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.temperature.is_none() && self.humidity.is_none() {
+            return Err(format!("observation of tile {} has neither temperature nor humidity", self.tile));
+        }
+        if let Some(t) = self.temperature
+            && !(t.is_finite() && t > 0.0)
+        {
+            return Err(format!("observation of tile {}: temperature must be above 0 K, got {}", self.tile, t));
+        }
+        if let Some(h) = self.humidity
+            && !(0.0..=1.0).contains(&h)
+        {
+            return Err(format!("observation of tile {}: humidity must be 0.0-1.0, got {}", self.tile, h));
+        }
+        if self.ticks == 0 {
+            return Err(format!("observation of tile {}: ticks must be at least 1", self.tile));
+        }
+        if !(self.strength > 0.0 && self.strength <= 1.0) {
+            return Err(format!("observation of tile {}: strength must be in (0.0, 1.0], got {}", self.tile, self.strength));
+        }
+        Ok(())
+    }
+}
+
+/// Pull each observed tile toward its observation and count the observation's
+/// ticks down, dropping those that have run out. Frozen tiles are left alone
+/// but their observations still run out. Returns the number of tiles nudged.
+pub fn nudge(world: &mut World) -> usize {
+    let mut nudged = 0;
+    for observation in &mut world.observations {
+        observation.ticks = observation.ticks.saturating_sub(1);
+        if world.frozen_tiles.contains(&observation.tile) {
+            continue;
+        }
+        let Some(tile) = world.tiles.get_mut(observation.tile as usize) else {
+            continue;
+        };
+        let weather = &mut tile.weather;
+        if let Some(temperature) = observation.temperature {
+            weather.temperature += observation.strength * (temperature - weather.temperature);
+        }
+        if let Some(humidity) = observation.humidity {
+            weather.humidity = (weather.humidity + observation.strength * (humidity - weather.humidity)).clamp(0.0, 1.0);
+        }
+        nudged += 1;
+    }
+    world.observations.retain(|o| o.ticks > 0);
+    nudged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+
+    fn observation(tile: u32, temperature: f32) -> Observation {
+        Observation { tile, temperature: Some(temperature), humidity: None, ticks: 3, strength: 0.5 }
+    }
+
+    #[test]
+    fn observations_nudge_then_expire() {
+        let mut world = test_world(100);
+        world.tiles[4].weather.temperature = 280.0;
+        world.tiles[5].weather.humidity = 0.2;
+        let humidity_before = world.tiles[4].weather.humidity;
+        let unknown = world.observe(vec![
+            observation(4, 300.0),
+            Observation { tile: 5, temperature: None, humidity: Some(0.6), ticks: 1, strength: 0.5 },
+            observation(100_000, 300.0),
+        ]);
+        assert_eq!(unknown, vec![100_000]);
+
+        assert_eq!(nudge(&mut world), 2);
+        assert_eq!(world.tiles[4].weather.temperature, 290.0);
+        assert_eq!(world.tiles[4].weather.humidity, humidity_before);
+        assert!((world.tiles[5].weather.humidity - 0.4).abs() < 1e-6);
+        // The one-tick humidity observation is used up
+        assert_eq!(world.observations.len(), 1);
+
+        assert_eq!(nudge(&mut world), 1);
+        assert_eq!(world.tiles[4].weather.temperature, 295.0);
+        assert_eq!(nudge(&mut world), 1);
+        assert!(world.observations.is_empty());
+        assert_eq!(nudge(&mut world), 0);
+        assert_eq!(world.tiles[4].weather.temperature, 297.5);
+
+        // A newer observation of a tile replaces the older one
+        world.observe(vec![observation(4, 250.0)]);
+        world.observe(vec![observation(4, 310.0)]);
+        assert_eq!(world.observations.len(), 1);
+        assert_eq!(world.observations[0].temperature, Some(310.0));
+
+        world.frozen_tiles.insert(4);
+        assert_eq!(nudge(&mut world), 0);
+        assert_eq!(world.tiles[4].weather.temperature, 297.5);
+        assert_eq!(world.observations[0].ticks, 2);
+    }
+
+    #[test]
+    fn observations_are_validated() {
+        assert!(observation(1, 290.0).validate().is_ok());
+        let blank = Observation { temperature: None, ..observation(1, 290.0) };
+        assert!(blank.validate().unwrap_err().contains("neither"));
+        assert!(observation(1, -5.0).validate().unwrap_err().contains("temperature"));
+        let wet = Observation { humidity: Some(1.5), ..observation(1, 290.0) };
+        assert!(wet.validate().unwrap_err().contains("humidity"));
+        assert!(Observation { ticks: 0, ..observation(1, 290.0) }.validate().unwrap_err().contains("ticks"));
+        assert!(Observation { strength: 0.0, ..observation(1, 290.0) }.validate().unwrap_err().contains("strength"));
+
+        let parsed: Observation = serde_json::from_str(r#"{"tile": 3, "temperature": 288.0}"#).unwrap();
+        assert_eq!((parsed.ticks, parsed.strength), (DEFAULT_TICKS, DEFAULT_STRENGTH));
+    }
+}
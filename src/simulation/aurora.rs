@@ -0,0 +1,136 @@
+//! Optional space weather driver: cosmetic aurora for the viewer.
+//!
+//! Geomagnetic storms start at random with a low per-tick chance, peak at
+//! onset and fade linearly over their lifetime. The auroral oval sits near
+//! 67° latitude in both hemispheres and pushes equatorward as storms
+//! strengthen; between storms a faint quiet-time oval remains. The result is
+//! written to `weather.aurora`, which rules cannot read, so enabling it never
+//! changes simulation behavior. It draws from its own RNG stream in
+//! `World::space_weather`, leaving macro weather untouched.
+
+
+use crate::simulation::macro_weather::{rand_f64, xorshift64};
+use crate::world::World;
+
+/// Oval strength between storms.
+const QUIET_STRENGTH: f32 = 0.1;
+/// Oval latitude (degrees) with no storm.
+const QUIET_OVAL_LATITUDE: f32 = 67.0;
+/// Degrees the oval moves equatorward at full storm strength.
+const STORM_OVAL_SHIFT: f32 = 20.0;
+/// Latitude half-width (degrees) of the oval's Gaussian profile.
+const OVAL_WIDTH: f32 = 8.0;
+/// Storm peak strength range.
+const MIN_STORM_PEAK: f32 = 0.3;
+const MAX_STORM_PEAK: f32 = 1.0;
+/// Storm duration range in ticks.
+const MIN_STORM_TICKS: u32 = 10;
+const MAX_STORM_TICKS: u32 = 40;
+/// Aurora below this is written as 0.0.
+const AURORA_FLOOR: f32 = 0.01;
+
+/// A geomagnetic storm that began this tick (a wire protocol type).
+pub use worldground_protocol::AuroraEvent;
+
+/// Space weather settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aurora {
+    /// Chance per tick that a quiet sky turns into a geomagnetic storm
+    pub chance: f32,
+}
+
+/// Latitude of the auroral oval for a given strength.
+pub fn oval_latitude(strength: f32) -> f32 {
+    QUIET_OVAL_LATITUDE - STORM_OVAL_SHIFT * strength.clamp(0.0, 1.0)
+}
+
+/// Aurora brightness at a latitude for a given strength.
+pub fn aurora_at(latitude: f32, strength: f32) -> f32 {
+    let offset = (latitude.abs() - oval_latitude(strength)) / OVAL_WIDTH;
+    let aurora = strength * (-offset * offset).exp();
+    if aurora < AURORA_FLOOR { 0.0 } else { aurora }
+}
+
+impl Aurora {
+    /// Advance the storm state and write `weather.aurora` on every unfrozen
+    /// tile. Returns the storm that started this tick, if any.
+    pub fn apply(&self, world: &mut World) -> Option<AuroraEvent> {
+        let state = &mut world.space_weather;
+        let mut onset = None;
+        if state.ticks_remaining > 0 {
+            state.ticks_remaining -= 1;
+        } else if rand_f64(&mut state.rng_state) < self.chance as f64 {
+            let peak = MIN_STORM_PEAK
+                + (MAX_STORM_PEAK - MIN_STORM_PEAK) * rand_f64(&mut state.rng_state) as f32;
+            let span = (MAX_STORM_TICKS - MIN_STORM_TICKS + 1) as u64;
+            let duration = MIN_STORM_TICKS + (xorshift64(&mut state.rng_state) % span) as u32;
+            state.storm_peak = peak;
+            state.storm_duration = duration;
+            state.ticks_remaining = duration;
+            onset = Some(AuroraEvent { strength: peak, oval_latitude: oval_latitude(peak) });
+        }
+
+        let storm = if state.ticks_remaining > 0 {
+            state.storm_peak * state.ticks_remaining as f32 / state.storm_duration.max(1) as f32
+        } else {
+            0.0
+        };
+        let strength = storm.max(QUIET_STRENGTH);
+
+        for tile in &mut world.tiles {
+            if world.frozen_tiles.contains(&tile.id) {
+                continue;
+            }
+            tile.weather.aurora = aurora_at(tile.climate.latitude, strength);
+        }
+        onset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+
+    #[test]
+    fn oval_brightest_at_high_latitudes() {
+        assert!(aurora_at(67.0, QUIET_STRENGTH) > 0.09);
+        assert_eq!(aurora_at(-67.0, QUIET_STRENGTH), aurora_at(67.0, QUIET_STRENGTH));
+        assert_eq!(aurora_at(20.0, 1.0), 0.0);
+        // Strong storms reach further equatorward
+        assert!(aurora_at(50.0, 1.0) > aurora_at(50.0, 0.3));
+        assert!(oval_latitude(1.0) < oval_latitude(0.0));
+    }
+
+    #[test]
+    fn storm_starts_emits_event_and_fades() {
+        let mut world = test_world(200);
+        let event = Aurora { chance: 1.0 }.apply(&mut world).expect("storm onset");
+        assert!((MIN_STORM_PEAK..=MAX_STORM_PEAK).contains(&event.strength));
+        let duration = world.space_weather.storm_duration;
+        assert!((MIN_STORM_TICKS..=MAX_STORM_TICKS).contains(&duration));
+        let peak: f32 = world.tiles.iter().map(|t| t.weather.aurora).sum();
+
+        // No new onset while a storm is running, and it fades
+        assert!(Aurora { chance: 1.0 }.apply(&mut world).is_none());
+        let later: f32 = world.tiles.iter().map(|t| t.weather.aurora).sum();
+        assert!(later < peak);
+        for _ in 1..duration {
+            Aurora { chance: 0.0 }.apply(&mut world);
+        }
+        assert_eq!(world.space_weather.ticks_remaining, 0);
+    }
+
+    #[test]
+    fn aurora_leaves_simulation_state_alone() {
+        let mut world = test_world(200);
+        let before = world.clone();
+        Aurora { chance: 1.0 }.apply(&mut world);
+        assert_eq!(world.macro_weather, before.macro_weather);
+        for (a, b) in world.tiles.iter().zip(&before.tiles) {
+            assert_eq!(a.conditions, b.conditions);
+            assert_eq!(a.weather.temperature, b.weather.temperature);
+        }
+        assert!(world.tiles.iter().any(|t| t.weather.aurora > 0.0));
+    }
+}
@@ -0,0 +1,189 @@
+//! Edge behavior for non-wrapping flat worlds.
+//!
+//! Tiles on the border of a bounded flat grid have fewer than six neighbors.
+//! During phase execution each missing neighbor is replaced by a "ghost" tile
+//! derived from the edge tile, so neighbor averages and exchange rules see a full
+//! hex ring and weather does not artificially pool or drain at the map border.
+//! A wrapping_hex cylinder has edges only at its top and bottom rows.
+//!
+//! Ghosts are read-only: each has an id counted down from `u32::MAX`, outside
+//! the range of real tiles, so a lookup by its id finds nothing and a
+//! `set_neighbor()` aimed at it is rejected instead of landing on the edge tile.
+
+use crate::world::tile::{BiomeType, PrecipitationType, TerrainType};
+use crate::world::{Tile, TopologyType, World};
+
+/// Number of neighbors an interior flat hex tile has.
+pub const FLAT_NEIGHBOR_COUNT: usize = 6;
+
+/// The id of the ghost standing beyond edge tile `tile_id`.
+pub fn ghost_id(tile_id: u32) -> u32 {
+    u32::MAX - tile_id
+}
+
+/// How edge tiles of a flat world see the space beyond the border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryMode {
+    /// Toroidal wrapping — there are no edges.
+    Wrap,
+    /// Ghosts mirror the edge tile (zero gradient across the border).
+    Reflective,
+    /// Ghosts hold the edge tile's climatological baseline weather.
+    FixedClimate,
+    /// Ghosts are open ocean at the edge tile's base temperature.
+    OceanBuffer,
+}
+
+impl BoundaryMode {
+    /// Parse a `topology.boundary` config value.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "wrap" => Some(BoundaryMode::Wrap),
+            "reflective" => Some(BoundaryMode::Reflective),
+            "fixed_climate" => Some(BoundaryMode::FixedClimate),
+            "ocean_buffer" => Some(BoundaryMode::OceanBuffer),
+            _ => None,
+        }
+    }
+
+    /// The boundary mode in effect for a world. Geodesic worlds always wrap.
+    pub fn for_world(world: &World) -> Self {
+        if world.topology_type != TopologyType::FlatHex {
+            return BoundaryMode::Wrap;
+        }
+        Self::parse(&world.generation_params.topology.boundary).unwrap_or(BoundaryMode::Wrap)
+    }
+}
+
+/// Build the ghost neighbor for an edge tile.
+///
+/// Returns the ghost and how many missing neighbor slots it fills, or None for
+/// interior tiles and wrapping worlds.
+pub fn ghost_neighbor(tile: &Tile, mode: BoundaryMode) -> Option<(Tile, usize)> {
+    let missing = FLAT_NEIGHBOR_COUNT.saturating_sub(tile.neighbors.len());
+    if missing == 0 || mode == BoundaryMode::Wrap {
+        return None;
+    }
+
+    let mut ghost = tile.clone();
+    // Beyond the border the only tile a ghost touches is the edge tile
+    ghost.id = ghost_id(tile.id);
+    ghost.neighbors = vec![tile.id];
+    match mode {
+        BoundaryMode::Wrap | BoundaryMode::Reflective => {}
+        BoundaryMode::FixedClimate => {
+            let base_precip = tile.climate.base_precipitation;
+            ghost.weather.temperature = tile.climate.base_temperature;
+            ghost.weather.humidity = base_precip;
+            ghost.weather.precipitation = 0.0;
+            ghost.weather.precipitation_type = PrecipitationType::None;
+            ghost.weather.cloud_cover = base_precip * 0.5;
+            ghost.weather.wind_speed = 0.0;
+            ghost.weather.storm_intensity = 0.0;
+            ghost.weather.pressure = 1013.25;
+            ghost.weather.station_pressure = crate::simulation::macro_weather::station_pressure(
+                1013.25,
+                ghost.geology.elevation,
+                ghost.weather.temperature,
+            );
+            ghost.conditions.soil_moisture = base_precip;
+        }
+        BoundaryMode::OceanBuffer => {
+            ghost.geology.terrain_type = TerrainType::Ocean;
+            ghost.geology.elevation = 0.0;
+            ghost.biome.biome_type = BiomeType::Ocean;
+            ghost.biome.vegetation_density = 0.0;
+            ghost.biome.vegetation_health = 0.0;
+            ghost.weather.temperature = tile.climate.base_temperature;
+            ghost.weather.humidity = tile.weather.humidity.max(0.8);
+            ghost.weather.storm_intensity = 0.0;
+            ghost.conditions.soil_moisture = 1.0;
+            ghost.conditions.snow_depth = 0.0;
+            ghost.conditions.mud_level = 0.0;
+            ghost.conditions.fire_risk = 0.0;
+        }
+    }
+    Some((ghost, missing))
+}
+
+/// Ghost neighbors for every tile, indexed like `world.tiles`.
+///
+/// Returns an empty Vec for wrapping worlds so callers can skip the lookup.
+pub fn ghost_neighbors(world: &World) -> Vec<Option<(Tile, usize)>> {
+    let mode = BoundaryMode::for_world(world);
+    if mode == BoundaryMode::Wrap {
+        return Vec::new();
+    }
+    world.tiles.iter().map(|t| ghost_neighbor(t, mode)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    fn edge_tile() -> Tile {
+        let mut t = Tile::new_default(0, vec![1, 2], Position::flat(0.0, 0.0));
+        t.climate.base_temperature = 290.0;
+        t.climate.base_precipitation = 0.4;
+        t.weather.temperature = 310.0;
+        t.weather.humidity = 0.2;
+        t
+    }
+
+    #[test]
+    fn parse_all_modes() {
+        for (s, m) in [
+            ("wrap", BoundaryMode::Wrap),
+            ("reflective", BoundaryMode::Reflective),
+            ("fixed_climate", BoundaryMode::FixedClimate),
+            ("ocean_buffer", BoundaryMode::OceanBuffer),
+        ] {
+            assert_eq!(BoundaryMode::parse(s), Some(m));
+        }
+        assert_eq!(BoundaryMode::parse("bouncy"), None);
+    }
+
+    #[test]
+    fn interior_tiles_have_no_ghost() {
+        let t = Tile::new_default(0, vec![1, 2, 3, 4, 5, 6], Position::flat(0.0, 0.0));
+        assert!(ghost_neighbor(&t, BoundaryMode::Reflective).is_none());
+        assert!(ghost_neighbor(&edge_tile(), BoundaryMode::Wrap).is_none());
+    }
+
+    #[test]
+    fn reflective_ghost_mirrors_tile() {
+        let tile = edge_tile();
+        let (ghost, missing) = ghost_neighbor(&tile, BoundaryMode::Reflective).unwrap();
+        assert_eq!(missing, 4);
+        assert_eq!(ghost.weather, tile.weather);
+        assert_eq!(ghost.conditions, tile.conditions);
+        assert_eq!(ghost.geology, tile.geology);
+    }
+
+    #[test]
+    fn ghost_ids_are_outside_the_real_tile_range() {
+        let tile = edge_tile();
+        let (ghost, _) = ghost_neighbor(&tile, BoundaryMode::Reflective).unwrap();
+        assert_eq!(ghost.id, u32::MAX);
+        assert_ne!(ghost.id, tile.id);
+        assert_eq!(ghost.neighbors, vec![tile.id]);
+    }
+
+    #[test]
+    fn fixed_climate_ghost_uses_baseline() {
+        let (ghost, _) = ghost_neighbor(&edge_tile(), BoundaryMode::FixedClimate).unwrap();
+        assert_eq!(ghost.weather.temperature, 290.0);
+        assert!((ghost.weather.humidity - 0.4).abs() < 1e-6);
+        assert_eq!(ghost.weather.precipitation, 0.0);
+    }
+
+    #[test]
+    fn ocean_buffer_ghost_is_wet_ocean() {
+        let (ghost, _) = ghost_neighbor(&edge_tile(), BoundaryMode::OceanBuffer).unwrap();
+        assert_eq!(ghost.biome.biome_type, BiomeType::Ocean);
+        assert_eq!(ghost.geology.terrain_type, TerrainType::Ocean);
+        assert!(ghost.weather.humidity >= 0.8);
+        assert_eq!(ghost.weather.temperature, 290.0);
+    }
+}
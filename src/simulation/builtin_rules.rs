@@ -0,0 +1,82 @@
+//! The reference rule pack, compiled into the binary.
+//!
+//! These are the scripts in the repository's `rules/` directory, the ones
+//! the native Weather and Resources evaluators mirror. Naming `builtin` as
+//! the rule directory loads them from here, so `run` works on a machine
+//! with no rules on disk, and the scripts the native evaluators are held
+//! to have one source.
+
+use std::path::Path;
+
+use super::engine::Phase;
+
+/// The rule directory that stands for the built-in pack (a directory
+/// actually called `builtin` can be named as `./builtin`).
+pub const BUILTIN_RULES: &str = "builtin";
+
+/// Each built-in rule's phase, file name and source, in execution order
+/// within each phase.
+pub const RULES: &[(Phase, &str, &str)] = &[
+    (Phase::Weather, "01-wind-temperature.rhai", include_str!("../../rules/weather/01-wind-temperature.rhai")),
+    (Phase::Weather, "02-humidity.rhai", include_str!("../../rules/weather/02-humidity.rhai")),
+    (Phase::Weather, "03-clouds-precipitation.rhai", include_str!("../../rules/weather/03-clouds-precipitation.rhai")),
+    (Phase::Weather, "04-storms.rhai", include_str!("../../rules/weather/04-storms.rhai")),
+    (Phase::Conditions, "01-soil-moisture.rhai", include_str!("../../rules/conditions/01-soil-moisture.rhai")),
+    (Phase::Conditions, "02-snow-and-mud.rhai", include_str!("../../rules/conditions/02-snow-and-mud.rhai")),
+    (Phase::Terrain, "01-biome-pressure.rhai", include_str!("../../rules/terrain/01-biome-pressure.rhai")),
+    (Phase::Terrain, "02-vegetation-health.rhai", include_str!("../../rules/terrain/02-vegetation-health.rhai")),
+    (Phase::Terrain, "03-biome-transition.rhai", include_str!("../../rules/terrain/03-biome-transition.rhai")),
+    (Phase::Resources, "01-regeneration.rhai", include_str!("../../rules/resources/01-regeneration.rhai")),
+];
+
+/// The fixtures `rules test` runs against the built-in rules, as (file
+/// name, text).
+pub const FIXTURES: &[(&str, &str)] = &[
+    ("conditions.toml", include_str!("../../rules/tests/conditions.toml")),
+    ("weather.toml", include_str!("../../rules/tests/weather.toml")),
+];
+
+/// Whether `rule_dir` names the built-in pack rather than a directory.
+pub fn is_builtin(rule_dir: &Path) -> bool {
+    rule_dir == Path::new(BUILTIN_RULES)
+}
+
+/// The built-in rules of one phase as (file name, source), in order.
+pub fn phase_rules(phase: Phase) -> impl Iterator<Item = (&'static str, &'static str)> {
+    RULES.iter().filter(move |(p, ..)| *p == phase).map(|(_, name, source)| (*name, *source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::engine::RuleEngine;
+
+    #[test]
+    fn builtin_pack_is_the_rules_directory() {
+        fn files(dir: &str, extension: &str) -> Vec<(String, String)> {
+            let mut files: Vec<(String, String)> = std::fs::read_dir(Path::new("rules").join(dir))
+                .unwrap()
+                .map(|e| e.unwrap().path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == extension))
+                .map(|p| {
+                    let name = p.file_name().unwrap().to_str().unwrap().to_string();
+                    (name, std::fs::read_to_string(&p).unwrap())
+                })
+                .collect();
+            files.sort();
+            files
+        }
+        let owned = |(name, text): (&str, &str)| (name.to_string(), text.to_string());
+        for phase in Phase::all() {
+            let builtin: Vec<(String, String)> = phase_rules(*phase).map(owned).collect();
+            assert_eq!(builtin, files(phase.dir_name(), "rhai"), "{} rules differ from rules/", phase.dir_name());
+        }
+        let fixtures: Vec<(String, String)> = FIXTURES.iter().copied().map(owned).collect();
+        assert_eq!(fixtures, files("tests", "toml"), "fixtures differ from rules/tests");
+
+        let engine = RuleEngine::new(Path::new(BUILTIN_RULES), 10).unwrap();
+        assert_eq!(engine.rule_count(), RULES.len());
+        assert_eq!(engine.rules_hash(), RuleEngine::new(Path::new("rules"), 10).unwrap().rules_hash());
+        assert!(!is_builtin(Path::new("./builtin")));
+    }
+}
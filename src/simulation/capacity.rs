@@ -0,0 +1,157 @@
+//! Carrying capacity, computed natively at the end of the Resources phase.
+//!
+//! A single 0.0-1.0 score for how many people or animals a tile could
+//! support, for agent layers deciding where populations settle. Climate and
+//! water are hard limits: a frozen or parched tile supports little however
+//! rich its soil. Within those limits, soil fertility (including flood silt),
+//! living vegetation and stocked resources add up, and rough terrain scales
+//! the whole down. Ocean is always 0.
+
+use crate::world::tile::{SoilType, TerrainType};
+use crate::world::{Tile, World};
+
+/// Base temperature (K) at which climate is most hospitable.
+const IDEAL_TEMPERATURE: f32 = 290.0;
+/// Base-temperature distance (K) at which the climate factor falls to 1/e.
+const TEMPERATURE_TOLERANCE: f32 = 15.0;
+/// Aridity index at or above which water stops limiting capacity.
+const AMPLE_WATER_ARIDITY: f32 = 0.75;
+/// Soil moisture above which the ground counts as waterlogged.
+const WATERLOGGED_MOISTURE: f32 = 0.9;
+
+/// How well a soil feeds crops and grazing, before silt.
+fn soil_fertility(soil: SoilType) -> f32 {
+    match soil {
+        SoilType::Loam => 1.0,
+        SoilType::Silt => 0.9,
+        SoilType::Clay => 0.7,
+        SoilType::Sand => 0.4,
+        SoilType::Rock => 0.1,
+    }
+}
+
+/// How much of a tile's land is usable.
+fn terrain_factor(terrain: TerrainType) -> f32 {
+    match terrain {
+        TerrainType::Ocean => 0.0,
+        TerrainType::Plains | TerrainType::Coast => 1.0,
+        TerrainType::Hills => 0.7,
+        TerrainType::Wetlands => 0.5,
+        TerrainType::Mountains => 0.3,
+        TerrainType::Cliffs => 0.1,
+    }
+}
+
+/// Climate suitability (0.0-1.0) from the tile's long-term temperature.
+fn climate_factor(tile: &Tile) -> f32 {
+    let offset = (tile.climate.base_temperature - IDEAL_TEMPERATURE) / TEMPERATURE_TOLERANCE;
+    (-offset * offset).exp()
+}
+
+/// Water availability (0.0-1.0): drought limits it, and so do waterlogging and floods.
+fn water_factor(tile: &Tile) -> f32 {
+    let c = &tile.conditions;
+    let supply = (c.aridity_index / AMPLE_WATER_ARIDITY).clamp(0.0, 1.0);
+    let waterlogged = ((c.soil_moisture - WATERLOGGED_MOISTURE) / (1.0 - WATERLOGGED_MOISTURE))
+        .clamp(0.0, 1.0);
+    supply * (1.0 - 0.5 * waterlogged) * (1.0 - c.flood_level.clamp(0.0, 1.0))
+}
+
+/// Mean fill of the tile's resource deposits (0.0 with none).
+fn resource_factor(tile: &Tile) -> f32 {
+    let deposits = &tile.resources.resources;
+    if deposits.is_empty() {
+        return 0.0;
+    }
+    deposits
+        .iter()
+        .map(|d| if d.max_quantity > 0.0 { (d.quantity / d.max_quantity).clamp(0.0, 1.0) } else { 0.0 })
+        .sum::<f32>()
+        / deposits.len() as f32
+}
+
+/// Carrying capacity (0.0-1.0) for a tile's current state.
+pub fn carrying_capacity(tile: &Tile) -> f32 {
+    let terrain = terrain_factor(tile.geology.terrain_type);
+    if terrain == 0.0 {
+        return 0.0;
+    }
+    let fertility = (soil_fertility(tile.geology.soil_type) + 0.5 * tile.conditions.silt_fertility).min(1.0);
+    let vegetation = (tile.biome.vegetation_density * tile.biome.vegetation_health).clamp(0.0, 1.0);
+    let support = 0.4 * fertility + 0.4 * vegetation + 0.2 * resource_factor(tile);
+    (terrain * climate_factor(tile) * water_factor(tile) * support).clamp(0.0, 1.0)
+}
+
+/// Update `carrying_capacity` on every unfrozen tile.
+pub fn update_carrying_capacity(world: &mut World) {
+    for tile in &mut world.tiles {
+        if world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        tile.conditions.carrying_capacity = carrying_capacity(tile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{Position, ResourceDeposit};
+
+    fn farmland() -> Tile {
+        let mut t = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        t.climate.base_temperature = IDEAL_TEMPERATURE;
+        t.conditions.aridity_index = 1.0;
+        t.conditions.soil_moisture = 0.5;
+        t.biome.vegetation_density = 0.8;
+        t.biome.vegetation_health = 1.0;
+        t.resources.resources.push(ResourceDeposit {
+            resource_type: "grain".to_string(),
+            quantity: 50.0,
+            max_quantity: 100.0,
+            renewal_rate: 1.0,
+            requires_biome: None,
+            quality: 0.5,
+            discovered: true,
+        });
+        t
+    }
+
+    #[test]
+    fn temperate_loam_plain_scores_high() {
+        let capacity = carrying_capacity(&farmland());
+        assert!((capacity - (0.4 + 0.4 * 0.8 + 0.2 * 0.5)).abs() < 1e-6, "{}", capacity);
+
+        let mut ocean = farmland();
+        ocean.geology.terrain_type = TerrainType::Ocean;
+        assert_eq!(carrying_capacity(&ocean), 0.0);
+    }
+
+    #[test]
+    fn climate_and_water_limit_capacity() {
+        let base = carrying_capacity(&farmland());
+        let mut polar = farmland();
+        polar.climate.base_temperature = 250.0;
+        assert!(carrying_capacity(&polar) < 0.01);
+
+        let mut parched = farmland();
+        parched.conditions.aridity_index = 0.15;
+        assert!(carrying_capacity(&parched) < base / 4.0);
+
+        let mut flooded = farmland();
+        flooded.conditions.flood_level = 0.5;
+        assert!(carrying_capacity(&flooded) < base);
+    }
+
+    #[test]
+    fn silt_and_terrain_shift_capacity() {
+        let mut sandy = farmland();
+        sandy.geology.soil_type = SoilType::Sand;
+        let mut silted = sandy.clone();
+        silted.conditions.silt_fertility = 0.8;
+        assert!(carrying_capacity(&silted) > carrying_capacity(&sandy));
+
+        let mut mountain = farmland();
+        mountain.geology.terrain_type = TerrainType::Mountains;
+        assert!(carrying_capacity(&mountain) < carrying_capacity(&farmland()) / 2.0);
+    }
+}
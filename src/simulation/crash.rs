@@ -0,0 +1,203 @@
+//! Crash handling for the tick loop.
+//!
+//! A panic inside a tick, say from a bug in a new native evaluator, would
+//! otherwise take a long run down with nothing saved since the last
+//! snapshot. `run` runs each tick through [`catch_tick`], and on a panic
+//! saves the world as it was when the panic unwound, partway through the
+//! tick, to `quarantine/` under the snapshot directory, beside a crash
+//! report. Quarantined snapshots are never resumed from or pruned, since
+//! the tick that made them was cut short; `snapshots restore` or `inspect`
+//! can still load them.
+
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::error::WorldgroundError;
+use crate::persistence::{self, SnapshotError};
+use crate::world::World;
+
+/// Directory under the snapshot directory that crashed worlds are saved to.
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+thread_local! {
+    /// The part of the tick running on this thread.
+    static STEP: Cell<&'static str> = const { Cell::new("idle") };
+}
+
+/// The last panic seen by the hook, from whichever thread it happened on.
+static LAST_PANIC: Mutex<Option<HookRecord>> = Mutex::new(None);
+
+struct HookRecord {
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+}
+
+/// Record which part of the tick is about to run, for crash reports.
+pub fn enter_step(step: &'static str) {
+    STEP.with(|s| s.set(step));
+}
+
+/// Capture a backtrace for every panic, then report it as before. Rayon
+/// re-raises panics from its workers on the thread that started the work,
+/// where [`catch_tick`] picks up what the hook saw. Installing twice has no
+/// further effect.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let record = HookRecord {
+                message: payload_message(info.payload()),
+                location: info.location().map(|l| l.to_string()),
+                backtrace: Backtrace::force_capture().to_string(),
+            };
+            *LAST_PANIC.lock().unwrap_or_else(|e| e.into_inner()) = Some(record);
+            previous(info);
+        }));
+    });
+}
+
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panic with a non-string payload".to_string()
+    }
+}
+
+/// A panic caught by [`catch_tick`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickPanic {
+    pub message: String,
+    /// The part of the tick that panicked: "macro_weather", a phase, or "statistics"
+    pub step: &'static str,
+    pub location: Option<String>,
+    /// Empty unless the panic hook is installed
+    pub backtrace: String,
+}
+
+/// Run a tick, turning a panic inside it into an error.
+pub fn catch_tick<T>(tick: impl FnOnce() -> T) -> Result<T, TickPanic> {
+    enter_step("idle");
+    let outcome = panic::catch_unwind(AssertUnwindSafe(tick));
+    let step = STEP.with(|s| s.replace("idle"));
+    outcome.map_err(|payload| {
+        let message = payload_message(payload.as_ref());
+        // Only trust the hook's record if it is of this panic
+        let record = LAST_PANIC
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .filter(|r| r.message == message);
+        TickPanic {
+            message,
+            step,
+            location: record.as_ref().and_then(|r| r.location.clone()),
+            backtrace: record.map(|r| r.backtrace).unwrap_or_default(),
+        }
+    })
+}
+
+/// What `quarantine` writes next to the crashed world.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReport {
+    /// The tick that panicked
+    pub tick: u64,
+    pub step: &'static str,
+    pub message: String,
+    pub location: Option<String>,
+    /// Rule errors of the last tick that completed
+    pub last_rule_errors: Vec<String>,
+    /// File name of the emergency snapshot, if it could be saved
+    pub snapshot: Option<String>,
+    pub snapshot_error: Option<String>,
+    pub backtrace: String,
+}
+
+/// Save `world` and a crash report for `panic` to the quarantine directory
+/// under `snapshot_dir`. The snapshot is best effort: if it can't be
+/// written, the report says why. Returns the report's path.
+pub fn quarantine(
+    world: &World,
+    snapshot_dir: &Path,
+    tick: u64,
+    panic: &TickPanic,
+    last_rule_errors: &[String],
+) -> Result<PathBuf, WorldgroundError> {
+    let dir = snapshot_dir.join(QUARANTINE_DIR);
+    // Encoding a world halfway through a tick may itself panic
+    let saved = panic::catch_unwind(AssertUnwindSafe(|| persistence::save_snapshot(world, &dir)))
+        .unwrap_or_else(|payload| Err(SnapshotError::Serialize(payload_message(payload.as_ref()))));
+    let (snapshot, snapshot_error) = match saved {
+        Ok(path) => (path.file_name().map(|n| n.to_string_lossy().into_owned()), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let report = CrashReport {
+        tick,
+        step: panic.step,
+        message: panic.message.clone(),
+        location: panic.location.clone(),
+        last_rule_errors: last_rule_errors.to_vec(),
+        snapshot,
+        snapshot_error,
+        backtrace: panic.backtrace.clone(),
+    };
+
+    std::fs::create_dir_all(&dir).map_err(|source| WorldgroundError::Write { path: dir.clone(), source })?;
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let path = dir.join(format!("crash_tick{:08}_{}.json", tick, secs));
+    let json = serde_json::to_string_pretty(&report)?;
+    std::fs::write(&path, json).map_err(|source| WorldgroundError::Write { path: path.clone(), source })?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::GenerationParams;
+    use crate::world::generation::generate_world;
+
+    #[test]
+    fn a_panicking_tick_is_caught_with_its_step() {
+        install_panic_hook();
+        let outcome: Result<(), TickPanic> = catch_tick(|| {
+            enter_step("terrain");
+            panic!("slope out of range: {}", 7);
+        });
+        let panic = outcome.unwrap_err();
+        assert_eq!(panic.message, "slope out of range: 7");
+        assert_eq!(panic.step, "terrain");
+        assert!(panic.location.unwrap().contains("crash.rs"));
+        assert_eq!(catch_tick(|| 3), Ok(3));
+    }
+
+    #[test]
+    fn quarantine_saves_the_world_and_a_report() {
+        let world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        });
+        let dir = tempfile::TempDir::new().unwrap();
+        let panic = TickPanic { message: "boom".to_string(), step: "weather", location: None, backtrace: String::new() };
+        let errors = vec!["Tile 3: rule 'rain': division by zero".to_string()];
+
+        let path = quarantine(&world, dir.path(), world.tick_count + 1, &panic, &errors).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(report["step"], "weather");
+        assert_eq!(report["last_rule_errors"][0], errors[0]);
+        let snapshot = dir.path().join(QUARANTINE_DIR).join(report["snapshot"].as_str().unwrap());
+        assert_eq!(persistence::load_snapshot(&snapshot).unwrap().tiles.len(), world.tiles.len());
+        // Never resumed from
+        assert!(persistence::list_snapshots(dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+//! Optional atmospheric dust and volcanic ash, carried by the wind.
+//!
+//! One tracer, `weather.dust` (0.0-1.0), runs natively right after the
+//! weather phase. Strong winds lift it off dry, bare desert, and rare
+//! volcanic eruptions on mountain tiles throw ash over the vent and its
+//! neighbors. Each tick it is advected downwind with the same upwind
+//! weighting the native weather evaluator uses for humidity and cloud, then
+//! settles out, much faster where it rains.
+//!
+//! Dust in the air shades the ground, so the tile below runs cooler. Dust
+//! that settles on land adds to `conditions.silt_fertility`, fertilizing the
+//! soil the way receding floods do. Eruptions draw from a seed derived from
+//! the tick, so runs stay reproducible.
+
+use serde::Serialize;
+
+use crate::simulation::engine::Phase;
+use crate::simulation::macro_weather::{rand_f64, xorshift64};
+use crate::simulation::native_weather::{compute_advected, NeighborBearings};
+use crate::simulation::phase::compute_rng_seed;
+use crate::world::tile::{BiomeType, TerrainType};
+use crate::world::{Tile, World};
+
+/// Wind speed (m/s) above which dry desert starts to give up dust.
+const LIFT_WIND_SPEED: f32 = 8.0;
+/// Dust lifted per tick for each m/s of wind over the threshold, on bone-dry bare ground.
+const LIFT_RATE: f32 = 0.01;
+/// Soil moisture at which the ground is too damp to lift dust.
+const DAMP_SOIL: f32 = 0.3;
+/// Largest fraction of a tile's dust replaced by upwind air per tick.
+const TRANSPORT_MIX: f32 = 0.5;
+/// Smallest mixing fraction, so dust still spreads in calm air.
+const CALM_MIX: f32 = 0.1;
+/// Fraction of airborne dust settling out per tick in dry air.
+const SETTLING_RATE: f32 = 0.05;
+/// Extra fraction washed out per tick at full precipitation.
+const WASHOUT_RATE: f32 = 0.4;
+/// Silt fertility gained per unit of dust settling on land.
+const DEPOSIT_FERTILITY: f32 = 0.5;
+/// Temperature drop (K) under a full load of dust.
+pub const FULL_DUST_COOLING: f32 = 3.0;
+/// Ash thrown over an erupting tile; its neighbors get half.
+const ERUPTION_ASH: f32 = 1.0;
+/// Dust below this is written as 0.0.
+const DUST_FLOOR: f32 = 0.001;
+
+/// A volcanic eruption that happened this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EruptionEvent {
+    pub tile_id: u32,
+}
+
+/// Dust transport settings, with neighbor bearings computed once at startup.
+pub struct Dust {
+    /// Chance per tick that a mountain tile erupts
+    pub eruption_chance: f32,
+    bearings: NeighborBearings,
+}
+
+/// Dust lifted off a tile this tick: only dry, bare, snow-free desert or
+/// barren land in a strong wind gives any up.
+pub fn lifted_dust(tile: &Tile) -> f32 {
+    let dusty = matches!(tile.biome.biome_type, BiomeType::Desert | BiomeType::Barren);
+    if !dusty || tile.geology.terrain_type == TerrainType::Ocean || tile.conditions.snow_depth > 0.0 {
+        return 0.0;
+    }
+    let excess = tile.weather.wind_speed - LIFT_WIND_SPEED;
+    if excess <= 0.0 {
+        return 0.0;
+    }
+    let dryness = (1.0 - tile.conditions.soil_moisture / DAMP_SOIL).max(0.0);
+    let bare = 1.0 - tile.biome.vegetation_density.clamp(0.0, 1.0);
+    LIFT_RATE * excess * dryness * bare
+}
+
+/// A tile's dust after mixing with the air blowing in from upwind.
+fn transported(tile: &Tile, neighbors: &[&Tile], bearings: &NeighborBearings) -> f32 {
+    if neighbors.is_empty() {
+        return tile.weather.dust;
+    }
+    let (upwind, weight) = compute_advected(tile, neighbors, bearings, |t| t.weather.dust as f64);
+    let mix = TRANSPORT_MIX * (weight as f32).clamp(CALM_MIX / TRANSPORT_MIX, 1.0);
+    tile.weather.dust + mix * (upwind as f32 - tile.weather.dust)
+}
+
+/// Fraction of a tile's dust that settles out this tick.
+fn removal_rate(tile: &Tile) -> f32 {
+    (SETTLING_RATE + WASHOUT_RATE * tile.weather.precipitation.clamp(0.0, 1.0)).min(1.0)
+}
+
+impl Dust {
+    pub fn new(tiles: &[Tile], eruption_chance: f32) -> Self {
+        Self { eruption_chance, bearings: NeighborBearings::from_tiles(tiles) }
+    }
+
+    /// Erupt, lift, carry and settle dust on every unfrozen tile, cooling the
+    /// air under it and fertilizing the land it falls on. Returns the
+    /// eruption that happened this tick, if any.
+    pub fn apply(&self, world: &mut World) -> Option<EruptionEvent> {
+        let eruption = self.erupt(world);
+
+        for tile in &mut world.tiles {
+            if !world.frozen_tiles.contains(&tile.id) {
+                tile.weather.dust = (tile.weather.dust + lifted_dust(tile)).min(1.0);
+            }
+        }
+
+        let carried: Vec<f32> = world
+            .tiles
+            .iter()
+            .map(|tile| {
+                let neighbors: Vec<&Tile> =
+                    tile.neighbors.iter().filter_map(|&n| world.tiles.get(n as usize)).collect();
+                transported(tile, &neighbors, &self.bearings)
+            })
+            .collect();
+
+        for (tile, dust) in world.tiles.iter_mut().zip(carried) {
+            if world.frozen_tiles.contains(&tile.id) {
+                continue;
+            }
+            let deposited = dust * removal_rate(tile);
+            let airborne = (dust - deposited).clamp(0.0, 1.0);
+            tile.weather.dust = if airborne < DUST_FLOOR { 0.0 } else { airborne };
+            if tile.geology.terrain_type != TerrainType::Ocean {
+                let silt = &mut tile.conditions.silt_fertility;
+                *silt = (*silt + deposited * DEPOSIT_FERTILITY).min(1.0);
+            }
+            tile.weather.temperature -= FULL_DUST_COOLING * tile.weather.dust;
+        }
+        eruption
+    }
+
+    /// With `eruption_chance`, pick an unfrozen mountain tile and bury it and
+    /// its neighbors in ash.
+    fn erupt(&self, world: &mut World) -> Option<EruptionEvent> {
+        let mut rng = compute_rng_seed(world.tick_count, u32::MAX, Phase::Weather);
+        if rand_f64(&mut rng) >= self.eruption_chance as f64 {
+            return None;
+        }
+        let volcanoes: Vec<usize> = world
+            .tiles
+            .iter()
+            .filter(|t| t.geology.terrain_type == TerrainType::Mountains && !world.is_frozen(t.id))
+            .map(|t| t.id as usize)
+            .collect();
+        if volcanoes.is_empty() {
+            return None;
+        }
+        let vent = volcanoes[(xorshift64(&mut rng) % volcanoes.len() as u64) as usize];
+        let neighbors = world.tiles[vent].neighbors.clone();
+        let ash = &mut world.tiles[vent].weather.dust;
+        *ash = (*ash + ERUPTION_ASH).min(1.0);
+        for n in neighbors {
+            if let Some(tile) = world.tiles.get_mut(n as usize)
+                && !world.frozen_tiles.contains(&n)
+            {
+                tile.weather.dust = (tile.weather.dust + ERUPTION_ASH / 2.0).min(1.0);
+            }
+        }
+        Some(EruptionEvent { tile_id: vent as u32 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    /// A row of desert tiles on the equator, each 5° east of the last, with
+    /// the wind blowing east.
+    fn strip(n: u32) -> World {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = (0..n)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id + 1 < n).then_some(id + 1)];
+                let mut position = Position::flat(id as f64, 0.0);
+                position.lon = 5.0 * id as f64;
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), position);
+                t.biome.biome_type = BiomeType::Desert;
+                t.biome.vegetation_density = 0.0;
+                t.conditions.soil_moisture = 0.0;
+                t.weather.wind_speed = 4.0;
+                t.weather.wind_direction = 90.0;
+                t.weather.temperature = 300.0;
+                t
+            })
+            .collect();
+        world
+    }
+
+    #[test]
+    fn only_dry_bare_desert_in_strong_wind_lifts_dust() {
+        let mut tile = strip(1).tiles.remove(0);
+        assert_eq!(lifted_dust(&tile), 0.0);
+        tile.weather.wind_speed = 18.0;
+        assert!((lifted_dust(&tile) - 0.1).abs() < 1e-6);
+
+        let mut damp = tile.clone();
+        damp.conditions.soil_moisture = 0.3;
+        let mut grass = tile.clone();
+        grass.biome.biome_type = BiomeType::Grassland;
+        let mut snowy = tile.clone();
+        snowy.conditions.snow_depth = 0.1;
+        for t in [damp, grass, snowy] {
+            assert_eq!(lifted_dust(&t), 0.0);
+        }
+    }
+
+    #[test]
+    fn dust_drifts_downwind_cools_and_fertilizes() {
+        let mut world = strip(5);
+        world.tiles[0].weather.dust = 1.0;
+        let dust = Dust::new(&world.tiles, 0.0);
+        for _ in 0..3 {
+            for t in &mut world.tiles {
+                t.weather.temperature = 300.0;
+            }
+            assert!(dust.apply(&mut world).is_none());
+        }
+
+        let loads: Vec<f32> = world.tiles.iter().map(|t| t.weather.dust).collect();
+        // Carried east, nothing blown back west of the source
+        assert!(loads[1] > 0.0 && loads[2] > 0.0, "{:?}", loads);
+        assert!(loads[1] > loads[3], "{:?}", loads);
+        assert!(world.tiles[1].weather.temperature < 300.0);
+        assert!(world.tiles[1].conditions.silt_fertility > 0.0);
+        assert_eq!(world.tiles[4].conditions.silt_fertility, 0.0);
+
+        // Rain washes it out quickly
+        let before: f32 = world.tiles.iter().map(|t| t.weather.dust).sum();
+        for t in &mut world.tiles {
+            t.weather.precipitation = 1.0;
+        }
+        dust.apply(&mut world);
+        let after: f32 = world.tiles.iter().map(|t| t.weather.dust).sum();
+        assert!(after < 0.6 * before, "{} -> {}", before, after);
+    }
+
+    #[test]
+    fn eruptions_ash_a_mountain_and_its_neighbors() {
+        let mut world = strip(3);
+        world.tiles[1].geology.terrain_type = TerrainType::Mountains;
+        for t in &mut world.tiles {
+            t.weather.wind_speed = 0.0;
+        }
+        let dust = Dust::new(&world.tiles, 1.0);
+        assert_eq!(dust.erupt(&mut world), Some(EruptionEvent { tile_id: 1 }));
+        assert_eq!(world.tiles[1].weather.dust, 1.0);
+        assert_eq!(world.tiles[0].weather.dust, 0.5);
+
+        // Frozen mountains don't erupt
+        world.frozen_tiles.insert(1);
+        assert_eq!(dust.erupt(&mut world), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Transient effects on tiles, gathered at the end of each tick for viewer
+//! overlays.
+//!
+//! Floods, eruptions, landslides, glacier calvings and the aurora each leave
+//! their mark on some tiles. Rather than have every viewer guess from raw
+//! values which tiles count as flooded or lit, the tick lists the tiles under
+//! each effect, and the tick diff sends those lists. Floods and aurora last
+//! as long as the water or the glow; the others last the tick they happen.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::dust::EruptionEvent;
+use super::erosion::LandslideEvent;
+use super::flood::FLOOD_EVENT_THRESHOLD;
+use super::glacier::{GlacierEvent, GlacierEventKind};
+use crate::world::World;
+
+/// A transient effect on a tile (a wire protocol type).
+pub use worldground_protocol::TileEffect;
+
+/// Aurora at or above which a tile is listed as lit.
+pub const VISIBLE_AURORA: f32 = 0.05;
+
+/// The tiles under each effect this tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TileEffects {
+    tiles: BTreeMap<TileEffect, BTreeSet<u32>>,
+}
+
+impl TileEffects {
+    /// List the tiles under every effect after a tick, given the events the
+    /// tick's native steps reported. Inert tiles are never listed.
+    pub fn gather(
+        world: &World,
+        eruption: Option<&EruptionEvent>,
+        landslides: &[LandslideEvent],
+        glacier_events: &[GlacierEvent],
+    ) -> Self {
+        let mut effects = TileEffects::default();
+        let live = world.tiles.iter().filter(|t| !world.is_inert(t.id));
+        for tile in live {
+            if tile.conditions.flood_level >= FLOOD_EVENT_THRESHOLD {
+                effects.mark(TileEffect::Flood, tile.id);
+            }
+            if tile.weather.aurora >= VISIBLE_AURORA {
+                effects.mark(TileEffect::Aurora, tile.id);
+            }
+        }
+        if let Some(eruption) = eruption {
+            effects.mark(TileEffect::Eruption, eruption.tile_id);
+        }
+        for slide in landslides {
+            effects.mark(TileEffect::Landslide, slide.tile_id);
+        }
+        for event in glacier_events.iter().filter(|e| e.kind == GlacierEventKind::Calving) {
+            effects.mark(TileEffect::Calving, event.tile_id);
+        }
+        effects
+    }
+
+    /// Fold a later tick's effects into these: lasting effects are the
+    /// later tick's, one-tick effects of both are kept.
+    pub fn merge(&mut self, later: TileEffects) {
+        self.tiles.retain(|effect, _| !effect.lasts());
+        for (effect, ids) in later.tiles {
+            self.tiles.entry(effect).or_default().extend(ids);
+        }
+    }
+
+    /// Put a tile under an effect.
+    pub fn mark(&mut self, effect: TileEffect, tile_id: u32) {
+        self.tiles.entry(effect).or_default().insert(tile_id);
+    }
+
+    /// Whether a tile is under an effect.
+    pub fn contains(&self, effect: TileEffect, tile_id: u32) -> bool {
+        self.tiles.get(&effect).is_some_and(|ids| ids.contains(&tile_id))
+    }
+
+    /// Number of tiles under an effect.
+    pub fn count(&self, effect: TileEffect) -> usize {
+        self.tiles.get(&effect).map_or(0, BTreeSet::len)
+    }
+
+    /// Whether no tile is under any effect.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// The lists the tick diff sends: each effect's tiles in ascending ID order.
+    pub fn to_wire(&self) -> BTreeMap<TileEffect, Vec<u32>> {
+        self.tiles.iter().map(|(&effect, ids)| (effect, ids.iter().copied().collect())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::GenerationParams;
+    use crate::world::generation::generate_world;
+
+    #[test]
+    fn gather_lists_the_tiles_under_each_effect() {
+        let mut world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        });
+        for tile in &mut world.tiles {
+            tile.conditions.flood_level = 0.0;
+            tile.weather.aurora = 0.0;
+        }
+        assert!(TileEffects::gather(&world, None, &[], &[]).is_empty());
+
+        world.tiles[4].conditions.flood_level = FLOOD_EVENT_THRESHOLD;
+        world.tiles[9].conditions.flood_level = 0.9;
+        world.tiles[5].conditions.flood_level = 0.1;
+        world.tiles[7].weather.aurora = 0.4;
+        world.inert_tiles.insert(9);
+        let slides = [LandslideEvent { tile_id: 12, downslope_tile_id: 13, depth: 0.05 }];
+        let glaciers = [
+            GlacierEvent { tile_id: 20, kind: GlacierEventKind::Calving, mass: 0.2 },
+            GlacierEvent { tile_id: 21, kind: GlacierEventKind::Retreat, mass: 0.1 },
+        ];
+        let effects = TileEffects::gather(&world, Some(&EruptionEvent { tile_id: 30 }), &slides, &glaciers);
+
+        let wire = effects.to_wire();
+        assert_eq!(wire[&TileEffect::Flood], vec![4], "below the threshold or inert tiles are left out");
+        assert_eq!(wire[&TileEffect::Aurora], vec![7]);
+        assert_eq!(wire[&TileEffect::Eruption], vec![30]);
+        assert_eq!(wire[&TileEffect::Landslide], vec![12]);
+        assert_eq!(wire[&TileEffect::Calving], vec![20], "retreats are not calvings");
+        assert!(effects.contains(TileEffect::Landslide, 12) && !effects.contains(TileEffect::Landslide, 13));
+        assert_eq!(effects.count(TileEffect::Flood), 1);
+
+        // Over two ticks, the flood is as it is now and both landslides happened
+        let mut later = TileEffects::default();
+        later.mark(TileEffect::Flood, 5);
+        later.mark(TileEffect::Landslide, 14);
+        let mut merged = effects.clone();
+        merged.merge(later);
+        let wire = merged.to_wire();
+        assert_eq!(wire[&TileEffect::Flood], vec![5]);
+        assert_eq!(wire[&TileEffect::Landslide], vec![12, 14]);
+        assert!(!wire.contains_key(&TileEffect::Aurora));
+    }
+}
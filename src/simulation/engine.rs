@@ -1,10 +1,20 @@
-use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use rhai::{ASTNode, Array, Dynamic, Engine, EvalAltResult, Expr, Map, Scope, Stmt, AST};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hasher;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
-use tracing::debug;
-
+use tracing::{debug, warn};
+use worldground_protocol::units::ZERO_CELSIUS;
+
+use super::builtin_rules::{self, is_builtin};
+use super::events::{EmittedEvent, EventValue};
+use super::globals::{GlobalAccumulator, Reduction};
+use super::neighbor_writes::{Merge, NeighborWrite, MERGES};
+use super::replay::Fnv;
+use crate::error::WorldgroundError;
 use crate::world::tile::*;
 use crate::world::Tile;
 
@@ -43,12 +53,373 @@ pub struct CompiledRule {
     pub name: String,
     pub phase: Phase,
     pub ast: AST,
+    /// A batch rule (`*.batch.rhai`) runs once per batch of tiles rather
+    /// than once per tile.
+    pub batch: bool,
+    /// Shared by every thread evaluating the rule, and kept across reloads
+    counters: Arc<RuleCounters>,
+}
+
+/// Running execution totals for one rule.
+#[derive(Debug, Default)]
+struct RuleCounters {
+    invocations: AtomicU64,
+    errors: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl RuleCounters {
+    fn record(&self, elapsed: std::time::Duration, failed: bool) {
+        self.invocations.fetch_add(1, Ordering::Relaxed);
+        self.nanos.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// How often one rule ran, failed, and how long it took in total.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RuleMetrics {
+    /// Tile evaluations of the rule
+    pub invocations: u64,
+    /// Evaluations that ended in a script error
+    pub errors: u64,
+    /// Summed over threads, so rules in parallel add up to more than wall time
+    pub time: std::time::Duration,
+}
+
+/// Execution metrics for every rule, keyed by "phase/file"
+/// (e.g. "weather/01-temperature.rhai").
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleStats {
+    pub rules: BTreeMap<String, RuleMetrics>,
+}
+
+impl RuleStats {
+    pub fn merge(&mut self, other: &RuleStats) {
+        for (rule, m) in &other.rules {
+            let total = self.rules.entry(rule.clone()).or_default();
+            total.invocations += m.invocations;
+            total.errors += m.errors;
+            total.time += m.time;
+        }
+    }
+
+    /// What accumulated since `earlier`, a previous snapshot of the same
+    /// engine. Rules that did not run are left out.
+    pub fn since(&self, earlier: &RuleStats) -> RuleStats {
+        let rules = self
+            .rules
+            .iter()
+            .filter_map(|(rule, m)| {
+                let before = earlier.rules.get(rule).copied().unwrap_or_default();
+                let delta = RuleMetrics {
+                    invocations: m.invocations.saturating_sub(before.invocations),
+                    errors: m.errors.saturating_sub(before.errors),
+                    time: m.time.saturating_sub(before.time),
+                };
+                (delta.invocations > 0).then(|| (rule.clone(), delta))
+            })
+            .collect();
+        RuleStats { rules }
+    }
+
+    /// Rules by total time, slowest first.
+    pub fn slowest(&self) -> Vec<(&str, RuleMetrics)> {
+        let mut rules: Vec<_> = self.rules.iter().map(|(rule, m)| (rule.as_str(), *m)).collect();
+        rules.sort_by(|a, b| b.1.time.cmp(&a.1.time).then_with(|| a.0.cmp(b.0)));
+        rules
+    }
 }
 
 /// The result of evaluating rules for a single tile in a single phase.
 #[derive(Debug, Clone, Default)]
 pub struct TileMutations {
     pub mutations: Vec<(String, Dynamic)>,
+    /// Index into the phase's rule list of the rule that emitted each mutation.
+    /// Empty when the evaluator does not track sources (native evaluators).
+    pub rule_indices: Vec<usize>,
+    /// Contributions to world globals made via `contribute*()`.
+    pub contributions: GlobalAccumulator,
+    /// Writes to neighbors made via `set_neighbor()`, merged after the phase.
+    pub neighbor_writes: Vec<NeighborWrite>,
+    /// Events raised via `emit_event()`.
+    pub events: Vec<EmittedEvent>,
+}
+
+impl TileMutations {
+    /// Index of the rule that emitted mutation `i`, if sources are tracked.
+    pub fn rule_index(&self, i: usize) -> Option<usize> {
+        self.rule_indices.get(i).copied()
+    }
+}
+
+/// Why a mutation emitted by `set()` was not applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RejectionReason {
+    /// Field does not exist or is not writable in this phase.
+    UnwritableField,
+    /// Value has the wrong type for the field (e.g. an integer for a float field).
+    TypeMismatch,
+    /// Value has the right type but names no known variant (biome, precipitation
+    /// type) or is not a finite number.
+    InvalidValue,
+    /// Biome change not allowed by `valid_transitions`.
+    InvalidBiomeTransition,
+    /// New scratch variable would exceed `MAX_SCRATCH_KEYS`.
+    ScratchLimit,
+    /// `set_neighbor()` named a tile that isn't a neighbor.
+    NotANeighbor,
+    /// The same neighbor field was written with different merges.
+    MergeConflict,
+}
+
+impl RejectionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectionReason::UnwritableField => "unwritable_field",
+            RejectionReason::TypeMismatch => "type_mismatch",
+            RejectionReason::InvalidValue => "invalid_value",
+            RejectionReason::InvalidBiomeTransition => "invalid_biome_transition",
+            RejectionReason::ScratchLimit => "scratch_limit",
+            RejectionReason::NotANeighbor => "not_a_neighbor",
+            RejectionReason::MergeConflict => "merge_conflict",
+        }
+    }
+}
+
+/// Applied and rejected mutation counts for one tick, with rejections broken
+/// down by rule and reason.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MutationStats {
+    pub applied: u64,
+    pub rejected: BTreeMap<(String, RejectionReason), u64>,
+}
+
+impl MutationStats {
+    pub fn record_rejection(&mut self, rule_name: &str, reason: RejectionReason) {
+        if let Some(count) = self.rejected.get_mut(&(rule_name.to_string(), reason)) {
+            *count += 1;
+        } else {
+            self.rejected.insert((rule_name.to_string(), reason), 1);
+        }
+    }
+
+    pub fn total_rejected(&self) -> u64 {
+        self.rejected.values().sum()
+    }
+
+    pub fn merge(&mut self, other: MutationStats) {
+        self.applied += other.applied;
+        for (key, count) in other.rejected {
+            *self.rejected.entry(key).or_insert(0) += count;
+        }
+    }
+}
+
+/// A deprecated field name that `set()` still accepts, mapped to its current name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldAlias {
+    pub old_name: &'static str,
+    pub new_name: &'static str,
+    pub phase: Phase,
+    /// Release in which the old name stops being accepted.
+    pub removed_in: &'static str,
+}
+
+/// Deprecated mutation field names. Old rule scripts keep working (with a
+/// one-time warning) until the listed release, after which the entry is dropped.
+pub const FIELD_ALIASES: &[FieldAlias] = &[
+    FieldAlias { old_name: "moisture", new_name: "soil_moisture", phase: Phase::Conditions, removed_in: "0.3.0" },
+    FieldAlias { old_name: "snow", new_name: "snow_depth", phase: Phase::Conditions, removed_in: "0.3.0" },
+    FieldAlias { old_name: "vegetation", new_name: "vegetation_density", phase: Phase::Terrain, removed_in: "0.3.0" },
+];
+
+/// Look up a deprecated field name for the given phase.
+pub fn resolve_field_alias(field: &str, phase: Phase) -> Option<&'static FieldAlias> {
+    FIELD_ALIASES
+        .iter()
+        .find(|a| a.phase == phase && a.old_name == field)
+}
+
+/// Aliases already warned about, so each deprecation is logged once per process.
+static WARNED_ALIASES: std::sync::Mutex<Vec<&'static str>> = std::sync::Mutex::new(Vec::new());
+
+fn warn_alias_once(alias: &'static FieldAlias) {
+    let mut warned = WARNED_ALIASES.lock().unwrap_or_else(|e| e.into_inner());
+    if !warned.contains(&alias.old_name) {
+        warned.push(alias.old_name);
+        warn!(
+            "Rule field '{}' is deprecated, use '{}' instead (removed in {})",
+            alias.old_name, alias.new_name, alias.removed_in
+        );
+    }
+}
+
+/// A use of a deprecated field name found in a rule script.
+#[derive(Debug, Clone)]
+pub struct AliasUsage {
+    pub rule_path: std::path::PathBuf,
+    pub line: usize,
+    pub alias: &'static FieldAlias,
+}
+
+/// The `.rhai` files of one phase, sorted by name, with their sources. A
+/// missing phase directory has none; the built-in pack is read from the
+/// binary, under paths like `builtin/weather/01-wind-temperature.rhai`.
+fn phase_rule_sources(rule_dir: &Path, phase: Phase) -> Result<Vec<(std::path::PathBuf, String)>, String> {
+    let phase_dir = rule_dir.join(phase.dir_name());
+    if is_builtin(rule_dir) {
+        return Ok(builtin_rules::phase_rules(phase)
+            .map(|(name, source)| (phase_dir.join(name), source.to_string()))
+            .collect());
+    }
+    if !phase_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<_> = std::fs::read_dir(&phase_dir)
+        .map_err(|e| format!("Cannot read {}: {}", phase_dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "rhai"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let source = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Cannot read rule {}: {}", path.display(), e))?;
+            Ok((path, source))
+        })
+        .collect()
+}
+
+/// Scan rule sources for `set()` calls that use deprecated field names.
+///
+/// This is a textual scan of `set("name"` call sites, used by `rules check`.
+pub fn scan_alias_usages(rule_dir: &Path) -> Result<Vec<AliasUsage>, String> {
+    let mut usages = Vec::new();
+
+    for phase in Phase::all() {
+        for (path, source) in phase_rule_sources(rule_dir, *phase)? {
+            for (i, line) in source.lines().enumerate() {
+                for alias in FIELD_ALIASES.iter().filter(|a| a.phase == *phase) {
+                    let call = format!("set(\"{}\"", alias.old_name);
+                    if line.contains(&call) {
+                        usages.push(AliasUsage {
+                            rule_path: path.clone(),
+                            line: i + 1,
+                            alias,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(usages)
+}
+
+/// A `set()` call in a rule script whose field is a string literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetCall {
+    pub rule_path: std::path::PathBuf,
+    pub line: usize,
+    pub phase: Phase,
+    pub field: String,
+}
+
+impl SetCall {
+    /// Report line for a call whose field the phase can't write.
+    pub fn unwritable_message(&self) -> String {
+        format!(
+            "{}:{}: set(\"{}\"): not a field writable in the {} phase",
+            self.rule_path.display(),
+            self.line,
+            self.field,
+            self.phase.dir_name()
+        )
+    }
+}
+
+/// Lines and field names of the `set()` calls in a compiled script whose
+/// field is a string literal. Fields built at run time
+/// (`set(r.resource_type + ".quantity", ...)`) are not found.
+fn literal_set_calls(ast: &AST) -> Vec<(usize, String)> {
+    let mut calls = Vec::new();
+    ast.walk(&mut |path: &[ASTNode]| {
+        let (call, pos) = match path.last() {
+            Some(ASTNode::Stmt(Stmt::FnCall(call, pos))) | Some(ASTNode::Expr(Expr::FnCall(call, pos))) => (call, pos),
+            _ => return true,
+        };
+        match call.args.first() {
+            Some(Expr::StringConstant(field, _)) if call.name == "set" && !call.is_qualified() => {
+                calls.push((pos.line().unwrap_or(0), field.to_string()));
+            }
+            _ => {}
+        }
+        true
+    });
+    calls
+}
+
+/// `set()` calls with a literal field name that their rule's phase can't write.
+fn unwritable_set_calls(rule_dir: &Path, rules: &HashMap<Phase, Vec<CompiledRule>>) -> Vec<SetCall> {
+    let mut calls = Vec::new();
+    for phase in Phase::all() {
+        for rule in rules.get(phase).map(|v| v.as_slice()).unwrap_or(&[]) {
+            calls.extend(
+                literal_set_calls(&rule.ast)
+                    .into_iter()
+                    .filter(|(_, field)| !is_settable_field(field, *phase))
+                    .map(|(line, field)| SetCall {
+                        rule_path: rule_dir.join(phase.dir_name()).join(&rule.name),
+                        line,
+                        phase: *phase,
+                        field,
+                    }),
+            );
+        }
+    }
+    calls
+}
+
+/// Find the `set()` calls with a literal field name in every rule script, so
+/// they can be checked without running the rules. Scripts that don't compile
+/// are skipped; `rule_syntax_errors` reports them.
+pub fn scan_set_calls(rule_dir: &Path) -> Result<Vec<SetCall>, String> {
+    let engine = sandboxed_engine(&Arc::default());
+    let mut calls = Vec::new();
+    for phase in Phase::all() {
+        for (path, source) in phase_rule_sources(rule_dir, *phase)? {
+            let Ok(ast) = engine.compile(&source) else {
+                continue;
+            };
+            calls.extend(literal_set_calls(&ast).into_iter().map(|(line, field)| SetCall {
+                rule_path: path.clone(),
+                line,
+                phase: *phase,
+                field,
+            }));
+        }
+    }
+    Ok(calls)
+}
+
+/// Compile every rule script on its own and return one message per script
+/// that fails, where loading the engine stops at the first.
+pub fn rule_syntax_errors(rule_dir: &Path) -> Result<Vec<String>, String> {
+    let engine = sandboxed_engine(&Arc::default());
+    let mut errors = Vec::new();
+    for phase in Phase::all() {
+        for (path, source) in phase_rule_sources(rule_dir, *phase)? {
+            if let Err(e) = engine.compile(&source) {
+                errors.push(format!("Syntax error in {}: {}", path.display(), e));
+            }
+        }
+    }
+    Ok(errors)
 }
 
 /// Error from rule evaluation on a single tile.
@@ -69,173 +440,721 @@ impl std::fmt::Display for RuleError {
     }
 }
 
+/// Rules by phase, the `params` map and the rules hash, as compiled together.
+type CompiledRules = (HashMap<Phase, Vec<CompiledRule>>, Dynamic, u64);
+
 /// The rule engine loads, validates, and executes Rhai scripts against tile data.
 pub struct RuleEngine {
     engine: Engine,
+    /// Directory the rules were loaded from, for `reload_rules`.
+    rule_dir: std::path::PathBuf,
     rules: HashMap<Phase, Vec<CompiledRule>>,
-    timeout_ms: u64,
+    /// Hash of the loaded rules' paths and sources, for the replay log.
+    rules_hash: u64,
+    /// Rule pack the rules were loaded from, if any.
+    pack: Option<String>,
+    /// Constants from the rule directory's `rules.toml`, the scripts' `params`.
+    params: Dynamic,
+    /// Budget of each rule run in phases without their own.
+    limits: RuleLimits,
+    /// Budgets of phases that override `limits`, e.g. heavier terrain rules.
+    phase_limits: HashMap<Phase, RuleLimits>,
+    /// Ticks between runs of phases that don't run every tick.
+    phase_periods: HashMap<Phase, u32>,
+    /// Weight tick statistics by tile area rather than counting tiles.
+    area_weighted_statistics: bool,
     native_evaluators: HashMap<Phase, Box<dyn super::native_eval::NativePhaseEvaluator>>,
+    strict: bool,
+    /// Tiles a worker evaluates together, sharing one scope per batch.
+    batch_size: usize,
+    /// Latitude-band temperature relaxation run after the weather phase.
+    thermostat: Option<super::thermostat::Thermostat>,
+    /// Skipping rule evaluation on idle tiles.
+    idle_skip: Option<super::activity::IdleSkip>,
+    /// Freeze-thaw weathering run at the end of the conditions phase.
+    freeze_thaw: Option<super::freeze_thaw::FreezeThaw>,
+    /// Slope erosion and landslides run at the end of the conditions phase.
+    erosion: Option<super::erosion::Erosion>,
+    /// Persistent blocking highs formed during macro weather.
+    blocking: Option<super::macro_weather::Blocking>,
+    /// Cosmetic space weather run after macro weather.
+    aurora: Option<super::aurora::Aurora>,
+    /// Wind-blown dust and volcanic ash run after the weather phase.
+    dust: Option<super::dust::Dust>,
+    /// Ocean salinity and overturning run after the weather phase.
+    ocean: Option<super::ocean::Ocean>,
+    /// Wind waves on the open ocean run after the weather phase.
+    waves: Option<super::waves::Waves>,
+    /// Glacier growth, flow and melt run at the end of the conditions phase.
+    glaciers: Option<super::glacier::Glaciers>,
+    sea_level: Option<super::sea_level::SeaLevel>,
+    /// Sampling profiler timing rules and native steps.
+    profiler: Option<super::profiler::Profiler>,
+    /// Page file deposits live in outside the resources phase (bounded-memory mode).
+    paging: Option<crate::world::paging::PageFile>,
+    /// The globals rules read, shared with `global_get()`.
+    globals: Arc<std::sync::RwLock<RuleGlobals>>,
 }
 
-impl RuleEngine {
-    /// Create a new rule engine and load rules from the given directory.
-    ///
-    /// The rule directory must contain subdirectories: weather/, conditions/, terrain/, resources/.
-    /// Each subdirectory contains .rhai files sorted by filename.
-    pub fn new(rule_dir: &Path, timeout_ms: u64) -> Result<Self, String> {
-        if !rule_dir.exists() {
-            return Err(format!(
-                "Rule directory not found: {}. Create the directory with rule scripts.",
-                rule_dir.display()
-            ));
-        }
-
-        let mut engine = Engine::new();
-
-        // Sandbox: disable all dangerous operations
-        engine.set_max_operations(100_000);
-        engine.set_max_string_size(1024);
-        engine.set_max_array_size(1000);
-        engine.set_max_map_size(500);
+/// Globals as rules read them: last tick's reduced values, exposed as the
+/// `globals` map, and this tick's reduced over the phases run so far, which
+/// `global_get()` reads.
+#[derive(Default)]
+struct RuleGlobals {
+    previous: Map,
+    tick: BTreeMap<String, f64>,
+}
 
-        // Register the `set` function for tile mutations
-        engine.register_fn("set", |field: &str, value: Dynamic| {
-            MUTATIONS.with(|m| {
-                m.borrow_mut().push((field.to_string(), value));
-            });
+/// The Rhai engine rules run in: sandbox limits and the host functions
+/// rules may call. `global_get()` reads `globals`.
+fn sandboxed_engine(globals: &Arc<std::sync::RwLock<RuleGlobals>>) -> Engine {
+    let mut engine = Engine::new();
+
+    // Sandbox: disable all dangerous operations. Operations and time are
+    // bounded per rule run by the budget checked in `on_progress` below.
+    engine.set_max_string_size(1024);
+    engine.set_max_array_size(1000);
+    engine.set_max_map_size(500);
+
+    // Register the `set` function for tile mutations
+    engine.register_fn("set", |field: &str, value: Dynamic| {
+        MUTATIONS.with(|m| {
+            m.borrow_mut().push((field.to_string(), value));
         });
-
-        // Register `log` function
-        engine.register_fn("log", |msg: &str| {
-            LOG_MESSAGES.with(|l| {
-                l.borrow_mut().push(msg.to_string());
-            });
+    });
+
+    // Register `set_neighbor` for writes to neighbors, merged after the phase
+    // (ints accepted for counts)
+    fn set_neighbor(id: i64, field: &str, value: f64, merge: &str) -> Result<(), Box<EvalAltResult>> {
+        let merge = Merge::parse(merge).ok_or_else(|| {
+            format!("set_neighbor(): merge must be one of {}, got '{}'", MERGES.join(", "), merge)
+        })?;
+        let target = u32::try_from(id).map_err(|_| format!("set_neighbor({}): no such tile", id))?;
+        let rule = CURRENT_RULE.with(|r| r.get());
+        let write = NeighborWrite { target, field: field.to_string(), value, merge, rule };
+        NEIGHBOR_WRITES.with(|w| w.borrow_mut().push(write));
+        Ok(())
+    }
+    engine.register_fn("set_neighbor", |id: i64, field: &str, value: f64| set_neighbor(id, field, value, "mean"));
+    engine.register_fn("set_neighbor", |id: i64, field: &str, value: i64| set_neighbor(id, field, value as f64, "mean"));
+    engine.register_fn("set_neighbor", set_neighbor);
+    engine.register_fn("set_neighbor", |id: i64, field: &str, value: i64, merge: &str| {
+        set_neighbor(id, field, value as f64, merge)
+    });
+
+    // Register batch-rule helpers: the batch's tiles, and `select` to send
+    // later set/contribute/rand calls to one of them
+    engine.register_fn("batch_len", || -> i64 { BATCH.with(|b| b.borrow().len() as i64) });
+    engine.register_fn("batch_tile", |i: i64| -> Result<Dynamic, Box<EvalAltResult>> {
+        let i = batch_index("batch_tile", i)?;
+        Ok(BATCH.with(|b| b.borrow()[i].tile.clone()))
+    });
+    engine.register_fn("batch_neighbors", |i: i64| -> Result<Array, Box<EvalAltResult>> {
+        let i = batch_index("batch_neighbors", i)?;
+        Ok(BATCH.with(|b| b.borrow()[i].neighbors.clone()))
+    });
+    engine.register_fn("select", |i: i64| -> Result<(), Box<EvalAltResult>> {
+        focus_batch_tile(batch_index("select", i)?);
+        Ok(())
+    });
+
+    // Register `contribute*` functions for world globals (ints accepted for
+    // counts); `global_add` is `contribute` under the name accumulators use
+    for (name, reduction) in [
+        ("contribute", Reduction::Sum),
+        ("contribute_min", Reduction::Min),
+        ("contribute_max", Reduction::Max),
+        ("global_add", Reduction::Sum),
+    ] {
+        engine.register_fn(name, move |global: &str, value: f64| {
+            CONTRIBUTIONS.with(|c| c.borrow_mut().contribute(global, reduction, value));
         });
+        engine.register_fn(name, move |global: &str, value: i64| {
+            CONTRIBUTIONS.with(|c| c.borrow_mut().contribute(global, reduction, value as f64));
+        });
+    }
 
-        // Register rand functions using thread-local RNG state
-        engine.register_fn("rand", || -> f64 {
-            RNG_STATE.with(|r| {
-                let state = r.get();
-                let next = xorshift64(state);
-                r.set(next);
-                (next as f64) / (u64::MAX as f64)
+    // Register `global_get` for a global reduced over the tick's earlier phases
+    let globals = Arc::clone(globals);
+    engine.register_fn("global_get", move |global: &str| -> f64 {
+        globals.read().unwrap().tick.get(global).copied().unwrap_or(0.0)
+    });
+
+    // Register `emit_event` for discrete world events, tied to the tile and rule
+    fn emit_event(kind: &str, data: Map) -> Result<(), Box<EvalAltResult>> {
+        if kind.is_empty() {
+            return Err("emit_event(): kind must not be empty".into());
+        }
+        let data = data
+            .into_iter()
+            .map(|(key, value)| {
+                let value = if let Ok(b) = value.as_bool() {
+                    EventValue::Bool(b)
+                } else if let Ok(x) = value.as_float() {
+                    EventValue::Number(x)
+                } else if let Ok(n) = value.as_int() {
+                    EventValue::Number(n as f64)
+                } else if value.is_string() {
+                    EventValue::Text(value.to_string())
+                } else {
+                    return Err(format!(
+                        "emit_event(\"{}\"): data value '{}' must be a number, string or bool, got {}",
+                        kind,
+                        key,
+                        value.type_name()
+                    ));
+                };
+                Ok((key.to_string(), value))
             })
-        });
-        // Math helpers for directional wind calculations
-        engine.register_fn("sin_deg", |deg: f64| -> f64 {
-            (deg * std::f64::consts::PI / 180.0).sin()
-        });
-        engine.register_fn("cos_deg", |deg: f64| -> f64 {
-            (deg * std::f64::consts::PI / 180.0).cos()
-        });
-        engine.register_fn("sqrt", |x: f64| -> f64 { x.sqrt() });
-        engine.register_fn("abs", |v: f64| -> f64 { v.abs() });
-        engine.register_fn("clamp", |v: f64, min: f64, max: f64| -> f64 { v.clamp(min, max) });
-
-        // LEGACY: Planar wind alignment — uses 2D (x,y) which is incorrect on geodesic
-        // grids where position.x/y are 3D sphere coordinates. Kept for backwards
-        // compatibility with custom rules. Core weather rules now use macro_wind_*
-        // fields projected by the macro weather engine (sphere_math.rs).
-        engine.register_fn(
-            "wind_align",
-            |from_x: f64, from_y: f64, to_x: f64, to_y: f64, wind_dir: f64| -> f64 {
-                let dx = to_x - from_x;
-                let dy = to_y - from_y;
-                let dist_sq = dx * dx + dy * dy;
-                if dist_sq < 1e-6 {
-                    return 0.0;
-                }
-                let dist = dist_sq.sqrt();
-                let rad = wind_dir * std::f64::consts::PI / 180.0;
-                (rad.sin() * dx + rad.cos() * dy) / dist
-            },
-        );
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        let rule = CURRENT_RULE.with(|r| r.get());
+        EVENTS.with(|e| e.borrow_mut().push(EmittedEvent { kind: kind.to_string(), data, rule }));
+        Ok(())
+    }
+    engine.register_fn("emit_event", |kind: &str| emit_event(kind, Map::new()));
+    engine.register_fn("emit_event", emit_event);
 
-        // LEGACY: Planar direction — same caveat as wind_align above.
-        engine.register_fn(
-            "direction_to",
-            |from_x: f64, from_y: f64, to_x: f64, to_y: f64| -> Array {
-                let dx = to_x - from_x;
-                let dy = to_y - from_y;
-                let dist_sq = dx * dx + dy * dy;
-                if dist_sq < 1e-6 {
-                    return vec![Dynamic::from(0.0_f64), Dynamic::from(0.0_f64)];
-                }
-                let dist = dist_sq.sqrt();
-                vec![Dynamic::from(dx / dist), Dynamic::from(dy / dist)]
-            },
-        );
+    // Register `log` function
+    engine.register_fn("log", |msg: &str| {
+        LOG_MESSAGES.with(|l| {
+            l.borrow_mut().push(msg.to_string());
+        });
+    });
+
+    // Register rand functions using thread-local RNG state
+    engine.register_fn("rand", || -> f64 {
+        RNG_STATE.with(|r| {
+            let state = r.get();
+            let next = xorshift64(state);
+            r.set(next);
+            (next as f64) / (u64::MAX as f64)
+        })
+    });
+    // Math helpers for directional wind calculations
+    engine.register_fn("sin_deg", |deg: f64| -> f64 {
+        (deg * std::f64::consts::PI / 180.0).sin()
+    });
+    engine.register_fn("cos_deg", |deg: f64| -> f64 {
+        (deg * std::f64::consts::PI / 180.0).cos()
+    });
+    engine.register_fn("sqrt", |x: f64| -> f64 { x.sqrt() });
+    engine.register_fn("abs", |v: f64| -> f64 { v.abs() });
+    engine.register_fn("clamp", |v: f64, min: f64, max: f64| -> f64 { v.clamp(min, max) });
+    // Temperatures are Kelvin everywhere; these spare rules the 273.15
+    engine.register_fn("to_celsius", |kelvin: f64| -> f64 { kelvin - ZERO_CELSIUS });
+    engine.register_fn("to_celsius", |kelvin: i64| -> f64 { kelvin as f64 - ZERO_CELSIUS });
+    engine.register_fn("to_kelvin", |celsius: f64| -> f64 { celsius + ZERO_CELSIUS });
+    engine.register_fn("to_kelvin", |celsius: i64| -> f64 { celsius as f64 + ZERO_CELSIUS });
+
+    // LEGACY: Planar wind alignment — uses 2D (x,y) which is incorrect on geodesic
+    // grids where position.x/y are 3D sphere coordinates. Kept for backwards
+    // compatibility with custom rules. Core weather rules now use macro_wind_*
+    // fields projected by the macro weather engine (sphere_math.rs).
+    engine.register_fn(
+        "wind_align",
+        |from_x: f64, from_y: f64, to_x: f64, to_y: f64, wind_dir: f64| -> f64 {
+            let dx = to_x - from_x;
+            let dy = to_y - from_y;
+            let dist_sq = dx * dx + dy * dy;
+            if dist_sq < 1e-6 {
+                return 0.0;
+            }
+            let dist = dist_sq.sqrt();
+            let rad = wind_dir * std::f64::consts::PI / 180.0;
+            (rad.sin() * dx + rad.cos() * dy) / dist
+        },
+    );
 
-        // Native acceleration: average a nested field across neighbor maps.
-        // Path format: "layer.field" e.g. "weather.temperature"
-        engine.register_fn("neighbor_avg", |neighbors: Array, path: &str| -> f64 {
-            let mut sum = 0.0;
-            let mut count = 0usize;
-            for n in &neighbors {
-                if let Some(v) = get_nested_f64(n, path) {
-                    sum += v;
-                    count += 1;
-                }
+    // LEGACY: Planar direction — same caveat as wind_align above.
+    engine.register_fn(
+        "direction_to",
+        |from_x: f64, from_y: f64, to_x: f64, to_y: f64| -> Array {
+            let dx = to_x - from_x;
+            let dy = to_y - from_y;
+            let dist_sq = dx * dx + dy * dy;
+            if dist_sq < 1e-6 {
+                return vec![Dynamic::from(0.0_f64), Dynamic::from(0.0_f64)];
             }
-            if count > 0 {
-                sum / count as f64
-            } else {
-                0.0
+            let dist = dist_sq.sqrt();
+            vec![Dynamic::from(dx / dist), Dynamic::from(dy / dist)]
+        },
+    );
+
+    // Native acceleration: average a nested field across neighbor maps.
+    // Path format: "layer.field" e.g. "weather.temperature"
+    engine.register_fn("neighbor_avg", |neighbors: Array, path: &str| -> f64 {
+        let mut sum = 0.0;
+        let mut count = 0usize;
+        for n in &neighbors {
+            if let Some(v) = get_nested_f64(n, path) {
+                sum += v;
+                count += 1;
             }
-        });
+        }
+        if count > 0 {
+            sum / count as f64
+        } else {
+            0.0
+        }
+    });
 
-        // Native acceleration: sum a nested field across neighbor maps.
-        engine.register_fn("neighbor_sum", |neighbors: Array, path: &str| -> f64 {
-            neighbors
-                .iter()
-                .filter_map(|n| get_nested_f64(n, path))
-                .sum()
-        });
+    // Native acceleration: sum a nested field across neighbor maps.
+    engine.register_fn("neighbor_sum", |neighbors: Array, path: &str| -> f64 {
+        neighbors
+            .iter()
+            .filter_map(|n| get_nested_f64(n, path))
+            .sum()
+    });
 
-        // Native acceleration: max of a nested field across neighbor maps.
-        engine.register_fn("neighbor_max", |neighbors: Array, path: &str| -> f64 {
-            neighbors
-                .iter()
-                .filter_map(|n| get_nested_f64(n, path))
-                .reduce(f64::max)
-                .unwrap_or(0.0)
-        });
+    // Native acceleration: max of a nested field across neighbor maps.
+    engine.register_fn("neighbor_max", |neighbors: Array, path: &str| -> f64 {
+        neighbors
+            .iter()
+            .filter_map(|n| get_nested_f64(n, path))
+            .reduce(f64::max)
+            .unwrap_or(0.0)
+    });
+
+    // Native acceleration: precipitation multiplier for the wind forcing
+    // air up or down the terrain (see native_weather::orographic_factor).
+    engine.register_fn("orographic_factor", |tile: Dynamic, neighbors: Array| -> f64 {
+        orographic_factor_from_maps(&tile, &neighbors)
+    });
+
+    engine.register_fn("rand_range", |min: f64, max: f64| -> f64 {
+        RNG_STATE.with(|r| {
+            let state = r.get();
+            let next = xorshift64(state);
+            r.set(next);
+            let t = (next as f64) / (u64::MAX as f64);
+            min + t * (max - min)
+        })
+    });
+
+    // Stop a rule that goes over its run's budget. Reading the clock costs
+    // more than an operation, so the deadline is checked every so often.
+    engine.on_progress(|ops| {
+        let budget = BUDGET.with(|b| b.get());
+        if ops > budget.max_operations {
+            return Some(format!("Too many operations (limit {})", budget.max_operations).into());
+        }
+        match budget.deadline {
+            Some((start, timeout)) if ops % DEADLINE_CHECK_OPERATIONS == 0 && start.elapsed() > timeout => {
+                Some(format!("Timed out after {} ms", timeout.as_millis()).into())
+            }
+            _ => None,
+        }
+    });
 
-        engine.register_fn("rand_range", |min: f64, max: f64| -> f64 {
-            RNG_STATE.with(|r| {
-                let state = r.get();
-                let next = xorshift64(state);
-                r.set(next);
-                let t = (next as f64) / (u64::MAX as f64);
-                min + t * (max - min)
-            })
-        });
+    engine
+}
 
-        // Timeout enforcement via operation limit
-        // At ~100K operations with typical Rhai performance, this equates to roughly 10-50ms
-        // Combined with max_operations, this provides a reasonable timeout mechanism
-        engine.on_progress(move |_ops| {
-            // max_operations provides the hard cap for timeout enforcement
-            None
-        });
+impl RuleEngine {
+    /// Create a new rule engine and load rules from the given directory.
+    ///
+    /// The rule directory must contain subdirectories: weather/, conditions/, terrain/, resources/.
+    /// Each subdirectory contains .rhai files sorted by filename.
+    pub fn new(rule_dir: &Path, timeout_ms: u64) -> Result<Self, WorldgroundError> {
+        if !is_builtin(rule_dir) && !rule_dir.exists() {
+            return Err(WorldgroundError::Rules(format!(
+                "Rule directory not found: {}. Create the directory with rule scripts, or use '{}' for the rules built into the binary.",
+                rule_dir.display(),
+                builtin_rules::BUILTIN_RULES
+            )));
+        }
+
+        let globals = Arc::default();
+        let engine = sandboxed_engine(&globals);
 
         let mut rule_engine = RuleEngine {
             engine,
+            rule_dir: rule_dir.to_path_buf(),
             rules: HashMap::new(),
-            timeout_ms,
+            rules_hash: 0,
+            pack: None,
+            params: Dynamic::from(Map::new()),
+            limits: RuleLimits { max_operations: DEFAULT_MAX_OPERATIONS, timeout_ms },
+            phase_limits: HashMap::new(),
+            phase_periods: HashMap::new(),
+            area_weighted_statistics: false,
             native_evaluators: HashMap::new(),
+            strict: false,
+            batch_size: 1,
+            thermostat: None,
+            idle_skip: None,
+            freeze_thaw: None,
+            erosion: None,
+            blocking: None,
+            aurora: None,
+            dust: None,
+            ocean: None,
+            waves: None,
+            glaciers: None,
+            sea_level: None,
+            profiler: None,
+            paging: None,
+            globals,
         };
 
-        rule_engine.load_rules(rule_dir)?;
+        (rule_engine.rules, rule_engine.params, rule_engine.rules_hash) =
+            rule_engine.compile_rules()?;
+        for call in rule_engine.unwritable_set_calls() {
+            warn!("{}", call.unwritable_message());
+        }
         Ok(rule_engine)
     }
 
+    /// Rule packs in `rule_dir`, by name: subdirectories with phase
+    /// directories of their own, such as `rules/arid-world/weather/`.
+    pub fn rule_packs(rule_dir: &Path) -> Vec<String> {
+        if is_builtin(rule_dir) {
+            return Vec::new();
+        }
+        let Ok(entries) = std::fs::read_dir(rule_dir) else {
+            return Vec::new();
+        };
+        let mut packs: Vec<String> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|path| Phase::all().iter().any(|p| path.join(p.dir_name()).is_dir()))
+            .filter_map(|path| path.file_name().and_then(|n| n.to_str()).map(str::to_string))
+            .collect();
+        packs.sort();
+        packs
+    }
+
+    /// Load the rule pack `pack` from `rule_dir` (see `rule_packs`), or the
+    /// rules directly in `rule_dir` if `pack` is None.
+    pub fn with_pack(rule_dir: &Path, pack: Option<&str>, timeout_ms: u64) -> Result<Self, WorldgroundError> {
+        let Some(pack) = pack else {
+            return RuleEngine::new(rule_dir, timeout_ms);
+        };
+        if is_builtin(rule_dir) {
+            return Err(WorldgroundError::Rules(format!(
+                "The built-in rules have no packs; rule pack '{}' needs a rule directory",
+                pack
+            )));
+        }
+        let packs = RuleEngine::rule_packs(rule_dir);
+        if !packs.iter().any(|p| p == pack) {
+            return Err(WorldgroundError::Rules(format!(
+                "Rule pack '{}' not found in {} (packs: {})",
+                pack,
+                rule_dir.display(),
+                if packs.is_empty() { "none".to_string() } else { packs.join(", ") }
+            )));
+        }
+        let mut engine = RuleEngine::new(&rule_dir.join(pack), timeout_ms)?;
+        engine.pack = Some(pack.to_string());
+        Ok(engine)
+    }
+
+    /// The rule pack the rules came from, if any.
+    pub fn pack(&self) -> Option<&str> {
+        self.pack.as_deref()
+    }
+
+    /// Set the per-evaluation Rhai operation limit (default 100,000) of
+    /// phases without limits of their own.
+    pub fn with_max_operations(mut self, operations: u64) -> Self {
+        self.limits.max_operations = operations;
+        self
+    }
+
+    /// Set the maximum string length rules may build (default 1024).
+    pub fn with_max_string_size(mut self, size: usize) -> Self {
+        self.engine.set_max_string_size(size);
+        self
+    }
+
+    /// Set the maximum array length rules may build (default 1000).
+    pub fn with_max_array_size(mut self, size: usize) -> Self {
+        self.engine.set_max_array_size(size);
+        self
+    }
+
+    /// Set the maximum map size rules may build (default 500).
+    pub fn with_max_map_size(mut self, size: usize) -> Self {
+        self.engine.set_max_map_size(size);
+        self
+    }
+
+    /// Extend the Rhai environment, e.g. register extra host functions for
+    /// rules to call. Functions are resolved when a rule runs, so rules that
+    /// were loaded before registration can use them.
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use worldground::simulation::engine::RuleEngine;
+    /// let engine = RuleEngine::new(Path::new("rules"), 10)
+    ///     .unwrap()
+    ///     .with_max_operations(500_000)
+    ///     .with_host_functions(|rhai| {
+    ///         rhai.register_fn("to_fahrenheit", |k: f64| (k - 273.15) * 1.8 + 32.0);
+    ///     });
+    /// ```
+    pub fn with_host_functions(mut self, register: impl FnOnce(&mut Engine)) -> Self {
+        register(&mut self.engine);
+        self
+    }
+
     /// Enable native evaluation for a phase, bypassing Rhai.
+    ///
+    /// Any phase can run natively, including with a user-provided evaluator:
+    /// the phase's Rhai rules are then skipped while the other phases keep
+    /// running theirs. Registering a second evaluator for the same phase
+    /// replaces the first.
     pub fn register_native_evaluator(&mut self, evaluator: Box<dyn super::native_eval::NativePhaseEvaluator>) {
         let phase = evaluator.phase();
         self.native_evaluators.insert(phase, evaluator);
     }
 
+    /// Builder form of `register_native_evaluator`, for embedders writing
+    /// their own Rust-speed rules.
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use worldground::simulation::engine::{Phase, RuleEngine, TileMutations};
+    /// # use worldground::simulation::native_eval::NativePhaseEvaluator;
+    /// # use worldground::world::{Season, Tile};
+    /// struct Drought;
+    ///
+    /// impl NativePhaseEvaluator for Drought {
+    ///     fn phase(&self) -> Phase {
+    ///         Phase::Conditions
+    ///     }
+    ///
+    ///     fn evaluate(&self, tile: &Tile, _: &[&Tile], _: Season, _: u64, _: u64) -> TileMutations {
+    ///         let dried = tile.conditions.soil_moisture as f64 * 0.9;
+    ///         TileMutations {
+    ///             mutations: vec![("soil_moisture".into(), rhai::Dynamic::from(dried))],
+    ///             ..Default::default()
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let engine = RuleEngine::new(Path::new("rules"), 10).unwrap().with_native_evaluator(Drought);
+    /// ```
+    pub fn with_native_evaluator(
+        mut self,
+        evaluator: impl super::native_eval::NativePhaseEvaluator + 'static,
+    ) -> Self {
+        self.register_native_evaluator(Box::new(evaluator));
+        self
+    }
+
+    /// Stop evaluating a phase natively, so its Rhai rules run again.
+    /// Returns the evaluator that was registered, if any.
+    pub fn remove_native_evaluator(
+        &mut self,
+        phase: Phase,
+    ) -> Option<Box<dyn super::native_eval::NativePhaseEvaluator>> {
+        self.native_evaluators.remove(&phase)
+    }
+
+    /// Phases currently evaluated natively, in execution order.
+    pub fn native_phases(&self) -> Vec<Phase> {
+        Phase::all()
+            .iter()
+            .copied()
+            .filter(|p| self.native_evaluators.contains_key(p))
+            .collect()
+    }
+
+    /// Enable strict mode: `set()` on an unknown or phase-forbidden field becomes a
+    /// rule error instead of being dropped.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Whether strict mode is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Evaluate tiles in batches of `size` (at least 1): each batch shares one
+    /// scope, and batch rules run once per batch instead of once per tile.
+    pub fn set_batch_size(&mut self, size: usize) {
+        self.batch_size = size.max(1);
+    }
+
+    /// Tiles evaluated together per batch (1 unless configured).
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Give one phase's rules their own operation limit and timeout, e.g. a
+    /// larger budget for terrain rules than for weather rules.
+    pub fn set_phase_limits(&mut self, phase: Phase, limits: RuleLimits) {
+        self.phase_limits.insert(phase, limits);
+    }
+
+    /// The budget each rule run in `phase` gets.
+    pub fn limits(&self, phase: Phase) -> RuleLimits {
+        self.phase_limits.get(&phase).copied().unwrap_or(self.limits)
+    }
+
+    /// Run a phase only every `ticks` ticks (at least 1), on the weather and
+    /// conditions gathered meanwhile, e.g. terrain every 10 ticks.
+    pub fn set_phase_period(&mut self, phase: Phase, ticks: u32) {
+        self.phase_periods.insert(phase, ticks.max(1));
+    }
+
+    /// Ticks between runs of `phase` (1 unless configured).
+    pub fn phase_period(&self, phase: Phase) -> u32 {
+        self.phase_periods.get(&phase).copied().unwrap_or(1)
+    }
+
+    /// Weight tick statistics by tile area (see `World::tile_areas`).
+    pub fn set_area_weighted_statistics(&mut self, area_weighted: bool) {
+        self.area_weighted_statistics = area_weighted;
+    }
+
+    /// Whether tick statistics are weighted by tile area.
+    pub fn area_weighted_statistics(&self) -> bool {
+        self.area_weighted_statistics
+    }
+
+    /// Enable or disable the latitude-band thermostat.
+    pub fn set_thermostat(&mut self, thermostat: Option<super::thermostat::Thermostat>) {
+        self.thermostat = thermostat;
+    }
+
+    /// The thermostat applied after the weather phase, if enabled.
+    pub fn thermostat(&self) -> Option<&super::thermostat::Thermostat> {
+        self.thermostat.as_ref()
+    }
+
+    /// Enable or disable skipping rule evaluation on idle tiles.
+    pub fn set_idle_skip(&mut self, idle_skip: Option<super::activity::IdleSkip>) {
+        self.idle_skip = idle_skip;
+    }
+
+    /// How idle tiles are skipped, if they are.
+    pub fn idle_skip(&self) -> Option<&super::activity::IdleSkip> {
+        self.idle_skip.as_ref()
+    }
+
+    /// Enable or disable freeze-thaw weathering.
+    pub fn set_freeze_thaw(&mut self, freeze_thaw: Option<super::freeze_thaw::FreezeThaw>) {
+        self.freeze_thaw = freeze_thaw;
+    }
+
+    /// The freeze-thaw weathering applied after the conditions phase, if enabled.
+    pub fn freeze_thaw(&self) -> Option<&super::freeze_thaw::FreezeThaw> {
+        self.freeze_thaw.as_ref()
+    }
+
+    /// Enable or disable slope erosion and landslides.
+    pub fn set_erosion(&mut self, erosion: Option<super::erosion::Erosion>) {
+        self.erosion = erosion;
+    }
+
+    /// The slope erosion applied after the conditions phase, if enabled.
+    pub fn erosion(&self) -> Option<&super::erosion::Erosion> {
+        self.erosion.as_ref()
+    }
+
+    /// Enable or disable blocking regimes in macro weather.
+    pub fn set_blocking(&mut self, blocking: Option<super::macro_weather::Blocking>) {
+        self.blocking = blocking;
+    }
+
+    /// The blocking regime settings used by macro weather, if enabled.
+    pub fn blocking(&self) -> Option<&super::macro_weather::Blocking> {
+        self.blocking.as_ref()
+    }
+
+    /// Enable or disable the cosmetic aurora driver.
+    pub fn set_aurora(&mut self, aurora: Option<super::aurora::Aurora>) {
+        self.aurora = aurora;
+    }
+
+    /// The aurora driver run after macro weather, if enabled.
+    pub fn aurora(&self) -> Option<&super::aurora::Aurora> {
+        self.aurora.as_ref()
+    }
+
+    /// Enable or disable dust and volcanic ash transport.
+    pub fn set_dust(&mut self, dust: Option<super::dust::Dust>) {
+        self.dust = dust;
+    }
+
+    /// The dust transport applied after the weather phase, if enabled.
+    pub fn dust(&self) -> Option<&super::dust::Dust> {
+        self.dust.as_ref()
+    }
+
+    /// Enable or disable ocean salinity and the overturning circulation.
+    pub fn set_ocean(&mut self, ocean: Option<super::ocean::Ocean>) {
+        self.ocean = ocean;
+    }
+
+    /// The ocean circulation applied after the weather phase, if enabled.
+    pub fn ocean(&self) -> Option<&super::ocean::Ocean> {
+        self.ocean.as_ref()
+    }
+
+    /// Enable or disable wind waves.
+    pub fn set_waves(&mut self, waves: Option<super::waves::Waves>) {
+        self.waves = waves;
+    }
+
+    /// The wave step applied after the weather phase, if enabled.
+    pub fn waves(&self) -> Option<&super::waves::Waves> {
+        self.waves.as_ref()
+    }
+
+    /// Enable or disable glaciers.
+    pub fn set_glaciers(&mut self, glaciers: Option<super::glacier::Glaciers>) {
+        self.glaciers = glaciers;
+    }
+
+    /// The glacier step applied before hydrology, if enabled.
+    pub fn glaciers(&self) -> Option<&super::glacier::Glaciers> {
+        self.glaciers.as_ref()
+    }
+
+    /// Enable or disable the moving sea level.
+    pub fn set_sea_level(&mut self, sea_level: Option<super::sea_level::SeaLevel>) {
+        self.sea_level = sea_level;
+    }
+
+    /// The sea level step applied at the end of terrain, if enabled.
+    pub fn sea_level(&self) -> Option<&super::sea_level::SeaLevel> {
+        self.sea_level.as_ref()
+    }
+
+    /// Enable or disable the rule profiler.
+    pub fn set_profiler(&mut self, profiler: Option<super::profiler::Profiler>) {
+        self.profiler = profiler;
+    }
+
+    /// The rule profiler, if enabled.
+    pub fn profiler(&self) -> Option<&super::profiler::Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Enable or disable paging deposits out between resources phases.
+    pub fn set_paging(&mut self, paging: Option<crate::world::paging::PageFile>) {
+        self.paging = paging;
+    }
+
+    /// The page file deposits are paged out to, if enabled.
+    pub fn paging(&self) -> Option<&crate::world::paging::PageFile> {
+        self.paging.as_ref()
+    }
+
+    /// Replace the globals rules see: the `globals` map, normally with the
+    /// previous tick's values, and what `global_get()` reads, normally with
+    /// the contributions of the tick's phases run so far.
+    pub fn set_globals(&self, previous: &BTreeMap<String, f64>, tick: &GlobalAccumulator) {
+        let previous = previous
+            .iter()
+            .map(|(k, &v)| (k.as_str().into(), Dynamic::from(v)))
+            .collect();
+        *self.globals.write().unwrap() = RuleGlobals { previous, tick: tick.totals() };
+    }
+
     /// Check if a native evaluator is registered for a phase.
     pub fn has_native_evaluator(&self, phase: Phase) -> bool {
         self.native_evaluators.contains_key(&phase)
@@ -246,56 +1165,102 @@ impl RuleEngine {
         self.native_evaluators.get(&phase).map(|e| e.as_ref())
     }
 
-    fn load_rules(&mut self, rule_dir: &Path) -> Result<(), String> {
+    /// Compile every rule in the rule directory, by phase, read the
+    /// parameters in its `rules.toml`, and hash their names and sources.
+    fn compile_rules(&self) -> Result<CompiledRules, WorldgroundError> {
+        let mut rules = HashMap::new();
+        let mut hash = Fnv::default();
+        let (params, params_source) = super::rule_params::load(&self.rule_dir).map_err(WorldgroundError::Rules)?;
+        if let Some(source) = params_source {
+            hash.write(format!("{}\0", super::rule_params::RULE_PARAMS_FILE).as_bytes());
+            hash.write(source.as_bytes());
+            hash.write_u8(0);
+        }
         for phase in Phase::all() {
-            let phase_dir = rule_dir.join(phase.dir_name());
             let mut phase_rules = Vec::new();
 
-            if !phase_dir.exists() {
-                // Empty phase directory is OK — phase becomes no-op
-                self.rules.insert(*phase, phase_rules);
-                continue;
-            }
-
-            let mut entries: Vec<_> = std::fs::read_dir(&phase_dir)
-                .map_err(|e| format!("Cannot read {}: {}", phase_dir.display(), e))?
-                .filter_map(|e| e.ok())
-                .filter(|e| {
-                    e.path()
-                        .extension()
-                        .is_some_and(|ext| ext == "rhai")
-                })
-                .collect();
-
-            // Sort by filename for deterministic execution order
-            entries.sort_by_key(|e| e.file_name());
-
-            for entry in entries {
-                let path = entry.path();
+            // Sorted by filename for deterministic execution order; a missing
+            // phase directory is OK, the phase becomes a no-op
+            for (path, source) in phase_rule_sources(&self.rule_dir, *phase).map_err(WorldgroundError::Rules)? {
                 let name = path
                     .file_name()
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown")
                     .to_string();
 
-                let source = std::fs::read_to_string(&path)
-                    .map_err(|e| format!("Cannot read rule {}: {}", path.display(), e))?;
-
-                let ast = self.engine.compile(&source).map_err(|e| {
-                    format!("Syntax error in {}: {}", path.display(), e)
-                })?;
-
+                hash.write(format!("{}/{}\0", phase.dir_name(), name).as_bytes());
+                hash.write(source.as_bytes());
+                hash.write_u8(0);
+
+                let ast = self
+                    .engine
+                    .compile(&source)
+                    .map_err(|source| WorldgroundError::RuleSyntax { path: path.clone(), source })?;
+
+                // A reloaded rule keeps counting where it left off
+                let counters = self
+                    .rules_for_phase(*phase)
+                    .iter()
+                    .find(|r| r.name == name)
+                    .map_or_else(Default::default, |r| Arc::clone(&r.counters));
                 phase_rules.push(CompiledRule {
+                    batch: name.ends_with(BATCH_RULE_SUFFIX),
                     name,
                     phase: *phase,
                     ast,
+                    counters,
                 });
             }
 
-            self.rules.insert(*phase, phase_rules);
+            rules.insert(*phase, phase_rules);
         }
 
-        Ok(())
+        Ok((rules, Dynamic::from(params), hash.finish()))
+    }
+
+    /// Recompile the rules from the directory they were loaded from, so edits
+    /// take effect without a restart. Returns the new rule count. If any rule
+    /// fails to read or compile, or in strict mode sets a field its phase
+    /// can't write, the rules already loaded stay in place.
+    pub fn reload_rules(&mut self) -> Result<usize, WorldgroundError> {
+        let (rules, params, rules_hash) = self.compile_rules()?;
+        let unwritable = unwritable_set_calls(&self.rule_dir, &rules);
+        if self.strict && !unwritable.is_empty() {
+            return Err(WorldgroundError::Rules(
+                unwritable.iter().map(SetCall::unwritable_message).collect::<Vec<_>>().join("\n"),
+            ));
+        }
+        for call in &unwritable {
+            warn!("{}", call.unwritable_message());
+        }
+        self.rules = rules;
+        self.params = params;
+        self.rules_hash = rules_hash;
+        Ok(self.rule_count())
+    }
+
+    /// `set()` calls in the loaded rules whose literal field name their
+    /// phase can't write, found by walking each script's AST. These would
+    /// be dropped (or, in strict mode, fail the rule) on every tick.
+    pub fn unwritable_set_calls(&self) -> Vec<SetCall> {
+        unwritable_set_calls(&self.rule_dir, &self.rules)
+    }
+
+    /// Directory the rules are loaded from.
+    pub fn rule_dir(&self) -> &Path {
+        &self.rule_dir
+    }
+
+    /// Hash of the loaded rules' file names and sources, and of `rules.toml`:
+    /// the same rules hash the same wherever they are loaded from, the
+    /// built-in pack too.
+    pub fn rules_hash(&self) -> u64 {
+        self.rules_hash
+    }
+
+    /// Number of top-level parameters declared in `rules.toml`.
+    pub fn param_count(&self) -> usize {
+        self.params.read_lock::<Map>().map_or(0, |params| params.len())
     }
 
     /// Get the rules for a specific phase.
@@ -303,6 +1268,25 @@ impl RuleEngine {
         self.rules.get(&phase).map(|v| v.as_slice()).unwrap_or(&[])
     }
 
+    /// Execution metrics of the loaded rules since they were first loaded.
+    /// Rules that were removed by a reload are no longer listed.
+    pub fn rule_stats(&self) -> RuleStats {
+        let rules = Phase::all()
+            .iter()
+            .flat_map(|phase| self.rules_for_phase(*phase))
+            .map(|rule| {
+                let c = &rule.counters;
+                let metrics = RuleMetrics {
+                    invocations: c.invocations.load(Ordering::Relaxed),
+                    errors: c.errors.load(Ordering::Relaxed),
+                    time: std::time::Duration::from_nanos(c.nanos.load(Ordering::Relaxed)),
+                };
+                (format!("{}/{}", rule.phase.dir_name(), rule.name), metrics)
+            })
+            .collect();
+        RuleStats { rules }
+    }
+
     /// Get total rule count across all phases.
     pub fn rule_count(&self) -> usize {
         self.rules.values().map(|v| v.len()).sum()
@@ -321,77 +1305,9 @@ impl RuleEngine {
         tick: u64,
         rng_seed: u64,
     ) -> Result<TileMutations, RuleError> {
-        let rules = self.rules_for_phase(phase);
-        if rules.is_empty() {
-            return Ok(TileMutations::default());
-        }
-
-        // Build the scope with tile data exposed as Rhai maps
         let tile_map = tile_to_rhai_map(tile);
-        let neighbors_map: Vec<Dynamic> = neighbors.iter().map(|n| tile_to_rhai_map(n)).collect();
-
-        let season_str = match season {
-            Season::Spring => "Spring",
-            Season::Summer => "Summer",
-            Season::Autumn => "Autumn",
-            Season::Winter => "Winter",
-        };
-
-        // Clear thread-local mutations and logs
-        MUTATIONS.with(|m| m.borrow_mut().clear());
-        LOG_MESSAGES.with(|l| l.borrow_mut().clear());
-
-        // Set up the RNG thread-local
-        RNG_STATE.with(|r| r.set(rng_seed));
-
-        for rule in rules {
-            let mut scope = Scope::new();
-            scope.push("tile", tile_map.clone());
-            scope.push("neighbors", neighbors_map.clone());
-            scope.push_constant("season", season_str.to_string());
-            scope.push_constant("tick", tick as i64);
-
-            // Use the main engine (which has set/log/rand registered and operation limits)
-            // The on_progress callback provides wall-clock timeout
-            let start_time = Instant::now();
-            let timeout = self.timeout_ms;
-
-            // Create a scoped engine for this evaluation with timeout
-            let result = {
-                // We use the pre-compiled AST with the main engine
-                // Rhai ASTs are portable between compatible engines
-                self.engine.run_ast_with_scope(&mut scope, &rule.ast)
-            };
-
-            // Collect any log messages
-            LOG_MESSAGES.with(|l| {
-                for msg in l.borrow().iter() {
-                    debug!(rule = %rule.name, tile_id = tile.id, "{}", msg);
-                }
-                l.borrow_mut().clear();
-            });
-
-            if let Err(e) = result {
-                // Discard all mutations from this tile (error isolation)
-                MUTATIONS.with(|m| m.borrow_mut().clear());
-                return Err(RuleError {
-                    tile_id: tile.id,
-                    rule_name: rule.name.clone(),
-                    error: e.to_string(),
-                });
-            }
-
-            let _ = (start_time, timeout); // used by on_progress if we add it later
-        }
-
-        // Collect all mutations
-        let mutations = MUTATIONS.with(|m| {
-            let muts = m.borrow().clone();
-            m.borrow_mut().clear();
-            TileMutations { mutations: muts }
-        });
-
-        Ok(mutations)
+        let neighbor_maps = neighbors.iter().map(|n| tile_to_rhai_map(n)).collect();
+        self.evaluate_tile_preconverted(phase, &tile_map, neighbor_maps, season, tick, rng_seed, tile.id)
     }
 
     /// Evaluate all rules for a phase on a single tile using pre-converted Rhai maps.
@@ -408,9 +1324,31 @@ impl RuleEngine {
         rng_seed: u64,
         tile_id: u32,
     ) -> Result<TileMutations, RuleError> {
+        let tile = BatchTile { tile_map, neighbor_maps, rng_seed, tile_id };
+        self.evaluate_batch(phase, &[tile], season, tick)
+            .pop()
+            .unwrap_or_else(|| Ok(TileMutations::default()))
+    }
+
+    /// Evaluate all rules for a phase on a batch of tiles, returning each
+    /// tile's result in order.
+    ///
+    /// Ordinary rules run once per tile, against one scope set up for the
+    /// whole batch, with the same per-tile `rand()` streams as when tiles are
+    /// evaluated one at a time, so results don't depend on the batch size.
+    /// Batch rules run once, reading the batch with `batch_tile(i)` and
+    /// `batch_neighbors(i)` and pointing their output at a tile with
+    /// `select(i)`; a batch rule error fails every tile of the batch.
+    pub fn evaluate_batch(
+        &self,
+        phase: Phase,
+        tiles: &[BatchTile],
+        season: &Season,
+        tick: u64,
+    ) -> Vec<Result<TileMutations, RuleError>> {
         let rules = self.rules_for_phase(phase);
         if rules.is_empty() {
-            return Ok(TileMutations::default());
+            return tiles.iter().map(|_| Ok(TileMutations::default())).collect();
         }
 
         let season_str = match season {
@@ -421,54 +1359,272 @@ impl RuleEngine {
         };
 
         MUTATIONS.with(|m| m.borrow_mut().clear());
+        CONTRIBUTIONS.with(|c| c.take());
+        NEIGHBOR_WRITES.with(|w| w.borrow_mut().clear());
+        EVENTS.with(|e| e.borrow_mut().clear());
         LOG_MESSAGES.with(|l| l.borrow_mut().clear());
-        RNG_STATE.with(|r| r.set(rng_seed));
-
-        for rule in rules {
-            let mut scope = Scope::new();
-            scope.push_constant("tile", tile_map.clone());
-            scope.push_constant("neighbors", neighbor_maps.clone());
-            scope.push_constant("season", season_str.to_string());
-            scope.push_constant("tick", tick as i64);
+        RNG_STATE.with(|r| r.set(tiles.first().map_or(0, |t| t.rng_seed)));
+        BATCH_FOCUS.with(|f| f.set(0));
+        let has_batch_rules = rules.iter().any(|r| r.batch);
+        BATCH.with(|b| {
+            *b.borrow_mut() = tiles
+                .iter()
+                .map(|t| BatchSlot {
+                    rng: t.rng_seed,
+                    tile: if has_batch_rules { t.tile_map.clone() } else { Dynamic::UNIT },
+                    neighbors: if has_batch_rules { t.neighbor_maps.clone() } else { Array::new() },
+                    ..Default::default()
+                })
+                .collect();
+        });
 
-            let result = self.engine.run_ast_with_scope(&mut scope, &rule.ast);
+        // Set up once per batch; each rule run rewinds back to these
+        let mut scope = Scope::new();
+        scope.push_constant("season", season_str.to_string());
+        scope.push_constant("tick", tick as i64);
+        scope.push_constant("globals", Dynamic::from(self.globals.read().unwrap().previous.clone()));
+        scope.push_constant("params", self.params.clone());
+        let base = scope.len();
+        let limits = self.limits(phase);
+        let sampled: Vec<bool> = tiles
+            .iter()
+            .map(|t| self.profiler.as_ref().is_some_and(|p| p.samples(tick, t.tile_id)))
+            .collect();
+        let mut timings: Vec<Vec<(String, std::time::Duration)>> = vec![Vec::new(); tiles.len()];
+        let mut rule_indices: Vec<Vec<usize>> = vec![Vec::new(); tiles.len()];
+        let mut failures: Vec<Option<RuleError>> = vec![None; tiles.len()];
+        for (rule_idx, rule) in rules.iter().enumerate() {
+            CURRENT_RULE.with(|r| r.set(rule_idx));
+            if rule.batch {
+                if failures.iter().all(Option::is_some) {
+                    break;
+                }
+                focus_batch_tile(0);
+                scope.rewind(base);
+
+                let start = Instant::now();
+                limits.start(start);
+                let result = self.engine.run_ast_with_scope(&mut scope, &rule.ast);
+                let elapsed = start.elapsed();
+                rule.counters.record(elapsed, result.is_err());
+                self.flush_log(rule, tiles[0].tile_id);
+
+                for (j, tile) in tiles.iter().enumerate() {
+                    if failures[j].is_some() {
+                        continue;
+                    }
+                    if let Err(e) = &result {
+                        failures[j] = Some(RuleError {
+                            tile_id: tile.tile_id,
+                            rule_name: rule.name.clone(),
+                            error: rule_error_message(e),
+                        });
+                        continue;
+                    }
+                    focus_batch_tile(j);
+                    rule_indices[j].resize(MUTATIONS.with(|m| m.borrow().len()), rule_idx);
+                    if sampled[j] {
+                        timings[j].push((super::profiler::phase_stack(phase, &rule.name), elapsed / tiles.len() as u32));
+                    }
+                }
+                continue;
+            }
 
-            LOG_MESSAGES.with(|l| {
-                for msg in l.borrow().iter() {
-                    debug!(rule = %rule.name, tile_id, "{}", msg);
+            for (j, tile) in tiles.iter().enumerate() {
+                if failures[j].is_some() {
+                    continue;
                 }
-                l.borrow_mut().clear();
-            });
+                focus_batch_tile(j);
+                scope.rewind(base);
+                scope.push_constant("tile", tile.tile_map.clone());
+                scope.push_constant("neighbors", tile.neighbor_maps.clone());
+
+                let start = Instant::now();
+                limits.start(start);
+                let result = self.engine.run_ast_with_scope(&mut scope, &rule.ast);
+                let elapsed = start.elapsed();
+                rule.counters.record(elapsed, result.is_err());
+                if sampled[j] {
+                    timings[j].push((super::profiler::phase_stack(phase, &rule.name), elapsed));
+                }
+                self.flush_log(rule, tile.tile_id);
+
+                match result {
+                    Ok(_) => rule_indices[j].resize(MUTATIONS.with(|m| m.borrow().len()), rule_idx),
+                    Err(e) => {
+                        failures[j] = Some(RuleError {
+                            tile_id: tile.tile_id,
+                            rule_name: rule.name.clone(),
+                            error: rule_error_message(&e),
+                        });
+                    }
+                }
+            }
+        }
+        // Put the focused tile's output back in its slot with the others
+        swap_batch_slot(BATCH_FOCUS.with(|f| f.get()));
 
-            if let Err(e) = result {
-                MUTATIONS.with(|m| m.borrow_mut().clear());
-                return Err(RuleError {
-                    tile_id,
-                    rule_name: rule.name.clone(),
-                    error: e.to_string(),
-                });
+        if let Some(profiler) = &self.profiler {
+            for (j, timings) in timings.into_iter().enumerate() {
+                if sampled[j] {
+                    profiler.record_sample(timings);
+                }
             }
         }
 
-        let mutations = MUTATIONS.with(|m| {
-            let muts = m.borrow().clone();
-            m.borrow_mut().clear();
-            TileMutations { mutations: muts }
+        // Failed tiles lose everything their rules emitted (error isolation)
+        let slots = BATCH.with(|b| std::mem::take(&mut *b.borrow_mut()));
+        MUTATIONS.with(|m| m.borrow_mut().clear());
+        CONTRIBUTIONS.with(|c| c.take());
+        NEIGHBOR_WRITES.with(|w| w.borrow_mut().clear());
+        EVENTS.with(|e| e.borrow_mut().clear());
+        slots
+            .into_iter()
+            .zip(rule_indices)
+            .zip(failures)
+            .map(|((slot, rule_indices), failure)| match failure {
+                Some(err) => Err(err),
+                None => Ok(TileMutations {
+                    mutations: slot.mutations,
+                    rule_indices,
+                    contributions: slot.contributions,
+                    neighbor_writes: slot.neighbor_writes,
+                    events: slot.events,
+                }),
+            })
+            .collect()
+    }
+
+    /// Log what a rule passed to `log()`, then forget it.
+    fn flush_log(&self, rule: &CompiledRule, tile_id: u32) {
+        LOG_MESSAGES.with(|l| {
+            for msg in l.borrow().iter() {
+                debug!(rule = %rule.name, tile_id, "{}", msg);
+            }
+            l.borrow_mut().clear();
         });
+    }
+}
+
+/// Operations each rule run may take unless configured otherwise.
+pub const DEFAULT_MAX_OPERATIONS: u64 = 100_000;
+
+/// Operations between checks of a rule's deadline.
+const DEADLINE_CHECK_OPERATIONS: u64 = 256;
+
+/// How much work one rule run may do: a run that goes over either limit
+/// fails with a rule error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleLimits {
+    /// Rhai operations per run
+    pub max_operations: u64,
+    /// Wall-clock time per run in milliseconds; 0 means no time limit
+    pub timeout_ms: u64,
+}
+
+impl RuleLimits {
+    /// Start the budget of a rule run beginning at `start` on this thread.
+    fn start(&self, start: Instant) {
+        let deadline = (self.timeout_ms > 0).then(|| (start, std::time::Duration::from_millis(self.timeout_ms)));
+        BUDGET.with(|b| b.set(Budget { max_operations: self.max_operations, deadline }));
+    }
+}
+
+/// The budget of the rule run in progress on this thread.
+#[derive(Clone, Copy)]
+struct Budget {
+    max_operations: u64,
+    /// When the run started and how long it may take
+    deadline: Option<(Instant, std::time::Duration)>,
+}
+
+/// A rule's error as shown to users; a run stopped for going over its
+/// budget says which limit it hit.
+fn rule_error_message(error: &EvalAltResult) -> String {
+    match error.unwrap_inner() {
+        EvalAltResult::ErrorTerminated(reason, pos) if pos.is_none() => reason.to_string(),
+        EvalAltResult::ErrorTerminated(reason, pos) => format!("{} ({})", reason, pos),
+        _ => error.to_string(),
+    }
+}
+
+/// `i` as an index into the batch being evaluated, or an error naming `function`.
+fn batch_index(function: &str, i: i64) -> Result<usize, Box<EvalAltResult>> {
+    let len = BATCH.with(|b| b.borrow().len());
+    usize::try_from(i)
+        .ok()
+        .filter(|&i| i < len)
+        .ok_or_else(|| format!("{}({}): no such tile in this batch of {}", function, i, len).into())
+}
+
+/// Rule files with this suffix are batch rules.
+pub const BATCH_RULE_SUFFIX: &str = ".batch.rhai";
+
+/// One tile of a batch, as Rhai maps built from the phase's snapshot.
+pub struct BatchTile<'a> {
+    pub tile_map: &'a Dynamic,
+    pub neighbor_maps: Vec<Dynamic>,
+    pub rng_seed: u64,
+    pub tile_id: u32,
+}
+
+/// What one tile of the batch being evaluated has emitted so far, set
+/// aside while another tile has the focus.
+#[derive(Default)]
+struct BatchSlot {
+    mutations: Vec<(String, Dynamic)>,
+    contributions: GlobalAccumulator,
+    neighbor_writes: Vec<NeighborWrite>,
+    events: Vec<EmittedEvent>,
+    rng: u64,
+    /// The tile and its neighbors as batch rules see them; only filled in
+    /// when the phase has batch rules
+    tile: Dynamic,
+    neighbors: Array,
+}
 
-        Ok(mutations)
+/// Point `set`, `set_neighbor`, `contribute*`, `emit_event` and `rand` at tile `i` of the batch: the
+/// focused tile's slot is swapped with the thread-locals those calls use.
+fn focus_batch_tile(i: usize) {
+    let current = BATCH_FOCUS.with(|f| f.get());
+    if i != current && BATCH.with(|b| i < b.borrow().len()) {
+        swap_batch_slot(current);
+        swap_batch_slot(i);
+        BATCH_FOCUS.with(|f| f.set(i));
     }
 }
 
+/// Swap slot `i` with the thread-locals.
+fn swap_batch_slot(i: usize) {
+    BATCH.with(|b| {
+        if let Some(slot) = b.borrow_mut().get_mut(i) {
+            MUTATIONS.with(|m| std::mem::swap(&mut *m.borrow_mut(), &mut slot.mutations));
+            CONTRIBUTIONS.with(|c| std::mem::swap(&mut *c.borrow_mut(), &mut slot.contributions));
+            NEIGHBOR_WRITES.with(|w| std::mem::swap(&mut *w.borrow_mut(), &mut slot.neighbor_writes));
+            EVENTS.with(|e| std::mem::swap(&mut *e.borrow_mut(), &mut slot.events));
+            RNG_STATE.with(|r| slot.rng = r.replace(slot.rng));
+        }
+    });
+}
+
 // Thread-local storage for collecting mutations during rule execution
 thread_local! {
     static MUTATIONS: RefCell<Vec<(String, Dynamic)>> = RefCell::new(Vec::new());
+    static CONTRIBUTIONS: RefCell<GlobalAccumulator> = RefCell::new(GlobalAccumulator::default());
+    static NEIGHBOR_WRITES: RefCell<Vec<NeighborWrite>> = const { RefCell::new(Vec::new()) };
+    static EVENTS: RefCell<Vec<EmittedEvent>> = const { RefCell::new(Vec::new()) };
+    static CURRENT_RULE: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
     static LOG_MESSAGES: RefCell<Vec<String>> = RefCell::new(Vec::new());
     static RNG_STATE: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    static BATCH: RefCell<Vec<BatchSlot>> = const { RefCell::new(Vec::new()) };
+    static BATCH_FOCUS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    static BUDGET: std::cell::Cell<Budget> = const {
+        std::cell::Cell::new(Budget { max_operations: DEFAULT_MAX_OPERATIONS, deadline: None })
+    };
 }
 
 /// Simple xorshift64 PRNG for deterministic random numbers in rules.
-fn xorshift64(mut state: u64) -> u64 {
+pub(crate) fn xorshift64(mut state: u64) -> u64 {
     if state == 0 {
         state = 1;
     }
@@ -488,6 +1644,35 @@ fn get_nested_f64(dyn_val: &Dynamic, path: &str) -> Option<f64> {
     field_val.as_float().ok()
 }
 
+/// `native_weather::orographic_factor` for a tile and its neighbors as Rhai
+/// maps. Bearings come from latitude and longitude; on flat worlds, which
+/// have neither, it falls back to the terrain-type factor.
+fn orographic_factor_from_maps(tile: &Dynamic, neighbors: &Array) -> f64 {
+    use super::native_weather::{orographic_factor, terrain_orographic_factor};
+    use super::sphere_math::{direction_on_sphere, tangent_to_bearing};
+
+    let f = |map: &Dynamic, path: &str| get_nested_f64(map, path).unwrap_or(0.0);
+    let (lat, lon) = (f(tile, "position.lat"), f(tile, "position.lon"));
+    let has_geo = lat != 0.0
+        || lon != 0.0
+        || neighbors.iter().any(|n| f(n, "position.lat") != 0.0 || f(n, "position.lon") != 0.0);
+    if !has_geo {
+        let terrain = tile
+            .read_lock::<Map>()
+            .and_then(|m| m.get("geology")?.read_lock::<Map>()?.get("terrain_type")?.clone().into_string().ok());
+        return terrain_orographic_factor(terrain.as_deref().unwrap_or(""));
+    }
+    orographic_factor(
+        f(tile, "geology.elevation"),
+        f(tile, "weather.wind_direction"),
+        f(tile, "weather.wind_speed"),
+        neighbors.iter().map(|n| {
+            let (east, north) = direction_on_sphere(f(n, "position.lat"), f(n, "position.lon"), lat, lon);
+            (tangent_to_bearing(east, north), f(n, "geology.elevation"))
+        }),
+    )
+}
+
 /// Static string for TerrainType (avoids format!("{:?}") allocation).
 pub fn terrain_type_str(t: TerrainType) -> &'static str {
     match t {
@@ -551,6 +1736,16 @@ pub fn precipitation_type_str(p: PrecipitationType) -> &'static str {
     }
 }
 
+/// Rule scratch variables as a Rhai map of floats.
+fn scratch_rhai_map(tile: &Tile) -> Dynamic {
+    let scratch: Map = tile
+        .scratch
+        .iter()
+        .map(|(k, &v)| (k.as_str().into(), Dynamic::from(v)))
+        .collect();
+    Dynamic::from(scratch)
+}
+
 /// Convert a Tile to a Rhai Map for script access.
 pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
     let mut map = Map::new();
@@ -561,6 +1756,8 @@ pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
     let mut pos = Map::new();
     pos.insert("x".into(), Dynamic::from(tile.position.x as f64));
     pos.insert("y".into(), Dynamic::from(tile.position.y as f64));
+    pos.insert("lat".into(), Dynamic::from(tile.position.lat));
+    pos.insert("lon".into(), Dynamic::from(tile.position.lon));
     map.insert("position".into(), Dynamic::from(pos));
 
     // Geology layer
@@ -579,6 +1776,7 @@ pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
         "tectonic_stress".into(),
         Dynamic::from(tile.geology.tectonic_stress as f64),
     );
+    geo.insert("sediment".into(), Dynamic::from(tile.geology.sediment as f64));
     map.insert("geology".into(), Dynamic::from(geo));
 
     // Climate layer
@@ -620,6 +1818,10 @@ pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
         "ticks_in_current_biome".into(),
         Dynamic::from(tile.biome.ticks_in_current_biome as i64),
     );
+    biome.insert(
+        "root_strength".into(),
+        Dynamic::from(tile.biome.root_strength as f64),
+    );
     map.insert("biome".into(), Dynamic::from(biome));
 
     // Weather layer
@@ -660,6 +1862,10 @@ pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
         "pressure".into(),
         Dynamic::from(tile.weather.pressure as f64),
     );
+    weather.insert(
+        "station_pressure".into(),
+        Dynamic::from(tile.weather.station_pressure as f64),
+    );
     weather.insert(
         "macro_wind_speed".into(),
         Dynamic::from(tile.weather.macro_wind_speed as f64),
@@ -704,6 +1910,43 @@ pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
         "fire_risk".into(),
         Dynamic::from(tile.conditions.fire_risk as f64),
     );
+    conditions.insert(
+        "freeze_thaw_cycles".into(),
+        Dynamic::from(tile.conditions.freeze_thaw_cycles as i64),
+    );
+    conditions.insert(
+        "trafficability".into(),
+        Dynamic::from(tile.conditions.trafficability as f64),
+    );
+    conditions.insert(
+        "silt_fertility".into(),
+        Dynamic::from(tile.conditions.silt_fertility as f64),
+    );
+    conditions.insert("pet".into(), Dynamic::from(tile.conditions.pet as f64));
+    conditions.insert(
+        "aridity_index".into(),
+        Dynamic::from(tile.conditions.aridity_index as f64),
+    );
+    conditions.insert("fog".into(), Dynamic::from(tile.conditions.fog as f64));
+    conditions.insert(
+        "visibility_km".into(),
+        Dynamic::from(tile.conditions.visibility_km as f64),
+    );
+    conditions.insert(
+        "carrying_capacity".into(),
+        Dynamic::from(tile.conditions.carrying_capacity as f64),
+    );
+    conditions.insert(
+        "river_flow".into(),
+        Dynamic::from(tile.conditions.river_flow as f64),
+    );
+    conditions.insert(
+        "water_table".into(),
+        Dynamic::from(tile.conditions.water_table as f64),
+    );
+    conditions.insert("salinity".into(), Dynamic::from(tile.conditions.salinity as f64));
+    conditions.insert("glacier_mass".into(), Dynamic::from(tile.conditions.glacier_mass as f64));
+    conditions.insert("wave_height".into(), Dynamic::from(tile.conditions.wave_height as f64));
     map.insert("conditions".into(), Dynamic::from(conditions));
 
     // Resources (simplified — count and list)
@@ -717,11 +1960,15 @@ pub fn tile_to_rhai_map(tile: &Tile) -> Dynamic {
             rm.insert("quantity".into(), Dynamic::from(r.quantity as f64));
             rm.insert("max_quantity".into(), Dynamic::from(r.max_quantity as f64));
             rm.insert("renewal_rate".into(), Dynamic::from(r.renewal_rate as f64));
+            rm.insert("quality".into(), Dynamic::from(r.quality as f64));
+            rm.insert("discovered".into(), Dynamic::from(r.discovered));
             Dynamic::from(rm)
         })
         .collect();
     map.insert("resources".into(), Dynamic::from(res_list));
 
+    map.insert("scratch".into(), scratch_rhai_map(tile));
+
     // Neighbor IDs
     let neighbor_ids: Vec<Dynamic> = tile.neighbors.iter().map(|&n| Dynamic::from(n as i64)).collect();
     map.insert("neighbor_ids".into(), Dynamic::from(neighbor_ids));
@@ -740,6 +1987,8 @@ pub fn tile_immutable_rhai_map(tile: &Tile) -> Map {
     let mut pos = Map::new();
     pos.insert("x".into(), Dynamic::from(tile.position.x as f64));
     pos.insert("y".into(), Dynamic::from(tile.position.y as f64));
+    pos.insert("lat".into(), Dynamic::from(tile.position.lat));
+    pos.insert("lon".into(), Dynamic::from(tile.position.lon));
     map.insert("position".into(), Dynamic::from(pos));
 
     // Geology layer
@@ -758,6 +2007,7 @@ pub fn tile_immutable_rhai_map(tile: &Tile) -> Map {
         "tectonic_stress".into(),
         Dynamic::from(tile.geology.tectonic_stress as f64),
     );
+    geo.insert("sediment".into(), Dynamic::from(tile.geology.sediment as f64));
     map.insert("geology".into(), Dynamic::from(geo));
 
     // Climate layer
@@ -811,6 +2061,10 @@ pub fn tile_mutable_rhai_map(base: &Map, tile: &Tile, phase: Phase) -> Dynamic {
         "ticks_in_current_biome".into(),
         Dynamic::from(tile.biome.ticks_in_current_biome as i64),
     );
+    biome.insert(
+        "root_strength".into(),
+        Dynamic::from(tile.biome.root_strength as f64),
+    );
     map.insert("biome".into(), Dynamic::from(biome));
 
     // Weather layer
@@ -827,6 +2081,7 @@ pub fn tile_mutable_rhai_map(base: &Map, tile: &Tile, phase: Phase) -> Dynamic {
     weather.insert("humidity".into(), Dynamic::from(tile.weather.humidity as f64));
     weather.insert("storm_intensity".into(), Dynamic::from(tile.weather.storm_intensity as f64));
     weather.insert("pressure".into(), Dynamic::from(tile.weather.pressure as f64));
+    weather.insert("station_pressure".into(), Dynamic::from(tile.weather.station_pressure as f64));
     weather.insert("macro_wind_speed".into(), Dynamic::from(tile.weather.macro_wind_speed as f64));
     weather.insert("macro_wind_direction".into(), Dynamic::from(tile.weather.macro_wind_direction as f64));
     weather.insert("macro_humidity".into(), Dynamic::from(tile.weather.macro_humidity as f64));
@@ -841,6 +2096,43 @@ pub fn tile_mutable_rhai_map(base: &Map, tile: &Tile, phase: Phase) -> Dynamic {
     conditions.insert("frost_days".into(), Dynamic::from(tile.conditions.frost_days as i64));
     conditions.insert("drought_days".into(), Dynamic::from(tile.conditions.drought_days as i64));
     conditions.insert("fire_risk".into(), Dynamic::from(tile.conditions.fire_risk as f64));
+    conditions.insert(
+        "freeze_thaw_cycles".into(),
+        Dynamic::from(tile.conditions.freeze_thaw_cycles as i64),
+    );
+    conditions.insert(
+        "trafficability".into(),
+        Dynamic::from(tile.conditions.trafficability as f64),
+    );
+    conditions.insert(
+        "silt_fertility".into(),
+        Dynamic::from(tile.conditions.silt_fertility as f64),
+    );
+    conditions.insert("pet".into(), Dynamic::from(tile.conditions.pet as f64));
+    conditions.insert(
+        "aridity_index".into(),
+        Dynamic::from(tile.conditions.aridity_index as f64),
+    );
+    conditions.insert("fog".into(), Dynamic::from(tile.conditions.fog as f64));
+    conditions.insert(
+        "visibility_km".into(),
+        Dynamic::from(tile.conditions.visibility_km as f64),
+    );
+    conditions.insert(
+        "carrying_capacity".into(),
+        Dynamic::from(tile.conditions.carrying_capacity as f64),
+    );
+    conditions.insert(
+        "river_flow".into(),
+        Dynamic::from(tile.conditions.river_flow as f64),
+    );
+    conditions.insert(
+        "water_table".into(),
+        Dynamic::from(tile.conditions.water_table as f64),
+    );
+    conditions.insert("salinity".into(), Dynamic::from(tile.conditions.salinity as f64));
+    conditions.insert("glacier_mass".into(), Dynamic::from(tile.conditions.glacier_mass as f64));
+    conditions.insert("wave_height".into(), Dynamic::from(tile.conditions.wave_height as f64));
     map.insert("conditions".into(), Dynamic::from(conditions));
 
     // Resources: only build for Resources phase
@@ -855,6 +2147,8 @@ pub fn tile_mutable_rhai_map(base: &Map, tile: &Tile, phase: Phase) -> Dynamic {
                 rm.insert("quantity".into(), Dynamic::from(r.quantity as f64));
                 rm.insert("max_quantity".into(), Dynamic::from(r.max_quantity as f64));
                 rm.insert("renewal_rate".into(), Dynamic::from(r.renewal_rate as f64));
+                rm.insert("quality".into(), Dynamic::from(r.quality as f64));
+                rm.insert("discovered".into(), Dynamic::from(r.discovered));
                 Dynamic::from(rm)
             })
             .collect();
@@ -864,6 +2158,22 @@ pub fn tile_mutable_rhai_map(base: &Map, tile: &Tile, phase: Phase) -> Dynamic {
         map.insert("resources".into(), Dynamic::from(empty));
     }
 
+    map.insert("scratch".into(), scratch_rhai_map(tile));
+
+    Dynamic::from(map)
+}
+
+/// Add `since` to a tile's Rhai map for a slow phase's run: the ticks it
+/// stands in for, the precipitation summed over them, and the mean
+/// temperature and soil moisture.
+pub fn with_since(tile_map: Dynamic, since: &crate::world::phase_inputs::Since) -> Dynamic {
+    let mut map = tile_map.cast::<Map>();
+    let mut inputs = Map::new();
+    inputs.insert("ticks".into(), Dynamic::from(since.ticks as i64));
+    inputs.insert("precipitation".into(), Dynamic::from(since.precipitation as f64));
+    inputs.insert("temperature".into(), Dynamic::from(since.temperature as f64));
+    inputs.insert("soil_moisture".into(), Dynamic::from(since.soil_moisture as f64));
+    map.insert("since".into(), Dynamic::from(inputs));
     Dynamic::from(map)
 }
 
@@ -877,6 +2187,8 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
     let mut pos = Map::new();
     pos.insert("x".into(), Dynamic::from(tile.position.x as f64));
     pos.insert("y".into(), Dynamic::from(tile.position.y as f64));
+    pos.insert("lat".into(), Dynamic::from(tile.position.lat));
+    pos.insert("lon".into(), Dynamic::from(tile.position.lon));
     map.insert("position".into(), Dynamic::from(pos));
 
     // Geology layer
@@ -895,6 +2207,7 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
         "tectonic_stress".into(),
         Dynamic::from(tile.geology.tectonic_stress as f64),
     );
+    geo.insert("sediment".into(), Dynamic::from(tile.geology.sediment as f64));
     map.insert("geology".into(), Dynamic::from(geo));
 
     // Climate layer
@@ -936,6 +2249,10 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
         "ticks_in_current_biome".into(),
         Dynamic::from(tile.biome.ticks_in_current_biome as i64),
     );
+    biome.insert(
+        "root_strength".into(),
+        Dynamic::from(tile.biome.root_strength as f64),
+    );
     map.insert("biome".into(), Dynamic::from(biome));
 
     // Weather layer
@@ -952,6 +2269,7 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
     weather.insert("humidity".into(), Dynamic::from(tile.weather.humidity as f64));
     weather.insert("storm_intensity".into(), Dynamic::from(tile.weather.storm_intensity as f64));
     weather.insert("pressure".into(), Dynamic::from(tile.weather.pressure as f64));
+    weather.insert("station_pressure".into(), Dynamic::from(tile.weather.station_pressure as f64));
     weather.insert("macro_wind_speed".into(), Dynamic::from(tile.weather.macro_wind_speed as f64));
     weather.insert("macro_wind_direction".into(), Dynamic::from(tile.weather.macro_wind_direction as f64));
     weather.insert("macro_humidity".into(), Dynamic::from(tile.weather.macro_humidity as f64));
@@ -966,6 +2284,43 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
     conditions.insert("frost_days".into(), Dynamic::from(tile.conditions.frost_days as i64));
     conditions.insert("drought_days".into(), Dynamic::from(tile.conditions.drought_days as i64));
     conditions.insert("fire_risk".into(), Dynamic::from(tile.conditions.fire_risk as f64));
+    conditions.insert(
+        "freeze_thaw_cycles".into(),
+        Dynamic::from(tile.conditions.freeze_thaw_cycles as i64),
+    );
+    conditions.insert(
+        "trafficability".into(),
+        Dynamic::from(tile.conditions.trafficability as f64),
+    );
+    conditions.insert(
+        "silt_fertility".into(),
+        Dynamic::from(tile.conditions.silt_fertility as f64),
+    );
+    conditions.insert("pet".into(), Dynamic::from(tile.conditions.pet as f64));
+    conditions.insert(
+        "aridity_index".into(),
+        Dynamic::from(tile.conditions.aridity_index as f64),
+    );
+    conditions.insert("fog".into(), Dynamic::from(tile.conditions.fog as f64));
+    conditions.insert(
+        "visibility_km".into(),
+        Dynamic::from(tile.conditions.visibility_km as f64),
+    );
+    conditions.insert(
+        "carrying_capacity".into(),
+        Dynamic::from(tile.conditions.carrying_capacity as f64),
+    );
+    conditions.insert(
+        "river_flow".into(),
+        Dynamic::from(tile.conditions.river_flow as f64),
+    );
+    conditions.insert(
+        "water_table".into(),
+        Dynamic::from(tile.conditions.water_table as f64),
+    );
+    conditions.insert("salinity".into(), Dynamic::from(tile.conditions.salinity as f64));
+    conditions.insert("glacier_mass".into(), Dynamic::from(tile.conditions.glacier_mass as f64));
+    conditions.insert("wave_height".into(), Dynamic::from(tile.conditions.wave_height as f64));
     map.insert("conditions".into(), Dynamic::from(conditions));
 
     // Resources: only build for Resources phase, empty vec otherwise
@@ -980,6 +2335,8 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
                 rm.insert("quantity".into(), Dynamic::from(r.quantity as f64));
                 rm.insert("max_quantity".into(), Dynamic::from(r.max_quantity as f64));
                 rm.insert("renewal_rate".into(), Dynamic::from(r.renewal_rate as f64));
+                rm.insert("quality".into(), Dynamic::from(r.quality as f64));
+                rm.insert("discovered".into(), Dynamic::from(r.discovered));
                 Dynamic::from(rm)
             })
             .collect();
@@ -989,6 +2346,8 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
         map.insert("resources".into(), Dynamic::from(empty));
     }
 
+    map.insert("scratch".into(), scratch_rhai_map(tile));
+
     // Neighbor IDs
     let neighbor_ids: Vec<Dynamic> = tile.neighbors.iter().map(|&n| Dynamic::from(n as i64)).collect();
     map.insert("neighbor_ids".into(), Dynamic::from(neighbor_ids));
@@ -998,192 +2357,289 @@ pub fn tile_to_rhai_map_for_phase(tile: &Tile, phase: Phase) -> Dynamic {
 
 /// Apply mutations from rule evaluation to a tile's mutable fields for a given phase.
 ///
-/// Only fields writable in the given phase are applied. Returns the number of mutations applied.
+/// Only fields writable in the given phase are applied. Deprecated field names from
+/// `FIELD_ALIASES` are rewritten to their current names. Returns the number of mutations applied.
 pub fn apply_mutations(tile: &mut Tile, mutations: &TileMutations, phase: Phase) -> usize {
+    apply_mutations_tracked(tile, mutations, phase, |_, _| {})
+}
+
+/// Like `apply_mutations`, but calls `on_reject` with the index and reason of
+/// every mutation that could not be applied.
+pub fn apply_mutations_tracked(
+    tile: &mut Tile,
+    mutations: &TileMutations,
+    phase: Phase,
+    mut on_reject: impl FnMut(usize, RejectionReason),
+) -> usize {
     let mut applied = 0;
 
-    for (field, value) in &mutations.mutations {
-        let ok = match phase {
-            Phase::Weather => apply_weather_mutation(tile, field, value),
-            Phase::Conditions => apply_conditions_mutation(tile, field, value),
-            Phase::Terrain => apply_terrain_mutation(tile, field, value),
-            Phase::Resources => apply_resources_mutation(tile, field, value),
+    for (i, (field, value)) in mutations.mutations.iter().enumerate() {
+        let field = match resolve_field_alias(field, phase) {
+            Some(alias) => {
+                warn_alias_once(alias);
+                alias.new_name
+            }
+            None => field.as_str(),
+        };
+        let result = match field.strip_prefix(SCRATCH_PREFIX) {
+            Some(key) => apply_scratch_mutation(tile, key, value),
+            None => match phase {
+                Phase::Weather => apply_weather_mutation(tile, field, value),
+                Phase::Conditions => apply_conditions_mutation(tile, field, value),
+                Phase::Terrain => apply_terrain_mutation(tile, field, value),
+                Phase::Resources => apply_resources_mutation(tile, field, value),
+            },
         };
-        if ok {
-            applied += 1;
+        match result {
+            Ok(()) => applied += 1,
+            Err(reason) => on_reject(i, reason),
         }
     }
 
     applied
 }
 
-fn apply_weather_mutation(tile: &mut Tile, field: &str, value: &Dynamic) -> bool {
-    match field {
-        "temperature" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.temperature = v as f32;
-                return true;
-            }
-        }
-        "precipitation" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.precipitation = (v as f32).clamp(0.0, 1.0);
-                return true;
+/// Prefix for `set()` fields that write per-tile scratch variables (`"scratch.<key>"`).
+pub const SCRATCH_PREFIX: &str = "scratch.";
+
+/// Fields `set()` may write in each phase. Resource fields are per-deposit
+/// (`"<resource>.quantity"`, `"<resource>.renewal_rate"`) and checked by `is_writable_field`.
+pub fn writable_fields(phase: Phase) -> &'static [&'static str] {
+    match phase {
+        Phase::Weather => &[
+            "temperature",
+            "precipitation",
+            "precipitation_type",
+            "wind_speed",
+            "wind_direction",
+            "cloud_cover",
+            "storm_intensity",
+            "humidity",
+        ],
+        Phase::Conditions => &[
+            "soil_moisture",
+            "snow_depth",
+            "mud_level",
+            "flood_level",
+            "frost_days",
+            "drought_days",
+            "fire_risk",
+        ],
+        Phase::Terrain => &[
+            "vegetation_density",
+            "vegetation_health",
+            "transition_pressure",
+            "biome_type",
+        ],
+        Phase::Resources => &["quantity", "renewal_rate"],
+    }
+}
+
+/// Whether `field` could name something `set()` writes in this phase on some
+/// tile. Resource fields only need the `"<resource>.<field>"` form, since
+/// which deposits a tile holds is only known at run time.
+pub fn is_settable_field(field: &str, phase: Phase) -> bool {
+    if let Some(key) = field.strip_prefix(SCRATCH_PREFIX) {
+        return is_valid_scratch_key(key);
+    }
+    let field = resolve_field_alias(field, phase).map_or(field, |alias| alias.new_name);
+    match phase {
+        Phase::Resources => field
+            .split_once('.')
+            .is_some_and(|(res_name, res_field)| !res_name.is_empty() && writable_fields(phase).contains(&res_field)),
+        _ => writable_fields(phase).contains(&field),
+    }
+}
+
+/// Whether `field` names something `set()` can write on this tile in this phase.
+/// Deprecated aliases count as writable.
+pub fn is_writable_field(tile: &Tile, field: &str, phase: Phase) -> bool {
+    if let Some(key) = field.strip_prefix(SCRATCH_PREFIX) {
+        return is_valid_scratch_key(key);
+    }
+    let field = resolve_field_alias(field, phase).map_or(field, |alias| alias.new_name);
+    match phase {
+        Phase::Resources => field.split_once('.').is_some_and(|(res_name, res_field)| {
+            writable_fields(phase).contains(&res_field)
+                && tile.resources.resources.iter().any(|r| r.resource_type == res_name)
+        }),
+        _ => writable_fields(phase).contains(&field),
+    }
+}
+
+/// The value of a numeric field `set()` writes in this phase, as rules see it:
+/// a float, or an int for day counts. A scratch variable that isn't set reads
+/// as 0.0. None for fields that aren't numeric or writable in the phase.
+pub fn numeric_field(tile: &Tile, field: &str, phase: Phase) -> Option<Dynamic> {
+    let field = resolve_field_alias(field, phase).map_or(field, |alias| alias.new_name);
+    if let Some(key) = field.strip_prefix(SCRATCH_PREFIX) {
+        return is_valid_scratch_key(key).then(|| Dynamic::from(tile.scratch.get(key).copied().unwrap_or(0.0)));
+    }
+    let float = |v: f32| Some(Dynamic::from(v as f64));
+    let (w, c, b) = (&tile.weather, &tile.conditions, &tile.biome);
+    match (phase, field) {
+        (Phase::Weather, "temperature") => float(w.temperature),
+        (Phase::Weather, "precipitation") => float(w.precipitation),
+        (Phase::Weather, "wind_speed") => float(w.wind_speed),
+        (Phase::Weather, "wind_direction") => float(w.wind_direction),
+        (Phase::Weather, "cloud_cover") => float(w.cloud_cover),
+        (Phase::Weather, "storm_intensity") => float(w.storm_intensity),
+        (Phase::Weather, "humidity") => float(w.humidity),
+        (Phase::Conditions, "soil_moisture") => float(c.soil_moisture),
+        (Phase::Conditions, "snow_depth") => float(c.snow_depth),
+        (Phase::Conditions, "mud_level") => float(c.mud_level),
+        (Phase::Conditions, "flood_level") => float(c.flood_level),
+        (Phase::Conditions, "frost_days") => Some(Dynamic::from(c.frost_days as i64)),
+        (Phase::Conditions, "drought_days") => Some(Dynamic::from(c.drought_days as i64)),
+        (Phase::Conditions, "fire_risk") => float(c.fire_risk),
+        (Phase::Terrain, "vegetation_density") => float(b.vegetation_density),
+        (Phase::Terrain, "vegetation_health") => float(b.vegetation_health),
+        (Phase::Terrain, "transition_pressure") => float(b.transition_pressure),
+        (Phase::Resources, field) => {
+            let (name, res_field) = field.split_once('.')?;
+            let deposit = tile.resources.resources.iter().find(|r| r.resource_type == name)?;
+            match res_field {
+                "quantity" => float(deposit.quantity),
+                "renewal_rate" => float(deposit.renewal_rate),
+                _ => None,
             }
         }
+        _ => None,
+    }
+}
+
+/// A finite float. NaN would pass straight through `clamp`, so it is rejected
+/// along with the infinities (and doubles too large for `f32`).
+fn float_value(value: &Dynamic) -> Result<f32, RejectionReason> {
+    let v = value.as_float().map_err(|_| RejectionReason::TypeMismatch)? as f32;
+    if v.is_finite() { Ok(v) } else { Err(RejectionReason::InvalidValue) }
+}
+
+fn int_value(value: &Dynamic) -> Result<i64, RejectionReason> {
+    value.as_int().map_err(|_| RejectionReason::TypeMismatch)
+}
+
+fn string_value(value: &Dynamic) -> Result<String, RejectionReason> {
+    value
+        .clone()
+        .into_string()
+        .map_err(|_| RejectionReason::TypeMismatch)
+}
+
+/// Write or remove a scratch variable. Any phase may write scratch; setting a
+/// key to `()` removes it.
+fn apply_scratch_mutation(
+    tile: &mut Tile,
+    key: &str,
+    value: &Dynamic,
+) -> Result<(), RejectionReason> {
+    if !is_valid_scratch_key(key) {
+        return Err(RejectionReason::UnwritableField);
+    }
+    if value.is_unit() {
+        tile.scratch.remove(key);
+        return Ok(());
+    }
+    let v = value
+        .as_float()
+        .or_else(|_| value.as_int().map(|i| i as f64))
+        .map_err(|_| RejectionReason::TypeMismatch)?;
+    if !v.is_finite() {
+        return Err(RejectionReason::InvalidValue);
+    }
+    if !tile.scratch.contains_key(key) && tile.scratch.len() >= MAX_SCRATCH_KEYS {
+        return Err(RejectionReason::ScratchLimit);
+    }
+    tile.scratch.insert(key.to_string(), v);
+    Ok(())
+}
+
+fn apply_weather_mutation(
+    tile: &mut Tile,
+    field: &str,
+    value: &Dynamic,
+) -> Result<(), RejectionReason> {
+    match field {
+        "temperature" => tile.weather.temperature = float_value(value)?.max(0.0),
+        "precipitation" => tile.weather.precipitation = float_value(value)?.clamp(0.0, 1.0),
         "precipitation_type" => {
-            if let Some(s) = value.clone().into_string().ok() {
-                if let Some(pt) = parse_precipitation_type(&s) {
-                    tile.weather.precipitation_type = pt;
-                    return true;
-                }
-            }
-        }
-        "wind_speed" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.wind_speed = (v as f32).max(0.0);
-                return true;
-            }
+            tile.weather.precipitation_type = parse_precipitation_type(&string_value(value)?)
+                .ok_or(RejectionReason::InvalidValue)?;
         }
+        "wind_speed" => tile.weather.wind_speed = float_value(value)?.max(0.0),
         "wind_direction" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.wind_direction = ((v as f32) % 360.0 + 360.0) % 360.0;
-                return true;
-            }
-        }
-        "cloud_cover" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.cloud_cover = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        "storm_intensity" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.storm_intensity = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        "humidity" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.weather.humidity = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
+            tile.weather.wind_direction = (float_value(value)? % 360.0 + 360.0) % 360.0;
         }
-        _ => {}
+        "cloud_cover" => tile.weather.cloud_cover = float_value(value)?.clamp(0.0, 1.0),
+        "storm_intensity" => tile.weather.storm_intensity = float_value(value)?.clamp(0.0, 1.0),
+        "humidity" => tile.weather.humidity = float_value(value)?.clamp(0.0, 1.0),
+        _ => return Err(RejectionReason::UnwritableField),
     }
-    false
+    Ok(())
 }
 
-fn apply_conditions_mutation(tile: &mut Tile, field: &str, value: &Dynamic) -> bool {
+fn apply_conditions_mutation(
+    tile: &mut Tile,
+    field: &str,
+    value: &Dynamic,
+) -> Result<(), RejectionReason> {
     match field {
-        "soil_moisture" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.conditions.soil_moisture = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        "snow_depth" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.conditions.snow_depth = (v as f32).max(0.0);
-                return true;
-            }
-        }
-        "mud_level" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.conditions.mud_level = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        "flood_level" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.conditions.flood_level = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        "frost_days" => {
-            if let Some(v) = value.as_int().ok() {
-                tile.conditions.frost_days = v.max(0) as u32;
-                return true;
-            }
-        }
-        "drought_days" => {
-            if let Some(v) = value.as_int().ok() {
-                tile.conditions.drought_days = v.max(0) as u32;
-                return true;
-            }
-        }
-        "fire_risk" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.conditions.fire_risk = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        _ => {}
+        "soil_moisture" => tile.conditions.soil_moisture = float_value(value)?.clamp(0.0, 1.0),
+        "snow_depth" => tile.conditions.snow_depth = float_value(value)?.max(0.0),
+        "mud_level" => tile.conditions.mud_level = float_value(value)?.clamp(0.0, 1.0),
+        "flood_level" => tile.conditions.flood_level = float_value(value)?.clamp(0.0, 1.0),
+        "frost_days" => tile.conditions.frost_days = int_value(value)?.max(0) as u32,
+        "drought_days" => tile.conditions.drought_days = int_value(value)?.max(0) as u32,
+        "fire_risk" => tile.conditions.fire_risk = float_value(value)?.clamp(0.0, 1.0),
+        _ => return Err(RejectionReason::UnwritableField),
     }
-    false
+    Ok(())
 }
 
-fn apply_terrain_mutation(tile: &mut Tile, field: &str, value: &Dynamic) -> bool {
+fn apply_terrain_mutation(
+    tile: &mut Tile,
+    field: &str,
+    value: &Dynamic,
+) -> Result<(), RejectionReason> {
     match field {
         "vegetation_density" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.biome.vegetation_density = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
-        }
-        "vegetation_health" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.biome.vegetation_health = (v as f32).clamp(0.0, 1.0);
-                return true;
-            }
+            tile.biome.vegetation_density = float_value(value)?.clamp(0.0, 1.0);
         }
+        "vegetation_health" => tile.biome.vegetation_health = float_value(value)?.clamp(0.0, 1.0),
         "transition_pressure" => {
-            if let Some(v) = value.as_float().ok() {
-                tile.biome.transition_pressure = (v as f32).clamp(-1.0, 1.0);
-                return true;
-            }
+            tile.biome.transition_pressure = float_value(value)?.clamp(-1.0, 1.0);
         }
         "biome_type" => {
-            if let Some(s) = value.clone().into_string().ok() {
-                if let Some(bt) = parse_biome_type(&s) {
-                    tile.biome.biome_type = bt;
-                    tile.biome.ticks_in_current_biome = 0;
-                    return true;
-                }
-            }
+            tile.biome.biome_type =
+                parse_biome_type(&string_value(value)?).ok_or(RejectionReason::InvalidValue)?;
+            tile.biome.ticks_in_current_biome = 0;
         }
-        _ => {}
+        _ => return Err(RejectionReason::UnwritableField),
     }
-    false
+    Ok(())
 }
 
-fn apply_resources_mutation(tile: &mut Tile, field: &str, value: &Dynamic) -> bool {
+fn apply_resources_mutation(
+    tile: &mut Tile,
+    field: &str,
+    value: &Dynamic,
+) -> Result<(), RejectionReason> {
     // Resource mutations use a "resource_name.field" format
-    if let Some((res_name, res_field)) = field.split_once('.') {
-        if let Some(deposit) = tile
-            .resources
-            .resources
-            .iter_mut()
-            .find(|r| r.resource_type == res_name)
-        {
-            match res_field {
-                "quantity" => {
-                    if let Some(v) = value.as_float().ok() {
-                        deposit.quantity = (v as f32).max(0.0).min(deposit.max_quantity);
-                        return true;
-                    }
-                }
-                "renewal_rate" => {
-                    if let Some(v) = value.as_float().ok() {
-                        deposit.renewal_rate = (v as f32).max(0.0);
-                        return true;
-                    }
-                }
-                _ => {}
-            }
-        }
+    let (res_name, res_field) = field
+        .split_once('.')
+        .ok_or(RejectionReason::UnwritableField)?;
+    let deposit = tile
+        .resources
+        .resources
+        .iter_mut()
+        .find(|r| r.resource_type == res_name)
+        .ok_or(RejectionReason::UnwritableField)?;
+    match res_field {
+        "quantity" => deposit.quantity = float_value(value)?.max(0.0).min(deposit.max_quantity),
+        "renewal_rate" => deposit.renewal_rate = float_value(value)?.max(0.0),
+        _ => return Err(RejectionReason::UnwritableField),
     }
-    false
+    Ok(())
 }
 
 fn parse_precipitation_type(s: &str) -> Option<PrecipitationType> {
@@ -1218,6 +2674,7 @@ fn parse_biome_type(s: &str) -> Option<BiomeType> {
 mod tests {
     use super::*;
     use crate::world::tile::Position;
+    use proptest::prelude::*;
     use std::fs;
     use tempfile::TempDir;
 
@@ -1240,30 +2697,88 @@ mod tests {
     }
 
     #[test]
-    fn load_valid_rules() {
+    fn load_valid_rules() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[("01-test.rhai", "set(\"temperature\", 300.0);")],
+        );
+
+        let engine = RuleEngine::new(dir.path(), 10).unwrap();
+        assert_eq!(engine.rules_for_phase(Phase::Weather).len(), 1);
+        assert_eq!(engine.rules_for_phase(Phase::Conditions).len(), 0);
+        assert_eq!(engine.rule_count(), 1);
+    }
+
+    #[test]
+    fn orographic_factor_host_function_matches_native() {
         let dir = TempDir::new().unwrap();
         setup_empty_rule_dirs(dir.path());
         make_rule_dir(
             dir.path(),
             "weather",
-            &[("01-test.rhai", "set(\"temperature\", 300.0);")],
+            &[("01-lift.rhai", "set(\"precipitation\", orographic_factor(tile, neighbors) / 10.0);")],
         );
-
         let engine = RuleEngine::new(dir.path(), 10).unwrap();
-        assert_eq!(engine.rules_for_phase(Phase::Weather).len(), 1);
-        assert_eq!(engine.rules_for_phase(Phase::Conditions).len(), 0);
-        assert_eq!(engine.rule_count(), 1);
+        let factor = |tile: &Tile, neighbor: &Tile| {
+            let result = engine.evaluate_tile(Phase::Weather, tile, &[neighbor], &Season::Spring, 0, 1).unwrap();
+            result.mutations[0].1.as_float().unwrap() * 10.0
+        };
+
+        // Eastward wind up from a low neighbor 5° to the west, on the equator
+        let geo = |id: u32, lon: f64, elevation: f32| {
+            let mut t = Tile::new_default(id, vec![1 - id], Position { x: 0.0, y: 0.0, z: 0.0, lat: 0.0, lon });
+            t.geology.elevation = elevation;
+            t.weather.wind_direction = 90.0;
+            t.weather.wind_speed = 10.0;
+            t
+        };
+        let expected = super::super::native_weather::orographic_factor(0.5, 90.0, 10.0, [(90.0, 0.2)]);
+        assert!((factor(&geo(1, 5.0, 0.5), &geo(0, 0.0, 0.2)) - expected).abs() < 1e-6);
+
+        // Flat worlds have no bearings and fall back to terrain type
+        let mut mountain = make_test_tile();
+        mountain.geology.terrain_type = TerrainType::Mountains;
+        assert!((factor(&mountain, &make_test_tile()) - 1.8).abs() < 1e-6);
     }
 
     #[test]
     fn missing_rule_dir_error() {
         let result = RuleEngine::new(Path::new("/nonexistent/rules"), 10);
         match result {
-            Err(msg) => assert!(msg.contains("Rule directory not found")),
+            Err(err) => {
+                assert!(matches!(err, WorldgroundError::Rules(_)), "{:?}", err);
+                assert!(err.to_string().contains("Rule directory not found"));
+            }
             Ok(_) => panic!("Expected error for missing rule directory"),
         }
     }
 
+    #[test]
+    fn rule_packs_are_listed_and_loaded_by_name() {
+        let dir = TempDir::new().unwrap();
+        make_rule_dir(&dir.path().join("earthlike"), "weather", &[("01-rain.rhai", r#"set("precipitation", 0.4);"#)]);
+        setup_empty_rule_dirs(&dir.path().join("arid-world"));
+        make_rule_dir(dir.path(), "weather", &[("01-dry.rhai", r#"set("precipitation", 0.0);"#)]);
+        std::fs::create_dir_all(dir.path().join("notes")).unwrap();
+        assert_eq!(RuleEngine::rule_packs(dir.path()), vec!["arid-world", "earthlike"]);
+
+        let engine = RuleEngine::with_pack(dir.path(), Some("earthlike"), 10).unwrap();
+        assert_eq!(engine.pack(), Some("earthlike"));
+        assert_eq!(engine.rule_dir(), dir.path().join("earthlike"));
+        assert_eq!(engine.rule_count(), 1);
+        let engine = RuleEngine::with_pack(dir.path(), None, 10).unwrap();
+        assert_eq!(engine.pack(), None);
+        assert_eq!(engine.rule_dir(), dir.path());
+
+        let err = RuleEngine::with_pack(dir.path(), Some("notes"), 10).err().unwrap().to_string();
+        assert!(err.contains("packs: arid-world, earthlike"), "{}", err);
+        let err = RuleEngine::with_pack(Path::new(builtin_rules::BUILTIN_RULES), Some("earthlike"), 10).err().unwrap().to_string();
+        assert!(err.contains("no packs"), "{}", err);
+    }
+
     #[test]
     fn rhai_syntax_error_detected() {
         let dir = TempDir::new().unwrap();
@@ -1276,11 +2791,65 @@ mod tests {
 
         let result = RuleEngine::new(dir.path(), 10);
         match result {
-            Err(msg) => assert!(msg.contains("Syntax error")),
+            Err(err) => {
+                assert!(matches!(err, WorldgroundError::RuleSyntax { .. }), "{:?}", err);
+                assert!(err.to_string().contains("Syntax error"));
+            }
             Ok(_) => panic!("Expected syntax error"),
         }
     }
 
+    #[test]
+    fn reload_picks_up_edits_and_keeps_rules_on_syntax_error() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "weather", &[("01-temp.rhai", "set(\"temperature\", 280.0);")]);
+        let mut engine = RuleEngine::new(dir.path(), 10).unwrap();
+        let temperature = |engine: &RuleEngine| {
+            let result = engine.evaluate_tile(Phase::Weather, &make_test_tile(), &[], &Season::Spring, 0, 42);
+            result.unwrap().mutations[0].1.as_float().unwrap()
+        };
+        assert_eq!(temperature(&engine), 280.0);
+
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[("01-temp.rhai", "set(\"temperature\", 290.0);"), ("02-rain.rhai", "set(\"precipitation\", 0.2);")],
+        );
+        assert_eq!(engine.reload_rules().unwrap(), 2);
+        assert_eq!(temperature(&engine), 290.0);
+
+        make_rule_dir(dir.path(), "weather", &[("01-temp.rhai", "set(\"temperature\", ")]);
+        let err = engine.reload_rules().unwrap_err().to_string();
+        assert!(err.contains("01-temp.rhai"), "{}", err);
+        assert_eq!(engine.rule_count(), 2);
+        assert_eq!(temperature(&engine), 290.0);
+    }
+
+    #[test]
+    fn rules_read_params_from_rules_toml() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[("01-temp.rhai", "if \"base\" in params { set(\"temperature\", params.base + params.offsets.warm); }")],
+        );
+        let mut engine = RuleEngine::new(dir.path(), 10).unwrap();
+        assert_eq!(engine.param_count(), 0);
+        let mutations = |engine: &RuleEngine| {
+            engine.evaluate_tile(Phase::Weather, &make_test_tile(), &[], &Season::Spring, 0, 42).unwrap().mutations
+        };
+        assert!(mutations(&engine).is_empty());
+        let hash = engine.rules_hash();
+
+        std::fs::write(dir.path().join("rules.toml"), "base = 280.0\n\n[offsets]\nwarm = 5\n").unwrap();
+        engine.reload_rules().unwrap();
+        assert_eq!(engine.param_count(), 2);
+        assert_eq!(mutations(&engine)[0].1.as_float(), Ok(285.0));
+        assert_ne!(engine.rules_hash(), hash);
+    }
+
     #[test]
     fn empty_phase_dir_is_noop() {
         let dir = TempDir::new().unwrap();
@@ -1451,6 +3020,111 @@ mod tests {
         assert!(elapsed.as_secs() < 5);
     }
 
+    #[test]
+    fn operation_and_size_limits_configurable() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[(
+                "01-busy.rhai",
+                r#"
+                let a = [];
+                for i in 0..2000 { a.push(i); }
+                set("temperature", a.len().to_float());
+                "#,
+            )],
+        );
+        let tile = make_test_tile();
+
+        // 2000 elements exceeds the default array limit
+        let engine = RuleEngine::new(dir.path(), 10).unwrap();
+        assert!(engine.evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42).is_err());
+
+        let engine = RuleEngine::new(dir.path(), 1000).unwrap().with_max_array_size(5000);
+        let result = engine
+            .evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42)
+            .unwrap();
+        assert_eq!(result.mutations[0].1.as_float().unwrap(), 2000.0);
+
+        let engine = RuleEngine::new(dir.path(), 10)
+            .unwrap()
+            .with_max_array_size(5000)
+            .with_max_operations(500);
+        assert!(engine.evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42).is_err());
+    }
+
+    #[test]
+    fn phases_get_their_own_limits_and_timeouts() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        let busy = "let x = 0; for i in 0..5000 { x += i; }";
+        make_rule_dir(dir.path(), "weather", &[("01-busy.rhai", busy)]);
+        make_rule_dir(dir.path(), "terrain", &[("01-busy.rhai", busy)]);
+        make_rule_dir(dir.path(), "resources", &[("01-forever.rhai", "loop { }")]);
+        let tile = make_test_tile();
+
+        let mut engine = RuleEngine::new(dir.path(), 10).unwrap().with_max_operations(1000);
+        engine.set_phase_limits(Phase::Terrain, RuleLimits { max_operations: 1_000_000, timeout_ms: 0 });
+        let err = engine.evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42).unwrap_err();
+        assert!(err.error.starts_with("Too many operations (limit 1000)"), "{}", err.error);
+        assert!(engine.evaluate_tile(Phase::Terrain, &tile, &[], &Season::Spring, 0, 42).is_ok());
+
+        // Only the wall clock stops a rule with no operation limit
+        engine.set_phase_limits(Phase::Resources, RuleLimits { max_operations: u64::MAX, timeout_ms: 20 });
+        let start = Instant::now();
+        let err = engine.evaluate_tile(Phase::Resources, &tile, &[], &Season::Spring, 0, 42).unwrap_err();
+        assert!(err.error.starts_with("Timed out after 20 ms"), "{}", err.error);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn temperature_helpers_convert_celsius() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[("01-units.rhai", r#"
+                if to_celsius(tile.weather.temperature) > to_celsius(to_kelvin(10)) {
+                    set("temperature", to_kelvin(to_celsius(tile.weather.temperature) + 1.0));
+                }
+            "#)],
+        );
+        let mut tile = make_test_tile();
+        tile.weather.temperature = 290.0;
+
+        let engine = RuleEngine::new(dir.path(), 10).unwrap();
+        let result = engine
+            .evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42)
+            .unwrap();
+        assert!((result.mutations[0].1.as_float().unwrap() - 291.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn host_functions_registered_after_loading() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[("01-host.rhai", r#"set("temperature", celsius(20.0));"#)],
+        );
+        let tile = make_test_tile();
+
+        let engine = RuleEngine::new(dir.path(), 10).unwrap();
+        assert!(engine.evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42).is_err());
+
+        let engine = RuleEngine::new(dir.path(), 10).unwrap().with_host_functions(|rhai| {
+            rhai.register_fn("celsius", |c: f64| c + 273.15);
+        });
+        let result = engine
+            .evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42)
+            .unwrap();
+        assert!((result.mutations[0].1.as_float().unwrap() - 293.15).abs() < 1e-9);
+    }
+
     #[test]
     fn apply_weather_mutations() {
         let mut tile = make_test_tile();
@@ -1460,6 +3134,7 @@ mod tests {
                 ("precipitation".to_string(), Dynamic::from(0.8_f64)),
                 ("cloud_cover".to_string(), Dynamic::from(0.9_f64)),
             ],
+            ..Default::default()
         };
 
         let applied = apply_mutations(&mut tile, &mutations, Phase::Weather);
@@ -1477,6 +3152,7 @@ mod tests {
                 ("soil_moisture".to_string(), Dynamic::from(1.5_f64)), // should clamp to 1.0
                 ("frost_days".to_string(), Dynamic::from(5_i64)),
             ],
+            ..Default::default()
         };
 
         let applied = apply_mutations(&mut tile, &mutations, Phase::Conditions);
@@ -1496,6 +3172,7 @@ mod tests {
                     Dynamic::from(-0.5_f64),
                 ),
             ],
+            ..Default::default()
         };
 
         let applied = apply_mutations(&mut tile, &mutations, Phase::Terrain);
@@ -1510,6 +3187,7 @@ mod tests {
         let original_temp = tile.weather.temperature;
         let mutations = TileMutations {
             mutations: vec![("temperature".to_string(), Dynamic::from(999.0_f64))],
+            ..Default::default()
         };
 
         // Apply weather mutation during conditions phase — should be ignored
@@ -1518,6 +3196,417 @@ mod tests {
         assert_eq!(tile.weather.temperature, original_temp);
     }
 
+    #[test]
+    fn rejected_mutations_report_reason() {
+        let mut tile = make_test_tile();
+        let mutations = TileMutations {
+            mutations: vec![
+                ("humidity".to_string(), Dynamic::from(0.5_f64)),
+                ("soil_moisture".to_string(), Dynamic::from(0.5_f64)),
+                ("cloud_cover".to_string(), Dynamic::from(1_i64)),
+                ("precipitation_type".to_string(), Dynamic::from("Drizzle".to_string())),
+            ],
+            ..Default::default()
+        };
+
+        let mut rejected = Vec::new();
+        let applied = apply_mutations_tracked(&mut tile, &mutations, Phase::Weather, |i, reason| {
+            rejected.push((i, reason))
+        });
+        assert_eq!(applied, 1);
+        assert_eq!(
+            rejected,
+            vec![
+                (1, RejectionReason::UnwritableField),
+                (2, RejectionReason::TypeMismatch),
+                (3, RejectionReason::InvalidValue),
+            ]
+        );
+    }
+
+    #[test]
+    fn writable_fields_match_apply() {
+        for &phase in Phase::all() {
+            if phase == Phase::Resources {
+                continue;
+            }
+            for &field in writable_fields(phase) {
+                let mut tile = make_test_tile();
+                assert!(is_writable_field(&tile, field, phase));
+                let mutations = TileMutations {
+                    mutations: vec![(field.to_string(), Dynamic::from(0.5_f64))],
+                    ..Default::default()
+                };
+                apply_mutations_tracked(&mut tile, &mutations, phase, |_, reason| {
+                    assert_ne!(reason, RejectionReason::UnwritableField, "{}", field);
+                });
+            }
+        }
+
+        let tile = make_test_tile();
+        assert!(!is_writable_field(&tile, "temprature", Phase::Weather));
+        assert!(!is_writable_field(&tile, "temperature", Phase::Conditions));
+        assert!(is_writable_field(&tile, "moisture", Phase::Conditions));
+        assert!(!is_writable_field(&tile, "unobtainium.quantity", Phase::Resources));
+    }
+
+    /// Field names a rule might pass to `set()`: every writable field, the
+    /// deprecated aliases, per-deposit resource fields, scratch keys and junk.
+    fn arb_field() -> impl Strategy<Value = String> {
+        let mut known: Vec<String> = Phase::all()
+            .iter()
+            .flat_map(|&p| writable_fields(p).iter().map(|f| f.to_string()))
+            .collect();
+        known.extend(FIELD_ALIASES.iter().map(|a| a.old_name.to_string()));
+        known.extend(["grain.quantity", "grain.renewal_rate", "ore.quantity", "grain.max_quantity"].map(String::from));
+        prop_oneof![
+            4 => prop::sample::select(known),
+            1 => "[a-z]{1,3}".prop_map(|k| format!("{}{}", SCRATCH_PREFIX, k)),
+            1 => "\\PC{0,12}",
+        ]
+    }
+
+    /// Values a rule might pass to `set()`, including NaN and the infinities.
+    fn arb_value() -> impl Strategy<Value = Dynamic> {
+        prop_oneof![
+            prop::num::f64::ANY.prop_map(Dynamic::from),
+            any::<i64>().prop_map(Dynamic::from),
+            prop::sample::select(vec!["Rain", "Snow", "Grassland", "Desert", "Drizzle", ""])
+                .prop_map(|s| Dynamic::from(s.to_string())),
+            any::<bool>().prop_map(Dynamic::from),
+            Just(Dynamic::UNIT),
+        ]
+    }
+
+    fn arb_mutations() -> impl Strategy<Value = (Phase, Vec<(String, Dynamic)>)> {
+        (prop::sample::select(Phase::all().to_vec()), prop::collection::vec((arb_field(), arb_value()), 0..40))
+    }
+
+    /// Every field `apply_mutations` can write is finite and inside its range.
+    fn assert_tile_in_range(tile: &Tile) {
+        let unit = |v: f32| (0.0..=1.0).contains(&v);
+        let w = &tile.weather;
+        assert!(w.temperature.is_finite() && w.temperature >= 0.0, "temperature {}", w.temperature);
+        assert!(unit(w.precipitation) && unit(w.cloud_cover) && unit(w.storm_intensity) && unit(w.humidity));
+        assert!(w.wind_speed.is_finite() && w.wind_speed >= 0.0, "wind_speed {}", w.wind_speed);
+        assert!((0.0..360.0).contains(&w.wind_direction), "wind_direction {}", w.wind_direction);
+        let c = &tile.conditions;
+        assert!(unit(c.soil_moisture) && unit(c.mud_level) && unit(c.flood_level) && unit(c.fire_risk));
+        assert!(c.snow_depth.is_finite() && c.snow_depth >= 0.0, "snow_depth {}", c.snow_depth);
+        let b = &tile.biome;
+        assert!(unit(b.vegetation_density) && unit(b.vegetation_health));
+        assert!((-1.0..=1.0).contains(&b.transition_pressure));
+        for d in &tile.resources.resources {
+            assert!((0.0..=d.max_quantity).contains(&d.quantity), "{} quantity {}", d.resource_type, d.quantity);
+            assert!(d.renewal_rate.is_finite() && d.renewal_rate >= 0.0);
+        }
+        assert!(tile.scratch.len() <= MAX_SCRATCH_KEYS);
+        assert!(tile.scratch.values().all(|v| v.is_finite()));
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_mutations_keep_tiles_in_range((phase, mutations) in arb_mutations()) {
+            let mut tile = make_test_tile();
+            tile.resources.resources.push(crate::world::tile::ResourceDeposit {
+                resource_type: "grain".to_string(),
+                quantity: 50.0,
+                max_quantity: 100.0,
+                renewal_rate: 1.0,
+                requires_biome: None,
+                quality: 0.5,
+                discovered: true,
+            });
+            let count = mutations.len();
+            let mutations = TileMutations { mutations, ..Default::default() };
+            let mut rejected = 0;
+            let applied = apply_mutations_tracked(&mut tile, &mutations, phase, |_, _| rejected += 1);
+            prop_assert_eq!(applied + rejected, count);
+            assert_tile_in_range(&tile);
+        }
+    }
+
+    #[test]
+    fn mutations_attributed_to_emitting_rule() {
+        let dir = TempDir::new().unwrap();
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[
+                ("01-a.rhai", r#"set("temperature", 1.0); set("humidity", 0.1);"#),
+                ("02-none.rhai", "let x = 1;"),
+                ("03-b.rhai", r#"set("cloud_cover", 0.2);"#),
+            ],
+        );
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let tile = make_test_tile();
+
+        let result = engine
+            .evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 0, 42)
+            .unwrap();
+        assert_eq!(result.rule_indices, vec![0, 0, 2]);
+        assert_eq!(result.rule_index(2), Some(2));
+        assert_eq!(result.rule_index(3), None);
+    }
+
+    #[test]
+    fn scratch_mutations_apply_in_any_phase() {
+        let mut tile = make_test_tile();
+        let mutations = TileMutations {
+            mutations: vec![
+                ("scratch.ticks_since_fire".to_string(), Dynamic::from(3_i64)),
+                ("scratch.heat".to_string(), Dynamic::from(0.5_f64)),
+                ("scratch.bad key".to_string(), Dynamic::from(1.0_f64)),
+                ("scratch.label".to_string(), Dynamic::from("hot".to_string())),
+            ],
+            ..Default::default()
+        };
+        let mut rejected = Vec::new();
+        let applied = apply_mutations_tracked(&mut tile, &mutations, Phase::Terrain, |i, r| {
+            rejected.push((i, r))
+        });
+        assert_eq!(applied, 2);
+        assert_eq!(
+            rejected,
+            vec![(2, RejectionReason::UnwritableField), (3, RejectionReason::TypeMismatch)]
+        );
+        assert_eq!(tile.scratch["ticks_since_fire"], 3.0);
+        assert_eq!(tile.scratch["heat"], 0.5);
+
+        // Setting a key to () removes it
+        let remove = TileMutations {
+            mutations: vec![("scratch.heat".to_string(), Dynamic::UNIT)],
+            ..Default::default()
+        };
+        assert_eq!(apply_mutations(&mut tile, &remove, Phase::Weather), 1);
+        assert!(!tile.scratch.contains_key("heat"));
+
+        assert!(is_writable_field(&tile, "scratch.anything", Phase::Resources));
+        assert!(!is_writable_field(&tile, "scratch.", Phase::Weather));
+    }
+
+    #[test]
+    fn scratch_bounded_by_key_limit() {
+        let mut tile = make_test_tile();
+        for i in 0..MAX_SCRATCH_KEYS {
+            tile.scratch.insert(format!("k{}", i), 0.0);
+        }
+        let mutations = TileMutations {
+            mutations: vec![
+                ("scratch.extra".to_string(), Dynamic::from(1.0_f64)),
+                ("scratch.k0".to_string(), Dynamic::from(2.0_f64)),
+            ],
+            ..Default::default()
+        };
+        let mut rejected = Vec::new();
+        let applied = apply_mutations_tracked(&mut tile, &mutations, Phase::Weather, |i, r| {
+            rejected.push((i, r))
+        });
+        // Existing keys can still be overwritten once the map is full
+        assert_eq!(applied, 1);
+        assert_eq!(rejected, vec![(0, RejectionReason::ScratchLimit)]);
+        assert_eq!(tile.scratch.len(), MAX_SCRATCH_KEYS);
+        assert_eq!(tile.scratch["k0"], 2.0);
+    }
+
+    #[test]
+    fn scripts_read_and_write_scratch() {
+        let dir = TempDir::new().unwrap();
+        make_rule_dir(
+            dir.path(),
+            "conditions",
+            &[(
+                "01-count.rhai",
+                r#"let n = if "ticks" in tile.scratch { tile.scratch.ticks } else { 0.0 };
+set("scratch.ticks", n + 1.0);"#,
+            )],
+        );
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut tile = make_test_tile();
+
+        for _ in 0..3 {
+            let result = engine
+                .evaluate_tile(Phase::Conditions, &tile, &[], &Season::Spring, 0, 42)
+                .unwrap();
+            apply_mutations(&mut tile, &result, Phase::Conditions);
+        }
+        assert_eq!(tile.scratch["ticks"], 3.0);
+    }
+
+    #[test]
+    fn mutation_stats_merge() {
+        let mut a = MutationStats { applied: 3, ..Default::default() };
+        a.record_rejection("r.rhai", RejectionReason::UnwritableField);
+        let mut b = MutationStats { applied: 2, ..Default::default() };
+        b.record_rejection("r.rhai", RejectionReason::UnwritableField);
+        b.record_rejection("s.rhai", RejectionReason::TypeMismatch);
+
+        a.merge(b);
+        assert_eq!(a.applied, 5);
+        assert_eq!(a.total_rejected(), 3);
+        assert_eq!(a.rejected[&("r.rhai".to_string(), RejectionReason::UnwritableField)], 2);
+    }
+
+    #[test]
+    fn deprecated_field_alias_applied() {
+        let mut tile = make_test_tile();
+        let mutations = TileMutations {
+            mutations: vec![("moisture".to_string(), Dynamic::from(0.42_f64))],
+            ..Default::default()
+        };
+
+        let applied = apply_mutations(&mut tile, &mutations, Phase::Conditions);
+        assert_eq!(applied, 1);
+        assert!((tile.conditions.soil_moisture - 0.42).abs() < 0.001);
+
+        // Aliases are phase-scoped like the fields they point to
+        let mut other = make_test_tile();
+        assert_eq!(apply_mutations(&mut other, &mutations, Phase::Weather), 0);
+    }
+
+    #[test]
+    fn field_aliases_point_to_writable_fields() {
+        for alias in FIELD_ALIASES {
+            assert!(
+                resolve_field_alias(alias.new_name, alias.phase).is_none(),
+                "alias target '{}' is itself aliased",
+                alias.new_name
+            );
+            let mut tile = make_test_tile();
+            let mutations = TileMutations {
+                mutations: vec![(alias.old_name.to_string(), Dynamic::from(0.5_f64))],
+                ..Default::default()
+            };
+            assert_eq!(
+                apply_mutations(&mut tile, &mutations, alias.phase),
+                1,
+                "alias '{}' -> '{}' does not apply",
+                alias.old_name,
+                alias.new_name
+            );
+        }
+    }
+
+    #[test]
+    fn scan_reports_alias_usages() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "conditions",
+            &[("01-old.rhai", "let m = 0.5;\nset(\"moisture\", m);\nset(\"snow_depth\", 0.0);")],
+        );
+        // Weather rules writing "moisture" aren't using a conditions alias
+        make_rule_dir(dir.path(), "weather", &[("01-w.rhai", "set(\"moisture\", 1.0);")]);
+
+        let usages = scan_alias_usages(dir.path()).unwrap();
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].line, 2);
+        assert_eq!(usages[0].alias.new_name, "soil_moisture");
+        assert!(usages[0].rule_path.ends_with("01-old.rhai"));
+    }
+
+    #[test]
+    fn scan_finds_literal_set_calls() {
+        let engine = sandboxed_engine(&Arc::default());
+        let ast = engine.compile("set(\"a\", 1.0);\nif true { set( \"b\", 2.0) }").unwrap();
+        assert_eq!(literal_set_calls(&ast), vec![(1, "a".to_string()), (2, "b".to_string())]);
+        let ast = engine
+            .compile("let m = #{}; m.set(\"b\", 1); let name = \"c\"; set(name, 1.0); // set(\"a\", 1.0)")
+            .unwrap();
+        assert!(literal_set_calls(&ast).is_empty());
+
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "resources", &[("01-r.rhai", "\nset(\"iron.quantity\", 1.0);")]);
+        let calls = scan_set_calls(dir.path()).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!((calls[0].line, calls[0].phase), (2, Phase::Resources));
+        assert_eq!(calls[0].field, "iron.quantity");
+    }
+
+    #[test]
+    fn unwritable_set_calls_found_at_load() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "conditions",
+            &[("01-typo.rhai", "set(\"snow_depth\", 0.0);\nset(\"soil_moistrue\", 0.5);\nset(\"temperature\", 280.0);")],
+        );
+
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let calls = engine.unwritable_set_calls();
+        let fields: Vec<_> = calls.iter().map(|c| (c.line, c.field.as_str())).collect();
+        assert_eq!(fields, vec![(2, "soil_moistrue"), (3, "temperature")]);
+        assert!(calls[0].unwritable_message().contains("01-typo.rhai:2: set(\"soil_moistrue\")"));
+
+        // Strict mode refuses a reload that adds one and keeps the old rules
+        make_rule_dir(dir.path(), "conditions", &[("01-typo.rhai", "set(\"snow_depth\", 0.0);")]);
+        engine.set_strict(true);
+        assert_eq!(engine.reload_rules().unwrap(), 1);
+        make_rule_dir(dir.path(), "weather", &[("01-w.rhai", "set(\"snow_depth\", 0.0);")]);
+        assert!(engine.reload_rules().unwrap_err().to_string().contains("not a field writable in the weather phase"));
+        assert_eq!(engine.rule_count(), 1);
+    }
+
+    #[test]
+    fn settable_fields_checked_without_a_tile() {
+        assert!(is_settable_field("soil_moisture", Phase::Conditions));
+        assert!(is_settable_field("moisture", Phase::Conditions));
+        assert!(is_settable_field("scratch.flow", Phase::Conditions));
+        assert!(is_settable_field("iron.quantity", Phase::Resources));
+        assert!(!is_settable_field("soil_moistrue", Phase::Conditions));
+        assert!(!is_settable_field("temperature", Phase::Conditions));
+        assert!(!is_settable_field("iron.depth", Phase::Resources));
+    }
+
+    #[test]
+    fn syntax_errors_reported_for_every_script() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "weather", &[("01-bad.rhai", "let x = ;"), ("02-ok.rhai", "let y = 1;")]);
+        make_rule_dir(dir.path(), "terrain", &[("01-bad.rhai", "if {")]);
+
+        let errors = rule_syntax_errors(dir.path()).unwrap();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors.iter().all(|e| e.contains("01-bad.rhai")));
+    }
+
+    #[test]
+    fn rule_stats_count_calls_errors_and_survive_reload() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[("01-ok.rhai", "set(\"temperature\", 280.0);"), ("02-fails.rhai", "let x = undefined_var;")],
+        );
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let tile = make_test_tile();
+        for tick in 0..3 {
+            assert!(engine.evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, tick, 42).is_err());
+        }
+
+        let before = engine.rule_stats();
+        let ok = before.rules["weather/01-ok.rhai"];
+        let fails = before.rules["weather/02-fails.rhai"];
+        assert_eq!((ok.invocations, ok.errors), (3, 0));
+        assert_eq!((fails.invocations, fails.errors), (3, 3));
+        assert_eq!(before.slowest().len(), 2);
+
+        // Counting carries on across a reload; a removed rule drops out
+        fs::remove_file(dir.path().join("weather/02-fails.rhai")).unwrap();
+        engine.reload_rules().unwrap();
+        engine.evaluate_tile(Phase::Weather, &tile, &[], &Season::Spring, 3, 42).unwrap();
+        let after = engine.rule_stats();
+        assert_eq!(after.rules.len(), 1);
+        assert_eq!(after.rules["weather/01-ok.rhai"].invocations, 4);
+        let delta = after.since(&before);
+        assert_eq!(delta.rules["weather/01-ok.rhai"].invocations, 1);
+        assert!(!delta.rules.contains_key("weather/02-fails.rhai"));
+    }
+
     #[test]
     fn multiple_rules_last_write_wins() {
         let dir = TempDir::new().unwrap();
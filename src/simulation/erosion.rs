@@ -0,0 +1,520 @@
+//! Erosion, landslides and sediment transport, run natively after the Terrain phase.
+//!
+//! Soil creeps down every slope a little each tick: faster on steep, wet,
+//! bare ground and on loose soils, barely at all under dense vegetation or on
+//! bare rock. Creep moves elevation from a tile to its lowest neighbor.
+//!
+//! Roots hold slopes together. `root_strength` follows vegetation density,
+//! growing back slowly and rotting away over a few dozen ticks once the
+//! cover is gone. A steep, waterlogged slope whose roots have decayed (after
+//! a fire or blight, or one that was never vegetated) gives way in a
+//! landslide: a slab of the slope drops onto the tile below, burying its
+//! vegetation in mud.
+//!
+//! Rivers cut into their beds too, hardest where a large `river_flow` runs
+//! down a steep, bare slope. The cut material travels downstream as
+//! sediment until the river slows: wherever the flow and slope can no longer
+//! carry it, it settles, and a basin with no outlet keeps all of it until it
+//! fills to its rim. Strong winds strip dry, bare, loose soil and drop it on
+//! the tile downwind. Deposits build up `geology.sediment`; a deep enough
+//! layer of river silt turns the soil to Silt, and of blown sand to Sand.
+//! Over thousands of ticks mountains wear down and valleys fill in.
+//!
+//! Terrain types are left as they are; only elevation and soil move.
+
+use serde::Serialize;
+
+use crate::simulation::sphere_math::{direction_on_sphere, tangent_to_bearing};
+use crate::world::tile::{SoilType, TerrainType};
+use crate::world::{Tile, World};
+
+/// Elevation drop to the lowest neighbor that counts as a full-strength slope.
+pub(crate) const FULL_SLOPE_DROP: f32 = 0.3;
+/// Elevation crept per tick on a full-strength, saturated, bare slope.
+const CREEP_RATE: f32 = 0.00005;
+/// Root strength regained per tick while vegetation is denser than the roots.
+const ROOT_GROWTH: f32 = 0.005;
+/// Root strength lost per tick while vegetation is sparser than the roots.
+const ROOT_DECAY: f32 = 0.02;
+/// Instability at which a slope fails.
+pub const LANDSLIDE_THRESHOLD: f32 = 0.5;
+/// Largest elevation a single landslide moves.
+const SLIDE_DEPTH: f32 = 0.05;
+/// Mud added to the tile a landslide lands on.
+const SLIDE_MUD: f32 = 0.3;
+/// Fraction of the vegetation left on the tile a landslide lands on.
+const SLIDE_BURIAL: f32 = 0.3;
+/// Fraction of the vegetation left on the tile that slid.
+const SLIDE_SCOUR: f32 = 0.5;
+/// Elevation a river with flow 1.0 cuts per tick on a full-strength, bare slope.
+const FLUVIAL_RATE: f32 = 0.0002;
+/// Sediment a river can carry per unit of flow on a full-strength slope.
+const TRANSPORT_CAPACITY: f32 = 0.001;
+/// Wind speed (m/s) above which dry, bare, loose soil blows away.
+const DEFLATION_WIND: f32 = 10.0;
+/// Elevation stripped per tick for each m/s of wind over the threshold.
+const DEFLATION_RATE: f32 = 0.000002;
+/// Soil moisture at which the ground is too damp to blow away.
+const DEFLATION_DAMP_SOIL: f32 = 0.2;
+/// Sediment depth at which a deposit takes over the tile's soil type.
+const DEPOSIT_SOIL_DEPTH: f32 = 0.002;
+
+/// A slope that gave way this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LandslideEvent {
+    pub tile_id: u32,
+    /// Tile the slide came down on
+    pub downslope_tile_id: u32,
+    /// Elevation moved downslope
+    pub depth: f32,
+}
+
+/// Erosion settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Erosion {
+    /// Let steep, wet slopes with weak roots fail as landslides.
+    pub landslides: bool,
+    /// Let rivers and wind carry sediment and lay it down elsewhere.
+    pub sediment: bool,
+}
+
+/// How readily a soil moves downslope.
+fn soil_mobility(soil: SoilType) -> f32 {
+    match soil {
+        SoilType::Sand => 1.2,
+        SoilType::Silt => 1.1,
+        SoilType::Loam => 1.0,
+        SoilType::Clay => 0.8,
+        SoilType::Rock => 0.1,
+    }
+}
+
+/// The lowest neighbor and the steepness of the drop to it (0.0-1.0), or
+/// `None` when no neighbor is lower.
+pub fn downslope(world: &World, tile: &Tile) -> Option<(usize, f32)> {
+    let (lowest, elevation) = tile
+        .neighbors
+        .iter()
+        .map(|&n| n as usize)
+        .filter(|&n| n < world.tiles.len())
+        .map(|n| (n, world.tiles[n].geology.elevation))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    let drop = tile.geology.elevation - elevation;
+    (drop > 0.0).then(|| (lowest, (drop / FULL_SLOPE_DROP).min(1.0)))
+}
+
+/// How waterlogged the ground is (0.0-1.0).
+fn wetness(tile: &Tile) -> f32 {
+    tile.conditions.soil_moisture.max(tile.conditions.mud_level).clamp(0.0, 1.0)
+}
+
+/// How close a slope is to failing; it slides at `LANDSLIDE_THRESHOLD`.
+pub fn instability(tile: &Tile, slope: f32) -> f32 {
+    slope * wetness(tile) * (1.0 - tile.biome.root_strength) * soil_mobility(tile.geology.soil_type)
+}
+
+/// Elevation crept downslope this tick.
+pub fn creep(tile: &Tile, slope: f32) -> f32 {
+    let cover = tile.biome.vegetation_density.max(tile.biome.root_strength);
+    CREEP_RATE
+        * slope
+        * (0.3 + 0.7 * wetness(tile))
+        * (1.0 - cover).powi(2)
+        * soil_mobility(tile.geology.soil_type)
+}
+
+/// Elevation a river cuts from its bed this tick.
+pub fn fluvial_incision(tile: &Tile, slope: f32) -> f32 {
+    let cover = tile.biome.vegetation_density.max(tile.biome.root_strength).clamp(0.0, 1.0);
+    FLUVIAL_RATE
+        * tile.conditions.river_flow.max(0.0).sqrt()
+        * slope
+        * (1.0 - 0.8 * cover)
+        * soil_mobility(tile.geology.soil_type)
+}
+
+/// Sediment a tile's river can carry on to the next tile.
+pub fn transport_capacity(tile: &Tile, slope: f32) -> f32 {
+    TRANSPORT_CAPACITY * tile.conditions.river_flow.max(0.0) * slope
+}
+
+/// Elevation the wind strips from a tile this tick: only dry, bare, snow-free
+/// sand, silt or loam in a strong wind gives any up.
+pub fn deflation(tile: &Tile) -> f32 {
+    let loose = matches!(tile.geology.soil_type, SoilType::Sand | SoilType::Silt | SoilType::Loam);
+    let excess = tile.weather.wind_speed - DEFLATION_WIND;
+    if !loose || excess <= 0.0 || tile.conditions.snow_depth > 0.0 {
+        return 0.0;
+    }
+    let dryness = (1.0 - tile.conditions.soil_moisture / DEFLATION_DAMP_SOIL).max(0.0);
+    let bare = 1.0 - tile.biome.vegetation_density.max(tile.biome.root_strength).clamp(0.0, 1.0);
+    DEFLATION_RATE * excess * dryness * bare * bare
+}
+
+/// The neighbor the wind blows most directly toward, on worlds with real
+/// latitudes and longitudes. `None` when no neighbor lies downwind.
+fn downwind(world: &World, tile: &Tile) -> Option<usize> {
+    let angle_off = |n: &Tile| {
+        let (east, north) =
+            direction_on_sphere(tile.position.lat, tile.position.lon, n.position.lat, n.position.lon);
+        let diff = (tangent_to_bearing(east, north) - tile.weather.wind_direction as f64).rem_euclid(360.0);
+        diff.min(360.0 - diff)
+    };
+    tile.neighbors
+        .iter()
+        .map(|&n| n as usize)
+        .filter(|&n| n < world.tiles.len())
+        .map(|n| (n, angle_off(&world.tiles[n])))
+        .filter(|&(_, off)| off < 90.0)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(n, _)| n)
+}
+
+/// Take up to `depth` off a tile, from its loose sediment first.
+fn remove(tile: &mut Tile, depth: f32) {
+    tile.geology.elevation -= depth;
+    tile.geology.sediment = (tile.geology.sediment - depth).max(0.0);
+}
+
+/// Lay `depth` of sediment on a tile; a thick enough layer becomes its soil.
+fn deposit(tile: &mut Tile, depth: f32, soil: SoilType) {
+    if depth <= 0.0 {
+        return;
+    }
+    let g = &mut tile.geology;
+    g.elevation = (g.elevation + depth).min(1.0);
+    g.sediment += depth;
+    if g.sediment >= DEPOSIT_SOIL_DEPTH {
+        g.soil_type = soil;
+    }
+}
+
+/// Next tick's root strength: roots grow back slowly and rot faster.
+pub fn next_root_strength(tile: &Tile) -> f32 {
+    let (roots, density) = (tile.biome.root_strength, tile.biome.vegetation_density);
+    if density > roots {
+        (roots + ROOT_GROWTH).min(density)
+    } else {
+        (roots - ROOT_DECAY).max(density)
+    }
+}
+
+impl Erosion {
+    /// Update root strength, creep soil downslope and trigger landslides on
+    /// every unfrozen land tile, then move river and wind sediment when
+    /// enabled. Nothing is deposited on frozen tiles or in the ocean. Returns
+    /// the landslides that happened this tick.
+    pub fn apply(&self, world: &mut World) -> Vec<LandslideEvent> {
+        let is_land = |t: &Tile| t.geology.terrain_type != TerrainType::Ocean;
+        for tile in &mut world.tiles {
+            if is_land(tile) && !world.frozen_tiles.contains(&tile.id) {
+                tile.biome.root_strength = next_root_strength(tile);
+            }
+        }
+
+        // Work out every move against the same elevations, then apply them
+        let mut moves: Vec<(usize, usize, f32, bool)> = Vec::new();
+        for (i, tile) in world.tiles.iter().enumerate() {
+            if !is_land(tile) || world.frozen_tiles.contains(&tile.id) {
+                continue;
+            }
+            let Some((lower, slope)) = downslope(world, tile) else {
+                continue;
+            };
+            // Never cut a tile below the one it drains to
+            let half_drop = (tile.geology.elevation - world.tiles[lower].geology.elevation) / 2.0;
+            if self.landslides && instability(tile, slope) >= LANDSLIDE_THRESHOLD {
+                moves.push((i, lower, SLIDE_DEPTH.min(half_drop), true));
+            } else {
+                moves.push((i, lower, creep(tile, slope).min(half_drop), false));
+            }
+        }
+
+        let mut events = Vec::new();
+        for (from, to, depth, slide) in moves {
+            world.tiles[from].geology.elevation -= depth;
+            if slide {
+                world.tiles[from].biome.vegetation_density *= SLIDE_SCOUR;
+                events.push(LandslideEvent {
+                    tile_id: world.tiles[from].id,
+                    downslope_tile_id: world.tiles[to].id,
+                    depth,
+                });
+            }
+            let below = &world.tiles[to];
+            if !is_land(below) || world.frozen_tiles.contains(&below.id) {
+                continue;
+            }
+            let below = &mut world.tiles[to];
+            below.geology.elevation = (below.geology.elevation + depth).min(1.0);
+            if slide {
+                below.biome.vegetation_density *= SLIDE_BURIAL;
+                below.biome.vegetation_health *= SLIDE_BURIAL;
+                below.conditions.mud_level = (below.conditions.mud_level + SLIDE_MUD).min(1.0);
+            }
+        }
+        if self.sediment {
+            transport_sediment(world);
+            blow_sediment(world);
+        }
+        events
+    }
+}
+
+/// Cut river beds and carry the sediment downstream, from the highest tiles
+/// down, dropping whatever the river can no longer carry.
+fn transport_sediment(world: &mut World) {
+    let n = world.tiles.len();
+    let is_land = |t: &Tile| t.geology.terrain_type != TerrainType::Ocean;
+    let open = |world: &World, i: usize| is_land(&world.tiles[i]) && !world.frozen_tiles.contains(&(i as u32));
+    let mut order: Vec<usize> = (0..n).filter(|&i| is_land(&world.tiles[i])).collect();
+    order.sort_by(|&a, &b| world.tiles[b].geology.elevation.total_cmp(&world.tiles[a].geology.elevation));
+
+    let mut carried = vec![0.0_f32; n];
+    for i in order {
+        let tile = &world.tiles[i];
+        let Some((lower, slope)) = downslope(world, tile) else {
+            // A basin keeps everything, up to the rim
+            let rim = tile
+                .neighbors
+                .iter()
+                .filter_map(|&n| world.tiles.get(n as usize))
+                .map(|t| t.geology.elevation)
+                .fold(f32::MAX, f32::min);
+            if open(world, i) {
+                let room = (rim - tile.geology.elevation).max(0.0);
+                deposit(&mut world.tiles[i], carried[i].min(room), SoilType::Silt);
+            }
+            continue;
+        };
+        if open(world, i) {
+            let half_drop = (tile.geology.elevation - world.tiles[lower].geology.elevation) / 2.0;
+            let cut = fluvial_incision(tile, slope).min(half_drop);
+            let capacity = transport_capacity(tile, slope);
+            remove(&mut world.tiles[i], cut);
+            carried[i] += cut;
+            let dropped = (carried[i] - capacity).max(0.0);
+            deposit(&mut world.tiles[i], dropped, SoilType::Silt);
+            carried[i] -= dropped;
+        }
+        // Sediment reaching the ocean is lost
+        if is_land(&world.tiles[lower]) {
+            carried[lower] += carried[i];
+        }
+    }
+}
+
+/// Strip loose soil from dry, bare, windy tiles and drop it on the tile
+/// downwind. Tiles with nothing downwind of them are sheltered.
+fn blow_sediment(world: &mut World) {
+    let has_geo = world.tiles.iter().any(|t| t.position.lat != 0.0 || t.position.lon != 0.0);
+    if !has_geo {
+        return;
+    }
+    let mut moves = Vec::new();
+    for (i, tile) in world.tiles.iter().enumerate() {
+        if tile.geology.terrain_type == TerrainType::Ocean || world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        let depth = deflation(tile);
+        if depth > 0.0
+            && let Some(to) = downwind(world, tile)
+        {
+            moves.push((i, to, depth));
+        }
+    }
+    for (from, to, depth) in moves {
+        remove(&mut world.tiles[from], depth);
+        let below = &world.tiles[to];
+        if below.geology.terrain_type != TerrainType::Ocean && !world.frozen_tiles.contains(&below.id) {
+            deposit(&mut world.tiles[to], depth, SoilType::Sand);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    /// A slope (0) above a valley floor (1), both loam.
+    fn slope(drop: f32) -> World {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = (0..2)
+            .map(|id| Tile::new_default(id, vec![1 - id], Position::flat(id as f64, 0.0)))
+            .collect();
+        world.tiles[0].geology.elevation = 0.2 + drop;
+        world.tiles[1].geology.elevation = 0.2;
+        for t in &mut world.tiles {
+            t.conditions.soil_moisture = 0.9;
+            t.biome.vegetation_density = 0.8;
+            t.biome.root_strength = 0.8;
+        }
+        world
+    }
+
+    #[test]
+    fn vegetation_slows_creep() {
+        let world = slope(0.3);
+        let forested = &world.tiles[0];
+        let (_, steepness) = downslope(&world, forested).unwrap();
+        assert_eq!(steepness, 1.0);
+
+        let mut bare = forested.clone();
+        bare.biome.vegetation_density = 0.0;
+        bare.biome.root_strength = 0.0;
+        assert!(creep(&bare, 1.0) > 10.0 * creep(forested, 1.0));
+        let mut rock = bare.clone();
+        rock.geology.soil_type = SoilType::Rock;
+        assert!(creep(&rock, 1.0) < creep(&bare, 1.0) / 5.0);
+        // Gentle slopes creep less
+        assert!(creep(&bare, 0.2) < creep(&bare, 1.0));
+    }
+
+    #[test]
+    fn roots_rot_after_cover_is_lost() {
+        let mut tile = slope(0.3).tiles[0].clone();
+        tile.biome.vegetation_density = 0.0;
+        let mut ticks = 0;
+        while tile.biome.root_strength > 0.0 {
+            tile.biome.root_strength = next_root_strength(&tile);
+            ticks += 1;
+        }
+        assert!((40..=41).contains(&ticks), "{}", ticks);
+        tile.biome.vegetation_density = 0.5;
+        assert_eq!(next_root_strength(&tile), ROOT_GROWTH);
+    }
+
+    #[test]
+    fn deforested_wet_slope_slides_onto_tile_below() {
+        let mut world = slope(0.3);
+        let erosion = Erosion { landslides: true, sediment: false };
+        // Rooted: only creep
+        assert!(erosion.apply(&mut world).is_empty());
+
+        // Burnt off and the roots have rotted
+        world.tiles[0].biome.vegetation_density = 0.0;
+        world.tiles[0].biome.root_strength = 0.0;
+        let (top, bottom) = (world.tiles[0].geology.elevation, world.tiles[1].geology.elevation);
+        let events = erosion.apply(&mut world);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].downslope_tile_id, 1);
+        assert!((world.tiles[0].geology.elevation - (top - SLIDE_DEPTH)).abs() < 1e-6);
+        assert!((world.tiles[1].geology.elevation - (bottom + SLIDE_DEPTH)).abs() < 1e-6);
+        assert!(world.tiles[1].biome.vegetation_density < 0.3);
+        assert!(world.tiles[1].conditions.mud_level >= SLIDE_MUD);
+
+        // Dry slopes hold, and landslides can be turned off
+        let mut dry = slope(0.3);
+        dry.tiles[0].biome.root_strength = 0.0;
+        dry.tiles[0].biome.vegetation_density = 0.0;
+        let mut disabled = dry.clone();
+        dry.tiles[0].conditions.soil_moisture = 0.2;
+        assert!(erosion.apply(&mut dry).is_empty());
+        assert!(Erosion { landslides: false, sediment: false }.apply(&mut disabled).is_empty());
+    }
+
+    #[test]
+    fn frozen_and_ocean_tiles_receive_nothing() {
+        let mut world = slope(0.3);
+        world.tiles[0].biome.root_strength = 0.0;
+        world.tiles[0].biome.vegetation_density = 0.0;
+        world.frozen_tiles.insert(1);
+        let bottom = world.tiles[1].geology.elevation;
+        let top = world.tiles[0].geology.elevation;
+        Erosion { landslides: true, sediment: false }.apply(&mut world);
+        assert_eq!(world.tiles[1].geology.elevation, bottom);
+        assert!(world.tiles[0].geology.elevation < top);
+
+        world.frozen_tiles.clear();
+        world.frozen_tiles.insert(0);
+        let top = world.tiles[0].geology.elevation;
+        Erosion { landslides: true, sediment: false }.apply(&mut world);
+        assert_eq!(world.tiles[0].geology.elevation, top);
+    }
+
+    /// A river valley of `n` bare loam tiles in a line, each 5° east of the
+    /// last and lower than it, with the flow growing downstream.
+    fn valley(n: u32) -> World {
+        let mut world = slope(0.3);
+        world.tiles = (0..n)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id + 1 < n).then_some(id + 1)];
+                let mut position = Position::flat(id as f64, 0.0);
+                position.lon = 5.0 * id as f64;
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), position);
+                t.geology.elevation = 0.8 - 0.15 * id as f32;
+                t.conditions.river_flow = 1.0 + id as f32;
+                t.conditions.soil_moisture = 0.5;
+                t.biome.vegetation_density = 0.0;
+                t.biome.root_strength = 0.0;
+                t
+            })
+            .collect();
+        world
+    }
+
+    #[test]
+    fn rivers_wear_mountains_down_and_fill_basins_with_silt() {
+        let mut world = valley(5);
+        // The foot of the valley is a hollow below its only neighbor
+        world.tiles[4].geology.elevation = 0.02;
+        world.tiles[3].geology.elevation = 0.1;
+        let erosion = Erosion { landslides: false, sediment: true };
+        let before: Vec<f32> = world.tiles.iter().map(|t| t.geology.elevation).collect();
+        for _ in 0..50 {
+            erosion.apply(&mut world);
+        }
+        let top = &world.tiles[0].geology;
+        assert!(top.elevation < before[0] && top.sediment == 0.0, "{:?}", top);
+        let basin = &world.tiles[4].geology;
+        assert!(basin.elevation > before[4], "{:?}", basin);
+        assert!(basin.sediment >= DEPOSIT_SOIL_DEPTH);
+        assert_eq!(basin.soil_type, SoilType::Silt);
+
+        // Forest cover holds the bed
+        let mut bare = valley(5).tiles.remove(1);
+        let mut forest = bare.clone();
+        forest.biome.vegetation_density = 0.9;
+        assert!(fluvial_incision(&forest, 1.0) < fluvial_incision(&bare, 1.0) / 3.0);
+        bare.conditions.river_flow = 0.0;
+        assert_eq!(fluvial_incision(&bare, 1.0), 0.0);
+    }
+
+    #[test]
+    fn wind_blows_dry_bare_soil_downwind_as_sand() {
+        let mut world = valley(3);
+        for t in &mut world.tiles {
+            t.geology.elevation = 0.3;
+            t.conditions.river_flow = 0.0;
+            t.conditions.soil_moisture = 0.0;
+            t.weather.wind_speed = 20.0;
+            t.weather.wind_direction = 90.0;
+        }
+        assert!(deflation(&world.tiles[1]) > 0.0);
+        let mut damp = world.tiles[1].clone();
+        damp.conditions.soil_moisture = 0.3;
+        let mut grassy = world.tiles[1].clone();
+        grassy.biome.vegetation_density = 1.0;
+        assert_eq!(deflation(&damp), 0.0);
+        assert_eq!(deflation(&grassy), 0.0);
+
+        // Blowing east: the first tile only loses and the last, with nothing
+        // downwind, only gains
+        let erosion = Erosion { landslides: false, sediment: true };
+        for _ in 0..200 {
+            erosion.apply(&mut world);
+        }
+        assert!(world.tiles[0].geology.elevation < 0.3);
+        assert_eq!(world.tiles[0].geology.sediment, 0.0);
+        assert!(world.tiles[2].geology.elevation > 0.3);
+        assert_eq!(world.tiles[2].geology.soil_type, SoilType::Sand);
+    }
+}
@@ -0,0 +1,148 @@
+//! Discrete world events.
+//!
+//! Tile diffs say what each tile looks like now; events say what happened.
+//! The simulation raises a few itself: a volcano erupting, a hurricane
+//! coming ashore, a tile's biome flipping, a drought being declared. Rules
+//! raise their own with `emit_event(kind)` or `emit_event(kind, data)`, tied
+//! to the tile and rule that emitted them; a tile whose rule fails loses its
+//! events along with its mutations. Each tick's events come back in its
+//! `TickResult`, are streamed to clients as an `Events` message and, with
+//! `event_log` on, appended to `events.jsonl` in the snapshot directory.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub use worldground_protocol::{EventValue, WorldEvent};
+
+use crate::simulation::narration::SEVERE_DROUGHT_DAYS;
+use crate::world::tile::BiomeType;
+use crate::world::World;
+
+/// Name of the event log in the snapshot directory.
+pub const EVENT_LOG: &str = "events.jsonl";
+
+/// Most events a tick keeps, so a rule emitting on every tile can't flood
+/// clients; later ones are dropped.
+pub const MAX_EVENTS_PER_TICK: usize = 1000;
+
+/// An event the simulation raises itself on `tile`. Its tick is filled in
+/// when the tick ends.
+pub fn native(kind: &str, tile: u32) -> WorldEvent {
+    WorldEvent {
+        kind: kind.to_string(),
+        tick: 0,
+        tile: Some(tile),
+        source: WorldEvent::NATIVE.to_string(),
+        data: BTreeMap::new(),
+    }
+}
+
+/// One `emit_event()` call, before it is tied to its tile and rule name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmittedEvent {
+    pub kind: String,
+    pub data: BTreeMap<String, EventValue>,
+    /// Index into the phase's rule list of the rule that emitted it
+    pub rule: usize,
+}
+
+/// Each tile's biome and drought days at the start of a tick, to find the
+/// biome flips and declared droughts at its end.
+pub struct Baseline {
+    biomes: Vec<BiomeType>,
+    drought_days: Vec<u32>,
+}
+
+impl Baseline {
+    pub fn of(world: &World) -> Self {
+        Baseline {
+            biomes: world.tiles.iter().map(|t| t.biome.biome_type).collect(),
+            drought_days: world.tiles.iter().map(|t| t.conditions.drought_days).collect(),
+        }
+    }
+
+    /// Biome flips and droughts declared since the baseline, in tile order.
+    /// A drought is declared when a tile's drought days reach
+    /// `SEVERE_DROUGHT_DAYS`.
+    pub fn changes(&self, world: &World) -> Vec<WorldEvent> {
+        let mut events = Vec::new();
+        for ((tile, &biome), &drought_days) in world.tiles.iter().zip(&self.biomes).zip(&self.drought_days) {
+            if tile.biome.biome_type != biome {
+                let mut event = native(WorldEvent::BIOME_FLIP, tile.id);
+                event.data.insert("from".to_string(), EventValue::Text(format!("{:?}", biome)));
+                event.data.insert("to".to_string(), EventValue::Text(format!("{:?}", tile.biome.biome_type)));
+                events.push(event);
+            }
+            if drought_days < SEVERE_DROUGHT_DAYS && tile.conditions.drought_days >= SEVERE_DROUGHT_DAYS {
+                events.push(native(WorldEvent::DROUGHT_DECLARED, tile.id));
+            }
+        }
+        events
+    }
+}
+
+/// Append-only log of world events, one JSON line per event.
+pub struct EventLog {
+    path: PathBuf,
+    file: File,
+}
+
+impl EventLog {
+    /// Open (or start) the log in `snapshot_dir` for appending.
+    pub fn open(snapshot_dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(snapshot_dir)?;
+        let path = snapshot_dir.join(EVENT_LOG);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(EventLog { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a tick's events.
+    pub fn append(&mut self, events: &[WorldEvent]) -> io::Result<()> {
+        let mut lines = String::new();
+        for event in events {
+            lines.push_str(&serde_json::to_string(event).map_err(io::Error::other)?);
+            lines.push('\n');
+        }
+        self.file.write_all(lines.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+
+    #[test]
+    fn biome_flips_and_droughts_are_found_and_logged() {
+        let mut world = test_world(100);
+        world.tiles[3].conditions.drought_days = SEVERE_DROUGHT_DAYS - 1;
+        world.tiles[4].conditions.drought_days = SEVERE_DROUGHT_DAYS;
+        world.tiles[8].biome.biome_type = BiomeType::Grassland;
+        let baseline = Baseline::of(&world);
+        assert!(baseline.changes(&world).is_empty());
+
+        world.tiles[3].conditions.drought_days += 1;
+        world.tiles[4].conditions.drought_days += 1;
+        world.tiles[8].biome.biome_type = BiomeType::Desert;
+        let events = baseline.changes(&world);
+        assert_eq!(events.len(), 2, "an ongoing drought isn't declared again: {:?}", events);
+        assert_eq!((events[0].kind.as_str(), events[0].tile), (WorldEvent::DROUGHT_DECLARED, Some(3)));
+        assert_eq!((events[1].kind.as_str(), events[1].tile), (WorldEvent::BIOME_FLIP, Some(8)));
+        assert_eq!(events[1].data["from"], EventValue::Text("Grassland".to_string()));
+        assert_eq!(events[1].data["to"], EventValue::Text("Desert".to_string()));
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = EventLog::open(dir.path()).unwrap();
+        log.append(&events).unwrap();
+        log.append(&events[..1]).unwrap();
+        let text = fs::read_to_string(log.path()).unwrap();
+        let logged: Vec<WorldEvent> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(logged, [events.clone(), events[..1].to_vec()].concat());
+    }
+}
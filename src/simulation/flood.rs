@@ -0,0 +1,307 @@
+//! Flooding, computed natively at the end of the Conditions phase.
+//!
+//! A tile floods when the water reaching it (rain, sleet, snowmelt) exceeds
+//! what the ground can take: its drainage plus the room left in the soil,
+//! much reduced while frozen. Low, flat terrain holds floodwater; slopes shed
+//! it. Floods recede over a few ticks, damaging vegetation while they last
+//! and leaving silt behind as they drain, which feeds vegetation growth.
+//!
+//! Coast tiles also take storm surge: when a strong storm or deep low sits
+//! over an exposed coastline, wind drives seawater ashore, more so over a
+//! shallow shelf. Surge adds to flood_level and recedes like any other flood.
+//!
+//! Rivers that overtop their banks (see `hydrology`) add their spill to the
+//! water reaching the tile.
+
+
+use super::mud::liquid_water;
+use crate::world::tile::TerrainType;
+use crate::world::{Tile, World};
+
+/// Water the soil can drain per tick at drainage 1.0.
+const DRAINAGE_CAPACITY: f32 = 0.5;
+/// Extra water a completely dry soil can absorb per tick.
+const SOIL_ABSORPTION: f32 = 0.3;
+/// Frozen ground takes this fraction of its usual capacity.
+const FROZEN_CAPACITY: f32 = 0.2;
+/// Fraction of excess water that becomes standing floodwater.
+const FLOOD_RATE: f32 = 0.5;
+/// Fraction of floodwater left after a tick.
+const RECESSION: f32 = 0.8;
+/// Floodwater below this drains away completely.
+const FLOOD_FLOOR: f32 = 0.01;
+/// Vegetation health lost per tick at flood_level 1.0.
+const VEGETATION_DAMAGE: f32 = 0.05;
+/// Silt deposited per unit of receding floodwater.
+const SILT_PER_FLOOD: f32 = 0.5;
+/// Fraction of silt fertility left after a tick.
+const SILT_RETENTION: f32 = 0.998;
+/// Storm intensity (or equivalent low-pressure drive) needed for surge.
+const SURGE_STORM_THRESHOLD: f32 = 0.4;
+/// Pressure deficit (hPa) that counts as a full-strength storm.
+const SURGE_FULL_DEFICIT: f32 = 30.0;
+/// Wind speed giving full surge; stronger wind adds up to 25% more.
+const SURGE_REFERENCE_WIND: f32 = 20.0;
+/// Flood added per tick by a full-strength storm on a fully exposed coast.
+const SURGE_RATE: f32 = 0.6;
+/// A tile crossing this flood level emits a flood event.
+pub const FLOOD_EVENT_THRESHOLD: f32 = 0.3;
+
+/// A tile whose flood level rose past `FLOOD_EVENT_THRESHOLD` this tick
+/// (a wire protocol type).
+pub use worldground_protocol::FloodEvent;
+
+/// How much of the excess water a terrain holds as standing floodwater.
+fn ponding(terrain: TerrainType) -> f32 {
+    match terrain {
+        TerrainType::Ocean => 0.0,
+        TerrainType::Wetlands => 1.5,
+        TerrainType::Coast => 1.2,
+        TerrainType::Plains => 1.0,
+        TerrainType::Hills => 0.5,
+        TerrainType::Mountains => 0.2,
+        TerrainType::Cliffs => 0.1,
+    }
+}
+
+/// Next tick's flood level for a tile, from its current flood and conditions
+/// and the river water spilling onto it.
+pub fn next_flood_level(tile: &Tile, overbank: f32) -> f32 {
+    let (c, g) = (&tile.conditions, &tile.geology);
+    if g.terrain_type == TerrainType::Ocean {
+        return 0.0;
+    }
+    let mut capacity =
+        g.drainage * DRAINAGE_CAPACITY + (1.0 - c.soil_moisture).max(0.0) * SOIL_ABSORPTION;
+    if c.frost_days > 0 {
+        capacity *= FROZEN_CAPACITY;
+    }
+    let excess = (liquid_water(tile) + overbank - capacity).max(0.0) * ponding(g.terrain_type);
+    let flood = (c.flood_level * RECESSION + excess * FLOOD_RATE).clamp(0.0, 1.0);
+    if flood < FLOOD_FLOOR { 0.0 } else { flood }
+}
+
+/// How exposed each tile is to storm surge (0.0-1.0). Only Coast tiles are
+/// exposed: the share of their neighbors that are ocean, weighted toward
+/// shallow water. Ocean depth is relative to the world's deepest and
+/// shallowest ocean tiles, as there is no separate bathymetry.
+pub fn surge_exposure(world: &World) -> Vec<f32> {
+    let is_ocean = |i: usize| world.tiles[i].geology.terrain_type == TerrainType::Ocean;
+    let (lo, hi) = world
+        .tiles
+        .iter()
+        .filter(|t| t.geology.terrain_type == TerrainType::Ocean)
+        .map(|t| t.geology.elevation)
+        .fold((f32::MAX, f32::MIN), |(lo, hi), e| (lo.min(e), hi.max(e)));
+    let range = (hi - lo).max(1e-6);
+
+    world
+        .tiles
+        .iter()
+        .map(|tile| {
+            if tile.geology.terrain_type != TerrainType::Coast || tile.neighbors.is_empty() {
+                return 0.0;
+            }
+            let ocean: Vec<usize> = tile
+                .neighbors
+                .iter()
+                .map(|&n| n as usize)
+                .filter(|&n| n < world.tiles.len() && is_ocean(n))
+                .collect();
+            if ocean.is_empty() {
+                return 0.0;
+            }
+            let shallowness = ocean
+                .iter()
+                .map(|&n| (world.tiles[n].geology.elevation - lo) / range)
+                .sum::<f32>()
+                / ocean.len() as f32;
+            let open_water = ocean.len() as f32 / tile.neighbors.len() as f32;
+            open_water * (0.5 + 0.5 * shallowness)
+        })
+        .collect()
+}
+
+/// Surge flooding this tick for a tile with the given exposure.
+pub fn storm_surge(tile: &Tile, exposure: f32) -> f32 {
+    let w = &tile.weather;
+    let low = ((1013.25 - w.pressure) / SURGE_FULL_DEFICIT).clamp(0.0, 1.0);
+    let drive = w.storm_intensity.max(low);
+    if exposure <= 0.0 || drive < SURGE_STORM_THRESHOLD {
+        return 0.0;
+    }
+    let strength = (drive - SURGE_STORM_THRESHOLD) / (1.0 - SURGE_STORM_THRESHOLD);
+    let wind = (w.wind_speed / SURGE_REFERENCE_WIND).min(1.25);
+    SURGE_RATE * strength * wind * exposure
+}
+
+/// Update `flood_level` (including storm surge and river spill, indexed by
+/// tile, missing entries counting as none), flood damage and silt on every
+/// unfrozen tile. Returns the tiles that started flooding this tick.
+pub fn update_floods(world: &mut World, overbank: &[f32]) -> Vec<FloodEvent> {
+    let exposure = surge_exposure(world);
+    let mut events = Vec::new();
+    for (i, tile) in world.tiles.iter_mut().enumerate() {
+        if world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        let before = tile.conditions.flood_level;
+        let spill = overbank.get(i).copied().unwrap_or(0.0);
+        let flood = (next_flood_level(tile, spill) + storm_surge(tile, exposure[i])).min(1.0);
+        let c = &mut tile.conditions;
+        c.flood_level = flood;
+
+        let receded = (before - flood).max(0.0);
+        c.silt_fertility = (c.silt_fertility * SILT_RETENTION + receded * SILT_PER_FLOOD).min(1.0);
+        if flood > 0.0 {
+            let health = &mut tile.biome.vegetation_health;
+            *health = (*health - flood * VEGETATION_DAMAGE).max(0.0);
+        }
+        if before < FLOOD_EVENT_THRESHOLD && flood >= FLOOD_EVENT_THRESHOLD {
+            events.push(FloodEvent { tile_id: tile.id, flood_level: flood });
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{Position, PrecipitationType};
+
+    fn soaked_plain(id: u32) -> Tile {
+        let mut t = Tile::new_default(id, vec![], Position::flat(0.0, 0.0));
+        t.geology.drainage = 0.2;
+        t.conditions.soil_moisture = 0.9;
+        t.weather.precipitation = 0.9;
+        t.weather.precipitation_type = PrecipitationType::Rain;
+        t
+    }
+
+    fn world_of(tiles: Vec<Tile>) -> World {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = tiles;
+        world
+    }
+
+    #[test]
+    fn heavy_rain_on_saturated_plain_floods() {
+        let plain = soaked_plain(0);
+        let flood = next_flood_level(&plain, 0.0);
+        assert!(flood >= FLOOD_EVENT_THRESHOLD, "{}", flood);
+
+        let mut mountain = plain.clone();
+        mountain.geology.terrain_type = TerrainType::Mountains;
+        assert!(next_flood_level(&mountain, 0.0) < flood / 2.0);
+
+        let mut drained = plain.clone();
+        drained.geology.drainage = 1.0;
+        drained.conditions.soil_moisture = 0.2;
+        drained.weather.precipitation = 0.5;
+        assert_eq!(next_flood_level(&drained, 0.0), 0.0);
+    }
+
+    #[test]
+    fn frozen_ground_floods_more() {
+        let mut thawed = soaked_plain(0);
+        thawed.weather.precipitation = 0.4;
+        thawed.conditions.soil_moisture = 0.5;
+        let mut frozen = thawed.clone();
+        frozen.conditions.frost_days = 5;
+        assert!(next_flood_level(&frozen, 0.0) > next_flood_level(&thawed, 0.0));
+    }
+
+    #[test]
+    fn flood_onset_emits_event_and_damages_vegetation() {
+        let mut world = world_of(vec![soaked_plain(0), soaked_plain(1)]);
+        world.tiles[1].weather.precipitation = 0.0;
+
+        let events = update_floods(&mut world, &[]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tile_id, 0);
+        assert!(world.tiles[0].biome.vegetation_health < 1.0);
+        assert_eq!(world.tiles[1].conditions.flood_level, 0.0);
+
+        // Already flooded: no second event
+        assert!(update_floods(&mut world, &[]).is_empty());
+    }
+
+    /// A coast tile (0) between open ocean (1, 2) and land (3).
+    fn coastline() -> World {
+        let mut tiles: Vec<Tile> = (0..4)
+            .map(|id| Tile::new_default(id, vec![], Position::flat(id as f64, 0.0)))
+            .collect();
+        tiles[0].geology.terrain_type = TerrainType::Coast;
+        tiles[0].neighbors = vec![1, 2, 3];
+        tiles[0].conditions.soil_moisture = 0.0;
+        tiles[0].geology.drainage = 1.0;
+        for (i, elevation) in [(1, -0.2), (2, -0.9)] {
+            tiles[i].geology.terrain_type = TerrainType::Ocean;
+            tiles[i].geology.elevation = elevation;
+        }
+        world_of(tiles)
+    }
+
+    #[test]
+    fn exposure_favors_shallow_open_coast() {
+        let mut world = coastline();
+        let exposure = surge_exposure(&world);
+        // 2 of 3 neighbors ocean, one shallowest and one deepest
+        assert!((exposure[0] - 2.0 / 3.0 * 0.75).abs() < 1e-6, "{}", exposure[0]);
+        assert_eq!(&exposure[1..], &[0.0, 0.0, 0.0]);
+
+        world.tiles[0].geology.terrain_type = TerrainType::Plains;
+        assert_eq!(surge_exposure(&world)[0], 0.0);
+    }
+
+    #[test]
+    fn landfalling_storm_surges_and_recedes() {
+        let mut world = coastline();
+        let calm = update_floods(&mut world, &[]);
+        assert!(calm.is_empty());
+        assert_eq!(world.tiles[0].conditions.flood_level, 0.0);
+
+        let w = &mut world.tiles[0].weather;
+        w.storm_intensity = 1.0;
+        w.wind_speed = 25.0;
+        let events = update_floods(&mut world, &[]);
+        let surge = world.tiles[0].conditions.flood_level;
+        assert!(surge >= FLOOD_EVENT_THRESHOLD, "{}", surge);
+        assert_eq!(events[0].tile_id, 0);
+
+        // A weak storm does nothing; the surge recedes
+        world.tiles[0].weather.storm_intensity = 0.2;
+        update_floods(&mut world, &[]);
+        assert!((world.tiles[0].conditions.flood_level - surge * RECESSION).abs() < 1e-6);
+    }
+
+    #[test]
+    fn deep_low_drives_surge_without_storm() {
+        let mut tile = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        tile.weather.wind_speed = 20.0;
+        tile.weather.pressure = 1013.25 - SURGE_FULL_DEFICIT;
+        assert!((storm_surge(&tile, 1.0) - SURGE_RATE).abs() < 1e-6);
+        assert_eq!(storm_surge(&tile, 0.0), 0.0);
+    }
+
+    #[test]
+    fn receding_flood_leaves_silt() {
+        let mut world = world_of(vec![soaked_plain(0)]);
+        update_floods(&mut world, &[]);
+        assert_eq!(world.tiles[0].conditions.silt_fertility, 0.0);
+
+        world.tiles[0].weather.precipitation = 0.0;
+        for _ in 0..30 {
+            update_floods(&mut world, &[]);
+        }
+        assert_eq!(world.tiles[0].conditions.flood_level, 0.0);
+        assert!(world.tiles[0].conditions.silt_fertility > 0.1);
+    }
+}
@@ -0,0 +1,162 @@
+//! Fog and visibility, computed natively right after the Weather phase.
+//!
+//! Fog forms in near-saturated, calm air. It is helped by anything that
+//! traps cool air at the surface: a temperature inversion under high
+//! pressure, radiative cooling under clear skies, and valleys where cold air
+//! pools below the surrounding terrain. There is no day/night cycle yet, so
+//! clear-sky cooling stands in for night. Visibility falls with fog density
+//! and precipitation.
+
+use crate::world::tile::{PrecipitationType, TerrainType};
+use crate::world::{Tile, World};
+
+/// Humidity at which fog can start to form.
+const FOG_HUMIDITY: f32 = 0.8;
+/// Wind speed that disperses fog entirely.
+const FOG_DISPERSING_WIND: f32 = 8.0;
+/// Pressure (hPa) above which a calm surface inversion sets in.
+const INVERSION_PRESSURE: f32 = 1016.0;
+/// Pressure excess (hPa) for a full-strength inversion.
+const INVERSION_FULL_EXCESS: f32 = 10.0;
+/// Elevation below the neighbor mean that counts as a full valley.
+const FULL_VALLEY_DEPTH: f32 = 0.1;
+/// Visibility in clear air, km.
+pub const CLEAR_VISIBILITY_KM: f32 = 20.0;
+/// Visibility in the thickest fog, km.
+const THICK_FOG_VISIBILITY_KM: f32 = 0.1;
+
+/// How far below its neighbors a tile sits, 0.0 (ridge or flat) to 1.0 (deep valley).
+pub fn valley_factors(world: &World) -> Vec<f32> {
+    world
+        .tiles
+        .iter()
+        .map(|tile| {
+            let neighbors: Vec<f32> = tile
+                .neighbors
+                .iter()
+                .filter_map(|&n| world.tiles.get(n as usize))
+                .map(|n| n.geology.elevation)
+                .collect();
+            if neighbors.is_empty() {
+                return 0.0;
+            }
+            let mean = neighbors.iter().sum::<f32>() / neighbors.len() as f32;
+            ((mean - tile.geology.elevation) / FULL_VALLEY_DEPTH).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+/// Fog density (0.0-1.0) for a tile's current weather and valley factor.
+pub fn fog_density(tile: &Tile, valley: f32) -> f32 {
+    let w = &tile.weather;
+    let saturation = ((w.humidity - FOG_HUMIDITY) / (1.0 - FOG_HUMIDITY)).clamp(0.0, 1.0);
+    let calm = (1.0 - w.wind_speed / FOG_DISPERSING_WIND).clamp(0.0, 1.0);
+    if saturation == 0.0 || calm == 0.0 {
+        return 0.0;
+    }
+    let inversion = ((w.pressure - INVERSION_PRESSURE) / INVERSION_FULL_EXCESS).clamp(0.0, 1.0);
+    let clear_sky = (1.0 - w.cloud_cover).clamp(0.0, 1.0);
+    let trapping = inversion.max(clear_sky * 0.7).max(valley);
+    // Open water fogs less than land at the same humidity
+    let surface = if tile.geology.terrain_type == TerrainType::Ocean { 0.6 } else { 1.0 };
+    (saturation * calm * (0.4 + 0.6 * trapping) * surface).clamp(0.0, 1.0)
+}
+
+/// Visibility (km) through the given fog density and the tile's precipitation.
+pub fn visibility_km(tile: &Tile, fog: f32) -> f32 {
+    let fog_visibility =
+        CLEAR_VISIBILITY_KM * (THICK_FOG_VISIBILITY_KM / CLEAR_VISIBILITY_KM).powf(fog);
+    let w = &tile.weather;
+    let precip_factor = match w.precipitation_type {
+        PrecipitationType::None => 1.0,
+        PrecipitationType::Snow => 1.0 - 0.8 * w.precipitation.clamp(0.0, 1.0),
+        _ => 1.0 - 0.5 * w.precipitation.clamp(0.0, 1.0),
+    };
+    fog_visibility * precip_factor
+}
+
+/// Update `fog` and `visibility_km` on every unfrozen tile.
+pub fn update_fog(world: &mut World) {
+    let valleys = valley_factors(world);
+    for (i, tile) in world.tiles.iter_mut().enumerate() {
+        if world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        let fog = fog_density(tile, valleys[i]);
+        tile.conditions.fog = fog;
+        tile.conditions.visibility_km = visibility_km(tile, fog);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    fn calm_humid() -> Tile {
+        let mut t = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        t.weather.humidity = 0.95;
+        t.weather.wind_speed = 1.0;
+        t.weather.cloud_cover = 0.8;
+        t.weather.pressure = 1013.25;
+        t
+    }
+
+    #[test]
+    fn fog_needs_humid_calm_air() {
+        let foggy = fog_density(&calm_humid(), 0.0);
+        assert!(foggy > 0.2, "{}", foggy);
+
+        let mut dry = calm_humid();
+        dry.weather.humidity = 0.6;
+        assert_eq!(fog_density(&dry, 0.0), 0.0);
+
+        let mut windy = calm_humid();
+        windy.weather.wind_speed = 10.0;
+        assert_eq!(fog_density(&windy, 0.0), 0.0);
+    }
+
+    #[test]
+    fn inversions_and_valleys_thicken_fog() {
+        let base = fog_density(&calm_humid(), 0.0);
+        let mut high = calm_humid();
+        high.weather.pressure = 1026.0;
+        assert!(fog_density(&high, 0.0) > base);
+        assert!(fog_density(&calm_humid(), 1.0) > base);
+    }
+
+    #[test]
+    fn visibility_falls_with_fog_and_precipitation() {
+        let t = calm_humid();
+        assert_eq!(visibility_km(&t, 0.0), CLEAR_VISIBILITY_KM);
+        assert!((visibility_km(&t, 1.0) - THICK_FOG_VISIBILITY_KM).abs() < 1e-4);
+        let mut snowing = t.clone();
+        snowing.weather.precipitation_type = PrecipitationType::Snow;
+        snowing.weather.precipitation = 1.0;
+        assert!(visibility_km(&snowing, 0.0) < 5.0);
+    }
+
+    #[test]
+    fn valley_factor_from_neighbor_elevation() {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = (0..3)
+            .map(|id| Tile::new_default(id, vec![], Position::flat(id as f64, 0.0)))
+            .collect();
+        world.tiles[0].neighbors = vec![1, 2];
+        world.tiles[1].neighbors = vec![0];
+        world.tiles[1].geology.elevation = 0.3;
+        world.tiles[2].geology.elevation = 0.1;
+
+        let valleys = valley_factors(&world);
+        assert_eq!(valleys[0], 1.0);
+        assert_eq!(valleys[1], 0.0);
+        assert_eq!(valleys[2], 0.0);
+    }
+}
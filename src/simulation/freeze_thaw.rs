@@ -0,0 +1,149 @@
+//! Freeze-thaw weathering, run natively at the end of the Conditions phase.
+//!
+//! A thaw is a tile whose `frost_days` run ended this tick. Each thaw counts
+//! as one freeze-thaw cycle: meltwater raises `mud_level` in proportion to how
+//! long the ground was frozen and how poorly it drains, repeated cycles open
+//! up the soil (raising `drainage`), and over many cycles rock soil cracks
+//! down to sand.
+
+use crate::world::tile::{SoilType, TerrainType};
+use crate::world::World;
+
+/// Mud added per frozen day when the ground thaws.
+const THAW_MUD_PER_FROST_DAY: f32 = 0.02;
+/// Frozen days beyond this add no extra thaw mud.
+const MAX_THAW_FROST_DAYS: u32 = 20;
+/// Drainage gained per freeze-thaw cycle.
+const DRAINAGE_PER_CYCLE: f32 = 0.001;
+/// Freeze-thaw alone never opens soil past this drainage.
+const MAX_WEATHERED_DRAINAGE: f32 = 0.8;
+/// Cycles for rock soil to break down to sand.
+pub const ROCK_TO_SAND_CYCLES: u32 = 500;
+
+/// Freeze-thaw settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FreezeThaw {
+    /// Let rock soil break down to sand after `ROCK_TO_SAND_CYCLES` cycles.
+    pub rock_to_sand: bool,
+}
+
+impl FreezeThaw {
+    /// Apply weathering to every unfrozen land tile that thawed this tick.
+    /// `frost_before` holds each tile's `frost_days` before the Conditions
+    /// phase. Returns the number of tiles that thawed.
+    pub fn apply(&self, world: &mut World, frost_before: &[u32]) -> usize {
+        let mut thaws = 0;
+        for (i, tile) in world.tiles.iter_mut().enumerate() {
+            let frozen_for = frost_before[i];
+            if frozen_for == 0
+                || tile.conditions.frost_days > 0
+                || tile.geology.terrain_type == TerrainType::Ocean
+                || world.frozen_tiles.contains(&tile.id)
+            {
+                continue;
+            }
+            thaws += 1;
+
+            let c = &mut tile.conditions;
+            let g = &mut tile.geology;
+            c.freeze_thaw_cycles += 1;
+
+            let thaw_mud = THAW_MUD_PER_FROST_DAY
+                * frozen_for.min(MAX_THAW_FROST_DAYS) as f32
+                * (1.0 - g.drainage);
+            c.mud_level = (c.mud_level + thaw_mud).clamp(0.0, 1.0);
+
+            if g.drainage < MAX_WEATHERED_DRAINAGE {
+                g.drainage = (g.drainage + DRAINAGE_PER_CYCLE).min(MAX_WEATHERED_DRAINAGE);
+            }
+
+            if self.rock_to_sand
+                && g.soil_type == SoilType::Rock
+                && c.freeze_thaw_cycles >= ROCK_TO_SAND_CYCLES
+            {
+                g.soil_type = SoilType::Sand;
+                c.freeze_thaw_cycles = 0;
+            }
+        }
+        thaws
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+
+    fn make_world() -> World {
+        let mut world = test_world(100);
+        for tile in &mut world.tiles {
+            tile.geology.terrain_type = TerrainType::Plains;
+            tile.geology.soil_type = SoilType::Clay;
+            tile.geology.drainage = 0.2;
+            tile.conditions.mud_level = 0.0;
+            tile.conditions.frost_days = 0;
+        }
+        world
+    }
+
+    #[test]
+    fn thaw_adds_mud_and_drainage() {
+        let mut world = make_world();
+        let mut before = vec![0; world.tiles.len()];
+        before[0] = 10; // thawed this tick
+        before[1] = 40; // thawed after a long freeze
+        before[2] = 5; // still frozen
+        world.tiles[2].conditions.frost_days = 6;
+
+        let thaws = FreezeThaw { rock_to_sand: true }.apply(&mut world, &before);
+        assert_eq!(thaws, 2);
+
+        let t0 = &world.tiles[0];
+        assert!((t0.conditions.mud_level - 10.0 * THAW_MUD_PER_FROST_DAY * 0.8).abs() < 1e-6);
+        assert!((t0.geology.drainage - 0.201).abs() < 1e-6);
+        assert_eq!(t0.conditions.freeze_thaw_cycles, 1);
+        // Long freezes are capped
+        let t1 = &world.tiles[1];
+        assert!((t1.conditions.mud_level - 20.0 * THAW_MUD_PER_FROST_DAY * 0.8).abs() < 1e-6);
+        // Still-frozen and never-frozen tiles are untouched
+        assert_eq!(world.tiles[2].conditions.freeze_thaw_cycles, 0);
+        assert_eq!(world.tiles[3].conditions.mud_level, 0.0);
+    }
+
+    #[test]
+    fn rock_cracks_to_sand_after_many_cycles() {
+        let mut world = make_world();
+        world.tiles[0].geology.soil_type = SoilType::Rock;
+        world.tiles[0].geology.drainage = 0.7;
+        world.tiles[1].geology.soil_type = SoilType::Rock;
+        world.tiles[0].conditions.freeze_thaw_cycles = ROCK_TO_SAND_CYCLES - 1;
+        world.tiles[1].conditions.freeze_thaw_cycles = ROCK_TO_SAND_CYCLES - 1;
+        let mut before = vec![0; world.tiles.len()];
+        before[0] = 3;
+        before[1] = 3;
+
+        let mut disabled = world.clone();
+        FreezeThaw { rock_to_sand: false }.apply(&mut disabled, &before);
+        assert_eq!(disabled.tiles[0].geology.soil_type, SoilType::Rock);
+
+        FreezeThaw { rock_to_sand: true }.apply(&mut world, &before);
+        assert_eq!(world.tiles[0].geology.soil_type, SoilType::Sand);
+        assert_eq!(world.tiles[0].conditions.freeze_thaw_cycles, 0);
+        assert!(world.tiles[0].geology.drainage <= MAX_WEATHERED_DRAINAGE);
+    }
+
+    #[test]
+    fn ocean_and_frozen_tiles_skipped() {
+        let mut world = make_world();
+        world.tiles[0].geology.terrain_type = TerrainType::Ocean;
+        world.frozen_tiles.insert(1);
+        let mut before = vec![0; world.tiles.len()];
+        before[0] = 5;
+        before[1] = 5;
+
+        let thaws = FreezeThaw { rock_to_sand: true }.apply(&mut world, &before);
+        assert_eq!(thaws, 0);
+        assert_eq!(world.tiles[0].conditions.mud_level, 0.0);
+        assert_eq!(world.tiles[1].geology.drainage, 0.2);
+    }
+}
@@ -0,0 +1,261 @@
+//! Glaciers, run natively at the end of the Conditions phase, before hydrology.
+//!
+//! Where snow lies deeper than `FIRN_DEPTH`, the excess slowly compacts into
+//! glacier ice, so ice builds up only where snow survives the summers. Ice
+//! is kept in `conditions.glacier_mass`, in the same water-equivalent units
+//! as `snow_depth`. Above freezing it melts in proportion to the warmth, and
+//! the meltwater (`conditions.glacier_melt`) is routed by hydrology like rain,
+//! so rivers below glaciers run high in summer.
+//!
+//! Ice creeps toward the lowest neighbor, faster on steep slopes but never
+//! quite stopping on gentle ones. Moving ice grinds down its bed, carrying
+//! the rock to the tile below, so glaciated valleys deepen over thousands of
+//! ticks. A glacier that reaches the sea piles up at its snout until a slab
+//! breaks off and is lost: a calving. A tile whose ice thins below
+//! `GLACIATED_MASS` counts as a retreat, so a warming world reports its
+//! glaciers shrinking back up the mountains.
+
+use serde::Serialize;
+
+use crate::simulation::erosion::downslope;
+use crate::world::tile::TerrainType;
+use crate::world::World;
+
+/// Snow deeper than this compacts into ice.
+const FIRN_DEPTH: f32 = 1.0;
+/// Fraction of the snow beyond `FIRN_DEPTH` that turns to ice per tick.
+const FIRN_RATE: f32 = 0.02;
+/// Ice melts above this temperature (K), like snow.
+const MELT_TEMPERATURE: f32 = 275.0;
+/// Ice melted per tick for each K above `MELT_TEMPERATURE`.
+const ICE_MELT_RATE: f32 = 0.01;
+/// Fraction of a tile's ice flowing downslope per tick on a full-strength slope.
+const FLOW_RATE: f32 = 0.02;
+/// Ice flows at least this fraction of the full-slope rate wherever there is a lower neighbor.
+const MIN_FLOW_SLOPE: f32 = 0.1;
+/// Elevation carved per unit of ice moved down a full-strength slope.
+const CARVING_RATE: f32 = 0.0005;
+/// Ice at a snout on the sea beyond which a slab breaks off.
+pub const CALVING_MASS: f32 = 5.0;
+/// Fraction of the snout's ice lost in a calving.
+const CALVING_FRACTION: f32 = 0.5;
+/// Ice mass at which a tile counts as glaciated.
+pub const GLACIATED_MASS: f32 = 1.0;
+
+/// What happened to a glacier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlacierEventKind {
+    /// Ice broke off into the sea
+    Calving,
+    /// The ice thinned below `GLACIATED_MASS`
+    Retreat,
+}
+
+/// A calving or retreat this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GlacierEvent {
+    pub tile_id: u32,
+    pub kind: GlacierEventKind,
+    /// Ice lost from the tile this tick
+    pub mass: f32,
+}
+
+/// Glacier settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glaciers {
+    /// Let moving ice carve down its bed.
+    pub carving: bool,
+}
+
+/// Ice that melts this tick from `mass` at `temperature`.
+fn melt(mass: f32, temperature: f32) -> f32 {
+    (ICE_MELT_RATE * (temperature - MELT_TEMPERATURE)).clamp(0.0, mass)
+}
+
+impl Glaciers {
+    /// Grow, melt and move the ice on every unfrozen land tile. Frozen tiles
+    /// neither lose ice nor receive it. Returns this tick's calvings and
+    /// retreats.
+    pub fn apply(&self, world: &mut World) -> Vec<GlacierEvent> {
+        let open: Vec<bool> = world
+            .tiles
+            .iter()
+            .map(|t| t.geology.terrain_type != TerrainType::Ocean && !world.frozen_tiles.contains(&t.id))
+            .collect();
+        let before: Vec<f32> = world.tiles.iter().map(|t| t.conditions.glacier_mass).collect();
+
+        // Lasting snow compacts into ice; warmth melts it into the rivers
+        for (i, tile) in world.tiles.iter_mut().enumerate() {
+            if !open[i] {
+                continue;
+            }
+            let c = &mut tile.conditions;
+            let firn = (c.snow_depth - FIRN_DEPTH).max(0.0) * FIRN_RATE;
+            c.snow_depth -= firn;
+            c.glacier_mass += firn;
+            c.glacier_melt = melt(c.glacier_mass, tile.weather.temperature);
+            c.glacier_mass -= c.glacier_melt;
+        }
+
+        // Work out every flow against the same masses and elevations, then apply them
+        let mut flows: Vec<(usize, usize, f32, f32)> = Vec::new();
+        let mut snouts: Vec<usize> = Vec::new();
+        for (i, tile) in world.tiles.iter().enumerate() {
+            if !open[i] || tile.conditions.glacier_mass <= 0.0 {
+                continue;
+            }
+            let Some((lower, slope)) = downslope(world, tile) else {
+                continue;
+            };
+            let below = &world.tiles[lower];
+            if below.geology.terrain_type == TerrainType::Ocean {
+                if tile.conditions.glacier_mass > CALVING_MASS {
+                    snouts.push(i);
+                }
+                continue;
+            }
+            if !open[lower] {
+                continue;
+            }
+            let ice = tile.conditions.glacier_mass * FLOW_RATE * slope.max(MIN_FLOW_SLOPE);
+            // Never cut a tile below the one it flows to
+            let half_drop = (tile.geology.elevation - below.geology.elevation) / 2.0;
+            let carved = if self.carving { (CARVING_RATE * ice * slope).min(half_drop) } else { 0.0 };
+            flows.push((i, lower, ice, carved));
+        }
+
+        for (from, to, ice, carved) in flows {
+            world.tiles[from].conditions.glacier_mass -= ice;
+            world.tiles[from].geology.elevation -= carved;
+            world.tiles[to].conditions.glacier_mass += ice;
+            world.tiles[to].geology.elevation = (world.tiles[to].geology.elevation + carved).min(1.0);
+        }
+
+        let mut events = Vec::new();
+        for i in snouts {
+            let c = &mut world.tiles[i].conditions;
+            let mass = c.glacier_mass * CALVING_FRACTION;
+            c.glacier_mass -= mass;
+            events.push(GlacierEvent { tile_id: world.tiles[i].id, kind: GlacierEventKind::Calving, mass });
+        }
+        for (tile, before) in world.tiles.iter().zip(before) {
+            let after = tile.conditions.glacier_mass;
+            if before >= GLACIATED_MASS && after < GLACIATED_MASS {
+                events.push(GlacierEvent { tile_id: tile.id, kind: GlacierEventKind::Retreat, mass: before - after });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::hydrology::update_hydrology;
+    use crate::world::tile::{Position, Tile};
+
+    /// A mountainside of `n` land tiles in a line, highest first, ending in
+    /// an ocean tile.
+    fn mountainside(n: u32) -> World {
+        let mut world = crate::world::generation::generate_world(&crate::config::generation::GenerationParams {
+            seed: 5,
+            tile_count: 100,
+            ocean_ratio: 0.5,
+            ..Default::default()
+        });
+        world.tiles = (0..=n)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id < n).then_some(id + 1)];
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), Position::flat(0.0, 0.0));
+                t.geology.terrain_type = if id == n { TerrainType::Ocean } else { TerrainType::Mountains };
+                t.geology.elevation = 0.9 - id as f32 * 0.2;
+                t.weather.temperature = 260.0;
+                t
+            })
+            .collect();
+        world
+    }
+
+    #[test]
+    fn lasting_snow_becomes_ice_that_flows_and_carves() {
+        let mut world = mountainside(3);
+        world.tiles[0].conditions.snow_depth = 10.0;
+        let glaciers = Glaciers { carving: true };
+        let summit = world.tiles[0].geology.elevation;
+        for _ in 0..200 {
+            glaciers.apply(&mut world);
+        }
+        let mass = |w: &World, i: usize| w.tiles[i].conditions.glacier_mass;
+        assert!(mass(&world, 0) > 0.0);
+        assert!(mass(&world, 1) > 0.0, "ice should flow downslope");
+        assert!(world.tiles[0].conditions.snow_depth < 10.0);
+        assert!(world.tiles[0].geology.elevation < summit, "moving ice carves its bed");
+        assert_eq!(mass(&world, 3), 0.0, "no ice on the ocean");
+
+        // Without carving the bed stays put
+        let mut still = mountainside(3);
+        still.tiles[0].conditions.snow_depth = 10.0;
+        for _ in 0..200 {
+            Glaciers { carving: false }.apply(&mut still);
+        }
+        assert_eq!(still.tiles[0].geology.elevation, summit);
+    }
+
+    #[test]
+    fn summer_meltwater_feeds_rivers() {
+        let mut world = mountainside(3);
+        world.tiles[0].conditions.glacier_mass = 4.0;
+        let glaciers = Glaciers { carving: true };
+        glaciers.apply(&mut world);
+        assert_eq!(world.tiles[0].conditions.glacier_melt, 0.0, "no melt below freezing");
+
+        for t in &mut world.tiles {
+            t.weather.temperature = 285.0;
+        }
+        glaciers.apply(&mut world);
+        let melt = world.tiles[0].conditions.glacier_melt;
+        assert!(melt > 0.0);
+        update_hydrology(&mut world);
+        assert!(world.tiles[2].conditions.river_flow > 0.0, "meltwater runs down to the sea");
+    }
+
+    #[test]
+    fn tidewater_glaciers_calve_and_warming_glaciers_retreat() {
+        let mut world = mountainside(2);
+        world.tiles[1].conditions.glacier_mass = CALVING_MASS + 1.0;
+        let events = Glaciers { carving: true }.apply(&mut world);
+        let calving = events.iter().find(|e| e.kind == GlacierEventKind::Calving).expect("calving");
+        assert_eq!(calving.tile_id, 1);
+        assert!(world.tiles[1].conditions.glacier_mass < CALVING_MASS);
+
+        let mut world = mountainside(2);
+        world.tiles[0].conditions.glacier_mass = 2.0;
+        for t in &mut world.tiles {
+            t.weather.temperature = 295.0;
+        }
+        let mut retreats = Vec::new();
+        for _ in 0..20 {
+            retreats.extend(
+                Glaciers { carving: true }
+                    .apply(&mut world)
+                    .into_iter()
+                    .filter(|e| e.kind == GlacierEventKind::Retreat)
+                    .map(|e| e.tile_id),
+            );
+        }
+        assert_eq!(retreats, vec![0]);
+        assert_eq!(world.tiles[0].conditions.glacier_mass, 0.0);
+    }
+
+    #[test]
+    fn frozen_tiles_keep_their_ice() {
+        let mut world = mountainside(3);
+        world.tiles[0].conditions.glacier_mass = 3.0;
+        world.frozen_tiles.insert(0);
+        world.frozen_tiles.insert(1);
+        Glaciers { carving: true }.apply(&mut world);
+        assert_eq!(world.tiles[0].conditions.glacier_mass, 3.0);
+        assert_eq!(world.tiles[1].conditions.glacier_mass, 0.0);
+    }
+}
@@ -0,0 +1,131 @@
+//! World-level accumulators that rules contribute to with `contribute*()`.
+//!
+//! Each tick, every tile's contributions are reduced per name (sum, min or
+//! max). The reduced values are stored in `World::globals` and exposed to rules
+//! as the `globals` map on the following tick, so scripts can build global
+//! feedback loops (e.g. total evaporation driving next tick's rainfall).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use tracing::warn;
+
+/// How contributions to a global are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Reduction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reduction::Sum => "sum",
+            Reduction::Min => "min",
+            Reduction::Max => "max",
+        }
+    }
+
+    fn combine(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Reduction::Sum => a + b,
+            Reduction::Min => a.min(b),
+            Reduction::Max => a.max(b),
+        }
+    }
+}
+
+/// Partially reduced contributions, merged tile by tile in tile order so sums
+/// are deterministic regardless of evaluation parallelism.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlobalAccumulator {
+    values: BTreeMap<String, (Reduction, f64)>,
+    /// Names contributed to with more than one reduction; dropped at publish.
+    conflicts: BTreeSet<String>,
+}
+
+impl GlobalAccumulator {
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.conflicts.is_empty()
+    }
+
+    /// Fold one contribution into the accumulator. Non-finite values are ignored.
+    pub fn contribute(&mut self, name: &str, reduction: Reduction, value: f64) {
+        if !value.is_finite() || self.conflicts.contains(name) {
+            return;
+        }
+        match self.values.get_mut(name) {
+            Some((r, acc)) if *r == reduction => *acc = reduction.combine(*acc, value),
+            Some(_) => {
+                self.values.remove(name);
+                self.conflicts.insert(name.to_string());
+            }
+            None => {
+                self.values.insert(name.to_string(), (reduction, value));
+            }
+        }
+    }
+
+    /// Merge another accumulator into this one.
+    pub fn merge(&mut self, other: GlobalAccumulator) {
+        for name in other.conflicts {
+            self.values.remove(&name);
+            self.conflicts.insert(name);
+        }
+        for (name, (reduction, value)) in other.values {
+            self.contribute(&name, reduction, value);
+        }
+    }
+
+    /// Values reduced so far, without the globals with conflicting reductions.
+    pub fn totals(&self) -> BTreeMap<String, f64> {
+        self.values.iter().map(|(k, &(_, v))| (k.clone(), v)).collect()
+    }
+
+    /// Final reduced values. Globals with conflicting reductions are dropped
+    /// with a warning.
+    pub fn finish(self) -> BTreeMap<String, f64> {
+        for name in &self.conflicts {
+            warn!(global = %name, "Global contributed with mixed reductions; dropping it this tick");
+        }
+        self.values.into_iter().map(|(k, (_, v))| (k, v)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reductions_combine_contributions() {
+        let mut acc = GlobalAccumulator::default();
+        acc.contribute("evap", Reduction::Sum, 1.5);
+        acc.contribute("evap", Reduction::Sum, 2.0);
+        acc.contribute("hottest", Reduction::Max, 300.0);
+        acc.contribute("hottest", Reduction::Max, 310.0);
+        acc.contribute("coldest", Reduction::Min, 250.0);
+        acc.contribute("coldest", Reduction::Min, 240.0);
+        acc.contribute("evap", Reduction::Sum, f64::NAN);
+
+        let globals = acc.finish();
+        assert_eq!(globals["evap"], 3.5);
+        assert_eq!(globals["hottest"], 310.0);
+        assert_eq!(globals["coldest"], 240.0);
+    }
+
+    #[test]
+    fn mixed_reductions_are_dropped() {
+        let mut a = GlobalAccumulator::default();
+        a.contribute("x", Reduction::Sum, 1.0);
+        let mut b = GlobalAccumulator::default();
+        b.contribute("x", Reduction::Max, 5.0);
+        b.contribute("y", Reduction::Sum, 2.0);
+
+        a.merge(b);
+        // Later contributions can't resurrect a conflicted name
+        a.contribute("x", Reduction::Sum, 1.0);
+        let globals = a.finish();
+        assert!(!globals.contains_key("x"));
+        assert_eq!(globals["y"], 2.0);
+    }
+}
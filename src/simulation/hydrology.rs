@@ -0,0 +1,293 @@
+//! Rivers and groundwater, computed natively at the end of the Conditions phase.
+//!
+//! Water reaching the ground (rain, sleet, snowmelt) partly soaks in and
+//! partly runs off. What soaks in slowly recharges the tile's `water_table`,
+//! which seeps back out as baseflow, so rivers keep running between storms.
+//! Runoff and baseflow are routed downhill: every land tile drains to its
+//! lowest lower neighbor, and flow accumulates from the highest tiles down.
+//! A tile's `river_flow` is everything passing through it, in the same units
+//! as precipitation, so rivers stand out as the flow gathers toward the sea.
+//! Flow reaching the ocean leaves the world.
+//!
+//! A land tile with no lower neighbor is a basin: water draining into it
+//! becomes a lake, soaking into its water table. Rivers seep into their beds
+//! too, and a high water table keeps the soil above it moist, so valley
+//! floors and floodplains stay wetter than the hillsides around them. When a
+//! river suddenly carries far more than usual, the excess spills over its
+//! banks and floods the tile.
+//!
+//! Before any of that, soil moisture seeps sideways through the ground
+//! toward lower neighbors, faster on steep, well-drained slopes, so
+//! hillsides dry out and the valleys below them stay damp.
+
+use super::erosion::{downslope, FULL_SLOPE_DROP};
+use super::mud::liquid_water;
+use crate::world::tile::TerrainType;
+use crate::world::{Tile, World};
+
+/// Fraction of the water reaching the ground that soaks in at drainage 1.0.
+const INFILTRATION: f32 = 0.7;
+/// Frozen ground lets in this fraction of its usual infiltration.
+const FROZEN_INFILTRATION: f32 = 0.2;
+/// Fraction of the water soaking in that reaches the groundwater.
+const PERCOLATION: f32 = 0.3;
+/// Fraction of the water table seeping out as baseflow per tick.
+const BASEFLOW_RATE: f32 = 0.03;
+/// Fraction of upstream inflow soaking into a river bed.
+const CHANNEL_SEEPAGE: f32 = 0.02;
+/// Fraction of upstream inflow soaking into a lake basin.
+const LAKE_SEEPAGE: f32 = 0.2;
+/// Fraction of the gap between water table and soil moisture closed per tick.
+const CAPILLARY_RISE: f32 = 0.05;
+/// How quickly `river_flow` follows this tick's flow (0.0-1.0).
+const FLOW_RESPONSE: f32 = 0.3;
+/// Flow a channel always carries without spilling.
+const BANKFULL_MIN: f32 = 0.5;
+/// A river spills once it carries this many times its usual flow.
+const BANKFULL_RATIO: f32 = 2.0;
+/// Fraction of the flow above bankfull that spills onto the tile.
+const OVERBANK_RATE: f32 = 0.5;
+/// Fraction of soil moisture seeping toward each lower neighbor per tick, on
+/// a full-strength slope at drainage 1.0.
+const LATERAL_RATE: f32 = 0.1;
+/// Most of a tile's soil moisture that can seep away in one tick.
+const MAX_LATERAL_LOSS: f32 = 0.25;
+
+/// Fraction of the water reaching a tile that soaks in rather than running off.
+pub fn infiltration(tile: &Tile) -> f32 {
+    let soak = INFILTRATION * tile.geology.drainage.clamp(0.0, 1.0);
+    if tile.conditions.frost_days > 0 { soak * FROZEN_INFILTRATION } else { soak }
+}
+
+/// Water spilling over a river's banks: the part of this tick's flow beyond
+/// what the channel usually carries.
+pub fn overbank(flow: f32, usual_flow: f32) -> f32 {
+    let bankfull = (usual_flow * BANKFULL_RATIO).max(BANKFULL_MIN);
+    (flow - bankfull).max(0.0) * OVERBANK_RATE
+}
+
+/// Fraction of a tile's soil moisture seeping toward each neighbor this tick,
+/// as (neighbor, fraction) pairs; only lower neighbors get any.
+pub fn lateral_seepage(world: &World, tile: &Tile) -> Vec<(usize, f32)> {
+    let rate = LATERAL_RATE * tile.geology.drainage.clamp(0.0, 1.0);
+    let mut seepage: Vec<(usize, f32)> = tile
+        .neighbors
+        .iter()
+        .map(|&n| n as usize)
+        .filter(|&n| n < world.tiles.len())
+        .filter_map(|n| {
+            let drop = tile.geology.elevation - world.tiles[n].geology.elevation;
+            (drop > 0.0).then(|| (n, rate * (drop / FULL_SLOPE_DROP).min(1.0)))
+        })
+        .collect();
+    let total: f32 = seepage.iter().map(|(_, f)| f).sum();
+    if total > MAX_LATERAL_LOSS {
+        for (_, f) in &mut seepage {
+            *f *= MAX_LATERAL_LOSS / total;
+        }
+    }
+    seepage
+}
+
+/// Move soil moisture downslope between neighboring land tiles. Moisture
+/// seeping toward the ocean leaves the world; frozen tiles neither give nor
+/// take any.
+pub fn spread_soil_moisture(world: &mut World) {
+    let n = world.tiles.len();
+    let open = |t: &Tile| t.geology.terrain_type != TerrainType::Ocean && !world.frozen_tiles.contains(&t.id);
+    let mut change = vec![0.0_f32; n];
+    for (i, tile) in world.tiles.iter().enumerate() {
+        if !open(tile) {
+            continue;
+        }
+        let moisture = tile.conditions.soil_moisture.max(0.0);
+        for (to, fraction) in lateral_seepage(world, tile) {
+            let below = &world.tiles[to];
+            if world.frozen_tiles.contains(&below.id) {
+                continue;
+            }
+            change[i] -= moisture * fraction;
+            if below.geology.terrain_type != TerrainType::Ocean {
+                change[to] += moisture * fraction;
+            }
+        }
+    }
+    for (tile, delta) in world.tiles.iter_mut().zip(change) {
+        if delta != 0.0 {
+            let c = &mut tile.conditions;
+            c.soil_moisture = (c.soil_moisture + delta).clamp(0.0, 1.0);
+        }
+    }
+}
+
+/// Route this tick's water and update `river_flow`, `water_table` and soil
+/// moisture on every unfrozen land tile. Frozen tiles still pass water
+/// downhill but keep their values. Returns the water spilling over river
+/// banks on each tile, for the flood step.
+pub fn update_hydrology(world: &mut World) -> Vec<f32> {
+    let n = world.tiles.len();
+    let is_land = |t: &Tile| t.geology.terrain_type != TerrainType::Ocean;
+
+    // Each land tile drains to its lowest lower neighbor, if it has one
+    let receivers: Vec<Option<usize>> = world
+        .tiles
+        .iter()
+        .map(|t| if is_land(t) { downslope(world, t).map(|(r, _)| r) } else { None })
+        .collect();
+
+    let mut water_table = vec![0.0_f32; n];
+    let mut flow = vec![0.0_f32; n];
+    for (i, tile) in world.tiles.iter().enumerate() {
+        if !is_land(tile) {
+            continue;
+        }
+        let water = liquid_water(tile);
+        let soaked = water * infiltration(tile);
+        let baseflow = tile.conditions.water_table * BASEFLOW_RATE;
+        water_table[i] = tile.conditions.water_table - baseflow + soaked * PERCOLATION;
+        flow[i] = water - soaked + baseflow;
+    }
+
+    // Accumulate from the highest tiles down; a receiver is always lower, so
+    // it is visited after everything draining into it
+    let mut order: Vec<usize> = (0..n).filter(|&i| is_land(&world.tiles[i])).collect();
+    order.sort_by(|&a, &b| world.tiles[b].geology.elevation.total_cmp(&world.tiles[a].geology.elevation));
+    let mut inflow = vec![0.0_f32; n];
+    for &i in &order {
+        if let Some(r) = receivers[i]
+            && is_land(&world.tiles[r])
+        {
+            flow[r] += flow[i];
+            inflow[r] += flow[i];
+        }
+    }
+
+    let mut spilled = vec![0.0_f32; n];
+    for (i, tile) in world.tiles.iter_mut().enumerate() {
+        if !is_land(tile) || world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        let seepage = if receivers[i].is_some() { CHANNEL_SEEPAGE } else { LAKE_SEEPAGE };
+        let c = &mut tile.conditions;
+        c.water_table = (water_table[i] + inflow[i] * seepage).clamp(0.0, 1.0);
+        c.soil_moisture += (c.water_table - c.soil_moisture).max(0.0) * CAPILLARY_RISE;
+        spilled[i] = overbank(flow[i], c.river_flow);
+        c.river_flow += FLOW_RESPONSE * (flow[i] - c.river_flow);
+    }
+    spilled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{Position, PrecipitationType};
+
+    /// A valley of `n` land tiles in a line, highest first, draining into an
+    /// ocean tile at the end.
+    fn valley(n: u32) -> World {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = (0..=n)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id < n).then_some(id + 1)];
+                let position = Position::flat(id as f64, 0.0);
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), position);
+                t.geology.elevation = 1.0 - 0.1 * id as f32;
+                t.conditions.soil_moisture = 0.2;
+                t.weather.precipitation = 0.1;
+                t.weather.precipitation_type = PrecipitationType::Rain;
+                t
+            })
+            .collect();
+        world.tiles[n as usize].geology.terrain_type = TerrainType::Ocean;
+        world
+    }
+
+    #[test]
+    fn flow_accumulates_downhill_to_the_sea() {
+        let mut world = valley(4);
+        for _ in 0..20 {
+            update_hydrology(&mut world);
+        }
+        let flows: Vec<f32> = world.tiles.iter().map(|t| t.conditions.river_flow).collect();
+        assert!(flows.windows(2).take(3).all(|w| w[1] > w[0]), "{:?}", flows);
+        // Four tiles' runoff at the mouth; the ocean carries nothing
+        assert!((flows[3] / flows[0] - 4.0).abs() < 0.5, "{:?}", flows);
+        assert_eq!(flows[4], 0.0);
+        assert!(world.tiles[3].conditions.water_table > world.tiles[0].conditions.water_table);
+
+        // Rivers keep running on baseflow after the rain stops
+        for t in &mut world.tiles {
+            t.weather.precipitation = 0.0;
+        }
+        for _ in 0..10 {
+            update_hydrology(&mut world);
+        }
+        assert!(world.tiles[3].conditions.river_flow > 0.0);
+    }
+
+    #[test]
+    fn basins_fill_into_lakes() {
+        let mut world = valley(4);
+        // Tile 2 sits in a hollow below both neighbors
+        world.tiles[2].geology.elevation = 0.5;
+        world.tiles[3].geology.elevation = 0.6;
+        for _ in 0..30 {
+            update_hydrology(&mut world);
+        }
+        let (lake, slope) = (&world.tiles[2].conditions, &world.tiles[1].conditions);
+        assert!(lake.water_table > 2.0 * slope.water_table, "{} vs {}", lake.water_table, slope.water_table);
+        assert!(lake.soil_moisture > slope.soil_moisture);
+    }
+
+    #[test]
+    fn sudden_surges_spill_over_the_banks() {
+        assert_eq!(overbank(0.4, 0.0), 0.0);
+        assert_eq!(overbank(1.5, 1.0), 0.0);
+        assert!((overbank(3.0, 1.0) - 0.5).abs() < 1e-6);
+
+        // Frozen tiles pass water on without changing
+        let mut world = valley(4);
+        world.frozen_tiles.insert(1);
+        update_hydrology(&mut world);
+        assert_eq!(world.tiles[1].conditions.river_flow, 0.0);
+        assert!(world.tiles[2].conditions.river_flow > world.tiles[0].conditions.river_flow * 2.0);
+    }
+
+    #[test]
+    fn soil_moisture_seeps_downslope() {
+        let mut world = valley(4);
+        let total = |w: &World| w.tiles[..4].iter().map(|t| t.conditions.soil_moisture).sum::<f32>();
+        let before = total(&world);
+        spread_soil_moisture(&mut world);
+        let moisture: Vec<f32> = world.tiles.iter().map(|t| t.conditions.soil_moisture).collect();
+        // The top dries, the slope below passes on what it gets, and the ocean takes the rest
+        assert!(moisture[0] < 0.2 && (moisture[2] - 0.2).abs() < 1e-6, "{:?}", moisture);
+        assert!(total(&world) < before);
+        assert_eq!(world.tiles[4].conditions.soil_moisture, 0.2);
+        for _ in 0..50 {
+            spread_soil_moisture(&mut world);
+        }
+        let moisture: Vec<f32> = world.tiles.iter().map(|t| t.conditions.soil_moisture).collect();
+        assert!(moisture.windows(2).take(2).all(|w| w[1] > w[0]), "{:?}", moisture);
+
+        // Poorly drained ground holds its water, and frozen tiles are left alone
+        let mut clay = valley(4);
+        let mut frozen = valley(4);
+        for t in &mut clay.tiles {
+            t.geology.drainage = 0.0;
+        }
+        frozen.frozen_tiles.insert(1);
+        spread_soil_moisture(&mut clay);
+        spread_soil_moisture(&mut frozen);
+        assert!(clay.tiles.iter().all(|t| t.conditions.soil_moisture == 0.2));
+        assert_eq!(frozen.tiles[1].conditions.soil_moisture, 0.2);
+        assert_eq!(frozen.tiles[0].conditions.soil_moisture, 0.2);
+    }
+}
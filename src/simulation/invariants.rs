@@ -0,0 +1,317 @@
+//! Continuous sanity checks for a running simulation.
+//!
+//! Invariants are Rhai scripts in `rules/invariants/`. Every few ticks each
+//! invariant is evaluated against a sample of tiles and must return `true`.
+//! Returning `false` (or a string describing the problem) is a violation, as
+//! is a script error. Violations are aggregated per invariant, so a check
+//! produces at most one violation per script.
+
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use std::path::Path;
+
+use crate::error::WorldgroundError;
+use crate::simulation::engine::tile_to_rhai_map;
+use crate::simulation::events::{self, EventValue, WorldEvent};
+use crate::simulation::statistics::TickStatistics;
+use crate::world::World;
+
+/// Subdirectory of the rule directory holding invariant scripts.
+pub const INVARIANT_DIR: &str = "invariants";
+
+/// A compiled invariant script.
+#[derive(Debug, Clone)]
+pub struct Invariant {
+    pub name: String,
+    ast: AST,
+}
+
+/// One invariant failing on part of the sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvariantViolation {
+    pub invariant: String,
+    pub tick: u64,
+    /// Sampled tiles the invariant failed on.
+    pub failed: usize,
+    /// Tiles sampled in this check.
+    pub sampled: usize,
+    /// First failing tile.
+    pub tile_id: u32,
+    /// The script's message, error, or "returned false".
+    pub message: String,
+}
+
+impl std::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invariant '{}' failed on {}/{} sampled tiles (first: tile {}): {}",
+            self.invariant, self.failed, self.sampled, self.tile_id, self.message
+        )
+    }
+}
+
+impl InvariantViolation {
+    /// The violation as a world event on its first failing tile.
+    pub fn event(&self) -> WorldEvent {
+        let mut event = events::native(WorldEvent::INVARIANT_VIOLATION, self.tile_id);
+        event.tick = self.tick;
+        event.data.insert("invariant".to_string(), EventValue::Text(self.invariant.clone()));
+        event.data.insert("failed".to_string(), EventValue::Number(self.failed as f64));
+        event.data.insert("sampled".to_string(), EventValue::Number(self.sampled as f64));
+        event.data.insert("message".to_string(), EventValue::Text(self.message.clone()));
+        event
+    }
+}
+
+/// The invariants loaded from a rule directory.
+pub struct InvariantSet {
+    engine: Engine,
+    invariants: Vec<Invariant>,
+}
+
+impl InvariantSet {
+    /// Compile every `.rhai` file in `<rule_dir>/invariants/`, in filename order.
+    /// A missing directory yields an empty set.
+    pub fn load(rule_dir: &Path) -> Result<Self, WorldgroundError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(100_000);
+        engine.set_max_string_size(1024);
+        engine.set_max_array_size(1000);
+        engine.set_max_map_size(500);
+
+        let dir = rule_dir.join(INVARIANT_DIR);
+        let mut invariants = Vec::new();
+        if dir.exists() {
+            let mut paths: Vec<_> = std::fs::read_dir(&dir)
+                .map_err(|e| WorldgroundError::Rules(format!("Cannot read {}: {}", dir.display(), e)))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "rhai"))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let name = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let source = std::fs::read_to_string(&path)
+                    .map_err(|e| WorldgroundError::Rules(format!("Cannot read invariant {}: {}", path.display(), e)))?;
+                let ast = engine
+                    .compile(&source)
+                    .map_err(|source| WorldgroundError::RuleSyntax { path: path.clone(), source })?;
+                invariants.push(Invariant { name, ast });
+            }
+        }
+
+        Ok(InvariantSet { engine, invariants })
+    }
+
+    pub fn invariants(&self) -> &[Invariant] {
+        &self.invariants
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invariants.is_empty()
+    }
+
+    /// Evaluate every invariant against up to `sample_size` tiles.
+    ///
+    /// The sample is evenly spaced across the world and its offset rotates
+    /// with the tick, so successive checks cover different tiles.
+    pub fn check(
+        &self,
+        world: &World,
+        stats: &TickStatistics,
+        sample_size: usize,
+    ) -> Vec<InvariantViolation> {
+        if self.invariants.is_empty() || world.tiles.is_empty() {
+            return Vec::new();
+        }
+
+        let sample = sample_indices(world.tiles.len(), sample_size, world.tick_count);
+        let tile_maps: Vec<(u32, Dynamic)> = sample
+            .iter()
+            .map(|&i| (world.tiles[i].id, tile_to_rhai_map(&world.tiles[i])))
+            .collect();
+        let stats_map = Dynamic::from(stats_rhai_map(stats));
+        let globals: Map = world.globals.iter().map(|(k, &v)| (k.as_str().into(), Dynamic::from(v))).collect();
+        let globals = Dynamic::from(globals);
+        let season = format!("{:?}", world.season);
+
+        let mut violations = Vec::new();
+        for invariant in &self.invariants {
+            let mut first: Option<(u32, String)> = None;
+            let mut failed = 0;
+            for (tile_id, tile_map) in &tile_maps {
+                let mut scope = Scope::new();
+                scope.push_constant("tile", tile_map.clone());
+                scope.push_constant("stats", stats_map.clone());
+                scope.push_constant("globals", globals.clone());
+                scope.push_constant("season", season.clone());
+                scope.push_constant("tick", world.tick_count as i64);
+
+                let outcome = match self
+                    .engine
+                    .eval_ast_with_scope::<Dynamic>(&mut scope, &invariant.ast)
+                {
+                    Ok(v) if v.as_bool() == Ok(true) => None,
+                    Ok(v) if v.as_bool() == Ok(false) => Some("returned false".to_string()),
+                    Ok(v) if v.is_string() => Some(v.into_string().unwrap_or_default()),
+                    Ok(v) => Some(format!("must return a bool, got {}", v.type_name())),
+                    Err(e) => Some(e.to_string()),
+                };
+                if let Some(message) = outcome {
+                    failed += 1;
+                    first.get_or_insert((*tile_id, message));
+                }
+            }
+
+            if let Some((tile_id, message)) = first {
+                violations.push(InvariantViolation {
+                    invariant: invariant.name.clone(),
+                    tick: world.tick_count,
+                    failed,
+                    sampled: tile_maps.len(),
+                    tile_id,
+                    message,
+                });
+            }
+        }
+        violations
+    }
+}
+
+/// Evenly spaced tile indices, at most `sample_size` of them, starting at an
+/// offset that advances with `round`.
+fn sample_indices(tile_count: usize, sample_size: usize, round: u64) -> Vec<usize> {
+    let sample_size = sample_size.clamp(1, tile_count);
+    let stride = tile_count / sample_size;
+    let offset = (round % stride as u64) as usize;
+    (0..sample_size).map(|k| offset + k * stride).collect()
+}
+
+fn stats_rhai_map(stats: &TickStatistics) -> Map {
+    let mut map = Map::new();
+    map.insert("tick".into(), Dynamic::from(stats.tick as i64));
+    map.insert("avg_temperature".into(), Dynamic::from(stats.avg_temperature as f64));
+    map.insert("avg_moisture".into(), Dynamic::from(stats.avg_moisture as f64));
+    map.insert(
+        "avg_vegetation_health".into(),
+        Dynamic::from(stats.avg_vegetation_health as f64),
+    );
+    map.insert("diversity_index".into(), Dynamic::from(stats.diversity_index as f64));
+    map.insert("rule_errors".into(), Dynamic::from(stats.rule_errors as i64));
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn make_stats() -> TickStatistics {
+        TickStatistics {
+            tick: 0,
+            biome_distribution: HashMap::new(),
+            avg_temperature: 288.0,
+            avg_moisture: 0.4,
+            avg_vegetation_health: 0.7,
+            weather_coverage: HashMap::new(),
+            diversity_index: 0.65,
+            rule_errors: 0,
+            tick_duration_ms: 100.0,
+        }
+    }
+
+    fn load(invariants: &[(&str, &str)]) -> (TempDir, InvariantSet) {
+        let dir = TempDir::new().unwrap();
+        let inv_dir = dir.path().join(INVARIANT_DIR);
+        fs::create_dir_all(&inv_dir).unwrap();
+        for (name, source) in invariants {
+            fs::write(inv_dir.join(name), source).unwrap();
+        }
+        let set = InvariantSet::load(dir.path()).unwrap();
+        (dir, set)
+    }
+
+    #[test]
+    fn missing_directory_is_empty() {
+        let dir = TempDir::new().unwrap();
+        let set = InvariantSet::load(dir.path()).unwrap();
+        assert!(set.is_empty());
+        assert!(set.check(&test_world(100), &make_stats(), 10).is_empty());
+    }
+
+    #[test]
+    fn syntax_errors_fail_loading() {
+        let dir = TempDir::new().unwrap();
+        let inv_dir = dir.path().join(INVARIANT_DIR);
+        fs::create_dir_all(&inv_dir).unwrap();
+        fs::write(inv_dir.join("bad.rhai"), "tile.weather.humidity >=").unwrap();
+        let err = InvariantSet::load(dir.path()).err().unwrap();
+        assert!(matches!(err, WorldgroundError::RuleSyntax { .. }), "{:?}", err);
+        assert!(err.to_string().contains("bad.rhai"), "{}", err);
+    }
+
+    #[test]
+    fn violations_aggregated_per_invariant() {
+        let (_dir, set) = load(&[
+            ("01-ok.rhai", "tile.weather.humidity >= 0.0 && tile.weather.humidity <= 1.0"),
+            ("02-cold.rhai", "tile.weather.temperature < 100.0"),
+            ("03-msg.rhai", r#"if stats.avg_temperature > 280.0 { "world too warm" } else { true }"#),
+            ("04-unit.rhai", "let x = 1;"),
+            ("05-globals.rhai", "globals.rainfall > 1.0"),
+        ]);
+        let mut world = test_world(100);
+        world.globals.insert("rainfall".to_string(), 2.0);
+        let violations = set.check(&world, &make_stats(), 20);
+
+        let names: Vec<_> = violations.iter().map(|v| v.invariant.as_str()).collect();
+        assert_eq!(names, vec!["02-cold.rhai", "03-msg.rhai", "04-unit.rhai"]);
+        assert_eq!(violations[0].failed, 20);
+        assert_eq!(violations[0].sampled, 20);
+        assert_eq!(violations[0].message, "returned false");
+        assert_eq!(violations[1].message, "world too warm");
+        assert!(violations[2].message.contains("must return a bool"));
+    }
+
+    #[test]
+    fn violations_become_events() {
+        let (_dir, set) = load(&[("02-cold.rhai", "tile.weather.temperature < 100.0")]);
+        let mut world = test_world(100);
+        world.tick_count = 30;
+        let violations = set.check(&world, &make_stats(), 20);
+        assert_eq!(violations.len(), 1);
+
+        let event = violations[0].event();
+        assert_eq!(event.kind, WorldEvent::INVARIANT_VIOLATION);
+        assert_eq!((event.tick, event.tile), (30, Some(violations[0].tile_id)));
+        assert_eq!(event.source, WorldEvent::NATIVE);
+        assert_eq!(event.data["invariant"], EventValue::Text("02-cold.rhai".to_string()));
+        assert_eq!(event.data["failed"], EventValue::Number(20.0));
+        assert_eq!(event.data["message"], EventValue::Text("returned false".to_string()));
+    }
+
+    #[test]
+    fn shipped_invariants_hold_on_fresh_world() {
+        let rule_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("rules");
+        let set = InvariantSet::load(&rule_dir).unwrap();
+        assert!(!set.is_empty());
+        let violations = set.check(&test_world(100), &make_stats(), 1000);
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn sample_rotates_and_stays_in_bounds() {
+        assert_eq!(sample_indices(10, 5, 0), vec![0, 2, 4, 6, 8]);
+        assert_eq!(sample_indices(10, 5, 1), vec![1, 3, 5, 7, 9]);
+        assert_eq!(sample_indices(10, 5, 2), vec![0, 2, 4, 6, 8]);
+        assert_eq!(sample_indices(3, 100, 7), vec![0, 1, 2]);
+        assert_eq!(sample_indices(5, 0, 0), vec![0]);
+    }
+}
@@ -5,13 +5,59 @@
 
 use rayon::prelude::*;
 
+use crate::simulation::events::{self, EventValue, WorldEvent};
 use crate::simulation::sphere_math;
 use crate::world::tile::TerrainType;
-use crate::world::weather_systems::{PressureSystem, PressureSystemType};
+use crate::world::weather_systems::{PressureSystem, PressureSystemType, SystemFootprint};
 use crate::world::World;
 
+/// Gaussian weights below 0.01 are dropped, i.e. beyond sqrt(ln(100)/3) ≈ 1.24
+/// radii. Footprints use a slightly larger cutoff so no contributing tile is missed.
+const FOOTPRINT_RADII: f64 = 1.25;
+
+/// How far (radians) a system may drift from where its footprint was computed
+/// before the footprint is rebuilt. Footprints are padded by this much.
+const FOOTPRINT_MOVE_THRESHOLD: f64 = 0.05;
+
+/// Height (m) of elevation 1.0, matching the lapse rate in the weather rules.
+pub const METERS_PER_ELEVATION: f64 = 3000.0;
+/// Standard gravity (m/s²).
+const GRAVITY: f64 = 9.80665;
+/// Specific gas constant of dry air (J/(kg·K)).
+const DRY_AIR_GAS_CONSTANT: f64 = 287.05;
+/// Standard atmosphere lapse rate (K/m).
+const STANDARD_LAPSE_RATE: f64 = 0.0065;
+
+/// Blocking highs form where the westerlies meander, between these latitudes.
+const BLOCK_MIN_LATITUDE: f64 = 45.0;
+const BLOCK_MAX_LATITUDE: f64 = 65.0;
+/// Cyclones within this many radii of a block are steered around it.
+const BLOCK_DIVERSION_RADII: f64 = 1.5;
+/// Northward speed (rad/tick) a cyclone picks up at a block's center.
+const BLOCK_DIVERSION_SPEED: f32 = 0.008;
+
+/// Blocking regime settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Blocking {
+    /// Chance per tick that a blocking high forms while none is in place
+    pub chance: f32,
+}
+
+/// Station pressure (hPa) at a tile's surface from its sea-level pressure,
+/// elevation and surface temperature (K), by the hypsometric equation.
+///
+/// The air column between sea level and the surface is taken at its mean
+/// temperature under the standard lapse rate. Tiles at or below sea level
+/// keep the sea-level pressure.
+pub fn station_pressure(sea_level_pressure: f32, elevation: f32, temperature: f32) -> f32 {
+    let height = elevation.max(0.0) as f64 * METERS_PER_ELEVATION;
+    let mean_temperature = (temperature as f64).max(150.0) + STANDARD_LAPSE_RATE * height / 2.0;
+    (sea_level_pressure as f64 * (-GRAVITY * height / (DRY_AIR_GAS_CONSTANT * mean_temperature)).exp()) as f32
+}
+
 /// Spatial grid for fast nearest-tile lookup (~10-degree resolution).
-/// Bins tiles by lat/lon to avoid O(N) linear scan in intensify_decay.
+/// Bins tiles by lat/lon to avoid O(N) linear scans in intensify_decay
+/// and when building pressure system footprints.
 struct SpatialGrid {
     /// 18 lat bins x 36 lon bins = 648 cells
     cells: Vec<Vec<usize>>,
@@ -21,11 +67,15 @@ struct SpatialGrid {
 
 impl SpatialGrid {
     fn new(tiles: &[(f64, f64, TerrainType, f32)]) -> Self {
+        Self::from_coords(tiles.iter().map(|&(lat, lon, _, _)| (lat, lon)))
+    }
+
+    fn from_coords(coords: impl Iterator<Item = (f64, f64)>) -> Self {
         let lat_bins = 18; // 10-degree resolution: -90..90
         let lon_bins = 36; // 10-degree resolution: -180..180
         let mut cells = vec![Vec::new(); lat_bins * lon_bins];
 
-        for (i, &(lat, lon, _, _)) in tiles.iter().enumerate() {
+        for (i, (lat, lon)) in coords.enumerate() {
             let cell = Self::cell_index_static(lat, lon, lat_bins, lon_bins);
             cells[cell].push(i);
         }
@@ -33,6 +83,40 @@ impl SpatialGrid {
         SpatialGrid { cells, lat_bins, lon_bins }
     }
 
+    /// Tile indices in all cells that may hold points within `radius` radians
+    /// of (lat, lon). The result is a superset; callers filter by exact distance.
+    fn candidates_within(&self, lat: f64, lon: f64, radius: f64) -> Vec<usize> {
+        let radius_deg = radius.to_degrees();
+        let lat_step = 180.0 / self.lat_bins as f64;
+        let lon_step = 360.0 / self.lon_bins as f64;
+
+        let lat_lo = lat - radius_deg;
+        let lat_hi = lat + radius_deg;
+        let row_lo = ((lat_lo + 90.0) / lat_step).floor().max(0.0) as usize;
+        let row_hi = (((lat_hi + 90.0) / lat_step).floor() as usize).min(self.lat_bins - 1);
+
+        // Longitude half-width of a spherical cap: asin(sin r / cos lat).
+        // Caps reaching a pole span all longitudes.
+        let sin_half = radius.sin() / lat.to_radians().cos();
+        let cols: Vec<usize> = if lat_lo <= -90.0 || lat_hi >= 90.0 || sin_half >= 1.0 {
+            (0..self.lon_bins).collect()
+        } else {
+            let half = sin_half.asin().to_degrees();
+            let col_lo = ((lon - half + 180.0) / lon_step).floor() as isize;
+            let col_hi = ((lon + half + 180.0) / lon_step).floor() as isize;
+            let n = self.lon_bins as isize;
+            (col_lo..=col_hi).map(|c| (((c % n) + n) % n) as usize).collect()
+        };
+
+        let mut out = Vec::new();
+        for r in row_lo..=row_hi {
+            for &c in &cols {
+                out.extend_from_slice(&self.cells[r * self.lon_bins + c]);
+            }
+        }
+        out
+    }
+
     fn cell_index_static(lat: f64, lon: f64, lat_bins: usize, lon_bins: usize) -> usize {
         let lat_bin = ((lat + 90.0) / 180.0 * lat_bins as f64).floor() as usize;
         let lon_bin = ((lon + 180.0) / 360.0 * lon_bins as f64).floor() as usize;
@@ -48,12 +132,19 @@ impl SpatialGrid {
         lon: f64,
         tiles: &'a [(f64, f64, TerrainType, f32)],
     ) -> (TerrainType, f32) {
+        match self.nearest_index(lat, lon, tiles) {
+            Some(i) => (tiles[i].2, tiles[i].3),
+            None => (TerrainType::Ocean, 288.0),
+        }
+    }
+
+    /// Index of the nearest tile to (lat, lon), or `None` if no tile is binned near it.
+    fn nearest_index(&self, lat: f64, lon: f64, tiles: &[(f64, f64, TerrainType, f32)]) -> Option<usize> {
         let lat_bin = ((lat + 90.0) / 180.0 * self.lat_bins as f64).floor() as isize;
         let lon_bin = ((lon + 180.0) / 360.0 * self.lon_bins as f64).floor() as isize;
 
         let mut min_dist = f64::MAX;
-        let mut nearest_terrain = TerrainType::Ocean;
-        let mut nearest_temp = 288.0_f32;
+        let mut nearest = None;
 
         // Check 3x3 neighborhood (target + 8 neighbors)
         for dlat in -1..=1 {
@@ -71,23 +162,22 @@ impl SpatialGrid {
 
                 let cell_idx = r * self.lon_bins + c;
                 for &tile_idx in &self.cells[cell_idx] {
-                    let (tlat, tlon, terrain, temp) = tiles[tile_idx];
+                    let (tlat, tlon, _, _) = tiles[tile_idx];
                     let dist = sphere_math::angular_distance(lat, lon, tlat, tlon);
                     if dist < min_dist {
                         min_dist = dist;
-                        nearest_terrain = terrain;
-                        nearest_temp = temp;
+                        nearest = Some(tile_idx);
                     }
                 }
             }
         }
 
-        (nearest_terrain, nearest_temp)
+        nearest
     }
 }
 
 /// Simple xorshift64 PRNG for deterministic macro weather.
-fn xorshift64(state: &mut u64) -> u64 {
+pub(crate) fn xorshift64(state: &mut u64) -> u64 {
     if *state == 0 {
         *state = 1;
     }
@@ -98,7 +188,7 @@ fn xorshift64(state: &mut u64) -> u64 {
 }
 
 /// Returns a deterministic f64 in [0, 1) from the RNG state.
-fn rand_f64(state: &mut u64) -> f64 {
+pub(crate) fn rand_f64(state: &mut u64) -> f64 {
     xorshift64(state) as f64 / u64::MAX as f64
 }
 
@@ -108,13 +198,18 @@ fn rand_range(state: &mut u64, min: f64, max: f64) -> f64 {
 }
 
 /// Run the full macro weather step: evolve systems, then project onto tiles.
-pub fn macro_weather_step(world: &mut World) {
-    evolve_systems(world);
+/// With `blocking`, stationary highs occasionally form and divert cyclones.
+/// Returns the hurricane landfalls of the step.
+pub fn macro_weather_step(world: &mut World, blocking: Option<&Blocking>) -> Vec<WorldEvent> {
+    let landfalls = evolve_systems(world, blocking);
     project_macro_to_tiles(world);
+    landfalls
 }
 
-/// Evolve pressure systems: spawn new ones, move existing, intensify/decay, merge.
-fn evolve_systems(world: &mut World) {
+/// Evolve pressure systems: spawn new ones, move existing, intensify/decay,
+/// merge. Returns a landfall event for each tropical low that moved from over
+/// the ocean to over land.
+fn evolve_systems(world: &mut World, blocking: Option<&Blocking>) -> Vec<WorldEvent> {
     let tile_count = world.tiles.len();
     let max_systems = (tile_count / 100).max(5).min(80);
 
@@ -123,13 +218,10 @@ fn evolve_systems(world: &mut World) {
         // Attempt spawns based on world conditions
         spawn_systems(world, max_systems);
     }
-
-    // === MOVE ===
-    for system in &mut world.macro_weather.systems {
-        move_system(system);
+    if let Some(blocking) = blocking {
+        spawn_block(world, blocking);
     }
 
-    // === INTENSIFY / DECAY ===
     let tiles_snapshot: Vec<(f64, f64, TerrainType, f32)> = world
         .tiles
         .iter()
@@ -137,7 +229,44 @@ fn evolve_systems(world: &mut World) {
         .collect();
 
     let grid = SpatialGrid::new(&tiles_snapshot);
+    let over_land = |system: &PressureSystem| {
+        grid.nearest_index(system.lat, system.lon, &tiles_snapshot)
+            .filter(|&i| tiles_snapshot[i].2 != TerrainType::Ocean)
+    };
+
+    // === MOVE ===
+    let at_sea: Vec<bool> = world
+        .macro_weather
+        .systems
+        .iter()
+        .map(|s| s.system_type == PressureSystemType::TropicalLow && over_land(s).is_none())
+        .collect();
+    let blocks: Vec<(f64, f64, f32)> = world
+        .macro_weather
+        .systems
+        .iter()
+        .filter(|s| s.system_type == PressureSystemType::BlockingHigh)
+        .map(|s| (s.lat, s.lon, s.radius))
+        .collect();
+    for system in &mut world.macro_weather.systems {
+        move_system(system, &blocks);
+    }
+    let landfalls = world
+        .macro_weather
+        .systems
+        .iter()
+        .zip(at_sea)
+        .filter(|&(_, at_sea)| at_sea)
+        .filter_map(|(system, _)| {
+            let tile = over_land(system)?;
+            let mut event = events::native(WorldEvent::HURRICANE_LANDFALL, world.tiles[tile].id);
+            event.data.insert("system".to_string(), EventValue::Number(system.id as f64));
+            event.data.insert("pressure_anomaly".to_string(), EventValue::Number(system.pressure_anomaly as f64));
+            Some(event)
+        })
+        .collect();
 
+    // === INTENSIFY / DECAY ===
     let rng = &mut world.macro_weather.rng_state;
     for system in &mut world.macro_weather.systems {
         intensify_decay(system, &tiles_snapshot, &grid, rng);
@@ -148,6 +277,8 @@ fn evolve_systems(world: &mut World) {
 
     // === REMOVE DEAD ===
     world.macro_weather.systems.retain(|s| s.pressure_anomaly.abs() >= 2.0 && s.age <= s.max_age);
+
+    landfalls
 }
 
 fn spawn_systems(world: &mut World, max_systems: usize) {
@@ -209,64 +340,96 @@ fn spawn_systems(world: &mut World, max_systems: usize) {
     };
 
     if let Some(st) = system_type {
-        let (pressure_anomaly, radius, max_age, moisture) = match st {
-            PressureSystemType::MidLatCyclone => (
-                rand_range(rng, -20.0, -8.0) as f32,
-                rand_range(rng, 0.15, 0.35) as f32,
-                (rand_range(rng, 80.0, 200.0)) as u32,
-                rand_range(rng, 0.4, 0.8) as f32,
-            ),
-            PressureSystemType::SubtropicalHigh => (
-                rand_range(rng, 8.0, 18.0) as f32,
-                rand_range(rng, 0.25, 0.45) as f32,
-                (rand_range(rng, 200.0, 500.0)) as u32,
-                rand_range(rng, 0.1, 0.3) as f32,
-            ),
-            PressureSystemType::TropicalLow => (
-                rand_range(rng, -25.0, -10.0) as f32,
-                rand_range(rng, 0.1, 0.25) as f32,
-                (rand_range(rng, 60.0, 150.0)) as u32,
-                rand_range(rng, 0.6, 0.95) as f32,
-            ),
-            PressureSystemType::PolarHigh => (
-                rand_range(rng, 10.0, 25.0) as f32,
-                rand_range(rng, 0.2, 0.4) as f32,
-                (rand_range(rng, 300.0, 600.0)) as u32,
-                rand_range(rng, 0.05, 0.2) as f32,
-            ),
-            PressureSystemType::ThermalLow => (
-                rand_range(rng, -12.0, -5.0) as f32,
-                rand_range(rng, 0.1, 0.2) as f32,
-                (rand_range(rng, 40.0, 100.0)) as u32,
-                rand_range(rng, 0.1, 0.3) as f32,
-            ),
-        };
-
-        let (x, y, z) = sphere_math::lat_lon_to_xyz(lat, lon);
-        let id = world.macro_weather.next_id;
-        world.macro_weather.next_id += 1;
+        push_system(world, st, lat, lon);
+    }
+}
 
-        world.macro_weather.systems.push(PressureSystem {
-            id,
-            lat,
-            lon,
-            x,
-            y,
-            z,
-            pressure_anomaly,
-            radius,
-            velocity_east: 0.0,
-            velocity_north: 0.0,
-            age: 0,
-            max_age,
-            system_type: st,
-            moisture,
-        });
+/// Start a blocking high somewhere in the mid-latitudes, with chance
+/// `blocking.chance`, unless one is already in place.
+fn spawn_block(world: &mut World, blocking: &Blocking) {
+    let state = &mut world.macro_weather;
+    if state.systems.iter().any(|s| s.system_type == PressureSystemType::BlockingHigh) {
+        return;
+    }
+    let rng = &mut state.rng_state;
+    if rand_f64(rng) >= blocking.chance as f64 {
+        return;
     }
+    let hemisphere = if rand_f64(rng) < 0.5 { 1.0 } else { -1.0 };
+    let lat = hemisphere * rand_range(rng, BLOCK_MIN_LATITUDE, BLOCK_MAX_LATITUDE);
+    let lon = rand_range(rng, -180.0, 180.0);
+    push_system(world, PressureSystemType::BlockingHigh, lat, lon);
+}
+
+/// Add a new system of the given type at a position, drawing its strength,
+/// size, lifetime and moisture from the macro weather RNG.
+fn push_system(world: &mut World, st: PressureSystemType, lat: f64, lon: f64) {
+    let rng = &mut world.macro_weather.rng_state;
+    let (pressure_anomaly, radius, max_age, moisture) = match st {
+        PressureSystemType::MidLatCyclone => (
+            rand_range(rng, -20.0, -8.0) as f32,
+            rand_range(rng, 0.15, 0.35) as f32,
+            (rand_range(rng, 80.0, 200.0)) as u32,
+            rand_range(rng, 0.4, 0.8) as f32,
+        ),
+        PressureSystemType::SubtropicalHigh => (
+            rand_range(rng, 8.0, 18.0) as f32,
+            rand_range(rng, 0.25, 0.45) as f32,
+            (rand_range(rng, 200.0, 500.0)) as u32,
+            rand_range(rng, 0.1, 0.3) as f32,
+        ),
+        PressureSystemType::TropicalLow => (
+            rand_range(rng, -25.0, -10.0) as f32,
+            rand_range(rng, 0.1, 0.25) as f32,
+            (rand_range(rng, 60.0, 150.0)) as u32,
+            rand_range(rng, 0.6, 0.95) as f32,
+        ),
+        PressureSystemType::PolarHigh => (
+            rand_range(rng, 10.0, 25.0) as f32,
+            rand_range(rng, 0.2, 0.4) as f32,
+            (rand_range(rng, 300.0, 600.0)) as u32,
+            rand_range(rng, 0.05, 0.2) as f32,
+        ),
+        PressureSystemType::ThermalLow => (
+            rand_range(rng, -12.0, -5.0) as f32,
+            rand_range(rng, 0.1, 0.2) as f32,
+            (rand_range(rng, 40.0, 100.0)) as u32,
+            rand_range(rng, 0.1, 0.3) as f32,
+        ),
+        PressureSystemType::BlockingHigh => (
+            rand_range(rng, 15.0, 25.0) as f32,
+            rand_range(rng, 0.3, 0.45) as f32,
+            (rand_range(rng, 30.0, 90.0)) as u32,
+            rand_range(rng, 0.05, 0.15) as f32,
+        ),
+    };
+
+    let (x, y, z) = sphere_math::lat_lon_to_xyz(lat, lon);
+    let id = world.macro_weather.next_id;
+    world.macro_weather.next_id += 1;
+
+    world.macro_weather.systems.push(PressureSystem {
+        id,
+        lat,
+        lon,
+        x,
+        y,
+        z,
+        pressure_anomaly,
+        radius,
+        velocity_east: 0.0,
+        velocity_north: 0.0,
+        age: 0,
+        max_age,
+        system_type: st,
+        moisture,
+    });
 }
 
-/// Move a pressure system based on its type and latitude.
-fn move_system(system: &mut PressureSystem) {
+/// Move a pressure system based on its type and latitude. Cyclones that
+/// run into one of the `blocks` (lat, lon, radius) stall and are steered
+/// around it, poleward or equatorward of its center.
+fn move_system(system: &mut PressureSystem, blocks: &[(f64, f64, f32)]) {
     let abs_lat = system.lat.abs();
 
     // Steering flow by latitude band
@@ -293,8 +456,27 @@ fn move_system(system: &mut PressureSystem) {
             // Nearly stationary (tied to land heating)
             (0.0003_f32, 0.0_f32)
         }
+        PressureSystemType::BlockingHigh => {
+            // Locked in place until it collapses
+            (0.0_f32, 0.0_f32)
+        }
     };
 
+    let (mut base_east, mut base_north) = (base_east, base_north);
+    if system.system_type == PressureSystemType::MidLatCyclone {
+        for &(block_lat, block_lon, block_radius) in blocks {
+            let reach = block_radius as f64 * BLOCK_DIVERSION_RADII;
+            let dist = sphere_math::angular_distance(system.lat, system.lon, block_lat, block_lon);
+            if dist >= reach {
+                continue;
+            }
+            let strength = (1.0 - dist / reach) as f32;
+            let away = if system.lat >= block_lat { 1.0 } else { -1.0 };
+            base_east *= 1.0 - strength;
+            base_north += away * BLOCK_DIVERSION_SPEED * strength;
+        }
+    }
+
     // Blend current velocity toward steering flow
     system.velocity_east = system.velocity_east * 0.8 + base_east * 0.2;
     system.velocity_north = system.velocity_north * 0.8 + base_north * 0.2;
@@ -338,7 +520,7 @@ fn intensify_decay(
         PressureSystemType::TropicalLow => {
             if warm_ocean { 1.04 } else if over_ocean { 1.0 } else { 0.92 }
         }
-        PressureSystemType::SubtropicalHigh | PressureSystemType::PolarHigh => {
+        PressureSystemType::SubtropicalHigh | PressureSystemType::PolarHigh | PressureSystemType::BlockingHigh => {
             1.0 // stable
         }
         PressureSystemType::ThermalLow => {
@@ -404,12 +586,17 @@ fn merge_systems(systems: &mut Vec<PressureSystem>) {
 
 /// Project macro weather effects (pressure, wind, humidity) from all pressure systems
 /// onto every tile, using parallel evaluation.
+///
+/// Each tile only evaluates the systems whose cached footprint contains it, so
+/// cost scales with system area rather than tiles × systems.
 fn project_macro_to_tiles(world: &mut World) {
-    let systems = &world.macro_weather.systems;
-    if systems.is_empty() {
+    if world.macro_weather.systems.is_empty() {
+        world.macro_weather.projection_cache.footprints.clear();
         // Reset macro fields to defaults when no systems exist
         for tile in &mut world.tiles {
             tile.weather.pressure = 1013.25;
+            tile.weather.station_pressure =
+                station_pressure(1013.25, tile.geology.elevation, tile.weather.temperature);
             tile.weather.macro_wind_speed = 0.0;
             tile.weather.macro_wind_direction = 0.0;
             tile.weather.macro_humidity = 0.0;
@@ -417,6 +604,11 @@ fn project_macro_to_tiles(world: &mut World) {
         return;
     }
 
+    refresh_footprints(world);
+
+    let systems = &world.macro_weather.systems;
+    let footprints = &world.macro_weather.projection_cache.footprints;
+
     // Pre-compute system data for parallel access
     let system_data: Vec<_> = systems
         .iter()
@@ -432,33 +624,116 @@ fn project_macro_to_tiles(world: &mut World) {
         })
         .collect();
 
+    // Invert footprints into per-tile system lists (CSR layout). Systems are
+    // visited in order, so each tile sees them in the same order as a full scan.
+    let tile_count = world.tiles.len();
+    let mut offsets = vec![0usize; tile_count + 1];
+    for system in systems {
+        for &t in &footprints[&system.id].tiles {
+            offsets[t as usize + 1] += 1;
+        }
+    }
+    for i in 0..tile_count {
+        offsets[i + 1] += offsets[i];
+    }
+    let mut cursor = offsets.clone();
+    let mut tile_systems = vec![0usize; offsets[tile_count]];
+    for (si, system) in systems.iter().enumerate() {
+        for &t in &footprints[&system.id].tiles {
+            tile_systems[cursor[t as usize]] = si;
+            cursor[t as usize] += 1;
+        }
+    }
+
     // Compute macro fields for each tile in parallel
     let macro_fields: Vec<(f32, f32, f32, f32)> = world
         .tiles
         .par_iter()
-        .map(|tile| {
+        .enumerate()
+        .map(|(i, tile)| {
             compute_tile_macro_fields(
                 tile.position.lat,
                 tile.position.lon,
-                &system_data,
+                tile_systems[offsets[i]..offsets[i + 1]]
+                    .iter()
+                    .map(|&si| &system_data[si]),
             )
         })
         .collect();
 
     // Apply computed fields to tiles
     for (i, (pressure, wind_speed, wind_dir, humidity)) in macro_fields.into_iter().enumerate() {
-        world.tiles[i].weather.pressure = pressure;
-        world.tiles[i].weather.macro_wind_speed = wind_speed;
-        world.tiles[i].weather.macro_wind_direction = wind_dir;
-        world.tiles[i].weather.macro_humidity = humidity;
+        let tile = &mut world.tiles[i];
+        tile.weather.pressure = pressure;
+        tile.weather.station_pressure =
+            station_pressure(pressure, tile.geology.elevation, tile.weather.temperature);
+        tile.weather.macro_wind_speed = wind_speed;
+        tile.weather.macro_wind_direction = wind_dir;
+        tile.weather.macro_humidity = humidity;
+    }
+}
+
+/// Bring the footprint cache in line with the current systems: drop footprints
+/// of dead systems and rebuild any that are missing, or whose system has moved
+/// more than `FOOTPRINT_MOVE_THRESHOLD` or changed radius.
+fn refresh_footprints(world: &mut World) {
+    let cache = &mut world.macro_weather.projection_cache;
+    if cache.tile_count != world.tiles.len() {
+        cache.footprints.clear();
+        cache.tile_count = world.tiles.len();
+    }
+
+    let systems = &world.macro_weather.systems;
+    cache
+        .footprints
+        .retain(|id, _| systems.iter().any(|s| s.id == *id));
+
+    let mut grid: Option<SpatialGrid> = None;
+    for system in systems {
+        let stale = match cache.footprints.get(&system.id) {
+            Some(fp) => {
+                fp.radius != system.radius
+                    || sphere_math::angular_distance(fp.lat, fp.lon, system.lat, system.lon)
+                        > FOOTPRINT_MOVE_THRESHOLD
+            }
+            None => true,
+        };
+        if !stale {
+            continue;
+        }
+
+        let grid = grid.get_or_insert_with(|| {
+            SpatialGrid::from_coords(world.tiles.iter().map(|t| (t.position.lat, t.position.lon)))
+        });
+        let reach = system.radius as f64 * FOOTPRINT_RADII + FOOTPRINT_MOVE_THRESHOLD;
+        let mut tiles: Vec<u32> = grid
+            .candidates_within(system.lat, system.lon, reach)
+            .into_iter()
+            .filter(|&i| {
+                let p = &world.tiles[i].position;
+                sphere_math::angular_distance(system.lat, system.lon, p.lat, p.lon) <= reach
+            })
+            .map(|i| i as u32)
+            .collect();
+        tiles.sort_unstable();
+
+        cache.footprints.insert(
+            system.id,
+            SystemFootprint {
+                lat: system.lat,
+                lon: system.lon,
+                radius: system.radius,
+                tiles,
+            },
+        );
     }
 }
 
 /// Compute macro weather fields for a single tile from all pressure systems.
-fn compute_tile_macro_fields(
+fn compute_tile_macro_fields<'a>(
     tile_lat: f64,
     tile_lon: f64,
-    systems: &[(f64, f64, f32, f32, f32, PressureSystemType)],
+    systems: impl IntoIterator<Item = &'a (f64, f64, f32, f32, f32, PressureSystemType)>,
 ) -> (f32, f32, f32, f32) {
     let mut pressure_sum = 0.0_f32;
     let mut wind_east_sum = 0.0_f64;
@@ -555,12 +830,7 @@ mod tests {
             seed: 42,
             tile_count,
             ocean_ratio: 0.3,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: crate::config::generation::TopologyConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -569,15 +839,48 @@ mod tests {
             seed: 42,
             tile_count: 1000,
             ocean_ratio: 0.3,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
             topology: crate::config::generation::TopologyConfig {
                 mode: "geodesic".to_string(),
                 subdivision_level: level,
+                boundary: "wrap".to_string(),
             },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn station_pressure_follows_the_standard_atmosphere() {
+        // Sea level and below keep the sea-level pressure
+        assert_eq!(station_pressure(1013.25, 0.0, 288.15), 1013.25);
+        assert_eq!(station_pressure(1000.0, -0.4, 288.15), 1000.0);
+        // 1500 m (0.5) in the standard atmosphere is about 845 hPa, 3000 m about 701 hPa
+        let mid = station_pressure(1013.25, 0.5, 288.15 - 9.75);
+        assert!((mid - 845.6).abs() < 1.5, "{}", mid);
+        let top = station_pressure(1013.25, 1.0, 288.15 - 19.5);
+        assert!((top - 701.1).abs() < 2.0, "{}", top);
+        // Colder columns are denser, so pressure falls off faster
+        assert!(station_pressure(1013.25, 1.0, 240.0) < top);
+        // A sea-level low stays low at the station
+        assert!(station_pressure(990.0, 0.5, 278.4) < mid);
+    }
+
+    #[test]
+    fn projection_sets_station_pressure_from_elevation() {
+        let mut world = generate_world(&geodesic_gen_params(3));
+        for _ in 0..5 {
+            macro_weather_step(&mut world, None);
+        }
+        for tile in &world.tiles {
+            let w = &tile.weather;
+            if tile.geology.elevation <= 0.0 {
+                assert_eq!(w.station_pressure, w.pressure);
+            } else {
+                assert!(w.station_pressure < w.pressure);
+                assert_eq!(
+                    w.station_pressure,
+                    station_pressure(w.pressure, tile.geology.elevation, w.temperature)
+                );
+            }
         }
     }
 
@@ -588,8 +891,8 @@ mod tests {
 
         // Run 50 ticks of macro weather
         for _ in 0..50 {
-            macro_weather_step(&mut world_a);
-            macro_weather_step(&mut world_b);
+            macro_weather_step(&mut world_a, None);
+            macro_weather_step(&mut world_b, None);
         }
 
         assert_eq!(
@@ -616,7 +919,7 @@ mod tests {
 
         // Run enough ticks for systems to spawn
         for _ in 0..100 {
-            macro_weather_step(&mut world);
+            macro_weather_step(&mut world, None);
         }
 
         assert!(
@@ -643,6 +946,59 @@ mod tests {
                 system.pressure_anomaly
             );
         }
+        assert!(world.macro_weather.systems.iter().all(|s| s.system_type != PressureSystemType::BlockingHigh));
+    }
+
+    #[test]
+    fn blocking_high_forms_and_holds_its_place() {
+        let mut world = generate_world(&default_gen_params(500));
+        let blocking = Blocking { chance: 1.0 };
+        macro_weather_step(&mut world, Some(&blocking));
+        let blocks: Vec<_> = world
+            .macro_weather
+            .systems
+            .iter()
+            .filter(|s| s.system_type == PressureSystemType::BlockingHigh)
+            .cloned()
+            .collect();
+        assert_eq!(blocks.len(), 1);
+        let block = &blocks[0];
+        assert!((BLOCK_MIN_LATITUDE..=BLOCK_MAX_LATITUDE).contains(&block.lat.abs()));
+        assert!((30..=90).contains(&block.max_age));
+
+        // Only one at a time, and it stays where it formed
+        for _ in 0..20 {
+            macro_weather_step(&mut world, Some(&blocking));
+        }
+        let now: Vec<_> = world
+            .macro_weather
+            .systems
+            .iter()
+            .filter(|s| s.system_type == PressureSystemType::BlockingHigh)
+            .collect();
+        assert_eq!(now.len(), 1);
+        assert_eq!(now[0].id, block.id);
+        assert!(sphere_math::angular_distance(now[0].lat, now[0].lon, block.lat, block.lon) < 1e-9);
+    }
+
+    #[test]
+    fn cyclones_steered_around_blocks() {
+        let block = (50.0, 10.0, 0.4_f32);
+        let mut free = test_system(1, 53.0, 0.0, 0.2);
+        let mut north = free.clone();
+        let mut south = test_system(2, 47.0, 0.0, 0.2);
+        move_system(&mut free, &[]);
+        move_system(&mut north, &[block]);
+        move_system(&mut south, &[block]);
+
+        assert!(north.velocity_east < free.velocity_east);
+        assert!(north.velocity_north > free.velocity_north);
+        assert!(south.velocity_north < 0.0);
+
+        // Out of reach, nothing changes
+        let mut far = test_system(1, 53.0, 0.0, 0.2);
+        move_system(&mut far, &[(50.0, 120.0, 0.4)]);
+        assert_eq!(far, free);
     }
 
     #[test]
@@ -739,7 +1095,7 @@ mod tests {
         let mut world = generate_world(&geodesic_gen_params(2));
 
         for _ in 0..50 {
-            macro_weather_step(&mut world);
+            macro_weather_step(&mut world, None);
         }
 
         // Should work without errors on geodesic worlds
@@ -765,7 +1121,7 @@ mod tests {
 
         // Run many ticks to ensure spawning is capped
         for _ in 0..500 {
-            macro_weather_step(&mut world);
+            macro_weather_step(&mut world, None);
         }
 
         assert!(
@@ -847,6 +1203,114 @@ mod tests {
         assert_eq!(terrain, TerrainType::Coast);
     }
 
+    fn test_system(id: u32, lat: f64, lon: f64, radius: f32) -> PressureSystem {
+        let (x, y, z) = sphere_math::lat_lon_to_xyz(lat, lon);
+        PressureSystem {
+            id,
+            lat,
+            lon,
+            x,
+            y,
+            z,
+            pressure_anomaly: -15.0,
+            radius,
+            velocity_east: 0.0,
+            velocity_north: 0.0,
+            age: 0,
+            max_age: 1000,
+            system_type: PressureSystemType::MidLatCyclone,
+            moisture: 0.6,
+        }
+    }
+
+    #[test]
+    fn tropical_lows_moving_ashore_make_landfall() {
+        let mut world = generate_world(&geodesic_gen_params(3));
+        let mut hurricane = test_system(900, 15.0, -40.0, 0.2);
+        hurricane.system_type = PressureSystemType::TropicalLow;
+        hurricane.velocity_east = -0.2;
+        let mut moved = hurricane.clone();
+        move_system(&mut moved, &[]);
+        let tiles: Vec<_> =
+            world.tiles.iter().map(|t| (t.position.lat, t.position.lon, TerrainType::Ocean, 288.0)).collect();
+        let grid = SpatialGrid::new(&tiles);
+        let start = grid.nearest_index(hurricane.lat, hurricane.lon, &tiles).unwrap();
+        let ashore = grid.nearest_index(moved.lat, moved.lon, &tiles).unwrap();
+        assert_ne!(start, ashore);
+
+        // Open ocean with one island where the storm is heading
+        for tile in &mut world.tiles {
+            tile.geology.terrain_type = TerrainType::Ocean;
+        }
+        world.tiles[ashore].geology.terrain_type = TerrainType::Coast;
+        world.macro_weather.systems = vec![hurricane.clone()];
+        let events = macro_weather_step(&mut world, None);
+        assert_eq!(events.len(), 1, "{:?}", events);
+        assert_eq!(events[0].kind, WorldEvent::HURRICANE_LANDFALL);
+        assert_eq!(events[0].tile, Some(world.tiles[ashore].id));
+        assert_eq!(events[0].data["system"], EventValue::Number(900.0));
+
+        // A storm already over land doesn't make landfall again
+        world.tiles[start].geology.terrain_type = TerrainType::Coast;
+        world.macro_weather.systems = vec![hurricane];
+        assert!(macro_weather_step(&mut world, None).is_empty());
+    }
+
+    #[test]
+    fn culled_projection_matches_full_scan() {
+        let mut world = generate_world(&geodesic_gen_params(3));
+        // Include systems near a pole and straddling the date line
+        world.macro_weather.systems.push(test_system(900, 84.0, 30.0, 0.4));
+        world.macro_weather.systems.push(test_system(901, -10.0, 178.0, 0.3));
+        world.macro_weather.next_id = 1000;
+
+        for _ in 0..60 {
+            macro_weather_step(&mut world, None);
+
+            let all_systems: Vec<_> = world
+                .macro_weather
+                .systems
+                .iter()
+                .map(|s| (s.lat, s.lon, s.pressure_anomaly, s.radius, s.moisture, s.system_type))
+                .collect();
+            for tile in &world.tiles {
+                let (pressure, speed, dir, humidity) =
+                    compute_tile_macro_fields(tile.position.lat, tile.position.lon, &all_systems);
+                assert_eq!(tile.weather.pressure, pressure, "tile {}", tile.id);
+                assert_eq!(tile.weather.macro_wind_speed, speed, "tile {}", tile.id);
+                assert_eq!(tile.weather.macro_wind_direction, dir, "tile {}", tile.id);
+                assert_eq!(tile.weather.macro_humidity, humidity, "tile {}", tile.id);
+            }
+        }
+    }
+
+    #[test]
+    fn footprint_rebuilt_only_after_threshold_move() {
+        let mut world = generate_world(&geodesic_gen_params(2));
+        world.macro_weather.systems.push(test_system(1, 45.0, 0.0, 0.3));
+        project_macro_to_tiles(&mut world);
+
+        let fp = &world.macro_weather.projection_cache.footprints[&1];
+        assert_eq!((fp.lat, fp.lon), (45.0, 0.0));
+        assert!(!fp.tiles.is_empty() && fp.tiles.len() < world.tiles.len());
+
+        // A small move keeps the cached footprint
+        world.macro_weather.systems[0].lon = 1.0;
+        project_macro_to_tiles(&mut world);
+        assert_eq!(world.macro_weather.projection_cache.footprints[&1].lon, 0.0);
+
+        // Moving past the threshold rebuilds it around the new position
+        world.macro_weather.systems[0].lon = 10.0;
+        project_macro_to_tiles(&mut world);
+        assert_eq!(world.macro_weather.projection_cache.footprints[&1].lon, 10.0);
+
+        // Footprints of removed systems are dropped
+        world.macro_weather.systems[0].id = 2;
+        project_macro_to_tiles(&mut world);
+        let ids: Vec<u32> = world.macro_weather.projection_cache.footprints.keys().copied().collect();
+        assert_eq!(ids, vec![2]);
+    }
+
     #[test]
     fn empty_systems_resets_tile_fields() {
         let mut world = generate_world(&default_gen_params(100));
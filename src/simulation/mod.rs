@@ -1,14 +1,50 @@
+pub mod activity;
+pub mod aridity;
+pub mod assimilation;
+pub mod aurora;
+pub mod boundary;
+pub mod builtin_rules;
+pub mod capacity;
+pub mod crash;
+pub mod dust;
+pub mod effects;
 pub mod engine;
+pub mod erosion;
+pub mod events;
+pub mod flood;
+pub mod fog;
+pub mod freeze_thaw;
+pub mod glacier;
+pub mod globals;
+pub mod hydrology;
+pub mod invariants;
 pub mod macro_weather;
+pub mod mud;
+pub mod narration;
+pub mod neighbor_writes;
 pub mod native_eval;
+pub mod native_resources;
 pub mod native_weather;
+pub mod ocean;
 pub mod phase;
+pub mod profiler;
+pub mod regression;
+pub mod replay;
+pub mod rng_check;
+pub mod rule_params;
+pub mod rule_tests;
+pub mod rule_watch;
+pub mod sea_level;
 pub mod sphere_math;
 pub mod statistics;
+pub mod thermostat;
+pub mod waves;
 
-use tracing::warn;
+use tracing::{debug, warn};
 
-use crate::simulation::engine::{tile_immutable_rhai_map, Phase, RuleEngine, RuleError};
+use crate::simulation::engine::{tile_immutable_rhai_map, MutationStats, Phase, RuleEngine, RuleError, RuleStats};
+use crate::simulation::globals::GlobalAccumulator;
+use crate::simulation::profiler::{phase_stack, tick_stack, timed};
 use crate::simulation::statistics::TickStatistics;
 use crate::world::World;
 use std::time::Instant;
@@ -18,27 +54,85 @@ use std::time::Instant;
 pub struct TickResult {
     pub statistics: TickStatistics,
     pub rule_errors: Vec<RuleError>,
+    /// Mutations applied and rejected across all phases, by rule and reason
+    pub mutation_stats: MutationStats,
+    /// Invocations, errors and time of each Rhai rule this tick
+    pub rule_stats: RuleStats,
     /// Phase timings in ms: [MacroWeather, Weather, Conditions, Terrain, Resources, Statistics]
     pub phase_timings_ms: [f32; 6],
+    /// Tiles that started flooding this tick
+    pub floods: Vec<flood::FloodEvent>,
+    /// Slopes that gave way this tick, when erosion is enabled
+    pub landslides: Vec<erosion::LandslideEvent>,
+    /// Geomagnetic storm that started this tick, when the aurora driver is enabled
+    pub aurora: Option<aurora::AuroraEvent>,
+    /// Volcanic eruption this tick, when dust transport is enabled
+    pub eruption: Option<dust::EruptionEvent>,
+    /// Resource deposits that ran out this tick
+    pub depletions: Vec<native_resources::DepletionEvent>,
+    /// Calvings and retreats this tick, when glaciers are enabled
+    pub glacier_events: Vec<glacier::GlacierEvent>,
+    /// Tiles the sea took or gave up this tick, when sea level is enabled
+    pub shoreline_changes: Vec<sea_level::ShorelineChange>,
+    /// Tiles under each transient effect at the end of the tick
+    pub effects: effects::TileEffects,
+    /// Events raised by the simulation and emitted by rules this tick, in
+    /// the order they happened
+    pub events: Vec<events::WorldEvent>,
 }
 
 /// Execute a single simulation tick on the world.
 ///
-/// Runs the macro weather step (native Rust), then all 4 Rhai rule phases
-/// (Weather → Conditions → Terrain → Resources), advances tick count and
-/// season, increments biome stability counters, then computes statistics.
+/// Runs the macro weather step (native Rust) and the optional aurora driver, then all 4 Rhai rule phases
+/// (Weather → Conditions → Terrain → Resources) with the native aridity, fog, soil seepage, hydrology, flood,
+/// mud and carrying capacity steps (and optional thermostat/dust/ocean/waves/glacier/freeze-thaw/erosion/sea level) in between, publishes rule globals for
+/// the next tick, advances tick count and season, increments biome stability
+/// counters, gathers the tick's world events, updates world records and energy
+/// potential, then computes statistics.
 pub fn execute_tick(
     world: &mut World,
     engine: &RuleEngine,
     season_length: u32,
 ) -> TickResult {
     let tick_start = Instant::now();
+    let rules_before = engine.rule_stats();
     let mut all_errors: Vec<RuleError> = Vec::new();
+    let mut mutation_stats = MutationStats::default();
+    let mut contributions = GlobalAccumulator::default();
     let mut phase_timings = [0.0_f32; 6];
+    let mut floods = Vec::new();
+    let mut landslides = Vec::new();
+    let mut depletions = Vec::new();
+    let mut glacier_events = Vec::new();
+    let mut shoreline_changes = Vec::new();
+    let mut eruption = None;
+    let baseline = events::Baseline::of(world);
+
+    // Rules read the globals reduced over the previous tick, and through
+    // `global_get()` this tick's, which start from zero
+    engine.set_globals(&world.globals, &contributions);
+
+    // Idle tiles sit out this tick's rule phases
+    let activity_before = match engine.idle_skip() {
+        Some(idle_skip) => {
+            let skipped = idle_skip.choose(world);
+            debug!(tick = world.tick_count, skipped, "Idle tiles skipped");
+            Some(world.tiles.iter().map(activity::measure).collect::<Vec<_>>())
+        }
+        None => {
+            world.activity.skipped.clear();
+            None
+        }
+    };
+    let profiler = engine.profiler();
 
     // Phase 0: Macro weather (native Rust) — evolve pressure systems, project onto tiles
+    crash::enter_step("macro_weather");
     let macro_start = Instant::now();
-    macro_weather::macro_weather_step(world);
+    let mut events =
+        timed(profiler, || tick_stack("macro_weather"), || macro_weather::macro_weather_step(world, engine.blocking()));
+    // Optional cosmetic aurora; nothing rules can read
+    let aurora = timed(profiler, || tick_stack("aurora"), || engine.aurora().and_then(|a| a.apply(world)));
     phase_timings[0] = macro_start.elapsed().as_secs_f32() * 1000.0;
 
     // Build immutable maps once per tick — reused across all 4 Rhai phases
@@ -49,14 +143,128 @@ pub fn execute_tick(
     // Execute rule phases 1-4 (native Rust or Rhai per phase)
     for (i, p) in Phase::all().iter().enumerate() {
         let phase_start = Instant::now();
-        let errors = if engine.has_native_evaluator(*p) {
-            phase::execute_phase_native(world, engine.native_evaluator(*p).unwrap(), *p)
+        crash::enter_step(match p {
+            Phase::Weather => "weather",
+            Phase::Conditions => "conditions",
+            Phase::Terrain => "terrain",
+            Phase::Resources => "resources",
+        });
+        let step = |name: &str| phase_stack(*p, name);
+        let frost_before: Option<Vec<u32>> =
+            (*p == Phase::Conditions && engine.freeze_thaw().is_some())
+                .then(|| world.tiles.iter().map(|t| t.conditions.frost_days).collect());
+        // Deposits paged out in bounded-memory mode come back for the one phase that reads them
+        if *p == Phase::Resources
+            && let Some(pages) = engine.paging()
+            && let Err(e) = timed(profiler, || step("page_in"), || pages.page_in(&mut world.tiles))
+        {
+            panic!("{}", e);
+        }
+        let stocked_before =
+            (*p == Phase::Resources).then(|| native_resources::stocked_deposits(&world.tiles));
+        // Slow phases gather each tick's weather and conditions and only run
+        // once they have a whole period's worth; their native steps still run
+        let period = engine.phase_period(*p);
+        let due = period <= 1 || {
+            let inputs = world.phase_inputs.entry(p.dir_name().to_string()).or_default();
+            inputs.add(&world.tiles);
+            inputs.ticks >= period
+        };
+        let result = if !due {
+            phase::PhaseResult::default()
+        } else if engine.has_native_evaluator(*p) {
+            phase::execute_phase_native(world, engine.native_evaluator(*p).unwrap(), *p, profiler)
         } else {
             phase::execute_phase(world, engine, *p, &immutable_maps)
         };
+        if due && let Some(inputs) = world.phase_inputs.get_mut(p.dir_name()) {
+            inputs.clear();
+        }
+        events.extend(result.events);
+        // Optional latitude-band thermostat, applied natively right after weather
+        if *p == Phase::Weather
+            && let Some(thermostat) = engine.thermostat()
+        {
+            timed(profiler, || step("thermostat"), || thermostat.apply(world));
+        }
+        // Observed weather posted through the API, over the rules' and thermostat's
+        if *p == Phase::Weather && !world.observations.is_empty() {
+            timed(profiler, || step("assimilation"), || assimilation::nudge(world));
+        }
+        // Optional dust and ash transport, on the settled wind field
+        if *p == Phase::Weather
+            && let Some(dust) = engine.dust()
+        {
+            eruption = timed(profiler, || step("dust"), || dust.apply(world));
+            if let Some(event) = &eruption {
+                events.push(events::native(events::WorldEvent::VOLCANIC_ERUPTION, event.tile_id));
+            }
+        }
+        // Optional ocean salinity and overturning, before evaporation demand is read
+        if *p == Phase::Weather
+            && let Some(ocean) = engine.ocean()
+        {
+            timed(profiler, || step("ocean"), || ocean.apply(world));
+        }
+        // Optional wind waves, on the settled wind field
+        if *p == Phase::Weather
+            && let Some(waves) = engine.waves()
+        {
+            timed(profiler, || step("waves"), || waves.apply(world));
+        }
+        // Evaporation demand, aridity and fog, for the Conditions and Terrain rules
+        if *p == Phase::Weather {
+            timed(profiler, || step("aridity"), || aridity::update_aridity(world));
+            timed(profiler, || step("fog"), || fog::update_fog(world));
+        }
+        // Optional glaciers, so their meltwater joins this tick's runoff
+        if *p == Phase::Conditions
+            && let Some(glaciers) = engine.glaciers()
+        {
+            glacier_events = timed(profiler, || step("glaciers"), || glaciers.apply(world));
+        }
+        // Rivers, floods, mud and trafficability are computed natively once conditions are settled
+        if *p == Phase::Conditions {
+            timed(profiler, || step("soil_seepage"), || hydrology::spread_soil_moisture(world));
+            let overbank = timed(profiler, || step("hydrology"), || hydrology::update_hydrology(world));
+            floods = timed(profiler, || step("floods"), || flood::update_floods(world, &overbank));
+            timed(profiler, || step("mud"), || mud::update_mud(world));
+        }
+        // Optional freeze-thaw weathering, applied natively at the end of conditions
+        if let (Some(freeze_thaw), Some(frost_before)) = (engine.freeze_thaw(), &frost_before) {
+            timed(profiler, || step("freeze_thaw"), || freeze_thaw.apply(world, frost_before));
+        }
+        // Optional erosion and sediment transport, once the terrain has settled for this tick
+        if *p == Phase::Terrain
+            && let Some(erosion) = engine.erosion()
+        {
+            landslides = timed(profiler, || step("erosion"), || erosion.apply(world));
+        }
+        // Optional sea level, against this tick's land ice and carved terrain
+        if *p == Phase::Terrain
+            && let Some(sea_level) = engine.sea_level()
+        {
+            shoreline_changes = timed(profiler, || step("sea_level"), || sea_level.apply(world));
+        }
+        // Carrying capacity sums up the tile once resources have been renewed
+        if let Some(stocked_before) = &stocked_before {
+            depletions = native_resources::depleted_deposits(&world.tiles, stocked_before);
+            timed(profiler, || step("carrying_capacity"), || capacity::update_carrying_capacity(world));
+            if let Some(pages) = engine.paging()
+                && let Err(e) = timed(profiler, || step("page_out"), || pages.page_out(&mut world.tiles))
+            {
+                warn!(tick = world.tick_count, "Keeping deposits in memory: {}", e);
+            }
+        }
         phase_timings[i + 1] = phase_start.elapsed().as_secs_f32() * 1000.0;
-        all_errors.extend(errors);
+        all_errors.extend(result.errors);
+        mutation_stats.merge(result.mutation_stats);
+        if !result.contributions.is_empty() {
+            contributions.merge(result.contributions);
+            engine.set_globals(&world.globals, &contributions);
+        }
     }
+    world.globals = contributions.finish();
 
     // Advance tick count
     world.tick_count += 1;
@@ -71,11 +279,72 @@ pub fn execute_tick(
         tile.biome.ticks_in_current_biome += 1;
     }
 
+    if !floods.is_empty() {
+        debug!(tick = world.tick_count, count = floods.len(), "Floods started");
+    }
+    if !landslides.is_empty() {
+        debug!(tick = world.tick_count, count = landslides.len(), "Landslides");
+    }
+    if let Some(event) = &aurora {
+        debug!(tick = world.tick_count, strength = event.strength, "Geomagnetic storm started");
+    }
+    if let Some(event) = &eruption {
+        debug!(tick = world.tick_count, tile_id = event.tile_id, "Volcanic eruption");
+    }
+    if !depletions.is_empty() {
+        debug!(tick = world.tick_count, count = depletions.len(), "Resource deposits depleted");
+    }
+    if !glacier_events.is_empty() {
+        debug!(tick = world.tick_count, count = glacier_events.len(), "Glaciers calved or retreated");
+    }
+    if !shoreline_changes.is_empty() {
+        debug!(
+            tick = world.tick_count,
+            count = shoreline_changes.len(),
+            offset = world.sea_level.offset,
+            "Shoreline moved"
+        );
+    }
+
+    events.extend(baseline.changes(world));
+    if events.len() > events::MAX_EVENTS_PER_TICK {
+        warn!(
+            tick = world.tick_count,
+            count = events.len(),
+            kept = events::MAX_EVENTS_PER_TICK,
+            "Too many events in tick; dropping the rest"
+        );
+        events.truncate(events::MAX_EVENTS_PER_TICK);
+    }
+    for event in &mut events {
+        event.tick = world.tick_count;
+    }
+
+    let effects =
+        effects::TileEffects::gather(world, eruption.as_ref(), &landslides, &glacier_events);
+    if let (Some(idle_skip), Some(before)) = (engine.idle_skip(), &activity_before) {
+        idle_skip.update(world, before, &effects);
+    }
+
+    let broken = world.records.update(&world.tiles, world.tick_count);
+    if !broken.is_empty() {
+        debug!(tick = world.tick_count, ?broken, "World records broken");
+    }
+
+    // Wind and solar resource, averaged over a year
+    let declination = sphere_math::solar_declination(sphere_math::year_fraction(
+        world.season,
+        world.tick_count,
+        world.season_length,
+    ));
+    world.energy.update(&world.tiles, declination, world.season_length.saturating_mul(4));
+
     // Phase 6: Statistics
+    crash::enter_step("statistics");
     let stats_start = Instant::now();
     let tick_duration = tick_start.elapsed().as_secs_f32() * 1000.0;
     let statistics =
-        statistics::compute_statistics(world, all_errors.len() as u32, tick_duration);
+        statistics::compute_statistics(world, all_errors.len() as u32, tick_duration, engine.area_weighted_statistics());
     phase_timings[5] = stats_start.elapsed().as_secs_f32() * 1000.0;
 
     // Cascade detection: >10% tile errors
@@ -101,7 +370,18 @@ pub fn execute_tick(
     TickResult {
         statistics,
         rule_errors: all_errors,
+        mutation_stats,
+        rule_stats: engine.rule_stats().since(&rules_before),
         phase_timings_ms: phase_timings,
+        floods,
+        landslides,
+        aurora,
+        eruption,
+        depletions,
+        glacier_events,
+        shoreline_changes,
+        effects,
+        events,
     }
 }
 
@@ -122,12 +402,7 @@ mod tests {
             seed: 42,
             tile_count,
             ocean_ratio: 0.3,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: crate::config::generation::TopologyConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -184,6 +459,233 @@ mod tests {
         assert!(result.rule_errors.is_empty());
     }
 
+    /// An embedder's own Terrain evaluator: sets a fixed vegetation health
+    /// and tries to write a field the phase can't touch.
+    struct FixedHealth;
+
+    impl crate::simulation::native_eval::NativePhaseEvaluator for FixedHealth {
+        fn phase(&self) -> Phase {
+            Phase::Terrain
+        }
+
+        fn evaluate(&self, _: &Tile, _: &[&Tile], _: Season, _: u64, _: u64) -> engine::TileMutations {
+            engine::TileMutations {
+                mutations: vec![
+                    ("vegetation_health".to_string(), rhai::Dynamic::from(0.25_f64)),
+                    ("temperature".to_string(), rhai::Dynamic::from(400.0_f64)),
+                ],
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn custom_native_evaluator_mixes_with_rhai_phases() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "weather", &[("01-rain.rhai", r#"set("precipitation", 0.4);"#)]);
+        make_rule_dir(dir.path(), "terrain", &[("01-health.rhai", r#"set("vegetation_health", 0.9);"#)]);
+
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap().with_native_evaluator(FixedHealth);
+        assert_eq!(engine.native_phases(), vec![Phase::Terrain]);
+        let mut world = make_small_world();
+        let result = execute_tick(&mut world, &engine, 100);
+        assert!(result.rule_errors.is_empty());
+        // Rhai still runs the weather; the native evaluator replaces the terrain rule
+        assert!(world.tiles.iter().all(|t| t.weather.precipitation == 0.4));
+        assert!(world.tiles.iter().all(|t| t.biome.vegetation_health == 0.25));
+        let key = ("native/terrain".to_string(), engine::RejectionReason::UnwritableField);
+        assert_eq!(result.mutation_stats.rejected[&key], world.tiles.len() as u64);
+
+        // Removing it hands the phase back to Rhai
+        assert!(engine.remove_native_evaluator(Phase::Terrain).is_some());
+        assert!(engine.native_phases().is_empty());
+        execute_tick(&mut world, &engine, 100);
+        assert!(world.tiles.iter().all(|t| t.biome.vegetation_health == 0.9));
+    }
+
+    #[test]
+    fn accumulators_reach_later_phases_and_the_world_globals() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[
+                ("01-count.rhai", r#"global_add("tiles", 1); global_add("rain", 0.5);"#),
+                // The weather phase's own additions aren't in yet
+                ("02-read.rhai", r#"set("precipitation", global_get("tiles"));"#),
+            ],
+        );
+        make_rule_dir(
+            dir.path(),
+            "terrain",
+            &[("01-read.rhai", r#"set("vegetation_health", global_get("tiles") / 1000.0);"#)],
+        );
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = make_small_world();
+        let tiles = world.tiles.len() as f64;
+        let result = execute_tick(&mut world, &engine, 100);
+        assert!(result.rule_errors.is_empty(), "{:?}", result.rule_errors);
+        assert!(world.tiles.iter().all(|t| t.weather.precipitation == 0.0));
+        assert!(world.tiles.iter().all(|t| t.biome.vegetation_health == (tiles / 1000.0) as f32));
+        assert_eq!(world.globals["tiles"], tiles);
+        assert_eq!(world.globals["rain"], tiles * 0.5);
+
+        // Each tick starts over
+        execute_tick(&mut world, &engine, 100);
+        assert_eq!(world.globals["tiles"], tiles);
+        assert!(world.tiles.iter().all(|t| t.weather.precipitation == 0.0));
+    }
+
+    #[test]
+    fn rules_emit_events_tied_to_their_tile_and_rule() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[
+                (
+                    "01-squall.rhai",
+                    r#"
+                    if tile.id < 3 { emit_event("squall", #{ gusts: 0.5, count: 2, named: true, name: "Ana" }); }
+                    if tile.id == 1 { emit_event("calm"); }
+                    if tile.id == 3 { emit_event("odd", #{ list: [1] }); }
+                    "#,
+                ),
+                // A failing tile loses the events it emitted
+                ("02-fail.rhai", r#"if tile.id == 2 { throw "boom"; }"#),
+            ],
+        );
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = make_small_world();
+        let result = execute_tick(&mut world, &engine, 100);
+        assert_eq!(result.rule_errors.len(), 2, "{:?}", result.rule_errors);
+        assert!(result.rule_errors.iter().any(|e| e.tile_id == 3 && e.error.contains("'list'")));
+
+        let emitted: Vec<_> = result.events.iter().filter(|e| e.source != events::WorldEvent::NATIVE).collect();
+        let summary: Vec<_> = emitted.iter().map(|e| (e.kind.as_str(), e.tile, e.source.as_str())).collect();
+        assert_eq!(
+            summary,
+            [("squall", Some(0), "01-squall.rhai"), ("squall", Some(1), "01-squall.rhai"), ("calm", Some(1), "01-squall.rhai")]
+        );
+        assert!(result.events.iter().all(|e| e.tick == world.tick_count));
+        let data = &emitted[0].data;
+        assert_eq!(data["gusts"], events::EventValue::Number(0.5));
+        assert_eq!(data["count"], events::EventValue::Number(2.0));
+        assert_eq!(data["named"], events::EventValue::Bool(true));
+        assert_eq!(data["name"], events::EventValue::Text("Ana".to_string()));
+        assert!(emitted[2].data.is_empty());
+    }
+
+    #[test]
+    fn idle_tiles_sit_out_the_rule_phases() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "terrain", &[("01-health.rhai", r#"set("vegetation_health", 0.9);"#)]);
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        engine.set_idle_skip(Some(activity::IdleSkip { threshold: 0.05, interval: 4 }));
+        let mut world = make_small_world();
+        for tile in &mut world.tiles {
+            tile.biome.vegetation_health = 0.5;
+        }
+        // Every tile has been quiet
+        world.activity.scores = vec![0.0; world.tiles.len()];
+        world.activity.changes = vec![0.0; world.tiles.len()];
+        execute_tick(&mut world, &engine, 100);
+        for tile in &world.tiles {
+            let evaluated = (tile.id as u64).is_multiple_of(4);
+            assert_eq!(tile.biome.vegetation_health == 0.9, evaluated, "tile {}", tile.id);
+        }
+        assert!(world.activity.skipped.iter().any(|&s| s));
+        // The evaluated tiles moved, so they are awake next tick
+        assert!(world.activity.scores[0] >= 0.05);
+
+        // Without it every tile is evaluated again
+        engine.set_idle_skip(None);
+        execute_tick(&mut world, &engine, 100);
+        assert!(world.tiles.iter().all(|t| t.biome.vegetation_health == 0.9));
+        assert!(world.activity.skipped.is_empty());
+    }
+
+    #[test]
+    fn profiler_attributes_time_to_rules_and_native_steps() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "weather", &[("01-rain.rhai", r#"set("precipitation", 0.4);"#)]);
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap().with_native_evaluator(FixedHealth);
+        engine.set_profiler(Some(profiler::Profiler::new(4)));
+        let mut world = make_small_world();
+        execute_tick(&mut world, &engine, 100);
+
+        let collapsed = engine.profiler().unwrap().collapsed();
+        let stacks: Vec<&str> = collapsed.lines().filter_map(|l| l.rsplit_once(' ')).map(|(s, _)| s).collect();
+        for expected in ["tick;weather;01-rain.rhai", "tick;terrain;native/terrain", "tick;conditions;hydrology"] {
+            assert!(stacks.contains(&expected), "{} missing from {:?}", expected, stacks);
+        }
+        // Phases without rules or steps record nothing
+        assert!(!stacks.iter().any(|s| s.starts_with("tick;resources;native")));
+    }
+
+    #[test]
+    fn records_updated_each_tick() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = make_small_world();
+
+        execute_tick(&mut world, &engine, 100);
+        let hottest = world
+            .tiles
+            .iter()
+            .map(|t| t.weather.temperature as f64)
+            .fold(f64::MIN, f64::max);
+        let record = world.records.highest_temperature.expect("record set after first tick");
+        assert_eq!(record.value, hottest);
+        assert_eq!(record.tick, 1);
+        assert_eq!(world.tiles[record.tile_id as usize].weather.temperature as f64, hottest);
+    }
+
+    #[test]
+    fn rule_globals_reduce_and_are_readable_next_tick() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[(
+                "01-count.rhai",
+                r#"
+                contribute("tiles", 1);
+                contribute_max("hottest", tile.weather.temperature);
+                if "tiles" in globals {
+                    set("humidity", clamp(globals.tiles / 1000.0, 0.0, 1.0));
+                }
+                "#,
+            )],
+        );
+        make_rule_dir(dir.path(), "conditions", &[("01-count.rhai", r#"contribute("tiles", 1);"#)]);
+
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = make_small_world();
+        let hottest = world
+            .tiles
+            .iter()
+            .map(|t| t.weather.temperature as f64)
+            .fold(f64::MIN, f64::max);
+
+        execute_tick(&mut world, &engine, 100);
+        // Both phases contribute once per tile
+        let tiles = world.tiles.len() as f64;
+        assert_eq!(world.globals["tiles"], 2.0 * tiles);
+        assert!(world.globals["hottest"] >= hottest);
+
+        execute_tick(&mut world, &engine, 100);
+        let expected = (2.0 * tiles / 1000.0) as f32;
+        assert!(world.tiles.iter().all(|t| (t.weather.humidity - expected).abs() < 1e-6));
+    }
+
     #[test]
     fn phase_ordering_causal_chain() {
         // Rain in Phase 1 → moisture increase in Phase 2 → vegetation health in Phase 3
@@ -326,6 +828,119 @@ mod tests {
         }
     }
 
+    #[test]
+    fn slow_phase_runs_every_period_on_gathered_inputs() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "weather", &[("01-rain.rhai", r#"set("precipitation", 0.2);"#)]);
+        make_rule_dir(
+            dir.path(),
+            "terrain",
+            &[(
+                "01-since.rhai",
+                r#"
+                if "since" in tile {
+                    set("transition_pressure", tile.since.ticks * 0.1 + tile.since.precipitation);
+                } else {
+                    set("transition_pressure", -1.0);
+                }
+                "#,
+            )],
+        );
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        engine.set_phase_period(Phase::Terrain, 3);
+        let mut world = make_small_world();
+        for tile in &mut world.tiles {
+            tile.biome.transition_pressure = 0.0;
+        }
+
+        for _ in 0..2 {
+            let result = execute_tick(&mut world, &engine, 100);
+            assert!(result.rule_errors.is_empty());
+            assert!(world.tiles.iter().all(|t| t.biome.transition_pressure == 0.0), "terrain waits for its period");
+        }
+        assert_eq!(world.phase_inputs["terrain"].ticks, 2);
+        assert!(!world.phase_inputs.contains_key("resources"), "phases run every tick gather nothing");
+
+        // Three ticks, plus the rain summed over them
+        execute_tick(&mut world, &engine, 100);
+        assert!(world.tiles.iter().all(|t| (t.biome.transition_pressure - 0.9).abs() < 1e-6));
+        assert_eq!(world.phase_inputs["terrain"].ticks, 0);
+
+        // The built-in rules scale their per-tick changes by the period
+        let mut engine = RuleEngine::new(Path::new("rules"), 100).unwrap();
+        engine.set_phase_period(Phase::Terrain, 4);
+        engine.set_phase_period(Phase::Resources, 4);
+        let mut world = make_small_world();
+        for _ in 0..8 {
+            let result = execute_tick(&mut world, &engine, 100);
+            assert!(result.rule_errors.is_empty(), "{:?}", result.rule_errors.first());
+        }
+    }
+
+    #[test]
+    fn rollback_forgets_inputs_gathered_by_undone_ticks() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(dir.path(), "weather", &[("01-rain.rhai", r#"set("precipitation", 0.2);"#)]);
+        make_rule_dir(
+            dir.path(),
+            "terrain",
+            &[("01-since.rhai", r#"set("transition_pressure", tile.since.ticks * 0.1 + tile.since.precipitation);"#)],
+        );
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        engine.set_phase_period(Phase::Terrain, 3);
+        let mut world = make_small_world();
+        for tile in &mut world.tiles {
+            tile.biome.transition_pressure = 0.0;
+        }
+        let mut checkpoints = crate::world::Checkpoints::new(4);
+
+        execute_tick(&mut world, &engine, 100);
+        checkpoints.take(&world);
+        let before = world.state_hash();
+        execute_tick(&mut world, &engine, 100);
+        assert_eq!(world.phase_inputs["terrain"].ticks, 2);
+        assert_ne!(world.state_hash(), before, "gathered inputs are part of the state");
+        checkpoints.rollback(&mut world, 1).unwrap();
+        assert_eq!(world.phase_inputs["terrain"].ticks, 1);
+        assert_eq!(world.state_hash(), before);
+
+        // The undone tick runs again without being counted twice
+        execute_tick(&mut world, &engine, 100);
+        assert!(world.tiles.iter().all(|t| t.biome.transition_pressure == 0.0), "terrain waits for its period");
+        execute_tick(&mut world, &engine, 100);
+        assert!(world.tiles.iter().all(|t| (t.biome.transition_pressure - 0.9).abs() < 1e-6));
+    }
+
+    #[test]
+    fn paged_deposits_tick_like_resident_ones() {
+        use crate::simulation::native_resources::NativeResourcesEvaluator;
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        let resident = RuleEngine::new(dir.path(), 100).unwrap().with_native_evaluator(NativeResourcesEvaluator);
+        let mut paged = RuleEngine::new(dir.path(), 100).unwrap().with_native_evaluator(NativeResourcesEvaluator);
+        paged.set_paging(Some(crate::world::paging::PageFile::create(dir.path()).unwrap()));
+        let mut world = make_small_world();
+        // Emptied deposits renew, so every tick changes them
+        for deposit in world.tiles.iter_mut().flat_map(|t| &mut t.resources.resources) {
+            deposit.quantity = 0.0;
+        }
+        let mut paged_world = world.clone();
+
+        for _ in 0..3 {
+            execute_tick(&mut world, &resident, 100);
+            execute_tick(&mut paged_world, &paged, 100);
+        }
+        assert!(paged_world.tiles.iter().all(|t| t.resources.resources.is_empty()), "paged out between ticks");
+        let pages = paged.paging().unwrap();
+        let changes = pages.take_changes(&paged_world.tiles).unwrap();
+        assert!(!changes.is_empty());
+        assert!(changes.iter().all(|c| c.resources.as_ref() == Some(&world.tiles[c.id as usize].resources.discovered())));
+        pages.page_in(&mut paged_world.tiles).unwrap();
+        assert_eq!(paged_world.tiles, world.tiles);
+    }
+
     #[test]
     fn simulation_determinism_100_ticks() {
         let dir = TempDir::new().unwrap();
@@ -612,6 +1227,20 @@ mod tests {
             generation_params: default_gen_params(100),
             snapshot_path: None,
             macro_weather: Default::default(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: Default::default(),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
             tiles: vec![
                 {
                     let mut t = crate::world::Tile::new_default(
@@ -895,15 +1524,12 @@ mod tests {
             seed: 42,
             tile_count: 1000,
             ocean_ratio: 0.3,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
             topology: crate::config::generation::TopologyConfig {
                 mode: "geodesic".to_string(),
                 subdivision_level: level,
+                boundary: "wrap".to_string(),
             },
+            ..Default::default()
         }
     }
 
@@ -1018,10 +1644,7 @@ mod tests {
 
         // Estimate memory from tile size
         let tile_stack_size = std::mem::size_of::<crate::world::Tile>();
-        // Each tile also has heap allocations: neighbors Vec (~6 * 4 = 24 bytes),
-        // resources Vec (~3 entries * ~80 bytes each = ~240 bytes on average)
-        let estimated_heap_per_tile = 24 + 240;
-        let estimated_per_tile = tile_stack_size + estimated_heap_per_tile;
+        let estimated_per_tile = crate::world::generation::estimated_tile_bytes();
         let total_tiles_bytes = estimated_per_tile * world.tiles.len();
 
         // Double buffer during phase execution doubles the tile memory temporarily
@@ -1206,15 +1829,12 @@ mod tests {
             seed: 42,
             tile_count: 1000,
             ocean_ratio: 0.4,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
             topology: crate::config::generation::TopologyConfig {
                 mode: "geodesic".to_string(),
                 subdivision_level: 2,
+                boundary: "wrap".to_string(),
             },
+            ..Default::default()
         };
         let mut world = generate_world(&params);
 
@@ -0,0 +1,146 @@
+//! Mud and trafficability, computed natively at the end of the Conditions phase.
+//!
+//! Mud builds from rain, sleet and snowmelt on poorly drained, sticky soil and
+//! is held back by vegetation cover; it dries a little every tick, faster when
+//! warm, and stays locked in place while the ground is frozen. Rules may still
+//! `set("mud_level", ...)`; the native step starts from their value.
+
+use crate::world::tile::{PrecipitationType, SoilType, TerrainType};
+use crate::world::{Tile, World};
+
+/// Mud added per unit of liquid water on fully undrained, bare loam.
+const WETTING_RATE: f32 = 0.2;
+/// Fraction of snow depth that melts per tick above MELT_TEMPERATURE.
+const SNOWMELT_RATE: f32 = 0.1;
+const MELT_TEMPERATURE: f32 = 275.0;
+/// Soil moisture above this waterlogs the surface.
+const SATURATION_THRESHOLD: f32 = 0.8;
+const SATURATION_MUD: f32 = 0.05;
+/// Fraction of mud lost per tick, plus up to WARM_DRYING more at 30°C.
+const BASE_DRYING: f32 = 0.05;
+const WARM_DRYING: f32 = 0.05;
+/// Mud below this dries out completely.
+const MUD_FLOOR: f32 = 0.01;
+
+/// How readily a soil turns to mud, relative to loam.
+fn stickiness(soil: SoilType) -> f32 {
+    match soil {
+        SoilType::Clay => 1.3,
+        SoilType::Silt => 1.2,
+        SoilType::Loam => 1.0,
+        SoilType::Sand => 0.5,
+        SoilType::Rock => 0.3,
+    }
+}
+
+/// Liquid water reaching the ground this tick: rain, half of sleet, snowmelt
+/// and glacier meltwater.
+pub(crate) fn liquid_water(tile: &Tile) -> f32 {
+    let (w, c) = (&tile.weather, &tile.conditions);
+    let liquid = match w.precipitation_type {
+        PrecipitationType::Rain => w.precipitation,
+        PrecipitationType::Sleet => w.precipitation * 0.5,
+        _ => 0.0,
+    };
+    let melt = if w.temperature > MELT_TEMPERATURE { c.snow_depth * SNOWMELT_RATE } else { 0.0 };
+    liquid + melt + c.glacier_melt
+}
+
+/// Next tick's mud level for a tile, from its current mud and conditions.
+pub fn next_mud_level(tile: &Tile) -> f32 {
+    let (w, c, g) = (&tile.weather, &tile.conditions, &tile.geology);
+    if g.terrain_type == TerrainType::Ocean {
+        return 0.0;
+    }
+    if c.frost_days > 0 {
+        return c.mud_level;
+    }
+
+    let cover = 1.0 - 0.5 * tile.biome.vegetation_density;
+    let wetting = liquid_water(tile) * (1.0 - g.drainage) * stickiness(g.soil_type) * cover * WETTING_RATE
+        + (c.soil_moisture - SATURATION_THRESHOLD).max(0.0) * SATURATION_MUD;
+
+    let warmth = ((w.temperature - 283.15) / 20.0).clamp(0.0, 1.0);
+    let retention = 1.0 - BASE_DRYING - WARM_DRYING * warmth;
+    let mud = (c.mud_level * retention + wetting).clamp(0.0, 1.0);
+    if mud < MUD_FLOOR { 0.0 } else { mud }
+}
+
+/// Update `mud_level` and `trafficability` on every unfrozen tile.
+pub fn update_mud(world: &mut World) {
+    for tile in &mut world.tiles {
+        if world.frozen_tiles.contains(&tile.id) {
+            continue;
+        }
+        tile.conditions.mud_level = next_mud_level(tile);
+        tile.conditions.trafficability = tile.compute_trafficability();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    fn tile(soil: SoilType, drainage: f32) -> Tile {
+        let mut t = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        t.geology.soil_type = soil;
+        t.geology.drainage = drainage;
+        t.weather.temperature = 285.0;
+        t.weather.precipitation = 0.8;
+        t.weather.precipitation_type = PrecipitationType::Rain;
+        t.biome.vegetation_density = 0.0;
+        t
+    }
+
+    #[test]
+    fn rain_on_clay_muddier_than_sand() {
+        let clay = next_mud_level(&tile(SoilType::Clay, 0.2));
+        let sand = next_mud_level(&tile(SoilType::Sand, 0.8));
+        assert!(clay > 0.1, "clay {}", clay);
+        assert!(sand < clay / 4.0, "sand {} clay {}", sand, clay);
+    }
+
+    #[test]
+    fn vegetation_reduces_mud() {
+        let bare = tile(SoilType::Loam, 0.5);
+        let mut covered = bare.clone();
+        covered.biome.vegetation_density = 1.0;
+        assert!(next_mud_level(&covered) < next_mud_level(&bare));
+    }
+
+    #[test]
+    fn mud_dries_and_freezes_in_place() {
+        let mut t = tile(SoilType::Clay, 0.2);
+        t.weather.precipitation_type = PrecipitationType::None;
+        t.conditions.mud_level = 0.5;
+        let dried = next_mud_level(&t);
+        assert!(dried < 0.5 && dried > 0.4, "{}", dried);
+
+        t.conditions.frost_days = 3;
+        assert_eq!(next_mud_level(&t), 0.5);
+
+        t.conditions.frost_days = 0;
+        t.conditions.mud_level = 0.005;
+        assert_eq!(next_mud_level(&t), 0.0);
+    }
+
+    #[test]
+    fn trafficability_tracks_mud_snow_and_terrain() {
+        let mut t = tile(SoilType::Loam, 0.5);
+        assert_eq!(t.compute_trafficability(), 1.0);
+
+        t.conditions.mud_level = 0.6;
+        assert!((t.compute_trafficability() - 0.4).abs() < 1e-6);
+        // Frozen mud is firm
+        t.conditions.frost_days = 2;
+        assert_eq!(t.compute_trafficability(), 1.0);
+
+        t.conditions.snow_depth = 2.0;
+        assert!((t.compute_trafficability() - 0.5).abs() < 1e-6);
+
+        t.geology.terrain_type = TerrainType::Ocean;
+        assert_eq!(t.compute_trafficability(), 0.0);
+        assert_eq!(next_mud_level(&t), 0.0);
+    }
+}
@@ -0,0 +1,587 @@
+//! Plain-English summaries of what happened over a stretch of ticks.
+//!
+//! A `Narrator` remembers how the world looked at the start of a period and
+//! tallies floods, landslides, eruptions, glacier calvings and retreats,
+//! shoreline moves and auroras as they happen. When the period
+//! ends it compares the world against that baseline and writes a few
+//! sentences: regional droughts starting, continuing or breaking, biomes
+//! expanding or shrinking, the world warming or cooling, records falling,
+//! and the period's notable events. Nothing is written for changes too
+//! small to matter, so a quiet period gets a single line saying so.
+//!
+//! Regions are a latitude band (northern, equatorial, southern) and a biome,
+//! e.g. "the southern savanna".
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::simulation::glacier::GlacierEventKind;
+use crate::simulation::sea_level::ShorelineChangeKind;
+use crate::simulation::TickResult;
+use crate::world::tile::BiomeType;
+use crate::world::{Season, World, WorldRecords};
+
+/// Drought days after which a tile counts as in severe drought.
+pub const SEVERE_DROUGHT_DAYS: u32 = 30;
+/// Share of a region's tiles in severe drought for the region to be in drought.
+const REGION_DROUGHT_SHARE: f32 = 0.5;
+/// Share below which a regional drought counts as broken.
+const DROUGHT_BROKEN_SHARE: f32 = 0.25;
+/// Smallest region or biome (in tiles) worth narrating.
+const MIN_REGION_TILES: u32 = 5;
+/// Smallest biome area change (percent) worth narrating.
+const MIN_BIOME_CHANGE_PCT: f32 = 2.0;
+/// Smallest change in mean temperature (K) worth narrating.
+const MIN_TEMPERATURE_CHANGE: f32 = 0.5;
+/// Most drought and biome sentences in one narration.
+const MAX_LINES_PER_KIND: usize = 2;
+/// Latitude (degrees) beyond which a tile is northern or southern.
+const TROPIC_LATITUDE: f32 = 23.44;
+
+/// Biomes in the order they are listed when sizes tie.
+const BIOMES: [BiomeType; 11] = [
+    BiomeType::Ocean,
+    BiomeType::Ice,
+    BiomeType::Tundra,
+    BiomeType::BorealForest,
+    BiomeType::TemperateForest,
+    BiomeType::Grassland,
+    BiomeType::Savanna,
+    BiomeType::Desert,
+    BiomeType::TropicalForest,
+    BiomeType::Wetland,
+    BiomeType::Barren,
+];
+
+/// One period's summary.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Narration {
+    /// Tick the period started at
+    pub tick_start: u64,
+    /// Tick the period ended at
+    pub tick_end: u64,
+    /// Season at the end of the period
+    pub season: Season,
+    /// One sentence per observation, most newsworthy first
+    pub lines: Vec<String>,
+}
+
+impl Narration {
+    /// The lines as one paragraph.
+    pub fn summary(&self) -> String {
+        self.lines.join(" ")
+    }
+}
+
+/// Latitude band of a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Band {
+    Northern,
+    Equatorial,
+    Southern,
+}
+
+impl Band {
+    fn of(latitude: f32) -> Band {
+        if latitude > TROPIC_LATITUDE {
+            Band::Northern
+        } else if latitude < -TROPIC_LATITUDE {
+            Band::Southern
+        } else {
+            Band::Equatorial
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Band::Northern => "northern",
+            Band::Equatorial => "equatorial",
+            Band::Southern => "southern",
+        }
+    }
+}
+
+/// Biome name as it reads in a sentence.
+fn biome_name(biome: BiomeType) -> &'static str {
+    match biome {
+        BiomeType::Ocean => "ocean",
+        BiomeType::Ice => "ice",
+        BiomeType::Tundra => "tundra",
+        BiomeType::BorealForest => "boreal forest",
+        BiomeType::TemperateForest => "temperate forest",
+        BiomeType::Grassland => "grassland",
+        BiomeType::Savanna => "savanna",
+        BiomeType::Desert => "desert",
+        BiomeType::TropicalForest => "tropical forest",
+        BiomeType::Wetland => "wetland",
+        BiomeType::Barren => "barren land",
+    }
+}
+
+/// Biomes that are dry all the time, where drought is not news.
+fn always_dry(biome: BiomeType) -> bool {
+    matches!(biome, BiomeType::Ocean | BiomeType::Ice | BiomeType::Desert | BiomeType::Barren)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn times(n: usize) -> String {
+    match n {
+        1 => "once".to_string(),
+        2 => "twice".to_string(),
+        n => format!("{} times", n),
+    }
+}
+
+/// Tiles per region, and how many of them are in severe drought.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct RegionDrought {
+    tiles: u32,
+    dry: u32,
+}
+
+impl RegionDrought {
+    fn share(&self) -> f32 {
+        if self.tiles == 0 { 0.0 } else { self.dry as f32 / self.tiles as f32 }
+    }
+}
+
+/// The parts of a world a narration compares against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    tick: u64,
+    biomes: HashMap<BiomeType, u32>,
+    avg_temperature: f32,
+    records: WorldRecords,
+    droughts: HashMap<(Band, BiomeType), RegionDrought>,
+}
+
+impl Baseline {
+    /// Take a baseline from the world as it is now.
+    pub fn of(world: &World) -> Baseline {
+        let mut biomes = HashMap::new();
+        let mut droughts: HashMap<(Band, BiomeType), RegionDrought> = HashMap::new();
+        let mut temperature = 0.0_f64;
+        for tile in &world.tiles {
+            let biome = tile.biome.biome_type;
+            *biomes.entry(biome).or_insert(0) += 1;
+            temperature += tile.weather.temperature as f64;
+            let region = droughts.entry((Band::of(tile.climate.latitude), biome)).or_default();
+            region.tiles += 1;
+            if tile.conditions.drought_days >= SEVERE_DROUGHT_DAYS {
+                region.dry += 1;
+            }
+        }
+        Baseline {
+            tick: world.tick_count,
+            biomes,
+            avg_temperature: (temperature / world.tiles.len().max(1) as f64) as f32,
+            records: world.records.clone(),
+            droughts,
+        }
+    }
+}
+
+/// Events tallied over a period.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeriodEvents {
+    /// Tiles that flooded at least once
+    pub flooded_tiles: BTreeSet<u32>,
+    /// Landslides
+    pub landslides: usize,
+    /// Volcanic eruptions
+    pub eruptions: usize,
+    /// Glaciers breaking off into the sea
+    pub calvings: usize,
+    /// Tiles whose glacier thinned away
+    pub glacier_retreats: usize,
+    /// Tiles drowned by a rising sea
+    pub transgressions: usize,
+    /// Tiles left dry by a falling sea
+    pub regressions: usize,
+    /// Oval latitude of each geomagnetic storm
+    pub aurora_latitudes: Vec<f32>,
+}
+
+impl PeriodEvents {
+    /// Tally one tick's events.
+    pub fn record(&mut self, result: &TickResult) {
+        self.flooded_tiles.extend(result.floods.iter().map(|f| f.tile_id));
+        self.landslides += result.landslides.len();
+        self.eruptions += result.eruption.iter().count();
+        for event in &result.glacier_events {
+            match event.kind {
+                GlacierEventKind::Calving => self.calvings += 1,
+                GlacierEventKind::Retreat => self.glacier_retreats += 1,
+            }
+        }
+        for change in &result.shoreline_changes {
+            match change.kind {
+                ShorelineChangeKind::Transgression => self.transgressions += 1,
+                ShorelineChangeKind::Regression => self.regressions += 1,
+            }
+        }
+        self.aurora_latitudes.extend(result.aurora.map(|a| a.oval_latitude));
+    }
+}
+
+/// How a period is referred to: "this year", "this season" or "over the last 50 ticks".
+pub fn period_label(ticks: u64, season_length: u32) -> String {
+    if ticks == 4 * season_length as u64 {
+        "this year".to_string()
+    } else if ticks == season_length as u64 {
+        "this season".to_string()
+    } else {
+        format!("over the last {} ticks", ticks)
+    }
+}
+
+/// Describe how `world` changed since `baseline`, with the period's events.
+/// `period` finishes sentences, e.g. "this year".
+pub fn narrate(baseline: &Baseline, world: &World, events: &PeriodEvents, period: &str) -> Vec<String> {
+    let now = Baseline::of(world);
+    let mut lines = Vec::new();
+    lines.extend(drought_lines(baseline, &now));
+    lines.extend(biome_lines(baseline, &now, period));
+
+    let warming = now.avg_temperature - baseline.avg_temperature;
+    if warming.abs() >= MIN_TEMPERATURE_CHANGE {
+        let verb = if warming > 0.0 { "warmed" } else { "cooled" };
+        lines.push(format!("The world {} {:.1} K {}.", verb, warming.abs(), period));
+    }
+
+    lines.extend(record_lines(&baseline.records, &world.records));
+
+    if !events.flooded_tiles.is_empty() {
+        let n = events.flooded_tiles.len();
+        lines.push(format!("Floods struck {} tile{} {}.", n, if n == 1 { "" } else { "s" }, period));
+    }
+    if events.landslides > 0 {
+        let n = events.landslides;
+        lines.push(format!("{} landslide{} came down {}.", n, if n == 1 { "" } else { "s" }, period));
+    }
+    if events.eruptions > 0 {
+        let n = events.eruptions;
+        lines.push(format!("{} volcanic eruption{} darkened the sky {}.", n, if n == 1 { "" } else { "s" }, period));
+    }
+    if events.calvings > 0 {
+        let n = events.calvings;
+        lines.push(format!("Glaciers calved into the sea {} {}.", times(n), period));
+    }
+    if events.glacier_retreats > 0 {
+        let n = events.glacier_retreats;
+        lines.push(format!("Glaciers retreated from {} tile{} {}.", n, if n == 1 { "" } else { "s" }, period));
+    }
+    if events.transgressions > 0 {
+        let n = events.transgressions;
+        lines.push(format!("The sea rose over {} tile{} {}.", n, if n == 1 { "" } else { "s" }, period));
+    }
+    if events.regressions > 0 {
+        let n = events.regressions;
+        lines.push(format!("The sea fell back from {} tile{} {}.", n, if n == 1 { "" } else { "s" }, period));
+    }
+    if let Some(lowest) = events.aurora_latitudes.iter().copied().reduce(f32::min) {
+        lines.push(format!(
+            "Auroras lit the sky {}, reaching as far as {:.0}° from the equator.",
+            times(events.aurora_latitudes.len()),
+            lowest
+        ));
+    }
+
+    if lines.is_empty() {
+        lines.push(format!("Nothing of note happened {}.", period));
+    }
+    lines
+}
+
+fn drought_lines(baseline: &Baseline, now: &Baseline) -> Vec<String> {
+    // (share now, region, sentence)
+    let mut found: Vec<(f32, (Band, BiomeType), String)> = Vec::new();
+    for (&region, current) in &now.droughts {
+        let (band, biome) = region;
+        if always_dry(biome) || current.tiles < MIN_REGION_TILES {
+            continue;
+        }
+        let before = baseline.droughts.get(&region).map_or(0.0, RegionDrought::share);
+        let name = format!("the {} {}", band.name(), biome_name(biome));
+        let sentence = if current.share() >= REGION_DROUGHT_SHARE && before < REGION_DROUGHT_SHARE {
+            format!("A severe drought grips {}.", name)
+        } else if current.share() >= REGION_DROUGHT_SHARE {
+            format!("The drought in {} continues.", name)
+        } else if before >= REGION_DROUGHT_SHARE && current.share() < DROUGHT_BROKEN_SHARE {
+            format!("The drought in {} has broken.", name)
+        } else {
+            continue;
+        };
+        found.push((current.share().max(before), region, sentence));
+    }
+    found.sort_by(|a, b| {
+        let rank = |(band, biome): (Band, BiomeType)| (band, biome_rank(biome));
+        b.0.total_cmp(&a.0).then_with(|| rank(a.1).cmp(&rank(b.1)))
+    });
+    found.into_iter().take(MAX_LINES_PER_KIND).map(|(_, _, s)| s).collect()
+}
+
+fn biome_rank(biome: BiomeType) -> usize {
+    BIOMES.iter().position(|&b| b == biome).unwrap_or(BIOMES.len())
+}
+
+fn biome_lines(baseline: &Baseline, now: &Baseline, period: &str) -> Vec<String> {
+    // (size of the change in percent, sentence)
+    let mut found: Vec<(f32, String)> = Vec::new();
+    for biome in BIOMES {
+        if biome == BiomeType::Ocean {
+            continue;
+        }
+        let before = baseline.biomes.get(&biome).copied().unwrap_or(0);
+        let after = now.biomes.get(&biome).copied().unwrap_or(0);
+        let name = capitalize(biome_name(biome));
+        if before < MIN_REGION_TILES {
+            if after >= MIN_REGION_TILES {
+                found.push((f32::INFINITY, format!("{} took hold on {} tiles {}.", name, after, period)));
+            }
+            continue;
+        }
+        if after == 0 {
+            found.push((100.0, format!("The last of the {} disappeared {}.", biome_name(biome), period)));
+            continue;
+        }
+        let change = (after as f32 - before as f32) / before as f32 * 100.0;
+        if change.abs() >= MIN_BIOME_CHANGE_PCT {
+            let verb = if change > 0.0 { "expanded" } else { "shrank" };
+            found.push((change.abs(), format!("{} {} {:.0}% {}.", name, verb, change.abs(), period)));
+        }
+    }
+    // Stable sort keeps biome order for equal changes
+    found.sort_by(|a, b| b.0.total_cmp(&a.0));
+    found.into_iter().take(MAX_LINES_PER_KIND).map(|(_, s)| s).collect()
+}
+
+fn record_lines(before: &WorldRecords, after: &WorldRecords) -> Vec<String> {
+    let mut lines = Vec::new();
+    for ((name, old), (_, new)) in before.entries().into_iter().zip(after.entries()) {
+        // The first value ever seen is not a record worth announcing
+        let (Some(old), Some(new)) = (old, new) else {
+            continue;
+        };
+        if old == new {
+            continue;
+        }
+        let what = match name {
+            "highest_temperature" => format!("record high temperature of {:.1} K", new.value),
+            "lowest_temperature" => format!("record low temperature of {:.1} K", new.value),
+            "highest_humidity" => format!("record humidity of {:.0}%", new.value * 100.0),
+            "lowest_humidity" => format!("record low humidity of {:.0}%", new.value * 100.0),
+            "strongest_storm" => format!("record storm of intensity {:.2}", new.value),
+            "longest_drought" => format!("record drought of {} days", new.value),
+            _ => continue,
+        };
+        lines.push(format!("A new {} was set on tile {} at tick {}.", what, new.tile_id, new.tick));
+    }
+    lines
+}
+
+/// Writes a narration every `interval` ticks.
+pub struct Narrator {
+    interval: u64,
+    period: String,
+    baseline: Baseline,
+    events: PeriodEvents,
+}
+
+impl Narrator {
+    /// Start narrating from the world as it is now.
+    pub fn new(world: &World, interval: u64, season_length: u32) -> Narrator {
+        let interval = interval.max(1);
+        Narrator {
+            interval,
+            period: period_label(interval, season_length),
+            baseline: Baseline::of(world),
+            events: PeriodEvents::default(),
+        }
+    }
+
+    /// Tally a tick's events. Returns a narration when the period is over.
+    pub fn observe(&mut self, world: &World, result: &TickResult) -> Option<Narration> {
+        self.events.record(result);
+        if world.tick_count < self.baseline.tick + self.interval {
+            return None;
+        }
+        let narration = Narration {
+            tick_start: self.baseline.tick,
+            tick_end: world.tick_count,
+            season: world.season,
+            lines: narrate(&self.baseline, world, &self.events, &self.period),
+        };
+        self.baseline = Baseline::of(world);
+        self.events = PeriodEvents::default();
+        Some(narration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::records::Record;
+    use crate::world::tile::{Position, Tile};
+
+    /// A world of `n` equatorial grassland tiles.
+    fn world(n: u32) -> World {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = (0..n)
+            .map(|id| {
+                let mut t = Tile::new_default(id, vec![], Position::flat(id as f64, 0.0));
+                t.biome.biome_type = BiomeType::Grassland;
+                t.climate.latitude = -40.0;
+                t.weather.temperature = 288.0;
+                t
+            })
+            .collect();
+        world.tile_count = n;
+        world.records = WorldRecords::default();
+        world
+    }
+
+    #[test]
+    fn quiet_period_says_so() {
+        let w = world(20);
+        let lines = narrate(&Baseline::of(&w), &w, &PeriodEvents::default(), "this year");
+        assert_eq!(lines, vec!["Nothing of note happened this year."]);
+    }
+
+    #[test]
+    fn droughts_start_continue_and_break() {
+        let mut w = world(20);
+        let baseline = Baseline::of(&w);
+        for t in &mut w.tiles[..12] {
+            t.conditions.drought_days = 40;
+        }
+        let lines = narrate(&baseline, &w, &PeriodEvents::default(), "this year");
+        assert_eq!(lines, vec!["A severe drought grips the southern grassland."]);
+
+        let baseline = Baseline::of(&w);
+        let lines = narrate(&baseline, &w, &PeriodEvents::default(), "this year");
+        assert_eq!(lines, vec!["The drought in the southern grassland continues."]);
+
+        for t in &mut w.tiles {
+            t.conditions.drought_days = 0;
+        }
+        let lines = narrate(&baseline, &w, &PeriodEvents::default(), "this year");
+        assert_eq!(lines, vec!["The drought in the southern grassland has broken."]);
+
+        // Deserts are always dry
+        for t in &mut w.tiles {
+            t.biome.biome_type = BiomeType::Desert;
+            t.conditions.drought_days = 400;
+        }
+        let baseline = Baseline::of(&w);
+        let lines = narrate(&baseline, &w, &PeriodEvents::default(), "this year");
+        assert_eq!(lines, vec!["Nothing of note happened this year."]);
+    }
+
+    #[test]
+    fn biome_shifts_and_warming_are_reported() {
+        let mut w = world(100);
+        for t in &mut w.tiles[..50] {
+            t.biome.biome_type = BiomeType::BorealForest;
+        }
+        let baseline = Baseline::of(&w);
+        for t in &mut w.tiles[50..52] {
+            t.biome.biome_type = BiomeType::BorealForest;
+        }
+        for t in &mut w.tiles {
+            t.weather.temperature += 1.2;
+        }
+        let lines = narrate(&baseline, &w, &PeriodEvents::default(), "this year");
+        assert_eq!(
+            lines,
+            vec![
+                "Boreal forest expanded 4% this year.",
+                "Grassland shrank 4% this year.",
+                "The world warmed 1.2 K this year.",
+            ]
+        );
+    }
+
+    #[test]
+    fn records_and_events_are_reported() {
+        let mut w = world(20);
+        w.records.highest_temperature = Some(Record { value: 310.0, tile_id: 1, tick: 5 });
+        let baseline = Baseline::of(&w);
+        w.records.highest_temperature = Some(Record { value: 318.25, tile_id: 4, tick: 90 });
+        // A record seen for the first time is not news
+        w.records.strongest_storm = Some(Record { value: 0.4, tile_id: 2, tick: 3 });
+        let events = PeriodEvents {
+            flooded_tiles: BTreeSet::from([3, 4, 9]),
+            landslides: 1,
+            eruptions: 1,
+            calvings: 3,
+            glacier_retreats: 2,
+            transgressions: 4,
+            regressions: 1,
+            aurora_latitudes: vec![60.0, 51.6],
+        };
+        let lines = narrate(&baseline, &w, &events, "this season");
+        assert_eq!(
+            lines,
+            vec![
+                "A new record high temperature of 318.2 K was set on tile 4 at tick 90.",
+                "Floods struck 3 tiles this season.",
+                "1 landslide came down this season.",
+                "1 volcanic eruption darkened the sky this season.",
+                "Glaciers calved into the sea 3 times this season.",
+                "Glaciers retreated from 2 tiles this season.",
+                "The sea rose over 4 tiles this season.",
+                "The sea fell back from 1 tile this season.",
+                "Auroras lit the sky twice, reaching as far as 52° from the equator.",
+            ]
+        );
+    }
+
+    #[test]
+    fn narrator_reports_once_per_interval() {
+        let mut w = world(20);
+        w.tick_count = 100;
+        let mut narrator = Narrator::new(&w, 4 * 90, 90);
+        let result = TickResult {
+            statistics: crate::simulation::statistics::compute_statistics(&w, 0, 0.0, false),
+            rule_errors: Vec::new(),
+            mutation_stats: Default::default(),
+            rule_stats: Default::default(),
+            phase_timings_ms: [0.0; 6],
+            floods: Vec::new(),
+            landslides: Vec::new(),
+            aurora: None,
+            eruption: None,
+            depletions: Vec::new(),
+            glacier_events: Vec::new(),
+            shoreline_changes: Vec::new(),
+            effects: Default::default(),
+            events: Vec::new(),
+        };
+        let mut narrations = Vec::new();
+        for _ in 0..800 {
+            w.tick_count += 1;
+            narrations.extend(narrator.observe(&w, &result));
+        }
+        assert_eq!(narrations.len(), 2);
+        assert_eq!((narrations[0].tick_start, narrations[0].tick_end), (100, 460));
+        assert_eq!((narrations[1].tick_start, narrations[1].tick_end), (460, 820));
+        assert_eq!(narrations[0].summary(), "Nothing of note happened this year.");
+        assert_eq!(period_label(50, 90), "over the last 50 ticks");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::simulation::engine::{Phase, TileMutations};
+use crate::world::phase_inputs::Since;
 use crate::world::tile::Season;
 use crate::world::Tile;
 
@@ -24,4 +25,20 @@ pub trait NativePhaseEvaluator: Send + Sync {
         tick: u64,
         rng_seed: u64,
     ) -> TileMutations;
+
+    /// Evaluate a tile for a run of a slow phase (see `phase_periods`), which
+    /// stands in for `since.ticks` ticks. Evaluators with per-tick rates
+    /// should scale them; by default the tile is evaluated as for one tick.
+    fn evaluate_since(
+        &self,
+        tile: &Tile,
+        neighbors: &[&Tile],
+        season: Season,
+        tick: u64,
+        rng_seed: u64,
+        since: &Since,
+    ) -> TileMutations {
+        let _ = since;
+        self.evaluate(tile, neighbors, season, tick, rng_seed)
+    }
 }
@@ -0,0 +1,226 @@
+//! Native Rust implementation of the Resources phase.
+//!
+//! Replaces `01-regeneration.rhai` when native evaluation is on. Renewable
+//! deposits grow back by their `renewal_rate`, scaled by the tile's local
+//! season: fastest in spring, slowest in winter, averaging the plain rate
+//! over a year. Seasons are mirrored in the southern hemisphere. A deposit
+//! whose `requires_biome` no longer includes the tile's biome (timber after
+//! a forest turns to grassland) stops regrowing and decays instead, and
+//! regrows once the biome comes back. When the phase runs only every few
+//! ticks, each run regrows for all of them.
+//!
+//! Depletion events are detected around the phase, so they are reported the
+//! same way whether rules or this evaluator ran it.
+
+use rhai::Dynamic;
+
+use crate::simulation::engine::{Phase, TileMutations};
+use crate::simulation::native_eval::NativePhaseEvaluator;
+use crate::world::phase_inputs::Since;
+use crate::world::tile::{ResourceDeposit, Season, Tile};
+
+/// Fraction of a deposit's capacity lost per tick while its biome is gone.
+const MISMATCH_DECAY: f32 = 0.02;
+
+/// A deposit that ran out this tick (a wire protocol type).
+pub use worldground_protocol::DepletionEvent;
+
+/// Regrowth multiplier for a local season; averages 1.0 over a year.
+fn seasonal_factor(season: Season) -> f32 {
+    match season {
+        Season::Spring => 1.4,
+        Season::Summer => 1.2,
+        Season::Autumn => 0.8,
+        Season::Winter => 0.6,
+    }
+}
+
+/// The season a tile is actually having: the world season in the north,
+/// the opposite one in the south.
+fn local_season(season: Season, latitude: f32) -> Season {
+    if latitude < 0.0 { season.next().next() } else { season }
+}
+
+/// Whether a deposit's biome requirement is met on this tile.
+fn biome_supports(deposit: &ResourceDeposit, tile: &Tile) -> bool {
+    deposit
+        .requires_biome
+        .as_ref()
+        .is_none_or(|biomes| biomes.contains(&tile.biome.biome_type))
+}
+
+/// A deposit's quantity after `ticks` ticks of regrowth or decay.
+fn next_quantity(deposit: &ResourceDeposit, tile: &Tile, season: Season, ticks: u32) -> f32 {
+    let ticks = ticks as f32;
+    let quantity = if !biome_supports(deposit, tile) {
+        deposit.quantity - MISMATCH_DECAY * deposit.max_quantity * ticks
+    } else if deposit.renewal_rate > 0.0 {
+        let season = local_season(season, tile.climate.latitude);
+        deposit.quantity + deposit.renewal_rate * seasonal_factor(season) * ticks
+    } else {
+        deposit.quantity
+    };
+    quantity.clamp(0.0, deposit.max_quantity.max(0.0))
+}
+
+/// Mutations for every deposit whose quantity changes over `ticks` ticks.
+fn regrow(tile: &Tile, season: Season, ticks: u32) -> TileMutations {
+    let mutations = tile
+        .resources
+        .resources
+        .iter()
+        .filter_map(|deposit| {
+            let quantity = next_quantity(deposit, tile, season, ticks);
+            (quantity != deposit.quantity).then(|| {
+                (format!("{}.quantity", deposit.resource_type), Dynamic::from(quantity as f64))
+            })
+        })
+        .collect();
+    TileMutations { mutations, ..Default::default() }
+}
+
+/// Native Resources phase evaluator.
+pub struct NativeResourcesEvaluator;
+
+impl NativePhaseEvaluator for NativeResourcesEvaluator {
+    fn phase(&self) -> Phase {
+        Phase::Resources
+    }
+
+    fn evaluate(
+        &self,
+        tile: &Tile,
+        _neighbors: &[&Tile],
+        season: Season,
+        _tick: u64,
+        _rng_seed: u64,
+    ) -> TileMutations {
+        regrow(tile, season, 1)
+    }
+
+    /// A slow run regrows (or decays) deposits for every tick it stands in for.
+    fn evaluate_since(
+        &self,
+        tile: &Tile,
+        _neighbors: &[&Tile],
+        season: Season,
+        _tick: u64,
+        _rng_seed: u64,
+        since: &Since,
+    ) -> TileMutations {
+        regrow(tile, season, since.ticks)
+    }
+}
+
+/// Which deposits are stocked, flattened across all tiles in order.
+pub fn stocked_deposits(tiles: &[Tile]) -> Vec<bool> {
+    tiles
+        .iter()
+        .flat_map(|t| t.resources.resources.iter().map(|d| d.quantity > 0.0))
+        .collect()
+}
+
+/// Deposits that were stocked before (see `stocked_deposits`) and are empty now.
+pub fn depleted_deposits(tiles: &[Tile], stocked_before: &[bool]) -> Vec<DepletionEvent> {
+    let deposits = tiles
+        .iter()
+        .flat_map(|t| t.resources.resources.iter().map(move |d| (t.id, d)));
+    deposits
+        .zip(stocked_before)
+        .filter(|((_, deposit), stocked)| **stocked && deposit.quantity <= 0.0)
+        .map(|((tile_id, deposit), _)| DepletionEvent {
+            tile_id,
+            resource_type: deposit.resource_type.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::engine::apply_mutations;
+    use crate::world::tile::{BiomeType, Position};
+
+    fn timber_tile(quantity: f32, latitude: f32) -> Tile {
+        let mut t = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        t.climate.latitude = latitude;
+        t.biome.biome_type = BiomeType::TemperateForest;
+        t.resources.resources = vec![ResourceDeposit {
+            resource_type: "timber".to_string(),
+            quantity,
+            max_quantity: 80.0,
+            renewal_rate: 1.0,
+            requires_biome: Some(vec![BiomeType::TemperateForest]),
+            quality: 0.5,
+            discovered: true,
+        }];
+        t
+    }
+
+    fn step(tile: &mut Tile, season: Season) {
+        let mutations = NativeResourcesEvaluator.evaluate(tile, &[], season, 0, 0);
+        apply_mutations(tile, &mutations, Phase::Resources);
+    }
+
+    #[test]
+    fn regrowth_follows_the_local_season() {
+        let mut north = timber_tile(10.0, 45.0);
+        let mut south = timber_tile(10.0, -45.0);
+        step(&mut north, Season::Spring);
+        step(&mut south, Season::Spring);
+        assert!((north.resources.resources[0].quantity - 11.4).abs() < 1e-5);
+        // Spring in the north is autumn in the south
+        assert!((south.resources.resources[0].quantity - 10.8).abs() < 1e-5);
+
+        let mut full = timber_tile(79.5, 45.0);
+        step(&mut full, Season::Summer);
+        assert_eq!(full.resources.resources[0].quantity, 80.0);
+
+        let year: f32 = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter]
+            .into_iter()
+            .map(seasonal_factor)
+            .sum();
+        assert!((year - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn deposits_decay_when_their_biome_is_gone() {
+        let mut tile = timber_tile(3.0, 45.0);
+        tile.biome.biome_type = BiomeType::Grassland;
+        step(&mut tile, Season::Spring);
+        assert!((tile.resources.resources[0].quantity - 1.4).abs() < 1e-5);
+
+        let stocked = stocked_deposits(std::slice::from_ref(&tile));
+        step(&mut tile, Season::Spring);
+        assert_eq!(tile.resources.resources[0].quantity, 0.0);
+        let events = depleted_deposits(std::slice::from_ref(&tile), &stocked);
+        assert_eq!(events, vec![DepletionEvent { tile_id: 0, resource_type: "timber".to_string() }]);
+
+        // Already empty: no mutation and no repeat event
+        assert!(NativeResourcesEvaluator.evaluate(&tile, &[], Season::Spring, 0, 0).mutations.is_empty());
+        let stocked = stocked_deposits(std::slice::from_ref(&tile));
+        assert!(depleted_deposits(std::slice::from_ref(&tile), &stocked).is_empty());
+
+        // The forest returns and so does the timber
+        tile.biome.biome_type = BiomeType::TemperateForest;
+        step(&mut tile, Season::Summer);
+        assert!(tile.resources.resources[0].quantity > 0.0);
+    }
+
+    #[test]
+    fn slow_runs_regrow_for_every_tick_they_cover() {
+        let tile = timber_tile(10.0, 45.0);
+        let since = Since { ticks: 5, precipitation: 1.0, temperature: 285.0, soil_moisture: 0.5 };
+        let mut slow = tile.clone();
+        let mutations = NativeResourcesEvaluator.evaluate_since(&slow, &[], Season::Spring, 0, 0, &since);
+        apply_mutations(&mut slow, &mutations, Phase::Resources);
+        assert!((slow.resources.resources[0].quantity - 17.0).abs() < 1e-4);
+
+        // Still capped at the deposit's size
+        let since = Since { ticks: 100, ..since };
+        let mutations = NativeResourcesEvaluator.evaluate_since(&tile, &[], Season::Spring, 0, 0, &since);
+        let mut capped = tile.clone();
+        apply_mutations(&mut capped, &mutations, Phase::Resources);
+        assert_eq!(capped.resources.resources[0].quantity, 80.0);
+    }
+}
@@ -118,7 +118,9 @@ fn terrain_is(tile: &Tile, name: &str) -> bool {
 /// Computed once at startup to avoid ~24K trig calls per tick.
 pub struct NeighborBearings {
     reverse_bearings: Vec<Vec<f64>>,
-    /// True when tiles have real lat/lon (geodesic). False when all lat/lon are 0 (flat hex).
+    /// True when tiles have real lat/lon. False when all lat/lon are 0 (a bare flat
+    /// hex grid). Cylinder longitudes span exactly 360°, so bearings across the
+    /// wrapping_hex seam come out the same as anywhere else.
     pub has_geo: bool,
 }
 
@@ -209,7 +211,7 @@ impl NativePhaseEvaluator for NativeWeatherEvaluator {
         // ===== Rule 4: Storms =====
         rule_storms(tile, neighbors, &mut rng, &mut accum);
 
-        TileMutations { mutations: accum.into_mutations() }
+        TileMutations { mutations: accum.into_mutations(), ..Default::default() }
     }
 }
 
@@ -365,7 +367,7 @@ fn advection_weight(neighbor_wind_dir: f64, bearing_to_tile: f64, neighbor_wind_
 /// Compute wind-directed advection of a quantity from neighbors toward a tile.
 /// Returns (advected_value, total_weight). If bearings are unavailable (flat hex),
 /// falls back to isotropic averaging.
-fn compute_advected(
+pub(crate) fn compute_advected(
     tile: &Tile,
     neighbors: &[&Tile],
     bearings: &NeighborBearings,
@@ -403,6 +405,55 @@ fn compute_advected(
     }
 }
 
+/// Extra precipitation per unit of elevation the air climbs, at full wind.
+const OROGRAPHIC_GAIN: f64 = 3.0;
+/// Bounds on the orographic precipitation multiplier.
+const MIN_OROGRAPHIC_FACTOR: f64 = 0.5;
+const MAX_OROGRAPHIC_FACTOR: f64 = 2.5;
+
+/// Precipitation multiplier for air forced up or down the terrain by the wind.
+///
+/// `neighbors` yields the bearing from each neighbor toward the tile and the
+/// neighbor's elevation. Neighbors the wind blows from are weighted by how
+/// squarely it blows from them, and the rise from them to the tile (water
+/// counts as sea level) is averaged. Air pushed up a windward slope rains
+/// more, air sinking down a lee slope rains less, and over a plateau's
+/// interior or in calm air the factor stays near 1.
+pub fn orographic_factor(
+    elevation: f64,
+    wind_direction: f64,
+    wind_speed: f64,
+    neighbors: impl IntoIterator<Item = (f64, f64)>,
+) -> f64 {
+    let mut rise = 0.0;
+    let mut total_weight = 0.0;
+    for (bearing_to_tile, neighbor_elevation) in neighbors {
+        let alignment = (wind_direction - bearing_to_tile).to_radians().cos();
+        if alignment > 0.0 {
+            rise += alignment * (elevation.max(0.0) - neighbor_elevation.max(0.0));
+            total_weight += alignment;
+        }
+    }
+    if total_weight <= 0.0 {
+        return 1.0;
+    }
+    // Less than one neighbor's worth of upwind weight means the wind mostly
+    // runs along the contours
+    let speed_factor = (wind_speed / 10.0).min(1.5);
+    (1.0 + OROGRAPHIC_GAIN * rise / total_weight.max(1.0) * speed_factor)
+        .clamp(MIN_OROGRAPHIC_FACTOR, MAX_OROGRAPHIC_FACTOR)
+}
+
+/// Stand-in for `orographic_factor` where neighbor bearings are unknown
+/// (flat hex worlds): rough terrain rains more, whatever the wind.
+pub fn terrain_orographic_factor(terrain: &str) -> f64 {
+    match terrain {
+        "Mountains" | "Cliffs" => 1.8,
+        "Hills" => 1.3,
+        _ => 1.0,
+    }
+}
+
 /// Convergence vertical motion based on latitude and season.
 /// Returns a value: positive = rising air (more clouds/humidity), negative = sinking (fewer clouds).
 fn convergence_vertical_motion(lat: f64, season: Season) -> f64 {
@@ -684,11 +735,21 @@ fn rule_clouds_precipitation(
         let excess = relative_humidity - 0.70;
         let mut intensity = excess * new_cloud * 1.2;
 
-        if terrain_str == "Mountains" || terrain_str == "Cliffs" {
-            intensity *= 1.8;
-        } else if terrain_str == "Hills" {
-            intensity *= 1.3;
-        }
+        // Orographic enhancement: the wind forcing air up the terrain
+        intensity *= if bearings.has_geo {
+            let tile_idx = tile.id as usize;
+            orographic_factor(
+                tile.geology.elevation as f64,
+                accum.wind_direction,
+                accum.wind_speed,
+                neighbors
+                    .iter()
+                    .enumerate()
+                    .map(|(j, n)| (bearings.bearing(tile_idx, j), n.geology.elevation as f64)),
+            )
+        } else {
+            terrain_orographic_factor(terrain_str)
+        };
 
         if temp > 290.0 && humidity > 0.5 {
             intensity *= 1.2;
@@ -763,6 +824,7 @@ fn rule_storms(
     let mut new_storm = current_storm;
 
     // === PRESSURE-DRIVEN NUCLEATION ===
+    // Sea-level pressure, so high ground is not mistaken for a low
     let pressure_deficit = 1013.25 - pressure;
 
     if pressure_deficit > 3.0 && humidity > 0.4 && cloud > 0.35 {
@@ -886,7 +948,7 @@ fn rule_storms(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::world::tile::Position;
+    use crate::world::tile::{Position, TerrainType};
 
     fn make_test_tile() -> Tile {
         Tile::new_default(0, vec![1, 2, 3, 4, 5, 6], Position::flat(0.0, 0.0))
@@ -1341,6 +1403,53 @@ mod tests {
             eq_cloud, st_cloud);
     }
 
+    #[test]
+    fn test_orographic_factor_follows_the_wind() {
+        // Neighbors to the west (bearing 90° toward the tile) and east (270°)
+        let slope = [(90.0, 0.2), (270.0, 0.8)];
+        // Eastward wind climbs from the low western neighbor
+        let windward = orographic_factor(0.5, 90.0, 10.0, slope);
+        assert!((windward - 1.9).abs() < 1e-9, "{}", windward);
+        // Westward wind comes down from the high eastern neighbor
+        assert_eq!(orographic_factor(0.5, 270.0, 10.0, slope), MIN_OROGRAPHIC_FACTOR);
+        // Crosswind, calm air and a plateau interior give no lift
+        assert!((orographic_factor(0.5, 0.0, 10.0, slope) - 1.0).abs() < 1e-9);
+        assert_eq!(orographic_factor(0.5, 90.0, 0.0, slope), 1.0);
+        assert_eq!(orographic_factor(0.5, 90.0, 10.0, [(90.0, 0.5), (270.0, 0.5)]), 1.0);
+        // Onshore wind counts the sea as sea level, not its floor
+        let coast = orographic_factor(0.1, 90.0, 10.0, [(90.0, -0.4)]);
+        assert!((coast - 1.3).abs() < 1e-9, "{}", coast);
+    }
+
+    #[test]
+    fn test_windward_slope_rains_more_than_plateau() {
+        let precipitation = |plateau: bool| {
+            let mut upwind = make_geo_tile(0, 0.0, 0.0, vec![1]);
+            upwind.geology.elevation = if plateau { 0.6 } else { 0.4 };
+            let mut t = make_geo_tile(1, 0.0, 5.0, vec![0]);
+            // Plains, so the humidity rule's terrain-based stripping stays out of it
+            t.geology.terrain_type = TerrainType::Plains;
+            t.geology.elevation = 0.6;
+            t.weather.temperature = 285.0;
+            t.weather.humidity = 0.8;
+            t.weather.cloud_cover = 0.6;
+            t.weather.wind_direction = 90.0;
+            t.weather.wind_speed = 10.0;
+            t.weather.macro_wind_direction = 90.0;
+            t.weather.macro_wind_speed = 10.0;
+            upwind.weather = t.weather.clone();
+            let evaluator = NativeWeatherEvaluator::new(&[upwind.clone(), t.clone()]);
+            let result = evaluator.evaluate(&t, &[&upwind], Season::Summer, 1, 42);
+            result.mutations.iter()
+                .find(|(f, _)| f == "precipitation")
+                .and_then(|(_, v)| v.as_float().ok())
+                .unwrap()
+        };
+        let (slope, plateau) = (precipitation(false), precipitation(true));
+        assert!(plateau > 0.0, "plateau should still rain");
+        assert!(slope > plateau * 1.4, "windward slope {} vs plateau {}", slope, plateau);
+    }
+
     #[test]
     fn test_advection_weight_alignment() {
         // Wind blowing directly toward target (alignment=1) should give max weight
@@ -1404,4 +1513,21 @@ mod tests {
         assert!((b - 90.0).abs() < 5.0,
             "Bearing from (0,0) to (0,5) should be ~90°, got {}", b);
     }
+
+    #[test]
+    fn test_neighbor_bearings_cross_the_cylinder_seam() {
+        let mut params = crate::config::generation::GenerationParams {
+            seed: 7,
+            tile_count: 400,
+            ..Default::default()
+        };
+        params.topology.mode = "wrapping_hex".to_string();
+        let world = crate::world::generation::generate_world(&params);
+        let bearings = NeighborBearings::from_tiles(&world.tiles);
+        // Column 0 of a mid-latitude row: its western neighbor sits across the seam
+        let tile = &world.tiles[10 * 20];
+        let j = tile.neighbors.iter().position(|&n| n == 10 * 20 + 19).unwrap();
+        let b = bearings.bearing(tile.id as usize, j);
+        assert!((b - 90.0).abs() < 1.0, "wind from across the seam blows east, got {}", b);
+    }
 }
@@ -0,0 +1,145 @@
+//! Writes rules make to their neighbors with `set_neighbor()`.
+//!
+//! `set()` only changes the tile a rule runs on, which can't express a tile
+//! pushing something onto the tiles around it: runoff flowing downhill, seeds
+//! blown downwind. `set_neighbor(id, field, value, merge)` queues a write to
+//! neighbor `id` instead. Once every tile's own mutations are applied, the
+//! writes to each neighbor field are merged, in tile order so the result
+//! doesn't depend on evaluation parallelism, and applied like any `set()`.
+
+use std::collections::BTreeMap;
+
+use rhai::Dynamic;
+
+use crate::simulation::engine::{apply_mutations_tracked, numeric_field, Phase, RejectionReason, TileMutations};
+use crate::world::{Tile, World};
+
+/// How several writes to the same neighbor field are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Merge {
+    /// Added to the field's value after the phase's own `set()`s (flows).
+    Sum,
+    /// The field becomes the writes' mean (pushes).
+    Mean,
+    /// The field is lowered to the smallest write, if that is lower.
+    Min,
+    /// The field is raised to the largest write, if that is higher.
+    Max,
+}
+
+/// Valid `merge` arguments to `set_neighbor()`.
+pub const MERGES: &[&str] = &["sum", "mean", "min", "max"];
+
+impl Merge {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sum" => Some(Merge::Sum),
+            "mean" => Some(Merge::Mean),
+            "min" => Some(Merge::Min),
+            "max" => Some(Merge::Max),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Merge::Sum => "sum",
+            Merge::Mean => "mean",
+            Merge::Min => "min",
+            Merge::Max => "max",
+        }
+    }
+}
+
+/// One `set_neighbor()` call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeighborWrite {
+    /// ID of the tile written to
+    pub target: u32,
+    pub field: String,
+    pub value: f64,
+    pub merge: Merge,
+    /// Index into the phase's rule list of the rule that made the write
+    pub rule: usize,
+}
+
+/// The writes to one neighbor field gathered so far.
+struct Pending {
+    merge: Merge,
+    values: Vec<f64>,
+    /// Rule of each write, for rejections
+    rules: Vec<usize>,
+    /// Written with more than one merge; dropped when applied
+    conflict: bool,
+}
+
+/// Neighbor writes of one phase, gathered tile by tile and applied once every
+/// tile's own mutations are in.
+#[derive(Default)]
+pub struct NeighborWrites {
+    pending: BTreeMap<(u32, String), Pending>,
+}
+
+impl NeighborWrites {
+    /// Queue the writes tile `source` made. Writes to a tile that isn't one of
+    /// its neighbors are rejected with `NotANeighbor`.
+    pub fn add(&mut self, world: &World, source: usize, writes: Vec<NeighborWrite>, mut on_reject: impl FnMut(usize, RejectionReason)) {
+        for write in writes {
+            if !world.tiles[source].neighbors.contains(&write.target) {
+                on_reject(write.rule, RejectionReason::NotANeighbor);
+                continue;
+            }
+            let pending = self.pending.entry((write.target, write.field)).or_insert_with(|| Pending {
+                merge: write.merge,
+                values: Vec::new(),
+                rules: Vec::new(),
+                conflict: false,
+            });
+            pending.conflict |= pending.merge != write.merge;
+            pending.values.push(write.value);
+            pending.rules.push(write.rule);
+        }
+    }
+
+    /// Merge and apply the queued writes, in tile and field order. Frozen
+    /// tiles are left alone. Returns the number of fields written;
+    /// `on_reject` gets the rule of each write that was not applied.
+    pub fn apply(self, world: &mut World, phase: Phase, mut on_reject: impl FnMut(usize, RejectionReason)) -> usize {
+        let mut applied = 0;
+        for ((target, field), pending) in self.pending {
+            if world.is_frozen(target) {
+                continue;
+            }
+            match pending.apply(&mut world.tiles[target as usize], field, phase) {
+                Ok(()) => applied += 1,
+                Err(reason) => pending.rules.iter().for_each(|&rule| on_reject(rule, reason)),
+            }
+        }
+        applied
+    }
+}
+
+impl Pending {
+    /// Write the merged value to the tile's field.
+    fn apply(&self, tile: &mut Tile, field: String, phase: Phase) -> Result<(), RejectionReason> {
+        if self.conflict {
+            return Err(RejectionReason::MergeConflict);
+        }
+        let current = numeric_field(tile, &field, phase).ok_or(RejectionReason::UnwritableField)?;
+        let is_int = current.is_int();
+        let current = current.as_float().unwrap_or_else(|_| current.as_int().unwrap_or(0) as f64);
+        let writes = self.values.iter().copied();
+        let merged = match self.merge {
+            Merge::Sum => current + writes.sum::<f64>(),
+            Merge::Mean => writes.sum::<f64>() / self.values.len() as f64,
+            Merge::Min => writes.fold(current, f64::min),
+            Merge::Max => writes.fold(current, f64::max),
+        };
+        // Day counts take the merged value rounded
+        let value = if is_int { Dynamic::from(merged.round() as i64) } else { Dynamic::from(merged) };
+        let mutation = TileMutations { mutations: vec![(field, value)], ..Default::default() };
+        let mut rejected = Ok(());
+        apply_mutations_tracked(tile, &mutation, phase, |_, reason| rejected = Err(reason));
+        rejected
+    }
+}
@@ -0,0 +1,272 @@
+//! Optional sea-surface salinity and an abstract overturning circulation.
+//!
+//! Runs natively right after the weather phase. Each ocean tile's
+//! `conditions.salinity` (PSU) is a freshwater budget on a shallow mixed
+//! layer: evaporation concentrates salt, while precipitation and the rivers
+//! draining into the tile dilute it. Sea ice forming on water below freezing
+//! leaves its brine behind and raises it. Salinity also mixes with
+//! neighboring ocean tiles and relaxes slowly toward the deep-ocean mean.
+//!
+//! The overturning is one world-level number, `World::ocean.overturning`.
+//! Cold, salty water at high latitudes is dense and sinks, driving the
+//! circulation; the index follows the density contrast between polar and
+//! tropical surface water, normalized so a present-day-like contrast gives
+//! 1.0. It responds over about a year. Poleward heat transport scales with
+//! it: high-latitude ocean tiles are warmed and tropical ones cooled by the
+//! same total, so freshening or warming the polar seas weakens the
+//! circulation and cools the high latitudes.
+
+use crate::simulation::aridity::potential_evapotranspiration;
+use crate::simulation::erosion::downslope;
+use crate::world::tile::TerrainType;
+use crate::world::World;
+
+/// Deep-ocean mean salinity (PSU), also the starting value.
+pub const REFERENCE_SALINITY: f32 = 35.0;
+/// Depth of the mixed layer, in precipitation units: the freshwater one
+/// tick's fluxes are diluted into.
+const MIXED_LAYER: f32 = 20.0;
+/// Salinity (PSU) added per tick on water cold enough for sea ice to form.
+const BRINE_REJECTION: f32 = 0.02;
+/// Sea water freezes at about -1.8°C.
+const FREEZING_K: f32 = 271.35;
+/// Fraction of the gap to the neighbor mean closed per tick.
+const MIXING: f32 = 0.1;
+/// Fraction of the gap to the deep-ocean mean closed per tick.
+const RESTORING: f32 = 0.002;
+/// Salinity stays within this range (PSU).
+const SALINITY_RANGE: (f32, f32) = (20.0, 42.0);
+/// Density change (kg/m³) per K of warming, a linearized equation of state.
+const THERMAL_EXPANSION: f32 = 0.2;
+/// Density change (kg/m³) per PSU.
+const HALINE_CONTRACTION: f32 = 0.8;
+/// Polar-minus-tropical density contrast (kg/m³) that gives an index of 1.0:
+/// water 25 K colder at equal salinity.
+const REFERENCE_CONTRAST: f32 = 25.0 * THERMAL_EXPANSION;
+/// Strongest overturning index, for a very dense polar ocean.
+const MAX_OVERTURNING: f32 = 2.0;
+/// Latitude (degrees) poleward of which surface water can sink.
+const POLAR_LATITUDE: f32 = 50.0;
+/// Latitude (degrees) equatorward of which water counts as tropical.
+const TROPICAL_LATITUDE: f32 = 30.0;
+
+/// Ocean circulation settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ocean {
+    /// Warming (K) of the polar ocean at an overturning index of 1.0
+    pub heat_transport: f32,
+}
+
+/// Mixed-layer salinity after one tick of fluxes, before mixing.
+fn next_salinity(salinity: f32, evaporation: f32, fresh_water: f32, temperature: f32) -> f32 {
+    let concentration = (evaporation - fresh_water) / MIXED_LAYER;
+    let brine = if temperature < FREEZING_K { BRINE_REJECTION } else { 0.0 };
+    salinity * (1.0 + concentration.clamp(-0.5, 0.5)) + brine
+}
+
+/// Surface density anomaly (kg/m³) relative to 0°C water at the reference salinity.
+pub fn density_anomaly(temperature: f32, salinity: f32) -> f32 {
+    -THERMAL_EXPANSION * (temperature - 273.15) + HALINE_CONTRACTION * (salinity - REFERENCE_SALINITY)
+}
+
+/// Weight of a tile in the poleward heat transport: 1.0 at the pole, 0.0
+/// equatorward of the tropical band's edge.
+fn polar_weight(latitude: f32) -> f32 {
+    ((latitude.abs() - TROPICAL_LATITUDE) / (90.0 - TROPICAL_LATITUDE)).clamp(0.0, 1.0)
+}
+
+impl Ocean {
+    /// Update salinity on every unfrozen ocean tile, step the overturning
+    /// index, and apply this tick's poleward heat transport.
+    pub fn apply(&self, world: &mut World) {
+        let n = world.tiles.len();
+        let ocean: Vec<bool> =
+            world.tiles.iter().map(|t| t.geology.terrain_type == TerrainType::Ocean).collect();
+        let is_ocean = |i: usize| ocean[i];
+
+        // Rivers discharge into the ocean tile their mouth drains to
+        let mut river_inflow = vec![0.0_f32; n];
+        for tile in &world.tiles {
+            if tile.geology.terrain_type != TerrainType::Ocean
+                && tile.conditions.river_flow > 0.0
+                && let Some((to, _)) = downslope(world, tile)
+                && is_ocean(to)
+            {
+                river_inflow[to] += tile.conditions.river_flow;
+            }
+        }
+
+        let fluxed: Vec<f32> = world
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                if !is_ocean(i) {
+                    return 0.0;
+                }
+                // Tiles from older snapshots start at the deep-ocean mean
+                let s = if t.conditions.salinity > 0.0 { t.conditions.salinity } else { REFERENCE_SALINITY };
+                let fresh_water = t.weather.precipitation + river_inflow[i];
+                next_salinity(s, potential_evapotranspiration(t), fresh_water, t.weather.temperature)
+            })
+            .collect();
+
+        // Mix with ocean neighbors and relax toward the deep ocean
+        let salinity: Vec<f32> = (0..n)
+            .map(|i| {
+                if !is_ocean(i) {
+                    return 0.0;
+                }
+                let (sum, count) = world.tiles[i]
+                    .neighbors
+                    .iter()
+                    .map(|&j| j as usize)
+                    .filter(|&j| j < n && is_ocean(j))
+                    .fold((0.0, 0), |(sum, count), j| (sum + fluxed[j], count + 1));
+                let mut s = fluxed[i];
+                if count > 0 {
+                    s += MIXING * (sum / count as f32 - s);
+                }
+                s += RESTORING * (REFERENCE_SALINITY - s);
+                s.clamp(SALINITY_RANGE.0, SALINITY_RANGE.1)
+            })
+            .collect();
+
+        for (tile, s) in world.tiles.iter_mut().zip(salinity) {
+            if !world.frozen_tiles.contains(&tile.id) {
+                tile.conditions.salinity = s;
+            }
+        }
+
+        // Overturning follows the polar-tropical density contrast
+        let mean_density = |band: &dyn Fn(f32) -> bool| {
+            let (sum, count) = world
+                .tiles
+                .iter()
+                .filter(|t| t.geology.terrain_type == TerrainType::Ocean && band(t.climate.latitude.abs()))
+                .fold((0.0_f64, 0_u32), |(sum, count), t| {
+                    (sum + density_anomaly(t.weather.temperature, t.conditions.salinity) as f64, count + 1)
+                });
+            (count > 0).then(|| (sum / count as f64) as f32)
+        };
+        let polar = mean_density(&|lat| lat >= POLAR_LATITUDE);
+        let tropical = mean_density(&|lat| lat < TROPICAL_LATITUDE);
+        if let (Some(polar), Some(tropical)) = (polar, tropical) {
+            let target = ((polar - tropical) / REFERENCE_CONTRAST).clamp(0.0, MAX_OVERTURNING);
+            let year = (world.season_length.max(1) * 4) as f32;
+            world.ocean.overturning += (target - world.ocean.overturning) / year;
+        }
+
+        // Heat moves poleward in proportion; the tropics pay for it
+        let ocean_weights: Vec<(usize, f32)> = (0..n)
+            .filter(|&i| is_ocean(i) && !world.frozen_tiles.contains(&world.tiles[i].id))
+            .map(|i| (i, polar_weight(world.tiles[i].climate.latitude)))
+            .collect();
+        let warmed: f32 = ocean_weights.iter().map(|(_, w)| w).sum();
+        let tropical_count = ocean_weights.iter().filter(|(_, w)| *w == 0.0).count();
+        if warmed > 0.0 && tropical_count > 0 {
+            let warming = self.heat_transport * world.ocean.overturning;
+            let cooling = warming * warmed / tropical_count as f32;
+            for (i, w) in ocean_weights {
+                let t = &mut world.tiles[i].weather.temperature;
+                *t += if w > 0.0 { warming * w } else { -cooling };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{Position, Tile};
+
+    /// A single meridian of ocean tiles from the equator to the pole, each
+    /// linked to the next.
+    fn meridian() -> World {
+        let mut world = crate::world::generation::generate_world(&crate::config::generation::GenerationParams {
+            seed: 3,
+            tile_count: 100,
+            ocean_ratio: 0.5,
+            ..Default::default()
+        });
+        world.tiles = (0..10_u32)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id < 9).then_some(id + 1)];
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), Position::flat(0.0, 0.0));
+                t.geology.terrain_type = TerrainType::Ocean;
+                t.climate.latitude = id as f32 * 10.0;
+                t.weather.temperature = 300.0 - id as f32 * 3.0;
+                t.weather.humidity = 0.7;
+                t
+            })
+            .collect();
+        world
+    }
+
+    fn run(world: &mut World, ticks: u32, ocean: &Ocean) {
+        let temperatures: Vec<f32> = world.tiles.iter().map(|t| t.weather.temperature).collect();
+        for _ in 0..ticks {
+            // Weather resets temperature every tick; the transport is an offset on top
+            for (t, &temp) in world.tiles.iter_mut().zip(&temperatures) {
+                t.weather.temperature = temp;
+            }
+            ocean.apply(world);
+        }
+    }
+
+    #[test]
+    fn evaporation_concentrates_and_rain_freshens() {
+        let dry = next_salinity(35.0, 0.3, 0.0, 300.0);
+        let wet = next_salinity(35.0, 0.1, 0.5, 300.0);
+        assert!(dry > 35.0 && wet < 35.0, "{} {}", dry, wet);
+        assert!(next_salinity(35.0, 0.0, 0.0, 270.0) > 35.0, "sea ice leaves brine behind");
+        // Salty water is denser; warm water lighter
+        assert!(density_anomaly(275.0, 36.0) > density_anomaly(275.0, 34.0));
+        assert!(density_anomaly(300.0, 35.0) < density_anomaly(275.0, 35.0));
+    }
+
+    #[test]
+    fn salinity_starts_at_reference_and_stays_bounded() {
+        let mut world = meridian();
+        let ocean = Ocean { heat_transport: 2.0 };
+        run(&mut world, 1, &ocean);
+        for t in &world.tiles {
+            assert!((t.conditions.salinity - REFERENCE_SALINITY).abs() < 1.0, "{}", t.conditions.salinity);
+        }
+        for t in &mut world.tiles {
+            t.weather.precipitation = 5.0;
+        }
+        run(&mut world, 500, &ocean);
+        for t in &world.tiles {
+            let s = t.conditions.salinity;
+            assert!((SALINITY_RANGE.0..=SALINITY_RANGE.1).contains(&s), "{}", s);
+            assert!(s < REFERENCE_SALINITY);
+        }
+    }
+
+    #[test]
+    fn freshened_polar_sea_weakens_overturning_and_heat_transport() {
+        let ocean = Ocean { heat_transport: 2.0 };
+        let mut salty = meridian();
+        let mut fresh = meridian();
+        // Meltwater and rain pour into the polar seas
+        for t in fresh.tiles.iter_mut().filter(|t| t.climate.latitude >= POLAR_LATITUDE) {
+            t.weather.precipitation = 2.0;
+        }
+        run(&mut salty, 1000, &ocean);
+        run(&mut fresh, 1000, &ocean);
+        assert!(
+            salty.ocean.overturning > fresh.ocean.overturning + 0.1,
+            "{} vs {}",
+            salty.ocean.overturning,
+            fresh.ocean.overturning
+        );
+
+        let pole = |w: &World| w.tiles[9].weather.temperature;
+        assert!(pole(&salty) > pole(&fresh));
+        // Transport only moves heat around
+        let before: f32 = (0..10).map(|i| 300.0 - i as f32 * 3.0).sum();
+        let after: f32 = salty.tiles.iter().map(|t| t.weather.temperature).sum();
+        assert!((before - after).abs() < 0.01, "{} vs {}", before, after);
+    }
+}
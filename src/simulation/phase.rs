@@ -1,35 +1,62 @@
 use rayon::prelude::*;
 use tracing::warn;
 
+use crate::simulation::boundary::ghost_neighbors;
 use crate::simulation::engine::{
-    apply_mutations, tile_mutable_rhai_map, Phase, RuleEngine, RuleError, TileMutations,
+    apply_mutations_tracked, is_writable_field, tile_immutable_rhai_map, tile_mutable_rhai_map, with_since,
+    BatchTile, MutationStats, Phase, RejectionReason, RuleEngine, RuleError, TileMutations,
 };
+use crate::simulation::events::WorldEvent;
+use crate::simulation::globals::GlobalAccumulator;
 use crate::simulation::native_eval::NativePhaseEvaluator;
+use crate::simulation::neighbor_writes::NeighborWrites;
+use crate::simulation::profiler::{phase_stack, Profiler};
 use crate::world::tile::BiomeType;
 use crate::world::World;
 use rhai::Dynamic;
+use std::time::Instant;
+
+/// Outcome of executing one phase across all tiles.
+#[derive(Debug, Default)]
+pub struct PhaseResult {
+    pub errors: Vec<RuleError>,
+    pub mutation_stats: MutationStats,
+    /// Rule contributions to world globals, merged in tile order.
+    pub contributions: GlobalAccumulator,
+    /// Events rules emitted, in tile order.
+    pub events: Vec<WorldEvent>,
+}
 
 /// Execute a single phase across all tiles using double buffering and parallel evaluation.
 ///
 /// Reads from a snapshot of current tile state (so all tiles in this phase
 /// see the same input), evaluates tiles in parallel via rayon, then writes
-/// mutations to the live tiles sequentially.
+/// mutations to the live tiles sequentially, followed by the merged
+/// `set_neighbor()` writes.
 /// Uses cached immutable maps to avoid rebuilding geology/climate/position each phase.
 pub fn execute_phase(
     world: &mut World,
     engine: &RuleEngine,
     phase: Phase,
     immutable_maps: &[rhai::Map],
-) -> Vec<RuleError> {
+) -> PhaseResult {
     let rules = engine.rules_for_phase(phase);
     if rules.is_empty() {
-        return Vec::new();
+        return PhaseResult::default();
     }
 
     // Build Rhai maps from current (pre-mutation) state — these serve as the snapshot.
     // Uses cached immutable maps + current mutable state per tile.
+    // A slow phase's run also sees what each tile gathered since its last run
+    let inputs = world.phase_inputs.get(phase.dir_name());
     let tile_maps: Vec<Dynamic> = world.tiles.iter().enumerate()
-        .map(|(i, t)| tile_mutable_rhai_map(&immutable_maps[i], t, phase))
+        .map(|(i, t)| {
+            let map = tile_mutable_rhai_map(&immutable_maps[i], t, phase);
+            match inputs.and_then(|inputs| inputs.since(i)) {
+                Some(since) => with_since(map, &since),
+                None => map,
+            }
+        })
         .collect();
     // Extract neighbor lists for the par_iter closure (since we won't clone tiles)
     let neighbor_lists: Vec<Vec<u32>> = world.tiles.iter().map(|t| t.neighbors.clone()).collect();
@@ -40,31 +67,59 @@ pub fn execute_phase(
     let tile_count = world.tiles.len();
     // Capture tile IDs for RNG seed computation (avoids borrowing world.tiles in par_iter)
     let tile_ids: Vec<u32> = world.tiles.iter().map(|t| t.id).collect();
+    // Idle tiles sit the phase out like frozen ones
+    let frozen: Vec<bool> = tile_ids
+        .iter()
+        .enumerate()
+        .map(|(i, &id)| world.is_frozen(id) || world.activity.is_skipped(i))
+        .collect();
+    // Ghost neighbors stand in for missing neighbors at the edges of bounded flat worlds
+    let ghost_maps: Vec<Option<(Dynamic, usize)>> = ghost_neighbors(world)
+        .into_iter()
+        .map(|g| {
+            g.map(|(ghost, missing)| {
+                let immutable = tile_immutable_rhai_map(&ghost);
+                (tile_mutable_rhai_map(&immutable, &ghost, phase), missing)
+            })
+        })
+        .collect();
 
-    // Parallel evaluation: each tile is independently evaluated by a rayon worker thread.
+    // Parallel evaluation: each batch of tiles is evaluated by a rayon worker thread.
     // Thread-local MUTATIONS and RNG_STATE in engine.rs are per-worker, so this is safe.
-    let results: Vec<(usize, Result<TileMutations, RuleError>)> = (0..tile_count)
+    let batch_size = engine.batch_size();
+    let results: Vec<(usize, Result<TileMutations, RuleError>)> = (0..tile_count.div_ceil(batch_size))
         .into_par_iter()
-        .map(|i| {
-            // Gather pre-converted neighbor maps
-            let neighbor_maps: Vec<Dynamic> = neighbor_lists[i]
-                .iter()
-                .filter_map(|&nid| tile_maps.get(nid as usize).cloned())
+        .flat_map_iter(|b| {
+            let range = b * batch_size..((b + 1) * batch_size).min(tile_count);
+            // Frozen tiles are still visible to neighbors but never evaluated
+            let batch: Vec<BatchTile> = range
+                .clone()
+                .filter(|&i| !frozen[i])
+                .map(|i| {
+                    // Gather pre-converted neighbor maps
+                    let mut neighbor_maps: Vec<Dynamic> = neighbor_lists[i]
+                        .iter()
+                        .filter_map(|&nid| tile_maps.get(nid as usize).cloned())
+                        .collect();
+                    if let Some(Some((ghost, missing))) = ghost_maps.get(i) {
+                        neighbor_maps.extend(std::iter::repeat_n(ghost.clone(), *missing));
+                    }
+                    BatchTile {
+                        tile_map: &tile_maps[i],
+                        neighbor_maps,
+                        rng_seed: compute_rng_seed(tick_count, tile_ids[i], phase),
+                        tile_id: tile_ids[i],
+                    }
+                })
                 .collect();
 
-            let rng_seed = compute_rng_seed(tick_count, tile_ids[i], phase);
-
-            let result = engine.evaluate_tile_preconverted(
-                phase,
-                &tile_maps[i],
-                neighbor_maps,
-                &season,
-                tick_count,
-                rng_seed,
-                tile_ids[i],
-            );
-
-            (i, result)
+            let mut evaluated = engine.evaluate_batch(phase, &batch, &season, tick_count).into_iter();
+            range
+                .map(|i| {
+                    let result = if frozen[i] { Ok(TileMutations::default()) } else { evaluated.next().expect("one result per tile") };
+                    (i, result)
+                })
+                .collect::<Vec<_>>()
         })
         .collect();
 
@@ -76,16 +131,76 @@ pub fn execute_phase(
     };
 
     // Sequential: apply mutations to live tiles
+    let rule_name = |idx: Option<usize>| idx.and_then(|r| rules.get(r)).map_or("unknown", |r| r.name.as_str());
     let mut errors = Vec::new();
+    let mut stats = MutationStats::default();
+    let mut contributions = GlobalAccumulator::default();
+    let mut neighbor_writes = NeighborWrites::default();
+    let mut events = Vec::new();
     for (i, result) in results {
         match result {
-            Ok(mutations) => {
-                let mutations = if phase == Phase::Terrain {
-                    filter_invalid_biome_transitions_by_biome(pre_phase_biome_types[i], mutations)
-                } else {
-                    mutations
-                };
-                apply_mutations(&mut world.tiles[i], &mutations, phase);
+            Ok(mut mutations) => {
+                // Strict mode: a write to an unknown or forbidden field fails the rule,
+                // discarding the tile's mutations like any other rule error
+                if engine.is_strict() {
+                    let tile = &world.tiles[i];
+                    let bad = mutations
+                        .mutations
+                        .iter()
+                        .position(|(field, _)| !is_writable_field(tile, field, phase));
+                    if let Some(m) = bad {
+                        errors.push(RuleError {
+                            tile_id: tile.id,
+                            rule_name: rule_name(mutations.rule_index(m)).to_string(),
+                            error: format!(
+                                "set(\"{}\"): unknown field or not writable in the {} phase",
+                                mutations.mutations[m].0,
+                                phase.dir_name()
+                            ),
+                        });
+                        continue;
+                    }
+                    let bad = mutations.neighbor_writes.iter().find(|w| {
+                        !world.tiles.get(w.target as usize).is_some_and(|t| is_writable_field(t, &w.field, phase))
+                    });
+                    if let Some(write) = bad {
+                        errors.push(RuleError {
+                            tile_id: tile.id,
+                            rule_name: rule_name(Some(write.rule)).to_string(),
+                            error: format!(
+                                "set_neighbor({}, \"{}\"): unknown field or not writable in the {} phase",
+                                write.target,
+                                write.field,
+                                phase.dir_name()
+                            ),
+                        });
+                        continue;
+                    }
+                }
+                if phase == Phase::Terrain {
+                    let rejected = filter_invalid_biome_transitions_by_biome(
+                        pre_phase_biome_types[i],
+                        &mut mutations,
+                    );
+                    for idx in rejected {
+                        stats.record_rejection(rule_name(idx), RejectionReason::InvalidBiomeTransition);
+                    }
+                }
+                let applied = apply_mutations_tracked(&mut world.tiles[i], &mutations, phase, |m, reason| {
+                    stats.record_rejection(rule_name(mutations.rule_index(m)), reason);
+                });
+                stats.applied += applied as u64;
+                contributions.merge(mutations.contributions);
+                neighbor_writes.add(world, i, mutations.neighbor_writes, |rule, reason| {
+                    stats.record_rejection(rule_name(Some(rule)), reason);
+                });
+                events.extend(mutations.events.into_iter().map(|e| WorldEvent {
+                    kind: e.kind,
+                    tick: world.tick_count,
+                    tile: Some(world.tiles[i].id),
+                    source: rule_name(Some(e.rule)).to_string(),
+                    data: e.data,
+                }));
             }
             Err(err) => {
                 errors.push(err);
@@ -93,7 +208,18 @@ pub fn execute_phase(
         }
     }
 
-    errors
+    // Writes to neighbors go in after every tile's own mutations
+    let applied = neighbor_writes.apply(world, phase, |rule, reason| {
+        stats.record_rejection(rule_name(Some(rule)), reason);
+    });
+    stats.applied += applied as u64;
+
+    PhaseResult {
+        errors,
+        mutation_stats: stats,
+        contributions,
+        events,
+    }
 }
 
 /// Execute a single phase natively (no Rhai) using a NativePhaseEvaluator.
@@ -105,23 +231,40 @@ pub fn execute_phase_native(
     world: &mut World,
     evaluator: &dyn NativePhaseEvaluator,
     phase: Phase,
-) -> Vec<RuleError> {
+    profiler: Option<&Profiler>,
+) -> PhaseResult {
     let tick_count = world.tick_count;
     let season = world.season;
 
+    let ghosts = ghost_neighbors(world);
+    let inputs = world.phase_inputs.get(phase.dir_name());
+
     let results: Vec<(usize, TileMutations)> = {
         let tiles: &[crate::world::Tile] = &world.tiles;
         (0..tiles.len())
             .into_par_iter()
+            .filter(|&i| !world.is_frozen(tiles[i].id) && !world.activity.is_skipped(i))
             .map(|i| {
                 let tile = &tiles[i];
-                let neighbors: Vec<&crate::world::Tile> = tile
+                let mut neighbors: Vec<&crate::world::Tile> = tile
                     .neighbors
                     .iter()
                     .filter_map(|&nid| tiles.get(nid as usize))
                     .collect();
+                if let Some(Some((ghost, missing))) = ghosts.get(i) {
+                    neighbors.extend(std::iter::repeat_n(ghost, *missing));
+                }
                 let rng_seed = compute_rng_seed(tick_count, tile.id, phase);
-                let mutations = evaluator.evaluate(tile, &neighbors, season, tick_count, rng_seed);
+                let sampler = profiler.filter(|p| p.samples(tick_count, tile.id));
+                let start = sampler.map(|_| Instant::now());
+                let mutations = match inputs.and_then(|inputs| inputs.since(i)) {
+                    Some(since) => evaluator.evaluate_since(tile, &neighbors, season, tick_count, rng_seed, &since),
+                    None => evaluator.evaluate(tile, &neighbors, season, tick_count, rng_seed),
+                };
+                if let (Some(profiler), Some(start)) = (sampler, start) {
+                    let stack = phase_stack(phase, &format!("native/{}", phase.dir_name()));
+                    profiler.record_sample([(stack, start.elapsed())]);
+                }
                 (i, mutations)
             })
             .collect()
@@ -134,31 +277,57 @@ pub fn execute_phase_native(
         Vec::new()
     };
 
-    // Sequential: apply mutations to live tiles
-    for (i, mutations) in results {
-        let mutations = if phase == Phase::Terrain {
-            filter_invalid_biome_transitions_by_biome(pre_phase_biome_types[i], mutations)
-        } else {
-            mutations
-        };
-        apply_mutations(&mut world.tiles[i], &mutations, phase);
+    // Sequential: apply mutations to live tiles. Native evaluators don't track
+    // which rule emitted a mutation, so rejections are attributed to the phase.
+    let source = format!("native/{}", phase.dir_name());
+    let mut stats = MutationStats::default();
+    for (i, mut mutations) in results {
+        if phase == Phase::Terrain {
+            let rejected =
+                filter_invalid_biome_transitions_by_biome(pre_phase_biome_types[i], &mut mutations);
+            for _ in rejected {
+                stats.record_rejection(&source, RejectionReason::InvalidBiomeTransition);
+            }
+        }
+        let applied = apply_mutations_tracked(&mut world.tiles[i], &mutations, phase, |_, reason| {
+            stats.record_rejection(&source, reason);
+        });
+        stats.applied += applied as u64;
     }
 
-    Vec::new()
+    PhaseResult {
+        mutation_stats: stats,
+        ..Default::default()
+    }
 }
 
 /// Compute a deterministic RNG seed for a tile evaluation.
-fn compute_rng_seed(tick: u64, tile_id: u32, phase: Phase) -> u64 {
+///
+/// The raw combination of tick, tile and phase is passed through a splitmix64
+/// finalizer: xorshift64 is linear, so seeds differing in only a few bits
+/// (consecutive phases differ by 1) would otherwise start correlated streams.
+/// `worldground rules rng` measures the result.
+pub fn compute_rng_seed(tick: u64, tile_id: u32, phase: Phase) -> u64 {
     let phase_offset: u64 = match phase {
         Phase::Weather => 0,
         Phase::Conditions => 1,
         Phase::Terrain => 2,
         Phase::Resources => 3,
     };
-    tick.wrapping_mul(6364136223846793005)
-        .wrapping_add(tile_id as u64)
-        .wrapping_mul(1442695040888963407)
-        .wrapping_add(phase_offset)
+    splitmix64(
+        tick.wrapping_mul(6364136223846793005)
+            .wrapping_add(tile_id as u64)
+            .wrapping_mul(1442695040888963407)
+            .wrapping_add(phase_offset),
+    )
+}
+
+/// splitmix64 output function: every input bit affects every output bit.
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
 /// Valid biome transitions — adjacent biomes on the moisture/temperature gradient.
@@ -187,36 +356,56 @@ pub fn valid_transitions(biome: BiomeType) -> &'static [BiomeType] {
     }
 }
 
-/// Filter out invalid biome transitions using just the biome type.
+/// Remove invalid biome transitions using just the biome type.
+///
+/// Returns the rule index (if tracked) of each removed mutation.
 fn filter_invalid_biome_transitions_by_biome(
     current_biome: BiomeType,
-    mut mutations: TileMutations,
-) -> TileMutations {
+    mutations: &mut TileMutations,
+) -> Vec<Option<usize>> {
     let valid = valid_transitions(current_biome);
 
-    mutations.mutations.retain(|(field, value)| {
-        if field != "biome_type" {
-            return true;
-        }
-        if let Ok(s) = value.clone().into_string() {
-            if let Some(target) = parse_biome_type(&s) {
-                if target == current_biome {
-                    return true; // No-op, keep it
-                }
-                if !valid.contains(&target) {
-                    warn!(
-                        from = ?current_biome,
-                        to = ?target,
-                        "Invalid biome transition rejected"
-                    );
-                    return false;
+    let keep: Vec<bool> = mutations
+        .mutations
+        .iter()
+        .map(|(field, value)| {
+            if field != "biome_type" {
+                return true;
+            }
+            if let Ok(s) = value.clone().into_string() {
+                if let Some(target) = parse_biome_type(&s) {
+                    if target == current_biome {
+                        return true; // No-op, keep it
+                    }
+                    if !valid.contains(&target) {
+                        warn!(
+                            from = ?current_biome,
+                            to = ?target,
+                            "Invalid biome transition rejected"
+                        );
+                        return false;
+                    }
                 }
             }
-        }
-        true
-    });
+            true
+        })
+        .collect();
+
+    let rejected = keep
+        .iter()
+        .enumerate()
+        .filter(|&(_, &k)| !k)
+        .map(|(i, _)| mutations.rule_index(i))
+        .collect();
 
-    mutations
+    let mut flags = keep.iter();
+    mutations.mutations.retain(|_| *flags.next().unwrap());
+    if !mutations.rule_indices.is_empty() {
+        let mut flags = keep.iter();
+        mutations.rule_indices.retain(|_| *flags.next().unwrap());
+    }
+
+    rejected
 }
 
 fn parse_biome_type(s: &str) -> Option<BiomeType> {
@@ -286,22 +475,30 @@ mod tests {
             generation_params: crate::config::generation::GenerationParams {
                 seed: 42,
                 tile_count: 100,
-                ocean_ratio: 0.6,
-                mountain_ratio: 0.1,
-                elevation_roughness: 0.5,
-                climate_bands: true,
-                resource_density: 0.3,
-                initial_biome_maturity: 0.5,
-                topology: crate::config::generation::TopologyConfig::default(),
+                ..Default::default()
             },
             snapshot_path: None,
             macro_weather: Default::default(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: Default::default(),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
             tiles: vec![make_test_tile(0), make_test_tile(1)],
         };
 
         let original = world.tiles.clone();
         let immutable_maps = build_immutable_maps(&world);
-        let errors = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+        let errors = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps).errors;
 
         assert!(errors.is_empty());
         assert_eq!(world.tiles, original);
@@ -347,16 +544,24 @@ mod tests {
             generation_params: crate::config::generation::GenerationParams {
                 seed: 42,
                 tile_count: 100,
-                ocean_ratio: 0.6,
-                mountain_ratio: 0.1,
-                elevation_roughness: 0.5,
-                climate_bands: true,
-                resource_density: 0.3,
-                initial_biome_maturity: 0.5,
-                topology: crate::config::generation::TopologyConfig::default(),
+                ..Default::default()
             },
             snapshot_path: None,
             macro_weather: Default::default(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: Default::default(),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
             tiles: vec![
                 {
                     let mut t = make_test_tile(0);
@@ -382,6 +587,388 @@ mod tests {
         assert!((world.tiles[1].weather.temperature - 280.0).abs() < 0.01);
     }
 
+    #[test]
+    fn bounded_edges_see_full_ring_of_ghost_neighbors() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[(
+                "01-count.rhai",
+                r#"
+                let hot = 0.0;
+                for n in neighbors {
+                    if n.weather.temperature > 350.0 { hot += 1.0; }
+                }
+                set("temperature", 100.0 + neighbors.len() + hot * 1000.0);
+                "#,
+            )],
+        );
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+
+        let mut params = crate::config::generation::GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        };
+        params.topology.boundary = "reflective".to_string();
+        let mut world = crate::world::generation::generate_world(&params);
+        assert_eq!(world.tiles[0].neighbors.len(), 2);
+        // Corner tile is hot; reflective ghosts mirror it, so it sees itself 4 times
+        world.tiles[0].weather.temperature = 400.0;
+
+        let immutable_maps = build_immutable_maps(&world);
+        execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+
+        assert!((world.tiles[0].weather.temperature - 4106.0).abs() < 0.01);
+        for t in &world.tiles {
+            let seen = (t.weather.temperature - 100.0) % 1000.0;
+            assert!((seen - 6.0).abs() < 0.01, "tile {} saw {} neighbors", t.id, seen);
+        }
+    }
+
+    #[test]
+    fn ghost_neighbors_cannot_be_written() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "conditions",
+            &[(
+                "01-push.rhai",
+                r#"
+                let mirrors = 0;
+                for n in neighbors {
+                    if n.id == tile.id { mirrors += 1; }
+                    if tile.id == 0 && !tile.neighbor_ids.contains(n.id) {
+                        set_neighbor(n.id, "fire_risk", 0.9, "max");
+                    }
+                }
+                set("mud_level", mirrors / 10.0);
+                "#,
+            )],
+        );
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+
+        let mut params = generated_world(100).generation_params;
+        params.topology.boundary = "reflective".to_string();
+        let mut world = crate::world::generation::generate_world(&params);
+        assert_eq!(world.tiles[0].neighbors.len(), 2);
+        world.tiles[0].conditions.fire_risk = 0.1;
+
+        let immutable_maps = build_immutable_maps(&world);
+        let result = execute_phase(&mut world, &engine, Phase::Conditions, &immutable_maps);
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        // No ghost shares the id of the edge tile it mirrors
+        assert!(world.tiles.iter().all(|t| t.conditions.mud_level == 0.0));
+        // The corner's four ghosts mirror it, but writing to them doesn't reach it
+        assert_eq!(world.tiles[0].conditions.fire_risk, 0.1);
+        let rejected = &result.mutation_stats.rejected;
+        assert_eq!(rejected[&("01-push.rhai".to_string(), RejectionReason::NotANeighbor)], 4);
+    }
+
+    #[test]
+    fn frozen_tiles_not_mutated_but_readable() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[(
+                "01-copy-neighbor.rhai",
+                r#"
+                for n in neighbors {
+                    set("temperature", n.weather.temperature);
+                }
+                "#,
+            )],
+        );
+
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 42,
+                tile_count: 100,
+                ..Default::default()
+            },
+        );
+        world.tiles.truncate(2);
+        world.tiles[0].neighbors = vec![1];
+        world.tiles[0].weather.temperature = 280.0;
+        world.tiles[1].neighbors = vec![0];
+        world.tiles[1].weather.temperature = 300.0;
+        world.frozen_tiles.insert(1);
+
+        let immutable_maps = build_immutable_maps(&world);
+        execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+
+        // Unfrozen tile reads the frozen neighbor; frozen tile keeps its value
+        assert!((world.tiles[0].weather.temperature - 300.0).abs() < 0.01);
+        assert!((world.tiles[1].weather.temperature - 300.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn rejected_mutations_counted_by_rule_and_reason() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "terrain",
+            &[
+                ("01-good.rhai", r#"set("vegetation_health", 0.5);"#),
+                (
+                    "02-bad.rhai",
+                    r#"
+                    set("temperature", 300.0);
+                    set("vegetation_density", 1);
+                    set("biome_type", "Desert");
+                    "#,
+                ),
+            ],
+        );
+
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 42,
+                tile_count: 100,
+                ..Default::default()
+            },
+        );
+        world.tiles.truncate(2);
+        for tile in &mut world.tiles {
+            tile.neighbors.clear();
+            tile.biome.biome_type = BiomeType::Tundra;
+        }
+
+        let immutable_maps = build_immutable_maps(&world);
+        let result = execute_phase(&mut world, &engine, Phase::Terrain, &immutable_maps);
+
+        let stats = result.mutation_stats;
+        assert_eq!(stats.applied, 2);
+        assert_eq!(stats.total_rejected(), 6);
+        for reason in [
+            RejectionReason::UnwritableField,
+            RejectionReason::TypeMismatch,
+            RejectionReason::InvalidBiomeTransition,
+        ] {
+            assert_eq!(stats.rejected[&("02-bad.rhai".to_string(), reason)], 2, "{:?}", reason);
+        }
+        assert_eq!(world.tiles[0].biome.biome_type, BiomeType::Tundra);
+    }
+
+    fn generated_world(tile_count: u32) -> crate::world::World {
+        crate::world::generation::generate_world(&crate::config::generation::GenerationParams {
+            seed: 42,
+            tile_count,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn batch_size_does_not_change_results() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[
+                (
+                    "01-mix.rhai",
+                    r#"
+                    set("temperature", neighbor_avg(neighbors, "weather.temperature") + rand());
+                    contribute("heat", tile.weather.temperature * rand());
+                    "#,
+                ),
+                ("02-fail.rhai", r#"if tile.id % 9 == 4 { throw "boom"; } set("humidity", rand());"#),
+                ("03-more.rhai", r#"contribute("heat", rand()); contribute_max("top", rand());"#),
+            ],
+        );
+
+        let run = |batch_size: usize| {
+            let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+            engine.set_batch_size(batch_size);
+            let mut world = generated_world(100);
+            world.frozen_tiles.insert(5);
+            let immutable_maps = build_immutable_maps(&world);
+            let result = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+            (world.tiles, result.errors.len(), result.contributions.finish())
+        };
+        let single = run(1);
+        assert!(single.1 > 0);
+        for batch_size in [7, 100, 256] {
+            assert_eq!(run(batch_size), single, "batch size {}", batch_size);
+        }
+    }
+
+    #[test]
+    fn batch_rules_run_once_per_batch_and_select_tiles() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[
+                (
+                    "01-rank.batch.rhai",
+                    r#"
+                    for i in 0..batch_len() {
+                        select(i);
+                        set("humidity", i / 10.0);
+                        set("cloud_cover", batch_tile(i).id / 100.0);
+                    }
+                    "#,
+                ),
+                ("02-each.rhai", r#"set("storm_intensity", 0.5);"#),
+            ],
+        );
+
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        engine.set_batch_size(4);
+        let mut world = generated_world(10);
+        world.tiles.truncate(10);
+        world.tile_count = 10;
+        let immutable_maps = build_immutable_maps(&world);
+        let result = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let humidity: Vec<f32> = world.tiles.iter().map(|t| t.weather.humidity).collect();
+        assert_eq!(humidity, [0.0, 0.1, 0.2, 0.3, 0.0, 0.1, 0.2, 0.3, 0.0, 0.1]);
+        assert!(world.tiles.iter().all(|t| t.weather.cloud_cover == t.id as f32 / 100.0));
+        assert!(world.tiles.iter().all(|t| t.weather.storm_intensity == 0.5));
+        let stats = engine.rule_stats();
+        assert_eq!(stats.rules["weather/01-rank.batch.rhai"].invocations, 3);
+        assert_eq!(stats.rules["weather/02-each.rhai"].invocations, 10);
+
+        // A failing batch rule fails every tile of its batch: only the last
+        // batch, of two tiles, has no tile 3
+        make_rule_dir(dir.path(), "weather", &[("01-rank.batch.rhai", "select(3);")]);
+        engine.reload_rules().unwrap();
+        let result = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+        let failed: Vec<u32> = result.errors.iter().map(|e| e.tile_id).collect();
+        assert_eq!(failed, [world.tiles[8].id, world.tiles[9].id]);
+        assert!(result.errors[0].error.contains("select(3)"), "{}", result.errors[0].error);
+    }
+
+    #[test]
+    fn neighbor_writes_merge_after_own_mutations() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "conditions",
+            &[
+                ("01-own.rhai", r#"set("soil_moisture", 0.2);"#),
+                (
+                    "02-push.rhai",
+                    r#"
+                    for n in neighbors {
+                        set_neighbor(n.id, "soil_moisture", 0.1, "sum");
+                        set_neighbor(n.id, "drought_days", tile.id * 10, "max");
+                        set_neighbor(n.id, "fire_risk", (tile.id + 1) / 10.0);
+                    }
+                    "#,
+                ),
+                (
+                    "03-bad.rhai",
+                    r#"
+                    if tile.id == 0 { set_neighbor(2, "soil_moisture", 1.0, "sum"); }
+                    if tile.id == 1 {
+                        set_neighbor(0, "mud_level", 0.5, "max");
+                        set_neighbor(0, "mud_level", 0.1, "min");
+                    }
+                    "#,
+                ),
+            ],
+        );
+
+        let engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = generated_world(100);
+        world.tiles.truncate(3);
+        world.tiles[0].neighbors = vec![1];
+        world.tiles[1].neighbors = vec![0, 2];
+        world.tiles[2].neighbors = vec![1];
+        for tile in &mut world.tiles {
+            tile.conditions.drought_days = 5;
+            tile.conditions.mud_level = 0.3;
+        }
+        let immutable_maps = build_immutable_maps(&world);
+        let result = execute_phase(&mut world, &engine, Phase::Conditions, &immutable_maps);
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let soil: Vec<f32> = world.tiles.iter().map(|t| t.conditions.soil_moisture).collect();
+        assert!(soil.iter().zip([0.3, 0.4, 0.3]).all(|(a, b)| (a - b).abs() < 1e-6), "{:?}", soil);
+        let drought: Vec<u32> = world.tiles.iter().map(|t| t.conditions.drought_days).collect();
+        assert_eq!(drought, [10, 20, 10]);
+        // Tile 1 hears from tiles 0 and 2 and takes the mean of their pushes
+        assert!((world.tiles[1].conditions.fire_risk - 0.2).abs() < 1e-6);
+        assert!((world.tiles[0].conditions.fire_risk - 0.2).abs() < 1e-6);
+        assert_eq!(world.tiles[0].conditions.mud_level, 0.3);
+
+        let rejected = &result.mutation_stats.rejected;
+        assert_eq!(rejected[&("03-bad.rhai".to_string(), RejectionReason::NotANeighbor)], 1);
+        assert_eq!(rejected[&("03-bad.rhai".to_string(), RejectionReason::MergeConflict)], 2);
+
+        make_rule_dir(dir.path(), "conditions", &[("03-bad.rhai", r#"set_neighbor(0, "mud_level", 0.5, "median");"#)]);
+        let mut engine = engine;
+        engine.reload_rules().unwrap();
+        let result = execute_phase(&mut world, &engine, Phase::Conditions, &immutable_maps);
+        assert_eq!(result.errors.len(), 3);
+        assert!(result.errors[0].error.contains("merge must be one of sum, mean, min, max"), "{}", result.errors[0].error);
+    }
+
+    #[test]
+    fn strict_mode_turns_unknown_fields_into_rule_errors() {
+        let dir = TempDir::new().unwrap();
+        setup_empty_rule_dirs(dir.path());
+        make_rule_dir(
+            dir.path(),
+            "weather",
+            &[
+                ("01-humid.rhai", r#"set("humidity", 0.25);"#),
+                ("02-typo.rhai", r#"set("temprature", 300.0);"#),
+            ],
+        );
+
+        let mut engine = RuleEngine::new(dir.path(), 100).unwrap();
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 42,
+                tile_count: 100,
+                ..Default::default()
+            },
+        );
+        world.tiles.truncate(2);
+        for tile in &mut world.tiles {
+            tile.neighbors.clear();
+            tile.weather.humidity = 0.9;
+        }
+        let immutable_maps = build_immutable_maps(&world);
+
+        // Lenient (default): the typo is dropped and the valid write still lands
+        let mut lenient = world.clone();
+        let result = execute_phase(&mut lenient, &engine, Phase::Weather, &immutable_maps);
+        assert!(result.errors.is_empty());
+        assert!((lenient.tiles[0].weather.humidity - 0.25).abs() < 0.001);
+
+        // Strict: the typo is a rule error and the tile's mutations are discarded
+        engine.set_strict(true);
+        let result = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0].rule_name, "02-typo.rhai");
+        assert!(result.errors[0].error.contains("temprature"));
+        assert!((world.tiles[0].weather.humidity - 0.9).abs() < 0.001);
+
+        // So is a neighbor write to a field the phase can't write
+        make_rule_dir(dir.path(), "weather", &[("02-typo.rhai", r#"set_neighbor(1, "soil_moisture", 0.5);"#)]);
+        engine.reload_rules().unwrap();
+        let result = execute_phase(&mut world, &engine, Phase::Weather, &immutable_maps);
+        assert_eq!(result.errors.len(), 2);
+        assert!(result.errors[0].error.contains("set_neighbor(1, \"soil_moisture\")"), "{}", result.errors[0].error);
+    }
+
     #[test]
     fn invalid_biome_transition_tundra_to_desert_rejected() {
         let tile = {
@@ -390,11 +977,13 @@ mod tests {
             t
         };
 
-        let mutations = TileMutations {
+        let mut mutations = TileMutations {
             mutations: vec![("biome_type".to_string(), Dynamic::from("Desert".to_string()))],
+            ..Default::default()
         };
 
-        let filtered = filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, mutations);
+        filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, &mut mutations);
+        let filtered = mutations;
         // The biome_type mutation should have been removed
         assert!(
             !filtered
@@ -413,14 +1002,16 @@ mod tests {
             t
         };
 
-        let mutations = TileMutations {
+        let mut mutations = TileMutations {
             mutations: vec![(
                 "biome_type".to_string(),
                 Dynamic::from("Savanna".to_string()),
             )],
+            ..Default::default()
         };
 
-        let filtered = filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, mutations);
+        filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, &mut mutations);
+        let filtered = mutations;
         assert!(
             filtered
                 .mutations
@@ -438,14 +1029,16 @@ mod tests {
             t
         };
 
-        let mutations = TileMutations {
+        let mut mutations = TileMutations {
             mutations: vec![(
                 "biome_type".to_string(),
                 Dynamic::from("Grassland".to_string()),
             )],
+            ..Default::default()
         };
 
-        let filtered = filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, mutations);
+        filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, &mut mutations);
+        let filtered = mutations;
         assert!(
             !filtered
                 .mutations
@@ -463,7 +1056,7 @@ mod tests {
             t
         };
 
-        let mutations = TileMutations {
+        let mut mutations = TileMutations {
             mutations: vec![
                 (
                     "vegetation_health".to_string(),
@@ -478,9 +1071,11 @@ mod tests {
                     Dynamic::from(0.0_f64),
                 ),
             ],
+            ..Default::default()
         };
 
-        let filtered = filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, mutations);
+        filter_invalid_biome_transitions_by_biome(tile.biome.biome_type, &mut mutations);
+        let filtered = mutations;
         // biome_type removed, but other mutations preserved
         assert_eq!(filtered.mutations.len(), 2);
         assert!(filtered.mutations.iter().any(|(f, _)| f == "vegetation_health"));
@@ -0,0 +1,147 @@
+//! Optional sampling profiler for rule execution.
+//!
+//! Timing every rule on every tile would cost more than some rules do, so
+//! only one tile evaluation in `sample_interval` is timed, rule by rule, and
+//! the result is scaled up to estimate the time across all tiles. Which tiles
+//! are sampled rotates from tick to tick. Native phase evaluators are sampled
+//! the same way; the native steps between phases (hydrology, floods, mud and
+//! the optional ones) run once per tick and are timed in full.
+//!
+//! Time is attributed to stacks like `tick;conditions;01-soil-moisture.rhai`
+//! or `tick;conditions;hydrology` and exported in the collapsed-stack format
+//! that `flamegraph.pl` and `inferno-flamegraph` read, one `stack value` line
+//! per stack with the value in microseconds of thread time. Rules run in
+//! parallel, so the totals add up to more than the wall-clock tick time.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::WorldgroundError;
+use crate::simulation::engine::Phase;
+
+/// Root frame of every stack.
+const ROOT: &str = "tick";
+
+/// Rule timings accumulated since the profiler was created.
+pub struct Profiler {
+    sample_interval: u32,
+    /// Estimated microseconds per stack
+    stacks: Mutex<BTreeMap<String, f64>>,
+}
+
+/// The stack for a rule, native evaluator or native step within a phase.
+pub fn phase_stack(phase: Phase, frame: &str) -> String {
+    format!("{};{};{}", ROOT, phase.dir_name(), frame)
+}
+
+/// The stack for work outside the rule phases, like macro weather.
+pub fn tick_stack(frame: &str) -> String {
+    format!("{};{}", ROOT, frame)
+}
+
+/// Run `f`, recording its time under `stack` when profiling.
+pub fn timed<T>(profiler: Option<&Profiler>, stack: impl FnOnce() -> String, f: impl FnOnce() -> T) -> T {
+    let Some(profiler) = profiler else {
+        return f();
+    };
+    let start = Instant::now();
+    let result = f();
+    profiler.record(&stack(), start.elapsed());
+    result
+}
+
+impl Profiler {
+    /// Time one tile evaluation in `sample_interval` (at least 1: every one).
+    pub fn new(sample_interval: u32) -> Self {
+        Self { sample_interval: sample_interval.max(1), stacks: Mutex::new(BTreeMap::new()) }
+    }
+
+    pub fn sample_interval(&self) -> u32 {
+        self.sample_interval
+    }
+
+    /// Whether a tile's evaluation is timed this tick.
+    pub fn samples(&self, tick: u64, tile_id: u32) -> bool {
+        (tick + tile_id as u64).is_multiple_of(self.sample_interval as u64)
+    }
+
+    /// Record a step timed in full.
+    pub fn record(&self, stack: &str, elapsed: Duration) {
+        self.add(std::iter::once((stack.to_string(), elapsed)), 1.0);
+    }
+
+    /// Record the timings of one sampled tile evaluation, scaled up to
+    /// estimate the time across all tiles.
+    pub fn record_sample(&self, timings: impl IntoIterator<Item = (String, Duration)>) {
+        self.add(timings, self.sample_interval as f64);
+    }
+
+    fn add(&self, timings: impl IntoIterator<Item = (String, Duration)>, scale: f64) {
+        let mut stacks = self.stacks.lock().unwrap_or_else(|e| e.into_inner());
+        for (stack, elapsed) in timings {
+            *stacks.entry(stack).or_insert(0.0) += elapsed.as_secs_f64() * 1e6 * scale;
+        }
+    }
+
+    /// Estimated microseconds per stack, slowest first.
+    pub fn top(&self) -> Vec<(String, u64)> {
+        let stacks = self.stacks.lock().unwrap_or_else(|e| e.into_inner());
+        let mut top: Vec<(String, u64)> = stacks.iter().map(|(s, us)| (s.clone(), us.round() as u64)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top
+    }
+
+    /// The profile in collapsed-stack format, one `stack microseconds` line per stack.
+    pub fn collapsed(&self) -> String {
+        let stacks = self.stacks.lock().unwrap_or_else(|e| e.into_inner());
+        stacks
+            .iter()
+            .filter(|(_, us)| us.round() >= 1.0)
+            .map(|(stack, us)| format!("{} {}\n", stack, us.round() as u64))
+            .collect()
+    }
+
+    /// Write the collapsed-stack profile, replacing any earlier one.
+    pub fn write_collapsed(&self, path: &Path) -> Result<(), WorldgroundError> {
+        std::fs::write(path, self.collapsed()).map_err(|source| WorldgroundError::Write { path: path.to_path_buf(), source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_rotate_across_tiles() {
+        let profiler = Profiler::new(4);
+        let sampled = |tick| (0..100).filter(|&t| profiler.samples(tick, t)).collect::<Vec<u32>>();
+        assert_eq!(sampled(0).len(), 25);
+        assert_ne!(sampled(0), sampled(1));
+        // Every tile gets its turn
+        let covered: std::collections::BTreeSet<u32> = (0..4).flat_map(sampled).collect();
+        assert_eq!(covered.len(), 100);
+        assert!(Profiler::new(0).samples(7, 3));
+    }
+
+    #[test]
+    fn collapsed_stacks_scale_sampled_time() {
+        let profiler = Profiler::new(10);
+        let rule = phase_stack(Phase::Weather, "01-temperature.rhai");
+        profiler.record_sample([(rule.clone(), Duration::from_micros(5))]);
+        profiler.record_sample([(rule.clone(), Duration::from_micros(3))]);
+        profiler.record(&phase_stack(Phase::Conditions, "hydrology"), Duration::from_micros(120));
+        profiler.record(&tick_stack("macro_weather"), Duration::from_nanos(100));
+
+        assert_eq!(
+            profiler.collapsed(),
+            "tick;conditions;hydrology 120\ntick;weather;01-temperature.rhai 80\n"
+        );
+        assert_eq!(profiler.top()[0], ("tick;conditions;hydrology".to_string(), 120));
+
+        assert_eq!(timed(None, || unreachable!(), || 7), 7);
+        timed(Some(&profiler), || tick_stack("statistics"), || ());
+        assert!(profiler.top().iter().any(|(s, _)| s == "tick;statistics"));
+    }
+}
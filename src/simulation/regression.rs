@@ -0,0 +1,215 @@
+//! Behavior regression check for rule packs.
+//!
+//! `rules check` only proves that rules compile. To catch a change that
+//! compiles but makes the world behave differently, a standard world is run
+//! for a fixed number of ticks with the current rules and its end statistics
+//! are compared against a baseline recorded earlier. Each statistic may move
+//! within a tolerance stored with the baseline; anything beyond it fails.
+//! The simulation is deterministic, so an unchanged rule pack reproduces its
+//! baseline exactly.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::generation::{GenerationParams, TopologyConfig};
+use crate::server::protocol::TickStatSummary;
+
+/// Ticks run when recording a new baseline without `--ticks`.
+pub const DEFAULT_TICKS: u32 = 100;
+
+/// The world regressions run on unless a baseline names another: a small
+/// geodesic world with a fixed seed.
+pub fn standard_world() -> GenerationParams {
+    GenerationParams {
+        seed: 1,
+        tile_count: 642, // ignored for geodesic; level 3 gives 642 tiles
+        topology: TopologyConfig { mode: "geodesic".to_string(), subdivision_level: 3, ..TopologyConfig::default() },
+        ..Default::default()
+    }
+}
+
+/// How far each statistic may move from the baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tolerances {
+    /// Mean temperature, in K
+    pub avg_temperature: f32,
+    pub avg_moisture: f32,
+    pub avg_vegetation_health: f32,
+    pub diversity_index: f32,
+    /// Any one biome's share of the tiles (0.02 = 2 percentage points)
+    pub biome_share: f32,
+    /// Rule errors beyond the baseline's
+    pub rule_errors: u32,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Tolerances {
+            avg_temperature: 1.0,
+            avg_moisture: 0.05,
+            avg_vegetation_health: 0.05,
+            diversity_index: 0.05,
+            biome_share: 0.02,
+            rule_errors: 0,
+        }
+    }
+}
+
+/// End statistics of a recorded run, with the world and tick count that
+/// produced them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegressionBaseline {
+    pub ticks: u32,
+    pub world: GenerationParams,
+    pub statistics: TickStatSummary,
+    #[serde(default)]
+    pub tolerances: Tolerances,
+}
+
+/// One statistic compared against its baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    pub tolerance: f64,
+}
+
+impl Comparison {
+    /// Whether the statistic stayed within tolerance. Rule errors may only
+    /// grow by the tolerance; everything else may move either way.
+    pub fn passed(&self) -> bool {
+        if self.metric == "rule_errors" {
+            self.current - self.baseline <= self.tolerance
+        } else {
+            (self.current - self.baseline).abs() <= self.tolerance + 1e-6
+        }
+    }
+}
+
+/// Share of the tiles in each biome, by name.
+fn biome_share(stats: &TickStatSummary, biome: &str) -> f64 {
+    let total: u32 = stats.biome_distribution.values().sum();
+    let count = stats.biome_distribution.get(biome).copied().unwrap_or(0);
+    count as f64 / total.max(1) as f64
+}
+
+/// Compare end statistics with the baseline, one entry per statistic and
+/// per biome present in either run.
+pub fn compare(baseline: &RegressionBaseline, current: &TickStatSummary) -> Vec<Comparison> {
+    let expected = &baseline.statistics;
+    let tolerances = &baseline.tolerances;
+    let entry = |metric: &str, baseline: f32, current: f32, tolerance: f32| Comparison {
+        metric: metric.to_string(),
+        baseline: baseline as f64,
+        current: current as f64,
+        tolerance: tolerance as f64,
+    };
+
+    let mut comparisons = vec![
+        entry("avg_temperature", expected.avg_temperature, current.avg_temperature, tolerances.avg_temperature),
+        entry("avg_moisture", expected.avg_moisture, current.avg_moisture, tolerances.avg_moisture),
+        entry(
+            "avg_vegetation_health",
+            expected.avg_vegetation_health,
+            current.avg_vegetation_health,
+            tolerances.avg_vegetation_health,
+        ),
+        entry("diversity_index", expected.diversity_index, current.diversity_index, tolerances.diversity_index),
+        Comparison {
+            metric: "rule_errors".to_string(),
+            baseline: expected.rule_errors as f64,
+            current: current.rule_errors as f64,
+            tolerance: tolerances.rule_errors as f64,
+        },
+    ];
+
+    let biomes: BTreeSet<&String> =
+        expected.biome_distribution.keys().chain(current.biome_distribution.keys()).collect();
+    comparisons.extend(biomes.into_iter().map(|biome| Comparison {
+        metric: format!("biome_share.{}", biome),
+        baseline: biome_share(expected, biome),
+        current: biome_share(current, biome),
+        tolerance: tolerances.biome_share as f64,
+    }));
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn stats(grassland: u32, desert: u32, temperature: f32) -> TickStatSummary {
+        TickStatSummary {
+            tick: 100,
+            biome_distribution: HashMap::from([
+                ("Grassland".to_string(), grassland),
+                ("Desert".to_string(), desert),
+            ])
+            .into_iter()
+            .filter(|(_, n)| *n > 0)
+            .collect(),
+            avg_temperature: temperature,
+            avg_moisture: 0.4,
+            avg_vegetation_health: 0.6,
+            diversity_index: 0.7,
+            rule_errors: 0,
+            tick_duration_ms: 12.0,
+        }
+    }
+
+    fn failures(baseline: &RegressionBaseline, current: &TickStatSummary) -> Vec<String> {
+        compare(baseline, current).into_iter().filter(|c| !c.passed()).map(|c| c.metric).collect()
+    }
+
+    #[test]
+    fn changes_within_tolerance_pass() {
+        let baseline = RegressionBaseline {
+            ticks: 100,
+            world: standard_world(),
+            statistics: stats(90, 10, 288.0),
+            tolerances: Tolerances::default(),
+        };
+        assert!(failures(&baseline, &baseline.statistics).is_empty());
+        // 1 point of biome share and 0.5 K are within the defaults
+        assert!(failures(&baseline, &stats(89, 11, 288.5)).is_empty());
+    }
+
+    #[test]
+    fn changes_beyond_tolerance_fail() {
+        let baseline = RegressionBaseline {
+            ticks: 100,
+            world: standard_world(),
+            statistics: stats(90, 10, 288.0),
+            tolerances: Tolerances::default(),
+        };
+        let mut current = stats(100, 0, 286.0);
+        current.rule_errors = 1;
+        assert_eq!(
+            failures(&baseline, &current),
+            vec!["avg_temperature", "rule_errors", "biome_share.Desert", "biome_share.Grassland"]
+        );
+
+        // Fewer rule errors than the baseline are never a regression
+        let mut fewer = baseline.clone();
+        fewer.statistics.rule_errors = 5;
+        assert!(failures(&fewer, &stats(90, 10, 288.0)).is_empty());
+    }
+
+    #[test]
+    fn tolerances_may_be_partly_given() {
+        let json = serde_json::json!({
+            "ticks": 50,
+            "world": standard_world(),
+            "statistics": stats(90, 10, 288.0),
+            "tolerances": { "avg_temperature": 3.0 },
+        });
+        let baseline: RegressionBaseline = serde_json::from_value(json).unwrap();
+        assert_eq!(baseline.tolerances.avg_temperature, 3.0);
+        assert_eq!(baseline.tolerances.biome_share, Tolerances::default().biome_share);
+        assert!(failures(&baseline, &stats(90, 10, 290.5)).is_empty());
+    }
+}
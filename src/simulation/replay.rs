@@ -0,0 +1,338 @@
+//! Deterministic replay: a compact log of every tick, and re-running ticks
+//! from a snapshot to check that they come out the same.
+//!
+//! A tick depends only on the world it starts from, the rules and the
+//! config: tile `rand()` streams are seeded from the tick number, and the
+//! macro weather RNG state is part of the world. With `replay_log` on, `run`
+//! appends one JSON line per tick to `replay.jsonl` in the snapshot
+//! directory: the macro weather seed the tick started from, a hash of the
+//! rules it ran, the pressure systems that formed and dissipated, and a hash
+//! of the world it left (`World::state_hash`). `worldground replay` loads a
+//! snapshot, runs it forward and compares each tick with its line, so an
+//! emergent-behavior bug can be reproduced, and bisected by replaying with
+//! other rules or another build, down to the first tick that differs.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hasher;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::simulation::engine::RuleEngine;
+use crate::simulation::execute_tick;
+use crate::world::World;
+
+/// Name of the replay log in the snapshot directory.
+pub const REPLAY_LOG: &str = "replay.jsonl";
+
+/// FNV-1a, which unlike std's hasher is stable across Rust releases and
+/// runs. Written to as an `io::Write`, so values can be encoded straight
+/// into it.
+#[derive(Debug, Clone, Copy)]
+pub struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Write for Fnv {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        Hasher::write(self, bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Hashes are written as 16 hex digits, which JSON tools read without
+/// rounding them to a double.
+mod hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:016x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        u64::from_str_radix(&text, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+/// One line of the replay log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickRecord {
+    /// Tick count once the tick has run, as in a snapshot saved after it
+    pub tick: u64,
+    /// Macro weather RNG state the tick started from
+    #[serde(with = "hex")]
+    pub seed: u64,
+    /// Hash of the rule scripts it ran (`RuleEngine::rules_hash`)
+    #[serde(with = "hex")]
+    pub rules: u64,
+    /// Hash of the world it left (`World::state_hash`)
+    #[serde(with = "hex")]
+    pub state: u64,
+    /// IDs of pressure systems that formed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formed: Vec<u32>,
+    /// IDs of pressure systems that dissipated or merged away
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dissipated: Vec<u32>,
+    /// Tiles were frozen, thawed, surveyed or observed through the API
+    /// before the tick, which a replay can't repeat
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub edited: bool,
+}
+
+/// What a tick starts from, completed into a `TickRecord` once it has run.
+#[derive(Debug, Clone)]
+pub struct TickStart {
+    seed: u64,
+    systems: BTreeSet<u32>,
+}
+
+impl TickStart {
+    /// Note the state `world` is about to tick from.
+    pub fn of(world: &World) -> Self {
+        TickStart {
+            seed: world.macro_weather.rng_state,
+            systems: world.macro_weather.systems.iter().map(|s| s.id).collect(),
+        }
+    }
+
+    /// The record of the tick that took the world from this start to `world`.
+    pub fn finish(self, world: &World, rules: u64, edited: bool) -> TickRecord {
+        let systems: BTreeSet<u32> = world.macro_weather.systems.iter().map(|s| s.id).collect();
+        TickRecord {
+            tick: world.tick_count,
+            seed: self.seed,
+            rules,
+            state: world.state_hash(),
+            formed: systems.difference(&self.systems).copied().collect(),
+            dissipated: self.systems.difference(&systems).copied().collect(),
+            edited,
+        }
+    }
+}
+
+/// The replay log of a running world, appended to after every tick.
+#[derive(Debug)]
+pub struct ReplayLog {
+    path: PathBuf,
+    file: File,
+}
+
+impl ReplayLog {
+    /// Open (or start) the log in `snapshot_dir` for appending.
+    pub fn open(snapshot_dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(snapshot_dir)?;
+        let path = snapshot_dir.join(REPLAY_LOG);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(ReplayLog { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one tick's record as a line.
+    pub fn append(&mut self, record: &TickRecord) -> io::Result<()> {
+        let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())
+    }
+
+    /// Drop the records of ticks up to and including `tick`, e.g. those no
+    /// longer reachable from the snapshots kept.
+    pub fn trim(&mut self, tick: u64) -> io::Result<()> {
+        let text = fs::read_to_string(&self.path)?;
+        let kept: String = text
+            .lines()
+            .filter(|line| serde_json::from_str::<TickRecord>(line).is_ok_and(|r| r.tick > tick))
+            .flat_map(|line| [line, "\n"])
+            .collect();
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, kept)?;
+        fs::rename(&tmp, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Read a replay log, keyed by tick. A tick run more than once (after a
+/// rollback) keeps its latest record.
+pub fn read_log(path: &Path) -> Result<BTreeMap<u64, TickRecord>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mut records = BTreeMap::new();
+    for (i, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let record: TickRecord =
+            serde_json::from_str(line).map_err(|e| format!("{} line {}: {}", path.display(), i + 1, e))?;
+        records.insert(record.tick, record);
+    }
+    Ok(records)
+}
+
+/// How a replay went.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    /// Ticks run
+    pub ticks: u64,
+    /// Ticks compared against a record in the log
+    pub checked: u64,
+    /// First tick whose rules hash differs from the log's, if any
+    pub rules_changed_at: Option<u64>,
+    /// First tick that came out differently, and how
+    pub divergence: Option<(u64, String)>,
+}
+
+/// Run `world` forward to tick `to`, comparing each tick with its record in
+/// `log` and stopping at the first that differs. Ticks the log has no
+/// record of are run unchecked.
+pub fn replay(
+    world: &mut World,
+    engine: &RuleEngine,
+    season_length: u32,
+    to: u64,
+    log: &BTreeMap<u64, TickRecord>,
+) -> ReplayReport {
+    let mut report = ReplayReport::default();
+    let rules = engine.rules_hash();
+    while world.tick_count < to {
+        let logged = log.get(&(world.tick_count + 1));
+        if let Some(logged) = logged {
+            if logged.edited {
+                report.divergence = Some((
+                    logged.tick,
+                    "tiles were frozen, thawed, surveyed or observed through the API before this tick".to_string(),
+                ));
+                break;
+            }
+            if logged.rules != rules && report.rules_changed_at.is_none() {
+                report.rules_changed_at = Some(logged.tick);
+            }
+        }
+        let start = TickStart::of(world);
+        execute_tick(world, engine, season_length);
+        report.ticks += 1;
+        let Some(logged) = logged else { continue };
+        report.checked += 1;
+
+        let record = start.finish(world, rules, false);
+        let mut differences = Vec::new();
+        if record.seed != logged.seed {
+            differences.push(format!("started from seed {:016x}, the log has {:016x}", record.seed, logged.seed));
+        }
+        if record.formed != logged.formed || record.dissipated != logged.dissipated {
+            differences.push(format!(
+                "pressure systems formed {:?} and dissipated {:?}, the log has {:?} and {:?}",
+                record.formed, record.dissipated, logged.formed, logged.dissipated
+            ));
+        }
+        if record.state != logged.state {
+            differences.push(format!("state hash {:016x}, the log has {:016x}", record.state, logged.state));
+        }
+        if !differences.is_empty() {
+            report.divergence = Some((record.tick, differences.join("; ")));
+            break;
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::{GenerationParams, TopologyConfig};
+    use crate::persistence;
+    use crate::simulation::regression;
+    use crate::world::generation::generate_world;
+
+    fn world() -> World {
+        let standard = regression::standard_world();
+        generate_world(&GenerationParams {
+            topology: TopologyConfig { subdivision_level: 2, ..standard.topology.clone() },
+            ..standard
+        })
+    }
+
+    /// Run `ticks` ticks, logging each one.
+    fn run_logged(world: &mut World, engine: &RuleEngine, ticks: u64, log: &mut ReplayLog) {
+        for _ in 0..ticks {
+            let start = TickStart::of(world);
+            execute_tick(world, engine, 90);
+            log.append(&start.finish(world, engine.rules_hash(), false)).unwrap();
+        }
+    }
+
+    #[test]
+    fn replay_from_a_snapshot_matches_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = RuleEngine::new(Path::new("rules"), 1000).unwrap();
+        let mut world = world();
+        let mut log = ReplayLog::open(dir.path()).unwrap();
+        run_logged(&mut world, &engine, 5, &mut log);
+        let snapshot = persistence::save_snapshot(&world, dir.path()).unwrap();
+        run_logged(&mut world, &engine, 10, &mut log);
+
+        let records = read_log(log.path()).unwrap();
+        assert_eq!(records.len(), 15);
+        // Macro weather draws from its RNG every tick
+        assert_ne!(records[&1].seed, records[&15].seed);
+
+        let mut replayed = persistence::load_snapshot(&snapshot).unwrap();
+        let report = replay(&mut replayed, &engine, 90, 15, &records);
+        assert_eq!(report.divergence, None);
+        assert_eq!((report.ticks, report.checked, report.rules_changed_at), (10, 10, None));
+        assert_eq!(replayed.state_hash(), world.state_hash());
+
+        // Trimming keeps only what the snapshot can reach
+        log.trim(5).unwrap();
+        let records = read_log(log.path()).unwrap();
+        assert_eq!(records.keys().next(), Some(&6));
+        assert_eq!(records.len(), 10);
+    }
+
+    #[test]
+    fn first_differing_tick_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = RuleEngine::new(Path::new("rules"), 1000).unwrap();
+        let mut world = world();
+        let start = world.clone();
+        let mut log = ReplayLog::open(dir.path()).unwrap();
+        run_logged(&mut world, &engine, 4, &mut log);
+        let mut records = read_log(log.path()).unwrap();
+        records.get_mut(&3).unwrap().state ^= 1;
+        records.get_mut(&2).unwrap().rules ^= 1;
+
+        let report = replay(&mut start.clone(), &engine, 90, 4, &records);
+        assert_eq!(report.rules_changed_at, Some(2));
+        let (tick, what) = report.divergence.unwrap();
+        assert_eq!(tick, 3);
+        assert!(what.starts_with("state hash"), "{}", what);
+        assert_eq!(report.checked, 3);
+
+        records.get_mut(&3).unwrap().edited = true;
+        let report = replay(&mut start.clone(), &engine, 90, 4, &records);
+        assert_eq!(report.ticks, 2);
+        assert!(report.divergence.unwrap().1.contains("through the API"));
+    }
+}
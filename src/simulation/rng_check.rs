@@ -0,0 +1,183 @@
+//! Statistical check of the per-tile RNG streams rules draw from.
+//!
+//! Every tile evaluation seeds its own xorshift64 stream from
+//! `(tick, tile_id, phase)`. If nearby seeds produced related streams,
+//! neighboring tiles (or the same tile in the next phase or tick) would see
+//! correlated `rand()` values, which shows up as striping in rule output.
+//! This replays the first draws of each stream over a world and measures
+//! those correlations against what independent streams would give.
+
+use crate::simulation::engine::{xorshift64, Phase};
+use crate::simulation::phase::compute_rng_seed;
+use crate::world::World;
+
+/// A correlation counts as an artifact above this multiple of the value
+/// expected from independent streams.
+const CORRELATION_TOLERANCE: f64 = 2.0;
+/// Bit bias counts as an artifact above this many standard deviations.
+const BIT_BIAS_SIGMAS: f64 = 5.0;
+
+/// Correlations between RNG streams that rules expect to be independent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RngReport {
+    /// Ticks replayed
+    pub ticks: u32,
+    /// Draws taken from each stream per tick
+    pub draws: u32,
+    /// Mean |r| between streams of adjacent tiles in the same phase
+    pub neighbor_correlation: f64,
+    /// Largest |r| between any pair of adjacent tiles
+    pub max_neighbor_correlation: f64,
+    /// Mean |r| between consecutive phases of the same tile
+    pub phase_correlation: f64,
+    /// Mean |r| between consecutive ticks of the same tile
+    pub tick_correlation: f64,
+    /// Mean |r| that independent streams of this length would show
+    pub expected_correlation: f64,
+    /// Largest deviation of any output bit's frequency from 0.5
+    pub bit_bias: f64,
+    /// Bit bias that counts as an artifact for this sample size
+    pub bit_bias_limit: f64,
+}
+
+impl RngReport {
+    /// Whether every measured correlation is within noise.
+    pub fn is_ok(&self) -> bool {
+        let limit = CORRELATION_TOLERANCE * self.expected_correlation;
+        self.neighbor_correlation <= limit
+            && self.phase_correlation <= limit
+            && self.tick_correlation <= limit
+            && self.bit_bias <= self.bit_bias_limit
+    }
+}
+
+/// Pearson correlation of two equal-length samples (0.0 when either is constant).
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len() as f64;
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        0.0
+    } else {
+        cov / (var_a * var_b).sqrt()
+    }
+}
+
+/// The first `draws` values a rule's `rand()` would return for a seed.
+fn stream(seed: u64, draws: u32, out: &mut Vec<f64>, bit_counts: &mut [u64; 64]) {
+    let mut state = seed;
+    for _ in 0..draws {
+        state = xorshift64(state);
+        for (bit, count) in bit_counts.iter_mut().enumerate() {
+            *count += (state >> bit) & 1;
+        }
+        out.push(state as f64 / u64::MAX as f64);
+    }
+}
+
+/// Replay `ticks` ticks of `draws` draws per tile and phase, starting at the
+/// world's current tick, and measure cross-stream correlations.
+pub fn check_rng(world: &World, ticks: u32, draws: u32) -> RngReport {
+    let ticks = ticks.max(2);
+    let draws = draws.max(1);
+    let phases = Phase::all();
+    let mut bit_counts = [0_u64; 64];
+
+    // samples[phase][tile] = draws for ticks 0..ticks, concatenated
+    let samples: Vec<Vec<Vec<f64>>> = phases
+        .iter()
+        .map(|&phase| {
+            world
+                .tiles
+                .iter()
+                .map(|tile| {
+                    let mut out = Vec::with_capacity((ticks * draws) as usize);
+                    for t in 0..ticks as u64 {
+                        let seed = compute_rng_seed(world.tick_count + t, tile.id, phase);
+                        stream(seed, draws, &mut out, &mut bit_counts);
+                    }
+                    out
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut neighbor = Vec::new();
+    for streams in &samples {
+        for (i, tile) in world.tiles.iter().enumerate() {
+            for &n in &tile.neighbors {
+                if (n as usize) > i && (n as usize) < streams.len() {
+                    neighbor.push(correlation(&streams[i], &streams[n as usize]).abs());
+                }
+            }
+        }
+    }
+
+    let mut phase = Vec::new();
+    for pair in samples.windows(2) {
+        for (a, b) in pair[0].iter().zip(&pair[1]) {
+            phase.push(correlation(a, b).abs());
+        }
+    }
+
+    let span = ((ticks - 1) * draws) as usize;
+    let mut tick = Vec::new();
+    for streams in &samples {
+        for s in streams {
+            tick.push(correlation(&s[..span], &s[draws as usize..]).abs());
+        }
+    }
+
+    let mean = |v: &[f64]| if v.is_empty() { 0.0 } else { v.iter().sum::<f64>() / v.len() as f64 };
+    let total_draws = (phases.len() * world.tiles.len()) as f64 * (ticks * draws) as f64;
+    let bit_bias = bit_counts
+        .iter()
+        .map(|&c| (c as f64 / total_draws.max(1.0) - 0.5).abs())
+        .fold(0.0, f64::max);
+
+    RngReport {
+        ticks,
+        draws,
+        neighbor_correlation: mean(&neighbor),
+        max_neighbor_correlation: neighbor.iter().cloned().fold(0.0, f64::max),
+        phase_correlation: mean(&phase),
+        tick_correlation: mean(&tick),
+        // E|r| for independent samples of length n is about sqrt(2 / (pi n))
+        expected_correlation: (2.0 / (std::f64::consts::PI * (ticks * draws) as f64)).sqrt(),
+        bit_bias,
+        bit_bias_limit: BIT_BIAS_SIGMAS * 0.5 / total_draws.max(1.0).sqrt(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::GenerationParams;
+    use crate::world::generation::generate_world;
+
+    #[test]
+    fn correlation_of_known_samples() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        assert!((correlation(&a, &[2.0, 4.0, 6.0, 8.0]) - 1.0).abs() < 1e-12);
+        assert!((correlation(&a, &[4.0, 3.0, 2.0, 1.0]) + 1.0).abs() < 1e-12);
+        assert_eq!(correlation(&a, &[5.0; 4]), 0.0);
+    }
+
+    #[test]
+    fn tile_streams_are_independent() {
+        let world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 200,
+            ocean_ratio: 0.3,
+            ..Default::default()
+        });
+        let report = check_rng(&world, 50, 4);
+        assert!(report.is_ok(), "{:?}", report);
+    }
+}
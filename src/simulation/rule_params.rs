@@ -0,0 +1,73 @@
+//! Tunable constants for rule scripts.
+//!
+//! A rule directory may hold a `rules.toml` beside its phase directories,
+//! declaring constants such as `drought_threshold = 10`. Every script sees
+//! them as the `params` map, so a threshold used by several rules is tuned in
+//! one place instead of in each script. Nested tables become nested maps.
+//! The built-in pack has none.
+
+use std::path::Path;
+
+use rhai::{Array, Dynamic, Map};
+
+use super::builtin_rules::is_builtin;
+
+/// File in a rule directory that declares the rules' parameters.
+pub const RULE_PARAMS_FILE: &str = "rules.toml";
+
+/// A rule directory's parameters as a Rhai map, with the text they were read
+/// from (None without a `rules.toml`).
+pub fn load(rule_dir: &Path) -> Result<(Map, Option<String>), String> {
+    let path = rule_dir.join(RULE_PARAMS_FILE);
+    if is_builtin(rule_dir) || !path.is_file() {
+        return Ok((Map::new(), None));
+    }
+    let source = std::fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let table: toml::Table = toml::from_str(&source).map_err(|e| format!("Invalid TOML in {}: {}", path.display(), e))?;
+    let params = table.into_iter().map(|(key, value)| (key.into(), to_dynamic(value))).collect();
+    Ok((params, Some(source)))
+}
+
+/// TOML integers become Rhai integers and floats floats; dates are kept as
+/// their text.
+fn to_dynamic(value: toml::Value) -> Dynamic {
+    match value {
+        toml::Value::String(s) => s.into(),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(d) => d.to_string().into(),
+        toml::Value::Array(values) => values.into_iter().map(to_dynamic).collect::<Array>().into(),
+        toml::Value::Table(table) => {
+            table.into_iter().map(|(key, value)| (key.into(), to_dynamic(value))).collect::<Map>().into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn rules_toml_becomes_a_map() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(load(dir.path()).unwrap().0.len(), 0);
+
+        std::fs::write(
+            dir.path().join(RULE_PARAMS_FILE),
+            "drought_threshold = 10\nwet_soil = 0.6\nnames = [\"a\", \"b\"]\n\n[storms]\nenabled = true\n",
+        )
+        .unwrap();
+        let (params, source) = load(dir.path()).unwrap();
+        assert!(source.unwrap().contains("drought_threshold"));
+        assert_eq!(params["drought_threshold"].as_int(), Ok(10));
+        assert_eq!(params["wet_soil"].as_float(), Ok(0.6));
+        assert_eq!(params["names"].clone().into_array().unwrap().len(), 2);
+        let storms = params["storms"].read_lock::<Map>().unwrap();
+        assert_eq!(storms["enabled"].as_bool(), Ok(true));
+
+        std::fs::write(dir.path().join(RULE_PARAMS_FILE), "drought_threshold = ").unwrap();
+        assert!(load(dir.path()).unwrap_err().contains("rules.toml"));
+    }
+}
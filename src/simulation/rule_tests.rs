@@ -0,0 +1,321 @@
+//! Unit tests for rule packs, read from TOML fixture files.
+//!
+//! A fixture describes a tile and its neighbors field by field, the phases to
+//! run on it, and what the tile should look like afterwards. Each case builds
+//! a tiny world of just those tiles, runs the rules of each phase on the
+//! fixture tile exactly as a tick would (neighbors are frozen, so they stay
+//! as given), and checks the expectations. This lets a rule author see what
+//! a script does to one tile without running a whole world.
+//!
+//! ```toml
+//! [[case]]
+//! name = "deep low starts a storm"
+//! phases = ["weather"]
+//!
+//! [case.tile]
+//! "weather.pressure" = 985.0
+//! "weather.humidity" = 0.8
+//!
+//! [[case.neighbors]]
+//! "weather.temperature" = 280.0
+//!
+//! [case.expect]
+//! "weather.storm_intensity" = { min = 0.05 }
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::config::generation::{GenerationParams, TopologyConfig};
+use crate::error::WorldgroundError;
+use crate::simulation::engine::{tile_immutable_rhai_map, Phase, RuleEngine};
+use crate::simulation::phase::execute_phase;
+use crate::simulation::regression;
+use crate::world::generation::generate_world;
+use crate::world::tile::Position;
+use crate::world::{Season, Tile, World};
+
+/// Tolerance for expectations given as a bare number.
+const DEFAULT_WITHIN: f64 = 1e-4;
+
+/// The cases in one fixture file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    #[serde(rename = "case", default)]
+    pub cases: Vec<TestCase>,
+}
+
+/// One tile, the phases to run on it, and the expected result.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestCase {
+    pub name: String,
+    /// Phase directory names, run in order
+    pub phases: Vec<String>,
+    #[serde(default = "default_season")]
+    pub season: Season,
+    /// Also picks the `rand()` stream
+    #[serde(default)]
+    pub tick: u64,
+    /// Tile field paths ("weather.temperature") laid over a default tile
+    #[serde(default)]
+    pub tile: BTreeMap<String, toml::Value>,
+    /// Each neighbor starts as a copy of the fixture tile
+    #[serde(default)]
+    pub neighbors: Vec<BTreeMap<String, toml::Value>>,
+    #[serde(default)]
+    pub globals: BTreeMap<String, f64>,
+    /// Tile field paths and their expected values after the phases run
+    #[serde(default)]
+    pub expect: BTreeMap<String, Expectation>,
+}
+
+fn default_season() -> Season {
+    Season::Spring
+}
+
+/// What a field should hold once the phases have run.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Expectation {
+    /// Within `within` of `value`
+    Approx { value: f64, within: f64 },
+    /// Between the bounds given, inclusive
+    Range { min: Option<f64>, max: Option<f64> },
+    /// Within 1e-4
+    Number(f64),
+    /// Equal, e.g. a biome or precipitation type
+    Text(String),
+    Flag(bool),
+}
+
+impl Expectation {
+    /// Why `actual` does not meet the expectation, if it doesn't.
+    fn check(&self, actual: &Value) -> Option<String> {
+        let number = actual.as_f64();
+        let met = match (self, number) {
+            (Expectation::Approx { value, within }, Some(n)) => (n - value).abs() <= *within,
+            (Expectation::Number(value), Some(n)) => (n - value).abs() <= DEFAULT_WITHIN,
+            (Expectation::Range { min, max }, Some(n)) => {
+                min.is_none_or(|min| n >= min) && max.is_none_or(|max| n <= max)
+            }
+            (Expectation::Text(text), _) => actual.as_str() == Some(text.as_str()),
+            (Expectation::Flag(flag), _) => actual.as_bool() == Some(*flag),
+            _ => false,
+        };
+        if met {
+            return None;
+        }
+        let expected = match self {
+            Expectation::Approx { value, within } => format!("{} ± {}", value, within),
+            Expectation::Number(value) => value.to_string(),
+            Expectation::Range { min, max } => match (min, max) {
+                (Some(min), Some(max)) => format!("{} to {}", min, max),
+                (Some(min), None) => format!(">= {}", min),
+                (None, Some(max)) => format!("<= {}", max),
+                (None, None) => "a number".to_string(),
+            },
+            Expectation::Text(text) => format!("\"{}\"", text),
+            Expectation::Flag(flag) => flag.to_string(),
+        };
+        // Tile fields are mostly f32; show them as such rather than widened
+        let got = match number {
+            Some(n) => (n as f32).to_string(),
+            None => actual.to_string(),
+        };
+        Some(format!("expected {}, got {}", expected, got))
+    }
+}
+
+/// How one case went.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaseOutcome {
+    /// Rule errors and unmet expectations; empty if the case passed
+    pub failures: Vec<String>,
+    /// Mutations the engine dropped, as "rule: reason × count"
+    pub rejected: Vec<String>,
+}
+
+impl CaseOutcome {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl Fixture {
+    pub fn parse(text: &str) -> Result<Self, WorldgroundError> {
+        let fixture: Fixture = toml::from_str(text).map_err(|e| WorldgroundError::Rules(e.to_string()))?;
+        for case in &fixture.cases {
+            if case.phases.is_empty() {
+                return Err(WorldgroundError::Rules(format!("case '{}' names no phases", case.name)));
+            }
+            for name in &case.phases {
+                parse_phase(name).map_err(|e| WorldgroundError::Rules(format!("case '{}': {}", case.name, e)))?;
+            }
+        }
+        Ok(fixture)
+    }
+}
+
+fn parse_phase(name: &str) -> Result<Phase, WorldgroundError> {
+    Phase::all().iter().copied().find(|p| p.dir_name() == name).ok_or_else(|| {
+        WorldgroundError::Rules(format!(
+            "unknown phase '{}' (expected weather, conditions, terrain or resources)",
+            name
+        ))
+    })
+}
+
+/// The field at a dotted path ("weather.temperature", "resources.resources.0.quantity").
+fn field_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.').try_fold(value, |v, key| match v {
+        Value::Object(map) => map.get_mut(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+        _ => None,
+    })
+}
+
+/// A default tile with the given fields overlaid.
+fn build_tile(id: u32, neighbors: Vec<u32>, layers: &[&BTreeMap<String, toml::Value>]) -> Result<Tile, WorldgroundError> {
+    let mut value = serde_json::to_value(Tile::new_default(id, neighbors, Position::flat(0.0, 0.0)))?;
+    for (path, field) in layers.iter().flat_map(|fields| fields.iter()) {
+        if path == "id" || path == "neighbors" {
+            return Err(WorldgroundError::Rules(format!("'{}' is set by the harness", path)));
+        }
+        let slot = field_mut(&mut value, path)
+            .ok_or_else(|| WorldgroundError::Rules(format!("unknown tile field '{}'", path)))?;
+        *slot = serde_json::to_value(field)?;
+    }
+    serde_json::from_value(value).map_err(|e| WorldgroundError::Rules(format!("invalid tile: {}", e)))
+}
+
+/// The world every case runs in: a handful of tiles, replaced per case.
+fn base_world() -> World {
+    let standard = regression::standard_world();
+    generate_world(&GenerationParams {
+        topology: TopologyConfig { subdivision_level: 1, ..standard.topology.clone() },
+        ..standard
+    })
+}
+
+/// Run one case. Errors in the fixture itself (unknown fields, bad values)
+/// are returned as `Err`; rule errors and unmet expectations are failures.
+pub fn run_case(engine: &RuleEngine, case: &TestCase) -> Result<CaseOutcome, WorldgroundError> {
+    let neighbor_ids: Vec<u32> = (1..=case.neighbors.len() as u32).collect();
+    let mut tiles = vec![build_tile(0, neighbor_ids.clone(), &[&case.tile])?];
+    for (&id, fields) in neighbor_ids.iter().zip(&case.neighbors) {
+        tiles.push(
+            build_tile(id, vec![0], &[&case.tile, fields])
+                .map_err(|e| WorldgroundError::Rules(format!("neighbor {}: {}", id, e)))?,
+        );
+    }
+
+    let mut world = base_world();
+    world.tile_count = tiles.len() as u32;
+    world.tiles = tiles;
+    world.frozen_tiles = neighbor_ids.into_iter().collect();
+    world.inert_tiles.clear();
+    world.tick_count = case.tick;
+    world.season = case.season;
+    world.globals = case.globals.clone();
+    engine.set_globals(&world.globals, &Default::default());
+
+    let mut outcome = CaseOutcome::default();
+    for name in &case.phases {
+        let phase = parse_phase(name)?;
+        let immutable_maps: Vec<rhai::Map> = world.tiles.iter().map(tile_immutable_rhai_map).collect();
+        let result = execute_phase(&mut world, engine, phase, &immutable_maps);
+        outcome.failures.extend(
+            result.errors.iter().map(|e| format!("{} rule {} failed: {}", name, e.rule_name, e.error)),
+        );
+        outcome.rejected.extend(
+            result.mutation_stats.rejected.iter().map(|((rule, reason), count)| {
+                format!("{} rule {}: {} × {}", name, rule, reason.as_str(), count)
+            }),
+        );
+    }
+
+    let mut actual = serde_json::to_value(&world.tiles[0])?;
+    for (path, expectation) in &case.expect {
+        match field_mut(&mut actual, path) {
+            Some(value) => {
+                if let Some(failure) = expectation.check(value) {
+                    outcome.failures.push(format!("{}: {}", path, failure));
+                }
+            }
+            None => return Err(WorldgroundError::Rules(format!("unknown tile field '{}' in expect", path))),
+        }
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn engine() -> RuleEngine {
+        RuleEngine::new(Path::new("rules"), 10).unwrap()
+    }
+
+    fn case(toml: &str) -> TestCase {
+        Fixture::parse(toml).unwrap().cases.remove(0)
+    }
+
+    #[test]
+    fn shipped_fixtures_pass() {
+        let engine = engine();
+        let mut cases = 0;
+        for entry in std::fs::read_dir("rules/tests").unwrap() {
+            let path = entry.unwrap().path();
+            let fixture = Fixture::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            for case in &fixture.cases {
+                let outcome = run_case(&engine, case).unwrap();
+                assert!(outcome.passed(), "{}: {}: {:?}", path.display(), case.name, outcome.failures);
+                cases += 1;
+            }
+        }
+        assert!(cases > 0);
+    }
+
+    #[test]
+    fn unmet_expectations_are_reported() {
+        let outcome = run_case(
+            &engine(),
+            &case(
+                r#"
+[[case]]
+name = "snow falls"
+phases = ["conditions"]
+
+[case.tile]
+"weather.precipitation" = 0.4
+"weather.precipitation_type" = "Snow"
+"conditions.snow_depth" = 0.2
+
+[case.expect]
+"conditions.snow_depth" = { min = 1.0 }
+"weather.precipitation_type" = "Rain"
+"climate.latitude" = { value = 10.0, within = 1.0 }
+"#,
+            ),
+        )
+        .unwrap();
+        assert_eq!(outcome.failures.len(), 3, "{:?}", outcome.failures);
+        assert!(outcome.failures[0].starts_with("climate.latitude: expected 10 ± 1"));
+        assert_eq!(outcome.failures[1], "conditions.snow_depth: expected >= 1, got 0.4");
+    }
+
+    #[test]
+    fn bad_fixtures_rejected() {
+        assert!(Fixture::parse("[[case]]\nname = \"x\"\nphases = [\"weathr\"]").unwrap_err().to_string().contains("unknown phase"));
+        assert!(Fixture::parse("[[case]]\nname = \"x\"\nphases = []").unwrap_err().to_string().contains("no phases"));
+        let typo = case("[[case]]\nname = \"x\"\nphases = [\"weather\"]\n[case.tile]\n\"weather.temprature\" = 1.0");
+        assert!(run_case(&engine(), &typo).unwrap_err().to_string().contains("weather.temprature"));
+        let wrong_type = case("[[case]]\nname = \"x\"\nphases = [\"weather\"]\n[case.tile]\n\"biome.biome_type\" = \"Jungle\"");
+        assert!(run_case(&engine(), &wrong_type).unwrap_err().to_string().contains("invalid tile"));
+    }
+}
@@ -0,0 +1,215 @@
+//! Optional moving sea level, run natively at the end of the Terrain phase.
+//!
+//! The sea stands at `World::sea_level.offset` above the shoreline the world
+//! was generated with. Two things move it: glacier ice melting off the land
+//! (or building up on it), spread over the ocean, and climate scenario rules
+//! contributing to the `sea_level_change` global, which is added each tick.
+//!
+//! When the sea rises, land tiles below the water line that border the sea
+//! are drowned, one ring of tiles per tick; when it falls, sea floor above
+//! the water line next to land is exposed as new shelf. Drowned tiles become
+//! ocean and lose their vegetation, new shelf starts out barren, and the
+//! coasts around every changed tile are reclassified. Low basins cut off
+//! from the sea stay dry, however deep they lie.
+
+use serde::Serialize;
+
+use crate::world::tile::{BiomeType, TerrainType};
+use crate::world::World;
+
+/// Global that scenario rules contribute to, in elevation units per tick.
+pub const SEA_LEVEL_GLOBAL: &str = "sea_level_change";
+/// The sea never moves further than this from its starting level.
+const MAX_OFFSET: f32 = 1.0;
+
+/// Which way a shoreline moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShorelineChangeKind {
+    /// The sea rose over the tile
+    Transgression,
+    /// The sea fell away and left the tile dry
+    Regression,
+}
+
+/// A tile the sea took or gave up this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ShorelineChange {
+    pub tile_id: u32,
+    pub kind: ShorelineChangeKind,
+}
+
+/// Sea level settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeaLevel {
+    /// Rise in elevation units for each unit of land ice melted per ocean tile.
+    pub ice_factor: f32,
+}
+
+/// Midway between the highest sea floor and the lowest land, or 0.0 on a
+/// world that is all land or all sea.
+fn shoreline_elevation(world: &World) -> f32 {
+    let (mut sea, mut land) = (f32::NEG_INFINITY, f32::INFINITY);
+    for tile in &world.tiles {
+        if tile.geology.terrain_type == TerrainType::Ocean {
+            sea = sea.max(tile.geology.elevation);
+        } else {
+            land = land.min(tile.geology.elevation);
+        }
+    }
+    if sea.is_finite() && land.is_finite() { (sea + land) / 2.0 } else { 0.0 }
+}
+
+impl SeaLevel {
+    /// Move the sea and redraw the shoreline. Frozen tiles keep their land
+    /// or water. Returns the tiles the sea took or gave up.
+    pub fn apply(&self, world: &mut World) -> Vec<ShorelineChange> {
+        let is_ocean: Vec<bool> =
+            world.tiles.iter().map(|t| t.geology.terrain_type == TerrainType::Ocean).collect();
+        let land_ice: f32 = world
+            .tiles
+            .iter()
+            .filter(|t| !is_ocean[t.id as usize])
+            .map(|t| t.conditions.glacier_mass)
+            .sum();
+
+        // The first tick only records where the sea starts
+        let Some(datum) = world.sea_level.datum else {
+            world.sea_level.datum = Some(shoreline_elevation(world));
+            world.sea_level.land_ice = land_ice;
+            return Vec::new();
+        };
+
+        let ocean_tiles = is_ocean.iter().filter(|&&o| o).count().max(1) as f32;
+        let melt = world.sea_level.land_ice - land_ice;
+        let scripted = world.globals.get(SEA_LEVEL_GLOBAL).copied().unwrap_or(0.0) as f32;
+        let state = &mut world.sea_level;
+        state.offset = (state.offset + self.ice_factor * melt / ocean_tiles + scripted).clamp(-MAX_OFFSET, MAX_OFFSET);
+        state.land_ice = land_ice;
+        let water_line = datum + state.offset;
+
+        // One ring per tick, decided against the shoreline as it was
+        let borders = |tile: &crate::world::Tile, ocean: bool| {
+            tile.neighbors.iter().any(|&n| is_ocean.get(n as usize) == Some(&ocean))
+        };
+        let (mut drowned, mut exposed) = (Vec::new(), Vec::new());
+        for tile in world.tiles.iter().filter(|t| !world.frozen_tiles.contains(&t.id)) {
+            let below = tile.geology.elevation < water_line;
+            match is_ocean[tile.id as usize] {
+                false if below && borders(tile, true) => drowned.push(tile.id),
+                true if !below && borders(tile, false) => exposed.push(tile.id),
+                _ => {}
+            }
+        }
+        if drowned.is_empty() && exposed.is_empty() {
+            return Vec::new();
+        }
+
+        world.set_sea(&drowned, true);
+        world.set_sea(&exposed, false);
+        let biomes = drowned
+            .iter()
+            .map(|&id| (id, BiomeType::Ocean, 0.0))
+            .chain(exposed.iter().map(|&id| (id, BiomeType::Barren, 0.8)));
+        for (id, biome_type, vegetation_health) in biomes {
+            let biome = &mut world.tiles[id as usize].biome;
+            biome.biome_type = biome_type;
+            biome.vegetation_density = 0.0;
+            biome.vegetation_health = vegetation_health;
+            biome.transition_pressure = 0.0;
+            biome.ticks_in_current_biome = 0;
+            biome.root_strength = 0.0;
+        }
+
+        let change = |kind| move |&tile_id: &u32| ShorelineChange { tile_id, kind };
+        drowned
+            .iter()
+            .map(change(ShorelineChangeKind::Transgression))
+            .chain(exposed.iter().map(change(ShorelineChangeKind::Regression)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{Position, Tile};
+
+    /// A slope of `n` tiles in a line, from sea floor at tile 0 rising 0.1
+    /// per tile, with the shoreline between tiles 1 and 2.
+    fn slope(n: u32) -> World {
+        let mut world = crate::world::generation::generate_world(&crate::config::generation::GenerationParams {
+            seed: 5,
+            tile_count: 100,
+            ocean_ratio: 0.5,
+            ..Default::default()
+        });
+        world.tiles = (0..n)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id + 1 < n).then_some(id + 1)];
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), Position::flat(0.0, 0.0));
+                t.geology.terrain_type = match id {
+                    0 | 1 => TerrainType::Ocean,
+                    2 => TerrainType::Coast,
+                    _ => TerrainType::Plains,
+                };
+                t.geology.elevation = -0.2 + id as f32 * 0.1;
+                t.biome.biome_type = if id < 2 { BiomeType::Ocean } else { BiomeType::Grassland };
+                t
+            })
+            .collect();
+        world
+    }
+
+    fn terrain(world: &World) -> Vec<TerrainType> {
+        world.tiles.iter().map(|t| t.geology.terrain_type).collect()
+    }
+
+    #[test]
+    fn rising_sea_drowns_the_coast_one_ring_at_a_time() {
+        use TerrainType::*;
+        let mut world = slope(6);
+        let sea = SeaLevel { ice_factor: 0.0 };
+        assert!(sea.apply(&mut world).is_empty(), "first tick sets the datum");
+        assert!((world.sea_level.datum.unwrap() - -0.05).abs() < 1e-6);
+
+        world.globals.insert(SEA_LEVEL_GLOBAL.to_string(), 0.2);
+        let changes = sea.apply(&mut world);
+        assert_eq!(changes, vec![ShorelineChange { tile_id: 2, kind: ShorelineChangeKind::Transgression }]);
+        assert_eq!(terrain(&world), vec![Ocean, Ocean, Ocean, Coast, Plains, Plains]);
+        assert_eq!(world.tiles[2].biome.biome_type, BiomeType::Ocean);
+
+        // The water line is now above tile 3 as well, so it goes next tick
+        world.globals.clear();
+        sea.apply(&mut world);
+        assert_eq!(terrain(&world), vec![Ocean, Ocean, Ocean, Ocean, Coast, Plains]);
+        assert!(sea.apply(&mut world).is_empty());
+    }
+
+    #[test]
+    fn falling_sea_exposes_barren_shelf() {
+        use TerrainType::*;
+        let mut world = slope(5);
+        let sea = SeaLevel { ice_factor: 0.0 };
+        sea.apply(&mut world);
+        world.globals.insert(SEA_LEVEL_GLOBAL.to_string(), -0.1);
+        let changes = sea.apply(&mut world);
+        assert_eq!(changes, vec![ShorelineChange { tile_id: 1, kind: ShorelineChangeKind::Regression }]);
+        assert_eq!(terrain(&world), vec![Ocean, Coast, Plains, Plains, Plains]);
+        assert_eq!(world.tiles[1].biome.biome_type, BiomeType::Barren);
+    }
+
+    #[test]
+    fn melting_land_ice_raises_the_sea() {
+        let mut world = slope(6);
+        world.frozen_tiles.insert(2);
+        world.tiles[5].conditions.glacier_mass = 10.0;
+        let sea = SeaLevel { ice_factor: 0.05 };
+        sea.apply(&mut world);
+
+        world.tiles[5].conditions.glacier_mass = 0.0;
+        assert!(sea.apply(&mut world).is_empty(), "the frozen coast holds back the sea");
+        assert!((world.sea_level.offset - 0.25).abs() < 1e-6, "10 units over 2 ocean tiles");
+        assert_eq!(world.tiles[2].geology.terrain_type, TerrainType::Coast);
+    }
+}
@@ -163,6 +163,23 @@ pub fn advance_position(lat: f64, lon: f64, vel_east: f64, vel_north: f64, dt: f
     (new_lat, new_lon)
 }
 
+/// Axial tilt in degrees, the maximum solar declination.
+pub const AXIAL_TILT_DEG: f64 = 23.44;
+
+/// Solar declination in degrees for a point in the year, where 0.0 is the
+/// spring equinox and 0.25 the northern summer solstice.
+pub fn solar_declination(year_fraction: f64) -> f64 {
+    AXIAL_TILT_DEG * (2.0 * std::f64::consts::PI * year_fraction).sin()
+}
+
+/// Point in the year (0.0-1.0) for a tick. The year starts at the spring
+/// equinox, so the northern summer solstice falls on the first tick of Summer.
+pub fn year_fraction(season: crate::world::Season, tick: u64, season_length: u32) -> f64 {
+    let season_length = season_length.max(1);
+    let tick_in_year = season.index() as u64 * season_length as u64 + tick % season_length as u64;
+    tick_in_year as f64 / (4 * season_length as u64) as f64
+}
+
 /// Convert lat/lon (degrees) to unit sphere coordinates (x, y, z).
 pub fn lat_lon_to_xyz(lat: f64, lon: f64) -> (f64, f64, f64) {
     let lat_rad = lat.to_radians();
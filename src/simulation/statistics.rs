@@ -17,13 +17,20 @@ pub struct TickStatistics {
     pub tick_duration_ms: f32,
 }
 
-/// Compute statistics for the current world state after a tick.
+/// Compute statistics for the current world state after a tick. Inert
+/// tiles are not counted. With `area_weighted`, the averages and the
+/// diversity index weight each tile by its area (see `World::tile_areas`),
+/// so a flat grid's crowded polar rows don't outweigh the tropics; the
+/// biome and weather counts stay tile counts.
 pub fn compute_statistics(
     world: &World,
     rule_errors: u32,
     tick_duration_ms: f32,
+    area_weighted: bool,
 ) -> TickStatistics {
-    let total = world.tiles.len() as f64;
+    let areas = area_weighted.then(|| world.tile_areas());
+    let active = world.tiles.iter().filter(|t| !world.is_inert(t.id));
+    let total = active.clone().count() as f64;
     if total == 0.0 {
         return TickStatistics {
             tick: world.tick_count,
@@ -39,29 +46,34 @@ pub fn compute_statistics(
     }
 
     let mut biome_dist: HashMap<BiomeType, u32> = HashMap::new();
+    let mut biome_area: HashMap<BiomeType, f64> = HashMap::new();
     let mut weather_cov: HashMap<PrecipitationType, u32> = HashMap::new();
+    let mut total_area = 0.0_f64;
     let mut total_temp = 0.0_f64;
     let mut total_moisture = 0.0_f64;
     let mut total_veg_health = 0.0_f64;
 
-    for tile in &world.tiles {
+    for tile in active {
+        let area = areas.as_ref().map_or(1.0, |a| a[tile.id as usize]);
         *biome_dist.entry(tile.biome.biome_type).or_insert(0) += 1;
+        *biome_area.entry(tile.biome.biome_type).or_insert(0.0) += area;
         *weather_cov
             .entry(tile.weather.precipitation_type)
             .or_insert(0) += 1;
-        total_temp += tile.weather.temperature as f64;
-        total_moisture += tile.conditions.soil_moisture as f64;
-        total_veg_health += tile.biome.vegetation_health as f64;
+        total_area += area;
+        total_temp += tile.weather.temperature as f64 * area;
+        total_moisture += tile.conditions.soil_moisture as f64 * area;
+        total_veg_health += tile.biome.vegetation_health as f64 * area;
     }
 
-    let diversity = shannon_diversity(&biome_dist, world.tiles.len() as u32);
+    let diversity = shannon_diversity(biome_area.values().copied(), total_area);
 
     TickStatistics {
         tick: world.tick_count,
         biome_distribution: biome_dist,
-        avg_temperature: (total_temp / total) as f32,
-        avg_moisture: (total_moisture / total) as f32,
-        avg_vegetation_health: (total_veg_health / total) as f32,
+        avg_temperature: (total_temp / total_area) as f32,
+        avg_moisture: (total_moisture / total_area) as f32,
+        avg_vegetation_health: (total_veg_health / total_area) as f32,
         weather_coverage: weather_cov,
         diversity_index: diversity,
         rule_errors,
@@ -69,21 +81,21 @@ pub fn compute_statistics(
     }
 }
 
-/// Shannon diversity index normalized to [0, 1].
+/// Shannon diversity index normalized to [0, 1], from each biome's share of
+/// `total` (tiles or area).
 /// 0 = monoculture (all tiles same biome), 1 = maximum diversity (all types equally represented).
-fn shannon_diversity(distribution: &HashMap<BiomeType, u32>, total: u32) -> f32 {
-    if total == 0 {
+fn shannon_diversity(amounts: impl Iterator<Item = f64>, total: f64) -> f32 {
+    if total <= 0.0 {
         return 0.0;
     }
 
-    let total_f = total as f64;
     let mut entropy = 0.0_f64;
     let mut non_zero_types = 0_u32;
 
-    for &count in distribution.values() {
-        if count > 0 {
+    for amount in amounts {
+        if amount > 0.0 {
             non_zero_types += 1;
-            let p = count as f64 / total_f;
+            let p = amount / total;
             entropy -= p * p.ln();
         }
     }
@@ -125,16 +137,24 @@ mod tests {
             generation_params: GenerationParams {
                 seed: 42,
                 tile_count: tile_count as u32,
-                ocean_ratio: 0.6,
-                mountain_ratio: 0.1,
-                elevation_roughness: 0.5,
-                climate_bands: true,
-                resource_density: 0.3,
-                initial_biome_maturity: 0.5,
-                topology: crate::config::generation::TopologyConfig::default(),
+                ..Default::default()
             },
             snapshot_path: None,
             macro_weather: Default::default(),
+            frozen_tiles: Default::default(),
+            inert_tiles: Default::default(),
+            globals: Default::default(),
+            records: Default::default(),
+            thermostat: Default::default(),
+            space_weather: Default::default(),
+            energy: Default::default(),
+            bookmarks: Default::default(),
+            ocean: Default::default(),
+            sea_level: Default::default(),
+            phase_inputs: Default::default(),
+            rule_pack: None,
+            observations: Vec::new(),
+            activity: Default::default(),
             tiles,
         }
     }
@@ -149,7 +169,7 @@ mod tests {
         world.tiles[1].conditions.soil_moisture = 0.4;
         world.tiles[2].conditions.soil_moisture = 0.6;
 
-        let stats = compute_statistics(&world, 0, 10.0);
+        let stats = compute_statistics(&world, 0, 10.0, false);
 
         assert!((stats.avg_temperature - 290.0).abs() < 0.01);
         assert!((stats.avg_moisture - 0.4).abs() < 0.01);
@@ -158,6 +178,20 @@ mod tests {
         assert!((stats.tick_duration_ms - 10.0).abs() < 0.01);
     }
 
+    #[test]
+    fn inert_tiles_are_not_counted() {
+        let mut world = make_test_world(3);
+        world.tiles[0].weather.temperature = 280.0;
+        world.tiles[1].weather.temperature = 290.0;
+        world.tiles[2].weather.temperature = 400.0;
+        world.tiles[2].biome.biome_type = BiomeType::Desert;
+        world.set_inert_tiles([2].into());
+
+        let stats = compute_statistics(&world, 0, 1.0, false);
+        assert!((stats.avg_temperature - 285.0).abs() < 0.01);
+        assert!(!stats.biome_distribution.contains_key(&BiomeType::Desert));
+    }
+
     #[test]
     fn compute_statistics_biome_distribution() {
         let mut world = make_test_world(4);
@@ -166,7 +200,7 @@ mod tests {
         world.tiles[2].biome.biome_type = BiomeType::Desert;
         world.tiles[3].biome.biome_type = BiomeType::Ocean;
 
-        let stats = compute_statistics(&world, 0, 5.0);
+        let stats = compute_statistics(&world, 0, 5.0, false);
 
         assert_eq!(stats.biome_distribution[&BiomeType::Grassland], 2);
         assert_eq!(stats.biome_distribution[&BiomeType::Desert], 1);
@@ -176,7 +210,7 @@ mod tests {
     #[test]
     fn diversity_index_monoculture_is_zero() {
         let world = make_test_world(10); // All default to Grassland
-        let stats = compute_statistics(&world, 0, 1.0);
+        let stats = compute_statistics(&world, 0, 1.0, false);
         assert_eq!(stats.diversity_index, 0.0);
     }
 
@@ -188,11 +222,35 @@ mod tests {
         world.tiles[2].biome.biome_type = BiomeType::Ocean;
         world.tiles[3].biome.biome_type = BiomeType::Tundra;
 
-        let stats = compute_statistics(&world, 0, 1.0);
+        let stats = compute_statistics(&world, 0, 1.0, false);
         // 4 equal types: Shannon entropy = ln(4), normalized = 1.0
         assert!((stats.diversity_index - 1.0).abs() < 0.01);
     }
 
+    #[test]
+    fn area_weighting_favors_low_latitudes() {
+        let mut world = make_test_world(4);
+        // Rows as narrow as on a 100-row grid
+        world.generation_params.tile_count = 10_000;
+        for (tile, (lat, temp, biome)) in world.tiles.iter_mut().zip([
+            (0.0, 300.0, BiomeType::Grassland),
+            (85.0, 250.0, BiomeType::Tundra),
+            (86.0, 250.0, BiomeType::Tundra),
+            (87.0, 250.0, BiomeType::Tundra),
+        ]) {
+            tile.position.lat = lat;
+            tile.weather.temperature = temp;
+            tile.biome.biome_type = biome;
+        }
+
+        let by_tile = compute_statistics(&world, 0, 1.0, false);
+        let by_area = compute_statistics(&world, 0, 1.0, true);
+        assert!((by_tile.avg_temperature - 262.5).abs() < 0.01);
+        assert!(by_area.avg_temperature > 280.0, "{}", by_area.avg_temperature);
+        assert!(by_area.diversity_index < by_tile.diversity_index);
+        assert_eq!(by_area.biome_distribution[&BiomeType::Tundra], 3);
+    }
+
     #[test]
     fn weather_coverage_counted() {
         let mut world = make_test_world(3);
@@ -200,7 +258,7 @@ mod tests {
         world.tiles[1].weather.precipitation_type = PrecipitationType::Rain;
         world.tiles[2].weather.precipitation_type = PrecipitationType::None;
 
-        let stats = compute_statistics(&world, 0, 1.0);
+        let stats = compute_statistics(&world, 0, 1.0, false);
         assert_eq!(stats.weather_coverage[&PrecipitationType::Rain], 2);
         assert_eq!(stats.weather_coverage[&PrecipitationType::None], 1);
     }
@@ -208,7 +266,7 @@ mod tests {
     #[test]
     fn empty_world_returns_zeroed_stats() {
         let world = make_test_world(0);
-        let stats = compute_statistics(&world, 0, 0.0);
+        let stats = compute_statistics(&world, 0, 0.0, false);
         assert_eq!(stats.diversity_index, 0.0);
         assert_eq!(stats.avg_temperature, 0.0);
     }
@@ -0,0 +1,179 @@
+//! Latitude-band thermostat: an optional weak restoring force on temperature.
+//!
+//! Nothing in the rules anchors temperatures, so century-scale runs can drift.
+//! After the weather phase, tiles are grouped into latitude bands and each
+//! band's year-long running mean temperature is nudged toward its climatology
+//! (the band's mean `climate.base_temperature`). Every tile in a band receives
+//! the same correction, so weather patterns within a band and the seasonal
+//! cycle (which averages out over the running mean) are left intact.
+
+use crate::world::World;
+
+/// Seasons per year; the running mean spans one full year.
+const SEASONS_PER_YEAR: u32 = 4;
+
+/// Thermostat settings. A `rate` of 0.0 makes it a no-op.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thermostat {
+    /// Fraction of a band's running anomaly removed per tick (0.0-1.0)
+    pub rate: f32,
+    /// Width of a latitude band in degrees
+    pub band_degrees: f32,
+}
+
+impl Thermostat {
+    fn band_count(&self) -> usize {
+        ((180.0 / self.band_degrees).ceil() as usize).max(1)
+    }
+
+    fn band_of(&self, latitude: f32, bands: usize) -> usize {
+        (((latitude + 90.0) / self.band_degrees).max(0.0) as usize).min(bands - 1)
+    }
+
+    /// Update the running band means and shift unfrozen tiles toward
+    /// climatology. Returns the correction (K) applied to each band.
+    pub fn apply(&self, world: &mut World) -> Vec<f32> {
+        let bands = self.band_count();
+        let mut temp_sum = vec![0.0_f64; bands];
+        let mut base_sum = vec![0.0_f64; bands];
+        let mut counts = vec![0_u32; bands];
+        let tile_bands: Vec<usize> = world
+            .tiles
+            .iter()
+            .map(|t| self.band_of(t.climate.latitude, bands))
+            .collect();
+
+        for (tile, &band) in world.tiles.iter().zip(&tile_bands) {
+            if world.is_frozen(tile.id) {
+                continue;
+            }
+            temp_sum[band] += tile.weather.temperature as f64;
+            base_sum[band] += tile.climate.base_temperature as f64;
+            counts[band] += 1;
+        }
+
+        let state = &mut world.thermostat;
+        let fresh = state.band_means.len() != bands;
+        if fresh {
+            state.band_means = vec![0.0; bands];
+        }
+
+        let year = (world.season_length.max(1) * SEASONS_PER_YEAR) as f32;
+        let alpha = 1.0 / year;
+        let mut corrections = vec![0.0_f32; bands];
+        for band in 0..bands {
+            if counts[band] == 0 {
+                continue;
+            }
+            let mean = (temp_sum[band] / counts[band] as f64) as f32;
+            let climatology = (base_sum[band] / counts[band] as f64) as f32;
+            let running = &mut state.band_means[band];
+            if fresh {
+                *running = mean;
+            } else {
+                *running += alpha * (mean - *running);
+            }
+
+            let correction = self.rate * (climatology - *running);
+            // The correction shifts the band mean by exactly this much; fold it
+            // in now rather than waiting a year for the running mean to notice.
+            *running += correction;
+            corrections[band] = correction;
+        }
+
+        for (i, tile) in world.tiles.iter_mut().enumerate() {
+            if !world.frozen_tiles.contains(&tile.id) {
+                tile.weather.temperature += corrections[tile_bands[i]];
+            }
+        }
+        corrections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generation::test_world;
+
+    fn thermostat(rate: f32) -> Thermostat {
+        Thermostat { rate, band_degrees: 10.0 }
+    }
+
+    fn mean_anomaly(world: &World) -> f32 {
+        world
+            .tiles
+            .iter()
+            .map(|t| t.weather.temperature - t.climate.base_temperature)
+            .sum::<f32>()
+            / world.tiles.len() as f32
+    }
+
+    #[test]
+    fn bands_cover_all_latitudes() {
+        let t = thermostat(0.1);
+        assert_eq!(t.band_count(), 18);
+        assert_eq!(t.band_of(-90.0, 18), 0);
+        assert_eq!(t.band_of(-85.0, 18), 0);
+        assert_eq!(t.band_of(0.0, 18), 9);
+        assert_eq!(t.band_of(90.0, 18), 17);
+        assert_eq!(Thermostat { rate: 0.1, band_degrees: 7.0 }.band_count(), 26);
+    }
+
+    #[test]
+    fn zero_rate_leaves_temperatures_alone() {
+        let mut world = test_world(200);
+        for tile in &mut world.tiles {
+            tile.weather.temperature = tile.climate.base_temperature + 15.0;
+        }
+        let before: Vec<f32> = world.tiles.iter().map(|t| t.weather.temperature).collect();
+        thermostat(0.0).apply(&mut world);
+        let after: Vec<f32> = world.tiles.iter().map(|t| t.weather.temperature).collect();
+        assert_eq!(before, after);
+        assert_eq!(world.thermostat.band_means.len(), 18);
+    }
+
+    #[test]
+    fn warm_drift_relaxes_toward_climatology() {
+        let mut world = test_world(200);
+        for tile in &mut world.tiles {
+            tile.weather.temperature = tile.climate.base_temperature + 10.0;
+        }
+        let t = thermostat(0.05);
+        for _ in 0..100 {
+            t.apply(&mut world);
+        }
+        let anomaly = mean_anomaly(&world);
+        assert!(anomaly > 0.0 && anomaly < 0.1, "anomaly {}", anomaly);
+    }
+
+    #[test]
+    fn within_band_pattern_preserved() {
+        let mut world = test_world(200);
+        for (i, tile) in world.tiles.iter_mut().enumerate() {
+            tile.weather.temperature = tile.climate.base_temperature + 5.0 + (i % 3) as f32;
+        }
+        let before: Vec<f32> = world.tiles.iter().map(|t| t.weather.temperature).collect();
+        let t = thermostat(0.2);
+        let corrections = t.apply(&mut world);
+
+        for (i, tile) in world.tiles.iter().enumerate() {
+            let band = t.band_of(tile.climate.latitude, corrections.len());
+            let shift = tile.weather.temperature - before[i];
+            assert!((shift - corrections[band]).abs() < 1e-4);
+            assert!(shift < 0.0, "warm band should be cooled");
+        }
+    }
+
+    #[test]
+    fn frozen_tiles_untouched() {
+        let mut world = test_world(200);
+        for tile in &mut world.tiles {
+            tile.weather.temperature = tile.climate.base_temperature + 10.0;
+        }
+        world.frozen_tiles.insert(3);
+        let before = world.tiles[3].weather.temperature;
+        thermostat(0.5).apply(&mut world);
+        assert_eq!(world.tiles[3].weather.temperature, before);
+        assert!(world.tiles[4].weather.temperature < world.tiles[4].climate.base_temperature + 10.0);
+    }
+}
@@ -0,0 +1,203 @@
+//! Optional wind waves on the open ocean, computed natively right after the
+//! weather phase.
+//!
+//! Each ocean tile gets a significant wave height, `conditions.wave_height`
+//! in meters, from its wind speed, its fetch and its storm intensity. Fetch
+//! is the stretch of open water the wind has blown across to reach the tile:
+//! it is walked upwind through the neighbor graph, tile by tile, until the
+//! walk reaches land or turns too far off the wind. Short fetches give the
+//! fetch-limited height of the JONSWAP growth curve; long ones level off at
+//! a fully developed sea. Storms heap the sea higher still. Land tiles carry
+//! no waves.
+
+use crate::simulation::sphere_math::{angular_distance, direction_on_sphere, tangent_to_bearing};
+use crate::world::tile::TerrainType;
+use crate::world::{Tile, World};
+
+/// Mean radius of the planet, km.
+const PLANET_RADIUS_KM: f64 = 6371.0;
+/// Gravitational acceleration, m/s².
+const GRAVITY: f32 = 9.81;
+/// JONSWAP fetch-limited growth: g·H / U² = 0.0016 · (g·F / U²)^½.
+const FETCH_GROWTH: f32 = 0.0016;
+/// Pierson-Moskowitz fully developed sea: g·H / U² = 0.243.
+const FULLY_DEVELOPED: f32 = 0.243;
+/// Fetch (km) past which the walk stops; the sea is fully developed well before it.
+const MAX_FETCH_KM: f32 = 2000.0;
+/// Most tiles the upwind walk crosses.
+const MAX_FETCH_STEPS: usize = 64;
+/// Furthest a step may turn from straight upwind, degrees.
+const MAX_TURN_DEG: f64 = 60.0;
+/// Extra height at full storm intensity, as a fraction of the wind sea.
+const STORM_GAIN: f32 = 1.0;
+
+/// Significant wave height (m) for a wind of `wind_speed` m/s blowing over
+/// `fetch_km` of open water, raised by `storm_intensity` (0.0-1.0).
+pub fn wave_height(wind_speed: f32, fetch_km: f32, storm_intensity: f32) -> f32 {
+    if wind_speed <= 0.0 || fetch_km <= 0.0 {
+        return 0.0;
+    }
+    let fetch_limited = FETCH_GROWTH * wind_speed * (fetch_km * 1000.0 / GRAVITY).sqrt();
+    let developed = FULLY_DEVELOPED * wind_speed * wind_speed / GRAVITY;
+    fetch_limited.min(developed) * (1.0 + STORM_GAIN * storm_intensity.clamp(0.0, 1.0))
+}
+
+/// Wave settings, with each tile's neighbor bearings and distances computed
+/// once at startup.
+pub struct Waves {
+    /// For each tile, (bearing toward the neighbor in degrees, distance in km)
+    /// in the order of its neighbor list
+    links: Vec<Vec<(f64, f32)>>,
+}
+
+impl Waves {
+    pub fn new(tiles: &[Tile]) -> Self {
+        let links = tiles
+            .iter()
+            .map(|tile| {
+                tile.neighbors
+                    .iter()
+                    .filter_map(|&n| tiles.get(n as usize))
+                    .map(|n| {
+                        let (p, q) = (&tile.position, &n.position);
+                        let (east, north) = direction_on_sphere(p.lat, p.lon, q.lat, q.lon);
+                        let km = angular_distance(p.lat, p.lon, q.lat, q.lon) * PLANET_RADIUS_KM;
+                        (tangent_to_bearing(east, north), km as f32)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { links }
+    }
+
+    /// Open water (km) upwind of an ocean tile. Waves build across the tile
+    /// itself, so it counts half its mean spacing to its neighbors.
+    pub fn fetch_km(&self, world: &World, tile_id: u32) -> f32 {
+        let start = &world.tiles[tile_id as usize];
+        let links = &self.links[tile_id as usize];
+        if links.is_empty() {
+            return 0.0;
+        }
+        let mut fetch = links.iter().map(|&(_, km)| km).sum::<f32>() / links.len() as f32 / 2.0;
+        // Wind direction is where the wind blows toward
+        let upwind = (start.weather.wind_direction as f64 + 180.0) % 360.0;
+        let mut current = start;
+        for _ in 0..MAX_FETCH_STEPS {
+            let Some((j, turn)) = self.links[current.id as usize]
+                .iter()
+                .map(|&(bearing, _)| bearing_difference(bearing, upwind))
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+            else {
+                break;
+            };
+            let next = &world.tiles[current.neighbors[j] as usize];
+            if turn > MAX_TURN_DEG || next.geology.terrain_type != TerrainType::Ocean {
+                break;
+            }
+            fetch += self.links[current.id as usize][j].1;
+            if fetch >= MAX_FETCH_KM {
+                return MAX_FETCH_KM;
+            }
+            current = next;
+        }
+        fetch
+    }
+
+    /// Set `wave_height` on every unfrozen tile: from wind, fetch and storms
+    /// at sea, 0.0 on land.
+    pub fn apply(&self, world: &mut World) {
+        let heights: Vec<f32> = world
+            .tiles
+            .iter()
+            .map(|tile| {
+                if tile.geology.terrain_type != TerrainType::Ocean {
+                    return 0.0;
+                }
+                let fetch = self.fetch_km(world, tile.id);
+                wave_height(tile.weather.wind_speed, fetch, tile.weather.storm_intensity)
+            })
+            .collect();
+        for (tile, height) in world.tiles.iter_mut().zip(heights) {
+            if !world.frozen_tiles.contains(&tile.id) {
+                tile.conditions.wave_height = height;
+            }
+        }
+    }
+}
+
+/// Angle between two bearings, 0-180 degrees.
+fn bearing_difference(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    /// A row of ocean tiles on the equator, each 1° east of the last, with
+    /// land at the west end and the wind blowing east.
+    fn strip(n: u32) -> World {
+        let mut world = crate::world::generation::generate_world(
+            &crate::config::generation::GenerationParams {
+                seed: 1,
+                tile_count: 10,
+                ocean_ratio: 0.3,
+                ..Default::default()
+            },
+        );
+        world.tiles = (0..n)
+            .map(|id| {
+                let neighbors = [id.checked_sub(1), (id + 1 < n).then_some(id + 1)];
+                let mut position = Position::flat(id as f64, 0.0);
+                position.lon = id as f64;
+                let mut t = Tile::new_default(id, neighbors.into_iter().flatten().collect(), position);
+                t.geology.terrain_type = if id == 0 { TerrainType::Plains } else { TerrainType::Ocean };
+                t.weather.wind_speed = 15.0;
+                t.weather.wind_direction = 90.0;
+                t
+            })
+            .collect();
+        world
+    }
+
+    #[test]
+    fn waves_grow_with_wind_and_fetch_up_to_a_developed_sea() {
+        assert_eq!(wave_height(0.0, 500.0, 0.0), 0.0);
+        assert!(wave_height(10.0, 100.0, 0.0) < wave_height(10.0, 300.0, 0.0));
+        assert!(wave_height(10.0, 100.0, 0.0) < wave_height(20.0, 100.0, 0.0));
+        // About 1.6 m for 10 m/s over 100 km
+        assert!((wave_height(10.0, 100.0, 0.0) - 1.62).abs() < 0.05);
+        // Long fetches level off
+        let developed = wave_height(10.0, 1500.0, 0.0);
+        assert_eq!(developed, wave_height(10.0, 2000.0, 0.0));
+        assert_eq!(wave_height(10.0, 1500.0, 1.0), 2.0 * developed);
+    }
+
+    #[test]
+    fn fetch_runs_upwind_to_the_shore() {
+        let mut world = strip(6);
+        let waves = Waves::new(&world.tiles);
+        // 1° of longitude on the equator is about 111 km
+        let step = waves.links[1][0].1;
+        assert!((step - 111.2).abs() < 0.5, "{}", step);
+        let near = waves.fetch_km(&world, 1);
+        let far = waves.fetch_km(&world, 5);
+        assert!((near - step / 2.0).abs() < 1.0, "{}", near);
+        assert!((far - (4.0 * step + step / 2.0)).abs() < 1.0, "{}", far);
+
+        waves.apply(&mut world);
+        let heights: Vec<f32> = world.tiles.iter().map(|t| t.conditions.wave_height).collect();
+        assert_eq!(heights[0], 0.0, "no waves on land");
+        assert!(heights.windows(2).skip(1).all(|w| w[0] < w[1]), "{:?}", heights);
+
+        // With the wind reversed, the long fetch is at the west end
+        for t in &mut world.tiles {
+            t.weather.wind_direction = 270.0;
+        }
+        assert!((waves.fetch_km(&world, 1) - (4.0 * step + step / 2.0)).abs() < 1.0);
+        assert!((waves.fetch_km(&world, 5) - step / 2.0).abs() < 1.0);
+    }
+}
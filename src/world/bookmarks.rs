@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::world::Tile;
+pub use worldground_protocol::Bookmark;
+
+/// Named views and guided tours curated by world authors.
+///
+/// Saved with the world and edited through `POST /api/bookmarks`. A tour is
+/// an ordered list of bookmarks, each shown for a number of seconds; the
+/// server plays it to every connected viewer as `TourStep` messages.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bookmarks {
+    pub bookmarks: BTreeMap<String, Bookmark>,
+    pub tours: BTreeMap<String, Tour>,
+}
+
+/// An ordered walkthrough of bookmarks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tour {
+    pub stops: Vec<TourStop>,
+}
+
+/// One stop of a tour: a bookmark and how long to stay on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TourStop {
+    pub bookmark: String,
+    pub dwell_secs: f32,
+}
+
+impl Bookmarks {
+    /// Apply a batch of edits in order: tour removals, bookmark removals,
+    /// bookmark upserts, tour upserts. Bookmarks get their latitude and
+    /// longitude from the tile. Returns one message per edit that was
+    /// skipped: a bookmark on a tile that doesn't exist, removing a bookmark
+    /// a tour still visits, or a tour with no stops, a missing bookmark or a
+    /// dwell time that isn't a positive number.
+    pub fn apply(
+        &mut self,
+        tiles: &[Tile],
+        set: &BTreeMap<String, Bookmark>,
+        remove: &[String],
+        tours: &BTreeMap<String, Tour>,
+        remove_tours: &[String],
+    ) -> Vec<String> {
+        let mut skipped = Vec::new();
+        for name in remove_tours {
+            self.tours.remove(name);
+        }
+        for name in remove {
+            match self.tours.iter().find(|(_, tour)| tour.stops.iter().any(|s| &s.bookmark == name)) {
+                Some((tour, _)) => skipped.push(format!("bookmark '{}' is used by tour '{}'", name, tour)),
+                None => {
+                    self.bookmarks.remove(name);
+                }
+            }
+        }
+        for (name, bookmark) in set {
+            let Some(tile) = tiles.get(bookmark.tile_id as usize) else {
+                skipped.push(format!("bookmark '{}': no tile {}", name, bookmark.tile_id));
+                continue;
+            };
+            if !bookmark.radius_deg.is_finite() || bookmark.radius_deg < 0.0 {
+                skipped.push(format!("bookmark '{}': radius_deg must be 0 or more", name));
+                continue;
+            }
+            let mut bookmark = bookmark.clone();
+            bookmark.lat = tile.position.lat;
+            bookmark.lon = tile.position.lon;
+            self.bookmarks.insert(name.clone(), bookmark);
+        }
+        for (name, tour) in tours {
+            if tour.stops.is_empty() {
+                skipped.push(format!("tour '{}' has no stops", name));
+            } else if let Some(stop) = tour.stops.iter().find(|s| !self.bookmarks.contains_key(&s.bookmark)) {
+                skipped.push(format!("tour '{}': no bookmark '{}'", name, stop.bookmark));
+            } else if tour.stops.iter().any(|s| !s.dwell_secs.is_finite() || s.dwell_secs <= 0.0) {
+                skipped.push(format!("tour '{}': dwell_secs must be a positive number", name));
+            } else {
+                self.tours.insert(name.clone(), tour.clone());
+            }
+        }
+        skipped
+    }
+
+    /// A tour's stops resolved to their bookmarks, or `None` if there is no such tour.
+    pub fn tour_views(&self, name: &str) -> Option<Vec<(String, Bookmark, f32)>> {
+        let tour = self.tours.get(name)?;
+        Some(
+            tour.stops
+                .iter()
+                .filter_map(|stop| {
+                    let bookmark = self.bookmarks.get(&stop.bookmark)?;
+                    Some((stop.bookmark.clone(), bookmark.clone(), stop.dwell_secs))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    fn tiles() -> Vec<Tile> {
+        (0..3)
+            .map(|id| {
+                let mut position = Position::flat(id as f64, 0.0);
+                position.lat = 10.0 * id as f64;
+                position.lon = -5.0 * id as f64;
+                Tile::new_default(id, vec![], position)
+            })
+            .collect()
+    }
+
+    fn bookmark(tile_id: u32) -> Bookmark {
+        Bookmark { tile_id, lat: 0.0, lon: 0.0, radius_deg: 5.0, caption: format!("tile {}", tile_id) }
+    }
+
+    fn tour(stops: &[(&str, f32)]) -> Tour {
+        Tour {
+            stops: stops
+                .iter()
+                .map(|&(bookmark, dwell_secs)| TourStop { bookmark: bookmark.to_string(), dwell_secs })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn bookmarks_take_their_position_from_the_tile() {
+        let mut bookmarks = Bookmarks::default();
+        let set = BTreeMap::from([("peak".to_string(), bookmark(2)), ("void".to_string(), bookmark(99))]);
+        let skipped = bookmarks.apply(&tiles(), &set, &[], &BTreeMap::new(), &[]);
+
+        assert_eq!(skipped, vec!["bookmark 'void': no tile 99".to_string()]);
+        let peak = &bookmarks.bookmarks["peak"];
+        assert_eq!((peak.lat, peak.lon), (20.0, -10.0));
+    }
+
+    #[test]
+    fn tours_must_visit_existing_bookmarks_for_positive_times() {
+        let mut bookmarks = Bookmarks::default();
+        let set = BTreeMap::from([("a".to_string(), bookmark(0)), ("b".to_string(), bookmark(1))]);
+        let tours = BTreeMap::from([
+            ("good".to_string(), tour(&[("a", 4.0), ("b", 2.5)])),
+            ("missing".to_string(), tour(&[("a", 4.0), ("c", 1.0)])),
+            ("instant".to_string(), tour(&[("a", 0.0)])),
+            ("empty".to_string(), tour(&[])),
+        ]);
+        let skipped = bookmarks.apply(&tiles(), &set, &[], &tours, &[]);
+
+        assert_eq!(skipped.len(), 3, "{:?}", skipped);
+        assert_eq!(bookmarks.tours.keys().collect::<Vec<_>>(), vec!["good"]);
+        let views = bookmarks.tour_views("good").unwrap();
+        assert_eq!(views.iter().map(|v| (v.0.as_str(), v.2)).collect::<Vec<_>>(), vec![("a", 4.0), ("b", 2.5)]);
+        assert!(bookmarks.tour_views("missing").is_none());
+    }
+
+    #[test]
+    fn bookmarks_in_use_survive_removal_until_their_tour_goes() {
+        let mut bookmarks = Bookmarks::default();
+        let set = BTreeMap::from([("a".to_string(), bookmark(0))]);
+        let tours = BTreeMap::from([("t".to_string(), tour(&[("a", 1.0)]))]);
+        bookmarks.apply(&tiles(), &set, &[], &tours, &[]);
+
+        let remove = vec!["a".to_string()];
+        let skipped = bookmarks.apply(&tiles(), &BTreeMap::new(), &remove, &BTreeMap::new(), &[]);
+        assert_eq!(skipped, vec!["bookmark 'a' is used by tour 't'".to_string()]);
+        assert!(bookmarks.bookmarks.contains_key("a"));
+
+        let skipped = bookmarks.apply(&tiles(), &BTreeMap::new(), &remove, &BTreeMap::new(), &["t".to_string()]);
+        assert!(skipped.is_empty());
+        assert_eq!(bookmarks, Bookmarks::default());
+    }
+}
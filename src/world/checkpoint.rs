@@ -0,0 +1,278 @@
+//! In-memory checkpoints of recent ticks, so a bad rule deployment can be
+//! undone live without reloading a snapshot from disk.
+//!
+//! A checkpoint holds what ticks change: the tile layers (as arrays, like
+//! diffs and layer snapshots), each tile's geology for the steps that move
+//! soil, ice and coastlines, and the world-level simulation state. What the
+//! user edits (frozen tiles, bookmarks) and what never changes (topology,
+//! climate baselines) is left out, so rolling back keeps those as they are.
+//! Observations are posted by the user too, but every tick counts them down,
+//! so they are rolled back with the ticks that nudged toward them.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::Hasher;
+
+use super::layers::{DiffLayer, DIFF_LAYERS};
+use crate::error::WorldgroundError;
+use crate::simulation::activity::Activity;
+use crate::simulation::assimilation::Observation;
+use crate::simulation::replay::Fnv;
+use super::tile::GeologyLayer;
+use super::{
+    EnergyPotential, LayerArrays, MacroWeatherState, OceanState, PhaseInputs, SeaLevelState, Season,
+    SpaceWeatherState, ThermostatState, World, WorldRecords,
+};
+
+/// The simulation state of a world as of one tick.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub tick_count: u64,
+    pub season: Season,
+    layers: LayerArrays,
+    geology: Vec<GeologyLayer>,
+    macro_weather: MacroWeatherState,
+    globals: BTreeMap<String, f64>,
+    records: WorldRecords,
+    thermostat: ThermostatState,
+    space_weather: SpaceWeatherState,
+    energy: EnergyPotential,
+    ocean: OceanState,
+    sea_level: SeaLevelState,
+    phase_inputs: BTreeMap<String, PhaseInputs>,
+    activity: Activity,
+    observations: Vec<Observation>,
+}
+
+impl World {
+    /// Capture the simulation state as of the last completed tick.
+    pub fn checkpoint(&self) -> Checkpoint {
+        let mut layers = DIFF_LAYERS.to_vec();
+        layers.push(DiffLayer::Scratch);
+        Checkpoint {
+            tick_count: self.tick_count,
+            season: self.season,
+            layers: self.capture_layers(&layers),
+            geology: self.tiles.iter().map(|t| t.geology.clone()).collect(),
+            macro_weather: self.macro_weather.clone(),
+            globals: self.globals.clone(),
+            records: self.records.clone(),
+            thermostat: self.thermostat.clone(),
+            space_weather: self.space_weather.clone(),
+            energy: self.energy.clone(),
+            ocean: self.ocean.clone(),
+            sea_level: self.sea_level.clone(),
+            phase_inputs: self.phase_inputs.clone(),
+            activity: self.activity.clone(),
+            observations: self.observations.clone(),
+        }
+    }
+
+    /// Hash of what a checkpoint holds: equal for two worlds exactly when
+    /// their tile layers, geology and world-level simulation state match
+    /// bit for bit. Stable across runs and builds, for the replay log.
+    pub fn state_hash(&self) -> u64 {
+        let mut hash = Fnv::default();
+        // Encoding into a hasher can't fail
+        for tile in &self.tiles {
+            let layers = (&tile.weather, &tile.conditions, &tile.biome, &tile.resources, &tile.scratch, &tile.geology);
+            let _ = bincode::serialize_into(&mut hash, &layers);
+        }
+        let state = (
+            self.tick_count,
+            self.season,
+            &self.macro_weather,
+            &self.globals,
+            &self.records,
+            &self.thermostat,
+            &self.space_weather,
+            &self.energy,
+            &self.ocean,
+            &self.sea_level,
+            &self.phase_inputs,
+            &self.activity,
+            &self.observations,
+        );
+        let _ = bincode::serialize_into(&mut hash, &state);
+        hash.finish()
+    }
+
+    /// Put the world back as it was at `checkpoint`, which must have been
+    /// taken of this world.
+    pub fn restore_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<(), WorldgroundError> {
+        if checkpoint.geology.len() != self.tiles.len() {
+            return Err(WorldgroundError::Simulation(format!(
+                "checkpoint covers {} tiles, the world has {}",
+                checkpoint.geology.len(),
+                self.tiles.len()
+            )));
+        }
+        self.restore_layers(checkpoint.layers)?;
+        for (tile, geology) in self.tiles.iter_mut().zip(checkpoint.geology) {
+            tile.geology = geology;
+        }
+        self.tick_count = checkpoint.tick_count;
+        self.season = checkpoint.season;
+        self.macro_weather = checkpoint.macro_weather;
+        self.globals = checkpoint.globals;
+        self.records = checkpoint.records;
+        self.thermostat = checkpoint.thermostat;
+        self.space_weather = checkpoint.space_weather;
+        self.energy = checkpoint.energy;
+        self.ocean = checkpoint.ocean;
+        self.sea_level = checkpoint.sea_level;
+        self.phase_inputs = checkpoint.phase_inputs;
+        self.activity = checkpoint.activity;
+        self.observations = checkpoint.observations;
+        Ok(())
+    }
+}
+
+/// The last few ticks' checkpoints, oldest first; taking one past capacity
+/// drops the oldest.
+#[derive(Debug, Clone, Default)]
+pub struct Checkpoints {
+    capacity: usize,
+    checkpoints: VecDeque<Checkpoint>,
+}
+
+impl Checkpoints {
+    /// Keep up to `capacity` checkpoints; 0 keeps none.
+    pub fn new(capacity: usize) -> Self {
+        Checkpoints { capacity, checkpoints: VecDeque::with_capacity(capacity) }
+    }
+
+    /// Checkpoint `world` as it stands, before its next tick runs.
+    pub fn take(&mut self, world: &World) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.checkpoints.len() == self.capacity {
+            self.checkpoints.pop_front();
+        }
+        self.checkpoints.push_back(world.checkpoint());
+    }
+
+    /// How many ticks `world` can be rolled back.
+    pub fn depth(&self, world: &World) -> u64 {
+        self.checkpoints.front().map_or(0, |c| world.tick_count.saturating_sub(c.tick_count))
+    }
+
+    /// Roll `world` back `ticks` ticks. The checkpoint restored and every
+    /// later one are dropped, since the ticks they followed are undone.
+    /// Returns the tick the world is back at.
+    pub fn rollback(&mut self, world: &mut World, ticks: u64) -> Result<u64, WorldgroundError> {
+        let depth = self.depth(world);
+        if ticks == 0 || ticks > depth {
+            return Err(WorldgroundError::Simulation(format!(
+                "can roll back 1-{} tick(s), not {}",
+                depth, ticks
+            )));
+        }
+        let target = world.tick_count - ticks;
+        let index = self
+            .checkpoints
+            .iter()
+            .position(|c| c.tick_count == target)
+            .ok_or_else(|| WorldgroundError::Simulation(format!("no checkpoint of tick {}", target)))?;
+        let checkpoint = self.checkpoints.drain(index..).next().expect("index is in range");
+        world.restore_checkpoint(checkpoint)?;
+        Ok(target)
+    }
+
+    /// Forget every checkpoint, e.g. when the world is replaced.
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::GenerationParams;
+    use crate::world::generation::generate_world;
+
+    fn world() -> World {
+        generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        })
+    }
+
+    /// Stand-in for a tick: change some of everything a checkpoint holds.
+    fn tick(world: &mut World) {
+        world.tick_count += 1;
+        world.tiles[0].weather.temperature += 1.0;
+        world.tiles[1].geology.sediment += 0.5;
+        world.tiles[2].scratch.insert("t".to_string(), world.tick_count as f64);
+        world.globals.insert("ticks".to_string(), world.tick_count as f64);
+        world.activity.scores = vec![world.tick_count as f32; world.tiles.len()];
+        world.observations.retain_mut(|o| {
+            o.ticks -= 1;
+            o.ticks > 0
+        });
+    }
+
+    #[test]
+    fn rollback_restores_an_earlier_tick() {
+        let mut world = world();
+        world.observations.push(Observation {
+            tile: 4,
+            temperature: Some(290.0),
+            humidity: None,
+            ticks: 4,
+            strength: 0.5,
+        });
+        let start = world.clone();
+        let mut checkpoints = Checkpoints::new(3);
+        for _ in 0..5 {
+            checkpoints.take(&world);
+            tick(&mut world);
+        }
+        assert_eq!(checkpoints.depth(&world), 3);
+        assert!(checkpoints.rollback(&mut world, 4).unwrap_err().to_string().contains("1-3"));
+
+        world.frozen_tiles.insert(7);
+        assert_eq!(checkpoints.rollback(&mut world, 2).unwrap(), 3);
+        assert_eq!(world.tick_count, 3);
+        assert_eq!(world.tiles[0].weather.temperature, start.tiles[0].weather.temperature + 3.0);
+        assert_eq!(world.tiles[1].geology.sediment, start.tiles[1].geology.sediment + 1.5);
+        assert_eq!(world.tiles[2].scratch["t"], 3.0);
+        assert_eq!(world.globals["ticks"], 3.0);
+        assert_eq!(world.activity.scores[0], 3.0);
+        assert_eq!(world.observations[0].ticks, 1);
+        // User edits survive
+        assert!(world.frozen_tiles.contains(&7));
+        // Only the tick before the one restored is left
+        assert_eq!(checkpoints.depth(&world), 1);
+        assert_eq!(checkpoints.rollback(&mut world, 1).unwrap(), 2);
+        assert_eq!(checkpoints.depth(&world), 0);
+    }
+
+    #[test]
+    fn state_hash_follows_what_ticks_change() {
+        let mut world = world();
+        let start = world.state_hash();
+        assert_eq!(world.clone().state_hash(), start);
+        world.frozen_tiles.insert(7);
+        assert_eq!(world.state_hash(), start);
+        tick(&mut world);
+        let after = world.state_hash();
+        assert_ne!(after, start);
+        world.tiles[1].geology.sediment += f32::EPSILON * 64.0;
+        assert_ne!(world.state_hash(), after);
+        let after = world.state_hash();
+        world.activity.scores[0] += 1.0;
+        assert_ne!(world.state_hash(), after);
+    }
+
+    #[test]
+    fn no_capacity_keeps_nothing() {
+        let mut world = world();
+        let mut checkpoints = Checkpoints::new(0);
+        checkpoints.take(&world);
+        tick(&mut world);
+        assert_eq!(checkpoints.depth(&world), 0);
+        assert!(checkpoints.rollback(&mut world, 1).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::world::Tile;
+
+/// Solar constant at the top of the atmosphere (W/m²).
+const SOLAR_CONSTANT: f64 = 1361.0;
+/// Fraction of top-of-atmosphere sunlight reaching the ground under a clear sky.
+const CLEAR_SKY_TRANSMISSIVITY: f64 = 0.75;
+/// Fraction of sunlight blocked by the thickest dust or ash.
+const FULL_DUST_DIMMING: f64 = 0.5;
+/// Specific gas constant of dry air (J/(kg·K)).
+const DRY_AIR_GAS_CONSTANT: f64 = 287.05;
+/// Header row of `EnergyPotential::to_csv`.
+pub const CSV_HEADER: &str = "tile_id,latitude,longitude,terrain,wind_power_w_m2,insolation_w_m2\n";
+
+/// Rolling-mean wind and solar resource per tile, for siting wind farms and
+/// solar plants without replaying the weather.
+///
+/// Updated at the end of every tick and saved with the world. Each value is
+/// a running mean over the last year (four seasons) of ticks; until a full
+/// year has passed it is the plain mean of every tick so far. Both vectors
+/// are indexed by tile and reset when the tile count changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergyPotential {
+    /// Ticks folded into the means so far, capped at the window length
+    pub samples: u32,
+    /// Mean wind power density (W/m²)
+    pub wind_power: Vec<f32>,
+    /// Mean surface solar irradiance (W/m²)
+    pub insolation: Vec<f32>,
+}
+
+/// Wind power density (W/m²) for a tile's current wind and surface air
+/// density, which thins with elevation.
+pub fn wind_power_density(tile: &Tile) -> f64 {
+    let w = &tile.weather;
+    let density = (w.station_pressure as f64 * 100.0) / (DRY_AIR_GAS_CONSTANT * (w.temperature as f64).max(150.0));
+    0.5 * density * (w.wind_speed.max(0.0) as f64).powi(3)
+}
+
+/// Daily-mean surface irradiance (W/m²) at a latitude for a solar
+/// declination and cloud cover, both in degrees / 0.0-1.0.
+pub fn surface_insolation(latitude: f64, declination: f64, cloud_cover: f64) -> f64 {
+    let (phi, delta) = (latitude.to_radians(), declination.to_radians());
+    // Hour angle of sunset; clamped for polar day and night
+    let h0 = (-phi.tan() * delta.tan()).clamp(-1.0, 1.0).acos();
+    let top = SOLAR_CONSTANT / std::f64::consts::PI
+        * (h0 * phi.sin() * delta.sin() + phi.cos() * delta.cos() * h0.sin());
+    // Kasten-Czeplak cloud attenuation
+    let clouds = 1.0 - 0.75 * cloud_cover.clamp(0.0, 1.0).powf(3.4);
+    (top * CLEAR_SKY_TRANSMISSIVITY * clouds).max(0.0)
+}
+
+impl EnergyPotential {
+    /// Fold this tick's weather into the running means. `declination` is the
+    /// solar declination in degrees and `window` the averaging length in ticks.
+    pub fn update(&mut self, tiles: &[Tile], declination: f64, window: u32) {
+        if self.wind_power.len() != tiles.len() || self.insolation.len() != tiles.len() {
+            self.samples = 0;
+            self.wind_power = vec![0.0; tiles.len()];
+            self.insolation = vec![0.0; tiles.len()];
+        }
+        self.samples = (self.samples + 1).min(window.max(1));
+        let alpha = 1.0 / self.samples as f32;
+        for (i, tile) in tiles.iter().enumerate() {
+            let wind = wind_power_density(tile) as f32;
+            let dust = 1.0 - FULL_DUST_DIMMING * (tile.weather.dust.clamp(0.0, 1.0) as f64);
+            let sun = (surface_insolation(
+                tile.climate.latitude as f64,
+                declination,
+                tile.weather.cloud_cover as f64,
+            ) * dust) as f32;
+            self.wind_power[i] += alpha * (wind - self.wind_power[i]);
+            self.insolation[i] += alpha * (sun - self.insolation[i]);
+        }
+    }
+
+    /// The means as CSV, one row per tile.
+    pub fn to_csv(&self, tiles: &[Tile]) -> String {
+        let mut out = CSV_HEADER.as_bytes().to_vec();
+        // Writing to a Vec can't fail
+        let _ = self.write_csv_rows(tiles, &mut out);
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    /// Write CSV rows for some of the world's tiles, looked up by tile ID,
+    /// so a large world can be written a chunk at a time.
+    pub fn write_csv_rows(&self, tiles: &[Tile], out: &mut impl Write) -> std::io::Result<()> {
+        for tile in tiles {
+            let i = tile.id as usize;
+            let (Some(wind), Some(sun)) = (self.wind_power.get(i), self.insolation.get(i)) else {
+                continue;
+            };
+            writeln!(
+                out,
+                "{},{:.3},{:.3},{:?},{:.2},{:.2}",
+                tile.id, tile.position.lat, tile.position.lon, tile.geology.terrain_type, wind, sun
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    fn tile(wind_speed: f32, cloud_cover: f32, latitude: f32) -> Tile {
+        let mut t = Tile::new_default(0, vec![], Position::flat(0.0, 0.0));
+        t.weather.wind_speed = wind_speed;
+        t.weather.cloud_cover = cloud_cover;
+        t.weather.temperature = 288.15;
+        t.weather.pressure = 1013.25;
+        t.weather.station_pressure = 1013.25;
+        t.climate.latitude = latitude;
+        t
+    }
+
+    #[test]
+    fn wind_power_grows_with_the_cube_of_speed() {
+        // Sea-level air is about 1.225 kg/m³: 0.5 * 1.225 * 10³ ≈ 612 W/m²
+        let p = wind_power_density(&tile(10.0, 0.0, 0.0));
+        assert!((p - 612.5).abs() < 2.0, "{}", p);
+        assert!((wind_power_density(&tile(20.0, 0.0, 0.0)) / p - 8.0).abs() < 1e-6);
+        assert_eq!(wind_power_density(&tile(0.0, 0.0, 0.0)), 0.0);
+
+        // Thinner air on a plateau carries less power at the same speed
+        let mut plateau = tile(10.0, 0.0, 0.0);
+        plateau.weather.station_pressure = 700.0;
+        assert!((wind_power_density(&plateau) / p - 700.0 / 1013.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn insolation_follows_latitude_season_and_cloud() {
+        let equinox_equator = surface_insolation(0.0, 0.0, 0.0);
+        assert!((equinox_equator - 1361.0 / std::f64::consts::PI * 0.75).abs() < 1e-6);
+        assert!(surface_insolation(60.0, 0.0, 0.0) < equinox_equator);
+        // Polar night and midnight sun
+        assert_eq!(surface_insolation(85.0, -23.44, 0.0), 0.0);
+        assert!(surface_insolation(85.0, 23.44, 0.0) > surface_insolation(85.0, 0.0, 0.0));
+        assert!(surface_insolation(0.0, 0.0, 1.0) < 0.3 * equinox_equator);
+    }
+
+    #[test]
+    fn update_keeps_a_running_mean_over_the_window() {
+        let mut energy = EnergyPotential::default();
+        energy.update(&[tile(10.0, 0.0, 0.0)], 0.0, 4);
+        energy.update(&[tile(0.0, 0.0, 0.0)], 0.0, 4);
+        // Plain mean until the window fills
+        assert_eq!(energy.samples, 2);
+        let full = wind_power_density(&tile(10.0, 0.0, 0.0)) as f32;
+        assert!((energy.wind_power[0] - full / 2.0).abs() < 1e-3);
+
+        for _ in 0..10 {
+            energy.update(&[tile(0.0, 0.0, 0.0)], 0.0, 4);
+        }
+        assert_eq!(energy.samples, 4);
+        assert!(energy.wind_power[0] < full / 20.0);
+
+        // A different tile count starts over
+        energy.update(&[tile(0.0, 0.0, 0.0), tile(0.0, 0.0, 0.0)], 0.0, 4);
+        assert_eq!(energy.samples, 1);
+        assert_eq!(energy.wind_power.len(), 2);
+        assert_eq!(energy.to_csv(&[tile(0.0, 0.0, 0.0)]).lines().count(), 2);
+
+        // Dust and ash shade the ground
+        let mut dusty = tile(0.0, 0.0, 0.0);
+        dusty.weather.dust = 1.0;
+        let mut hazy = EnergyPotential::default();
+        hazy.update(&[dusty], 0.0, 4);
+        assert!((hazy.insolation[0] - 0.5 * surface_insolation(0.0, 0.0, 0.0) as f32).abs() < 1e-3);
+    }
+}
@@ -5,17 +5,80 @@ use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use uuid::Uuid;
 
-use crate::config::generation::GenerationParams;
+use crate::config::generation::{ClimateOverride, GenerationParams, NoiseConfig};
+use crate::error::WorldgroundError;
+use crate::world::generator::{GeneratorRegistry, PROCEDURAL};
+use crate::world::heightmap::Heightmap;
+use crate::world::terrain::{coastal_terrain, reclassify_coasts};
 use crate::world::tile::*;
-use crate::world::topology::{generate_flat_hex_grid, generate_geodesic_grid, grid_dimensions};
-use crate::world::weather_systems::MacroWeatherState;
+use crate::world::topology::{
+    generate_bounded_flat_hex_grid, generate_flat_hex_grid, generate_geodesic_grid, generate_wrapping_hex_grid,
+    geodesic_tile_count, grid_dimensions, nearest_geodesic_level,
+};
+use crate::world::weather_systems::{MacroWeatherState, SpaceWeatherState};
 use crate::world::World;
 
+/// The grid a world's parameters give, worked out without generating it.
+/// Flat grids round `tile_count` up to whole rows and columns; geodesic
+/// grids ignore it and follow `subdivision_level`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridSize {
+    /// A flat hex grid or cylinder of `width` columns by `height` rows.
+    Flat { width: u32, height: u32 },
+    /// An icosphere at a subdivision level.
+    Geodesic { level: u32 },
+}
+
+impl GridSize {
+    pub fn of(params: &GenerationParams) -> Self {
+        if params.topology.is_geodesic() {
+            GridSize::Geodesic { level: params.topology.subdivision_level }
+        } else {
+            let (width, height) = grid_dimensions(params.tile_count);
+            GridSize::Flat { width, height }
+        }
+    }
+
+    /// Number of tiles the grid has.
+    pub fn tile_count(self) -> u32 {
+        match self {
+            GridSize::Flat { width, height } => width * height,
+            GridSize::Geodesic { level } => geodesic_tile_count(level),
+        }
+    }
+}
+
 /// Generate a new world from the given parameters.
 ///
 /// If `params.seed` is 0, a random seed is chosen. The actual seed used
 /// is stored in the returned World's `generation_params` for reproducibility.
+///
+/// # Panics
+/// Panics if the parameters name a heightmap that can't be read; use
+/// [`try_generate_world`] to get the error instead.
 pub fn generate_world(params: &GenerationParams) -> World {
+    try_generate_world(params).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Generate a new world with the generator `params.generator` names, or say
+/// why it can't be generated.
+pub fn try_generate_world(params: &GenerationParams) -> Result<World, WorldgroundError> {
+    GeneratorRegistry::default().generate(params)
+}
+
+/// A small seeded world for tests of the simulation's passes.
+#[cfg(test)]
+pub(crate) fn test_world(tile_count: u32) -> World {
+    generate_world(&GenerationParams { seed: 42, tile_count, ocean_ratio: 0.3, ..Default::default() })
+}
+
+/// The built-in procedural generator, or why its heightmap can't be read.
+pub(crate) fn procedural_world(params: &GenerationParams) -> Result<World, WorldgroundError> {
+    let heightmap = params.heightmap_path().map(Heightmap::load).transpose()?;
+    Ok(build_world(params, heightmap.as_ref()))
+}
+
+fn build_world(params: &GenerationParams, heightmap: Option<&Heightmap>) -> World {
     let seed = if params.seed == 0 {
         rand::thread_rng().r#gen()
     } else {
@@ -27,29 +90,51 @@ pub fn generate_world(params: &GenerationParams) -> World {
     };
     let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
-    let (mut tiles, topology_type) = if params.topology.is_geodesic() {
-        let tiles = generate_geodesic_grid(params.topology.subdivision_level);
-        (tiles, TopologyType::Geodesic)
-    } else {
-        let (width, height) = grid_dimensions(params.tile_count);
-        let tiles = generate_flat_hex_grid(width, height);
-        (tiles, TopologyType::FlatHex)
+    let size = GridSize::of(params);
+    let (mut tiles, topology_type) = match size {
+        GridSize::Geodesic { level } => (generate_geodesic_grid(level), TopologyType::Geodesic),
+        GridSize::Flat { width, height } => {
+            let tiles = if params.topology.is_wrapping_hex() {
+                generate_wrapping_hex_grid(width, height)
+            } else if params.topology.wraps() {
+                generate_flat_hex_grid(width, height)
+            } else {
+                generate_bounded_flat_hex_grid(width, height)
+            };
+            (tiles, TopologyType::FlatHex)
+        }
     };
     let actual_count = tiles.len() as u32;
 
     let is_geodesic = topology_type == TopologyType::Geodesic;
-    generate_elevation(&mut tiles, seed as u32, params.elevation_roughness, is_geodesic);
+    let cylinder_width = match size {
+        GridSize::Flat { width, .. } if params.topology.is_wrapping_hex() => Some(width),
+        _ => None,
+    };
+    if let (Some(width), GridSize::Flat { height, .. }) = (cylinder_width, size) {
+        assign_cylinder_coordinates(&mut tiles, width, height);
+    }
+    match heightmap {
+        Some(heightmap) => heightmap_elevation(&mut tiles, heightmap, is_geodesic || cylinder_width.is_some()),
+        None => {
+            let noise = ElevationNoise::new(seed as u32, &params.noise);
+            generate_elevation(&mut tiles, &noise, params.elevation_roughness, is_geodesic, cylinder_width)
+        }
+    }
     assign_terrain_types(&mut tiles, params.ocean_ratio, params.mountain_ratio);
-    if is_geodesic {
-        // Geodesic tiles already have lat/lon from Phase 1; assign climate from those.
-        assign_climate_from_lat(&mut tiles, params.climate_bands);
-    } else {
-        let (_, height) = grid_dimensions(params.tile_count);
-        assign_climate(&mut tiles, height, params.climate_bands);
+    match size {
+        // Geodesic and cylinder tiles already have lat/lon; assign climate from those.
+        GridSize::Flat { height, .. } if cylinder_width.is_none() => {
+            assign_climate(&mut tiles, height, params.climate_bands);
+        }
+        _ => assign_climate_from_lat(&mut tiles, params.climate_bands),
     }
+    apply_climate_overrides(&mut tiles, &params.climate_overrides);
     assign_soil(&mut tiles, seed.wrapping_add(1) as u32, is_geodesic);
     assign_initial_biomes(&mut tiles, params.initial_biome_maturity);
-    scatter_resources(&mut tiles, &mut rng, params.resource_density);
+    // Deposit quality draws from its own stream so the rest of generation is unchanged
+    let mut quality_rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(2));
+    scatter_resources(&mut tiles, &mut rng, &mut quality_rng, params.resource_density);
     initialize_weather(&mut tiles, &mut rng);
     initialize_conditions(&mut tiles);
 
@@ -74,9 +159,90 @@ pub fn generate_world(params: &GenerationParams) -> World {
         snapshot_path: None,
         tiles,
         macro_weather: MacroWeatherState::with_seed(seed),
+        frozen_tiles: Default::default(),
+        inert_tiles: Default::default(),
+        globals: Default::default(),
+        records: Default::default(),
+        thermostat: Default::default(),
+        space_weather: SpaceWeatherState::with_seed(seed),
+        energy: Default::default(),
+        bookmarks: Default::default(),
+        ocean: Default::default(),
+        sea_level: Default::default(),
+        phase_inputs: Default::default(),
+        rule_pack: None,
+        observations: Vec::new(),
+        activity: Default::default(),
     }
 }
 
+/// Rough bytes a tile takes in memory: the tile itself, plus its neighbor
+/// list (~6 * 4 bytes) and resources (~3 entries * ~80 bytes) on the heap.
+pub fn estimated_tile_bytes() -> usize {
+    std::mem::size_of::<Tile>() + 24 + 240
+}
+
+/// Wall-clock tick time per tile with the built-in rules, as measured on a
+/// 10-core machine in a release build (16,256 tiles at ~1 tick a second).
+const MEASURED_MS_PER_TILE: f64 = 0.06;
+
+/// Print what a world's parameters would generate, without generating it:
+/// its grid, tile count, memory and the cost of a tick.
+pub fn print_world_preview(params: &GenerationParams) {
+    let size = GridSize::of(params);
+    let tiles = size.tile_count();
+    println!("=== World Preview ===");
+    if params.generator != PROCEDURAL {
+        println!("Generator: {} (the grid below is what procedural generation would give)", params.generator);
+    }
+    match size {
+        GridSize::Flat { width, height } => {
+            println!("Topology: {}, {} x {} grid", params.topology.mode, width, height);
+            if tiles != params.tile_count {
+                println!("Tiles: {} (tile_count {} rounded up to whole rows and columns)", tiles, params.tile_count);
+            } else {
+                println!("Tiles: {}", tiles);
+            }
+        }
+        GridSize::Geodesic { level } => {
+            println!("Topology: geodesic, subdivision_level {}", level);
+            let nearest = nearest_geodesic_level(params.tile_count);
+            if nearest != level {
+                println!(
+                    "Tiles: {} (tile_count {} is ignored; subdivision_level {} would give the nearest, {})",
+                    tiles,
+                    params.tile_count,
+                    nearest,
+                    geodesic_tile_count(nearest)
+                );
+            } else {
+                println!("Tiles: {}", tiles);
+            }
+        }
+    }
+
+    match params.heightmap_path() {
+        Some(path) => println!("Elevation: heightmap {}", path.display()),
+        None => {
+            let noise = &params.noise;
+            let octaves = if noise.octaves == 1 { "octave" } else { "octaves" };
+            let warp = if noise.warp > 0.0 { format!(", warp {}", noise.warp) } else { String::new() };
+            println!("Elevation: noise, {} {} {}{}", noise.octaves, noise.style, octaves, warp);
+        }
+    }
+
+    // Tiles are double-buffered while a phase runs
+    let mb = (estimated_tile_bytes() * tiles as usize) as f64 / 1024.0 / 1024.0;
+    println!("Memory: ~{:.1} MB of tiles, ~{:.1} MB while a tick runs", mb, mb * 2.0);
+    let tick_secs = tiles as f64 * MEASURED_MS_PER_TILE / 1000.0;
+    println!(
+        "Tick cost: ~{:.2} s, ~{:.1} ticks/sec (at {} ms per tile on 10 cores; scale by core count and rules)",
+        tick_secs,
+        1.0 / tick_secs,
+        MEASURED_MS_PER_TILE
+    );
+}
+
 /// Print a summary of the generated world.
 pub fn print_world_summary(world: &World) {
     println!("=== World Summary ===");
@@ -150,33 +316,257 @@ pub fn print_world_summary(world: &World) {
     }
 }
 
+// --- Splicing regions between worlds ---
+
+/// A `MIN,MAX` pair of degrees, as given on the command line.
+pub fn parse_range(s: &str) -> Result<(f64, f64), String> {
+    let bad = || format!("expected MIN,MAX in degrees, got '{}'", s);
+    let (min, max) = s.split_once(',').ok_or_else(bad)?;
+    let min = min.trim().parse().map_err(|_| bad())?;
+    let max = max.trim().parse().map_err(|_| bad())?;
+    Ok((min, max))
+}
+
+/// Tiles whose latitude lies in `lat` and longitude in `lon`, both in
+/// degrees and inclusive. A longitude range whose start is east of its end
+/// wraps across the antimeridian.
+pub fn tiles_in_box(world: &World, lat: (f64, f64), lon: (f64, f64)) -> Vec<u32> {
+    let in_lon = |l: f64| {
+        if lon.0 <= lon.1 {
+            (lon.0..=lon.1).contains(&l)
+        } else {
+            l >= lon.0 || l <= lon.1
+        }
+    };
+    world
+        .tiles
+        .iter()
+        .filter(|t| (lat.0..=lat.1).contains(&t.position.lat) && in_lon(t.position.lon))
+        .map(|t| t.id)
+        .collect()
+}
+
+/// Pair each of `ids` with the tile `cols` columns east and `rows` rows
+/// south of it on a flat hex grid, as (source, destination). A wrapping grid
+/// wraps the shift, a wrapping_hex grid only east-west; a tile shifted off
+/// a hard edge is an error. Rows alternate their offset, so an odd row shift skews the region
+/// by half a tile.
+pub fn shift_on_grid(world: &World, ids: &[u32], cols: i32, rows: i32) -> Result<Vec<(u32, u32)>, String> {
+    if world.topology_type != TopologyType::FlatHex {
+        return Err("Only regions of flat hex worlds can be shifted".to_string());
+    }
+    let (width, height) = grid_dimensions(world.generation_params.tile_count);
+    if (width * height) as usize != world.tiles.len() {
+        return Err(format!(
+            "World has {} tiles, not the {}x{} grid its generation parameters give",
+            world.tiles.len(),
+            width,
+            height
+        ));
+    }
+    let wraps = world.generation_params.topology.wraps();
+    let wraps_columns = wraps || world.generation_params.topology.is_wrapping_hex();
+    ids.iter()
+        .map(|&id| {
+            if id >= width * height {
+                return Err(format!("No tile {}", id));
+            }
+            let mut col = (id % width) as i64 + cols as i64;
+            let row = (id / width) as i64 + rows as i64;
+            if wraps_columns {
+                col = col.rem_euclid(width as i64);
+            }
+            let (col, row) = if wraps {
+                (col, row.rem_euclid(height as i64))
+            } else if (0..width as i64).contains(&col) && (0..height as i64).contains(&row) {
+                (col, row)
+            } else {
+                return Err(format!("Tile {} would be shifted off the grid", id));
+            };
+            Ok((id, (row * width as i64 + col) as u32))
+        })
+        .collect()
+}
+
+/// Copy tiles of `from` into `into`, given as (source ID, destination ID)
+/// pairs. The worlds must share a topology and tile count. A spliced tile
+/// takes every layer of its source but keeps the destination's ID, position
+/// and neighbor links, so the grid stays whole wherever the region lands.
+///
+/// Climate is rescaled to where the tile lands: it takes the destination's
+/// latitude and zone, and keeps how much warmer and wetter than its own
+/// zone it was. Its weather temperature moves with its base temperature.
+/// Coasts in and around the region are then reclassified. Returns the
+/// destination IDs spliced, in order.
+pub fn splice_tiles(into: &mut World, from: &World, pairs: &[(u32, u32)]) -> Result<Vec<u32>, WorldgroundError> {
+    if from.topology_type != into.topology_type || from.tiles.len() != into.tiles.len() {
+        return Err(WorldgroundError::Simulation(format!(
+            "Worlds differ in topology: {} {:?} tiles against {} {:?} tiles",
+            from.tiles.len(),
+            from.topology_type,
+            into.tiles.len(),
+            into.topology_type
+        )));
+    }
+    let mut spliced = std::collections::BTreeSet::new();
+    for &(src, dst) in pairs {
+        let source = from
+            .tiles
+            .get(src as usize)
+            .ok_or_else(|| WorldgroundError::Simulation(format!("No tile {}", src)))?;
+        if !spliced.insert(dst) {
+            return Err(WorldgroundError::Simulation(format!("Tile {} would be spliced into twice", dst)));
+        }
+        let target = into
+            .tiles
+            .get_mut(dst as usize)
+            .ok_or_else(|| WorldgroundError::Simulation(format!("No tile {}", dst)))?;
+
+        let mut tile = source.clone();
+        tile.id = target.id;
+        tile.position = target.position;
+        tile.neighbors = std::mem::take(&mut target.neighbors);
+
+        let warmth = source.climate.base_temperature
+            - zone_base_temperature(source.climate.zone, source.geology.elevation);
+        let wetness = source.climate.base_precipitation / zone_base_precipitation(source.climate.zone);
+        tile.climate.zone = target.climate.zone;
+        tile.climate.latitude = target.climate.latitude;
+        tile.climate.base_temperature = zone_base_temperature(tile.climate.zone, tile.geology.elevation) + warmth;
+        tile.climate.base_precipitation = zone_base_precipitation(tile.climate.zone) * wetness;
+        tile.weather.temperature += tile.climate.base_temperature - source.climate.base_temperature;
+
+        *target = tile;
+    }
+    let spliced: Vec<u32> = spliced.into_iter().collect();
+    reclassify_coasts(&mut into.tiles, &spliced);
+    Ok(spliced)
+}
+
 // --- Internal generation functions ---
 
-fn generate_elevation(tiles: &mut [Tile], seed: u32, roughness: f32, is_geodesic: bool) {
-    let perlin = Perlin::new(seed);
+/// Seed offset of the noise fields that warp the sampling point, so they
+/// don't repeat the elevation octaves.
+const WARP_SEED: u32 = 0x5741_5250;
+
+/// Elevation noise as `NoiseConfig` layers it, about -1.0 to 1.0.
+struct ElevationNoise<'a> {
+    config: &'a NoiseConfig,
+    /// One field per octave; the first is the plain noise worlds always used
+    octaves: Vec<Perlin>,
+    /// One field per coordinate, to displace the sampling point
+    warp: [Perlin; 3],
+}
+
+impl<'a> ElevationNoise<'a> {
+    fn new(seed: u32, config: &'a NoiseConfig) -> Self {
+        ElevationNoise {
+            config,
+            octaves: (0..config.octaves).map(|i| Perlin::new(seed.wrapping_add(i))).collect(),
+            warp: std::array::from_fn(|k| Perlin::new(seed.wrapping_add(WARP_SEED).wrapping_add(k as u32))),
+        }
+    }
+
+    /// Noise at a 2D (flat grid) or 3D (sphere, cylinder) point.
+    fn get<const N: usize>(&self, mut point: [f64; N]) -> f64
+    where
+        Perlin: NoiseFn<f64, N>,
+    {
+        if self.config.warp > 0.0 {
+            let offsets: [f64; N] = std::array::from_fn(|k| self.warp[k].get(point));
+            for (c, offset) in point.iter_mut().zip(offsets) {
+                *c += self.config.warp as f64 * offset;
+            }
+        }
+        let (mut sum, mut total, mut amplitude, mut frequency) = (0.0, 0.0, 1.0, 1.0);
+        for perlin in &self.octaves {
+            let n = perlin.get(point.map(|c| c * frequency));
+            sum += amplitude
+                * match self.config.style.as_str() {
+                    "ridged" => 1.0 - 2.0 * n.abs(),
+                    "billow" => 2.0 * n.abs() - 1.0,
+                    _ => n,
+                };
+            total += amplitude;
+            amplitude *= self.config.persistence as f64;
+            frequency *= self.config.lacunarity as f64;
+        }
+        sum / total
+    }
+}
+
+/// `cylinder_width` is the grid width of a wrapping_hex world, whose noise is
+/// sampled around a cylinder so land runs on unbroken across the seam.
+fn generate_elevation(
+    tiles: &mut [Tile],
+    noise: &ElevationNoise,
+    roughness: f32,
+    is_geodesic: bool,
+    cylinder_width: Option<u32>,
+) {
     if is_geodesic {
-        // 3D Perlin noise sampled at unit sphere positions
+        // 3D noise sampled at unit sphere positions
         let scale = 3.0;
         for tile in tiles.iter_mut() {
-            let e = perlin.get([
+            let e = noise.get([
                 tile.position.x * scale,
                 tile.position.y * scale,
                 tile.position.z * scale,
             ]) as f32;
             tile.geology.elevation = (e * roughness).clamp(-1.0, 1.0);
         }
+    } else if let Some(width) = cylinder_width {
+        // 3D noise on a cylinder whose circumference is the grid width
+        let scale = 0.08;
+        let radius = 3.0_f64.sqrt() * width as f64 / std::f64::consts::TAU;
+        for tile in tiles.iter_mut() {
+            let angle = tile.position.x / radius;
+            let e = noise.get([
+                radius * angle.cos() * scale,
+                radius * angle.sin() * scale,
+                tile.position.y * scale,
+            ]) as f32;
+            tile.geology.elevation = (e * roughness).clamp(-1.0, 1.0);
+        }
     } else {
-        // 2D Perlin noise for flat hex grid
+        // 2D noise for flat hex grid
         let scale = 0.08;
         for tile in tiles.iter_mut() {
             let nx = tile.position.x * scale;
             let ny = tile.position.y * scale;
-            let e = perlin.get([nx, ny]) as f32;
+            let e = noise.get([nx, ny]) as f32;
             tile.geology.elevation = (e * roughness).clamp(-1.0, 1.0);
         }
     }
 }
 
+/// Take elevation from a heightmap in place of noise, black at -1 and white
+/// at 1. Tiles with lat/lon read it as an equirectangular map; flat grids
+/// stretch it over their extent with the last row (north) at the top.
+fn heightmap_elevation(tiles: &mut [Tile], heightmap: &Heightmap, by_lat_lon: bool) {
+    let brightness: Vec<f32> = if by_lat_lon {
+        tiles.iter().map(|t| heightmap.at_lat_lon(t.position.lat, t.position.lon)).collect()
+    } else {
+        let bounds = |coord: fn(&Tile) -> f64| {
+            tiles.iter().map(coord).fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(c), hi.max(c)))
+        };
+        let (min_x, max_x) = bounds(|t| t.position.x);
+        let (min_y, max_y) = bounds(|t| t.position.y);
+        let across = |c: f64, lo: f64, hi: f64| if hi > lo { (c - lo) / (hi - lo) } else { 0.5 };
+        tiles
+            .iter()
+            .map(|t| {
+                let u = across(t.position.x, min_x, max_x);
+                let v = 1.0 - across(t.position.y, min_y, max_y);
+                heightmap.sample(u, v, false)
+            })
+            .collect()
+    };
+    for (tile, b) in tiles.iter_mut().zip(brightness) {
+        tile.geology.elevation = (b * 2.0 - 1.0).clamp(-1.0, 1.0);
+    }
+}
+
 fn assign_terrain_types(tiles: &mut [Tile], ocean_ratio: f32, mountain_ratio: f32) {
     // Sort tile indices by elevation to assign types by percentile
     let mut indices: Vec<usize> = (0..tiles.len()).collect();
@@ -229,13 +619,7 @@ fn assign_terrain_types(tiles: &mut [Tile], ocean_ratio: f32, mountain_ratio: f3
         .enumerate()
         .filter(|(_, t)| t.geology.terrain_type != TerrainType::Ocean)
         .filter(|(_, t)| t.neighbors.iter().any(|n| ocean_set.contains(n)))
-        .map(|(i, t)| {
-            let new_type = match t.geology.terrain_type {
-                TerrainType::Mountains | TerrainType::Hills => TerrainType::Cliffs,
-                _ => TerrainType::Coast,
-            };
-            (i, new_type)
-        })
+        .map(|(i, t)| (i, coastal_terrain(t.geology.terrain_type, true)))
         .collect();
 
     for (idx, terrain) in to_update {
@@ -263,6 +647,29 @@ fn assign_terrain_types(tiles: &mut [Tile], ocean_ratio: f32, mountain_ratio: f3
     }
 }
 
+/// Base temperature (K) of a tile in `zone` at `elevation`.
+fn zone_base_temperature(zone: ClimateZone, elevation: f32) -> f32 {
+    let zone_temp = match zone {
+        ClimateZone::Polar => 250.0,
+        ClimateZone::Subpolar => 265.0,
+        ClimateZone::Temperate => 283.0,
+        ClimateZone::Subtropical => 295.0,
+        ClimateZone::Tropical => 300.0,
+    };
+    // Elevation lapse: higher = colder
+    zone_temp - elevation.max(0.0) * 20.0
+}
+
+fn zone_base_precipitation(zone: ClimateZone) -> f32 {
+    match zone {
+        ClimateZone::Polar => 0.2,
+        ClimateZone::Subpolar => 0.3,
+        ClimateZone::Temperate => 0.5,
+        ClimateZone::Subtropical => 0.4,
+        ClimateZone::Tropical => 0.7,
+    }
+}
+
 /// Assign climate for geodesic tiles using lat/lon already set in position.
 fn assign_climate_from_lat(tiles: &mut [Tile], use_bands: bool) {
     for tile in tiles.iter_mut() {
@@ -284,23 +691,45 @@ fn assign_climate_from_lat(tiles: &mut [Tile], use_bands: bool) {
             };
         }
 
-        let zone_temp = match tile.climate.zone {
-            ClimateZone::Polar => 250.0,
-            ClimateZone::Subpolar => 265.0,
-            ClimateZone::Temperate => 283.0,
-            ClimateZone::Subtropical => 295.0,
-            ClimateZone::Tropical => 300.0,
-        };
-        let elevation_effect = tile.geology.elevation.max(0.0) * 20.0;
-        tile.climate.base_temperature = zone_temp - elevation_effect;
-
-        tile.climate.base_precipitation = match tile.climate.zone {
-            ClimateZone::Polar => 0.2,
-            ClimateZone::Subpolar => 0.3,
-            ClimateZone::Temperate => 0.5,
-            ClimateZone::Subtropical => 0.4,
-            ClimateZone::Tropical => 0.7,
-        };
+        tile.climate.base_temperature = zone_base_temperature(tile.climate.zone, tile.geology.elevation);
+        tile.climate.base_precipitation = zone_base_precipitation(tile.climate.zone);
+    }
+}
+
+/// Force the climate of each `[[climate_override]]` region, after the
+/// latitude bands, so initial biomes follow the forced zone. A zone brings
+/// its own base temperature and precipitation; `precipitation` replaces the
+/// latter.
+fn apply_climate_overrides(tiles: &mut [Tile], overrides: &[ClimateOverride]) {
+    for climate_override in overrides {
+        for tile in tiles.iter_mut() {
+            if !climate_override.covers(tile.id, tile.climate.latitude, tile.position.lon as f32) {
+                continue;
+            }
+            if let Some(zone) = climate_override.zone {
+                tile.climate.zone = zone;
+                tile.climate.base_temperature = zone_base_temperature(zone, tile.geology.elevation);
+                tile.climate.base_precipitation = zone_base_precipitation(zone);
+            }
+            if let Some(precipitation) = climate_override.precipitation {
+                tile.climate.base_precipitation = precipitation;
+            }
+        }
+    }
+}
+
+/// Give a cylinder's tiles lat/lon. Longitude spans exactly 360° across the
+/// grid's width, so tiles either side of the seam are as close in longitude
+/// as any other neighbors and bearings across it come out east or west.
+/// Latitude runs through row centers, stopping half a row short of each
+/// pole so no row collapses onto a single point.
+fn assign_cylinder_coordinates(tiles: &mut [Tile], width: u32, height: u32) {
+    let period = 3.0_f64.sqrt() * width as f64;
+    let row_height = 1.5_f64;
+    for tile in tiles.iter_mut() {
+        let row = (tile.position.y / row_height).round();
+        tile.position.lat = (row + 0.5) / height as f64 * 180.0 - 90.0;
+        tile.position.lon = tile.position.x / period * 360.0 - 180.0;
     }
 }
 
@@ -339,24 +768,8 @@ fn assign_climate(tiles: &mut [Tile], grid_height: u32, use_bands: bool) {
             };
         }
 
-        let zone_temp = match tile.climate.zone {
-            ClimateZone::Polar => 250.0,
-            ClimateZone::Subpolar => 265.0,
-            ClimateZone::Temperate => 283.0,
-            ClimateZone::Subtropical => 295.0,
-            ClimateZone::Tropical => 300.0,
-        };
-        // Elevation lapse: higher = colder
-        let elevation_effect = tile.geology.elevation.max(0.0) * 20.0;
-        tile.climate.base_temperature = zone_temp - elevation_effect;
-
-        tile.climate.base_precipitation = match tile.climate.zone {
-            ClimateZone::Polar => 0.2,
-            ClimateZone::Subpolar => 0.3,
-            ClimateZone::Temperate => 0.5,
-            ClimateZone::Subtropical => 0.4,
-            ClimateZone::Tropical => 0.7,
-        };
+        tile.climate.base_temperature = zone_base_temperature(tile.climate.zone, tile.geology.elevation);
+        tile.climate.base_precipitation = zone_base_precipitation(tile.climate.zone);
     }
 }
 
@@ -468,6 +881,8 @@ fn assign_initial_biomes(tiles: &mut [Tile], maturity: f32) {
             BiomeType::Wetland => 0.5,
         };
 
+        tile.biome.root_strength = tile.biome.vegetation_density;
+
         tile.biome.vegetation_health = match biome {
             BiomeType::Ocean | BiomeType::Ice => 0.0,
             _ => 0.8,
@@ -477,7 +892,16 @@ fn assign_initial_biomes(tiles: &mut [Tile], maturity: f32) {
     }
 }
 
-fn scatter_resources(tiles: &mut [Tile], rng: &mut impl Rng, density: f32) {
+/// Grade of a new deposit around a geology-dependent mean. The sum of two
+/// uniform draws keeps most deposits near the mean and a few exceptional.
+fn deposit_quality(mean: f32, rng: &mut impl Rng) -> f32 {
+    let spread = rng.gen_range(-0.25..0.25) + rng.gen_range(-0.25..0.25);
+    (mean + spread).clamp(0.0, 1.0)
+}
+
+/// Place starting deposits. Ore and stone lie underground and start
+/// undiscovered; timber and grain are in plain sight.
+fn scatter_resources(tiles: &mut [Tile], rng: &mut impl Rng, quality_rng: &mut impl Rng, density: f32) {
     for tile in tiles.iter_mut() {
         tile.resources.resources.clear();
 
@@ -485,30 +909,47 @@ fn scatter_resources(tiles: &mut [Tile], rng: &mut impl Rng, density: f32) {
             continue;
         }
 
-        if matches!(
-            tile.geology.terrain_type,
-            TerrainType::Mountains | TerrainType::Hills
-        ) {
+        let geology = &tile.geology;
+        let mountains = geology.terrain_type == TerrainType::Mountains;
+        if matches!(geology.terrain_type, TerrainType::Mountains | TerrainType::Hills) {
             if rng.r#gen::<f32>() < density * 0.5 {
+                // Richer ore where the crust is folded and stressed
+                let mean = 0.35 + 0.3 * geology.tectonic_stress.clamp(0.0, 1.0) + if mountains { 0.15 } else { 0.0 };
                 tile.resources.resources.push(ResourceDeposit {
                     resource_type: "iron".to_string(),
                     quantity: rng.gen_range(20.0..100.0),
                     max_quantity: 100.0,
                     renewal_rate: 0.0,
                     requires_biome: None,
+                    quality: deposit_quality(mean, quality_rng),
+                    discovered: false,
                 });
             }
             if rng.r#gen::<f32>() < density * 0.3 {
+                // Mountain bedrock is sounder than weathered hill stone
+                let mean = if mountains { 0.6 } else { 0.45 };
                 tile.resources.resources.push(ResourceDeposit {
                     resource_type: "stone".to_string(),
                     quantity: rng.gen_range(50.0..200.0),
                     max_quantity: 200.0,
                     renewal_rate: 0.0,
                     requires_biome: None,
+                    quality: deposit_quality(mean, quality_rng),
+                    discovered: false,
                 });
             }
         }
 
+        // Timber and grain grade with the soil they grow in
+        let soil_mean = match geology.soil_type {
+            SoilType::Loam => 0.65,
+            SoilType::Silt => 0.55,
+            SoilType::Clay => 0.45,
+            SoilType::Sand => 0.35,
+            SoilType::Rock => 0.25,
+        };
+        let drained = 0.8 + 0.2 * geology.drainage.clamp(0.0, 1.0);
+
         if matches!(
             tile.biome.biome_type,
             BiomeType::BorealForest | BiomeType::TemperateForest | BiomeType::TropicalForest
@@ -524,6 +965,8 @@ fn scatter_resources(tiles: &mut [Tile], rng: &mut impl Rng, density: f32) {
                         BiomeType::TemperateForest,
                         BiomeType::TropicalForest,
                     ]),
+                    quality: deposit_quality(soil_mean, quality_rng),
+                    discovered: true,
                 });
             }
         }
@@ -540,6 +983,8 @@ fn scatter_resources(tiles: &mut [Tile], rng: &mut impl Rng, density: f32) {
                     max_quantity: 50.0,
                     renewal_rate: 0.5,
                     requires_biome: Some(vec![BiomeType::Grassland, BiomeType::Savanna]),
+                    quality: deposit_quality(soil_mean * drained, quality_rng),
+                    discovered: true,
                 });
             }
         }
@@ -598,6 +1043,7 @@ fn initialize_conditions(tiles: &mut [Tile]) {
             0
         };
         tile.conditions.drought_days = 0;
+        tile.conditions.trafficability = tile.compute_trafficability();
         tile.conditions.fire_risk = 0.0;
     }
 }
@@ -605,19 +1051,13 @@ fn initialize_conditions(tiles: &mut [Tile]) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::generation::TopologyConfig;
+    use crate::config::generation::{NoiseConfig, TopologyConfig};
 
     fn default_params() -> GenerationParams {
         GenerationParams {
             seed: 42,
             tile_count: 1000,
-            ocean_ratio: 0.6,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
-            topology: TopologyConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -840,16 +1280,12 @@ mod tests {
         GenerationParams {
             seed: 42,
             tile_count: 1000, // ignored for geodesic; tile count comes from subdivision level
-            ocean_ratio: 0.6,
-            mountain_ratio: 0.1,
-            elevation_roughness: 0.5,
-            climate_bands: true,
-            resource_density: 0.3,
-            initial_biome_maturity: 0.5,
             topology: TopologyConfig {
                 mode: "geodesic".to_string(),
                 subdivision_level: level,
+                boundary: "wrap".to_string(),
             },
+            ..Default::default()
         }
     }
 
@@ -908,6 +1344,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn heightmap_elevation_follows_the_image() {
+        // Bright west, dark east, on an equirectangular map
+        let map = Heightmap::from_values(4, 2, vec![1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+        let mut tiles = generate_world(&geodesic_params(3)).tiles;
+        heightmap_elevation(&mut tiles, &map, true);
+        for tile in &tiles {
+            // Between the centers of the first two pixel columns, and the last two
+            if (-135.0..=-45.0).contains(&tile.position.lon) {
+                assert!(tile.geology.elevation > 0.9, "tile {} at lon {:.0}", tile.id, tile.position.lon);
+            } else if (45.0..=135.0).contains(&tile.position.lon) {
+                assert!(tile.geology.elevation < -0.9, "tile {} at lon {:.0}", tile.id, tile.position.lon);
+            }
+        }
+
+        // A flat grid stretches the map, north at the top
+        let north = Heightmap::from_values(2, 2, vec![1.0, 1.0, 0.0, 0.0]);
+        let mut tiles = generate_world(&default_params()).tiles;
+        heightmap_elevation(&mut tiles, &north, false);
+        let max_y = tiles.iter().map(|t| t.position.y).fold(f64::MIN, f64::max);
+        let top = tiles.iter().find(|t| t.position.y == max_y).unwrap();
+        let bottom = tiles.iter().find(|t| t.position.y == 0.0).unwrap();
+        assert_eq!(top.geology.elevation, 1.0);
+        assert_eq!(bottom.geology.elevation, -1.0);
+    }
+
+    #[test]
+    fn layered_noise_adds_detail_and_defaults_to_one_octave() {
+        let plain = NoiseConfig::default();
+        let noise = ElevationNoise::new(7, &plain);
+        let perlin = Perlin::new(7);
+        for point in [[0.3, 1.7, -2.2], [5.1, 0.0, 0.9]] {
+            assert_eq!(noise.get(point), perlin.get(point));
+        }
+
+        // Mean elevation step between neighbors against the spread of
+        // elevations: finer octaves make the land rougher for its relief
+        let roughness = |noise: NoiseConfig| {
+            let mut params = geodesic_params(4);
+            params.noise = noise;
+            let world = generate_world(&params);
+            let elevations: Vec<f32> = world.tiles.iter().map(|t| t.geology.elevation).collect();
+            let mean = elevations.iter().sum::<f32>() / elevations.len() as f32;
+            let spread = (elevations.iter().map(|e| (e - mean).powi(2)).sum::<f32>() / elevations.len() as f32).sqrt();
+            let steps: Vec<f32> = world
+                .tiles
+                .iter()
+                .flat_map(|t| t.neighbors.iter().map(|&n| (t.geology.elevation - elevations[n as usize]).abs()))
+                .collect();
+            steps.iter().sum::<f32>() / steps.len() as f32 / spread
+        };
+        let layered = NoiseConfig { octaves: 6, ..NoiseConfig::default() };
+        assert!(roughness(layered.clone()) > roughness(NoiseConfig::default()) * 1.1);
+
+        // Every style and the warp stay in range, and the same seed gives the same world
+        for style in ["ridged", "billow"] {
+            let mut params = geodesic_params(3);
+            params.noise = NoiseConfig { style: style.to_string(), warp: 0.8, ..layered.clone() };
+            let world = generate_world(&params);
+            assert_eq!(world.tiles, generate_world(&params).tiles);
+            assert!(world.tiles.iter().all(|t| (-1.0..=1.0).contains(&t.geology.elevation)));
+            assert_ne!(world.tiles, generate_world(&geodesic_params(3)).tiles, "{}", style);
+        }
+    }
+
+    #[test]
+    fn try_generate_world_reports_a_missing_heightmap() {
+        let mut params = default_params();
+        params.elevation_source = "heightmap".to_string();
+        params.heightmap = Some("/nonexistent/map.png".to_string());
+        let err = try_generate_world(&params).unwrap_err();
+        assert!(matches!(err, WorldgroundError::Heightmap { .. }), "{err:?}");
+        assert!(err.to_string().contains("/nonexistent/map.png"), "{}", err);
+    }
+
+    #[test]
+    fn grid_size_predicts_the_generated_tile_count() {
+        let mut params = default_params();
+        params.tile_count = 150;
+        for mode in ["flat", "wrapping_hex"] {
+            for boundary in ["wrap", "reflective"] {
+                params.topology = TopologyConfig { mode: mode.to_string(), boundary: boundary.to_string(), ..TopologyConfig::default() };
+                let size = GridSize::of(&params);
+                assert_eq!(size.tile_count(), generate_world(&params).tile_count, "{} {}", mode, boundary);
+                assert!(size.tile_count() >= 150);
+            }
+        }
+        let size = GridSize::of(&geodesic_params(2));
+        assert_eq!(size, GridSize::Geodesic { level: 2 });
+        assert_eq!(size.tile_count(), generate_world(&geodesic_params(2)).tile_count);
+    }
+
+    #[test]
+    fn climate_overrides_force_the_zone_in_their_region() {
+        use crate::config::generation::ClimateOverride;
+        let mut params = geodesic_params(3);
+        params.climate_overrides = vec![
+            // An ice cap on the equator, then a dry strip across it
+            ClimateOverride { lat: Some([-10.0, 10.0]), lon: None, tiles: None, zone: Some(ClimateZone::Polar), precipitation: None },
+            ClimateOverride { lat: None, lon: Some([0.0, 30.0]), tiles: None, zone: None, precipitation: Some(0.05) },
+        ];
+        let world = generate_world(&params);
+        let plain = generate_world(&geodesic_params(3));
+
+        for (tile, before) in world.tiles.iter().zip(&plain.tiles) {
+            let lat = tile.climate.latitude;
+            let lon = tile.position.lon;
+            if (-10.0..=10.0).contains(&lat) {
+                assert_eq!(tile.climate.zone, ClimateZone::Polar, "tile {} at lat {:.1}", tile.id, lat);
+                assert!(tile.climate.base_temperature < before.climate.base_temperature);
+            } else {
+                assert_eq!(tile.climate.zone, before.climate.zone, "tile {} outside the region", tile.id);
+            }
+            if (0.0..=30.0).contains(&lon) {
+                assert_eq!(tile.climate.base_precipitation, 0.05);
+            } else if !(-10.0..=10.0).contains(&lat) {
+                assert_eq!(tile.climate, before.climate);
+            }
+        }
+        assert!(world.tiles.iter().any(|t| t.climate.zone == ClimateZone::Polar && t.climate.latitude.abs() < 10.0));
+    }
+
     #[test]
     fn geodesic_climate_follows_latitude() {
         let world = generate_world(&geodesic_params(3));
@@ -961,4 +1519,86 @@ mod tests {
             "Resolved seed should be non-zero"
         );
     }
+
+    #[test]
+    fn splice_keeps_the_destination_grid_and_rescales_climate() {
+        let mut from = generate_world(&GenerationParams { seed: 1, ..default_params() });
+        let mut into = generate_world(&GenerationParams { seed: 2, ..default_params() });
+        // 32x32 grid: tile 3 is row 0, column 3; shift it to row 16, column 8
+        let pairs = shift_on_grid(&from, &[3], 5, 16).unwrap();
+        assert_eq!(pairs, vec![(3, 16 * 32 + 8)]);
+        let dst = pairs[0].1 as usize;
+        from.tiles[3].geology.elevation = 0.25;
+        from.tiles[3].climate.base_temperature =
+            zone_base_temperature(from.tiles[3].climate.zone, 0.25) + 3.0;
+        into.tiles[dst].climate.zone = ClimateZone::Tropical;
+        let before = into.tiles[dst].clone();
+
+        assert_eq!(splice_tiles(&mut into, &from, &pairs).unwrap(), vec![dst as u32]);
+        let tile = &into.tiles[dst];
+        assert_eq!(tile.id, before.id);
+        assert_eq!(tile.neighbors, before.neighbors);
+        assert_eq!(tile.position, before.position);
+        assert_eq!(tile.geology.elevation, 0.25);
+        assert_eq!(tile.climate.latitude, before.climate.latitude);
+        // Still 3 K warmer than its zone, now the destination's
+        assert!((tile.climate.base_temperature - (zone_base_temperature(ClimateZone::Tropical, 0.25) + 3.0)).abs() < 1e-4);
+        let moved = tile.climate.base_temperature - from.tiles[3].climate.base_temperature;
+        assert!((tile.weather.temperature - (from.tiles[3].weather.temperature + moved)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn splice_rejects_mismatched_worlds_and_bad_shifts() {
+        let flat = generate_world(&default_params());
+        let mut geodesic = generate_world(&geodesic_params(2));
+        assert!(splice_tiles(&mut geodesic, &flat, &[(0, 0)]).is_err());
+        assert!(shift_on_grid(&geodesic, &[0], 1, 0).is_err());
+
+        // Wraps on a torus, falls off a bounded grid
+        assert_eq!(shift_on_grid(&flat, &[31], 1, -2).unwrap(), vec![(31, 30 * 32)]);
+        let mut params = default_params();
+        params.topology.boundary = "reflective".to_string();
+        let bounded = generate_world(&params);
+        assert!(shift_on_grid(&bounded, &[31], 1, 0).is_err());
+        assert_eq!(shift_on_grid(&bounded, &[31], -1, 2).unwrap(), vec![(31, 2 * 32 + 30)]);
+        // A cylinder wraps east-west only
+        params.topology.mode = "wrapping_hex".to_string();
+        let cylinder = generate_world(&params);
+        assert_eq!(shift_on_grid(&cylinder, &[31], 1, 2).unwrap(), vec![(31, 2 * 32)]);
+        assert!(shift_on_grid(&cylinder, &[31], 1, -2).is_err());
+    }
+
+    #[test]
+    fn wrapping_hex_world_is_a_cylinder() {
+        let mut params = default_params();
+        params.topology.mode = "wrapping_hex".to_string();
+        let world = generate_world(&params);
+        assert_eq!(world.topology_type, TopologyType::FlatHex);
+        assert_eq!(world.tiles.len(), 32 * 32);
+
+        // Longitude spans exactly one turn, so the seam is no wider than any other step
+        let step = 360.0 / 32.0;
+        let (west, east) = (&world.tiles[5 * 32], &world.tiles[5 * 32 + 31]);
+        assert!(west.neighbors.contains(&east.id));
+        assert!(((west.position.lon + 360.0 - east.position.lon) - step).abs() < 1e-9);
+        // Rows stop short of the poles
+        assert!(world.tiles.iter().all(|t| t.position.lat.abs() < 90.0));
+        assert!(world.tiles[0].climate.latitude < -80.0);
+        assert_eq!(world.tiles[0].climate.zone, ClimateZone::Polar);
+        // Terrain runs on across the seam
+        assert!((west.geology.elevation - east.geology.elevation).abs() < 0.2);
+    }
+
+    #[test]
+    fn box_selection_wraps_across_the_antimeridian() {
+        let world = generate_world(&geodesic_params(2));
+        let ids = tiles_in_box(&world, (-30.0, 30.0), (170.0, -170.0));
+        assert!(!ids.is_empty());
+        for id in ids {
+            let p = &world.tiles[id as usize].position;
+            assert!(p.lat.abs() <= 30.0 && p.lon.abs() >= 170.0);
+        }
+        assert_eq!(parse_range("-10, 20.5"), Ok((-10.0, 20.5)));
+        assert!(parse_range("10").is_err());
+    }
 }
@@ -0,0 +1,141 @@
+//! Pluggable world generators.
+//!
+//! `generator` in worldgen.toml names the [`WorldGenerator`] that builds a
+//! world from its parameters. The procedural generator in `generation.rs` is
+//! registered as "procedural" and is the default. Embedders that build worlds
+//! some other way (tectonic simulation, imported data, scripts) implement the
+//! trait and register it in a [`GeneratorRegistry`] of their own.
+
+use std::collections::BTreeMap;
+
+use crate::config::generation::GenerationParams;
+use crate::error::WorldgroundError;
+use crate::world::generation::procedural_world;
+use crate::world::World;
+
+/// Name of the built-in procedural generator.
+pub const PROCEDURAL: &str = "procedural";
+
+/// Builds a world from generation parameters.
+pub trait WorldGenerator: Send + Sync {
+    /// The name worldgen.toml selects this generator by.
+    fn name(&self) -> &str;
+
+    /// Generate a world. Parameters the generator doesn't use can be ignored,
+    /// but the returned world's `generation_params` should record the seed
+    /// actually used, so the world can be generated again.
+    fn generate(&self, params: &GenerationParams) -> Result<World, WorldgroundError>;
+}
+
+/// Noise or heightmap elevation, latitude climate bands, soil, resources and
+/// biomes; see `generation.rs`.
+pub struct ProceduralGenerator;
+
+impl WorldGenerator for ProceduralGenerator {
+    fn name(&self) -> &str {
+        PROCEDURAL
+    }
+
+    fn generate(&self, params: &GenerationParams) -> Result<World, WorldgroundError> {
+        procedural_world(params)
+    }
+}
+
+/// World generators by name.
+pub struct GeneratorRegistry {
+    generators: BTreeMap<String, Box<dyn WorldGenerator>>,
+}
+
+impl Default for GeneratorRegistry {
+    /// A registry with the built-in procedural generator.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(ProceduralGenerator));
+        registry
+    }
+}
+
+impl GeneratorRegistry {
+    /// A registry with no generators, not even the procedural one.
+    pub fn empty() -> Self {
+        Self { generators: BTreeMap::new() }
+    }
+
+    /// Add a generator, replacing any registered under the same name.
+    pub fn register(&mut self, generator: Box<dyn WorldGenerator>) {
+        self.generators.insert(generator.name().to_string(), generator);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn WorldGenerator> {
+        self.generators.get(name).map(|g| g.as_ref())
+    }
+
+    /// Names of the registered generators, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        self.generators.keys().map(String::as_str).collect()
+    }
+
+    /// Generate a world with the generator `params.generator` names.
+    pub fn generate(&self, params: &GenerationParams) -> Result<World, WorldgroundError> {
+        let generator = self.get(&params.generator).ok_or_else(|| {
+            WorldgroundError::Config(format!(
+                "Unknown world generator '{}' (generators: {})",
+                params.generator,
+                self.names().join(", ")
+            ))
+        })?;
+        let mut world = generator.generate(params)?;
+        world.generation_params.generator = params.generator.clone();
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(generator: &str) -> GenerationParams {
+        GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            generator: generator.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Procedural generation with every tile flooded.
+    struct Drowned;
+
+    impl WorldGenerator for Drowned {
+        fn name(&self) -> &str {
+            "drowned"
+        }
+
+        fn generate(&self, params: &GenerationParams) -> Result<World, WorldgroundError> {
+            let mut world = procedural_world(params)?;
+            for tile in &mut world.tiles {
+                tile.geology.elevation = -0.5;
+            }
+            Ok(world)
+        }
+    }
+
+    #[test]
+    fn generators_are_chosen_by_name() {
+        let mut registry = GeneratorRegistry::default();
+        assert_eq!(registry.names(), vec![PROCEDURAL]);
+        assert_eq!(
+            registry.generate(&params(PROCEDURAL)).unwrap(),
+            procedural_world(&params(PROCEDURAL)).unwrap()
+        );
+
+        let err = registry.generate(&params("drowned")).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown world generator 'drowned' (generators: procedural)");
+
+        registry.register(Box::new(Drowned));
+        assert_eq!(registry.names(), vec!["drowned", PROCEDURAL]);
+        let world = registry.generate(&params("drowned")).unwrap();
+        assert!(world.tiles.iter().all(|t| t.geology.elevation < 0.0));
+        assert_eq!(world.generation_params.generator, "drowned");
+    }
+}
@@ -0,0 +1,124 @@
+//! Grayscale images as an elevation source.
+//!
+//! With `elevation_source = "heightmap"`, generation takes elevation from a
+//! grayscale PNG or TIFF instead of Perlin noise, so a world can have
+//! real-world-like continents. Geodesic worlds and cylinders read the image
+//! as an equirectangular map: its columns span longitude -180° to 180° and
+//! its rows latitude 90° (top) to -90°. Flat grids stretch it over the grid
+//! with north at the top. Black is the lowest ground and white the highest;
+//! `ocean_ratio` still decides where the coastline falls.
+
+use std::path::Path;
+
+use image::ImageError;
+
+use crate::error::WorldgroundError;
+
+/// A grayscale image's brightness, 0.0 (black) to 1.0 (white), row by row
+/// from the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    width: u32,
+    height: u32,
+    values: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Read a PNG or TIFF. Color images are read as their luminance, and
+    /// 16-bit images keep their full precision.
+    pub fn load(path: &Path) -> Result<Self, WorldgroundError> {
+        let error = |source| WorldgroundError::Heightmap { path: path.to_path_buf(), source };
+        let image = image::ImageReader::open(path)
+            .map_err(|e| error(ImageError::IoError(e)))?
+            .with_guessed_format()
+            .map_err(|e| error(ImageError::IoError(e)))?
+            .decode()
+            .map_err(error)?
+            .into_luma16();
+        let (width, height) = image.dimensions();
+        if width < 2 || height < 2 {
+            return Err(WorldgroundError::Config(format!(
+                "Heightmap {} is {}x{} pixels; it needs at least 2x2",
+                path.display(),
+                width,
+                height
+            )));
+        }
+        let values = image.into_raw().into_iter().map(|v| v as f32 / u16::MAX as f32).collect();
+        Ok(Heightmap { width, height, values })
+    }
+
+    /// A heightmap from brightness values, row by row from the top.
+    ///
+    /// # Panics
+    /// Panics if there aren't `width * height` values.
+    pub fn from_values(width: u32, height: u32, values: Vec<f32>) -> Self {
+        assert_eq!(values.len(), (width * height) as usize, "Heightmap needs width * height values");
+        Heightmap { width, height, values }
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Brightness at `u` across and `v` down the image, both 0.0-1.0,
+    /// interpolated between the four nearest pixel centers. With `wrap`,
+    /// the left and right edges meet, as longitude does.
+    pub fn sample(&self, u: f64, v: f64, wrap: bool) -> f32 {
+        let (w, h) = (self.width as f64, self.height as f64);
+        let y = (v * h - 0.5).clamp(0.0, h - 1.0);
+        let x = if wrap { (u * w - 0.5).rem_euclid(w) } else { (u * w - 0.5).clamp(0.0, w - 1.0) };
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let x1 = if wrap { (x0 + 1) % self.width } else { (x0 + 1).min(self.width - 1) };
+        let y1 = (y0 + 1).min(self.height - 1);
+        let (fx, fy) = ((x - x0 as f64) as f32, (y - y0 as f64) as f32);
+        let at = |x: u32, y: u32| self.values[(y * self.width + x) as usize];
+        let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+        let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    /// Brightness at a latitude and longitude in degrees, reading the image
+    /// as an equirectangular map.
+    pub fn at_lat_lon(&self, lat: f64, lon: f64) -> f32 {
+        self.sample((lon + 180.0) / 360.0, (90.0 - lat) / 180.0, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_interpolate_and_wrap_in_longitude() {
+        // Dark west, bright east, brighter to the south
+        let map = Heightmap::from_values(4, 2, vec![0.0, 0.2, 0.4, 0.6, 0.2, 0.4, 0.6, 0.8]);
+        assert_eq!(map.sample(0.125, 0.25, false), 0.0);
+        assert!((map.sample(0.25, 0.25, false) - 0.1).abs() < 1e-6);
+        assert!((map.sample(0.125, 0.5, false) - 0.1).abs() < 1e-6);
+        // Past the last column, wrapping blends back toward the first
+        assert!((map.sample(1.0, 0.25, true) - 0.3).abs() < 1e-6);
+        assert_eq!(map.sample(1.0, 0.25, false), 0.6);
+        assert_eq!(map.at_lat_lon(45.0, -135.0), 0.0);
+        assert_eq!(map.at_lat_lon(-45.0, 135.0), 0.8);
+    }
+
+    #[test]
+    fn load_reads_16_bit_png_and_rejects_non_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("map.png");
+        let pixels: Vec<u16> = vec![0, u16::MAX, u16::MAX / 2, 1000];
+        image::ImageBuffer::<image::Luma<u16>, _>::from_raw(2, 2, pixels).unwrap().save(&path).unwrap();
+        let map = Heightmap::load(&path).unwrap();
+        assert_eq!(map.dimensions(), (2, 2));
+        assert_eq!(map.sample(0.0, 0.0, false), 0.0);
+        assert_eq!(map.sample(1.0, 0.0, false), 1.0);
+        assert!((map.sample(0.25, 0.75, false) - 0.5).abs() < 1e-4);
+
+        let text = dir.path().join("map.txt");
+        std::fs::write(&text, "not an image").unwrap();
+        let err = Heightmap::load(&text).unwrap_err();
+        assert!(matches!(err, WorldgroundError::Heightmap { .. }), "{:?}", err);
+        assert!(err.to_string().contains("Cannot read heightmap"));
+    }
+}
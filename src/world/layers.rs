@@ -0,0 +1,199 @@
+//! Per-tile layers split out of the world, column by column.
+//!
+//! Tiles keep all their layers together, which is what rules want. Diffs,
+//! layer snapshots and exports only need some layers of every tile, so they
+//! capture those as arrays in tile order and work from the arrays. Tick
+//! diffs and layer snapshots share this one path, so what counts as a
+//! change, and what a layer holds, is decided in one place.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::WorldgroundError;
+use crate::world::tile::{BiomeLayer, ConditionsLayer, ResourceLayer, WeatherLayer};
+use crate::world::{Tile, World};
+
+/// A per-tile layer that changes as the world runs (a wire protocol type).
+pub use worldground_protocol::DiffLayer;
+use worldground_protocol::TileChange;
+
+/// The layers every tick diff carries; scratch is opt-in.
+pub const DIFF_LAYERS: [DiffLayer; 4] =
+    [DiffLayer::Weather, DiffLayer::Conditions, DiffLayer::Biome, DiffLayer::Resources];
+
+/// The layer named `name`, spelled as in the protocol ("weather", "biome", ...).
+pub fn parse_layer(name: &str) -> Result<DiffLayer, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).map_err(|_| {
+        format!("unknown layer '{}' (expected weather, conditions, biome, resources or scratch)", name)
+    })
+}
+
+/// Some layers of every tile, one array per layer in tile order. Layers
+/// that were not captured are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayerArrays {
+    pub weather: Option<Vec<WeatherLayer>>,
+    pub conditions: Option<Vec<ConditionsLayer>>,
+    pub biome: Option<Vec<BiomeLayer>>,
+    pub resources: Option<Vec<ResourceLayer>>,
+    pub scratch: Option<Vec<BTreeMap<String, f64>>>,
+}
+
+impl LayerArrays {
+    /// Copy `layers` out of each tile.
+    pub fn capture(tiles: &[Tile], layers: &[DiffLayer]) -> Self {
+        let column = |layer: DiffLayer| layers.contains(&layer);
+        LayerArrays {
+            weather: column(DiffLayer::Weather).then(|| tiles.iter().map(|t| t.weather.clone()).collect()),
+            conditions: column(DiffLayer::Conditions).then(|| tiles.iter().map(|t| t.conditions.clone()).collect()),
+            biome: column(DiffLayer::Biome).then(|| tiles.iter().map(|t| t.biome.clone()).collect()),
+            resources: column(DiffLayer::Resources).then(|| tiles.iter().map(|t| t.resources.clone()).collect()),
+            scratch: column(DiffLayer::Scratch).then(|| tiles.iter().map(|t| t.scratch.clone()).collect()),
+        }
+    }
+
+    /// The captured layers.
+    pub fn layers(&self) -> Vec<DiffLayer> {
+        [
+            (DiffLayer::Weather, self.weather.is_some()),
+            (DiffLayer::Conditions, self.conditions.is_some()),
+            (DiffLayer::Biome, self.biome.is_some()),
+            (DiffLayer::Resources, self.resources.is_some()),
+            (DiffLayer::Scratch, self.scratch.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(layer, captured)| captured.then_some(layer))
+        .collect()
+    }
+
+    /// Number of tiles captured, or `None` if the arrays disagree.
+    pub fn tile_count(&self) -> Option<usize> {
+        let lens = [
+            self.weather.as_ref().map(Vec::len),
+            self.conditions.as_ref().map(Vec::len),
+            self.biome.as_ref().map(Vec::len),
+            self.resources.as_ref().map(Vec::len),
+            self.scratch.as_ref().map(Vec::len),
+        ];
+        let mut lens = lens.into_iter().flatten();
+        let first = lens.next().unwrap_or(0);
+        lens.all(|len| len == first).then_some(first)
+    }
+
+    /// Write the captured layers back onto `tiles`, leaving the others alone.
+    pub fn restore(self, tiles: &mut [Tile]) -> Result<(), WorldgroundError> {
+        match self.tile_count() {
+            Some(n) if n == tiles.len() || self.layers().is_empty() => {}
+            Some(n) => {
+                return Err(WorldgroundError::Simulation(format!(
+                    "layers cover {} tiles, the world has {}",
+                    n,
+                    tiles.len()
+                )));
+            }
+            None => return Err(WorldgroundError::Simulation("layer arrays have different lengths".to_string())),
+        }
+        fn put<T>(column: Option<Vec<T>>, tiles: &mut [Tile], field: impl Fn(&mut Tile) -> &mut T) {
+            for (tile, value) in tiles.iter_mut().zip(column.into_iter().flatten()) {
+                *field(tile) = value;
+            }
+        }
+        put(self.weather, tiles, |t| &mut t.weather);
+        put(self.conditions, tiles, |t| &mut t.conditions);
+        put(self.biome, tiles, |t| &mut t.biome);
+        put(self.resources, tiles, |t| &mut t.resources);
+        put(self.scratch, tiles, |t| &mut t.scratch);
+        Ok(())
+    }
+
+    /// How `tile`, at `index` in tile order, differs from its captured
+    /// layers, or `None` if none of them changed. Resources are sent as
+    /// only the deposits that have been discovered.
+    pub fn change(&self, index: usize, tile: &Tile) -> Option<TileChange> {
+        fn changed<T: PartialEq + Clone>(column: &Option<Vec<T>>, index: usize, now: &T) -> Option<T> {
+            let before = column.as_ref()?.get(index)?;
+            (before != now).then(|| now.clone())
+        }
+        let change = TileChange {
+            id: tile.id,
+            weather: changed(&self.weather, index, &tile.weather),
+            conditions: changed(&self.conditions, index, &tile.conditions),
+            biome: changed(&self.biome, index, &tile.biome),
+            resources: changed(&self.resources, index, &tile.resources).map(|r| r.discovered()),
+            scratch: changed(&self.scratch, index, &tile.scratch),
+        };
+        let any = change.weather.is_some()
+            || change.conditions.is_some()
+            || change.biome.is_some()
+            || change.resources.is_some()
+            || change.scratch.is_some();
+        any.then_some(change)
+    }
+
+    /// Changes across `tiles`, in order.
+    pub fn changes(&self, tiles: &[Tile]) -> Vec<TileChange> {
+        tiles.iter().enumerate().filter_map(|(i, tile)| self.change(i, tile)).collect()
+    }
+}
+
+impl World {
+    /// Copy `layers` out of every tile.
+    pub fn capture_layers(&self, layers: &[DiffLayer]) -> LayerArrays {
+        LayerArrays::capture(&self.tiles, layers)
+    }
+
+    /// Write captured layers back onto the tiles, e.g. to replay weather
+    /// from a layer snapshot over the full world it was taken from.
+    pub fn restore_layers(&mut self, layers: LayerArrays) -> Result<(), WorldgroundError> {
+        layers.restore(&mut self.tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{BiomeType, Position};
+
+    fn tiles() -> Vec<Tile> {
+        (0..3).map(|i| Tile::new_default(i, vec![], Position::flat(i as f64, 0.0))).collect()
+    }
+
+    #[test]
+    fn captures_only_the_layers_asked_for() {
+        let arrays = LayerArrays::capture(&tiles(), &[DiffLayer::Biome, DiffLayer::Scratch]);
+        assert_eq!(arrays.layers(), vec![DiffLayer::Biome, DiffLayer::Scratch]);
+        assert!(arrays.weather.is_none());
+        assert_eq!(arrays.tile_count(), Some(3));
+        assert_eq!(parse_layer("biome"), Ok(DiffLayer::Biome));
+        assert!(parse_layer("Biome").unwrap_err().contains("unknown layer"));
+    }
+
+    #[test]
+    fn changes_cover_captured_layers_only() {
+        let mut tiles = tiles();
+        let before = LayerArrays::capture(&tiles, &DIFF_LAYERS);
+        tiles[1].weather.temperature += 5.0;
+        tiles[2].scratch.insert("x".to_string(), 1.0);
+
+        let changes = before.changes(&tiles);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].id, 1);
+        assert!(changes[0].weather.is_some());
+        assert!(changes[0].biome.is_none());
+    }
+
+    #[test]
+    fn restore_round_trips_and_checks_length() {
+        let mut tiles = tiles();
+        tiles[0].biome.biome_type = BiomeType::Desert;
+        let saved = LayerArrays::capture(&tiles, &[DiffLayer::Biome]);
+        tiles[0].biome.biome_type = BiomeType::Grassland;
+        tiles[0].weather.temperature = 200.0;
+
+        saved.clone().restore(&mut tiles).unwrap();
+        assert_eq!(tiles[0].biome.biome_type, BiomeType::Desert);
+        assert_eq!(tiles[0].weather.temperature, 200.0);
+        assert!(saved.restore(&mut tiles[..2]).unwrap_err().to_string().contains("3 tiles"));
+    }
+}
@@ -1,14 +1,36 @@
+pub mod bookmarks;
+pub mod checkpoint;
+pub mod energy;
 pub mod generation;
+pub mod generator;
+pub mod heightmap;
+pub mod layers;
+pub mod paging;
+pub mod phase_inputs;
+pub mod records;
+pub mod terrain;
 pub mod tile;
 pub mod topology;
 pub mod weather_systems;
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
 use crate::config::generation::GenerationParams;
+use crate::simulation::activity::Activity;
+use crate::simulation::assimilation::Observation;
+use crate::simulation::sphere_math::angular_distance;
+use crate::world::generation::GridSize;
+use crate::world::tile::TerrainType;
+pub use bookmarks::Bookmarks;
+pub use checkpoint::{Checkpoint, Checkpoints};
+pub use energy::EnergyPotential;
+pub use layers::LayerArrays;
+pub use phase_inputs::PhaseInputs;
 pub use tile::{Season, Tile, TopologyType};
-pub use weather_systems::MacroWeatherState;
+pub use records::WorldRecords;
+pub use weather_systems::{MacroWeatherState, OceanState, SeaLevelState, SpaceWeatherState, ThermostatState};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct World {
@@ -23,6 +45,371 @@ pub struct World {
     pub generation_params: GenerationParams,
     pub snapshot_path: Option<String>,
     pub tiles: Vec<Tile>,
-    #[serde(default)]
     pub macro_weather: MacroWeatherState,
+    /// Tiles that rules may read but never mutate (protected areas, fixed boundaries).
+    pub frozen_tiles: BTreeSet<u32>,
+    /// Tiles outside the simulated region. They are also frozen, for good, and
+    /// are left out of tick diffs and statistics; neighbors still read them.
+    pub inert_tiles: BTreeSet<u32>,
+    /// Rule globals reduced over the last tick, readable by rules as `globals`.
+    pub globals: BTreeMap<String, f64>,
+    /// All-time weather and condition extremes.
+    pub records: WorldRecords,
+    /// Running band temperatures for the optional thermostat.
+    pub thermostat: ThermostatState,
+    /// Geomagnetic storm state for the optional aurora.
+    pub space_weather: SpaceWeatherState,
+    /// Rolling-mean wind and solar resource per tile.
+    pub energy: EnergyPotential,
+    /// Named views and guided tours for the viewer.
+    pub bookmarks: Bookmarks,
+    /// Overturning strength for the optional ocean circulation.
+    pub ocean: OceanState,
+    /// Where the sea stands, for the optional moving sea level.
+    pub sea_level: SeaLevelState,
+    /// Weather and conditions gathered for each slow phase since it last
+    /// ran, keyed by phase name.
+    pub phase_inputs: BTreeMap<String, PhaseInputs>,
+    /// Rule pack the world was created with, a subdirectory of the rule
+    /// directory; None runs the rules directly in it.
+    pub rule_pack: Option<String>,
+    /// Observed weather the simulation is still being nudged toward.
+    pub observations: Vec<Observation>,
+    /// Per-tile activity, for skipping rule evaluation on idle tiles.
+    pub activity: Activity,
+}
+
+impl World {
+    /// Whether rule mutations are blocked for this tile.
+    pub fn is_frozen(&self, tile_id: u32) -> bool {
+        self.frozen_tiles.contains(&tile_id)
+    }
+
+    /// Whether the tile lies outside the simulated region.
+    pub fn is_inert(&self, tile_id: u32) -> bool {
+        self.inert_tiles.contains(&tile_id)
+    }
+
+    /// Frozen tiles other than the inert ones, as listed by `/api/frozen`.
+    pub fn protected_tiles(&self) -> BTreeSet<u32> {
+        self.frozen_tiles.difference(&self.inert_tiles).copied().collect()
+    }
+
+    /// Freeze and thaw tiles. Returns IDs that were ignored because no such
+    /// tile exists. Inert tiles stay frozen.
+    pub fn update_frozen_tiles(&mut self, add: &[u32], remove: &[u32]) -> Vec<u32> {
+        let mut unknown = Vec::new();
+        for &id in add {
+            if (id as usize) < self.tiles.len() {
+                self.frozen_tiles.insert(id);
+            } else {
+                unknown.push(id);
+            }
+        }
+        for id in remove.iter().filter(|id| !self.inert_tiles.contains(id)) {
+            self.frozen_tiles.remove(id);
+        }
+        unknown
+    }
+
+    /// Turn tiles into ocean (`sea`) or dry plains, then reclassify the
+    /// coasts around them. Returns every tile whose terrain type changed,
+    /// including the ones given, in order; unknown IDs are ignored.
+    pub fn set_sea(&mut self, ids: &[u32], sea: bool) -> Vec<u32> {
+        let to = if sea { TerrainType::Ocean } else { TerrainType::Plains };
+        let mut changed = BTreeSet::new();
+        for &id in ids {
+            if let Some(tile) = self.tiles.get_mut(id as usize)
+                && (tile.geology.terrain_type == TerrainType::Ocean) != sea
+            {
+                tile.geology.terrain_type = to;
+                changed.insert(id);
+            }
+        }
+        let flipped: Vec<u32> = changed.iter().copied().collect();
+        changed.extend(terrain::reclassify_coasts(&mut self.tiles, &flipped));
+        changed.into_iter().collect()
+    }
+
+    /// Tiles further than `radius_deg` of arc from (`lat`, `lon`).
+    pub fn tiles_outside(&self, lat: f64, lon: f64, radius_deg: f64) -> BTreeSet<u32> {
+        self.tiles
+            .iter()
+            .filter(|t| angular_distance(lat, lon, t.position.lat, t.position.lon).to_degrees() > radius_deg)
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Replace the inert set, thawing tiles that were inert before and
+    /// freezing the new ones. Unknown IDs are dropped.
+    pub fn set_inert_tiles(&mut self, inert: BTreeSet<u32>) {
+        for id in &self.inert_tiles {
+            self.frozen_tiles.remove(id);
+        }
+        let tile_count = self.tiles.len();
+        self.inert_tiles = inert.into_iter().filter(|&id| (id as usize) < tile_count).collect();
+        self.frozen_tiles.extend(&self.inert_tiles);
+    }
+
+    /// Mark every deposit on these tiles as discovered. Returns the deposits
+    /// newly found and the IDs ignored because no such tile exists.
+    pub fn survey_tiles(&mut self, ids: &[u32]) -> (usize, Vec<u32>) {
+        let mut found = 0;
+        let mut unknown = Vec::new();
+        for &id in ids {
+            let Some(tile) = self.tiles.get_mut(id as usize) else {
+                unknown.push(id);
+                continue;
+            };
+            for deposit in tile.resources.resources.iter_mut().filter(|d| !d.discovered) {
+                deposit.discovered = true;
+                found += 1;
+            }
+        }
+        (found, unknown)
+    }
+
+    /// Start nudging tiles toward observed weather; a tile's newer
+    /// observation replaces any it still has. Returns the IDs ignored because
+    /// no such tile exists.
+    pub fn observe(&mut self, observations: Vec<Observation>) -> Vec<u32> {
+        let mut unknown = Vec::new();
+        for observation in observations {
+            if observation.tile as usize >= self.tiles.len() {
+                unknown.push(observation.tile);
+                continue;
+            }
+            self.observations.retain(|o| o.tile != observation.tile);
+            self.observations.push(observation);
+        }
+        unknown
+    }
+
+    /// Each tile's area on the sphere relative to the mean tile, for weighting
+    /// world-wide averages. Geodesic tiles are near enough equal to count
+    /// the same. A flat grid's rows have the same number of tiles at every
+    /// latitude, so each tile is weighted by the area of its row's latitude
+    /// band, which shrinks toward the poles.
+    pub fn tile_areas(&self) -> Vec<f64> {
+        let rows = match GridSize::of(&self.generation_params) {
+            GridSize::Geodesic { .. } => return vec![1.0; self.tiles.len()],
+            GridSize::Flat { height, .. } => height.max(1) as f64,
+        };
+        let half_band = 90.0 / rows;
+        let mut areas: Vec<f64> = self
+            .tiles
+            .iter()
+            .map(|t| {
+                let north = (t.position.lat + half_band).min(90.0).to_radians();
+                let south = (t.position.lat - half_band).max(-90.0).to_radians();
+                north.sin() - south.sin()
+            })
+            .collect();
+        let mean = areas.iter().sum::<f64>() / areas.len().max(1) as f64;
+        if mean > 0.0 {
+            areas.iter_mut().for_each(|a| *a /= mean);
+        }
+        areas
+    }
+
+    /// Settle the rule pack the world runs: the one it was created with, or
+    /// `configured` if it has none yet. Returns `configured` if it was
+    /// passed over for the world's own pack.
+    pub fn adopt_rule_pack(&mut self, configured: Option<&str>) -> Option<String> {
+        match (&self.rule_pack, configured) {
+            (Some(kept), Some(configured)) if kept != configured => Some(configured.to_string()),
+            (None, Some(configured)) => {
+                self.rule_pack = Some(configured.to_string());
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Copy of the world-level state with an empty tile list.
+    pub fn without_tiles(&self) -> World {
+        World {
+            id: self.id,
+            name: self.name.clone(),
+            created_at: self.created_at.clone(),
+            tick_count: self.tick_count,
+            season: self.season,
+            season_length: self.season_length,
+            tile_count: self.tile_count,
+            topology_type: self.topology_type,
+            generation_params: self.generation_params.clone(),
+            snapshot_path: self.snapshot_path.clone(),
+            tiles: Vec::new(),
+            macro_weather: self.macro_weather.clone(),
+            frozen_tiles: self.frozen_tiles.clone(),
+            inert_tiles: self.inert_tiles.clone(),
+            globals: self.globals.clone(),
+            records: self.records.clone(),
+            thermostat: self.thermostat.clone(),
+            space_weather: self.space_weather.clone(),
+            energy: self.energy.clone(),
+            bookmarks: self.bookmarks.clone(),
+            ocean: self.ocean.clone(),
+            sea_level: self.sea_level.clone(),
+            phase_inputs: self.phase_inputs.clone(),
+            rule_pack: self.rule_pack.clone(),
+            observations: self.observations.clone(),
+            activity: self.activity.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generation::TopologyConfig;
+    use crate::world::generation::generate_world;
+
+    #[test]
+    fn update_frozen_tiles_ignores_unknown_ids() {
+        let mut world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        });
+
+        let unknown = world.update_frozen_tiles(&[5, 7, 100_000], &[]);
+        assert_eq!(unknown, vec![100_000]);
+        assert!(world.is_frozen(5) && world.is_frozen(7));
+
+        world.update_frozen_tiles(&[], &[5]);
+        assert!(!world.is_frozen(5));
+        assert_eq!(world.frozen_tiles.len(), 1);
+    }
+
+    #[test]
+    fn a_world_keeps_the_rule_pack_it_was_created_with() {
+        let mut world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        });
+        assert_eq!(world.adopt_rule_pack(None), None);
+        assert_eq!(world.rule_pack, None);
+
+        assert_eq!(world.adopt_rule_pack(Some("earthlike")), None);
+        assert_eq!(world.rule_pack.as_deref(), Some("earthlike"));
+        assert_eq!(world.adopt_rule_pack(Some("earthlike")), None);
+        assert_eq!(world.adopt_rule_pack(None), None);
+
+        assert_eq!(world.adopt_rule_pack(Some("arid-world")), Some("arid-world".to_string()));
+        assert_eq!(world.rule_pack.as_deref(), Some("earthlike"));
+    }
+
+    #[test]
+    fn flat_tiles_shrink_toward_the_poles() {
+        let mut params = GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ..Default::default()
+        };
+        let world = generate_world(&params);
+        let areas = world.tile_areas();
+        assert!((areas.iter().sum::<f64>() / areas.len() as f64 - 1.0).abs() < 1e-9);
+        let area_at = |lat: f64| {
+            let tile = world
+                .tiles
+                .iter()
+                .min_by(|a, b| (a.position.lat - lat).abs().total_cmp(&(b.position.lat - lat).abs()))
+                .unwrap();
+            areas[tile.id as usize]
+        };
+        assert!(area_at(90.0) > 0.0);
+        assert!(area_at(90.0) < area_at(45.0));
+        assert!(area_at(45.0) < area_at(0.0));
+
+        params.topology = TopologyConfig { mode: "geodesic".to_string(), subdivision_level: 2, ..Default::default() };
+        assert!(generate_world(&params).tile_areas().iter().all(|&a| a == 1.0));
+    }
+
+    #[test]
+    fn inert_tiles_stay_frozen_and_clear_cleanly() {
+        let mut world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            topology: TopologyConfig { mode: "geodesic".to_string(), subdivision_level: 2, ..Default::default() },
+            ..Default::default()
+        });
+        let outside = world.tiles_outside(45.0, 10.0, 40.0);
+        assert!(!outside.is_empty() && outside.len() < world.tiles.len());
+        let inside = world.tiles.iter().find(|t| !outside.contains(&t.id)).unwrap().id;
+
+        world.update_frozen_tiles(&[inside], &[]);
+        world.set_inert_tiles(outside.clone());
+        let some_inert = *outside.first().unwrap();
+        assert!(world.is_inert(some_inert) && world.is_frozen(some_inert));
+        assert_eq!(world.protected_tiles(), BTreeSet::from([inside]));
+
+        // Thawing can't wake an inert tile
+        world.update_frozen_tiles(&[], &[some_inert]);
+        assert!(world.is_frozen(some_inert));
+
+        world.set_inert_tiles(BTreeSet::new());
+        assert!(!world.is_frozen(some_inert));
+        assert_eq!(world.frozen_tiles, BTreeSet::from([inside]));
+    }
+
+    #[test]
+    fn set_sea_reclassifies_only_the_surrounding_coast() {
+        let mut world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 100,
+            ocean_ratio: 0.0,
+            ..Default::default()
+        });
+        let lake = 55;
+        let shore = world.tiles[lake as usize].neighbors.clone();
+        let changed = world.set_sea(&[lake], true);
+        assert!(changed.contains(&lake));
+        for t in &world.tiles {
+            let coastal = matches!(t.geology.terrain_type, TerrainType::Coast | TerrainType::Cliffs);
+            assert_eq!(coastal, shore.contains(&t.id), "tile {}", t.id);
+        }
+        assert_eq!(changed.len(), shore.len() + 1);
+
+        // Draining it again leaves no coast behind
+        world.set_sea(&[lake, 1_000], false);
+        assert!(world.tiles.iter().all(|t| !matches!(
+            t.geology.terrain_type,
+            TerrainType::Ocean | TerrainType::Coast | TerrainType::Cliffs
+        )));
+    }
+
+    #[test]
+    fn survey_reveals_hidden_deposits_once() {
+        let mut world = generate_world(&GenerationParams {
+            seed: 42,
+            tile_count: 400,
+            ocean_ratio: 0.3,
+            mountain_ratio: 0.3,
+            resource_density: 1.0,
+            ..Default::default()
+        });
+        let hidden: Vec<u32> = world
+            .tiles
+            .iter()
+            .filter(|t| t.resources.resources.iter().any(|d| !d.discovered))
+            .map(|t| t.id)
+            .collect();
+        assert!(!hidden.is_empty(), "generation should bury some ore");
+        for tile in &world.tiles {
+            for d in &tile.resources.resources {
+                assert!((0.0..=1.0).contains(&d.quality), "{} quality {}", d.resource_type, d.quality);
+                assert_eq!(d.discovered, !matches!(d.resource_type.as_str(), "iron" | "stone"));
+            }
+        }
+
+        let (found, unknown) = world.survey_tiles(&[hidden[0], 100_000]);
+        assert!(found > 0);
+        assert_eq!(unknown, vec![100_000]);
+        let tile = &world.tiles[hidden[0] as usize];
+        assert!(tile.resources.resources.iter().all(|d| d.discovered));
+        assert_eq!(tile.resources.discovered(), tile.resources);
+        assert_eq!(world.survey_tiles(&[hidden[0]]), (0, Vec::new()));
+    }
 }
@@ -0,0 +1,331 @@
+//! Bounded-memory mode: deposits paged out to a memory-mapped file.
+//!
+//! Rules read a tile's deposits only in the resources phase; the rest of a
+//! tick works on weather, conditions and biome. With `page_cold_layers`,
+//! the resources layer lives in a page file next to the snapshots outside
+//! that phase, and tiles keep an empty layer, so the deposit lists (a
+//! tile's largest heap allocations) are freed for most of each tick and
+//! between ticks. The file is memory-mapped, so the OS keeps in memory only
+//! what is being read, and can drop it again under pressure.
+//!
+//! Tiles are paged `PAGE_TILES` at a time, each chunk's layers one
+//! zstd-compressed bincode page. A page that outgrows its slot moves to the
+//! end of the file, with room to grow. Paging out compares each page with
+//! the one it replaces and remembers which tiles' deposits changed, so tick
+//! diffs still carry them though the tiles no longer do.
+
+use std::collections::BTreeSet;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use memmap2::MmapMut;
+use rayon::prelude::*;
+use worldground_protocol::TileChange;
+
+use crate::error::WorldgroundError;
+use crate::world::tile::ResourceLayer;
+use crate::world::Tile;
+
+/// Tiles whose deposits share a page.
+pub const PAGE_TILES: usize = 4096;
+
+/// Pages are rewritten every tick, so compression favors speed.
+const ZSTD_LEVEL: i32 = 1;
+
+/// The page file of a running world. Dropping it deletes the file.
+pub struct PageFile {
+    path: PathBuf,
+    pages: Mutex<Pages>,
+}
+
+struct Pages {
+    file: File,
+    /// Unmapped until the first page is written; an empty file can't be mapped
+    map: Option<MmapMut>,
+    /// Where each chunk's page is, in tile order
+    slots: Vec<Slot>,
+    /// Where the last slot ends, which is the file's length
+    end: usize,
+    /// Whether the tiles hold their deposits rather than the file
+    resident: bool,
+    /// Tiles whose deposits changed since `take_changes`, by index
+    changed: BTreeSet<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    offset: usize,
+    len: usize,
+    capacity: usize,
+}
+
+impl PageFile {
+    /// Create an empty page file in `dir`. Until the first `page_out`, the
+    /// tiles hold their deposits.
+    pub fn create(dir: &Path) -> Result<Self, WorldgroundError> {
+        fs::create_dir_all(dir).map_err(|source| WorldgroundError::Write { path: dir.to_path_buf(), source })?;
+        // Named by process, so a handoff successor sharing the directory has its own
+        let path = dir.join(format!("worldground-{}.pages", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .map_err(|source| WorldgroundError::Write { path: path.clone(), source })?;
+        Ok(PageFile {
+            path,
+            pages: Mutex::new(Pages {
+                file,
+                map: None,
+                slots: Vec::new(),
+                end: 0,
+                resident: true,
+                changed: BTreeSet::new(),
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the tiles hold their deposits, rather than the file.
+    pub fn is_resident(&self) -> bool {
+        self.lock().resident
+    }
+
+    /// Move the tiles' deposits into the file, leaving each tile an empty
+    /// layer. Tiles whose deposits differ from the page they replace are
+    /// kept for `take_changes`. Does nothing if they are already paged out;
+    /// on error the tiles keep their deposits.
+    pub fn page_out(&self, tiles: &mut [Tile]) -> Result<(), WorldgroundError> {
+        let mut pages = self.lock();
+        if !pages.resident {
+            return Ok(());
+        }
+        let encoded: Vec<Vec<u8>> =
+            tiles.par_chunks(PAGE_TILES).map(|chunk| self.encode(chunk)).collect::<Result<_, _>>()?;
+
+        // Compare against the pages being replaced before overwriting them
+        let mut changed = Vec::new();
+        for (i, (bytes, chunk)) in encoded.iter().zip(tiles.chunks(PAGE_TILES)).enumerate() {
+            let Some(old) = pages.page(i).filter(|old| *old != bytes.as_slice()) else {
+                continue;
+            };
+            let old = self.decode(old, chunk.len())?;
+            changed.extend(
+                old.iter()
+                    .zip(chunk)
+                    .enumerate()
+                    .filter(|(_, (before, tile))| **before != tile.resources)
+                    .map(|(j, _)| i * PAGE_TILES + j),
+            );
+        }
+        pages.write(&encoded, &self.path)?;
+        pages.changed.extend(changed);
+
+        tiles.par_iter_mut().for_each(|tile| tile.resources.resources = Vec::new());
+        pages.resident = false;
+        Ok(())
+    }
+
+    /// Put the deposits back on the tiles. Does nothing if they are there.
+    pub fn page_in(&self, tiles: &mut [Tile]) -> Result<(), WorldgroundError> {
+        let mut pages = self.lock();
+        if pages.resident {
+            return Ok(());
+        }
+        let paged = &*pages;
+        tiles.par_chunks_mut(PAGE_TILES).enumerate().try_for_each(|(i, chunk)| {
+            let page = paged.page(i).ok_or_else(|| self.corrupt(format!("no page for tile {}", i * PAGE_TILES)))?;
+            let layers = self.decode(page, chunk.len())?;
+            for (tile, layer) in chunk.iter_mut().zip(layers) {
+                tile.resources = layer;
+            }
+            Ok::<_, WorldgroundError>(())
+        })?;
+        pages.resident = true;
+        Ok(())
+    }
+
+    /// The deposits of the tiles that changed since the last call, as diff
+    /// changes in tile order. Each page holding one is decoded once.
+    pub fn take_changes(&self, tiles: &[Tile]) -> Result<Vec<TileChange>, WorldgroundError> {
+        let mut pages = self.lock();
+        let changed: Vec<usize> =
+            std::mem::take(&mut pages.changed).into_iter().filter(|&i| i < tiles.len()).collect();
+        let mut changes = Vec::with_capacity(changed.len());
+        for group in changed.chunk_by(|a, b| a / PAGE_TILES == b / PAGE_TILES) {
+            let page = group[0] / PAGE_TILES;
+            let paged = match pages.resident {
+                true => None,
+                false => {
+                    let bytes = pages.page(page).ok_or_else(|| self.corrupt(format!("no page for tile {}", group[0])))?;
+                    Some(self.decode(bytes, tiles[page * PAGE_TILES..].len().min(PAGE_TILES))?)
+                }
+            };
+            for &index in group {
+                let layer = paged.as_ref().map_or(&tiles[index].resources, |layers| &layers[index % PAGE_TILES]);
+                changes.push(TileChange {
+                    id: tiles[index].id,
+                    weather: None,
+                    conditions: None,
+                    biome: None,
+                    resources: Some(layer.discovered()),
+                    scratch: None,
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pages> {
+        self.pages.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn encode(&self, chunk: &[Tile]) -> Result<Vec<u8>, WorldgroundError> {
+        let layers: Vec<&ResourceLayer> = chunk.iter().map(|t| &t.resources).collect();
+        let raw = bincode::serialize(&layers).map_err(|e| self.corrupt(e.to_string()))?;
+        zstd::bulk::compress(&raw, ZSTD_LEVEL).map_err(|source| WorldgroundError::Write { path: self.path.clone(), source })
+    }
+
+    fn decode(&self, page: &[u8], tiles: usize) -> Result<Vec<ResourceLayer>, WorldgroundError> {
+        let raw = zstd::stream::decode_all(page).map_err(|e| self.corrupt(e.to_string()))?;
+        let layers: Vec<ResourceLayer> = bincode::deserialize(&raw).map_err(|e| self.corrupt(e.to_string()))?;
+        if layers.len() != tiles {
+            return Err(self.corrupt(format!("page holds {} tiles, expected {}", layers.len(), tiles)));
+        }
+        Ok(layers)
+    }
+
+    fn corrupt(&self, reason: String) -> WorldgroundError {
+        WorldgroundError::Persistence(format!("Page file {} is unreadable: {}", self.path.display(), reason))
+    }
+}
+
+impl Drop for PageFile {
+    fn drop(&mut self) {
+        if let Ok(pages) = self.pages.get_mut() {
+            pages.map = None;
+        }
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl Pages {
+    fn page(&self, index: usize) -> Option<&[u8]> {
+        let slot = self.slots.get(index)?;
+        Some(&self.map.as_ref()?[slot.offset..slot.offset + slot.len])
+    }
+
+    /// Write each chunk's page to its slot, moving the ones that no longer
+    /// fit to the end of the file, which grows (and is remapped) once.
+    fn write(&mut self, encoded: &[Vec<u8>], path: &Path) -> Result<(), WorldgroundError> {
+        let mut end = self.end;
+        for (i, bytes) in encoded.iter().enumerate() {
+            if self.slots.get(i).is_some_and(|slot| bytes.len() <= slot.capacity) {
+                continue;
+            }
+            // Half again as much room, so a page that gains a few deposits stays put
+            let slot = Slot { offset: end, len: 0, capacity: bytes.len() + bytes.len() / 2 };
+            end += slot.capacity;
+            match self.slots.get_mut(i) {
+                Some(old) => *old = slot,
+                None => self.slots.push(slot),
+            }
+        }
+        if end > self.end {
+            self.map = None;
+            self.file
+                .set_len(end as u64)
+                .map_err(|source| WorldgroundError::Write { path: path.to_path_buf(), source })?;
+            // SAFETY: the file is this process's own (named by its ID) and is
+            // only ever written through this map.
+            let map = unsafe { MmapMut::map_mut(&self.file) }
+                .map_err(|source| WorldgroundError::Write { path: path.to_path_buf(), source })?;
+            self.map = Some(map);
+            self.end = end;
+        }
+        if let Some(map) = &mut self.map {
+            for (slot, bytes) in self.slots.iter_mut().zip(encoded) {
+                map[slot.offset..slot.offset + bytes.len()].copy_from_slice(bytes);
+                slot.len = bytes.len();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::{Position, ResourceDeposit};
+
+    fn tiles(count: u32) -> Vec<Tile> {
+        (0..count)
+            .map(|i| {
+                let mut tile = Tile::new_default(i, vec![], Position::flat(i as f64, 0.0));
+                tile.resources.resources.push(ResourceDeposit {
+                    resource_type: "iron".to_string(),
+                    quantity: i as f32,
+                    max_quantity: 100.0,
+                    renewal_rate: 0.0,
+                    requires_biome: None,
+                    quality: 0.5,
+                    discovered: true,
+                });
+                tile
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deposits_round_trip_through_the_page_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let pages = PageFile::create(dir.path()).unwrap();
+        let original = tiles(PAGE_TILES as u32 + 10);
+        let mut world_tiles = original.clone();
+
+        pages.page_out(&mut world_tiles).unwrap();
+        assert!(!pages.is_resident());
+        assert!(world_tiles.iter().all(|t| t.resources.resources.is_empty()));
+        assert!(fs::metadata(pages.path()).unwrap().len() > 0);
+
+        pages.page_in(&mut world_tiles).unwrap();
+        assert!(pages.is_resident());
+        assert_eq!(world_tiles, original);
+
+        let path = pages.path().to_path_buf();
+        drop(pages);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn changed_deposits_are_reported_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let pages = PageFile::create(dir.path()).unwrap();
+        let mut world_tiles = tiles(PAGE_TILES as u32 + 10);
+        // The first page-out has nothing to compare with
+        pages.page_out(&mut world_tiles).unwrap();
+        assert!(pages.take_changes(&world_tiles).unwrap().is_empty());
+
+        pages.page_in(&mut world_tiles).unwrap();
+        world_tiles[4].resources.resources[0].quantity = 50.0;
+        // Outgrows its slot, so the page moves
+        let grown = world_tiles[0].resources.resources[0].clone();
+        world_tiles[PAGE_TILES + 2].resources.resources.extend(std::iter::repeat_n(grown, 200));
+        pages.page_out(&mut world_tiles).unwrap();
+
+        let changes = pages.take_changes(&world_tiles).unwrap();
+        assert_eq!(changes.iter().map(|c| c.id).collect::<Vec<_>>(), vec![4, PAGE_TILES as u32 + 2]);
+        assert_eq!(changes[0].resources.as_ref().unwrap().resources[0].quantity, 50.0);
+        assert_eq!(changes[1].resources.as_ref().unwrap().resources.len(), 201);
+        assert!(pages.take_changes(&world_tiles).unwrap().is_empty());
+
+        pages.page_in(&mut world_tiles).unwrap();
+        assert_eq!(world_tiles[PAGE_TILES + 2].resources.resources.len(), 201);
+        assert_eq!(world_tiles[4].resources.resources[0].quantity, 50.0);
+    }
+}
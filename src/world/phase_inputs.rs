@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::world::Tile;
+
+/// What one tile saw over the ticks a slow phase's run stands in for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Since {
+    /// Ticks since the phase last ran, this one included
+    pub ticks: u32,
+    /// Precipitation summed over those ticks
+    pub precipitation: f32,
+    /// Mean temperature (K)
+    pub temperature: f32,
+    /// Mean soil moisture
+    pub soil_moisture: f32,
+}
+
+/// Weather and conditions gathered per tile for a phase that runs only
+/// every few ticks (see `phase_periods`), so its run can act on the whole
+/// stretch rather than the last tick alone.
+///
+/// Added to after the conditions phase of every tick the phase is scheduled
+/// for and emptied once it has run. Saved with the world so a restart
+/// doesn't lose a half-gathered stretch. The vectors are indexed by tile and
+/// start over when the tile count changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseInputs {
+    /// Ticks gathered so far
+    pub ticks: u32,
+    /// Summed precipitation
+    pub precipitation: Vec<f32>,
+    /// Summed temperature (K)
+    pub temperature: Vec<f32>,
+    /// Summed soil moisture
+    pub soil_moisture: Vec<f32>,
+}
+
+impl PhaseInputs {
+    /// Fold one tick's weather and conditions in.
+    pub fn add(&mut self, tiles: &[Tile]) {
+        if self.precipitation.len() != tiles.len()
+            || self.temperature.len() != tiles.len()
+            || self.soil_moisture.len() != tiles.len()
+        {
+            self.ticks = 0;
+            self.precipitation = vec![0.0; tiles.len()];
+            self.temperature = vec![0.0; tiles.len()];
+            self.soil_moisture = vec![0.0; tiles.len()];
+        }
+        self.ticks += 1;
+        for (i, tile) in tiles.iter().enumerate() {
+            self.precipitation[i] += tile.weather.precipitation;
+            self.temperature[i] += tile.weather.temperature;
+            self.soil_moisture[i] += tile.conditions.soil_moisture;
+        }
+    }
+
+    /// What the tile at `index` saw since the phase last ran, or None if
+    /// nothing has been gathered.
+    pub fn since(&self, index: usize) -> Option<Since> {
+        if self.ticks == 0 || index >= self.precipitation.len() {
+            return None;
+        }
+        let n = self.ticks as f32;
+        Some(Since {
+            ticks: self.ticks,
+            precipitation: self.precipitation[index],
+            temperature: self.temperature[index] / n,
+            soil_moisture: self.soil_moisture[index] / n,
+        })
+    }
+
+    /// Start gathering again, after the phase has run.
+    pub fn clear(&mut self) {
+        self.ticks = 0;
+        self.precipitation.fill(0.0);
+        self.temperature.fill(0.0);
+        self.soil_moisture.fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    #[test]
+    fn sums_precipitation_and_averages_the_rest() {
+        let mut tiles = vec![Tile::new_default(0, vec![], Position::flat(0.0, 0.0))];
+        let mut inputs = PhaseInputs::default();
+        assert_eq!(inputs.since(0), None);
+
+        for (rain, temp, soil) in [(0.2, 280.0, 0.4), (0.0, 290.0, 0.2), (0.4, 300.0, 0.3)] {
+            tiles[0].weather.precipitation = rain;
+            tiles[0].weather.temperature = temp;
+            tiles[0].conditions.soil_moisture = soil;
+            inputs.add(&tiles);
+        }
+        let since = inputs.since(0).unwrap();
+        assert_eq!(since.ticks, 3);
+        assert!((since.precipitation - 0.6).abs() < 1e-6);
+        assert!((since.temperature - 290.0).abs() < 1e-3);
+        assert!((since.soil_moisture - 0.3).abs() < 1e-6);
+        assert_eq!(inputs.since(1), None);
+
+        inputs.clear();
+        assert_eq!(inputs.since(0), None);
+        inputs.add(&tiles);
+        assert_eq!(inputs.since(0).unwrap().precipitation, 0.4);
+
+        // A different tile count starts over
+        tiles.push(Tile::new_default(1, vec![], Position::flat(1.0, 0.0)));
+        inputs.add(&tiles);
+        assert_eq!(inputs.since(1).unwrap().ticks, 1);
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::world::Tile;
+use worldground_protocol::units::{Quantity, Units};
+
+/// One record-setting observation: the value, where it happened, and when.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub value: f64,
+    pub tile_id: u32,
+    pub tick: u64,
+}
+
+/// All-time weather and condition extremes for a world.
+///
+/// Updated at the end of every tick and saved with the world. A record only
+/// changes hands when it is strictly beaten, so ties stay with the first tile
+/// and tick that reached the value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldRecords {
+    /// Highest tile temperature (K)
+    pub highest_temperature: Option<Record>,
+    /// Lowest tile temperature (K)
+    pub lowest_temperature: Option<Record>,
+    /// Highest humidity (0.0-1.0)
+    pub highest_humidity: Option<Record>,
+    /// Lowest humidity (0.0-1.0)
+    pub lowest_humidity: Option<Record>,
+    /// Highest storm intensity (0.0-1.0)
+    pub strongest_storm: Option<Record>,
+    /// Most consecutive drought days on one tile
+    pub longest_drought: Option<Record>,
+}
+
+impl WorldRecords {
+    /// Check every tile against the current records. Returns the names of the
+    /// records broken this tick.
+    pub fn update(&mut self, tiles: &[Tile], tick: u64) -> Vec<&'static str> {
+        let mut best = self.clone();
+        for tile in tiles {
+            let w = &tile.weather;
+            raise(&mut best.highest_temperature, w.temperature as f64, tile.id, tick);
+            lower(&mut best.lowest_temperature, w.temperature as f64, tile.id, tick);
+            raise(&mut best.highest_humidity, w.humidity as f64, tile.id, tick);
+            lower(&mut best.lowest_humidity, w.humidity as f64, tile.id, tick);
+            raise(&mut best.strongest_storm, w.storm_intensity as f64, tile.id, tick);
+            raise(&mut best.longest_drought, tile.conditions.drought_days as f64, tile.id, tick);
+        }
+
+        let broken = best
+            .entries()
+            .into_iter()
+            .zip(self.entries())
+            .filter(|((_, new), (_, old))| new != old)
+            .map(|((name, _), _)| name)
+            .collect();
+        *self = best;
+        broken
+    }
+
+    /// These records with temperatures and humidities in `units`.
+    pub fn in_units(&self, units: Units) -> WorldRecords {
+        let convert = |record: Option<Record>, quantity: Quantity| {
+            record.map(|r| Record { value: units.convert(quantity, r.value as f32) as f64, ..r })
+        };
+        WorldRecords {
+            highest_temperature: convert(self.highest_temperature, Quantity::Temperature),
+            lowest_temperature: convert(self.lowest_temperature, Quantity::Temperature),
+            highest_humidity: convert(self.highest_humidity, Quantity::Humidity),
+            lowest_humidity: convert(self.lowest_humidity, Quantity::Humidity),
+            ..self.clone()
+        }
+    }
+
+    /// Records in display order, with their names.
+    pub fn entries(&self) -> [(&'static str, Option<Record>); 6] {
+        [
+            ("highest_temperature", self.highest_temperature),
+            ("lowest_temperature", self.lowest_temperature),
+            ("highest_humidity", self.highest_humidity),
+            ("lowest_humidity", self.lowest_humidity),
+            ("strongest_storm", self.strongest_storm),
+            ("longest_drought", self.longest_drought),
+        ]
+    }
+}
+
+fn raise(record: &mut Option<Record>, value: f64, tile_id: u32, tick: u64) {
+    if record.is_none_or(|r| value > r.value) {
+        *record = Some(Record { value, tile_id, tick });
+    }
+}
+
+fn lower(record: &mut Option<Record>, value: f64, tile_id: u32, tick: u64) {
+    if record.is_none_or(|r| value < r.value) {
+        *record = Some(Record { value, tile_id, tick });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+    use worldground_protocol::units::{HumidityUnit, TemperatureUnit};
+
+    fn tile(id: u32, temperature: f32, drought_days: u32) -> Tile {
+        let mut t = Tile::new_default(id, vec![], Position::flat(0.0, 0.0));
+        t.weather.temperature = temperature;
+        t.conditions.drought_days = drought_days;
+        t
+    }
+
+    #[test]
+    fn first_update_sets_every_record() {
+        let mut records = WorldRecords::default();
+        let broken = records.update(&[tile(0, 280.0, 3), tile(1, 300.0, 1)], 1);
+        assert_eq!(broken.len(), 6);
+        assert_eq!(
+            records.highest_temperature,
+            Some(Record { value: 300.0, tile_id: 1, tick: 1 })
+        );
+        assert_eq!(records.lowest_temperature.unwrap().tile_id, 0);
+        assert_eq!(records.longest_drought.unwrap().value, 3.0);
+    }
+
+    #[test]
+    fn records_convert_only_temperatures_and_humidities() {
+        let mut records = WorldRecords::default();
+        records.update(&[tile(0, 300.0, 3)], 1);
+        let units = Units { temperature: TemperatureUnit::Fahrenheit, humidity: HumidityUnit::Percent };
+        let converted = records.in_units(units);
+        assert!((converted.highest_temperature.unwrap().value - 80.33).abs() < 1e-3);
+        let humidity = records.highest_humidity.unwrap().value;
+        assert!((converted.highest_humidity.unwrap().value - humidity * 100.0).abs() < 1e-4);
+        assert_eq!(converted.longest_drought, records.longest_drought);
+        assert_eq!(records.in_units(Units::default()), records);
+    }
+
+    #[test]
+    fn records_change_only_when_strictly_beaten() {
+        let mut records = WorldRecords::default();
+        records.update(&[tile(0, 280.0, 3), tile(1, 300.0, 1)], 1);
+
+        // Tie on the high, new low, no change elsewhere
+        let broken = records.update(&[tile(2, 300.0, 0), tile(3, 270.0, 0)], 2);
+        assert_eq!(broken, vec!["lowest_temperature"]);
+        assert_eq!(records.highest_temperature.unwrap().tile_id, 1);
+        assert_eq!(
+            records.lowest_temperature,
+            Some(Record { value: 270.0, tile_id: 3, tick: 2 })
+        );
+    }
+
+    #[test]
+    fn records_serde_round_trip() {
+        let mut records = WorldRecords::default();
+        records.update(&[tile(0, 280.0, 3)], 7);
+        let encoded = bincode::serialize(&records).expect("serialize");
+        let decoded: WorldRecords = bincode::deserialize(&encoded).expect("deserialize");
+        assert_eq!(records, decoded);
+    }
+}
@@ -0,0 +1,124 @@
+//! Terrain types that depend on a tile's neighbors.
+//!
+//! Coast and Cliffs are land tiles bordering the ocean: steep ground
+//! (Hills, Mountains) becomes Cliffs and everything else Coast. Generation
+//! classifies every tile once; when water bodies change at runtime only the
+//! tiles that changed and their neighbors are reclassified, so terrain stays
+//! consistent with the map without rescanning the world.
+
+use std::collections::BTreeSet;
+
+use crate::world::tile::TerrainType;
+use crate::world::Tile;
+
+/// The terrain a tile should have, given its current terrain and whether
+/// it borders the ocean. A tile that stops being coastal falls back to the
+/// inland type it would have had: Cliffs to Hills, Coast to Plains.
+pub fn coastal_terrain(terrain: TerrainType, coastal: bool) -> TerrainType {
+    match (terrain, coastal) {
+        (TerrainType::Ocean, _) => TerrainType::Ocean,
+        (TerrainType::Coast | TerrainType::Cliffs, true) => terrain,
+        (TerrainType::Hills | TerrainType::Mountains, true) => TerrainType::Cliffs,
+        (_, true) => TerrainType::Coast,
+        (TerrainType::Cliffs, false) => TerrainType::Hills,
+        (TerrainType::Coast, false) => TerrainType::Plains,
+        (terrain, false) => terrain,
+    }
+}
+
+/// Whether any of the tile's neighbors is ocean.
+fn borders_ocean(tiles: &[Tile], tile: &Tile) -> bool {
+    tile.neighbors
+        .iter()
+        .filter_map(|&n| tiles.get(n as usize))
+        .any(|n| n.geology.terrain_type == TerrainType::Ocean)
+}
+
+/// Reclassify the coasts around tiles whose land or water changed: the
+/// tiles themselves and their neighbors. Returns the IDs whose terrain type
+/// changed, in order.
+pub fn reclassify_coasts(tiles: &mut [Tile], changed: &[u32]) -> Vec<u32> {
+    let affected: BTreeSet<usize> = changed
+        .iter()
+        .filter_map(|&id| tiles.get(id as usize))
+        .flat_map(|t| std::iter::once(t.id).chain(t.neighbors.iter().copied()))
+        .map(|id| id as usize)
+        .filter(|&i| i < tiles.len())
+        .collect();
+
+    let updates: Vec<(usize, TerrainType)> = affected
+        .into_iter()
+        .filter_map(|i| {
+            let current = tiles[i].geology.terrain_type;
+            let next = coastal_terrain(current, borders_ocean(tiles, &tiles[i]));
+            (next != current).then_some((i, next))
+        })
+        .collect();
+
+    updates
+        .into_iter()
+        .map(|(i, terrain)| {
+            tiles[i].geology.terrain_type = terrain;
+            tiles[i].id
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tile::Position;
+
+    /// A line of land tiles, each linked to the next.
+    fn shoreline(terrain: &[TerrainType]) -> Vec<Tile> {
+        let n = terrain.len() as u32;
+        terrain
+            .iter()
+            .zip(0_u32..)
+            .map(|(&t, id)| {
+                let neighbors = [id.checked_sub(1), (id + 1 < n).then_some(id + 1)];
+                let mut tile = Tile::new_default(id, neighbors.into_iter().flatten().collect(), Position::flat(0.0, 0.0));
+                tile.geology.terrain_type = t;
+                tile
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new_sea_turns_neighbors_into_coast_and_cliffs() {
+        use TerrainType::*;
+        let mut tiles = shoreline(&[Plains, Plains, Mountains, Plains]);
+        tiles[1].geology.terrain_type = Ocean;
+        let changed = reclassify_coasts(&mut tiles, &[1]);
+        assert_eq!(changed, vec![0, 2]);
+        let types: Vec<_> = tiles.iter().map(|t| t.geology.terrain_type).collect();
+        assert_eq!(types, vec![Coast, Ocean, Cliffs, Plains], "tile 3 is out of reach of the change");
+    }
+
+    #[test]
+    fn drained_sea_turns_coasts_back_inland() {
+        use TerrainType::*;
+        let mut tiles = shoreline(&[Coast, Ocean, Cliffs, Wetlands]);
+        tiles[1].geology.terrain_type = Plains;
+        let changed = reclassify_coasts(&mut tiles, &[1]);
+        assert_eq!(changed, vec![0, 2]);
+        let types: Vec<_> = tiles.iter().map(|t| t.geology.terrain_type).collect();
+        assert_eq!(types, vec![Plains, Plains, Hills, Wetlands]);
+
+        // Nothing left to change
+        assert!(reclassify_coasts(&mut tiles, &[0, 1, 2, 3, 99]).is_empty());
+    }
+
+    #[test]
+    fn generated_worlds_are_already_consistent() {
+        let world = crate::world::generation::generate_world(&crate::config::generation::GenerationParams {
+            seed: 9,
+            tile_count: 400,
+            ocean_ratio: 0.5,
+            ..Default::default()
+        });
+        let mut tiles = world.tiles.clone();
+        let all: Vec<u32> = tiles.iter().map(|t| t.id).collect();
+        assert!(reclassify_coasts(&mut tiles, &all).is_empty());
+    }
+}
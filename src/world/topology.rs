@@ -1,9 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use hexasphere::shapes::IcoSphereBase;
 use hexasphere::Subdivided;
 
-use crate::world::tile::{Position, Tile};
+use crate::world::tile::{Position, Tile, TopologyType};
 
 /// Neighbor offsets for even rows (row % 2 == 0) in odd-r offset layout.
 const EVEN_ROW_NEIGHBORS: [(i32, i32); 6] = [
@@ -52,6 +52,40 @@ pub fn generate_flat_hex_grid(width: u32, height: u32) -> Vec<Tile> {
         "Grid height must be even for toroidal wrapping"
     );
 
+    build_flat_hex_grid(width, height, true, true)
+}
+
+/// Generate a flat hex grid that wraps east-west only: a cylinder.
+///
+/// Columns wrap as on the torus, so every row is a closed ring of hexagons
+/// with no polar pentagons. The top and bottom rows are hard edges with four
+/// neighbors each, handled during simulation by the topology's boundary mode.
+///
+/// # Panics
+/// Panics if width < 3 (a two-column ring would list one neighbor twice) or
+/// height < 2.
+pub fn generate_wrapping_hex_grid(width: u32, height: u32) -> Vec<Tile> {
+    assert!(width >= 3, "Grid width must be at least 3 for east-west wrapping");
+    assert!(height >= 2, "Grid height must be at least 2");
+
+    build_flat_hex_grid(width, height, true, false)
+}
+
+/// Generate a flat hex grid with hard (non-wrapping) edges.
+///
+/// Interior tiles have 6 neighbors; edge tiles have fewer. Edge behavior during
+/// simulation is controlled by the topology's boundary mode.
+///
+/// # Panics
+/// Panics if width < 2 or height < 2.
+pub fn generate_bounded_flat_hex_grid(width: u32, height: u32) -> Vec<Tile> {
+    assert!(width >= 2, "Grid width must be at least 2");
+    assert!(height >= 2, "Grid height must be at least 2");
+
+    build_flat_hex_grid(width, height, false, false)
+}
+
+fn build_flat_hex_grid(width: u32, height: u32, wrap_cols: bool, wrap_rows: bool) -> Vec<Tile> {
     let total = (width * height) as usize;
     let mut tiles = Vec::with_capacity(total);
 
@@ -74,9 +108,17 @@ pub fn generate_flat_hex_grid(width: u32, height: u32) -> Vec<Tile> {
 
             let mut neighbors = Vec::with_capacity(6);
             for &(dc, dr) in offsets {
-                let nc = (col as i32 + dc).rem_euclid(width as i32) as u32;
-                let nr = (row as i32 + dr).rem_euclid(height as i32) as u32;
-                neighbors.push(nr * width + nc);
+                let mut c = col as i32 + dc;
+                let mut r = row as i32 + dr;
+                if wrap_cols {
+                    c = c.rem_euclid(width as i32);
+                }
+                if wrap_rows {
+                    r = r.rem_euclid(height as i32);
+                }
+                if (0..width as i32).contains(&c) && (0..height as i32).contains(&r) {
+                    neighbors.push(r as u32 * width + c as u32);
+                }
             }
             tiles[id].neighbors = neighbors;
         }
@@ -100,17 +142,29 @@ pub fn geodesic_tile_count(level: u32) -> u32 {
     10 * 4u32.pow(level) + 2
 }
 
+/// Highest supported geodesic subdivision level (655,362 tiles).
+pub const MAX_GEODESIC_LEVEL: u32 = 8;
+
+/// The supported geodesic subdivision level whose tile count is nearest
+/// `tile_count`. Ties go to the smaller level.
+pub fn nearest_geodesic_level(tile_count: u32) -> u32 {
+    (1..=MAX_GEODESIC_LEVEL)
+        .min_by_key(|&level| geodesic_tile_count(level).abs_diff(tile_count))
+        .unwrap_or(1)
+}
+
 /// Generate a geodesic grid by subdividing an icosahedron.
 ///
 /// Produces a hex grid on a unit sphere with exactly 12 pentagons (5 neighbors)
 /// and all other tiles as hexagons (6 neighbors).
 ///
 /// # Panics
-/// Panics if `level` is not in 1..=7.
+/// Panics if `level` is not in 1..=[`MAX_GEODESIC_LEVEL`].
 pub fn generate_geodesic_grid(level: u32) -> Vec<Tile> {
     assert!(
-        (1..=7).contains(&level),
-        "Geodesic subdivision level must be 1-7, got {}",
+        (1..=MAX_GEODESIC_LEVEL).contains(&level),
+        "Geodesic subdivision level must be 1-{}, got {}",
+        MAX_GEODESIC_LEVEL,
         level
     );
 
@@ -118,25 +172,12 @@ pub fn generate_geodesic_grid(level: u32) -> Vec<Tile> {
     // To match our formula (10 * 4^level + 2), we need subdivisions = 2^level - 1.
     let hexasphere_subdivisions = (1usize << level) - 1;
     let sphere = Subdivided::<(), IcoSphereBase>::new(hexasphere_subdivisions, |_| ());
+    let adjacency = Adjacency::from_triangles(&sphere);
     let points = sphere.raw_points();
-    let indices = sphere.get_all_indices();
-    let vertex_count = points.len();
-
-    // Build neighbor adjacency from shared triangle edges
-    let mut neighbor_sets: Vec<HashSet<u32>> = vec![HashSet::new(); vertex_count];
-    for chunk in indices.chunks(3) {
-        let a = chunk[0];
-        let b = chunk[1];
-        let c = chunk[2];
-        neighbor_sets[a as usize].insert(b);
-        neighbor_sets[a as usize].insert(c);
-        neighbor_sets[b as usize].insert(a);
-        neighbor_sets[b as usize].insert(c);
-        neighbor_sets[c as usize].insert(a);
-        neighbor_sets[c as usize].insert(b);
-    }
-
-    let mut tiles = Vec::with_capacity(vertex_count);
+
+    // Tiles are built straight from the points and the compact adjacency,
+    // so nothing else of tile size is held alongside them
+    let mut tiles = Vec::with_capacity(points.len());
     for (i, point) in points.iter().enumerate() {
         let x = point.x as f64;
         let y = point.y as f64;
@@ -144,9 +185,6 @@ pub fn generate_geodesic_grid(level: u32) -> Vec<Tile> {
         let lat = z.asin().to_degrees();
         let lon = y.atan2(x).to_degrees();
 
-        let mut neighbor_vec: Vec<u32> = neighbor_sets[i].iter().copied().collect();
-        neighbor_vec.sort_unstable(); // deterministic ordering
-
         let position = Position {
             x,
             y,
@@ -154,12 +192,226 @@ pub fn generate_geodesic_grid(level: u32) -> Vec<Tile> {
             lat,
             lon,
         };
-        tiles.push(Tile::new_default(i as u32, neighbor_vec, position));
+        tiles.push(Tile::new_default(i as u32, adjacency.neighbors(i).to_vec(), position));
     }
 
     tiles
 }
 
+/// Neighbor lists of a triangulated sphere, packed into one array: the
+/// neighbors of vertex `v` are `targets[offsets[v]..offsets[v + 1]]`,
+/// sorted by ID.
+struct Adjacency {
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+}
+
+impl Adjacency {
+    /// Collect each triangle edge once as a sorted (low, high) pair, one base
+    /// triangle at a time, then count and place both directions of each. A
+    /// closed triangulation has about 3 edges per vertex, so this needs a
+    /// few integers per vertex where a hash set per vertex needs hundreds of
+    /// bytes.
+    fn from_triangles(sphere: &Subdivided<(), IcoSphereBase>) -> Adjacency {
+        let vertex_count = sphere.raw_points().len();
+        let mut edges: Vec<(u32, u32)> = Vec::with_capacity(vertex_count * 6);
+        let mut indices = Vec::new();
+        for triangle in 0..sphere.main_triangles().len() {
+            indices.clear();
+            sphere.get_indices(triangle, &mut indices);
+            for t in indices.chunks_exact(3) {
+                for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                    edges.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        // Interior edges are shared by two triangles
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut offsets = vec![0u32; vertex_count + 1];
+        for &(a, b) in &edges {
+            offsets[a as usize + 1] += 1;
+            offsets[b as usize + 1] += 1;
+        }
+        for v in 0..vertex_count {
+            offsets[v + 1] += offsets[v];
+        }
+        let mut fill = offsets.clone();
+        let mut targets = vec![0u32; edges.len() * 2];
+        // Edges are sorted, so each vertex receives its lower neighbors in
+        // order, then its higher ones in order: every list ends up sorted
+        for &(a, b) in &edges {
+            targets[fill[b as usize] as usize] = a;
+            fill[b as usize] += 1;
+        }
+        for &(a, b) in &edges {
+            targets[fill[a as usize] as usize] = b;
+            fill[a as usize] += 1;
+        }
+        Adjacency { offsets, targets }
+    }
+
+    fn neighbors(&self, v: usize) -> &[u32] {
+        &self.targets[self.offsets[v] as usize..self.offsets[v + 1] as usize]
+    }
+}
+
+/// A problem found in a world's neighbor graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphIssue {
+    /// Tile at `index` in the tile list has a different `id`.
+    IdMismatch { index: usize, id: u32 },
+    /// Neighbor ID does not refer to any tile.
+    OutOfRange { tile: u32, neighbor: u32 },
+    /// Tile lists itself as a neighbor.
+    SelfNeighbor { tile: u32 },
+    /// Tile lists the same neighbor more than once.
+    DuplicateNeighbor { tile: u32, neighbor: u32 },
+    /// `tile` lists `neighbor`, but `neighbor` does not list `tile`.
+    Asymmetric { tile: u32, neighbor: u32 },
+    /// Neighbor count outside what the topology allows.
+    UnexpectedDegree { tile: u32, count: usize, expected: &'static str },
+    /// A geodesic grid must have exactly 12 pentagons.
+    PentagonCount { count: usize },
+}
+
+impl GraphIssue {
+    /// Whether `repair_neighbor_graph` fixes this kind of issue.
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            GraphIssue::OutOfRange { .. }
+                | GraphIssue::SelfNeighbor { .. }
+                | GraphIssue::DuplicateNeighbor { .. }
+                | GraphIssue::Asymmetric { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for GraphIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphIssue::IdMismatch { index, id } => {
+                write!(f, "tile at index {} has id {}", index, id)
+            }
+            GraphIssue::OutOfRange { tile, neighbor } => {
+                write!(f, "tile {}: neighbor {} does not exist", tile, neighbor)
+            }
+            GraphIssue::SelfNeighbor { tile } => write!(f, "tile {}: lists itself as a neighbor", tile),
+            GraphIssue::DuplicateNeighbor { tile, neighbor } => {
+                write!(f, "tile {}: neighbor {} listed more than once", tile, neighbor)
+            }
+            GraphIssue::Asymmetric { tile, neighbor } => write!(
+                f,
+                "tile {}: lists {} as a neighbor, but {} does not list {}",
+                tile, neighbor, neighbor, tile
+            ),
+            GraphIssue::UnexpectedDegree { tile, count, expected } => {
+                write!(f, "tile {}: has {} neighbors, expected {}", tile, count, expected)
+            }
+            GraphIssue::PentagonCount { count } => {
+                write!(f, "geodesic grid has {} pentagons, expected 12", count)
+            }
+        }
+    }
+}
+
+/// Index of each tile id in `tiles`. Neighbor lists hold ids, which only
+/// equal indices in a healthy graph; the first tile wins a duplicated id.
+fn index_by_id(tiles: &[Tile]) -> HashMap<u32, usize> {
+    let mut index = HashMap::with_capacity(tiles.len());
+    for (i, tile) in tiles.iter().enumerate() {
+        index.entry(tile.id).or_insert(i);
+    }
+    index
+}
+
+/// Validate a neighbor graph against the invariants the generators guarantee.
+///
+/// `wraps` is false for bounded flat grids, whose edge tiles have fewer neighbors.
+pub fn validate_neighbor_graph(tiles: &[Tile], topology: TopologyType, wraps: bool) -> Vec<GraphIssue> {
+    let mut issues = Vec::new();
+    let index = index_by_id(tiles);
+
+    for (i, tile) in tiles.iter().enumerate() {
+        if tile.id as usize != i {
+            issues.push(GraphIssue::IdMismatch { index: i, id: tile.id });
+        }
+
+        let mut seen = HashSet::new();
+        for &n in &tile.neighbors {
+            let Some(&neighbor) = index.get(&n) else {
+                issues.push(GraphIssue::OutOfRange { tile: tile.id, neighbor: n });
+                continue;
+            };
+            if n == tile.id {
+                issues.push(GraphIssue::SelfNeighbor { tile: tile.id });
+            } else if !seen.insert(n) {
+                issues.push(GraphIssue::DuplicateNeighbor { tile: tile.id, neighbor: n });
+            } else if !tiles[neighbor].neighbors.contains(&tile.id) {
+                issues.push(GraphIssue::Asymmetric { tile: tile.id, neighbor: n });
+            }
+        }
+
+        let degree = tile.neighbors.len();
+        let (ok, expected) = match (topology, wraps) {
+            (TopologyType::Geodesic, _) => (degree == 5 || degree == 6, "5 or 6"),
+            (TopologyType::FlatHex, true) => (degree == 6, "6"),
+            (TopologyType::FlatHex, false) => ((2..=6).contains(&degree), "2-6"),
+        };
+        if !ok {
+            issues.push(GraphIssue::UnexpectedDegree { tile: tile.id, count: degree, expected });
+        }
+    }
+
+    if topology == TopologyType::Geodesic {
+        let pentagons = tiles.iter().filter(|t| t.neighbors.len() == 5).count();
+        if pentagons != 12 {
+            issues.push(GraphIssue::PentagonCount { count: pentagons });
+        }
+    }
+
+    issues
+}
+
+/// Repair the neighbor graph in place.
+///
+/// Drops neighbor entries naming no tile, the tile itself, or a neighbor
+/// already listed, then makes every remaining link bidirectional by adding the
+/// missing back-link. Tiles are looked up by id, so a graph whose ids don't
+/// match their indices is repaired without misplacing links. Id and degree
+/// problems are not repairable and are left for `validate_neighbor_graph` to
+/// report. Returns the number of neighbor entries removed or added.
+pub fn repair_neighbor_graph(tiles: &mut [Tile]) -> usize {
+    let index = index_by_id(tiles);
+    let mut changes = 0;
+
+    for tile in tiles.iter_mut() {
+        let id = tile.id;
+        let mut seen = HashSet::new();
+        let before = tile.neighbors.len();
+        tile.neighbors.retain(|&n| index.contains_key(&n) && n != id && seen.insert(n));
+        changes += before - tile.neighbors.len();
+    }
+
+    let mut missing_back_links = Vec::new();
+    for tile in tiles.iter() {
+        for n in &tile.neighbors {
+            let neighbor = index[n];
+            if !tiles[neighbor].neighbors.contains(&tile.id) {
+                missing_back_links.push((neighbor, tile.id));
+            }
+        }
+    }
+    for (neighbor, id) in missing_back_links {
+        tiles[neighbor].neighbors.push(id);
+        changes += 1;
+    }
+
+    changes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +455,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bounded_grid_edges_have_fewer_neighbors() {
+        let tiles = generate_bounded_flat_hex_grid(10, 10);
+        assert_eq!(tiles.len(), 100);
+        // Corner (0,0) on an even row: east, southeast only
+        assert_eq!(tiles[0].neighbors.len(), 2);
+        // Interior tile keeps all six
+        assert_eq!(tiles[5 * 10 + 5].neighbors.len(), 6);
+        for tile in &tiles {
+            assert!(!tile.neighbors.contains(&tile.id));
+            for &n in &tile.neighbors {
+                assert!(tiles[n as usize].neighbors.contains(&tile.id));
+            }
+        }
+    }
+
+    #[test]
+    fn wrapping_hex_grid_wraps_columns_only() {
+        let tiles = generate_wrapping_hex_grid(10, 9);
+        assert_eq!(tiles.len(), 90);
+        // West of column 0 is column 9 on the same row
+        assert!(tiles[3 * 10].neighbors.contains(&(3 * 10 + 9)));
+        // Top and bottom rows lose their two outward neighbors
+        assert!(tiles[..10].iter().all(|t| t.neighbors.len() == 4));
+        assert!(tiles[80..].iter().all(|t| t.neighbors.len() == 4));
+        assert!(tiles[10..80].iter().all(|t| t.neighbors.len() == 6));
+        assert!(validate_neighbor_graph(&tiles, TopologyType::FlatHex, false).is_empty());
+    }
+
     #[test]
     fn no_self_neighbors() {
         let tiles = generate_flat_hex_grid(10, 10);
@@ -317,6 +598,15 @@ mod tests {
 
     // === Geodesic grid tests ===
 
+    #[test]
+    fn nearest_geodesic_level_rounds_to_the_closest_count() {
+        assert_eq!(nearest_geodesic_level(0), 1);
+        assert_eq!(nearest_geodesic_level(2562), 4);
+        assert_eq!(nearest_geodesic_level(16000), 5, "10,242 is nearer than 40,962");
+        assert_eq!(nearest_geodesic_level(30000), 6);
+        assert_eq!(nearest_geodesic_level(u32::MAX), MAX_GEODESIC_LEVEL);
+    }
+
     #[test]
     fn geodesic_tile_count_formula() {
         assert_eq!(geodesic_tile_count(1), 42);
@@ -386,6 +676,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn geodesic_neighbors_are_the_triangle_edges() {
+        let level = 3;
+        let sphere = Subdivided::<(), IcoSphereBase>::new((1usize << level) - 1, |_| ());
+        let mut expected: Vec<HashSet<u32>> = vec![HashSet::new(); sphere.raw_points().len()];
+        for t in sphere.get_all_indices().chunks(3) {
+            for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                expected[a as usize].insert(b);
+                expected[b as usize].insert(a);
+            }
+        }
+
+        let tiles = generate_geodesic_grid(level);
+        for tile in &tiles {
+            let mut want: Vec<u32> = expected[tile.id as usize].iter().copied().collect();
+            want.sort_unstable();
+            assert_eq!(tile.neighbors, want, "tile {}", tile.id);
+        }
+    }
+
     #[test]
     fn geodesic_no_self_neighbors() {
         let tiles = generate_geodesic_grid(3);
@@ -484,4 +794,67 @@ mod tests {
             assert_eq!(t1.position, t2.position);
         }
     }
+
+    #[test]
+    fn generated_grids_pass_validation() {
+        assert!(validate_neighbor_graph(&generate_flat_hex_grid(10, 10), TopologyType::FlatHex, true).is_empty());
+        assert!(
+            validate_neighbor_graph(&generate_bounded_flat_hex_grid(10, 10), TopologyType::FlatHex, false)
+                .is_empty()
+        );
+        assert!(validate_neighbor_graph(&generate_geodesic_grid(2), TopologyType::Geodesic, true).is_empty());
+    }
+
+    #[test]
+    fn validation_detects_corruption() {
+        let mut tiles = generate_flat_hex_grid(10, 10);
+        let victim = tiles[0].neighbors[0];
+        tiles[0].neighbors[1] = 999;
+        tiles[5].neighbors.push(5);
+        tiles[victim as usize].neighbors.retain(|&n| n != 0);
+
+        let issues = validate_neighbor_graph(&tiles, TopologyType::FlatHex, true);
+        assert!(issues.contains(&GraphIssue::OutOfRange { tile: 0, neighbor: 999 }));
+        assert!(issues.contains(&GraphIssue::SelfNeighbor { tile: 5 }));
+        assert!(issues.contains(&GraphIssue::Asymmetric { tile: 0, neighbor: victim }));
+        assert!(issues
+            .iter()
+            .any(|i| matches!(i, GraphIssue::UnexpectedDegree { tile, .. } if *tile == victim)));
+    }
+
+    #[test]
+    fn repair_restores_symmetry() {
+        let mut tiles = generate_geodesic_grid(2);
+        let a = tiles[10].neighbors[0];
+        tiles[a as usize].neighbors.retain(|&n| n != 10);
+        tiles[20].neighbors.push(20);
+        let dup = tiles[20].neighbors[0];
+        tiles[20].neighbors.push(dup);
+
+        let changes = repair_neighbor_graph(&mut tiles);
+        assert_eq!(changes, 3);
+        assert!(validate_neighbor_graph(&tiles, TopologyType::Geodesic, true).is_empty());
+    }
+
+    #[test]
+    fn repair_looks_tiles_up_by_id() {
+        // Tile ids 0..n stored in reverse order, so no id matches its index
+        let mut tiles = generate_flat_hex_grid(4, 4);
+        tiles.reverse();
+        let last = tiles.len() - 1;
+        // Tile 0 (stored last) loses its back-link from its first neighbor
+        let a = tiles[last].neighbors[0];
+        let a_index = tiles.iter().position(|t| t.id == a).unwrap();
+        tiles[a_index].neighbors.retain(|&n| n != 0);
+
+        let issues = validate_neighbor_graph(&tiles, TopologyType::FlatHex, true);
+        assert!(issues.contains(&GraphIssue::Asymmetric { tile: 0, neighbor: a }));
+        assert!(!issues.iter().any(|i| matches!(i, GraphIssue::OutOfRange { .. })));
+
+        let changes = repair_neighbor_graph(&mut tiles);
+        assert_eq!(changes, 1);
+        assert!(tiles[a_index].neighbors.contains(&0));
+        let remaining = validate_neighbor_graph(&tiles, TopologyType::FlatHex, true);
+        assert!(remaining.iter().all(|i| matches!(i, GraphIssue::IdMismatch { .. })), "{:?}", remaining);
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Type of pressure system, determining behavior and lifecycle.
@@ -8,6 +10,8 @@ pub enum PressureSystemType {
     TropicalLow,
     PolarHigh,
     ThermalLow,
+    /// A stationary mid-latitude high that holds its place for tens of ticks
+    BlockingHigh,
 }
 
 /// A pressure system — a macro-scale weather entity that moves, intensifies, and decays.
@@ -48,6 +52,9 @@ pub struct MacroWeatherState {
     pub systems: Vec<PressureSystem>,
     pub next_id: u32,
     pub rng_state: u64,
+    /// Per-system tile lists for projection culling (rebuilt on demand, never persisted)
+    #[serde(skip)]
+    pub projection_cache: ProjectionCache,
 }
 
 impl Default for MacroWeatherState {
@@ -56,6 +63,7 @@ impl Default for MacroWeatherState {
             systems: Vec::new(),
             next_id: 1,
             rng_state: 1,
+            projection_cache: ProjectionCache::default(),
         }
     }
 }
@@ -66,10 +74,107 @@ impl MacroWeatherState {
             systems: Vec::new(),
             next_id: 1,
             rng_state: if seed == 0 { 1 } else { seed },
+            projection_cache: ProjectionCache::default(),
+        }
+    }
+}
+
+/// Slow-moving temperature state kept by the latitude-band thermostat.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermostatState {
+    /// Year-long running mean of each band's temperature (K), south to north.
+    /// Reset when the band layout changes.
+    pub band_means: Vec<f32>,
+}
+
+/// World-level state of the optional ocean circulation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OceanState {
+    /// Strength of the overturning circulation, 1.0 for a present-day-like
+    /// density contrast between polar and tropical surface water.
+    pub overturning: f32,
+}
+
+impl Default for OceanState {
+    fn default() -> Self {
+        Self { overturning: 1.0 }
+    }
+}
+
+/// World-level state of the optional moving sea level.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeaLevelState {
+    /// Height of the sea above its starting level, in elevation units.
+    pub offset: f32,
+    /// Elevation of the starting shoreline, found on the first tick.
+    pub datum: Option<f32>,
+    /// Glacier ice on land as of the last tick, so melt can raise the sea.
+    pub land_ice: f32,
+}
+
+/// Geomagnetic storm state driving the optional cosmetic aurora.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpaceWeatherState {
+    /// Peak strength (0.0-1.0) of the current storm, 0.0 when quiet.
+    pub storm_peak: f32,
+    /// Length in ticks of the current storm.
+    pub storm_duration: u32,
+    /// Ticks left in the current storm.
+    pub ticks_remaining: u32,
+    /// Separate from the macro weather RNG so aurora never perturbs weather.
+    pub rng_state: u64,
+}
+
+impl Default for SpaceWeatherState {
+    fn default() -> Self {
+        Self::with_seed(1)
+    }
+}
+
+impl SpaceWeatherState {
+    pub fn with_seed(seed: u64) -> Self {
+        // Decorrelate from the macro weather stream seeded with the same value
+        let mixed = seed ^ 0x9E37_79B9_7F4A_7C15;
+        Self {
+            storm_peak: 0.0,
+            storm_duration: 0,
+            ticks_remaining: 0,
+            rng_state: if mixed == 0 { 1 } else { mixed },
         }
     }
 }
 
+/// Candidate tiles a pressure system can influence, computed around the
+/// position the system had when the list was built.
+#[derive(Debug, Clone, Default)]
+pub struct SystemFootprint {
+    /// Latitude of the system when the footprint was computed
+    pub lat: f64,
+    /// Longitude of the system when the footprint was computed
+    pub lon: f64,
+    /// System radius when the footprint was computed
+    pub radius: f32,
+    /// Indices into `World::tiles`, ascending
+    pub tiles: Vec<u32>,
+}
+
+/// Cached system footprints keyed by system ID.
+///
+/// Derived from the systems and tile positions, so it is skipped during
+/// serialization and ignored when comparing states.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectionCache {
+    pub footprints: HashMap<u32, SystemFootprint>,
+    /// Tile count the footprints were built for; a mismatch invalidates them
+    pub tile_count: usize,
+}
+
+impl PartialEq for ProjectionCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +244,7 @@ mod tests {
             ],
             next_id: 2,
             rng_state: 12345,
+            projection_cache: ProjectionCache::default(),
         };
 
         let encoded = bincode::serialize(&state).expect("serialize");
@@ -154,6 +260,7 @@ mod tests {
             PressureSystemType::TropicalLow,
             PressureSystemType::PolarHigh,
             PressureSystemType::ThermalLow,
+            PressureSystemType::BlockingHigh,
         ];
         for t in &types {
             let encoded = bincode::serialize(t).expect("serialize");